@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::models::{CrawlSummary, HtmlContentTypes, IssueType, PageInfo, SeoIssue};
+use crate::page_store::PageStore;
+use crate::reporter::Reporter;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Snapshot of known issues; runs checked against it only report issues it doesn't contain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub issues: Vec<BaselineEntry>,
+}
+
+/// A known issue, identified by its fingerprint. The remaining fields are for readers of the file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub page_url: String,
+    pub issue_type: IssueType,
+    pub message: String,
+    /// How many issues on the page share the fingerprint; a later run reports any beyond this
+    /// many as new
+    #[serde(default = "default_count", skip_serializing_if = "is_single")]
+    pub count: usize,
+}
+
+const fn default_count() -> usize {
+    1
+}
+
+fn is_single(count: &usize) -> bool {
+    *count == 1
+}
+
+impl Baseline {
+    /// Record every issue of the pages, sorted so that rewriting a baseline produces small diffs
+    pub fn from_pages(pages: &dyn PageStore) -> Result<Self> {
+        let mut issues = Vec::new();
+        pages.for_each_page(&mut |page_url, page| {
+            issues.extend(Self::page_entries(page_url, page));
+            Ok(())
+        })?;
+        Ok(Self::from_entries(issues))
+    }
+
+    fn page_entries<'a>(
+        page_url: &'a str,
+        page: &'a PageInfo,
+    ) -> impl Iterator<Item = BaselineEntry> + 'a {
+        page.issues.iter().map(move |issue| BaselineEntry {
+            fingerprint: Self::fingerprint(issue, page_url),
+            page_url: page_url.to_string(),
+            issue_type: issue.issue_type,
+            message: issue.message.clone(),
+            count: 1,
+        })
+    }
+
+    fn from_entries(mut issues: Vec<BaselineEntry>) -> Self {
+        issues.sort_by(|a, b| {
+            (&a.page_url, &a.fingerprint, &a.message).cmp(&(
+                &b.page_url,
+                &b.fingerprint,
+                &b.message,
+            ))
+        });
+        issues.dedup_by(|duplicate, kept| {
+            let same = duplicate.fingerprint == kept.fingerprint;
+            if same {
+                kept.count += duplicate.count;
+            }
+            same
+        });
+
+        Self {
+            version: BASELINE_VERSION,
+            issues,
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline file: {}", path.display()))?;
+        let baseline: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse baseline file: {}", path.display()))?;
+
+        if baseline.version != BASELINE_VERSION {
+            anyhow::bail!(
+                "Unsupported baseline version {} in {} (expected {})",
+                baseline.version,
+                path.display(),
+                BASELINE_VERSION
+            );
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write baseline file: {}", path.display()))
+    }
+
+    /// Remove issues recorded in the baseline from the pages and refresh the report's summary.
+    /// Returns the number of suppressed issues.
+    pub fn suppress_known_issues(
+        &self,
+        pages: &mut dyn PageStore,
+        summary: &mut CrawlSummary,
+        html_content_types: &HtmlContentTypes,
+    ) -> Result<usize> {
+        let mut known = self.known_fingerprints();
+        let mut suppressed = 0;
+
+        pages.update_each(&mut |page_url, page| {
+            suppressed += Self::suppress_page_issues(&mut known, page_url, page);
+            Ok(())
+        })?;
+
+        *summary = Reporter::summarize_pages(pages, html_content_types)?;
+        Ok(suppressed)
+    }
+
+    /// How many issues with each fingerprint are known
+    fn known_fingerprints(&self) -> HashMap<&str, usize> {
+        let mut known = HashMap::new();
+        for entry in &self.issues {
+            *known.entry(entry.fingerprint.as_str()).or_default() += entry.count;
+        }
+        known
+    }
+
+    /// Drop the page's known issues, each known one only as many times as the baseline saw it
+    fn suppress_page_issues(
+        known: &mut HashMap<&str, usize>,
+        page_url: &str,
+        page: &mut PageInfo,
+    ) -> usize {
+        let before = page.issues.len();
+        page.issues.retain(|issue| {
+            match known.get_mut(Self::fingerprint(issue, page_url).as_str()) {
+                Some(remaining) if *remaining > 0 => {
+                    *remaining -= 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+        before - page.issues.len()
+    }
+
+    fn fingerprint(issue: &SeoIssue, page_url: &str) -> String {
+        issue
+            .fingerprint
+            .clone()
+            .unwrap_or_else(|| issue.compute_fingerprint(page_url))
+    }
+}
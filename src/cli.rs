@@ -1,85 +1,628 @@
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use crate::json_style::JsonStyle;
+use crate::limits::{ByteSize, HumanDuration};
+use crate::link_checker::SampleRate;
+use crate::logs::LogFormat;
+use crate::progress::ProgressFormat;
+use crate::render::RenderMode;
+use crate::screenshot::ScreenshotMode;
 
 pub const DEFAULT_DEPTH: usize = 5;
 pub const DEFAULT_MAX_PAGES: usize = 200;
 pub const DEFAULT_CONCURRENCY: usize = 5;
+pub const DEFAULT_MAX_SITEMAPS: usize = 50;
 pub const DEFAULT_RESPECT_ROBOTS_TXT: bool = true;
+pub const DEFAULT_ROBOTS_AGENT: &str = "scoutly";
+pub const DEFAULT_RETRIES: usize = 0;
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
+pub const GENTLE_CONCURRENCY: usize = 1;
+pub const GENTLE_RATE_LIMIT: f64 = 1.0;
+pub const DEFAULT_TRENDS_LIMIT: usize = 10;
+pub const DEFAULT_LOGS_LIMIT: usize = 20;
+pub const DEFAULT_HEALTH_MAX_TTFB_MS: u64 = 2000;
+pub const DEFAULT_HEALTH_MIN_TLS_DAYS: i64 = 14;
+pub const DEFAULT_DAEMON_LISTEN: &str = "127.0.0.1:8080";
+pub const DEFAULT_DAEMON_WORKERS: usize = 2;
+pub const DEFAULT_DAEMON_ARCHIVE_DIR: &str = "scoutly-archive";
+pub const DEFAULT_JOB_POLL_INTERVAL_SECS: u64 = 2;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     Text,
     Json,
+    /// One JSON object per line, each page written as soon as it is crawled
+    Jsonl,
 }
 
 impl OutputFormat {
     pub const fn is_json(self) -> bool {
-        matches!(self, Self::Json)
+        matches!(self, Self::Json | Self::Jsonl)
+    }
+}
+
+/// Which discovered links the link checker should validate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkCheckScope {
+    Internal,
+    External,
+    #[default]
+    All,
+    None,
+}
+
+impl LinkCheckScope {
+    pub const fn includes(self, is_external: bool) -> bool {
+        match self {
+            Self::Internal => !is_external,
+            Self::External => is_external,
+            Self::All => true,
+            Self::None => false,
+        }
+    }
+}
+
+/// Bundled crawl settings for common use cases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CrawlPreset {
+    /// Shallow crawl that only checks internal links
+    Quick,
+    /// The built-in defaults
+    Standard,
+    /// Large, thorough crawl
+    Deep,
+}
+
+/// Values a preset supplies when neither the CLI nor the config file sets them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PresetSettings {
+    pub depth: usize,
+    pub max_pages: usize,
+    pub concurrency: usize,
+    pub check_links: LinkCheckScope,
+}
+
+impl CrawlPreset {
+    pub const fn settings(self) -> PresetSettings {
+        match self {
+            Self::Quick => PresetSettings {
+                depth: 2,
+                max_pages: 50,
+                concurrency: 10,
+                check_links: LinkCheckScope::Internal,
+            },
+            Self::Standard => PresetSettings {
+                depth: DEFAULT_DEPTH,
+                max_pages: DEFAULT_MAX_PAGES,
+                concurrency: DEFAULT_CONCURRENCY,
+                check_links: LinkCheckScope::All,
+            },
+            Self::Deep => PresetSettings {
+                depth: 10,
+                max_pages: 1000,
+                concurrency: 10,
+                check_links: LinkCheckScope::All,
+            },
+        }
     }
 }
 
-#[derive(Parser, Debug, Clone)]
+#[derive(Parser, Debug, Clone, Default)]
 #[command(name = "scoutly")]
 #[command(about = "A CLI website crawler and SEO analyzer", long_about = None)]
+#[command(subcommand_precedence_over_arg = true)]
 pub struct Cli {
-    /// The URL to start crawling from (optional in TUI mode)
+    /// The URLs to start crawling from; every start URL's host counts as internal (optional in TUI mode)
     #[arg(value_name = "URL")]
-    pub url: Option<String>,
+    pub urls: Vec<String>,
+
+    /// Crawl preset: quick, standard, or deep (explicit options and config values take precedence)
+    #[arg(long, value_enum, global = true)]
+    pub preset: Option<CrawlPreset>,
 
     /// Maximum crawl depth (default: 5)
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub depth: Option<usize>,
 
     /// Maximum number of pages to crawl (default: 200)
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub max_pages: Option<usize>,
 
-    /// CLI output format: text or json
-    #[arg(short, long, value_enum, conflicts_with = "tui")]
+    /// CLI output format: text, json, or jsonl (one line per page as it is crawled)
+    #[arg(short, long, value_enum, global = true)]
     pub output: Option<OutputFormat>,
 
+    /// Field names in the JSON report, printed or saved: snake_case or camelCase (default: snake_case)
+    #[arg(long, value_enum, value_name = "STYLE", global = true)]
+    pub json_style: Option<JsonStyle>,
+
     /// Force CLI mode instead of launching the TUI
     #[arg(long, conflicts_with = "tui")]
     pub cli: bool,
 
     /// Force the interactive TUI (errors if no interactive terminal is available)
-    #[arg(long, conflicts_with = "cli")]
+    #[arg(long, conflicts_with_all = ["cli", "output"])]
     pub tui: bool,
 
     /// Save report to file
     #[arg(short, long)]
     pub save: Option<String>,
 
+    /// Save the report as one JSON file per page plus an index.json summary in this directory, for tracking reports in git
+    #[arg(long, value_name = "DIR")]
+    pub save_dir: Option<String>,
+
+    /// Write a small JSON summary of the run's outcome (exit code, checks, counts) to this file, for CI steps that only need pass/fail
+    #[arg(long, value_name = "FILE")]
+    pub result_file: Option<String>,
+
+    /// Record the issues found in this run to a baseline file
+    #[arg(long, value_name = "FILE", conflicts_with = "baseline")]
+    pub write_baseline: Option<String>,
+
+    /// Write a sitemap.xml of the crawled internal HTML pages that returned 200
+    #[arg(long, value_name = "FILE")]
+    pub emit_sitemap: Option<String>,
+
+    /// Write each crawled page's heading outline as a Markdown file in this directory (implies --heading-outline)
+    #[arg(long, value_name = "DIR")]
+    pub outline_dir: Option<String>,
+
+    /// Checkpoint the crawl to this file and continue from it if an earlier run was interrupted
+    #[arg(long, value_name = "FILE")]
+    pub resume: Option<String>,
+
+    /// Keep pages in this directory and on later runs only re-download the ones the server says changed, using ETag and Last-Modified
+    #[arg(long, value_name = "DIR")]
+    pub cache_dir: Option<String>,
+
+    /// Keep crawled pages in this SQLite file instead of in memory, for very large sites
+    #[arg(long, value_name = "FILE", conflicts_with = "resume")]
+    pub page_store: Option<String>,
+
+    /// Write each fetched page body to this directory, named after a hash of its URL, and record the file in the report
+    #[arg(long, value_name = "DIR")]
+    pub save_html: Option<String>,
+
+    /// Crawl only the URLs listed in this file, one per line, without following their links
+    #[arg(long, value_name = "FILE")]
+    pub url_file: Option<String>,
+
+    /// Fetch the pages of a saved JSON report again and merge the fresh results into it
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["url_file", "resume", "tui"])]
+    pub recrawl_urls_from: Option<String>,
+
+    /// With --recrawl-urls-from, fetch only pages that had errors, failed to load, or had broken links
+    #[arg(long, requires = "recrawl_urls_from")]
+    pub only_errors: bool,
+
+    /// Report why this URL was or wasn't crawled: where it was found and which depth, robots.txt, pattern, or duplicate rule applied (repeatable)
+    #[arg(long, value_name = "URL")]
+    pub explain: Vec<String>,
+
+    /// Suppress issues recorded in a baseline file and fail only on new errors or warnings
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<String>,
+
+    /// Directory where summary metrics of each run are kept for `scoutly trends`
+    #[arg(long, value_name = "DIR")]
+    pub state_dir: Option<String>,
+
+    /// Capture a screenshot of every page (all) or of pages with errors (errors) next to the saved report; needs Chrome or Chromium
+    #[arg(long, value_enum, value_name = "PAGES")]
+    pub screenshots: Option<ScreenshotMode>,
+
     /// Follow external links
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_external")]
     pub external: bool,
 
+    /// Turn off --external, overriding the config file
+    #[arg(long, overrides_with = "external")]
+    pub no_external: bool,
+
     /// Verbose output
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_verbose")]
     pub verbose: bool,
 
+    /// Turn off --verbose, overriding the config file
+    #[arg(long, overrides_with = "verbose")]
+    pub no_verbose: bool,
+
     /// Ignore redirect issues in the report
-    #[arg(long)]
+    #[arg(long, overrides_with = "no_ignore_redirects")]
     pub ignore_redirects: bool,
 
+    /// Turn off --ignore-redirects, overriding the config file
+    #[arg(long, overrides_with = "ignore_redirects")]
+    pub no_ignore_redirects: bool,
+
+    /// Which links to check: internal, external, all, or none (default: all)
+    #[arg(long, value_enum)]
+    pub check_links: Option<LinkCheckScope>,
+
     /// Treat URLs with fragment identifiers (#) as unique links
-    #[arg(long)]
+    #[arg(long, overrides_with = "no_keep_fragments")]
     pub keep_fragments: bool,
 
+    /// Turn off --keep-fragments, overriding the config file
+    #[arg(long, overrides_with = "keep_fragments")]
+    pub no_keep_fragments: bool,
+
+    /// Only crawl discovered URLs matching this regex; repeat to allow several patterns
+    #[arg(long = "include", value_name = "REGEX")]
+    pub include: Vec<String>,
+
+    /// Never crawl URLs matching this regex, e.g. '/wp-admin/'; repeat for several patterns
+    #[arg(long = "exclude", value_name = "REGEX")]
+    pub exclude: Vec<String>,
+
+    /// Drop utm_*, fbclid, gclid, and other click-tracking query parameters before comparing URLs
+    #[arg(long, overrides_with = "no_strip_tracking_params")]
+    pub strip_tracking_params: bool,
+
+    /// Turn off --strip-tracking-params, overriding the config file
+    #[arg(long, overrides_with = "strip_tracking_params")]
+    pub no_strip_tracking_params: bool,
+
+    /// Sort query parameters before comparing URLs, so ?a=1&b=2 and ?b=2&a=1 are crawled once
+    #[arg(long, overrides_with = "no_sort_query")]
+    pub sort_query: bool,
+
+    /// Turn off --sort-query, overriding the config file
+    #[arg(long, overrides_with = "sort_query")]
+    pub no_sort_query: bool,
+
+    /// Don't follow links marked rel="nofollow", "ugc", or "sponsored"; they're still reported and checked
+    #[arg(long, overrides_with = "no_respect_nofollow")]
+    pub respect_nofollow: bool,
+
+    /// Turn off --respect-nofollow, overriding the config file
+    #[arg(long, overrides_with = "respect_nofollow")]
+    pub no_respect_nofollow: bool,
+
+    /// Drop this query parameter before comparing URLs, e.g. 'sessionid' or 'ref_*'; repeat for several
+    #[arg(long = "ignore-param", value_name = "NAME")]
+    pub ignore_params: Vec<String>,
+
     /// Rate limit for requests per second (optional, e.g., 1.0 for 1 req/s)
-    #[arg(short = 'r', long)]
+    #[arg(short = 'r', long, global = true)]
     pub rate_limit: Option<f64>,
 
     /// Number of concurrent requests (default: 5)
-    #[arg(short = 'c', long)]
+    #[arg(short = 'c', long, global = true)]
     pub concurrency: Option<usize>,
 
+    /// Seconds to wait for each page to respond (default: 30)
+    #[arg(long, value_name = "SECS", global = true)]
+    pub timeout: Option<u64>,
+
+    /// Seconds to wait for each checked link to respond, also used by the hreflang, migration,
+    /// and Core Web Vitals lookups (default: 10)
+    #[arg(long, value_name = "SECS", global = true)]
+    pub link_timeout: Option<u64>,
+
+    /// Skip TLS certificate verification, e.g. for staging sites with self-signed certificates
+    #[arg(long, overrides_with = "no_insecure", global = true)]
+    pub insecure: bool,
+
+    /// Turn off --insecure, overriding the config file
+    #[arg(long, overrides_with = "insecure", global = true)]
+    pub no_insecure: bool,
+
+    /// Also trust the CA certificates in this PEM file, e.g. an internal CA bundle
+    #[arg(long, value_name = "FILE", global = true)]
+    pub ca_cert: Option<String>,
+
     /// Respect robots.txt rules (default: true)
-    #[arg(long, action = clap::ArgAction::Set)]
+    #[arg(long, action = clap::ArgAction::Set, global = true)]
     pub respect_robots_txt: Option<bool>,
 
-    /// Path to configuration file (JSON, TOML, or YAML)
-    #[arg(long)]
-    pub config: Option<String>,
+    /// Consult each external host's robots.txt before checking its links
+    #[arg(long, overrides_with = "no_respect_robots_for_links")]
+    pub respect_robots_for_links: bool,
+
+    /// Turn off --respect-robots-for-links, overriding the config file
+    #[arg(long, overrides_with = "respect_robots_for_links")]
+    pub no_respect_robots_for_links: bool,
+
+    /// Check every link with utm_* or affiliate parameters, whatever --check-links says, and flag redirects that drop those parameters
+    #[arg(long, overrides_with = "no_audit_tracking_links")]
+    pub audit_tracking_links: bool,
+
+    /// Turn off --audit-tracking-links, overriding the config file
+    #[arg(long, overrides_with = "audit_tracking_links")]
+    pub no_audit_tracking_links: bool,
+
+    /// Don't crawl or check external links whose host is, resolves to, or redirects to a private, loopback, or link-local address; they are reported as skipped
+    #[arg(long, overrides_with = "no_block_private_ips")]
+    pub block_private_ips: bool,
+
+    /// Turn off --block-private-ips, overriding the config file
+    #[arg(long, overrides_with = "block_private_ips")]
+    pub no_block_private_ips: bool,
+
+    /// User-agent token to match against robots.txt groups; a group applies when this token starts with its name, ignoring case (default: scoutly)
+    #[arg(long, value_name = "TOKEN")]
+    pub robots_agent: Option<String>,
+
+    /// Send this cookie to the start URLs' hosts, e.g. 'session=abc123' to crawl pages behind a login; repeat for several
+    #[arg(long = "cookie", value_name = "NAME=VALUE")]
+    pub cookies: Vec<String>,
+
+    /// Load cookies from a Netscape-format cookies.txt file, as exported by browsers and curl
+    #[arg(long, value_name = "FILE")]
+    pub cookies_file: Option<String>,
+
+    /// Re-request each page with another user agent and with its own cookies to find content that varies without a matching Vary header
+    #[arg(long, overrides_with = "no_audit_vary")]
+    pub audit_vary: bool,
+
+    /// Turn off --audit-vary, overriding the config file
+    #[arg(long, overrides_with = "audit_vary")]
+    pub no_audit_vary: bool,
+
+    /// Follow redirects one hop at a time and record each page's redirect chain with the status of every hop
+    #[arg(long, overrides_with = "no_trace_redirects")]
+    pub trace_redirects: bool,
+
+    /// Turn off --trace-redirects, overriding the config file
+    #[arg(long, overrides_with = "trace_redirects")]
+    pub no_trace_redirects: bool,
+
+    /// With --trace-redirects, report pages reached through more than this many redirects (default: 1)
+    #[arg(long, value_name = "N")]
+    pub redirect_chain_limit: Option<usize>,
+
+    /// Report email addresses and phone numbers shown as plain text on pages
+    #[arg(long, overrides_with = "no_find_contacts")]
+    pub find_contacts: bool,
+
+    /// Turn off --find-contacts, overriding the config file
+    #[arg(long, overrides_with = "find_contacts")]
+    pub no_find_contacts: bool,
+
+    /// Scan page source for API keys, tokens, private keys, and internal IP addresses
+    #[arg(long, overrides_with = "no_scan_secrets")]
+    pub scan_secrets: bool,
+
+    /// Turn off --scan-secrets, overriding the config file
+    #[arg(long, overrides_with = "scan_secrets")]
+    pub no_scan_secrets: bool,
+
+    /// Add each page's H1-H6 heading outline to the report as a tree
+    #[arg(long, overrides_with = "no_heading_outline")]
+    pub heading_outline: bool,
+
+    /// Turn off --heading-outline, overriding the config file
+    #[arg(long, overrides_with = "heading_outline")]
+    pub no_heading_outline: bool,
+
+    /// Also crawl the pages listed in /sitemap.xml and in sitemaps named by robots.txt, even when no link reaches them
+    #[arg(long, overrides_with = "no_use_sitemap")]
+    pub use_sitemap: bool,
+
+    /// Turn off --use-sitemap, overriding the config file
+    #[arg(long, overrides_with = "use_sitemap")]
+    pub no_use_sitemap: bool,
+
+    /// Fetch at most this many sitemaps with --use-sitemap, counting those nested in sitemap indexes (default: 50)
+    #[arg(long, value_name = "N")]
+    pub max_sitemaps: Option<usize>,
+
+    /// Parse pages as served (http) or after running their JavaScript in headless Chrome or Chromium (js); js is slower (default: http)
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub render: Option<RenderMode>,
+
+    /// Crawl politely: 1 request at a time at 1 req/s unless set otherwise, jittered, slowed to robots.txt Crawl-delay, and waiting out Retry-After
+    #[arg(long, overrides_with = "no_gentle", global = true)]
+    pub gentle: bool,
+
+    /// Turn off --gentle, overriding the config file
+    #[arg(long, overrides_with = "gentle", global = true)]
+    pub no_gentle: bool,
+
+    /// Fetch fewer pages at once while the site slows down or answers with 429 or 5xx, and more again as it recovers, up to --concurrency
+    #[arg(long, overrides_with = "no_auto_throttle", global = true)]
+    pub auto_throttle: bool,
+
+    /// Turn off --auto-throttle, overriding the config file
+    #[arg(long, overrides_with = "auto_throttle", global = true)]
+    pub no_auto_throttle: bool,
+
+    /// Stop sending requests after this many, across the crawl and link checks
+    #[arg(long, value_name = "N", global = true)]
+    pub max_requests: Option<usize>,
+
+    /// Stop sending requests once responses total this size, e.g. 500MB or 2GiB
+    #[arg(long, value_name = "SIZE", global = true)]
+    pub max_bandwidth: Option<ByteSize>,
+
+    /// Stop reading a page once its body passes this size and report it as too large instead of parsing it (default: 10MB)
+    #[arg(long, value_name = "SIZE", global = true)]
+    pub max_body_bytes: Option<ByteSize>,
+
+    /// Stop queueing pages once the crawl has run this long, e.g. 10m or 1h30m
+    #[arg(long, value_name = "DURATION", global = true)]
+    pub max_duration: Option<HumanDuration>,
+
+    /// Retry requests that time out, lose their connection, or get a 5xx response up to this many times (default: 0)
+    #[arg(long, value_name = "N", global = true)]
+    pub retries: Option<usize>,
+
+    /// Milliseconds to wait before the first retry, doubled for each retry after it (default: 500)
+    #[arg(long, value_name = "MS", global = true)]
+    pub retry_backoff_ms: Option<u64>,
+
+    /// Wait a random time between half and all of each retry backoff (default: true)
+    #[arg(long, action = clap::ArgAction::Set, value_name = "BOOL", global = true)]
+    pub retry_jitter: Option<bool>,
+
+    /// Number of external links checked at once, in a phase after the site's own links (default: --concurrency)
+    #[arg(long, value_name = "N")]
+    pub external_concurrency: Option<usize>,
+
+    /// Requests per second sent to external hosts while checking their links (default: no limit)
+    #[arg(long, value_name = "RPS")]
+    pub external_rate_limit: Option<f64>,
+
+    /// Retry external link checks that fail transiently up to this many times (default: --retries)
+    #[arg(long, value_name = "N")]
+    pub external_retries: Option<usize>,
+
+    /// Seconds to wait for an external link to respond (default: --link-timeout)
+    #[arg(long, value_name = "SECS")]
+    pub external_timeout: Option<u64>,
+
+    /// Check only this share of external links, e.g. 20%, and mark the rest as sampled out; the same links are picked on every run
+    #[arg(long, value_name = "PERCENT")]
+    pub external_check_sample: Option<SampleRate>,
+
+    /// List at most this many issues of each type per page and count the rest (default: all)
+    #[arg(long, value_name = "N")]
+    pub max_issues_per_type: Option<usize>,
+
+    /// List at most this many pages in each section of the text report (default: all)
+    #[arg(long, value_name = "N")]
+    pub max_pages_shown: Option<usize>,
+
+    /// Rhai script with custom checks to run against every HTML page; repeat for several scripts
+    #[arg(long = "script", value_name = "PATH")]
+    pub scripts: Vec<String>,
+
+    /// How to show scan progress in CLI mode: bar, json lines on stderr, or none (default: bar)
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub progress: Option<ProgressFormat>,
+
+    /// Path to configuration file (JSON, TOML, or YAML); repeat to layer files, later ones win
+    #[arg(long, global = true)]
+    pub config: Vec<String>,
+
+    /// Named profile from the config file to apply on top of its base settings
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Answer a few questions to create a config file and see the equivalent command
+    Wizard,
+
+    /// Chart broken links, errors, warnings, and health score across runs recorded with --state-dir
+    Trends {
+        /// Site to show (defaults to the site of the most recent run)
+        url: Option<String>,
+
+        /// Directory holding the run history (defaults to `state_dir` from the config file)
+        #[arg(long, value_name = "DIR")]
+        state_dir: Option<String>,
+
+        /// Number of recent runs listed in the table
+        #[arg(long, default_value_t = DEFAULT_TRENDS_LIMIT)]
+        limit: usize,
+
+        /// Also write the charts to an HTML file
+        #[arg(long, value_name = "FILE")]
+        html: Option<String>,
+    },
+
+    /// Crawl two deployments of a site and report pages whose status, title, meta description, or canonical differ
+    CompareSites {
+        /// Reference site, e.g. production
+        #[arg(value_name = "LEFT_URL")]
+        left: String,
+
+        /// Site to check against the reference, e.g. staging
+        #[arg(value_name = "RIGHT_URL")]
+        right: String,
+    },
+
+    /// Check that each old URL in a CSV of old,new mappings permanently redirects to its new URL
+    ValidateMigration {
+        /// CSV file with one `old_url,new_url` pair per line (a header row is allowed)
+        #[arg(value_name = "CSV")]
+        mappings: PathBuf,
+    },
+
+    /// Recrawl the pages of a list of issues from an earlier scan and report which are resolved and which persist
+    VerifyFixes {
+        /// Saved JSON report, baseline file, or CSV with `page_url,issue_type[,offending_url]` rows
+        #[arg(value_name = "FILE")]
+        issues: PathBuf,
+    },
+
+    /// Cross-reference Googlebot hits in an access log with a crawl to find wasted crawl budget
+    Logs {
+        /// Access log file
+        #[arg(value_name = "LOG_FILE")]
+        log: PathBuf,
+
+        /// Site to crawl for comparison (defaults to `url` from the config file)
+        url: Option<String>,
+
+        /// Layout of the access log lines
+        #[arg(long, value_enum, default_value_t = LogFormat::Combined)]
+        format: LogFormat,
+
+        /// Compare against a saved JSON report instead of crawling
+        #[arg(long, value_name = "FILE", conflicts_with = "url")]
+        report: Option<PathBuf>,
+
+        /// Number of URLs listed in each section of the text report
+        #[arg(long, default_value_t = DEFAULT_LOGS_LIMIT)]
+        limit: usize,
+    },
+
+    /// Fetch one URL and check its status, time to first byte, certificate expiry, key headers, and robots.txt
+    Health {
+        /// Page to check
+        url: String,
+
+        /// Slowest acceptable time to first byte, in milliseconds
+        #[arg(long, value_name = "MS", default_value_t = DEFAULT_HEALTH_MAX_TTFB_MS)]
+        max_ttfb_ms: u64,
+
+        /// Fewest days the TLS certificate must remain valid
+        #[arg(long, value_name = "DAYS", default_value_t = DEFAULT_HEALTH_MIN_TLS_DAYS)]
+        min_tls_days: i64,
+    },
+
+    /// Run a crawl service that executes queued jobs from an HTTP API or a job directory
+    Serve {
+        /// Address for the HTTP API
+        #[arg(long, default_value = DEFAULT_DAEMON_LISTEN)]
+        listen: SocketAddr,
+
+        /// Number of crawls that run at the same time
+        #[arg(long, default_value_t = DEFAULT_DAEMON_WORKERS)]
+        workers: usize,
+
+        /// Directory watched for job files (config files that include a url)
+        #[arg(long)]
+        jobs_dir: Option<PathBuf>,
+
+        /// Directory of site config files, each with a url and a cron schedule, crawled on that schedule
+        #[arg(long)]
+        sites_dir: Option<PathBuf>,
+
+        /// Directory where finished reports are archived
+        #[arg(long, default_value = DEFAULT_DAEMON_ARCHIVE_DIR)]
+        archive_dir: PathBuf,
+
+        /// Seconds between scans of the job and sites directories
+        #[arg(long, default_value_t = DEFAULT_JOB_POLL_INTERVAL_SECS)]
+        poll_interval: u64,
+
+        /// Serve the API without api_tokens, open to anyone who can reach it
+        #[arg(long)]
+        no_auth: bool,
+    },
 }
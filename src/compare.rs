@@ -0,0 +1,219 @@
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use url::Url;
+
+use crate::models::PageInfo;
+
+/// Page attribute compared between the two sites
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparedField {
+    StatusCode,
+    Title,
+    MetaDescription,
+    Canonical,
+}
+
+impl ComparedField {
+    pub const ALL: [Self; 4] = [
+        Self::StatusCode,
+        Self::Title,
+        Self::MetaDescription,
+        Self::Canonical,
+    ];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::StatusCode => "Status code",
+            Self::Title => "Title",
+            Self::MetaDescription => "Meta description",
+            Self::Canonical => "Canonical",
+        }
+    }
+}
+
+/// A page that exists on both sites with a different value for one field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageDifference {
+    /// Path and query shared by both URLs
+    pub path: String,
+    pub field: ComparedField,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// Result of crawling two deployments of the same site and matching their pages by path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SiteComparison {
+    pub left_url: String,
+    pub right_url: String,
+    pub pages_compared: usize,
+    pub only_in_left: Vec<String>,
+    pub only_in_right: Vec<String>,
+    pub differences: Vec<PageDifference>,
+}
+
+impl SiteComparison {
+    /// Match pages by path and query and compare the fields that matter for a release
+    pub fn new(
+        left_url: &str,
+        left_pages: &HashMap<String, PageInfo>,
+        right_url: &str,
+        right_pages: &HashMap<String, PageInfo>,
+    ) -> Self {
+        let origins: Vec<String> = [left_url, right_url]
+            .into_iter()
+            .filter_map(origin_of)
+            .collect();
+        let left = pages_by_path(left_pages);
+        let right = pages_by_path(right_pages);
+
+        let only_in_left = left
+            .keys()
+            .filter(|path| !right.contains_key(*path))
+            .cloned()
+            .collect();
+        let only_in_right = right
+            .keys()
+            .filter(|path| !left.contains_key(*path))
+            .cloned()
+            .collect();
+
+        let mut pages_compared = 0;
+        let mut differences = Vec::new();
+        for (path, left_page) in &left {
+            let Some(right_page) = right.get(path) else {
+                continue;
+            };
+            pages_compared += 1;
+
+            for field in ComparedField::ALL {
+                let left_value = field_value(left_page, field, &origins);
+                let right_value = field_value(right_page, field, &origins);
+                if left_value != right_value {
+                    differences.push(PageDifference {
+                        path: path.clone(),
+                        field,
+                        left: left_value,
+                        right: right_value,
+                    });
+                }
+            }
+        }
+
+        Self {
+            left_url: left_url.to_string(),
+            right_url: right_url.to_string(),
+            pages_compared,
+            only_in_left,
+            only_in_right,
+            differences,
+        }
+    }
+
+    /// Number of findings: differing fields plus pages found on only one site
+    pub fn total_differences(&self) -> usize {
+        self.differences.len() + self.only_in_left.len() + self.only_in_right.len()
+    }
+
+    pub fn print_text_report(&self) {
+        println!("\n{}", "=".repeat(80).bright_blue());
+        println!("{}", "SITE COMPARISON".bright_cyan().bold());
+        println!("{}", "=".repeat(80).bright_blue());
+        println!("{} {}", "Left:".bright_white().bold(), self.left_url);
+        println!("{} {}", "Right:".bright_white().bold(), self.right_url);
+        println!(
+            "{} {}",
+            "Pages compared:".bright_white().bold(),
+            self.pages_compared
+        );
+
+        if self.total_differences() == 0 {
+            println!("\n{}", "No differences found.".bright_green().bold());
+            return;
+        }
+
+        for (heading, paths) in [
+            ("Only on the left site", &self.only_in_left),
+            ("Only on the right site", &self.only_in_right),
+        ] {
+            if paths.is_empty() {
+                continue;
+            }
+            println!("\n{} ({})", heading.bright_yellow().bold(), paths.len());
+            for path in paths {
+                println!("  {path}");
+            }
+        }
+
+        if !self.differences.is_empty() {
+            println!(
+                "\n{} ({})",
+                "Changed pages".bright_yellow().bold(),
+                self.differences.len()
+            );
+            for difference in &self.differences {
+                println!(
+                    "  {} {}",
+                    difference.path.bright_white(),
+                    difference.field.label().bold()
+                );
+                println!(
+                    "    {} {}",
+                    "-".red(),
+                    display_value(difference.left.as_deref())
+                );
+                println!(
+                    "    {} {}",
+                    "+".green(),
+                    display_value(difference.right.as_deref())
+                );
+            }
+        }
+    }
+}
+
+fn pages_by_path(pages: &HashMap<String, PageInfo>) -> BTreeMap<String, &PageInfo> {
+    pages
+        .values()
+        .filter_map(|page| Some((path_key(&Url::parse(&page.url).ok()?), page)))
+        .collect()
+}
+
+pub(crate) fn path_key(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+fn origin_of(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .map(|url| url.origin().ascii_serialization())
+}
+
+fn field_value(page: &PageInfo, field: ComparedField, origins: &[String]) -> Option<String> {
+    match field {
+        ComparedField::StatusCode => page.status_code.map(|code| code.to_string()),
+        ComparedField::Title => page.title.clone(),
+        ComparedField::MetaDescription => page.meta_description.clone(),
+        // Canonicals pointing at either site are compared by path, so a staging page that
+        // canonicalizes to its production twin matches production's self-reference
+        ComparedField::Canonical => {
+            page.canonical
+                .as_deref()
+                .map(|canonical| match Url::parse(canonical) {
+                    Ok(url) if origins.contains(&url.origin().ascii_serialization()) => {
+                        path_key(&url)
+                    }
+                    _ => canonical.to_string(),
+                })
+        }
+    }
+}
+
+fn display_value(value: Option<&str>) -> String {
+    value.map_or_else(|| "(none)".to_string(), |value| format!("{value:?}"))
+}
@@ -1,17 +1,43 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::cli::{
-    Cli, DEFAULT_CONCURRENCY, DEFAULT_DEPTH, DEFAULT_MAX_PAGES, DEFAULT_RESPECT_ROBOTS_TXT,
-    OutputFormat,
+    Cli, CrawlPreset, DEFAULT_MAX_SITEMAPS, DEFAULT_RESPECT_ROBOTS_TXT, DEFAULT_RETRIES,
+    DEFAULT_RETRY_BACKOFF_MS, DEFAULT_ROBOTS_AGENT, GENTLE_CONCURRENCY, GENTLE_RATE_LIMIT,
+    LinkCheckScope, OutputFormat,
 };
+use crate::crawler::{
+    DEFAULT_MAX_BODY_BYTES, DEFAULT_PAGE_TIMEOUT_SECS, DEFAULT_REDIRECT_CHAIN_LIMIT,
+};
+use crate::daemon::api::ApiToken;
+use crate::http_client::{RetryPolicy, TlsOptions};
+use crate::json_style::JsonStyle;
+use crate::limits::{ByteSize, HumanDuration};
+use crate::link_checker::{DEFAULT_LINK_TIMEOUT_SECS, ExternalCheckPolicy, SampleRate};
+use crate::login::LoginConfig;
+use crate::models::HtmlContentTypes;
+use crate::progress::ProgressFormat;
+use crate::render::RenderMode;
+use crate::rules::{PageAssertion, PerformanceBudget, RuleOverride};
+use crate::screenshot::ScreenshotMode;
 
 /// Configuration file structure that mirrors CLI arguments
 /// All fields are optional to allow partial configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
+    /// The URL to start crawling from, used when none is given on the command line
+    pub url: Option<String>,
+
+    /// More URLs to start crawling from alongside `url`; their hosts count as internal too
+    pub urls: Option<Vec<String>>,
+
+    /// Crawl preset supplying depth, page, concurrency, and link-check defaults
+    pub preset: Option<CrawlPreset>,
+
     /// Maximum crawl depth
     pub depth: Option<usize>,
 
@@ -21,12 +47,42 @@ pub struct Config {
     /// CLI output format
     pub output: Option<OutputFormat>,
 
+    /// Field names in the JSON report: "snake_case" or "camelCase"
+    pub json_style: Option<JsonStyle>,
+
     /// Prefer the CLI renderer instead of the default TUI
     pub cli: Option<bool>,
 
     /// Save report to file
     pub save: Option<String>,
 
+    /// Directory to save the report to as one JSON file per page plus an index
+    pub save_dir: Option<String>,
+
+    /// File to write a short JSON summary of the run's outcome to
+    pub result_file: Option<String>,
+
+    /// Directory of pages kept from earlier runs, revalidated with conditional requests
+    pub cache_dir: Option<String>,
+
+    /// SQLite file to keep crawled pages in instead of in memory
+    pub page_store: Option<String>,
+
+    /// Directory to write each fetched page body to
+    pub save_html: Option<String>,
+
+    /// Baseline file of known issues to suppress
+    pub baseline: Option<String>,
+
+    /// Directory where summary metrics of each run are kept for `scoutly trends`
+    pub state_dir: Option<String>,
+
+    /// Capture screenshots of all pages or only pages with errors
+    pub screenshots: Option<ScreenshotMode>,
+
+    /// Chrome or Chromium executable used for screenshots and `render = "js"` (defaults to the first one on PATH)
+    pub screenshot_browser: Option<String>,
+
     /// Follow external links
     pub external: Option<bool>,
 
@@ -36,36 +92,290 @@ pub struct Config {
     /// Ignore redirect issues in the report
     pub ignore_redirects: Option<bool>,
 
+    /// Which links to check (internal, external, all, none)
+    pub check_links: Option<LinkCheckScope>,
+
+    /// Domains whose links are never checked (e.g. login-walled or rate-limit-sensitive hosts)
+    pub never_check_domains: Option<Vec<String>>,
+
+    /// Domains whose links are always checked, even outside the `check_links` scope
+    pub always_check_domains: Option<Vec<String>>,
+
+    /// Content types parsed as HTML for links and SEO data (default: text/html and application/xhtml+xml)
+    pub html_content_types: Option<Vec<String>>,
+
     /// Treat URLs with fragment identifiers (#) as unique links
     pub keep_fragments: Option<bool>,
 
+    /// Regexes a discovered URL must match one of to be crawled
+    pub include: Option<Vec<String>>,
+
+    /// Regexes for URLs that are never crawled
+    pub exclude: Option<Vec<String>>,
+
+    /// Drop click-tracking query parameters such as utm_* and fbclid before comparing URLs
+    pub strip_tracking_params: Option<bool>,
+
+    /// Sort query parameters before comparing URLs
+    pub sort_query: Option<bool>,
+
+    /// Query parameters dropped before comparing URLs; a trailing `*` matches any suffix
+    pub ignore_params: Option<Vec<String>>,
+
+    /// Don't follow links marked `rel="nofollow"`, `rel="ugc"`, or `rel="sponsored"`
+    pub respect_nofollow: Option<bool>,
+
     /// Rate limit for requests per second
     pub rate_limit: Option<f64>,
 
     /// Number of concurrent requests
     pub concurrency: Option<usize>,
 
+    /// Seconds to wait for each page to respond
+    pub timeout: Option<u64>,
+
+    /// Seconds to wait for each checked link to respond
+    pub link_timeout: Option<u64>,
+
+    /// Skip TLS certificate verification
+    pub insecure: Option<bool>,
+
+    /// PEM file of extra CA certificates to trust
+    pub ca_cert: Option<String>,
+
     /// Respect robots.txt rules
     pub respect_robots_txt: Option<bool>,
+
+    /// Consult each external host's robots.txt before checking its links
+    pub respect_robots_for_links: Option<bool>,
+
+    /// Check links with tracking or affiliate parameters and flag redirects that drop them
+    pub audit_tracking_links: Option<bool>,
+
+    /// Skip external links whose host is or resolves to a private address
+    pub block_private_ips: Option<bool>,
+
+    /// User-agent token matched against robots.txt groups
+    pub robots_agent: Option<String>,
+
+    /// `name=value` cookies sent to the start URLs' hosts
+    pub cookies: Option<Vec<String>>,
+
+    /// Netscape-format cookies.txt file to load cookies from
+    pub cookies_file: Option<String>,
+
+    /// Login form submitted once before crawling so members-only pages can be audited
+    pub login: Option<LoginConfig>,
+
+    /// Re-request pages to detect content that varies on User-Agent or Cookie without declaring it
+    pub audit_vary: Option<bool>,
+
+    /// Follow redirects one hop at a time to record each page's redirect chain
+    pub trace_redirects: Option<bool>,
+
+    /// Redirects a traced chain may have before it is reported as too long
+    pub redirect_chain_limit: Option<usize>,
+
+    /// Report email addresses and phone numbers shown as plain text
+    pub find_contacts: Option<bool>,
+
+    /// Scan page source for keys, tokens, and internal IP addresses
+    pub scan_secrets: Option<bool>,
+
+    /// Add each page's H1-H6 heading outline to the report
+    pub heading_outline: Option<bool>,
+
+    /// Secret scan patterns by name, replacing same-named built-in ones; empty turns one off
+    pub secret_patterns: Option<BTreeMap<String, String>>,
+
+    /// Queue the pages listed in the site's sitemaps before following links
+    pub use_sitemap: Option<bool>,
+
+    /// Sitemaps fetched with `use_sitemap`, counting those nested in sitemap indexes
+    pub max_sitemaps: Option<usize>,
+
+    /// Parse pages as served or after running their JavaScript in a headless browser
+    pub render: Option<RenderMode>,
+
+    /// Crawl politely: low concurrency and rate limit by default, jitter, Crawl-delay, and Retry-After
+    pub gentle: Option<bool>,
+
+    /// Lower the crawl's concurrency while the site slows down or answers with 429 or 5xx
+    pub auto_throttle: Option<bool>,
+
+    /// Requests sent across the crawl and link checks before the scan stops
+    pub max_requests: Option<usize>,
+
+    /// Response bytes downloaded before the scan stops, e.g. "500MB" or a plain byte count
+    pub max_bandwidth: Option<ByteSize>,
+
+    /// Largest page body read before the page is reported as too large, e.g. "10MB"
+    pub max_body_bytes: Option<ByteSize>,
+
+    /// Stop queueing pages once the crawl has run this long, e.g. "10m"
+    pub max_duration: Option<HumanDuration>,
+
+    /// Times a request that timed out, lost its connection, or got a 5xx response is retried
+    pub retries: Option<usize>,
+
+    /// Milliseconds before the first retry, doubled for each retry after it
+    pub retry_backoff_ms: Option<u64>,
+
+    /// Randomize retry waits between half and all of the backoff
+    pub retry_jitter: Option<bool>,
+
+    /// External links checked at once, after the site's own links
+    pub external_concurrency: Option<usize>,
+
+    /// Requests per second sent to external hosts while checking links
+    pub external_rate_limit: Option<f64>,
+
+    /// Retries for external link checks that fail transiently
+    pub external_retries: Option<usize>,
+
+    /// Seconds to wait for an external link to respond
+    pub external_timeout: Option<u64>,
+
+    /// Share of external links to check, e.g. "20%"
+    pub external_check_sample: Option<SampleRate>,
+
+    /// Issues of one type listed per page before the rest are only counted
+    pub max_issues_per_type: Option<usize>,
+
+    /// Pages listed in each section of the text report before the rest are only counted
+    pub max_pages_shown: Option<usize>,
+
+    /// Rhai scripts with custom checks run against every HTML page
+    pub scripts: Option<Vec<String>>,
+
+    /// How scan progress is shown in CLI mode (bar or json)
+    pub progress: Option<ProgressFormat>,
+
+    /// Chrome UX Report API key; when set, Core Web Vitals field data is added to each page
+    pub crux_api_key: Option<String>,
+
+    /// Performance budgets applied to every page (per-path rules can override them)
+    pub budgets: Option<PerformanceBudget>,
+
+    /// Words that flag an HTML comment as a leftover note (default: TODO, FIXME, HACK, XXX)
+    pub comment_markers: Option<Vec<String>>,
+
+    /// Threshold overrides and disabled checks keyed by URL path pattern (e.g. `/blog/**`)
+    pub rules: Option<BTreeMap<String, RuleOverride>>,
+
+    /// CSS selectors or regexes that must (or must not) match on pages under a path pattern
+    pub assertions: Option<Vec<PageAssertion>>,
+
+    /// Cron expression for crawling this site from `scoutly serve --sites-dir`, e.g. "0 3 * * *"
+    pub schedule: Option<String>,
+
+    /// Webhook that `scoutly serve` POSTs to when a crawl of this site finds more errors or
+    /// warnings than the previous one
+    pub notify_url: Option<String>,
+
+    /// Bearer token required by this site's `/sites/{name}` routes in `scoutly serve`
+    pub api_token: Option<String>,
+
+    /// Queue position of this crawl in `scoutly serve`: jobs with a higher priority start
+    /// first, and equal priorities start in submission order (default: 0)
+    pub priority: Option<i32>,
+
+    /// Bearer tokens accepted by the `scoutly serve` API and what each may do; with none, the
+    /// API is open
+    pub api_tokens: Option<Vec<ApiToken>>,
+
+    /// Named sets of overrides selected with `--profile`
+    pub profiles: Option<BTreeMap<String, Config>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct RuntimeOptions {
     pub url: Option<String>,
+    /// Start URLs after the first, crawled as part of the same site
+    pub additional_urls: Vec<String>,
     pub depth: usize,
     pub max_pages: usize,
     pub output: Option<OutputFormat>,
+    pub json_style: JsonStyle,
     pub save: Option<String>,
+    pub save_dir: Option<String>,
+    pub result_file: Option<String>,
+    pub write_baseline: Option<String>,
+    pub emit_sitemap: Option<String>,
+    pub outline_dir: Option<String>,
+    pub resume: Option<String>,
+    pub cache_dir: Option<String>,
+    pub page_store: Option<String>,
+    pub save_html: Option<String>,
+    pub explain: Vec<String>,
+    pub baseline: Option<String>,
+    pub state_dir: Option<String>,
+    pub screenshots: Option<ScreenshotMode>,
+    pub screenshot_browser: Option<String>,
     pub cli: bool,
     pub external: bool,
     pub verbose: bool,
     pub ignore_redirects: bool,
+    pub check_links: LinkCheckScope,
+    pub never_check_domains: Vec<String>,
+    pub always_check_domains: Vec<String>,
+    pub html_content_types: Option<Vec<String>>,
     pub keep_fragments: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub strip_tracking_params: bool,
+    pub sort_query: bool,
+    pub ignore_params: Vec<String>,
+    pub respect_nofollow: bool,
     pub rate_limit: Option<f64>,
     pub concurrency: usize,
+    pub timeout: u64,
+    pub link_timeout: u64,
+    pub insecure: bool,
+    pub ca_cert: Option<String>,
     pub respect_robots_txt: bool,
+    pub respect_robots_for_links: bool,
+    pub audit_tracking_links: bool,
+    pub block_private_ips: bool,
+    pub robots_agent: String,
+    pub cookies: Vec<String>,
+    pub cookies_file: Option<String>,
+    pub login: Option<LoginConfig>,
+    pub audit_vary: bool,
+    pub trace_redirects: bool,
+    pub redirect_chain_limit: usize,
+    pub find_contacts: bool,
+    pub scan_secrets: bool,
+    pub heading_outline: bool,
+    pub secret_patterns: BTreeMap<String, String>,
+    pub use_sitemap: bool,
+    pub max_sitemaps: usize,
+    pub render: RenderMode,
+    pub gentle: bool,
+    pub auto_throttle: bool,
+    pub max_requests: Option<usize>,
+    pub max_bandwidth: Option<ByteSize>,
+    pub max_body_bytes: ByteSize,
+    pub max_duration: Option<HumanDuration>,
+    pub retries: usize,
+    pub retry_backoff_ms: u64,
+    pub retry_jitter: bool,
+    pub external_concurrency: Option<usize>,
+    pub external_rate_limit: Option<f64>,
+    pub external_retries: Option<usize>,
+    pub external_timeout: u64,
+    pub external_check_sample: Option<SampleRate>,
+    pub max_issues_per_type: Option<usize>,
+    pub max_pages_shown: Option<usize>,
+    pub scripts: Vec<String>,
+    pub progress: ProgressFormat,
+    pub budget: PerformanceBudget,
+    pub comment_markers: Option<Vec<String>>,
+    pub crux_api_key: Option<String>,
+    pub rule_overrides: BTreeMap<String, RuleOverride>,
+    pub assertions: Vec<PageAssertion>,
     pub tui: bool,
-    pub config: Option<String>,
+    pub config: Vec<String>,
 }
 
 /// Configuration file format based on file extension
@@ -120,8 +430,16 @@ impl Config {
         Ok(config)
     }
 
+    /// Load several configuration files and layer them in order; later files override earlier ones
+    pub fn from_files(paths: &[PathBuf]) -> Result<Self> {
+        paths.iter().try_fold(Self::default(), |merged, path| {
+            Ok(merged.merge(Self::from_file(path)?))
+        })
+    }
+
     /// Get the default configuration file paths to check (in order of priority)
-    /// Returns paths in order: current directory, user config directory
+    /// Returns paths in order: current directory, XDG-style user config directory,
+    /// platform-native user config directory
     pub fn default_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
 
@@ -145,8 +463,12 @@ impl Config {
             })
             .or_else(|| dirs::home_dir().map(|home| home.join(".config")));
 
-        if let Some(config_home) = config_home {
-            let scoutly_config_dir = config_home.join("scoutly");
+        // Then the platform's native config directory (AppData on Windows, Application Support
+        // on macOS); on Linux this is the XDG directory again and is skipped
+        let native_config_dir = dirs::config_dir().filter(|dir| Some(dir) != config_home.as_ref());
+
+        for config_dir in config_home.into_iter().chain(native_config_dir) {
+            let scoutly_config_dir = config_dir.join("scoutly");
             for format in &[ConfigFormat::Json, ConfigFormat::Toml, ConfigFormat::Yaml] {
                 for ext in format.extensions() {
                     paths.push(scoutly_config_dir.join(format!("config.{}", ext)));
@@ -168,38 +490,363 @@ impl Config {
         Ok(None)
     }
 
+    /// Apply the named profile on top of the base settings
+    pub fn with_profile(mut self, name: &str) -> Result<Self> {
+        let mut profiles = self.profiles.take().unwrap_or_default();
+        let Some(profile) = profiles.remove(name) else {
+            let available: Vec<&str> = profiles.keys().map(String::as_str).collect();
+            if available.is_empty() {
+                anyhow::bail!("Profile '{name}' not found: the config file defines no profiles");
+            }
+            anyhow::bail!(
+                "Profile '{name}' not found (available: {})",
+                available.join(", ")
+            );
+        };
+
+        Ok(self.merge(profile))
+    }
+
+    /// Overlay another config on top of this one; values set in `overlay` win
+    pub fn merge(self, overlay: Config) -> Self {
+        Self {
+            url: overlay.url.or(self.url),
+            urls: overlay.urls.or(self.urls),
+            preset: overlay.preset.or(self.preset),
+            depth: overlay.depth.or(self.depth),
+            max_pages: overlay.max_pages.or(self.max_pages),
+            output: overlay.output.or(self.output),
+            json_style: overlay.json_style.or(self.json_style),
+            cli: overlay.cli.or(self.cli),
+            save: overlay.save.or(self.save),
+            save_dir: overlay.save_dir.or(self.save_dir),
+            result_file: overlay.result_file.or(self.result_file),
+            cache_dir: overlay.cache_dir.or(self.cache_dir),
+            page_store: overlay.page_store.or(self.page_store),
+            save_html: overlay.save_html.or(self.save_html),
+            baseline: overlay.baseline.or(self.baseline),
+            state_dir: overlay.state_dir.or(self.state_dir),
+            screenshots: overlay.screenshots.or(self.screenshots),
+            screenshot_browser: overlay.screenshot_browser.or(self.screenshot_browser),
+            external: overlay.external.or(self.external),
+            verbose: overlay.verbose.or(self.verbose),
+            ignore_redirects: overlay.ignore_redirects.or(self.ignore_redirects),
+            check_links: overlay.check_links.or(self.check_links),
+            never_check_domains: overlay.never_check_domains.or(self.never_check_domains),
+            always_check_domains: overlay.always_check_domains.or(self.always_check_domains),
+            html_content_types: overlay.html_content_types.or(self.html_content_types),
+            keep_fragments: overlay.keep_fragments.or(self.keep_fragments),
+            include: overlay.include.or(self.include),
+            exclude: overlay.exclude.or(self.exclude),
+            strip_tracking_params: overlay.strip_tracking_params.or(self.strip_tracking_params),
+            sort_query: overlay.sort_query.or(self.sort_query),
+            respect_nofollow: overlay.respect_nofollow.or(self.respect_nofollow),
+            ignore_params: overlay.ignore_params.or(self.ignore_params),
+            rate_limit: overlay.rate_limit.or(self.rate_limit),
+            concurrency: overlay.concurrency.or(self.concurrency),
+            timeout: overlay.timeout.or(self.timeout),
+            link_timeout: overlay.link_timeout.or(self.link_timeout),
+            insecure: overlay.insecure.or(self.insecure),
+            ca_cert: overlay.ca_cert.or(self.ca_cert),
+            respect_robots_txt: overlay.respect_robots_txt.or(self.respect_robots_txt),
+            respect_robots_for_links: overlay
+                .respect_robots_for_links
+                .or(self.respect_robots_for_links),
+            audit_tracking_links: overlay.audit_tracking_links.or(self.audit_tracking_links),
+            block_private_ips: overlay.block_private_ips.or(self.block_private_ips),
+            robots_agent: overlay.robots_agent.or(self.robots_agent),
+            cookies: overlay.cookies.or(self.cookies),
+            cookies_file: overlay.cookies_file.or(self.cookies_file),
+            login: overlay.login.or(self.login),
+            audit_vary: overlay.audit_vary.or(self.audit_vary),
+            trace_redirects: overlay.trace_redirects.or(self.trace_redirects),
+            redirect_chain_limit: overlay.redirect_chain_limit.or(self.redirect_chain_limit),
+            find_contacts: overlay.find_contacts.or(self.find_contacts),
+            scan_secrets: overlay.scan_secrets.or(self.scan_secrets),
+            heading_outline: overlay.heading_outline.or(self.heading_outline),
+            secret_patterns: merge_maps(self.secret_patterns, overlay.secret_patterns),
+            use_sitemap: overlay.use_sitemap.or(self.use_sitemap),
+            max_sitemaps: overlay.max_sitemaps.or(self.max_sitemaps),
+            render: overlay.render.or(self.render),
+            gentle: overlay.gentle.or(self.gentle),
+            auto_throttle: overlay.auto_throttle.or(self.auto_throttle),
+            max_requests: overlay.max_requests.or(self.max_requests),
+            max_bandwidth: overlay.max_bandwidth.or(self.max_bandwidth),
+            max_body_bytes: overlay.max_body_bytes.or(self.max_body_bytes),
+            max_duration: overlay.max_duration.or(self.max_duration),
+            retries: overlay.retries.or(self.retries),
+            retry_backoff_ms: overlay.retry_backoff_ms.or(self.retry_backoff_ms),
+            retry_jitter: overlay.retry_jitter.or(self.retry_jitter),
+            external_concurrency: overlay.external_concurrency.or(self.external_concurrency),
+            external_rate_limit: overlay.external_rate_limit.or(self.external_rate_limit),
+            external_retries: overlay.external_retries.or(self.external_retries),
+            external_timeout: overlay.external_timeout.or(self.external_timeout),
+            external_check_sample: overlay.external_check_sample.or(self.external_check_sample),
+            max_issues_per_type: overlay.max_issues_per_type.or(self.max_issues_per_type),
+            max_pages_shown: overlay.max_pages_shown.or(self.max_pages_shown),
+            scripts: overlay.scripts.or(self.scripts),
+            progress: overlay.progress.or(self.progress),
+            crux_api_key: overlay.crux_api_key.or(self.crux_api_key),
+            budgets: match (self.budgets, overlay.budgets) {
+                (Some(base), Some(overlay)) => Some(base.merge(&overlay)),
+                (base, overlay) => overlay.or(base),
+            },
+            comment_markers: overlay.comment_markers.or(self.comment_markers),
+            rules: merge_maps(self.rules, overlay.rules),
+            assertions: overlay.assertions.or(self.assertions),
+            schedule: overlay.schedule.or(self.schedule),
+            notify_url: overlay.notify_url.or(self.notify_url),
+            api_token: overlay.api_token.or(self.api_token),
+            priority: overlay.priority.or(self.priority),
+            api_tokens: overlay.api_tokens.or(self.api_tokens),
+            profiles: merge_maps(self.profiles, overlay.profiles),
+        }
+    }
+
     /// Resolve raw CLI inputs and config values into the effective runtime options.
-    /// Explicit CLI values take precedence; otherwise config values are used, then defaults.
+    /// Explicit CLI values take precedence; otherwise config values are used, then the
+    /// selected preset's values, then defaults.
     pub fn resolve_runtime_options(&self, cli: &Cli) -> RuntimeOptions {
+        let preset = cli
+            .preset
+            .or(self.preset)
+            .unwrap_or(CrawlPreset::Standard)
+            .settings();
+        let gentle = resolve_switch(cli.gentle, cli.no_gentle, self.gentle);
+        let link_timeout = cli
+            .link_timeout
+            .or(self.link_timeout)
+            .unwrap_or(DEFAULT_LINK_TIMEOUT_SECS);
+        // Start URLs on the command line replace the config file's
+        let start_urls: Vec<String> = if cli.urls.is_empty() {
+            self.url
+                .iter()
+                .chain(self.urls.iter().flatten())
+                .cloned()
+                .collect()
+        } else {
+            cli.urls.clone()
+        };
+
         RuntimeOptions {
-            url: cli.url.clone(),
-            depth: cli.depth.or(self.depth).unwrap_or(DEFAULT_DEPTH),
-            max_pages: cli
-                .max_pages
-                .or(self.max_pages)
-                .unwrap_or(DEFAULT_MAX_PAGES),
+            url: start_urls.first().cloned(),
+            additional_urls: start_urls.iter().skip(1).cloned().collect(),
+            depth: cli.depth.or(self.depth).unwrap_or(preset.depth),
+            max_pages: cli.max_pages.or(self.max_pages).unwrap_or(preset.max_pages),
             output: cli.output.or(self.output),
+            json_style: cli.json_style.or(self.json_style).unwrap_or_default(),
             save: cli.save.clone().or_else(|| self.save.clone()),
+            save_dir: cli.save_dir.clone().or_else(|| self.save_dir.clone()),
+            result_file: cli.result_file.clone().or_else(|| self.result_file.clone()),
+            write_baseline: cli.write_baseline.clone(),
+            emit_sitemap: cli.emit_sitemap.clone(),
+            outline_dir: cli.outline_dir.clone(),
+            resume: cli.resume.clone(),
+            cache_dir: cli.cache_dir.clone().or_else(|| self.cache_dir.clone()),
+            page_store: cli.page_store.clone().or_else(|| self.page_store.clone()),
+            save_html: cli.save_html.clone().or_else(|| self.save_html.clone()),
+            explain: cli.explain.clone(),
+            // Writing a baseline records every issue, so a configured baseline must not filter them first
+            baseline: cli.baseline.clone().or_else(|| {
+                self.baseline
+                    .clone()
+                    .filter(|_| cli.write_baseline.is_none())
+            }),
+            state_dir: cli.state_dir.clone().or_else(|| self.state_dir.clone()),
+            screenshots: cli.screenshots.or(self.screenshots),
+            screenshot_browser: self.screenshot_browser.clone(),
             cli: cli.cli || self.cli.unwrap_or(false),
-            external: cli.external || self.external.unwrap_or(false),
-            verbose: cli.verbose || self.verbose.unwrap_or(false),
-            ignore_redirects: cli.ignore_redirects || self.ignore_redirects.unwrap_or(false),
-            keep_fragments: cli.keep_fragments || self.keep_fragments.unwrap_or(false),
-            rate_limit: cli.rate_limit.or(self.rate_limit),
-            concurrency: cli
-                .concurrency
-                .or(self.concurrency)
-                .unwrap_or(DEFAULT_CONCURRENCY),
+            external: resolve_switch(cli.external, cli.no_external, self.external),
+            verbose: resolve_switch(cli.verbose, cli.no_verbose, self.verbose),
+            ignore_redirects: resolve_switch(
+                cli.ignore_redirects,
+                cli.no_ignore_redirects,
+                self.ignore_redirects,
+            ),
+            check_links: cli
+                .check_links
+                .or(self.check_links)
+                .unwrap_or(preset.check_links),
+            never_check_domains: self.never_check_domains.clone().unwrap_or_default(),
+            always_check_domains: self.always_check_domains.clone().unwrap_or_default(),
+            html_content_types: self.html_content_types.clone(),
+            keep_fragments: resolve_switch(
+                cli.keep_fragments,
+                cli.no_keep_fragments,
+                self.keep_fragments,
+            ),
+            include: if cli.include.is_empty() {
+                self.include.clone().unwrap_or_default()
+            } else {
+                cli.include.clone()
+            },
+            exclude: if cli.exclude.is_empty() {
+                self.exclude.clone().unwrap_or_default()
+            } else {
+                cli.exclude.clone()
+            },
+            strip_tracking_params: resolve_switch(
+                cli.strip_tracking_params,
+                cli.no_strip_tracking_params,
+                self.strip_tracking_params,
+            ),
+            sort_query: resolve_switch(cli.sort_query, cli.no_sort_query, self.sort_query),
+            respect_nofollow: resolve_switch(
+                cli.respect_nofollow,
+                cli.no_respect_nofollow,
+                self.respect_nofollow,
+            ),
+            ignore_params: if cli.ignore_params.is_empty() {
+                self.ignore_params.clone().unwrap_or_default()
+            } else {
+                cli.ignore_params.clone()
+            },
+            rate_limit: cli
+                .rate_limit
+                .or(self.rate_limit)
+                .or(gentle.then_some(GENTLE_RATE_LIMIT)),
+            concurrency: cli.concurrency.or(self.concurrency).unwrap_or(if gentle {
+                GENTLE_CONCURRENCY
+            } else {
+                preset.concurrency
+            }),
+            timeout: cli
+                .timeout
+                .or(self.timeout)
+                .unwrap_or(DEFAULT_PAGE_TIMEOUT_SECS),
+            link_timeout,
+            insecure: resolve_switch(cli.insecure, cli.no_insecure, self.insecure),
+            ca_cert: cli.ca_cert.clone().or(self.ca_cert.clone()),
             respect_robots_txt: cli
                 .respect_robots_txt
                 .or(self.respect_robots_txt)
                 .unwrap_or(DEFAULT_RESPECT_ROBOTS_TXT),
+            respect_robots_for_links: resolve_switch(
+                cli.respect_robots_for_links,
+                cli.no_respect_robots_for_links,
+                self.respect_robots_for_links,
+            ),
+            audit_tracking_links: resolve_switch(
+                cli.audit_tracking_links,
+                cli.no_audit_tracking_links,
+                self.audit_tracking_links,
+            ),
+            block_private_ips: resolve_switch(
+                cli.block_private_ips,
+                cli.no_block_private_ips,
+                self.block_private_ips,
+            ),
+            robots_agent: cli
+                .robots_agent
+                .clone()
+                .or(self.robots_agent.clone())
+                .unwrap_or_else(|| DEFAULT_ROBOTS_AGENT.to_string()),
+            cookies: if cli.cookies.is_empty() {
+                self.cookies.clone().unwrap_or_default()
+            } else {
+                cli.cookies.clone()
+            },
+            cookies_file: cli.cookies_file.clone().or(self.cookies_file.clone()),
+            login: self.login.clone(),
+            audit_vary: resolve_switch(cli.audit_vary, cli.no_audit_vary, self.audit_vary),
+            trace_redirects: resolve_switch(
+                cli.trace_redirects,
+                cli.no_trace_redirects,
+                self.trace_redirects,
+            ),
+            redirect_chain_limit: cli
+                .redirect_chain_limit
+                .or(self.redirect_chain_limit)
+                .unwrap_or(DEFAULT_REDIRECT_CHAIN_LIMIT),
+            find_contacts: resolve_switch(
+                cli.find_contacts,
+                cli.no_find_contacts,
+                self.find_contacts,
+            ),
+            scan_secrets: resolve_switch(cli.scan_secrets, cli.no_scan_secrets, self.scan_secrets),
+            heading_outline: resolve_switch(
+                cli.heading_outline,
+                cli.no_heading_outline,
+                self.heading_outline,
+            ) || cli.outline_dir.is_some(),
+            secret_patterns: self.secret_patterns.clone().unwrap_or_default(),
+            use_sitemap: resolve_switch(cli.use_sitemap, cli.no_use_sitemap, self.use_sitemap),
+            max_sitemaps: cli
+                .max_sitemaps
+                .or(self.max_sitemaps)
+                .unwrap_or(DEFAULT_MAX_SITEMAPS),
+            render: cli.render.or(self.render).unwrap_or_default(),
+            gentle,
+            auto_throttle: resolve_switch(
+                cli.auto_throttle,
+                cli.no_auto_throttle,
+                self.auto_throttle,
+            ),
+            max_requests: cli.max_requests.or(self.max_requests),
+            max_bandwidth: cli.max_bandwidth.or(self.max_bandwidth),
+            max_body_bytes: cli
+                .max_body_bytes
+                .or(self.max_body_bytes)
+                .unwrap_or(ByteSize(DEFAULT_MAX_BODY_BYTES)),
+            max_duration: cli.max_duration.or(self.max_duration),
+            retries: cli.retries.or(self.retries).unwrap_or(DEFAULT_RETRIES),
+            retry_backoff_ms: cli
+                .retry_backoff_ms
+                .or(self.retry_backoff_ms)
+                .unwrap_or(DEFAULT_RETRY_BACKOFF_MS),
+            retry_jitter: cli.retry_jitter.or(self.retry_jitter).unwrap_or(true),
+            external_concurrency: cli.external_concurrency.or(self.external_concurrency),
+            external_rate_limit: cli.external_rate_limit.or(self.external_rate_limit),
+            external_retries: cli.external_retries.or(self.external_retries),
+            external_timeout: cli
+                .external_timeout
+                .or(self.external_timeout)
+                .unwrap_or(link_timeout),
+            external_check_sample: cli.external_check_sample.or(self.external_check_sample),
+            max_issues_per_type: cli.max_issues_per_type.or(self.max_issues_per_type),
+            max_pages_shown: cli.max_pages_shown.or(self.max_pages_shown),
+            scripts: if cli.scripts.is_empty() {
+                self.scripts.clone().unwrap_or_default()
+            } else {
+                cli.scripts.clone()
+            },
+            progress: cli.progress.or(self.progress).unwrap_or_default(),
+            budget: self.budgets.unwrap_or_default(),
+            comment_markers: self.comment_markers.clone(),
+            crux_api_key: self.crux_api_key.clone(),
+            rule_overrides: self.rules.clone().unwrap_or_default(),
+            assertions: self.assertions.clone().unwrap_or_default(),
             tui: cli.tui,
             config: cli.config.clone(),
         }
     }
 }
 
+/// Merge keyed config tables entry by entry; entries from `overlay` replace same-named ones
+fn merge_maps<T>(
+    base: Option<BTreeMap<String, T>>,
+    overlay: Option<BTreeMap<String, T>>,
+) -> Option<BTreeMap<String, T>> {
+    match (base, overlay) {
+        (Some(mut base), Some(overlay)) => {
+            base.extend(overlay);
+            Some(base)
+        }
+        (base, overlay) => overlay.or(base),
+    }
+}
+
+/// Resolve an on/off switch: `--flag` or `--no-flag` on the command line win over the config value
+fn resolve_switch(enabled: bool, disabled: bool, config: Option<bool>) -> bool {
+    if enabled {
+        true
+    } else if disabled {
+        false
+    } else {
+        config.unwrap_or(false)
+    }
+}
+
 impl RuntimeOptions {
     pub fn from_cli_and_config(cli: &Cli, config: Option<&Config>) -> Self {
         match config {
@@ -207,31 +854,152 @@ impl RuntimeOptions {
             None => Config::default().resolve_runtime_options(cli),
         }
     }
+
+    /// How the crawler and link checker retry transient request failures
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.retries,
+            backoff: Duration::from_millis(self.retry_backoff_ms),
+            jitter: self.retry_jitter,
+        }
+    }
+
+    /// The content types parsed as HTML, from `html_content_types` or the defaults
+    pub fn resolved_html_content_types(&self) -> HtmlContentTypes {
+        self.html_content_types
+            .as_deref()
+            .map_or_else(HtmlContentTypes::default, HtmlContentTypes::new)
+    }
+
+    /// How page requests and link checks verify certificates
+    pub fn tls_options(&self) -> Result<TlsOptions> {
+        TlsOptions::new(self.insecure, self.ca_cert.as_deref().map(Path::new))
+    }
+
+    /// How the link checker paces and samples links to other hosts
+    pub fn external_check_policy(&self) -> ExternalCheckPolicy {
+        ExternalCheckPolicy {
+            concurrency: self.external_concurrency.unwrap_or(self.concurrency),
+            rate_limit: self.external_rate_limit,
+            retry_policy: RetryPolicy {
+                retries: self.external_retries.unwrap_or(self.retries),
+                ..self.retry_policy()
+            },
+            timeout: Duration::from_secs(self.external_timeout),
+            sample: self.external_check_sample,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::{DEFAULT_CONCURRENCY, DEFAULT_DEPTH, DEFAULT_MAX_PAGES};
+    use crate::daemon::api::ApiScope;
+    use crate::models::IssueType;
     use serial_test::serial;
     use tempfile::NamedTempFile;
 
     fn cli(url: &str) -> Cli {
         Cli {
-            url: Some(url.to_string()),
+            urls: vec![url.to_string()],
+            preset: None,
             depth: None,
             max_pages: None,
             output: None,
+            json_style: None,
             cli: false,
             tui: false,
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            url_file: None,
+            recrawl_urls_from: None,
+            only_errors: false,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
             external: false,
+            no_external: false,
             verbose: false,
+            no_verbose: false,
             ignore_redirects: false,
+            no_ignore_redirects: false,
+            check_links: None,
             keep_fragments: false,
+            no_keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            no_strip_tracking_params: false,
+            sort_query: false,
+            no_sort_query: false,
+            respect_nofollow: false,
+            no_respect_nofollow: false,
+            ignore_params: vec![],
             rate_limit: None,
             concurrency: None,
+            timeout: None,
+            link_timeout: None,
+            insecure: false,
+            no_insecure: false,
+            ca_cert: None,
             respect_robots_txt: None,
-            config: None,
+            respect_robots_for_links: false,
+            no_respect_robots_for_links: false,
+            audit_tracking_links: false,
+            no_audit_tracking_links: false,
+            block_private_ips: false,
+            no_block_private_ips: false,
+            robots_agent: None,
+            cookies: vec![],
+            cookies_file: None,
+            audit_vary: false,
+            no_audit_vary: false,
+            trace_redirects: false,
+            no_trace_redirects: false,
+            redirect_chain_limit: None,
+            find_contacts: false,
+            no_find_contacts: false,
+            scan_secrets: false,
+            no_scan_secrets: false,
+            heading_outline: false,
+            no_heading_outline: false,
+            use_sitemap: false,
+            no_use_sitemap: false,
+            max_sitemaps: None,
+            render: None,
+            gentle: false,
+            no_gentle: false,
+            auto_throttle: false,
+            no_auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: None,
+            max_duration: None,
+            retries: None,
+            retry_backoff_ms: None,
+            retry_jitter: None,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: None,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: None,
+            config: vec![],
+            profile: None,
+            command: None,
         }
     }
 
@@ -446,21 +1214,104 @@ url: "test
         };
 
         let cli = Cli {
-            url: Some("https://example.com".to_string()),
+            urls: vec!["https://example.com".to_string()],
+            preset: None,
             depth: Some(20),
             max_pages: Some(400),
             output: Some(OutputFormat::Text),
+            json_style: None,
             cli: false,
             tui: false,
             save: Some("report.txt".to_string()),
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            url_file: None,
+            recrawl_urls_from: None,
+            only_errors: false,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
             external: true,
+            no_external: false,
             verbose: true,
+            no_verbose: false,
             ignore_redirects: true,
+            no_ignore_redirects: false,
+            check_links: None,
             keep_fragments: false,
+            no_keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            no_strip_tracking_params: false,
+            sort_query: false,
+            no_sort_query: false,
+            respect_nofollow: false,
+            no_respect_nofollow: false,
+            ignore_params: vec![],
             rate_limit: Some(2.0),
             concurrency: Some(15),
+            timeout: None,
+            link_timeout: None,
+            insecure: false,
+            no_insecure: false,
+            ca_cert: None,
             respect_robots_txt: Some(false),
-            config: None,
+            respect_robots_for_links: false,
+            no_respect_robots_for_links: false,
+            audit_tracking_links: false,
+            no_audit_tracking_links: false,
+            block_private_ips: false,
+            no_block_private_ips: false,
+            robots_agent: None,
+            cookies: vec![],
+            cookies_file: None,
+            audit_vary: false,
+            no_audit_vary: false,
+            trace_redirects: false,
+            no_trace_redirects: false,
+            redirect_chain_limit: None,
+            find_contacts: false,
+            no_find_contacts: false,
+            scan_secrets: false,
+            no_scan_secrets: false,
+            heading_outline: false,
+            no_heading_outline: false,
+            use_sitemap: false,
+            no_use_sitemap: false,
+            max_sitemaps: None,
+            render: None,
+            gentle: false,
+            no_gentle: false,
+            auto_throttle: false,
+            no_auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: None,
+            max_duration: None,
+            retries: None,
+            retry_backoff_ms: None,
+            retry_jitter: None,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: None,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: None,
+            config: vec![],
+            profile: None,
+            command: None,
         };
 
         let resolved = config.resolve_runtime_options(&cli);
@@ -476,6 +1327,49 @@ url: "test
         assert!(!resolved.respect_robots_txt);
     }
 
+    #[test]
+    fn test_resolve_runtime_options_explicit_cli_default_values_win() {
+        use clap::Parser;
+
+        let config = Config {
+            depth: Some(10),
+            concurrency: Some(10),
+            external: Some(true),
+            verbose: Some(true),
+            keep_fragments: Some(true),
+            ..Default::default()
+        };
+
+        let explicit = Cli::parse_from([
+            "scoutly",
+            "https://example.com",
+            "--depth",
+            &DEFAULT_DEPTH.to_string(),
+            "--concurrency",
+            &DEFAULT_CONCURRENCY.to_string(),
+            "--no-external",
+            "--no-verbose",
+        ]);
+        let resolved = config.resolve_runtime_options(&explicit);
+        assert_eq!(resolved.depth, DEFAULT_DEPTH);
+        assert_eq!(resolved.concurrency, DEFAULT_CONCURRENCY);
+        assert!(!resolved.external);
+        assert!(!resolved.verbose);
+        assert!(
+            resolved.keep_fragments,
+            "Unset switches fall back to config"
+        );
+
+        // The last of `--flag` / `--no-flag` wins
+        let toggled = Cli::parse_from([
+            "scoutly",
+            "https://example.com",
+            "--no-keep-fragments",
+            "--keep-fragments",
+        ]);
+        assert!(config.resolve_runtime_options(&toggled).keep_fragments);
+    }
+
     #[test]
     fn test_runtime_options_from_cli_and_config_without_config_uses_defaults() {
         let resolved = RuntimeOptions::from_cli_and_config(&cli("https://example.com"), None);
@@ -503,6 +1397,522 @@ url: "test
         assert!(!resolved.respect_robots_txt);
     }
 
+    #[test]
+    fn test_resolve_runtime_options_check_links_scope() {
+        let config = Config {
+            check_links: Some(LinkCheckScope::Internal),
+            ..Default::default()
+        };
+
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(resolved.check_links, LinkCheckScope::Internal);
+
+        let mut explicit = cli("https://example.com");
+        explicit.check_links = Some(LinkCheckScope::None);
+        let resolved = config.resolve_runtime_options(&explicit);
+        assert_eq!(resolved.check_links, LinkCheckScope::None);
+
+        let resolved = RuntimeOptions::from_cli_and_config(&cli("https://example.com"), None);
+        assert_eq!(resolved.check_links, LinkCheckScope::All);
+    }
+
+    #[test]
+    fn test_resolve_runtime_options_baseline() {
+        let config = Config {
+            baseline: Some("scoutly-baseline.json".to_string()),
+            ..Default::default()
+        };
+
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(resolved.baseline.as_deref(), Some("scoutly-baseline.json"));
+
+        let mut writing = cli("https://example.com");
+        writing.write_baseline = Some("new-baseline.json".to_string());
+        let resolved = config.resolve_runtime_options(&writing);
+        assert_eq!(
+            resolved.write_baseline.as_deref(),
+            Some("new-baseline.json")
+        );
+        assert_eq!(
+            resolved.baseline, None,
+            "A configured baseline should not filter the issues being recorded"
+        );
+    }
+
+    #[test]
+    fn test_start_urls_from_command_line_replace_config_urls() {
+        let config = Config {
+            url: Some("https://example.com".to_string()),
+            urls: Some(vec!["https://blog.example.com".to_string()]),
+            ..Default::default()
+        };
+
+        let mut no_url = cli("https://example.com");
+        no_url.urls.clear();
+        let resolved = config.resolve_runtime_options(&no_url);
+        assert_eq!(resolved.url.as_deref(), Some("https://example.com"));
+        assert_eq!(resolved.additional_urls, ["https://blog.example.com"]);
+
+        let mut two_urls = cli("https://shop.example.com");
+        two_urls.urls.push("https://docs.example.com".to_string());
+        let resolved = config.resolve_runtime_options(&two_urls);
+        assert_eq!(resolved.url.as_deref(), Some("https://shop.example.com"));
+        assert_eq!(resolved.additional_urls, ["https://docs.example.com"]);
+    }
+
+    #[test]
+    fn test_query_normalization_options_from_config_and_command_line() {
+        let config = Config {
+            strip_tracking_params: Some(true),
+            ignore_params: Some(vec!["sessionid".to_string()]),
+            ..Default::default()
+        };
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert!(resolved.strip_tracking_params);
+        assert!(!resolved.sort_query);
+        assert_eq!(resolved.ignore_params, ["sessionid"]);
+
+        let mut overrides = cli("https://example.com");
+        overrides.no_strip_tracking_params = true;
+        overrides.sort_query = true;
+        overrides.ignore_params = vec!["ref_*".to_string()];
+        let resolved = config.resolve_runtime_options(&overrides);
+        assert!(!resolved.strip_tracking_params);
+        assert!(resolved.sort_query);
+        assert_eq!(resolved.ignore_params, ["ref_*"]);
+    }
+
+    #[test]
+    fn test_robots_agent_defaults_to_scoutly() {
+        let resolved = Config::default().resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(resolved.robots_agent, DEFAULT_ROBOTS_AGENT);
+
+        let config = Config {
+            robots_agent: Some("sitebot".to_string()),
+            ..Default::default()
+        };
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(resolved.robots_agent, "sitebot");
+
+        let mut override_agent = cli("https://example.com");
+        override_agent.robots_agent = Some("otherbot".to_string());
+        let resolved = config.resolve_runtime_options(&override_agent);
+        assert_eq!(resolved.robots_agent, "otherbot");
+    }
+
+    #[test]
+    fn test_load_api_tokens_from_toml_config() {
+        let toml_content = r#"
+[[api_tokens]]
+token = "ci-token"
+scopes = ["crawl", "reports"]
+
+[[api_tokens]]
+token = "dashboard-token"
+scopes = ["reports"]
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_path = temp_file.path().with_extension("toml");
+        fs::write(&temp_path, toml_content).unwrap();
+
+        let tokens = Config::from_file(&temp_path).unwrap().api_tokens.unwrap();
+        assert_eq!(
+            tokens,
+            [
+                ApiToken {
+                    token: "ci-token".to_string(),
+                    scopes: vec![ApiScope::Crawl, ApiScope::Reports],
+                },
+                ApiToken {
+                    token: "dashboard-token".to_string(),
+                    scopes: vec![ApiScope::Reports],
+                },
+            ]
+        );
+        fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_load_profiles_from_toml_config() {
+        let toml_content = r#"
+depth = 3
+concurrency = 4
+
+[profiles.staging]
+url = "https://staging.example.com"
+depth = 1
+
+[profiles.prod]
+url = "https://example.com"
+check_links = "external"
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_path = temp_file.path().with_extension("toml");
+        fs::write(&temp_path, toml_content).unwrap();
+
+        let config = Config::from_file(&temp_path).unwrap();
+        let staging = config.clone().with_profile("staging").unwrap();
+        assert_eq!(staging.url.as_deref(), Some("https://staging.example.com"));
+        assert_eq!(staging.depth, Some(1));
+        assert_eq!(staging.concurrency, Some(4));
+        assert!(staging.profiles.is_none());
+
+        let prod = config.clone().with_profile("prod").unwrap();
+        assert_eq!(prod.depth, Some(3));
+        assert_eq!(prod.check_links, Some(LinkCheckScope::External));
+
+        let mut no_url = cli("https://example.com");
+        no_url.urls.clear();
+        let resolved = staging.resolve_runtime_options(&no_url);
+        assert_eq!(resolved.url.as_deref(), Some("https://staging.example.com"));
+        let resolved = staging.resolve_runtime_options(&cli("https://override.example.com"));
+        assert_eq!(
+            resolved.url.as_deref(),
+            Some("https://override.example.com")
+        );
+
+        let error = config.with_profile("dev").unwrap_err().to_string();
+        assert!(error.contains("Profile 'dev' not found"));
+        assert!(error.contains("prod, staging"));
+
+        fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_load_path_rule_overrides_from_toml_config() {
+        let toml_content = r#"
+[rules."/blog/tags/**"]
+title_min_length = 10
+disable = ["ThinContent", "MissingMetaDescription"]
+
+[profiles.strict.rules."/docs/**"]
+title_max_length = 70
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_path = temp_file.path().with_extension("toml");
+        fs::write(&temp_path, toml_content).unwrap();
+
+        let config = Config::from_file(&temp_path).unwrap();
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        let tags = &resolved.rule_overrides["/blog/tags/**"];
+        assert_eq!(tags.title_min_length, Some(10));
+        assert_eq!(
+            tags.disable.as_deref(),
+            Some(&[IssueType::ThinContent, IssueType::MissingMetaDescription][..])
+        );
+
+        let strict = config.with_profile("strict").unwrap();
+        let rules = strict.rules.unwrap();
+        assert_eq!(rules.len(), 2, "Profile rules should extend the base rules");
+        assert_eq!(rules["/docs/**"].title_max_length, Some(70));
+
+        fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_load_page_assertions_from_toml_config() {
+        let toml_content = r#"
+[[assertions]]
+selector = "form#newsletter"
+on = "/blog/**"
+
+[[assertions]]
+selector = ".lorem-ipsum"
+must_exist = false
+message = "Placeholder text left on the page"
+
+[[assertions]]
+pattern = 'old-cdn\.example\.com'
+must_exist = false
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_path = temp_file.path().with_extension("toml");
+        fs::write(&temp_path, toml_content).unwrap();
+
+        let config = Config::from_file(&temp_path).unwrap();
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(
+            resolved.assertions,
+            [
+                PageAssertion {
+                    selector: Some("form#newsletter".to_string()),
+                    pattern: None,
+                    must_exist: true,
+                    on: Some("/blog/**".to_string()),
+                    message: None,
+                },
+                PageAssertion {
+                    selector: Some(".lorem-ipsum".to_string()),
+                    pattern: None,
+                    must_exist: false,
+                    on: None,
+                    message: Some("Placeholder text left on the page".to_string()),
+                },
+                PageAssertion {
+                    selector: None,
+                    pattern: Some(r"old-cdn\.example\.com".to_string()),
+                    must_exist: false,
+                    on: None,
+                    message: None,
+                },
+            ]
+        );
+
+        fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_load_login_from_toml_config() {
+        let toml_content = r#"
+[login]
+url = "https://example.com/login"
+success_selector = "a[href='/logout']"
+
+[login.fields]
+username = "auditor"
+password = "${SITE_PASSWORD}"
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_path = temp_file.path().with_extension("toml");
+        fs::write(&temp_path, toml_content).unwrap();
+
+        let config = Config::from_file(&temp_path).unwrap();
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(
+            resolved.login,
+            Some(LoginConfig {
+                url: "https://example.com/login".to_string(),
+                fields: BTreeMap::from([
+                    ("password".to_string(), "${SITE_PASSWORD}".to_string()),
+                    ("username".to_string(), "auditor".to_string()),
+                ]),
+                success_selector: "a[href='/logout']".to_string(),
+            })
+        );
+
+        fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_load_performance_budgets_from_toml_config() {
+        let toml_content = r#"
+[budgets]
+max_html_bytes = 100000
+max_scripts = 5
+
+[rules."/app/**"]
+max_scripts = 20
+
+[profiles.mobile.budgets]
+max_requests = 30
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_path = temp_file.path().with_extension("toml");
+        fs::write(&temp_path, toml_content).unwrap();
+
+        let config = Config::from_file(&temp_path).unwrap();
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(resolved.budget.max_html_bytes, Some(100_000));
+        assert_eq!(resolved.budget.max_requests, None);
+        assert_eq!(
+            resolved.rule_overrides["/app/**"].budget.max_scripts,
+            Some(20)
+        );
+
+        let mobile = config.with_profile("mobile").unwrap();
+        let resolved = mobile.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(
+            resolved.budget,
+            PerformanceBudget {
+                max_html_bytes: Some(100_000),
+                max_requests: Some(30),
+                max_scripts: Some(5),
+            },
+            "Profile budgets should extend the base budgets"
+        );
+
+        fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_from_files_layers_configs_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let base_path = dir.path().join("base.toml");
+        let site_path = dir.path().join("site.json");
+        fs::write(
+            &base_path,
+            r#"
+depth = 3
+concurrency = 4
+never_check_domains = ["linkedin.com"]
+
+[rules."/blog/**"]
+title_min_length = 20
+        "#,
+        )
+        .unwrap();
+        fs::write(
+            &site_path,
+            r#"{"depth": 6, "rules": {"/docs/**": {"title_max_length": 70}}}"#,
+        )
+        .unwrap();
+
+        let config = Config::from_files(&[base_path.clone(), site_path.clone()]).unwrap();
+        assert_eq!(config.depth, Some(6));
+        assert_eq!(config.concurrency, Some(4));
+        assert_eq!(
+            config.never_check_domains,
+            Some(vec!["linkedin.com".to_string()])
+        );
+        assert_eq!(config.rules.unwrap().len(), 2);
+
+        let reversed = Config::from_files(&[site_path, base_path]).unwrap();
+        assert_eq!(reversed.depth, Some(3));
+
+        let missing = Config::from_files(&[dir.path().join("missing.toml")]);
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_resolve_runtime_options_applies_preset_below_cli_and_config() {
+        let mut quick = cli("https://example.com");
+        quick.preset = Some(CrawlPreset::Quick);
+
+        let resolved = Config::default().resolve_runtime_options(&quick);
+        assert_eq!(resolved.depth, 2);
+        assert_eq!(resolved.max_pages, 50);
+        assert_eq!(resolved.concurrency, 10);
+        assert_eq!(resolved.check_links, LinkCheckScope::Internal);
+
+        let config = Config {
+            preset: Some(CrawlPreset::Deep),
+            max_pages: Some(300),
+            ..Default::default()
+        };
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(resolved.depth, 10);
+        assert_eq!(resolved.max_pages, 300, "Config values win over the preset");
+
+        quick.depth = Some(4);
+        let resolved = config.resolve_runtime_options(&quick);
+        assert_eq!(resolved.depth, 4, "Explicit CLI values win over the preset");
+        assert_eq!(
+            resolved.check_links,
+            LinkCheckScope::Internal,
+            "The CLI preset wins over the config preset"
+        );
+    }
+
+    #[test]
+    fn test_resolve_runtime_options_gentle_slows_down_unless_set_explicitly() {
+        let mut gentle = cli("https://example.com");
+        gentle.gentle = true;
+        gentle.preset = Some(CrawlPreset::Deep);
+
+        let resolved = Config::default().resolve_runtime_options(&gentle);
+        assert!(resolved.gentle);
+        assert_eq!(resolved.concurrency, GENTLE_CONCURRENCY);
+        assert_eq!(resolved.rate_limit, Some(GENTLE_RATE_LIMIT));
+        assert_eq!(resolved.depth, 10, "Other preset values still apply");
+
+        let config = Config {
+            gentle: Some(true),
+            rate_limit: Some(0.5),
+            ..Default::default()
+        };
+        let mut explicit = cli("https://example.com");
+        explicit.concurrency = Some(3);
+        let resolved = config.resolve_runtime_options(&explicit);
+        assert!(resolved.gentle);
+        assert_eq!(resolved.concurrency, 3);
+        assert_eq!(resolved.rate_limit, Some(0.5));
+
+        explicit.no_gentle = true;
+        let resolved = config.resolve_runtime_options(&explicit);
+        assert!(!resolved.gentle);
+        assert_eq!(resolved.rate_limit, Some(0.5));
+    }
+
+    #[test]
+    fn test_resolve_runtime_options_timeouts() {
+        let resolved = Config::default().resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(resolved.timeout, DEFAULT_PAGE_TIMEOUT_SECS);
+        assert_eq!(resolved.link_timeout, DEFAULT_LINK_TIMEOUT_SECS);
+        assert_eq!(resolved.external_timeout, DEFAULT_LINK_TIMEOUT_SECS);
+
+        let config = Config {
+            timeout: Some(60),
+            link_timeout: Some(20),
+            ..Default::default()
+        };
+        let mut explicit = cli("https://example.com");
+        explicit.link_timeout = Some(5);
+        let resolved = config.resolve_runtime_options(&explicit);
+        assert_eq!(resolved.timeout, 60);
+        assert_eq!(resolved.link_timeout, 5);
+        assert_eq!(
+            resolved.external_timeout, 5,
+            "External links wait as long as other links unless set separately"
+        );
+    }
+
+    #[test]
+    fn test_load_link_domain_rules_from_toml_config() {
+        let toml_content = r#"
+never_check_domains = ["linkedin.com", "*.internal.example"]
+always_check_domains = ["partner.example"]
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_path = temp_file.path().with_extension("toml");
+        fs::write(&temp_path, toml_content).unwrap();
+
+        let config = Config::from_file(&temp_path).unwrap();
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(
+            resolved.never_check_domains,
+            vec!["linkedin.com".to_string(), "*.internal.example".to_string()]
+        );
+        assert_eq!(
+            resolved.always_check_domains,
+            vec!["partner.example".to_string()]
+        );
+
+        fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_load_html_content_types_from_toml_config() {
+        let toml_content = r#"
+html_content_types = ["text/html", "text/xml"]
+        "#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let temp_path = temp_file.path().with_extension("toml");
+        fs::write(&temp_path, toml_content).unwrap();
+
+        let config = Config::from_file(&temp_path).unwrap();
+        let resolved = config.resolve_runtime_options(&cli("https://example.com"));
+        assert_eq!(
+            resolved.html_content_types,
+            Some(vec!["text/html".to_string(), "text/xml".to_string()])
+        );
+        assert_eq!(
+            Config::default()
+                .resolve_runtime_options(&cli("https://example.com"))
+                .html_content_types,
+            None
+        );
+
+        fs::remove_file(temp_path).ok();
+    }
+
     #[test]
     fn test_runtime_options_without_config_honors_explicit_cli_respect_robots_value() {
         let mut cli = cli("https://example.com");
@@ -642,6 +2052,21 @@ concurrency: 12
         );
     }
 
+    #[test]
+    #[serial]
+    fn test_default_paths_include_native_config_dir() {
+        let Some(native_dir) = dirs::config_dir() else {
+            return;
+        };
+
+        let paths = Config::default_paths();
+        let native_config = native_dir.join("scoutly").join("config.toml");
+        assert!(paths.contains(&native_config));
+
+        let unique: std::collections::HashSet<_> = paths.iter().collect();
+        assert_eq!(unique.len(), paths.len(), "Default paths should not repeat");
+    }
+
     #[test]
     #[serial]
     fn test_from_default_paths_finds_current_dir_config() {
@@ -0,0 +1,138 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::models::{HtmlContentTypes, IssueSeverity, IssueType, PageInfo, SeoIssue};
+use crate::rules::PathRules;
+
+/// Addresses and numbers quoted in an issue before the rest are only counted
+const MAX_LISTED: usize = 5;
+
+static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.([A-Za-z]{2,})\b")
+        .expect("email regex should be valid")
+});
+static PHONE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{1,4}\)[\s.-]?)?\d{2,4}(?:[\s.-]\d{2,5}){1,4}")
+        .expect("phone regex should be valid")
+});
+/// Dates and IP addresses have the shape of a phone number but aren't one
+static NOT_PHONE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\d{4}-\d{2}-\d{2}|\d{1,3}(?:\.\d{1,3}){3})$")
+        .expect("date and IP regex should be valid")
+});
+
+/// File extensions that make `logo@2x.png` look like an email address
+const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "svg", "webp"];
+
+/// Email addresses in `text`, in order of first appearance
+pub fn find_emails(text: &str) -> Vec<String> {
+    unique(EMAIL_REGEX.captures_iter(text).filter_map(|captures| {
+        let extension = captures.get(1)?.as_str().to_ascii_lowercase();
+        (!IMAGE_EXTENSIONS.contains(&extension.as_str()))
+            .then(|| captures.get(0).map(|found| found.as_str().to_string()))
+            .flatten()
+    }))
+}
+
+/// Phone numbers in `text`: 7 to 15 digits written with a country code, area code, or
+/// separators, in order of first appearance
+pub fn find_phone_numbers(text: &str) -> Vec<String> {
+    unique(
+        PHONE_REGEX
+            .find_iter(text)
+            .map(|found| found.as_str().trim().to_string())
+            .filter(|number| {
+                let digits = number.chars().filter(char::is_ascii_digit).count();
+                (7..=15).contains(&digits) && !NOT_PHONE_REGEX.is_match(number)
+            }),
+    )
+}
+
+/// Report the email addresses and phone numbers shown as plain text on each HTML page
+pub fn report_exposed_contacts(
+    pages: &mut HashMap<String, PageInfo>,
+    rules: &PathRules,
+    html_content_types: &HtmlContentTypes,
+) {
+    for page in pages.values_mut() {
+        report_page_contacts(page, rules, html_content_types);
+    }
+}
+
+/// `report_exposed_contacts` for a single page
+pub fn report_page_contacts(
+    page: &mut PageInfo,
+    rules: &PathRules,
+    html_content_types: &HtmlContentTypes,
+) {
+    if !html_content_types.contains(page.content_type.as_deref()) {
+        return;
+    }
+    let rule = rules.for_url(&page.url);
+
+    for (issue_type, found, label) in [
+        (
+            IssueType::ExposedEmail,
+            find_emails(&page.content.text),
+            "email address(es)",
+        ),
+        (
+            IssueType::ExposedPhoneNumber,
+            find_phone_numbers(&page.content.text),
+            "phone number(s)",
+        ),
+    ] {
+        if found.is_empty() || rule.is_disabled(issue_type) {
+            continue;
+        }
+
+        let mut listed = found[..found.len().min(MAX_LISTED)].join(", ");
+        if found.len() > MAX_LISTED {
+            listed.push_str(&format!(" and {} more", found.len() - MAX_LISTED));
+        }
+        page.issues.push(
+            SeoIssue::new(
+                IssueSeverity::Info,
+                issue_type,
+                format!("Page shows {} {label} in plain text", found.len()),
+            )
+            .with_actual(listed),
+        );
+    }
+}
+
+fn unique(values: impl Iterator<Item = String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for value in values {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_emails_skips_retina_image_names() {
+        assert_eq!(
+            find_emails(
+                "Write to sales@example.com or Jo.Doe+news@mail.example.co.uk. sales@example.com again, logo@2x.png"
+            ),
+            ["sales@example.com", "Jo.Doe+news@mail.example.co.uk"]
+        );
+    }
+
+    #[test]
+    fn test_find_phone_numbers_skips_dates_and_addresses() {
+        assert_eq!(
+            find_phone_numbers(
+                "Call +1 (555) 123-4567 or 020 7946 0958. Updated 2026-01-15 from 192.168.10.12, order 12345."
+            ),
+            ["+1 (555) 123-4567", "020 7946 0958"]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use reqwest::cookie::Jar;
+use std::path::Path;
+use std::sync::Arc;
+use url::Url;
+
+use crate::login::expand_env;
+
+/// Prefix curl and browser exports put before the domain of HttpOnly cookies
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// A cookie to put in the jar: a `Set-Cookie` value and a URL it could have been set by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedCookie {
+    pub set_cookie: String,
+    pub url: Url,
+}
+
+/// Build the jar shared by the crawler and link checker: `name=value` cookies for the hosts of
+/// the start URLs, with `${NAME}` in a value read from the environment variable `NAME`, plus
+/// the unexpired cookies of a Netscape cookies file. Cookies the sites set
+/// in their responses are added to it as the crawl goes.
+pub fn build_cookie_jar(
+    cookies: &[String],
+    cookies_file: Option<&Path>,
+    start_urls: &[&str],
+) -> Result<Arc<Jar>> {
+    let mut imported = Vec::new();
+    for cookie in cookies {
+        let (name, value) = parse_cookie_arg(cookie)?;
+        let value = expand_env(value).with_context(|| format!("Failed to read cookie {name}"))?;
+        for start_url in start_urls {
+            let url = Url::parse(start_url)
+                .with_context(|| format!("Invalid start URL for --cookie: {start_url}"))?;
+            imported.push(ImportedCookie {
+                set_cookie: format!("{name}={value}; Path=/"),
+                url,
+            });
+        }
+    }
+    if let Some(path) = cookies_file {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read cookies file: {}", path.display()))?;
+        let now = chrono::Utc::now().timestamp();
+        imported.extend(
+            parse_netscape_cookies(&contents, now)
+                .with_context(|| format!("Failed to parse cookies file: {}", path.display()))?,
+        );
+    }
+
+    let jar = Jar::default();
+    for cookie in &imported {
+        jar.add_cookie_str(&cookie.set_cookie, &cookie.url);
+    }
+    Ok(Arc::new(jar))
+}
+
+/// Split a `--cookie` value into its name and value
+pub fn parse_cookie_arg(cookie: &str) -> Result<(&str, &str)> {
+    let (name, value) = cookie
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("Cookie must be NAME=VALUE: {cookie}"))?;
+    let name = name.trim();
+    if name.is_empty() || name.contains([';', ' ']) || value.contains(';') {
+        anyhow::bail!("Cookie must be NAME=VALUE with no spaces in the name or ';': {cookie}");
+    }
+    Ok((name, value.trim()))
+}
+
+/// Read a Netscape cookies.txt file: one tab-separated `domain, include subdomains, path,
+/// secure, expires, name, value` line per cookie. Cookies that expired before `now` (a Unix
+/// timestamp) are left out; an expiry of 0 marks a session cookie, which is kept.
+pub fn parse_netscape_cookies(contents: &str, now: i64) -> Result<Vec<ImportedCookie>> {
+    let mut cookies = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        let line = line.strip_prefix(HTTP_ONLY_PREFIX).unwrap_or(line);
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 6 {
+            anyhow::bail!(
+                "Line {} must have 7 tab-separated fields: domain, include subdomains, path, secure, expires, name, value",
+                index + 1
+            );
+        }
+        let (domain, include_subdomains, path, secure, expires, name) = (
+            fields[0], fields[1], fields[2], fields[3], fields[4], fields[5],
+        );
+        let value = fields.get(6).copied().unwrap_or_default();
+        let expires: i64 = expires
+            .trim()
+            .parse()
+            .with_context(|| format!("Line {} has an invalid expiry: {expires}", index + 1))?;
+        if expires != 0 && expires < now {
+            continue;
+        }
+
+        let secure = secure.eq_ignore_ascii_case("TRUE");
+        let host = domain.trim_start_matches('.');
+        let scheme = if secure { "https" } else { "http" };
+        let url = Url::parse(&format!("{scheme}://{host}/"))
+            .with_context(|| format!("Line {} has an invalid domain: {domain}", index + 1))?;
+
+        let mut set_cookie = format!("{name}={value}; Path={path}");
+        if include_subdomains.eq_ignore_ascii_case("TRUE") {
+            set_cookie.push_str(&format!("; Domain={host}"));
+        }
+        if secure {
+            set_cookie.push_str("; Secure");
+        }
+        cookies.push(ImportedCookie { set_cookie, url });
+    }
+    Ok(cookies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cookie_arg() {
+        assert_eq!(
+            parse_cookie_arg("session=abc=123").unwrap(),
+            ("session", "abc=123")
+        );
+        assert!(parse_cookie_arg("session").is_err());
+        assert!(parse_cookie_arg("=abc").is_err());
+        assert!(parse_cookie_arg("session=abc; Path=/").is_err());
+    }
+
+    #[test]
+    fn test_build_cookie_jar_reads_values_from_the_environment() {
+        use reqwest::cookie::CookieStore;
+
+        // SAFETY: no other test reads or writes this variable
+        unsafe { std::env::set_var("SCOUTLY_TEST_COOKIE_VALUE", "abc123") };
+        let jar = build_cookie_jar(
+            &["session=${SCOUTLY_TEST_COOKIE_VALUE}".to_string()],
+            None,
+            &["https://example.com/"],
+        )
+        .unwrap();
+        let header = jar.cookies(&Url::parse("https://example.com/").unwrap());
+        assert_eq!(header.unwrap().to_str().unwrap(), "session=abc123");
+
+        let error = build_cookie_jar(
+            &["session=${SCOUTLY_TEST_COOKIE_UNSET}".to_string()],
+            None,
+            &["https://example.com/"],
+        )
+        .unwrap_err();
+        assert!(format!("{error:#}").contains("SCOUTLY_TEST_COOKIE_UNSET"));
+    }
+
+    #[test]
+    fn test_parse_netscape_cookies() {
+        let contents = "# Netscape HTTP Cookie File\n\
+            \n\
+            .example.com\tTRUE\t/\tTRUE\t0\tsession\tabc123\n\
+            #HttpOnly_shop.example.com\tFALSE\t/cart\tFALSE\t2000000000\tcart\t42\n\
+            example.com\tFALSE\t/\tFALSE\t1000\texpired\tgone\n";
+
+        let cookies = parse_netscape_cookies(contents, 1_500_000_000).unwrap();
+
+        assert_eq!(
+            cookies,
+            vec![
+                ImportedCookie {
+                    set_cookie: "session=abc123; Path=/; Domain=example.com; Secure".to_string(),
+                    url: Url::parse("https://example.com/").unwrap(),
+                },
+                ImportedCookie {
+                    set_cookie: "cart=42; Path=/cart".to_string(),
+                    url: Url::parse("http://shop.example.com/").unwrap(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_netscape_cookies_rejects_short_lines() {
+        let error = parse_netscape_cookies("example.com TRUE / FALSE 0 name value\n", 0)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Line 1"), "{error}");
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::models::{CrawlError, CrawlErrorCause, CrawlSource, PageContent, PageInfo};
+
+const CRAWL_STATE_VERSION: u32 = 1;
+
+/// Progress of an interrupted crawl, saved with `--resume` so a later run continues from it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlState {
+    pub version: u32,
+    pub start_url: String,
+    /// Normalized URLs already fetched or skipped
+    pub visited: Vec<String>,
+    pub to_visit: Vec<QueuedUrl>,
+    pub pages: Vec<SavedPage>,
+    /// Pages that failed to fetch, which are visited but have no saved page
+    #[serde(default)]
+    pub errors: BTreeMap<CrawlErrorCause, Vec<CrawlError>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedUrl {
+    pub url: String,
+    pub depth: usize,
+    pub source: CrawlSource,
+}
+
+/// A crawled page keyed by its normalized URL. Reports leave out `content`, so it is saved
+/// alongside for the analyzers that run after the crawl.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPage {
+    pub key: String,
+    pub page: PageInfo,
+    pub content: PageContent,
+}
+
+impl CrawlState {
+    pub fn new(start_url: &str) -> Self {
+        Self {
+            version: CRAWL_STATE_VERSION,
+            start_url: start_url.to_string(),
+            visited: Vec::new(),
+            to_visit: Vec::new(),
+            pages: Vec::new(),
+            errors: BTreeMap::new(),
+        }
+    }
+
+    /// Load a saved state, or `None` when the file doesn't exist yet
+    pub fn from_file(path: &Path) -> Result<Option<Self>> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(error).with_context(|| {
+                    format!("Failed to read crawl state file: {}", path.display())
+                });
+            }
+        };
+        let state: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse crawl state file: {}", path.display()))?;
+
+        if state.version != CRAWL_STATE_VERSION {
+            anyhow::bail!(
+                "Unsupported crawl state version {} in {} (expected {})",
+                state.version,
+                path.display(),
+                CRAWL_STATE_VERSION
+            );
+        }
+
+        Ok(Some(state))
+    }
+
+    /// Write through a temporary file so an interruption mid-write leaves the last checkpoint intact
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)?;
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, json).with_context(|| {
+            format!("Failed to write crawl state file: {}", temp_path.display())
+        })?;
+        fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to write crawl state file: {}", path.display()))
+    }
+}
+
+impl SavedPage {
+    pub fn new(key: &str, page: &PageInfo) -> Self {
+        Self {
+            key: key.to_string(),
+            page: page.clone(),
+            content: page.content.clone(),
+        }
+    }
+
+    pub fn into_page(self) -> (String, PageInfo) {
+        let mut page = self.page;
+        page.content = self.content;
+        (self.key, page)
+    }
+}
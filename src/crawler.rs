@@ -1,18 +1,49 @@
-use crate::http_client::build_http_client;
-use crate::models::{Image, Link, OpenGraphTags, PageInfo};
+use crate::cli::{DEFAULT_MAX_SITEMAPS, DEFAULT_ROBOTS_AGENT};
+use crate::crawl_state::{CrawlState, QueuedUrl, SavedPage};
+use crate::hosts;
+use crate::html_snapshot::HtmlSnapshots;
+use crate::http_cache::{CachedPage, HttpCache};
+use crate::http_client::{
+    MAX_REDIRECTS, PrivateAddressError, RedirectLoopError, RetryPolicy, TOO_MANY_REDIRECTS,
+    TlsOptions, build_crawl_client, build_http_client, build_public_only_client,
+    build_public_only_redirect_tracing_client, build_redirect_tracing_client, find_private_address,
+    find_redirect_loop, get_with_headers_honoring_retry_after,
+};
+use crate::limits::{
+    AutoThrottle, ByteSize, LimitReachedError, RequestLimits, RequestSample, find_limit_reached,
+};
+use crate::link_checker::redirect_loop_issue;
+use crate::login::{LoginConfig, log_in};
+use crate::models::{
+    CrawlError, CrawlErrorCause, CrawlSource, DiscoveryMethod, FrontierAction, FrontierDecision,
+    Heading, HreflangLink, HtmlContentTypes, Image, IssueSeverity, IssueType, Link, LinkHeaders,
+    OpenGraphTags, PageContent, PageInfo, PageWeight, RedirectHop, SeoIssue,
+};
+use crate::page_store::PageStore;
+use crate::render::{JsRenderer, RenderSession};
 use crate::reporter::Reporter;
 use crate::robots::RobotsTxt;
+use crate::rules::PageAssertions;
 use crate::runtime::{ProgressSnapshot, RunEvent, RunEventSender, RunStage};
+use crate::sitemap::{Sitemap, decode_sitemap_body, default_sitemap_url, parse_sitemap};
 use anyhow::{Context, Result, anyhow};
 use futures::stream::{self, StreamExt};
 use governor::{
-    Quota, RateLimiter, clock::DefaultClock, state::InMemoryState, state::direct::NotKeyed,
+    Jitter, Quota, RateLimiter, clock::DefaultClock, state::InMemoryState, state::direct::NotKeyed,
 };
 use indicatif::{ProgressBar, ProgressStyle};
 use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::cookie::Jar;
+use reqwest::header::{self, HeaderMap};
 use scraper::{Html, Selector};
-use std::collections::{HashMap, HashSet, VecDeque};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
 use url::Url;
 
 /// Configuration for the crawler
@@ -24,16 +55,99 @@ pub struct CrawlerConfig {
     pub requests_per_second: Option<f64>,
     pub concurrent_requests: usize,
     pub respect_robots_txt: bool,
+    /// Re-request HTML pages to find content that varies on User-Agent or Cookie
+    pub audit_vary: bool,
+    /// Regexes a discovered URL must match one of to be crawled; empty allows every URL
+    pub include: Vec<String>,
+    /// Regexes for URLs that are never queued, even when they match `include`
+    pub exclude: Vec<String>,
+}
+
+/// How query strings are rewritten before URLs are compared, so variants of one page are
+/// crawled once. The first variant found is the one requested.
+#[derive(Debug, Clone, Default)]
+pub struct QueryNormalization {
+    /// Drop `utm_*`, `fbclid`, and the other parameters in `TRACKING_PARAMS`
+    pub strip_tracking: bool,
+    /// Sort parameters so their order doesn't matter
+    pub sort: bool,
+    /// More parameter names to drop; a trailing `*` matches any suffix
+    pub ignored: Vec<String>,
+}
+
+impl QueryNormalization {
+    fn is_active(&self) -> bool {
+        self.strip_tracking || self.sort || !self.ignored.is_empty()
+    }
+
+    fn drops(&self, param: &str) -> bool {
+        let name = param.split('=').next().unwrap_or_default();
+        (self.strip_tracking
+            && TRACKING_PARAMS
+                .iter()
+                .any(|pattern| param_name_matches(pattern, name)))
+            || self
+                .ignored
+                .iter()
+                .any(|pattern| param_name_matches(pattern, name))
+    }
 }
 
+/// Click-tracking parameters that never change what a page shows
+pub(crate) const TRACKING_PARAMS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "_hsenc", "_hsmi",
+];
+
+/// Compares ignoring ASCII case; a trailing `*` in `pattern` matches any suffix
+pub(crate) fn param_name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+        None => name.eq_ignore_ascii_case(pattern),
+    }
+}
+
+/// How often `--resume` saves the crawl's progress
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Seconds a page request waits for a response unless configured otherwise
+pub const DEFAULT_PAGE_TIMEOUT_SECS: u64 = 30;
+
+/// Largest page body read unless configured otherwise; Googlebot stops at 15MB, and pages
+/// anywhere near that are a problem on their own
+pub const DEFAULT_MAX_BODY_BYTES: u64 = 10_000_000;
+
+/// Redirects a traced chain may have before it is reported, unless configured otherwise
+pub const DEFAULT_REDIRECT_CHAIN_LIMIT: usize = 1;
+
+/// Content types parsed as HTML unless configured otherwise
+pub const DEFAULT_HTML_CONTENT_TYPES: [&str; 2] = ["text/html", "application/xhtml+xml"];
+
+/// Googlebot smartphone, the user agent most worth comparing a browser's view against
+const VARY_PROBE_USER_AGENT: &str = "Mozilla/5.0 (Linux; Android 6.0.1; Nexus 5X Build/MMB29P) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Mobile Safari/537.36 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+
 // Cached selectors to avoid repeated parsing and eliminate unwrap() calls
 static TITLE_SELECTOR: Lazy<Selector> =
     Lazy::new(|| Selector::parse("title").expect("title selector should be valid"));
 static META_DESC_SELECTOR: Lazy<Selector> = Lazy::new(|| {
     Selector::parse("meta[name='description']").expect("meta description selector should be valid")
 });
+static CANONICAL_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse("link[rel='canonical']").expect("canonical selector should be valid")
+});
+static ALTERNATE_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse("link[rel='alternate'][hreflang]").expect("alternate selector should be valid")
+});
 static H1_SELECTOR: Lazy<Selector> =
     Lazy::new(|| Selector::parse("h1").expect("h1 selector should be valid"));
+static HEADING_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse("h1, h2, h3, h4, h5, h6").expect("heading selector should be valid")
+});
+static META_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("meta[content]").expect("meta selector should be valid"));
+static BODY_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("body").expect("body selector should be valid"));
 static IMG_SELECTOR: Lazy<Selector> =
     Lazy::new(|| Selector::parse("img[src]").expect("img[src] selector should be valid"));
 
@@ -62,6 +176,14 @@ static OG_LOCALE_SELECTOR: Lazy<Selector> = Lazy::new(|| {
 });
 
 // Unified selector for all link-bearing elements (single DOM pass optimization)
+static SCRIPT_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("script").expect("script selector should be valid"));
+static SUBRESOURCE_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse(
+        "script[src], link[rel~='stylesheet'][href], img[src], iframe[src], video[src], audio[src], source[src], embed[src], object[data]",
+    )
+    .expect("subresource selector should be valid")
+});
 static LINK_ELEMENTS_SELECTOR: Lazy<Selector> = Lazy::new(|| {
     Selector::parse(
         "a[href], iframe[src], video[src], source[src], audio[src], embed[src], object[data]",
@@ -71,36 +193,126 @@ static LINK_ELEMENTS_SELECTOR: Lazy<Selector> = Lazy::new(|| {
 
 pub struct Crawler {
     client: reqwest::Client,
+    /// Client that returns redirects instead of following them, set with `set_trace_redirects`
+    redirect_client: Option<reqwest::Client>,
+    trace_redirects: bool,
+    /// Refuse off-site pages that are, resolve to, or redirect to private addresses
+    block_private_ips: bool,
+    /// Like `client` and `redirect_client`, but refusing private addresses, for off-site pages
+    /// with `block_private_ips`
+    public_only_client: Option<reqwest::Client>,
+    public_only_redirect_client: Option<reqwest::Client>,
+    redirect_chain_limit: usize,
+    timeout_secs: u64,
+    tls: TlsOptions,
     base_url: Url,
+    seed_urls: Vec<Url>,
     max_depth: usize,
     max_pages: usize,
     follow_external: bool,
     keep_fragments: bool,
+    query_normalization: QueryNormalization,
     visited: HashSet<String>,
-    to_visit: VecDeque<(String, usize)>,
+    to_visit: VecDeque<(String, usize, CrawlSource)>,
+    /// Normalized URLs waiting in `to_visit` with the shallowest depth they were found at, so
+    /// a URL many pages link to is queued once and crawled at its shortest discovery path
+    queued: HashMap<String, usize>,
     pub pages: HashMap<String, PageInfo>,
+    /// Pages that failed to fetch, grouped by cause
+    pub errors: BTreeMap<CrawlErrorCause, Vec<CrawlError>>,
     rate_limiter: Option<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    /// Time between requests the rate limiter allows
+    request_interval: Option<Duration>,
+    gentle: bool,
+    request_limits: Option<Arc<RequestLimits>>,
+    cookie_jar: Option<Arc<Jar>>,
+    login: Option<LoginConfig>,
+    max_duration: Option<Duration>,
+    timed_out: bool,
     concurrent_requests: usize,
+    /// Varies the pages fetched at once with the site's response times and error rate
+    auto_throttle: Option<AutoThrottle>,
     respect_robots_txt: bool,
+    /// User-agent token matched against robots.txt groups
+    robots_agent: String,
+    audit_vary: bool,
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    use_sitemap: bool,
+    max_sitemaps: usize,
+    /// `<lastmod>` of each page URL listed in the sitemaps read with `set_use_sitemap`, keyed
+    /// like `pages`
+    pub sitemap_lastmods: HashMap<String, String>,
+    keep_html: bool,
+    respect_nofollow: bool,
+    retry_policy: RetryPolicy,
+    assertions: PageAssertions,
+    renderer: Option<JsRenderer>,
+    /// Normalized URLs whose frontier decisions are recorded for `--explain`
+    explain_targets: HashSet<String>,
+    explanations: BTreeMap<String, Vec<FrontierDecision>>,
+    /// Where `--resume` checkpoints the crawl, and whether the crawl continues a saved one
+    state_file: Option<PathBuf>,
+    resumed: bool,
+    html_content_types: HtmlContentTypes,
+    max_body_bytes: u64,
+    /// Status and body hash of each parsed page, mapped to its URL, so byte-identical copies
+    /// are linked to it instead of parsed again
+    content_hashes: Mutex<HashMap<(u16, [u8; 32]), String>>,
+    http_cache: Option<HttpCache>,
+    html_snapshots: Option<HtmlSnapshots>,
+    /// Where crawled pages are kept instead of `pages`
+    page_store: Option<Box<dyn PageStore>>,
+    /// Links on the pages kept so far, counted as they are kept so progress doesn't have to
+    /// read a page store back
+    links_discovered: usize,
     robots_txt: RobotsTxt,
     progress_bar: Option<ProgressBar>,
     progress_sender: Option<RunEventSender>,
+    page_sender: Option<UnboundedSender<PageInfo>>,
 }
 
 impl Crawler {
-    pub fn new(start_url: &str, config: CrawlerConfig) -> Result<Self> {
-        let base_url = Url::parse(start_url).context("Invalid URL")?;
-
-        // Validate URL scheme - only allow http and https
-        if !Self::has_supported_web_scheme(&base_url) {
-            return Err(anyhow!(
-                "Invalid URL scheme '{}': only http and https are supported",
-                base_url.scheme()
-            ));
-        }
+    /// Create a crawler that starts from every URL in `start_urls`. Links to any start URL's
+    /// host count as internal; the first one is the site's base URL for resumed crawls.
+    pub fn new<S: AsRef<str>>(start_urls: &[S], config: CrawlerConfig) -> Result<Self> {
+        let seed_urls = start_urls
+            .iter()
+            .map(|start_url| {
+                let url = Url::parse(start_url.as_ref()).context("Invalid URL")?;
+
+                // Validate URL scheme - only allow http and https
+                if !Self::has_supported_web_scheme(&url) {
+                    return Err(anyhow!(
+                        "Invalid URL scheme '{}': only http and https are supported",
+                        url.scheme()
+                    ));
+                }
+                Ok(url)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let base_url = seed_urls
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow!("At least one start URL is required"))?;
+
+        let compile = |kind: &str, patterns: &[String]| -> Result<Vec<Regex>> {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern)
+                        .with_context(|| format!("Invalid {kind} pattern '{pattern}'"))
+                })
+                .collect()
+        };
+        let include = compile("include", &config.include)?;
+        let exclude = compile("exclude", &config.exclude)?;
 
-        let mut to_visit = VecDeque::new();
-        to_visit.push_back((start_url.to_string(), 0));
+        // The start URL is always crawled so an include list can name the pages it links to
+        let to_visit = start_urls
+            .iter()
+            .map(|start_url| (start_url.as_ref().to_string(), 0, CrawlSource::default()))
+            .collect();
 
         // Initialize rate limiter if requests_per_second is specified
         let rate_limiter = config.requests_per_second.map(|rps| {
@@ -108,25 +320,491 @@ impl Crawler {
             RateLimiter::direct(quota)
         });
 
+        let request_interval = config
+            .requests_per_second
+            .map(|rps| Duration::from_secs_f64(1.0 / rps.ceil()));
+
         Ok(Self {
-            client: build_http_client(30)?,
+            client: build_http_client(DEFAULT_PAGE_TIMEOUT_SECS)?,
+            redirect_client: None,
+            trace_redirects: false,
+            block_private_ips: false,
+            public_only_client: None,
+            public_only_redirect_client: None,
+            redirect_chain_limit: DEFAULT_REDIRECT_CHAIN_LIMIT,
+            timeout_secs: DEFAULT_PAGE_TIMEOUT_SECS,
+            tls: TlsOptions::default(),
             base_url,
+            seed_urls,
             max_depth: config.max_depth,
             max_pages: config.max_pages,
             follow_external: config.follow_external,
             keep_fragments: config.keep_fragments,
+            query_normalization: QueryNormalization::default(),
             visited: HashSet::new(),
             to_visit,
+            queued: HashMap::new(),
             pages: HashMap::new(),
+            errors: BTreeMap::new(),
             rate_limiter,
+            request_interval,
+            gentle: false,
+            request_limits: None,
+            cookie_jar: None,
+            login: None,
+            max_duration: None,
+            timed_out: false,
             concurrent_requests: config.concurrent_requests,
+            auto_throttle: None,
             respect_robots_txt: config.respect_robots_txt,
+            robots_agent: DEFAULT_ROBOTS_AGENT.to_string(),
+            audit_vary: config.audit_vary,
+            include,
+            exclude,
+            use_sitemap: false,
+            max_sitemaps: DEFAULT_MAX_SITEMAPS,
+            sitemap_lastmods: HashMap::new(),
+            keep_html: false,
+            respect_nofollow: false,
+            retry_policy: RetryPolicy::default(),
+            assertions: PageAssertions::default(),
+            renderer: None,
+            explain_targets: HashSet::new(),
+            explanations: BTreeMap::new(),
+            state_file: None,
+            resumed: false,
+            html_content_types: HtmlContentTypes::default(),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            content_hashes: Mutex::new(HashMap::new()),
+            http_cache: None,
+            html_snapshots: None,
+            page_store: None,
+            links_discovered: 0,
             robots_txt: RobotsTxt::new(),
             progress_bar: None,
             progress_sender: None,
+            page_sender: None,
         })
     }
 
+    /// Replace the content types that are parsed as HTML, e.g. to add `text/xml`
+    pub fn set_html_content_types(&mut self, content_types: HtmlContentTypes) {
+        self.html_content_types = content_types;
+    }
+
+    /// Stop reading a page's body once it passes `bytes`; the page is reported with a
+    /// `ResponseTooLarge` issue instead of being parsed
+    pub fn set_max_body_bytes(&mut self, bytes: u64) {
+        self.max_body_bytes = bytes;
+    }
+
+    /// Rewrite query strings before URLs are compared, so tracking-parameter and
+    /// parameter-order variants of a page are crawled once. Call before `set_state_file`.
+    pub fn set_query_normalization(&mut self, normalization: QueryNormalization) {
+        self.query_normalization = normalization;
+    }
+
+    /// Before following links, queue every page listed in `/sitemap.xml` and in the sitemaps
+    /// robots.txt names, so pages no link reaches are crawled too
+    pub fn set_use_sitemap(&mut self, enabled: bool) {
+        self.use_sitemap = enabled;
+    }
+
+    /// Keep each page's raw HTML in its `content` for analyzers that query the DOM
+    pub fn set_keep_html(&mut self, enabled: bool) {
+        self.keep_html = enabled;
+    }
+
+    /// Don't queue links marked `rel="nofollow"`, `rel="ugc"`, or `rel="sponsored"`. They're
+    /// still extracted, so they're reported and checked like any other link.
+    pub fn set_respect_nofollow(&mut self, enabled: bool) {
+        self.respect_nofollow = enabled;
+    }
+
+    /// Record why each of `urls` was or wasn't crawled: when it was queued and from where,
+    /// which rule skipped it, and how its request went. Call after `set_state_file` so
+    /// URLs already queued by a resumed crawl are covered.
+    pub fn set_explain(&mut self, urls: &[String]) {
+        self.explain_targets = urls.iter().map(|url| self.normalize_url(url)).collect();
+
+        let queued: Vec<_> = self
+            .to_visit
+            .iter()
+            .map(|(url, depth, source)| (url.clone(), *depth, source.clone()))
+            .collect();
+        for (url, depth, source) in queued {
+            let reason = match &source.found_on {
+                Some(_) => format!("queued at depth {depth} by the resumed crawl"),
+                None => "start URL".to_string(),
+            };
+            self.explain(&url, FrontierAction::Queued, reason, source.found_on);
+        }
+    }
+
+    /// How the crawl handled each `--explain` URL; URLs it never came across get one entry
+    /// saying so
+    pub fn explanations(&self) -> BTreeMap<String, Vec<FrontierDecision>> {
+        let mut explanations = self.explanations.clone();
+        for target in &self.explain_targets {
+            explanations.entry(target.clone()).or_insert_with(|| {
+                vec![FrontierDecision {
+                    action: FrontierAction::Skipped,
+                    reason: "never found: no crawled page or sitemap links to it".to_string(),
+                    found_on: None,
+                }]
+            });
+        }
+        explanations
+    }
+
+    fn explain(
+        &mut self,
+        url: &str,
+        action: FrontierAction,
+        reason: String,
+        found_on: Option<String>,
+    ) {
+        if self.explain_targets.is_empty() {
+            return;
+        }
+        let normalized_url = self.normalize_url(url);
+        if self.explain_targets.contains(&normalized_url) {
+            self.explanations
+                .entry(normalized_url)
+                .or_default()
+                .push(FrontierDecision {
+                    action,
+                    reason,
+                    found_on,
+                });
+        }
+    }
+
+    /// Match robots.txt groups, including `Crawl-delay`, against this user-agent token instead
+    /// of "scoutly"
+    pub fn set_robots_agent(&mut self, agent: impl Into<String>) {
+        self.robots_agent = agent.into();
+    }
+
+    /// Checkpoint the crawl to `path` as it runs, continuing from the state saved there by an
+    /// earlier, interrupted run. The file is removed once the crawl completes.
+    pub fn set_state_file(&mut self, path: PathBuf) -> Result<()> {
+        if let Some(state) = CrawlState::from_file(&path)? {
+            if state.start_url != self.base_url.as_str() {
+                anyhow::bail!(
+                    "Crawl state file {} belongs to a crawl of {}, not {}",
+                    path.display(),
+                    state.start_url,
+                    self.base_url
+                );
+            }
+
+            tracing::info!(
+                path = %path.display(),
+                pages = state.pages.len(),
+                queued = state.to_visit.len(),
+                "Resuming crawl from saved state"
+            );
+            self.visited = state.visited.into_iter().collect();
+            self.to_visit = state
+                .to_visit
+                .into_iter()
+                .map(|queued| (queued.url, queued.depth, queued.source))
+                .collect();
+            self.pages = state.pages.into_iter().map(SavedPage::into_page).collect();
+            self.links_discovered = self.pages.values().map(|page| page.links.len()).sum();
+            self.errors = state.errors;
+            self.resumed = true;
+        }
+
+        self.state_file = Some(path);
+        Ok(())
+    }
+
+    fn save_state(&self) {
+        let Some(path) = &self.state_file else {
+            return;
+        };
+
+        let mut state = CrawlState::new(self.base_url.as_str());
+        state.visited = self.visited.iter().cloned().collect();
+        state.visited.sort_unstable();
+        state.to_visit = self
+            .to_visit
+            .iter()
+            .map(|(url, depth, source)| QueuedUrl {
+                url: url.clone(),
+                depth: *depth,
+                source: source.clone(),
+            })
+            .collect();
+        state.pages = self
+            .pages
+            .iter()
+            .map(|(key, page)| SavedPage::new(key, page))
+            .collect();
+        state.errors = self.errors.clone();
+
+        if let Err(error) = state.save(path) {
+            tracing::warn!(error = %error, "Failed to checkpoint the crawl");
+        }
+    }
+
+    /// Extract page data from the DOM a headless browser renders instead of the served HTML.
+    /// Status codes and headers still come from a plain request.
+    pub fn set_renderer(&mut self, renderer: JsRenderer) {
+        self.renderer = Some(renderer);
+    }
+
+    /// Keep pages served with an `ETag` or `Last-Modified` header in `cache`, and on later
+    /// runs reuse them when the server answers a conditional request with 304
+    pub fn set_http_cache(&mut self, cache: HttpCache) {
+        self.http_cache = Some(cache);
+    }
+
+    /// Write the body of every page read to `snapshots` and record the file in the page
+    pub fn set_html_snapshots(&mut self, snapshots: HtmlSnapshots) {
+        self.html_snapshots = Some(snapshots);
+    }
+
+    /// Keep crawled pages in `store` instead of in memory. They stay there once the crawl
+    /// ends, and `pages` stays empty; take the store with `take_page_store`.
+    pub fn set_page_store(&mut self, store: Box<dyn PageStore>) {
+        self.page_store = Some(store);
+    }
+
+    /// The page store holding the crawled pages, if one was set
+    pub fn take_page_store(&mut self) -> Option<Box<dyn PageStore>> {
+        self.page_store.take()
+    }
+
+    /// Check each crawled HTML page against the config's page assertions
+    pub fn set_assertions(&mut self, assertions: PageAssertions) {
+        self.assertions = assertions;
+    }
+
+    /// Stop fetching sitemaps, including ones nested in sitemap indexes, after this many
+    pub fn set_max_sitemaps(&mut self, max_sitemaps: usize) {
+        self.max_sitemaps = max_sitemaps;
+    }
+
+    /// Crawl politely: add random jitter to the rate limit, slow down to the robots.txt
+    /// `Crawl-delay`, and wait out `Retry-After` on 429 and 503 responses
+    pub fn set_gentle(&mut self, enabled: bool) {
+        self.gentle = enabled;
+    }
+
+    /// Fetch fewer pages at once while the site answers slowly or with 429s, 5xx responses,
+    /// or failed requests, and more again once it recovers, up to the configured concurrency
+    pub fn set_auto_throttle(&mut self, enabled: bool) {
+        self.auto_throttle = enabled.then(|| AutoThrottle::new(self.concurrent_requests));
+    }
+
+    /// Retry page requests that time out, lose their connection, or get a 5xx response
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Time between requests the crawl was paced to, including any robots.txt `Crawl-delay`
+    pub fn request_interval(&self) -> Option<Duration> {
+        self.request_interval
+    }
+
+    /// Stop sending requests once the shared request or bandwidth limit is reached. Retries,
+    /// redirects, robots.txt, and sitemaps count as requests too.
+    pub fn set_request_limits(&mut self, limits: Arc<RequestLimits>) -> Result<()> {
+        self.request_limits = Some(limits);
+        self.rebuild_client()
+    }
+
+    /// Limits the crawl counted its requests against, to be shared with the link checker
+    pub fn request_limits(&self) -> Option<Arc<RequestLimits>> {
+        self.request_limits.clone()
+    }
+
+    /// Give up on page requests that take longer than this many seconds
+    pub fn set_timeout(&mut self, timeout_secs: u64) -> Result<()> {
+        self.timeout_secs = timeout_secs;
+        self.rebuild_client()
+    }
+
+    /// Verify site certificates against extra CAs, or not at all
+    pub fn set_tls(&mut self, tls: TlsOptions) -> Result<()> {
+        self.tls = tls;
+        self.rebuild_client()
+    }
+
+    /// Send the jar's cookies with every request, and keep the cookies sites set in it
+    pub fn set_cookie_jar(&mut self, jar: Arc<Jar>) -> Result<()> {
+        self.cookie_jar = Some(jar);
+        self.rebuild_client()
+    }
+
+    fn rebuild_client(&mut self) -> Result<()> {
+        self.client = build_crawl_client(
+            self.timeout_secs,
+            self.cookie_jar.clone(),
+            &self.tls,
+            self.request_limits.clone(),
+        )?;
+        self.redirect_client = self
+            .trace_redirects
+            .then(|| {
+                build_redirect_tracing_client(self.timeout_secs, self.cookie_jar.clone(), &self.tls)
+            })
+            .transpose()?;
+        self.public_only_client = self
+            .block_private_ips
+            .then(|| {
+                build_public_only_client(
+                    self.timeout_secs,
+                    self.cookie_jar.clone(),
+                    &self.tls,
+                    self.request_limits.clone(),
+                )
+            })
+            .transpose()?;
+        self.public_only_redirect_client = (self.block_private_ips && self.trace_redirects)
+            .then(|| {
+                build_public_only_redirect_tracing_client(
+                    self.timeout_secs,
+                    self.cookie_jar.clone(),
+                    &self.tls,
+                )
+            })
+            .transpose()?;
+        Ok(())
+    }
+
+    /// Follow redirects one hop at a time and record each page's `redirect_chain`. Chains
+    /// with more than `set_redirect_chain_limit` redirects are reported.
+    pub fn set_trace_redirects(&mut self, enabled: bool) -> Result<()> {
+        self.trace_redirects = enabled;
+        self.rebuild_client()
+    }
+
+    /// With `follow_external`, don't crawl off-site pages that are, resolve to, or redirect to
+    /// a private address; they are left out of the crawl like the links the link checker
+    /// skips. Pages on the start URLs' hosts are still crawled.
+    pub fn set_block_private_ips(&mut self, enabled: bool) -> Result<()> {
+        self.block_private_ips = enabled;
+        self.rebuild_client()
+    }
+
+    pub fn set_redirect_chain_limit(&mut self, limit: usize) {
+        self.redirect_chain_limit = limit;
+    }
+
+    /// Cookies the crawl sent and collected, to be shared with the link checker
+    pub fn cookie_jar(&self) -> Option<Arc<Jar>> {
+        self.cookie_jar.clone()
+    }
+
+    /// Submit this login form before crawling; the session cookies it sets are kept for the crawl
+    pub fn set_login(&mut self, login: LoginConfig) -> Result<()> {
+        if self.cookie_jar.is_none() {
+            self.set_cookie_jar(Arc::default())?;
+        }
+        self.login = Some(login);
+        Ok(())
+    }
+
+    /// Stop queueing pages once the crawl has run this long; requests already sent finish
+    pub fn set_max_duration(&mut self, max_duration: Duration) {
+        self.max_duration = Some(max_duration);
+    }
+
+    /// Whether `max_duration` ran out before the crawl finished
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    fn acquire_request(&self) -> bool {
+        self.request_limits
+            .as_ref()
+            .is_none_or(|limits| limits.try_acquire())
+    }
+
+    fn record_bytes(&self, bytes: u64) {
+        if let Some(limits) = &self.request_limits {
+            limits.record_bytes(bytes);
+        }
+    }
+
+    /// Count the body of a response whose content isn't read against the bandwidth limit
+    async fn record_body(&self, response: reqwest::Response) {
+        if let Some(limits) = &self.request_limits {
+            limits.record_body(response).await;
+        }
+    }
+
+    /// Read a page's body as text, or `None` once it passes `max_body_bytes`. The body is
+    /// streamed so a huge page is dropped after the limit rather than held in memory.
+    async fn read_body_within_limit(
+        &self,
+        mut response: reqwest::Response,
+    ) -> Result<Option<String>> {
+        if response
+            .content_length()
+            .is_some_and(|length| length > self.max_body_bytes)
+        {
+            return Ok(None);
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            self.record_bytes(chunk.len() as u64);
+            if (body.len() + chunk.len()) as u64 > self.max_body_bytes {
+                return Ok(None);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+    }
+
+    /// Write `body` to the snapshot directory, if there is one, and return the file's path.
+    /// Failures are logged and leave the page without a snapshot.
+    fn save_html_snapshot(&self, url: &str, body: &str) -> Option<String> {
+        let snapshots = self.html_snapshots.as_ref()?;
+        snapshots
+            .save(url, body)
+            .inspect_err(|error| {
+                tracing::warn!(url = %url, error = %error, "Failed to save HTML snapshot");
+            })
+            .ok()
+            .map(|path| path.display().to_string())
+    }
+
+    /// The URL of an earlier page with the same status and body, or `None` after recording
+    /// `url` as the first page with them
+    fn earlier_copy(&self, status_code: u16, body: &str, url: &str) -> Option<String> {
+        let hash = Sha256::digest(body.as_bytes()).into();
+        let mut content_hashes = self
+            .content_hashes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let original = content_hashes
+            .entry((status_code, hash))
+            .or_insert_with(|| url.to_string());
+        // Another URL redirecting to the same page fetches it again; that isn't a copy
+        (original != url).then(|| original.clone())
+    }
+
+    /// Wait until the rate limit allows another request
+    async fn wait_for_turn(&self) {
+        let Some(limiter) = &self.rate_limiter else {
+            return;
+        };
+        match self.request_interval.filter(|_| self.gentle) {
+            Some(interval) => {
+                limiter
+                    .until_ready_with_jitter(Jitter::up_to(interval / 2))
+                    .await
+            }
+            None => limiter.until_ready().await,
+        }
+    }
+
     /// Enable progress bar for crawling
     pub fn enable_progress_bar(&mut self) {
         let pb = ProgressBar::new_spinner();
@@ -142,6 +820,12 @@ impl Crawler {
         self.progress_sender = Some(sender);
     }
 
+    /// Send each newly crawled page, as parsed, to `sender` before it is kept, so it can be
+    /// reported while the crawl goes on
+    pub fn set_page_sender(&mut self, sender: UnboundedSender<PageInfo>) {
+        self.page_sender = Some(sender);
+    }
+
     fn emit_progress(&self) {
         let Some(sender) = &self.progress_sender else {
             return;
@@ -149,41 +833,150 @@ impl Crawler {
 
         let mut snapshot = ProgressSnapshot::new(
             RunStage::Crawling,
-            format!("Crawled {} page(s)", self.pages.len()),
+            format!("Crawled {} page(s)", self.crawled_page_count()),
         );
-        snapshot.pages_crawled = self.pages.len();
-        snapshot.links_discovered = self.pages.values().map(|page| page.links.len()).sum();
+        snapshot.pages_crawled = self.crawled_page_count();
+        snapshot.queued_urls = self.to_visit.len();
+        snapshot.links_discovered = self.links_discovered;
         snapshot.total_links = snapshot.links_discovered;
-        snapshot.summary = Reporter::summarize_pages(&self.pages);
+        match &self.page_store {
+            // Summarizing would read every stored page back, so only the counts are kept
+            Some(_) => {
+                snapshot.summary.total_pages = snapshot.pages_crawled;
+                snapshot.summary.total_links = snapshot.links_discovered;
+            }
+            None => {
+                if let Ok(summary) =
+                    Reporter::summarize_pages(&self.pages, &self.html_content_types)
+                {
+                    snapshot.summary = summary;
+                }
+            }
+        }
 
         let _ = sender.send(RunEvent::Progress(snapshot));
     }
 
-    /// Normalizes a URL by optionally removing fragment identifiers
+    /// Keep a crawled page, in the page store when one is set
+    fn keep_page(&mut self, key: String, page: PageInfo) -> Result<()> {
+        self.links_discovered += page.links.len();
+        match &mut self.page_store {
+            Some(store) => store.insert(key, page),
+            None => {
+                self.pages.insert(key, page);
+                Ok(())
+            }
+        }
+    }
+
+    fn crawled_page_count(&self) -> usize {
+        match &self.page_store {
+            Some(store) => store.len().unwrap_or_default(),
+            None => self.pages.len(),
+        }
+    }
+
+    /// Normalizes a URL by optionally removing fragment identifiers and rewriting its query
     fn normalize_url(&self, url: &str) -> String {
-        if self.keep_fragments {
-            url.to_string()
+        let url = if self.keep_fragments {
+            url
         } else {
             // Strip fragment identifier if present
-            if let Some(pos) = url.find('#') {
-                url[..pos].to_string()
-            } else {
-                url.to_string()
-            }
+            url.split_once('#').map_or(url, |(url, _)| url)
+        };
+        self.normalize_query(url)
+    }
+
+    /// Drops and sorts query parameters as `QueryNormalization` says, leaving their encoding
+    /// untouched
+    fn normalize_query(&self, url: &str) -> String {
+        let normalization = &self.query_normalization;
+        let Some((base, rest)) = url.split_once('?').filter(|_| normalization.is_active()) else {
+            return url.to_string();
+        };
+        let (query, fragment) = match rest.split_once('#') {
+            Some((query, fragment)) => (query, Some(fragment)),
+            None => (rest, None),
+        };
+
+        let mut params: Vec<&str> = query
+            .split('&')
+            .filter(|param| !param.is_empty() && !normalization.drops(param))
+            .collect();
+        if normalization.sort {
+            params.sort();
+        }
+
+        let mut normalized = base.to_string();
+        if !params.is_empty() {
+            normalized.push('?');
+            normalized.push_str(&params.join("&"));
         }
+        if let Some(fragment) = fragment {
+            normalized.push('#');
+            normalized.push_str(fragment);
+        }
+        normalized
     }
 
-    /// Checks if a URL is external by comparing host and port with base_url
+    /// Checks if a URL is external by comparing host and port with every start URL
     fn is_external_url(&self, url: &Url) -> bool {
-        url.host_str() != self.base_url.host_str() || url.port() != self.base_url.port()
+        !self
+            .seed_urls
+            .iter()
+            .any(|seed| url.host_str() == seed.host_str() && url.port() == seed.port())
     }
 
     pub async fn crawl(&mut self) -> Result<()> {
-        // Fetch robots.txt for the base domain if respect_robots_txt is enabled
-        if self.respect_robots_txt
-            && let Err(e) = self.robots_txt.fetch(&self.client, &self.base_url).await
+        if let Some(login) = &self.login {
+            log_in(&self.client, login).await?;
+        }
+
+        // Keyed once normalization is configured and a resumed frontier is loaded
+        let mut queued: HashMap<String, usize> = HashMap::new();
+        for (url, depth, _) in &self.to_visit {
+            let queued_depth = queued.entry(self.normalize_url(url)).or_insert(*depth);
+            *queued_depth = (*queued_depth).min(*depth);
+        }
+        self.queued = queued;
+
+        // Fetch robots.txt for each start host if respect_robots_txt is enabled
+        if self.respect_robots_txt || self.gentle || self.use_sitemap {
+            for seed in &self.seed_urls {
+                if let Err(e) = self
+                    .robots_txt
+                    .fetch(&self.client, seed, self.request_limits.as_deref())
+                    .await
+                {
+                    tracing::warn!(url = %seed, error = %e, "Failed to fetch robots.txt, continuing anyway");
+                }
+            }
+        }
+
+        // A Crawl-delay slower than the configured rate limit replaces it; with several start
+        // hosts the slowest one applies to the whole crawl
+        if self.gentle
+            && let Some(delay) = self
+                .seed_urls
+                .iter()
+                .filter_map(|seed| self.robots_txt.crawl_delay(seed, &self.robots_agent))
+                .max()
+            && self
+                .request_interval
+                .is_none_or(|interval| delay > interval)
+            && let Some(quota) = Quota::with_period(delay)
         {
-            tracing::warn!(error = %e, "Failed to fetch robots.txt, continuing anyway");
+            tracing::info!(
+                delay_secs = delay.as_secs_f64(),
+                "Using robots.txt Crawl-delay"
+            );
+            self.rate_limiter = Some(RateLimiter::direct(quota));
+            self.request_interval = Some(delay);
+        }
+
+        // A resumed frontier already holds the sitemap pages
+        if self.use_sitemap && !self.resumed {
+            self.queue_sitemap_urls().await;
         }
 
         // Initialize progress bar if enabled
@@ -191,37 +984,100 @@ impl Crawler {
             pb.set_position(0);
         }
 
-        while !self.to_visit.is_empty() && self.visited.len() < self.max_pages {
+        let mut limit_reached = false;
+        let started = Instant::now();
+        let mut last_checkpoint = Instant::now();
+        while !limit_reached && !self.to_visit.is_empty() && self.visited.len() < self.max_pages {
+            if self
+                .max_duration
+                .is_some_and(|max_duration| started.elapsed() >= max_duration)
+            {
+                tracing::warn!(
+                    pages = self.crawled_page_count(),
+                    "Time limit reached, stopping the crawl"
+                );
+                self.timed_out = true;
+                limit_reached = true;
+                break;
+            }
+
             // Collect up to concurrent_requests URLs to fetch
+            let concurrency = self
+                .auto_throttle
+                .as_ref()
+                .map_or(self.concurrent_requests, AutoThrottle::concurrency);
             let mut batch = Vec::new();
-            while let Some((url, depth)) = self.to_visit.pop_front() {
+            while let Some((url, depth, source)) = self.to_visit.pop_front() {
                 let normalized_url = self.normalize_url(&url);
+                // A shorter path to the URL may have been found while it waited
+                let depth = self
+                    .queued
+                    .remove(&normalized_url)
+                    .map_or(depth, |queued_depth| queued_depth.min(depth));
 
                 // Check if already visited or depth exceeded before processing
-                if self.visited.contains(&normalized_url) || depth > self.max_depth {
+                if self.visited.contains(&normalized_url) {
+                    continue;
+                }
+                if depth > self.max_depth {
+                    self.explain(
+                        &url,
+                        FrontierAction::Skipped,
+                        format!("depth {depth} is beyond --depth {}", self.max_depth),
+                        source.found_on,
+                    );
                     continue;
                 }
 
                 // Check robots.txt if enabled
                 if self.respect_robots_txt
                     && let Ok(parsed_url) = Url::parse(&url)
-                    && !self.robots_txt.is_allowed(&parsed_url, "scoutly")
+                    && !self.robots_txt.is_allowed(&parsed_url, &self.robots_agent)
                 {
                     tracing::info!(url = %url, "Skipping URL disallowed by robots.txt");
+                    self.explain(
+                        &url,
+                        FrontierAction::Skipped,
+                        "disallowed by robots.txt".to_string(),
+                        source.found_on,
+                    );
                     self.visited.insert(normalized_url.clone());
                     continue;
                 }
 
                 // Check if adding this would exceed max_pages
                 if self.visited.len() + batch.len() >= self.max_pages {
+                    self.explain(
+                        &url,
+                        FrontierAction::Skipped,
+                        format!("--max-pages {} reached; left in the queue", self.max_pages),
+                        source.found_on.clone(),
+                    );
+                    self.queued.insert(normalized_url, depth);
+                    self.to_visit.push_front((url, depth, source));
+                    break;
+                }
+
+                if !self.acquire_request() {
+                    tracing::warn!(url = %url, "Request limit reached, stopping the crawl");
+                    self.explain(
+                        &url,
+                        FrontierAction::Skipped,
+                        "request or bandwidth limit reached; left in the queue".to_string(),
+                        source.found_on.clone(),
+                    );
+                    // Keep the URL queued for a resumed crawl
+                    self.queued.insert(normalized_url, depth);
+                    self.to_visit.push_front((url, depth, source));
+                    limit_reached = true;
                     break;
                 }
 
                 self.visited.insert(normalized_url.clone());
-                batch.push((url, depth, normalized_url));
+                batch.push((url, depth, normalized_url, source));
 
                 // Stop if we've reached the batch size
-                if batch.len() >= self.concurrent_requests {
+                if batch.len() >= concurrency {
                     break;
                 }
             }
@@ -230,85 +1086,652 @@ impl Crawler {
                 break;
             }
 
-            // Fetch batch concurrently using buffer_unordered
+            // Fetch batch concurrently; `buffered` keeps the results in batch order for the zip below
+            let crawler = &*self;
             let results = stream::iter(&batch)
-                .map(|(url, depth, _normalized_url)| self.fetch_page(url, *depth))
-                .buffer_unordered(self.concurrent_requests)
+                .map(|(url, depth, _normalized_url, source)| async move {
+                    // Wait for rate limiter before making request
+                    crawler.wait_for_turn().await;
+                    let sent = Instant::now();
+                    let result = crawler.fetch_page(url, *depth, source.clone()).await;
+                    (sent.elapsed(), result)
+                })
+                .buffered(concurrency)
                 .collect::<Vec<_>>()
                 .await;
 
+            if let Some(throttle) = &mut self.auto_throttle {
+                let samples: Vec<RequestSample> = results
+                    .iter()
+                    .map(|(elapsed, result)| {
+                        (
+                            *elapsed,
+                            result.as_ref().ok().and_then(|page| page.status_code),
+                        )
+                    })
+                    .collect();
+                throttle.record_batch(&samples);
+            }
+
             // Combine results with batch data
-            let results: Vec<_> = batch.into_iter().zip(results).collect();
+            let results: Vec<_> = batch
+                .into_iter()
+                .zip(results.into_iter().map(|(_, result)| result))
+                .collect();
 
             // Process results and queue new links
-            for ((url, depth, normalized_url), result) in results {
+            for ((url, depth, normalized_url, source), result) in results {
                 match result {
                     Ok(page_info) => {
+                        self.explain(
+                            &url,
+                            FrontierAction::Crawled,
+                            match page_info.status_code {
+                                Some(status) => format!("crawled at depth {depth}, HTTP {status}"),
+                                None => format!("crawled at depth {depth}"),
+                            },
+                            source.found_on.clone(),
+                        );
+
+                        if let Some(status) = page_info.status_code.filter(|code| *code >= 500) {
+                            self.errors
+                                .entry(CrawlErrorCause::ServerError)
+                                .or_default()
+                                .push(CrawlError {
+                                    url: url.clone(),
+                                    message: format!("HTTP {status}"),
+                                    status_code: Some(status),
+                                    crawl_depth: depth,
+                                    source: source.clone(),
+                                });
+                        }
+
+                        // Pages are keyed by the URL that served them, so URLs redirecting to
+                        // an already crawled page become its aliases instead of copies
+                        let key = self.normalize_url(&page_info.url);
+                        self.visited.insert(key.clone());
+                        let existing = match &self.page_store {
+                            Some(store) => store.get(&key)?,
+                            None => self.pages.remove(&key),
+                        };
+                        if let Some(mut existing) = existing {
+                            self.links_discovered -= existing.links.len();
+                            for alias in page_info.aliases.iter().chain([&url]) {
+                                existing.add_alias(alias);
+                            }
+                            self.keep_page(key, existing)?;
+                            continue;
+                        }
+
                         // Queue internal links for crawling
+                        let nofollow_page =
+                            self.respect_nofollow && page_info.has_robots_directive("nofollow");
                         if depth < self.max_depth {
                             for link in &page_info.links {
-                                if (link.is_external && !self.follow_external)
-                                    || !Self::should_crawl_discovered_url(&link.url)
-                                {
+                                let found_on = Some(page_info.url.clone());
+                                if nofollow_page {
+                                    self.explain(
+                                        &link.url,
+                                        FrontierAction::Skipped,
+                                        "the page's robots directives say nofollow and --respect-nofollow is on"
+                                            .to_string(),
+                                        found_on,
+                                    );
+                                    continue;
+                                }
+                                if link.is_external && !self.follow_external {
+                                    self.explain(
+                                        &link.url,
+                                        FrontierAction::Skipped,
+                                        "external link and --external is off".to_string(),
+                                        found_on,
+                                    );
+                                    continue;
+                                }
+                                if self.respect_nofollow && link.is_nofollow() {
+                                    self.explain(
+                                        &link.url,
+                                        FrontierAction::Skipped,
+                                        format!(
+                                            "rel=\"{}\" and --respect-nofollow is on",
+                                            link.rel.as_deref().unwrap_or_default()
+                                        ),
+                                        found_on,
+                                    );
+                                    continue;
+                                }
+                                if !Self::should_crawl_discovered_url(&link.url) {
+                                    self.explain(
+                                        &link.url,
+                                        FrontierAction::Skipped,
+                                        "not an http(s) URL, or a file type that isn't a page"
+                                            .to_string(),
+                                        found_on,
+                                    );
+                                    continue;
+                                }
+                                if let Some(reason) = self.url_pattern_rejection(&link.url) {
+                                    self.explain(
+                                        &link.url,
+                                        FrontierAction::Skipped,
+                                        reason,
+                                        found_on,
+                                    );
                                     continue;
                                 }
 
                                 let normalized_link_url = self.normalize_url(&link.url);
-                                if !self.visited.contains(&normalized_link_url) {
-                                    self.to_visit.push_back((link.url.clone(), depth + 1));
+                                if self.visited.contains(&normalized_link_url) {
+                                    self.explain(
+                                        &link.url,
+                                        FrontierAction::Skipped,
+                                        "already crawled".to_string(),
+                                        found_on,
+                                    );
+                                } else if let Some(queued_depth) =
+                                    self.queued.get_mut(&normalized_link_url)
+                                {
+                                    *queued_depth = (*queued_depth).min(depth + 1);
+                                    self.explain(
+                                        &link.url,
+                                        FrontierAction::Skipped,
+                                        "already queued".to_string(),
+                                        found_on,
+                                    );
+                                } else {
+                                    self.explain(
+                                        &link.url,
+                                        FrontierAction::Queued,
+                                        format!("linked at depth {}", depth + 1),
+                                        found_on,
+                                    );
+                                    self.queued.insert(normalized_link_url, depth + 1);
+                                    self.to_visit.push_back((
+                                        link.url.clone(),
+                                        depth + 1,
+                                        CrawlSource {
+                                            method: link.kind,
+                                            found_on: Some(page_info.url.clone()),
+                                        },
+                                    ));
                                 }
                             }
+                        } else {
+                            for link in &page_info.links {
+                                self.explain(
+                                    &link.url,
+                                    FrontierAction::Skipped,
+                                    format!(
+                                        "linked at depth {}, beyond --depth {}",
+                                        depth + 1,
+                                        self.max_depth
+                                    ),
+                                    Some(page_info.url.clone()),
+                                );
+                            }
                         }
 
-                        self.pages.insert(normalized_url, page_info);
+                        if let Some(sender) = &self.page_sender {
+                            let _ = sender.send(page_info.clone());
+                        }
+                        self.keep_page(key, page_info)?;
+                    }
+                    Err(e) if find_limit_reached(e.as_ref()).is_some() => {
+                        tracing::warn!(url = %url, "Request limit reached, stopping the crawl");
+                        self.explain(
+                            &url,
+                            FrontierAction::Skipped,
+                            "request or bandwidth limit reached; left in the queue".to_string(),
+                            source.found_on.clone(),
+                        );
+                        // Keep the URL queued for a resumed crawl
+                        self.visited.remove(&normalized_url);
+                        self.queued.insert(normalized_url, depth);
+                        self.to_visit.push_front((url, depth, source));
+                        limit_reached = true;
+                    }
+                    Err(e) if find_private_address(e.as_ref()).is_some() => {
+                        let refused = find_private_address(e.as_ref())
+                            .map(ToString::to_string)
+                            .unwrap_or_default();
+                        tracing::info!(url = %url, refused = %refused, "Not crawling an off-site page at a private address");
+                        self.explain(
+                            &url,
+                            FrontierAction::Skipped,
+                            format!("{refused} and --block-private-ips is on"),
+                            source.found_on.clone(),
+                        );
                     }
                     Err(e) => {
                         tracing::error!(url = %url, error = %e, "Failed to crawl page");
-                        // Still insert a minimal page info for failed pages
-                        self.pages.insert(
-                            normalized_url,
-                            PageInfo {
-                                url,
-                                status_code: None,
-                                content_type: None,
-                                title: None,
-                                meta_description: None,
-                                h1_tags: vec![],
-                                links: vec![],
-                                images: vec![],
-                                open_graph: OpenGraphTags::default(),
-                                issues: vec![],
-                                crawl_depth: depth,
-                            },
+                        self.explain(
+                            &url,
+                            FrontierAction::Crawled,
+                            format!("request failed at depth {depth}: {e}"),
+                            source.found_on.clone(),
                         );
+                        let cause = fetch_error_cause(&e);
+                        self.errors.entry(cause).or_default().push(CrawlError {
+                            url: url.clone(),
+                            message: format!("{e:#}"),
+                            status_code: None,
+                            crawl_depth: depth,
+                            source: source.clone(),
+                        });
+                        // A redirect loop is also an issue of the URL, so it gets a page to
+                        // report it on; other failures are only listed as errors
+                        if let Some(redirect_loop) = find_redirect_loop(e.as_ref()) {
+                            let issues = vec![redirect_loop_issue(&url, &redirect_loop.cycle)];
+                            self.keep_page(
+                                normalized_url,
+                                PageInfo {
+                                    url,
+                                    status_code: None,
+                                    content_type: None,
+                                    last_modified: None,
+                                    response_time_ms: None,
+                                    content_length: None,
+                                    redirect_chain: vec![],
+                                    duplicate_of: None,
+                                    title: None,
+                                    meta_description: None,
+                                    meta_robots: None,
+                                    x_robots_tag: None,
+                                    canonical: None,
+                                    alternates: vec![],
+                                    link_headers: Default::default(),
+                                    vary: vec![],
+                                    vary_observed: vec![],
+                                    h1_tags: vec![],
+                                    outline: vec![],
+                                    aliases: vec![],
+                                    links: vec![],
+                                    images: vec![],
+                                    weight: Default::default(),
+                                    open_graph: OpenGraphTags::default(),
+                                    content: Default::default(),
+                                    issues,
+                                    omitted_issues: vec![],
+                                    crawl_depth: depth,
+                                    source,
+                                    screenshot: None,
+                                    html_snapshot: None,
+                                    field_data: None,
+                                },
+                            )?;
+                        }
                     }
                 }
             }
 
             // Update progress bar
             if let Some(ref pb) = self.progress_bar {
-                pb.set_position(self.pages.len() as u64);
+                pb.set_position(self.crawled_page_count() as u64);
             }
 
             self.emit_progress();
+
+            if last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+                self.save_state();
+                last_checkpoint = Instant::now();
+            }
+        }
+
+        self.settle_crawl_depths()?;
+
+        // A crawl cut short by a request or time limit can be continued; a finished one starts afresh
+        if let Some(path) = &self.state_file {
+            if limit_reached {
+                self.save_state();
+            } else if let Err(error) = std::fs::remove_file(path)
+                && error.kind() != std::io::ErrorKind::NotFound
+            {
+                tracing::warn!(path = %path.display(), error = %error, "Failed to remove crawl state file");
+            }
         }
 
         // Finish progress bar
         if let Some(ref pb) = self.progress_bar {
-            pb.finish_with_message(format!("Crawled {} pages", self.pages.len()));
+            pb.finish_with_message(format!("Crawled {} pages", self.crawled_page_count()));
         }
 
         Ok(())
     }
 
-    async fn fetch_page(&self, url: &str, depth: usize) -> Result<PageInfo> {
-        // Wait for rate limiter before making request
-        if let Some(limiter) = &self.rate_limiter {
-            limiter.until_ready().await;
+    /// Lower each page's `crawl_depth` to its shortest path through the crawled pages' links.
+    /// Pages are crawled at the depth they were first found at, which is deeper than their
+    /// real click depth when the shorter path was only found later, e.g. through a redirect.
+    fn settle_crawl_depths(&mut self) -> Result<()> {
+        // Only keys, depths, and link targets are gathered, so pages in a store stay there
+        let mut depths: HashMap<String, usize> = HashMap::new();
+        let mut links: Vec<(String, Vec<String>)> = Vec::new();
+        // Links to a URL that redirected count as links to the page it redirected to
+        let mut keys: HashMap<String, String> = HashMap::new();
+        let pages: &dyn PageStore = match &self.page_store {
+            Some(store) => store.as_ref(),
+            None => &self.pages,
+        };
+        pages.for_each_page(&mut |key, page| {
+            depths.insert(key.to_string(), page.crawl_depth);
+            keys.insert(key.to_string(), key.to_string());
+            for alias in &page.aliases {
+                keys.insert(self.normalize_url(alias), key.to_string());
+            }
+            let targets = page
+                .links
+                .iter()
+                .map(|link| self.normalize_url(&link.url))
+                .collect();
+            links.push((key.to_string(), targets));
+            Ok(())
+        })?;
+        let targets: HashMap<&str, Vec<&str>> = links
+            .iter()
+            .map(|(key, targets)| {
+                let targets = targets
+                    .iter()
+                    .filter_map(|target| keys.get(target).map(String::as_str))
+                    .collect();
+                (key.as_str(), targets)
+            })
+            .collect();
+
+        let mut settled: HashMap<&str, usize> = depths
+            .iter()
+            .map(|(key, depth)| (key.as_str(), *depth))
+            .collect();
+        let mut pending: Vec<&str> = settled.keys().copied().collect();
+        pending.sort_by_key(|key| settled[key]);
+        let mut pending = VecDeque::from(pending);
+        while let Some(key) = pending.pop_front() {
+            let depth = settled[key] + 1;
+            for &target in &targets[key] {
+                if settled[target] > depth {
+                    settled.insert(target, depth);
+                    pending.push_back(target);
+                }
+            }
+        }
+
+        let changed: Vec<(String, usize)> = settled
+            .into_iter()
+            .filter(|(key, depth)| depths[*key] != *depth)
+            .map(|(key, depth)| (key.to_string(), depth))
+            .collect();
+        let pages: &mut dyn PageStore = match &mut self.page_store {
+            Some(store) => store.as_mut(),
+            None => &mut self.pages,
+        };
+        for (key, depth) in changed {
+            if let Some(mut page) = pages.get(&key)? {
+                page.crawl_depth = depth;
+                pages.insert(key, page)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Queue the same-site pages listed in the site's sitemaps one level below the start URL,
+    /// following sitemap indexes until `max_sitemaps` sitemaps have been fetched
+    async fn queue_sitemap_urls(&mut self) {
+        let mut pending: VecDeque<String> = self
+            .seed_urls
+            .iter()
+            .flat_map(|seed| {
+                default_sitemap_url(seed)
+                    .into_iter()
+                    .chain(self.robots_txt.sitemaps(seed).iter().cloned())
+            })
+            .collect();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut fetched = 0;
+
+        while let Some(sitemap_url) = pending.pop_front() {
+            if !seen.insert(sitemap_url.clone()) {
+                continue;
+            }
+            if fetched >= self.max_sitemaps {
+                tracing::warn!(
+                    max_sitemaps = self.max_sitemaps,
+                    skipped = pending.len() + 1,
+                    "Sitemap limit reached, skipping the remaining sitemaps"
+                );
+                break;
+            }
+            fetched += 1;
+
+            let sitemap = match self.fetch_sitemap(&sitemap_url).await {
+                Ok(sitemap) => sitemap,
+                Err(e) => {
+                    tracing::warn!(url = %sitemap_url, error = %e, "Failed to fetch sitemap");
+                    continue;
+                }
+            };
+            pending.extend(sitemap.sitemap_urls);
+            for (page_url, lastmod) in sitemap.lastmods {
+                self.sitemap_lastmods
+                    .insert(self.normalize_url(&page_url), lastmod);
+            }
+
+            let mut queued = 0;
+            for page_url in sitemap.page_urls {
+                let Ok(parsed_url) = Url::parse(&page_url) else {
+                    continue;
+                };
+                let found_on = Some(sitemap_url.clone());
+                let rejection = if !Self::has_supported_web_scheme(&parsed_url) {
+                    Some("not an http(s) URL".to_string())
+                } else if self.is_external_url(&parsed_url) && !self.follow_external {
+                    Some("external sitemap URL and --external is off".to_string())
+                } else {
+                    self.url_pattern_rejection(&page_url)
+                };
+                if let Some(reason) = rejection {
+                    self.explain(&page_url, FrontierAction::Skipped, reason, found_on);
+                    continue;
+                }
+                let normalized_url = self.normalize_url(&page_url);
+                if self.queued.contains_key(&normalized_url) {
+                    self.explain(
+                        &page_url,
+                        FrontierAction::Skipped,
+                        "already queued".to_string(),
+                        found_on,
+                    );
+                    continue;
+                }
+                self.queued.insert(normalized_url, 1);
+
+                self.explain(
+                    &page_url,
+                    FrontierAction::Queued,
+                    "listed in sitemap".to_string(),
+                    found_on,
+                );
+                self.to_visit.push_back((
+                    page_url,
+                    1,
+                    CrawlSource {
+                        method: DiscoveryMethod::Sitemap,
+                        found_on: Some(sitemap_url.clone()),
+                    },
+                ));
+                queued += 1;
+            }
+            tracing::info!(url = %sitemap_url, queued, "Queued URLs from sitemap");
+        }
+    }
+
+    async fn fetch_sitemap(&self, sitemap_url: &str) -> Result<Sitemap> {
+        if !self.acquire_request() {
+            return Err(LimitReachedError.into());
         }
+        self.wait_for_turn().await;
 
-        let response = self.client.get(url).send().await?;
+        let response = self.client.get(sitemap_url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP {}", response.status().as_u16()));
+        }
+        let body = response.bytes().await?;
+        self.record_bytes(body.len() as u64);
+        Ok(parse_sitemap(&decode_sitemap_body(&body)?))
+    }
+
+    async fn send_page_request(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        headers: &HeaderMap,
+    ) -> reqwest::Result<reqwest::Response> {
+        let limits = self.request_limits.as_deref();
+        self.retry_policy
+            .send(url, limits, || async {
+                if self.gentle {
+                    get_with_headers_honoring_retry_after(client, url, headers, limits).await
+                } else {
+                    client.get(url).headers(headers.clone()).send().await
+                }
+            })
+            .await
+    }
+
+    /// The cached copy of `url` to revalidate, when pages are cached and one is stored. Pages
+    /// checked against assertions, rendered, or compared across request variants are always
+    /// fetched, since the cached copy didn't go through those checks, and so are pages whose
+    /// body is saved, since a 304 has none.
+    fn cached_page(&self, url: &str) -> Option<CachedPage> {
+        if self.renderer.is_some()
+            || self.audit_vary
+            || !self.assertions.is_empty()
+            || self.html_snapshots.is_some()
+        {
+            return None;
+        }
+        self.http_cache
+            .as_ref()?
+            .get(url)
+            .filter(|entry| !self.keep_html || entry.content.html.is_some())
+    }
+
+    /// Request `url` with the client that doesn't follow redirects, following them here so
+    /// every hop is recorded. `headers` are only sent with the first request. Returns the
+    /// final response and the chain, which is empty when the first response wasn't a redirect.
+    async fn follow_redirects(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        headers: &HeaderMap,
+        block_private_ips: bool,
+    ) -> Result<(reqwest::Response, Vec<RedirectHop>)> {
+        let mut chain: Vec<RedirectHop> = Vec::new();
+        let mut current = url.to_string();
+        loop {
+            if let Some(start) = chain.iter().position(|hop| hop.url == current) {
+                let cycle = chain[start..]
+                    .iter()
+                    .map(|hop| hop.url.clone())
+                    .chain([current])
+                    .collect();
+                return Err(RedirectLoopError { cycle }.into());
+            }
+            if chain.len() > MAX_REDIRECTS {
+                return Err(anyhow!(TOO_MANY_REDIRECTS));
+            }
+            // The first request was counted when the URL left the frontier
+            if !chain.is_empty() && !self.acquire_request() {
+                return Err(LimitReachedError.into());
+            }
+            if block_private_ips {
+                refuse_private_address(&current)?;
+            }
+
+            let headers = if chain.is_empty() {
+                headers.clone()
+            } else {
+                HeaderMap::new()
+            };
+            let response = self.send_page_request(client, &current, &headers).await?;
+            let status = response.status();
+            chain.push(RedirectHop {
+                url: current.clone(),
+                status_code: status.as_u16(),
+            });
+            let next = status
+                .is_redirection()
+                .then(|| response.headers().get(header::LOCATION))
+                .flatten()
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| response.url().join(location).ok());
+            match next {
+                Some(next) => current = next.to_string(),
+                None => {
+                    if chain.len() == 1 {
+                        chain.clear();
+                    }
+                    return Ok((response, chain));
+                }
+            }
+        }
+    }
+
+    async fn fetch_page(&self, url: &str, depth: usize, source: CrawlSource) -> Result<PageInfo> {
+        let sent = Instant::now();
+        let cached = self.cached_page(url);
+        let conditional_headers = cached
+            .as_ref()
+            .map(CachedPage::conditional_headers)
+            .unwrap_or_default();
+        // Off-site pages go through the clients that refuse private addresses
+        let block_private_ips =
+            self.block_private_ips && Url::parse(url).is_ok_and(|url| self.is_external_url(&url));
+        let (client, redirect_client) = if block_private_ips {
+            (
+                self.public_only_client.as_ref().unwrap_or(&self.client),
+                self.public_only_redirect_client.as_ref(),
+            )
+        } else {
+            (&self.client, self.redirect_client.as_ref())
+        };
+        let (response, redirect_chain) = match redirect_client {
+            Some(client) => {
+                self.follow_redirects(client, url, &conditional_headers, block_private_ips)
+                    .await?
+            }
+            None => {
+                if block_private_ips {
+                    refuse_private_address(url)?;
+                }
+                (
+                    self.send_page_request(client, url, &conditional_headers)
+                        .await?,
+                    vec![],
+                )
+            }
+        };
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED
+            && let Some(cached) = cached
+        {
+            tracing::debug!(url = %url, "Page not modified, reusing the cached copy");
+            return Ok(PageInfo {
+                response_time_ms: Some(sent.elapsed().as_millis() as u64),
+                crawl_depth: depth,
+                source,
+                html_snapshot: None,
+                ..cached.into_page()
+            });
+        }
         let status_code = response.status().as_u16();
+        // Redirects were followed, so the page is reported under the URL that served it
+        let served_url = response.url().clone();
+        let (reported_url, aliases) = if Url::parse(url).is_ok_and(|url| url == served_url) {
+            (url.to_string(), vec![])
+        } else {
+            (served_url.to_string(), vec![url.to_string()])
+        };
+        let link_headers = Self::parse_link_headers(response.headers(), &served_url);
+        let vary = Self::parse_vary(response.headers());
+        let last_modified = Self::parse_last_modified(response.headers());
+        let x_robots_tag = Self::parse_x_robots_tag(response.headers());
 
         // Extract content type from response headers
         let content_type = response
@@ -317,7 +1740,48 @@ impl Crawler {
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string());
 
-        if !PageInfo::is_html_content_type(content_type.as_deref()) {
+        let cookies = Self::cookies_from_response(response.headers());
+        // Only pages served without redirects are cached, under the URL that was requested
+        let cacheable_headers =
+            (self.http_cache.is_some() && status_code == 200 && aliases.is_empty())
+                .then(|| response.headers().clone());
+        let declared_length = response.content_length();
+        let mut issues = vec![];
+        let redirects = redirect_chain.len().saturating_sub(1);
+        if redirects > self.redirect_chain_limit {
+            let chain = redirect_chain
+                .iter()
+                .map(|hop| format!("{} ({})", hop.url, hop.status_code))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            issues.push(
+                SeoIssue::new(
+                    IssueSeverity::Warning,
+                    IssueType::RedirectChainTooLong,
+                    format!("Page is reached through {redirects} redirects"),
+                )
+                .with_offending_url(url)
+                .with_expected(format!("at most {} redirect(s)", self.redirect_chain_limit))
+                .with_actual(chain),
+            );
+        }
+        let served_html = if self.html_content_types.contains(content_type.as_deref()) {
+            let html = self.read_body_within_limit(response).await?;
+            if html.is_none() {
+                tracing::warn!(url = %url, max_bytes = self.max_body_bytes, "Page body is too large, not parsing it");
+                issues.push(
+                    SeoIssue::new(
+                        IssueSeverity::Warning,
+                        IssueType::ResponseTooLarge,
+                        "Page body is larger than the crawler reads",
+                    )
+                    .with_expected(format!("at most {}", ByteSize(self.max_body_bytes)))
+                    .with_actual(format!("more than {}", ByteSize(self.max_body_bytes))),
+                );
+            }
+            html
+        } else {
+            self.record_body(response).await;
             if let Some(ref ct) = content_type {
                 tracing::info!(
                     url = %url,
@@ -325,33 +1789,101 @@ impl Crawler {
                     "Skipping HTML extraction for non-HTML response"
                 );
             }
+            None
+        };
 
+        let response_time_ms = Some(sent.elapsed().as_millis() as u64);
+        let html_snapshot = served_html
+            .as_deref()
+            .and_then(|html| self.save_html_snapshot(&reported_url, html));
+        let content_length = served_html
+            .as_ref()
+            .map(|html| html.len() as u64)
+            .or(declared_length);
+
+        // A rendered page can differ from its copies, e.g. one app shell served for every route
+        let duplicate_of = served_html
+            .as_deref()
+            .filter(|html| self.renderer.is_none() && !html.is_empty())
+            .and_then(|html| self.earlier_copy(status_code, html, &reported_url));
+        if let Some(original) = &duplicate_of {
+            tracing::info!(url = %url, original = %original, "Same body as an earlier page, not parsing it again");
+        }
+        let served_html = served_html.filter(|_| duplicate_of.is_none());
+
+        let Some(served_html) = served_html else {
             return Ok(PageInfo {
-                url: url.to_string(),
+                url: reported_url,
                 status_code: Some(status_code),
                 content_type,
+                last_modified,
+                response_time_ms,
+                content_length,
+                redirect_chain,
+                duplicate_of,
                 title: None,
                 meta_description: None,
+                meta_robots: None,
+                x_robots_tag,
+                canonical: link_headers.canonical.clone(),
+                alternates: link_headers.alternates.clone(),
+                link_headers,
+                vary,
+                vary_observed: vec![],
                 h1_tags: vec![],
+                outline: vec![],
+                aliases,
                 links: vec![],
                 images: vec![],
+                weight: Default::default(),
                 open_graph: OpenGraphTags::default(),
-                issues: vec![],
+                content: Default::default(),
+                issues,
+                omitted_issues: vec![],
                 crawl_depth: depth,
+                source,
+                screenshot: None,
+                html_snapshot,
+                field_data: None,
             });
-        }
-
-        let html_content = response.text().await?;
-        let document = Html::parse_document(&html_content);
+        };
+        let rendered = match &self.renderer {
+            Some(renderer) => renderer
+                .render(
+                    &served_url,
+                    RenderSession {
+                        cookies: self.cookie_jar.as_deref(),
+                        tls: &self.tls,
+                        limits: self.request_limits.as_deref(),
+                    },
+                )
+                .await
+                .inspect_err(|error| {
+                    tracing::warn!(url = %url, error = %error, "Rendering failed, using the served HTML");
+                })
+                .ok(),
+            None => None,
+        };
+        let html_content = rendered.as_deref().unwrap_or(&served_html);
+        let document = Html::parse_document(html_content);
 
-        // Parse URL once for use in extraction methods
-        let page_url = Url::parse(url)?;
+        // Relative links resolve against the URL that served the page
+        let page_url = served_url;
 
         // Extract title
         let title = Self::extract_title(&document);
 
-        // Extract meta description
+        // Extract meta description and robots directives
         let meta_description = Self::extract_meta_description(&document);
+        let meta_robots = Self::extract_meta_robots(&document);
+
+        // Extract canonical URL and language alternates, falling back to the Link header
+        let canonical = Self::extract_canonical(&document, &page_url)
+            .or_else(|| link_headers.canonical.clone());
+        let alternates = Self::merge_alternates(
+            Self::extract_alternates(&document, &page_url),
+            &link_headers.alternates,
+        );
 
         // Extract H1 tags
         let h1_tags = Self::extract_h1_tags(&document);
@@ -359,25 +1891,71 @@ impl Crawler {
         // Extract Open Graph tags
         let open_graph = Self::extract_open_graph_tags(&document);
 
+        // Keep text, headings, and meta tags for analyzers
+        let mut content = Self::extract_content(&document);
+        if self.keep_html {
+            content.html = Some(html_content.to_string());
+        }
+
+        // Check page assertions while the document is parsed
+        issues.extend(self.assertions.check(&page_url, &document, html_content));
+
         // Extract links
         let links = self.extract_links(&document, &page_url)?;
 
         // Extract images
         let images = self.extract_images(&document, &page_url)?;
 
-        Ok(PageInfo {
-            url: url.to_string(),
+        // Measure page weight for performance budgets
+        let weight = Self::measure_weight(&document, served_html.len());
+        let vary_observed = if self.audit_vary && (200..300).contains(&status_code) {
+            self.observe_variation(url, &page_url, &served_html, cookies.as_deref())
+                .await
+        } else {
+            vec![]
+        };
+
+        let page = PageInfo {
+            url: reported_url,
             status_code: Some(status_code),
             content_type,
+            last_modified,
+            response_time_ms,
+            content_length,
+            redirect_chain,
+            duplicate_of: None,
             title,
             meta_description,
+            meta_robots,
+            x_robots_tag,
+            canonical,
+            alternates,
+            link_headers,
+            vary,
+            vary_observed,
             h1_tags,
+            outline: vec![],
+            aliases,
             links,
             images,
+            weight,
             open_graph,
-            issues: vec![],
+            content,
+            issues,
+            omitted_issues: vec![],
             crawl_depth: depth,
-        })
+            source,
+            screenshot: None,
+            html_snapshot,
+            field_data: None,
+        };
+        if let (Some(cache), Some(headers)) = (&self.http_cache, cacheable_headers)
+            && let Some(entry) = CachedPage::new(url, &headers, &page)
+            && let Err(error) = cache.put(&entry)
+        {
+            tracing::warn!(url = %url, error = %error, "Failed to cache page");
+        }
+        Ok(page)
     }
 
     fn extract_title(document: &Html) -> Option<String> {
@@ -395,6 +1973,246 @@ impl Crawler {
             .map(|s| s.to_string())
     }
 
+    /// The `content` of every `<meta name="robots">` tag, joined with commas
+    fn extract_meta_robots(document: &Html) -> Option<String> {
+        let directives: Vec<&str> = document
+            .select(&META_SELECTOR)
+            .filter(|el| {
+                el.value()
+                    .attr("name")
+                    .is_some_and(|name| name.trim().eq_ignore_ascii_case("robots"))
+            })
+            .filter_map(|el| el.value().attr("content"))
+            .map(str::trim)
+            .filter(|content| !content.is_empty())
+            .collect();
+        (!directives.is_empty()).then(|| directives.join(", "))
+    }
+
+    fn extract_canonical(document: &Html, page_url: &Url) -> Option<String> {
+        document
+            .select(&CANONICAL_SELECTOR)
+            .next()
+            .and_then(|el| el.value().attr("href"))
+            .and_then(|href| page_url.join(href.trim()).ok())
+            .map(|url| url.to_string())
+    }
+
+    /// The hreflang alternates a response declares, from its HTML and its `Link` header
+    pub(crate) fn response_alternates(
+        headers: &HeaderMap,
+        page_url: &Url,
+        html: &str,
+    ) -> Vec<HreflangLink> {
+        Self::merge_alternates(
+            Self::extract_alternates(&Html::parse_document(html), page_url),
+            &Self::parse_link_headers(headers, page_url).alternates,
+        )
+    }
+
+    fn extract_alternates(document: &Html, page_url: &Url) -> Vec<HreflangLink> {
+        document
+            .select(&ALTERNATE_SELECTOR)
+            .filter_map(|el| {
+                let hreflang = el.value().attr("hreflang")?.trim();
+                let url = page_url.join(el.value().attr("href")?.trim()).ok()?;
+                Some(HreflangLink {
+                    hreflang: hreflang.to_string(),
+                    url: url.to_string(),
+                    skip_reason: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Header alternates only fill in languages the HTML doesn't declare
+    fn merge_alternates(
+        mut alternates: Vec<HreflangLink>,
+        header_alternates: &[HreflangLink],
+    ) -> Vec<HreflangLink> {
+        for alternate in header_alternates {
+            if !alternates
+                .iter()
+                .any(|existing| existing.hreflang.eq_ignore_ascii_case(&alternate.hreflang))
+            {
+                alternates.push(alternate.clone());
+            }
+        }
+        alternates
+    }
+
+    /// Collect `rel="canonical"` and `rel="alternate"; hreflang=..` entries from every `Link`
+    /// header, e.g. `<https://example.com/de>; rel="alternate"; hreflang="de"`
+    fn parse_link_headers(headers: &HeaderMap, page_url: &Url) -> LinkHeaders {
+        let mut link_headers = LinkHeaders::default();
+        let values = headers
+            .get_all(header::LINK)
+            .iter()
+            .filter_map(|value| value.to_str().ok());
+
+        for entry in values.flat_map(split_link_header) {
+            let Some((target, params)) = entry
+                .trim()
+                .strip_prefix('<')
+                .and_then(|rest| rest.split_once('>'))
+            else {
+                continue;
+            };
+            let Ok(url) = page_url.join(target.trim()) else {
+                continue;
+            };
+
+            let mut rels = Vec::new();
+            let mut hreflang = None;
+            for param in params.split(';') {
+                let Some((name, value)) = param.split_once('=') else {
+                    continue;
+                };
+                let value = value.trim().trim_matches('"');
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "rel" => rels.extend(value.split_whitespace().map(str::to_ascii_lowercase)),
+                    "hreflang" => hreflang = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+
+            if rels.iter().any(|rel| rel == "canonical") && link_headers.canonical.is_none() {
+                link_headers.canonical = Some(url.to_string());
+            }
+            if rels.iter().any(|rel| rel == "alternate")
+                && let Some(hreflang) = hreflang
+            {
+                link_headers.alternates.push(HreflangLink {
+                    hreflang,
+                    url: url.to_string(),
+                    skip_reason: None,
+                });
+            }
+        }
+
+        link_headers
+    }
+
+    /// Lowercased request header names from every `Vary` header
+    fn parse_vary(headers: &HeaderMap) -> Vec<String> {
+        headers
+            .get_all(header::VARY)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|name| name.trim().to_ascii_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    /// Every `X-Robots-Tag` header value, joined with commas
+    fn parse_x_robots_tag(headers: &HeaderMap) -> Option<String> {
+        let values: Vec<&str> = headers
+            .get_all("x-robots-tag")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .collect();
+        (!values.is_empty()).then(|| values.join(", "))
+    }
+
+    /// The `Last-Modified` HTTP date as an RFC 3339 timestamp, ignoring dates that don't parse
+    fn parse_last_modified(headers: &HeaderMap) -> Option<String> {
+        let value = headers.get(header::LAST_MODIFIED)?.to_str().ok()?;
+        let modified = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+        Some(
+            modified
+                .with_timezone(&chrono::Utc)
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        )
+    }
+
+    /// `Cookie` header value that sends back every cookie the response set
+    fn cookies_from_response(headers: &HeaderMap) -> Option<String> {
+        let cookies: Vec<&str> = headers
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .map(str::trim)
+            .filter(|pair| pair.contains('='))
+            .collect();
+        (!cookies.is_empty()).then(|| cookies.join("; "))
+    }
+
+    /// Request the page again with a crawler user agent, and with the cookies it set, and
+    /// report which of those request headers changed the content
+    async fn observe_variation(
+        &self,
+        url: &str,
+        page_url: &Url,
+        html: &str,
+        cookies: Option<&str>,
+    ) -> Vec<String> {
+        let baseline = self.content_signature(html, page_url);
+        let mut probes = vec![(header::USER_AGENT, VARY_PROBE_USER_AGENT)];
+        if let Some(cookies) = cookies {
+            probes.push((header::COOKIE, cookies));
+        }
+
+        let mut observed = Vec::new();
+        for (name, value) in probes {
+            if !self.acquire_request() {
+                break;
+            }
+            self.wait_for_turn().await;
+            let html = match self.client.get(url).header(&name, value).send().await {
+                Ok(response) => self.read_body_within_limit(response).await,
+                Err(error) => Err(error.into()),
+            };
+            match html {
+                Ok(Some(html)) if self.content_signature(&html, page_url) != baseline => {
+                    observed.push(name.as_str().to_string());
+                }
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    tracing::warn!(url = %url, header = %name, max_bytes = self.max_body_bytes, "Vary probe body is too large, skipping the probe");
+                }
+                Err(error) => {
+                    tracing::warn!(url = %url, header = %name, error = %error, "Vary probe request failed");
+                }
+            }
+        }
+        observed
+    }
+
+    /// The parts of a page that matter for search, so that timestamps, nonces and other
+    /// per-request noise don't count as variation
+    fn content_signature(&self, html: &str, page_url: &Url) -> String {
+        let document = Html::parse_document(html);
+        let mut links: Vec<String> = self
+            .extract_links(&document, page_url)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|link| link.url)
+            .collect();
+        links.sort();
+        links.dedup();
+
+        format!(
+            "{:?}\n{:?}\n{:?}\n{:?}\n{:?}",
+            Self::extract_title(&document),
+            Self::extract_meta_description(&document),
+            Self::extract_canonical(&document, page_url),
+            Self::extract_h1_tags(&document),
+            links
+        )
+    }
+
+    fn measure_weight(document: &Html, html_bytes: usize) -> PageWeight {
+        PageWeight {
+            html_bytes,
+            requests: 1 + document.select(&SUBRESOURCE_SELECTOR).count(),
+            scripts: document.select(&SCRIPT_SELECTOR).count(),
+        }
+    }
+
     fn extract_h1_tags(document: &Html) -> Vec<String> {
         document
             .select(&H1_SELECTOR)
@@ -402,6 +2220,71 @@ impl Crawler {
             .collect()
     }
 
+    fn extract_content(document: &Html) -> PageContent {
+        let headings = document
+            .select(&HEADING_SELECTOR)
+            .filter_map(|element| {
+                let level = element.value().name().strip_prefix('h')?.parse().ok()?;
+                let text = element.text().collect::<String>();
+                Some(Heading {
+                    level,
+                    text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                })
+            })
+            .collect();
+
+        let mut meta = BTreeMap::new();
+        for element in document.select(&META_SELECTOR) {
+            let element = element.value();
+            let key = ["name", "property", "http-equiv"]
+                .into_iter()
+                .find_map(|attribute| element.attr(attribute));
+            if let (Some(key), Some(value)) = (key, element.attr("content")) {
+                meta.entry(key.trim().to_ascii_lowercase())
+                    .or_insert_with(|| value.trim().to_string());
+            }
+        }
+
+        let comments = document
+            .tree
+            .nodes()
+            .filter_map(|node| node.value().as_comment())
+            .map(|comment| comment.trim().to_string())
+            .filter(|comment| !comment.is_empty())
+            .collect();
+
+        PageContent {
+            text: Self::extract_visible_text(document),
+            headings,
+            meta,
+            html: None,
+            comments,
+        }
+    }
+
+    /// Body text outside scripts, styles, and templates, with whitespace collapsed
+    fn extract_visible_text(document: &Html) -> String {
+        let Some(body) = document.select(&BODY_SELECTOR).next() else {
+            return String::new();
+        };
+
+        let mut text = String::new();
+        for node in body.descendants() {
+            let Some(node_text) = node.value().as_text() else {
+                continue;
+            };
+            let hidden = node.ancestors().any(|ancestor| {
+                ancestor.value().as_element().is_some_and(|element| {
+                    matches!(element.name(), "script" | "style" | "noscript" | "template")
+                })
+            });
+            if !hidden {
+                text.push_str(node_text);
+            }
+        }
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
     fn extract_open_graph_tags(document: &Html) -> OpenGraphTags {
         OpenGraphTags {
             og_title: document
@@ -463,20 +2346,26 @@ impl Crawler {
                 let is_external = self.is_external_url(&absolute_url);
 
                 // Generate text based on element type
-                let text = match element_name {
-                    "a" => element.text().collect::<String>().trim().to_string(),
+                let (text, kind) = match element_name {
+                    "a" => (
+                        element.text().collect::<String>().trim().to_string(),
+                        DiscoveryMethod::Anchor,
+                    ),
                     "iframe" => {
                         let title = element.value().attr("title").unwrap_or("");
-                        format!("[iframe] {}", title)
+                        (format!("[iframe] {}", title), DiscoveryMethod::Iframe)
                     }
-                    "video" => "[video]".to_string(),
+                    "video" => ("[video]".to_string(), DiscoveryMethod::Embed),
                     "source" => {
                         let media_type = element.value().attr("type").unwrap_or("");
-                        format!("[source type={}]", media_type)
+                        (
+                            format!("[source type={}]", media_type),
+                            DiscoveryMethod::Embed,
+                        )
                     }
-                    "audio" => "[audio]".to_string(),
-                    "embed" => "[embed]".to_string(),
-                    "object" => "[object]".to_string(),
+                    "audio" => ("[audio]".to_string(), DiscoveryMethod::Embed),
+                    "embed" => ("[embed]".to_string(), DiscoveryMethod::Embed),
+                    "object" => ("[object]".to_string(), DiscoveryMethod::Embed),
                     _ => continue, // Skip unknown elements
                 };
 
@@ -484,9 +2373,16 @@ impl Crawler {
                     url: url_str,
                     text,
                     is_external,
+                    kind,
+                    rel: (element_name == "a")
+                        .then(|| element.value().attr("rel").map(str::to_string))
+                        .flatten(),
                     status_code: None,
                     redirected_url: None,
                     check_error: None,
+                    skip_reason: None,
+                    content_type: None,
+                    content_length: None,
                 });
             }
         }
@@ -502,9 +2398,17 @@ impl Crawler {
                 && let Ok(absolute_url) = page_url.join(src)
             {
                 let alt = element.value().attr("alt").map(|s| s.to_string());
+                let dimension = |name| {
+                    element
+                        .value()
+                        .attr(name)
+                        .and_then(|value| value.trim().trim_end_matches("px").parse().ok())
+                };
                 images.push(Image {
                     src: absolute_url.to_string(),
                     alt,
+                    width: dimension("width"),
+                    height: dimension("height"),
                 });
             }
         }
@@ -521,6 +2425,15 @@ impl Crawler {
             && !Self::is_known_non_html_resource_url(&parsed_url)
     }
 
+    /// Why the include and exclude patterns reject `url`, if they do; exclude wins when both match
+    fn url_pattern_rejection(&self, url: &str) -> Option<String> {
+        if let Some(pattern) = self.exclude.iter().find(|pattern| pattern.is_match(url)) {
+            return Some(format!("matches --exclude pattern `{pattern}`"));
+        }
+        (!self.include.is_empty() && !self.include.iter().any(|pattern| pattern.is_match(url)))
+            .then(|| "doesn't match any --include pattern".to_string())
+    }
+
     fn has_supported_web_scheme(url: &Url) -> bool {
         matches!(url.scheme(), "http" | "https")
     }
@@ -594,3 +2507,94 @@ impl Crawler {
         )
     }
 }
+
+/// Fail with a `PrivateAddressError` when `url` names a private address. Hostnames are
+/// checked by the public-only clients when they resolve.
+fn refuse_private_address(url: &str) -> Result<()> {
+    let Ok(parsed) = Url::parse(url) else {
+        return Ok(());
+    };
+    match hosts::ip_address(&parsed) {
+        Some(address) if hosts::is_private_ip(address) => Err(PrivateAddressError {
+            host: parsed.host_str().unwrap_or_default().to_string(),
+            address,
+        }
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Sort a failed page fetch into the cause it is reported under
+fn fetch_error_cause(error: &anyhow::Error) -> CrawlErrorCause {
+    if find_redirect_loop(error.as_ref()).is_some()
+        || error
+            .chain()
+            .any(|source| source.to_string() == TOO_MANY_REDIRECTS)
+    {
+        return CrawlErrorCause::Redirect;
+    }
+    let Some(request_error) = error
+        .chain()
+        .find_map(|source| source.downcast_ref::<reqwest::Error>())
+    else {
+        return if error.chain().any(|source| source.is::<url::ParseError>()) {
+            CrawlErrorCause::Parse
+        } else {
+            CrawlErrorCause::Other
+        };
+    };
+
+    if request_error.is_timeout() {
+        CrawlErrorCause::Timeout
+    } else if request_error.is_redirect() {
+        CrawlErrorCause::Redirect
+    } else if request_error.is_connect() {
+        // The connector's errors only say what failed in their messages
+        let messages: Vec<String> =
+            std::iter::successors(Some(request_error as &dyn std::error::Error), |&error| {
+                error.source()
+            })
+            .map(|error| error.to_string().to_lowercase())
+            .collect();
+        let mentions = |words: &[&str]| {
+            messages
+                .iter()
+                .any(|message| words.iter().any(|word| message.contains(word)))
+        };
+        if mentions(&["dns error", "failed to lookup address"]) {
+            CrawlErrorCause::Dns
+        } else if mentions(&["certificate", "tls", "handshake"]) {
+            CrawlErrorCause::Tls
+        } else {
+            CrawlErrorCause::Connection
+        }
+    } else if request_error.is_body() || request_error.is_decode() {
+        CrawlErrorCause::Parse
+    } else {
+        CrawlErrorCause::Other
+    }
+}
+
+/// Split a `Link` header value into its comma-separated entries, ignoring commas inside
+/// `<...>` targets and quoted parameters
+fn split_link_header(value: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut start = 0;
+    let mut in_target = false;
+    let mut in_quotes = false;
+
+    for (index, ch) in value.char_indices() {
+        match ch {
+            '<' if !in_quotes => in_target = true,
+            '>' if !in_quotes => in_target = false,
+            '"' if !in_target => in_quotes = !in_quotes,
+            ',' if !in_target && !in_quotes => {
+                entries.push(&value[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(&value[start..]);
+    entries
+}
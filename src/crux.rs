@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use url::Url;
+
+use crate::http_client::build_http_client;
+use crate::link_checker::DEFAULT_LINK_TIMEOUT_SECS;
+use crate::models::{FieldData, FieldDataScope, HtmlContentTypes, PageInfo};
+use crate::page_store::PageStore;
+
+const CRUX_ENDPOINT: &str = "https://chromeuxreport.googleapis.com/v1/records:queryRecord";
+/// Kept low so a crawl stays well inside the API's per-minute quota
+const CONCURRENT_LOOKUPS: usize = 4;
+
+/// Looks up Core Web Vitals field data in the Chrome UX Report API
+pub struct CruxClient {
+    client: Client,
+    api_key: String,
+    endpoint: String,
+    html_content_types: HtmlContentTypes,
+}
+
+impl CruxClient {
+    pub fn new(api_key: impl Into<String>) -> Result<Self> {
+        Ok(Self {
+            client: build_http_client(DEFAULT_LINK_TIMEOUT_SECS)?,
+            api_key: api_key.into(),
+            endpoint: CRUX_ENDPOINT.to_string(),
+            html_content_types: HtmlContentTypes::default(),
+        })
+    }
+
+    /// Give up on lookups that take longer than this many seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Result<Self> {
+        self.client = build_http_client(timeout_secs)?;
+        Ok(self)
+    }
+
+    /// Send queries to another endpoint, e.g. a local stand-in for the API
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Count pages of these content types as HTML pages
+    pub fn with_html_content_types(mut self, html_content_types: HtmlContentTypes) -> Self {
+        self.html_content_types = html_content_types;
+        self
+    }
+
+    /// Attach field data to every successfully crawled HTML page. Origin records are shared, so
+    /// each origin is only queried once. Failed lookups are logged and leave the page unchanged.
+    pub async fn annotate_pages(&self, pages: &mut dyn PageStore) -> Result<()> {
+        let mut urls = Vec::new();
+        pages.for_each_page(&mut |key, page| {
+            if self.has_field_data(page) {
+                urls.push((key.to_string(), page.url.clone()));
+            }
+            Ok(())
+        })?;
+
+        for (key, data) in self.look_up(urls).await {
+            if let Some(mut page) = pages.get(&key)? {
+                page.field_data = data;
+                pages.insert(key, page)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn has_field_data(&self, page: &PageInfo) -> bool {
+        page.status_code
+            .is_some_and(|code| (200..300).contains(&code))
+            && self
+                .html_content_types
+                .contains(page.content_type.as_deref())
+    }
+
+    /// The field data of each `(key, url)`, falling back to the URL's origin when the API has
+    /// no record for the URL itself
+    async fn look_up(&self, urls: Vec<(String, String)>) -> Vec<(String, Option<FieldData>)> {
+        let url_records: Vec<(String, String, Result<Option<FieldData>>)> = stream::iter(urls)
+            .map(|(key, url)| async move {
+                let result = self
+                    .query(json!({ "url": &url }), FieldDataScope::Url)
+                    .await;
+                (key, url, result)
+            })
+            .buffer_unordered(CONCURRENT_LOOKUPS)
+            .collect()
+            .await;
+
+        let mut annotated = Vec::new();
+        let mut origin_records: HashMap<String, Option<FieldData>> = HashMap::new();
+        for (key, url, result) in url_records {
+            let data = match result {
+                Ok(Some(data)) => Some(data),
+                Ok(None) => {
+                    let Ok(parsed) = Url::parse(&url) else {
+                        continue;
+                    };
+                    let origin = parsed.origin().ascii_serialization();
+                    if !origin_records.contains_key(&origin) {
+                        let record = self
+                            .query(json!({ "origin": &origin }), FieldDataScope::Origin)
+                            .await
+                            .unwrap_or_else(|error| {
+                                tracing::warn!(origin = %origin, error = %error, "Failed to look up CrUX field data");
+                                None
+                            });
+                        origin_records.insert(origin.clone(), record);
+                    }
+                    origin_records[&origin].clone()
+                }
+                Err(error) => {
+                    tracing::warn!(url = %url, error = %error, "Failed to look up CrUX field data");
+                    None
+                }
+            };
+
+            annotated.push((key, data));
+        }
+        annotated
+    }
+
+    async fn query(&self, body: Value, scope: FieldDataScope) -> Result<Option<FieldData>> {
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .query(&[("key", &self.api_key)])
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to reach the CrUX API")?;
+
+        // The API answers 404 when a URL or origin has too little traffic to be included
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response
+                .json::<Value>()
+                .await
+                .ok()
+                .and_then(|body| body["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or_default();
+            anyhow::bail!("CrUX API returned {status}: {message}");
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse CrUX API response")?;
+        let metrics = &body["record"]["metrics"];
+        Ok(Some(FieldData {
+            scope,
+            lcp_ms: p75(&metrics["largest_contentful_paint"]),
+            cls: p75(&metrics["cumulative_layout_shift"]),
+            inp_ms: p75(&metrics["interaction_to_next_paint"]),
+        }))
+    }
+}
+
+/// The API reports most percentiles as numbers but CLS as a string
+fn p75(metric: &Value) -> Option<f64> {
+    match &metric["percentiles"]["p75"] {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,784 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+
+use super::sites::{self, Site, SiteInfo};
+use super::{Job, JobId, JobQueue, JobSource, JobStatus};
+use crate::cli::{CrawlPreset, LinkCheckScope};
+use crate::config::Config;
+use crate::limits::{ByteSize, HumanDuration};
+use crate::models::CrawlSummary;
+
+/// What an API token may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiScope {
+    /// Queue crawls
+    Crawl,
+    /// List sites and jobs and read their status and reports
+    Reports,
+}
+
+impl ApiScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Crawl => "crawl",
+            Self::Reports => "reports",
+        }
+    }
+}
+
+/// A bearer token accepted by the API, from `api_tokens` in the config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub token: String,
+    pub scopes: Vec<ApiScope>,
+}
+
+/// What the API handlers share: the job queue, the sites directory when sites are served, and
+/// the accepted tokens
+#[derive(Clone)]
+pub struct ApiState {
+    pub queue: Arc<JobQueue>,
+    pub sites_dir: Option<PathBuf>,
+    /// With no tokens every route is open, apart from sites with their own `api_token`; `serve`
+    /// only allows that with `--no-auth`
+    pub tokens: Vec<ApiToken>,
+}
+
+impl ApiState {
+    pub fn new(queue: Arc<JobQueue>) -> Self {
+        Self {
+            queue,
+            sites_dir: None,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+type ApiError = (StatusCode, String);
+
+/// The body of every error response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+/// Changes to a queued job
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct JobUpdate {
+    /// Queued jobs with a higher priority start first
+    pub priority: i32,
+}
+
+/// A crawl submitted to `POST /jobs`. Only the start URLs, limits, and crawl switches can be
+/// set: settings that read or write files on the server, run scripts, or make it send requests
+/// elsewhere are refused, and belong in a site file instead.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JobRequest {
+    /// The URL to start crawling from
+    pub url: Option<String>,
+    /// More URLs to start crawling from alongside `url`
+    pub urls: Option<Vec<String>>,
+    /// Crawl preset supplying depth, page, concurrency, and link-check defaults
+    #[schema(value_type = Option<String>, example = "quick")]
+    pub preset: Option<CrawlPreset>,
+    /// Maximum crawl depth
+    pub depth: Option<usize>,
+    /// Maximum number of pages to crawl
+    pub max_pages: Option<usize>,
+    /// Requests sent across the crawl and link checks before the scan stops
+    pub max_requests: Option<usize>,
+    /// Response bytes downloaded before the scan stops, e.g. "500MB"
+    #[schema(value_type = Option<String>, example = "500MB")]
+    pub max_bandwidth: Option<ByteSize>,
+    /// Stop queueing pages once the crawl has run this long, e.g. "10m"
+    #[schema(value_type = Option<String>, example = "10m")]
+    pub max_duration: Option<HumanDuration>,
+    /// Number of concurrent requests
+    pub concurrency: Option<usize>,
+    /// Rate limit for requests per second
+    pub rate_limit: Option<f64>,
+    /// Seconds to wait for each page to respond
+    pub timeout: Option<u64>,
+    /// Seconds to wait for each checked link to respond
+    pub link_timeout: Option<u64>,
+    /// Which links to check: internal, external, all, or none
+    #[schema(value_type = Option<String>, example = "internal")]
+    pub check_links: Option<LinkCheckScope>,
+    /// Follow external links
+    pub external: Option<bool>,
+    /// Ignore redirect issues in the report
+    pub ignore_redirects: Option<bool>,
+    /// Treat URLs with fragment identifiers (#) as unique links
+    pub keep_fragments: Option<bool>,
+    /// Regexes a discovered URL must match one of to be crawled
+    pub include: Option<Vec<String>>,
+    /// Regexes for URLs that are never crawled
+    pub exclude: Option<Vec<String>>,
+    /// Drop click-tracking query parameters before comparing URLs
+    pub strip_tracking_params: Option<bool>,
+    /// Sort query parameters before comparing URLs
+    pub sort_query: Option<bool>,
+    /// Don't follow links marked `rel="nofollow"`, `rel="ugc"`, or `rel="sponsored"`
+    pub respect_nofollow: Option<bool>,
+    /// Respect robots.txt rules
+    pub respect_robots_txt: Option<bool>,
+    /// Queue the pages listed in the site's sitemaps before following links
+    pub use_sitemap: Option<bool>,
+    /// Crawl politely: low concurrency and rate limit, jitter, Crawl-delay, and Retry-After
+    pub gentle: Option<bool>,
+    /// Lower the concurrency while the site slows down or answers with 429 or 5xx
+    pub auto_throttle: Option<bool>,
+    /// Record each page's redirect chain
+    pub trace_redirects: Option<bool>,
+    /// Detect content that varies on User-Agent or Cookie without declaring it
+    pub audit_vary: Option<bool>,
+    /// Report email addresses and phone numbers shown as plain text
+    pub find_contacts: Option<bool>,
+    /// Scan page source for keys, tokens, and internal IP addresses
+    pub scan_secrets: Option<bool>,
+    /// Add each page's heading outline to the report
+    pub heading_outline: Option<bool>,
+    /// Queued jobs with a higher priority start first
+    pub priority: Option<i32>,
+}
+
+impl JobRequest {
+    /// The config file settings this job runs with; everything it can't set keeps its default
+    pub fn into_config(self) -> Config {
+        Config {
+            url: self.url,
+            urls: self.urls,
+            preset: self.preset,
+            depth: self.depth,
+            max_pages: self.max_pages,
+            max_requests: self.max_requests,
+            max_bandwidth: self.max_bandwidth,
+            max_duration: self.max_duration,
+            concurrency: self.concurrency,
+            rate_limit: self.rate_limit,
+            timeout: self.timeout,
+            link_timeout: self.link_timeout,
+            check_links: self.check_links,
+            external: self.external,
+            ignore_redirects: self.ignore_redirects,
+            keep_fragments: self.keep_fragments,
+            include: self.include,
+            exclude: self.exclude,
+            strip_tracking_params: self.strip_tracking_params,
+            sort_query: self.sort_query,
+            respect_nofollow: self.respect_nofollow,
+            respect_robots_txt: self.respect_robots_txt,
+            use_sitemap: self.use_sitemap,
+            gentle: self.gentle,
+            auto_throttle: self.auto_throttle,
+            trace_redirects: self.trace_redirects,
+            audit_vary: self.audit_vary,
+            find_contacts: self.find_contacts,
+            scan_secrets: self.scan_secrets,
+            heading_outline: self.heading_outline,
+            priority: self.priority,
+            ..Config::default()
+        }
+    }
+}
+
+/// OpenAPI description of the routes in `router`, served at `/openapi.json`
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Scoutly", description = "Queue crawls and read their reports"),
+    paths(
+        health,
+        list_jobs,
+        submit_job,
+        get_job,
+        get_report,
+        update_job,
+        cancel_job,
+        list_sites,
+        get_site,
+        submit_site_job,
+        list_site_jobs,
+        get_site_job,
+        get_site_report,
+        update_site_job,
+        cancel_site_job
+    ),
+    components(schemas(
+        Job,
+        JobStatus,
+        JobSource,
+        JobRequest,
+        JobUpdate,
+        CrawlSummary,
+        SiteInfo,
+        ErrorBody
+    )),
+    modifiers(&BearerToken)
+)]
+pub struct ApiDoc;
+
+struct BearerToken;
+
+impl Modify for BearerToken {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// HTTP API for submitting jobs and reading their status and archived reports. Sites from the
+/// sites directory get their own routes under `/sites/{name}`, and their jobs are only visible
+/// there. `/health` and `/openapi.json` never need a token.
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/openapi.json", get(|| async { Json(ApiDoc::openapi()) }))
+        .route("/jobs", get(list_jobs).post(submit_job))
+        .route("/jobs/{id}", get(get_job).patch(update_job))
+        .route("/jobs/{id}/report", get(get_report))
+        .route("/jobs/{id}/cancel", post(cancel_job))
+        .route("/sites", get(list_sites))
+        .route("/sites/{name}", get(get_site))
+        .route(
+            "/sites/{name}/jobs",
+            get(list_site_jobs).post(submit_site_job),
+        )
+        .route(
+            "/sites/{name}/jobs/{id}",
+            get(get_site_job).patch(update_site_job),
+        )
+        .route("/sites/{name}/jobs/{id}/report", get(get_site_report))
+        .route("/sites/{name}/jobs/{id}/cancel", post(cancel_site_job))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "service",
+    responses((status = 200, description = "The service is up", body = Object))
+)]
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/jobs",
+    tag = "jobs",
+    responses(
+        (status = 200, description = "Jobs submitted without a site, oldest first", body = [Job]),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the reports scope", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn list_jobs(State(state): State<ApiState>, headers: HeaderMap) -> Response {
+    if let Err((status, message)) = authorize(&state, &headers, ApiScope::Reports) {
+        return error_response(status, message);
+    }
+    Json(state.queue.list_site(None)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/jobs",
+    tag = "jobs",
+    request_body = JobRequest,
+    responses(
+        (status = 202, description = "Job queued", body = Job),
+        (status = 400, description = "Missing url, or an invalid or unsupported field", body = ErrorBody),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the crawl scope", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn submit_job(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    request: Result<Json<JobRequest>, JsonRejection>,
+) -> Response {
+    if let Err((status, message)) = authorize(&state, &headers, ApiScope::Crawl) {
+        return error_response(status, message);
+    }
+    let Json(request) = match request {
+        Ok(request) => request,
+        Err(rejection) => return error_response(StatusCode::BAD_REQUEST, rejection.body_text()),
+    };
+    match state.queue.submit(request.into_config(), JobSource::Api) {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        Err(error) => error_response(StatusCode::BAD_REQUEST, error.to_string()),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}",
+    tag = "jobs",
+    params(("id" = u64, Path, description = "Job id")),
+    responses(
+        (status = 200, description = "The job's status", body = Job),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the reports scope", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn get_job(
+    State(state): State<ApiState>,
+    Path(id): Path<JobId>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err((status, message)) = authorize(&state, &headers, ApiScope::Reports) {
+        return error_response(status, message);
+    }
+    match find_job(&state.queue, None, id) {
+        Some(job) => Json(job).into_response(),
+        None => job_not_found(id),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}/report",
+    tag = "jobs",
+    params(("id" = u64, Path, description = "Job id")),
+    responses(
+        (status = 200, description = "The archived JSON report", body = Object),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the reports scope", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job hasn't finished", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn get_report(
+    State(state): State<ApiState>,
+    Path(id): Path<JobId>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err((status, message)) = authorize(&state, &headers, ApiScope::Reports) {
+        return error_response(status, message);
+    }
+    match find_job(&state.queue, None, id) {
+        Some(job) => report_response(job).await,
+        None => job_not_found(id),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/jobs/{id}",
+    tag = "jobs",
+    params(("id" = u64, Path, description = "Job id")),
+    request_body = JobUpdate,
+    responses(
+        (status = 200, description = "The updated job", body = Job),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the crawl scope", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job is no longer queued", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn update_job(
+    State(state): State<ApiState>,
+    Path(id): Path<JobId>,
+    headers: HeaderMap,
+    Json(update): Json<JobUpdate>,
+) -> Response {
+    if let Err((status, message)) = authorize(&state, &headers, ApiScope::Crawl) {
+        return error_response(status, message);
+    }
+    apply_update(&state.queue, None, id, &update)
+}
+
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/cancel",
+    tag = "jobs",
+    params(("id" = u64, Path, description = "Job id")),
+    responses(
+        (status = 200, description = "The cancelled job", body = Job),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the crawl scope", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job has already finished", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn cancel_job(
+    State(state): State<ApiState>,
+    Path(id): Path<JobId>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err((status, message)) = authorize(&state, &headers, ApiScope::Crawl) {
+        return error_response(status, message);
+    }
+    apply_cancel(&state.queue, None, id)
+}
+
+#[utoipa::path(
+    get,
+    path = "/sites",
+    tag = "sites",
+    responses(
+        (status = 200, description = "Sites in the sites directory", body = [SiteInfo]),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the reports scope", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn list_sites(State(state): State<ApiState>, headers: HeaderMap) -> Response {
+    if let Err((status, message)) = authorize(&state, &headers, ApiScope::Reports) {
+        return error_response(status, message);
+    }
+    let Some(sites_dir) = &state.sites_dir else {
+        return Json(Vec::<SiteInfo>::new()).into_response();
+    };
+    match sites::list_sites(sites_dir) {
+        Ok(sites) => Json(sites.iter().map(Site::info).collect::<Vec<_>>()).into_response(),
+        Err(error) => error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("{error:#}")),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/sites/{name}",
+    tag = "sites",
+    params(("name" = String, Path, description = "Site file name without its extension")),
+    responses(
+        (status = 200, description = "The site", body = SiteInfo),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the reports scope", body = ErrorBody),
+        (status = 404, description = "No such site", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn get_site(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    match authorized_site(&state, &name, &headers, ApiScope::Reports) {
+        Ok(site) => Json(site.info()).into_response(),
+        Err((status, message)) => error_response(status, message),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/sites/{name}/jobs",
+    tag = "sites",
+    params(("name" = String, Path, description = "Site file name without its extension")),
+    responses(
+        (status = 202, description = "Job queued with the site's config", body = Job),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the crawl scope", body = ErrorBody),
+        (status = 404, description = "No such site", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn submit_site_job(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let site = match authorized_site(&state, &name, &headers, ApiScope::Crawl) {
+        Ok(site) => site,
+        Err((status, message)) => return error_response(status, message),
+    };
+    match state.queue.submit_site(site, JobSource::Api) {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        Err(error) => error_response(StatusCode::BAD_REQUEST, error.to_string()),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/sites/{name}/jobs",
+    tag = "sites",
+    params(("name" = String, Path, description = "Site file name without its extension")),
+    responses(
+        (status = 200, description = "The site's jobs, oldest first", body = [Job]),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the reports scope", body = ErrorBody),
+        (status = 404, description = "No such site", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn list_site_jobs(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    match authorized_site(&state, &name, &headers, ApiScope::Reports) {
+        Ok(site) => Json(state.queue.list_site(Some(&site.name))).into_response(),
+        Err((status, message)) => error_response(status, message),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/sites/{name}/jobs/{id}",
+    tag = "sites",
+    params(
+        ("name" = String, Path, description = "Site file name without its extension"),
+        ("id" = u64, Path, description = "Job id")
+    ),
+    responses(
+        (status = 200, description = "The job's status", body = Job),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the reports scope", body = ErrorBody),
+        (status = 404, description = "No such site, or no such job for it", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn get_site_job(
+    State(state): State<ApiState>,
+    Path((name, id)): Path<(String, JobId)>,
+    headers: HeaderMap,
+) -> Response {
+    let site = match authorized_site(&state, &name, &headers, ApiScope::Reports) {
+        Ok(site) => site,
+        Err((status, message)) => return error_response(status, message),
+    };
+    match find_job(&state.queue, Some(&site.name), id) {
+        Some(job) => Json(job).into_response(),
+        None => job_not_found(id),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/sites/{name}/jobs/{id}/report",
+    tag = "sites",
+    params(
+        ("name" = String, Path, description = "Site file name without its extension"),
+        ("id" = u64, Path, description = "Job id")
+    ),
+    responses(
+        (status = 200, description = "The archived JSON report", body = Object),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the reports scope", body = ErrorBody),
+        (status = 404, description = "No such site, or no such job for it", body = ErrorBody),
+        (status = 409, description = "The job hasn't finished", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn get_site_report(
+    State(state): State<ApiState>,
+    Path((name, id)): Path<(String, JobId)>,
+    headers: HeaderMap,
+) -> Response {
+    let site = match authorized_site(&state, &name, &headers, ApiScope::Reports) {
+        Ok(site) => site,
+        Err((status, message)) => return error_response(status, message),
+    };
+    match find_job(&state.queue, Some(&site.name), id) {
+        Some(job) => report_response(job).await,
+        None => job_not_found(id),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/sites/{name}/jobs/{id}",
+    tag = "sites",
+    params(
+        ("name" = String, Path, description = "Site file name without its extension"),
+        ("id" = u64, Path, description = "Job id")
+    ),
+    request_body = JobUpdate,
+    responses(
+        (status = 200, description = "The updated job", body = Job),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the crawl scope", body = ErrorBody),
+        (status = 404, description = "No such site, or no such job for it", body = ErrorBody),
+        (status = 409, description = "The job is no longer queued", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn update_site_job(
+    State(state): State<ApiState>,
+    Path((name, id)): Path<(String, JobId)>,
+    headers: HeaderMap,
+    Json(update): Json<JobUpdate>,
+) -> Response {
+    match authorized_site(&state, &name, &headers, ApiScope::Crawl) {
+        Ok(site) => apply_update(&state.queue, Some(&site.name), id, &update),
+        Err((status, message)) => error_response(status, message),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/sites/{name}/jobs/{id}/cancel",
+    tag = "sites",
+    params(
+        ("name" = String, Path, description = "Site file name without its extension"),
+        ("id" = u64, Path, description = "Job id")
+    ),
+    responses(
+        (status = 200, description = "The cancelled job", body = Job),
+        (status = 401, description = "Missing or invalid API token", body = ErrorBody),
+        (status = 403, description = "Token lacks the crawl scope", body = ErrorBody),
+        (status = 404, description = "No such site, or no such job for it", body = ErrorBody),
+        (status = 409, description = "The job has already finished", body = ErrorBody)
+    ),
+    security((), ("bearer_token" = []))
+)]
+async fn cancel_site_job(
+    State(state): State<ApiState>,
+    Path((name, id)): Path<(String, JobId)>,
+    headers: HeaderMap,
+) -> Response {
+    match authorized_site(&state, &name, &headers, ApiScope::Crawl) {
+        Ok(site) => apply_cancel(&state.queue, Some(&site.name), id),
+        Err((status, message)) => error_response(status, message),
+    }
+}
+
+fn apply_update(queue: &JobQueue, site: Option<&str>, id: JobId, update: &JobUpdate) -> Response {
+    if find_job(queue, site, id).is_none() {
+        return job_not_found(id);
+    }
+    match queue.set_priority(id, update.priority) {
+        Ok(job) => Json(job).into_response(),
+        Err(error) => error_response(StatusCode::CONFLICT, error.to_string()),
+    }
+}
+
+fn apply_cancel(queue: &JobQueue, site: Option<&str>, id: JobId) -> Response {
+    if find_job(queue, site, id).is_none() {
+        return job_not_found(id);
+    }
+    match queue.cancel(id) {
+        Ok(job) => Json(job).into_response(),
+        Err(error) => error_response(StatusCode::CONFLICT, error.to_string()),
+    }
+}
+
+/// The job with this id, as long as it belongs to `site`; other sites' jobs look missing
+fn find_job(queue: &JobQueue, site: Option<&str>, id: JobId) -> Option<Job> {
+    queue.get(id).filter(|job| job.site.as_deref() == site)
+}
+
+/// Check the request's bearer token against the configured tokens, if there are any
+fn authorize(state: &ApiState, headers: &HeaderMap, scope: ApiScope) -> Result<(), ApiError> {
+    if state.tokens.is_empty() {
+        return Ok(());
+    }
+    let Some(token) = bearer_token(headers).and_then(|bearer| {
+        state
+            .tokens
+            .iter()
+            .find(|token| tokens_match(&token.token, bearer))
+    }) else {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid API token".to_string(),
+        ));
+    };
+    if !token.scopes.contains(&scope) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!("This API token lacks the {} scope", scope.as_str()),
+        ));
+    }
+    Ok(())
+}
+
+/// Load the named site and check the request's bearer token: the site's own `api_token` allows
+/// everything on its routes, and otherwise a configured token needs `scope`
+fn authorized_site(
+    state: &ApiState,
+    name: &str,
+    headers: &HeaderMap,
+    scope: ApiScope,
+) -> Result<Site, ApiError> {
+    let site_not_found = || (StatusCode::NOT_FOUND, format!("Site {name} not found"));
+    let Some(sites_dir) = &state.sites_dir else {
+        return Err(site_not_found());
+    };
+    let site = sites::find_site(sites_dir, name)
+        .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, format!("{error:#}")))?
+        .ok_or_else(site_not_found)?;
+
+    if site.config.api_token.is_none() {
+        authorize(state, headers, scope)?;
+    } else if !site.authorizes(bearer_token(headers)) {
+        if state.tokens.is_empty() {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                format!("Missing or invalid API token for site {name}"),
+            ));
+        }
+        authorize(state, headers, scope)?;
+    }
+    Ok(site)
+}
+
+/// Compare a presented token with an expected one in constant time. Both are hashed first, so
+/// neither the content nor the length of the expected token shows in how long this takes.
+pub(crate) fn tokens_match(expected: &str, presented: &str) -> bool {
+    let expected = Sha256::digest(expected.as_bytes());
+    let presented = Sha256::digest(presented.as_bytes());
+    expected
+        .iter()
+        .zip(presented.iter())
+        .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+        == 0
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+async fn report_response(job: Job) -> Response {
+    let Some(report_path) = job.report_path else {
+        return error_response(
+            StatusCode::CONFLICT,
+            format!(
+                "Job {} has no report yet (status: {:?})",
+                job.id, job.status
+            ),
+        );
+    };
+
+    match tokio::fs::read_to_string(&report_path).await {
+        Ok(report) => ([("content-type", "application/json")], report).into_response(),
+        Err(error) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read archived report {report_path}: {error}"),
+        ),
+    }
+}
+
+fn job_not_found(id: JobId) -> Response {
+    error_response(StatusCode::NOT_FOUND, format!("Job {id} not found"))
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, Json(ErrorBody { error: message })).into_response()
+}
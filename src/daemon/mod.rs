@@ -0,0 +1,585 @@
+pub mod api;
+pub mod schedule;
+pub mod sites;
+pub mod watch;
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use utoipa::ToSchema;
+
+use crate::cli::Cli;
+use crate::config::Config;
+use crate::models::{CrawlReport, CrawlSummary};
+use crate::{execute_scan, record_trend};
+use sites::Site;
+
+pub type JobId = u64;
+
+/// How long a webhook gets to accept a regression notification
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
+/// File in the archive directory the job queue is saved to
+pub const QUEUE_FILE_NAME: &str = "queue.json";
+
+/// Settings for the long-running crawl service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaemonOptions {
+    pub listen: SocketAddr,
+    pub workers: usize,
+    pub jobs_dir: Option<PathBuf>,
+    /// Directory of site config files crawled on their cron `schedule`
+    pub sites_dir: Option<PathBuf>,
+    pub archive_dir: PathBuf,
+    pub poll_interval: Duration,
+    /// Bearer tokens the HTTP API accepts; empty leaves it open
+    pub api_tokens: Vec<api::ApiToken>,
+    /// Allow serving the API without any `api_tokens`
+    pub no_auth: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Where a job was submitted from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case", tag = "type", content = "path")]
+pub enum JobSource {
+    Api,
+    File(String),
+    /// Queued by the cron `schedule` in this site file
+    Schedule(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Job {
+    #[schema(value_type = u64)]
+    pub id: JobId,
+    pub url: String,
+    /// The site from the sites directory this job crawls; its history is only served under
+    /// `/sites/{name}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site: Option<String>,
+    pub status: JobStatus,
+    /// Queued jobs with a higher priority start first
+    #[serde(default)]
+    pub priority: i32,
+    pub source: JobSource,
+    pub submitted_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub error: Option<String>,
+    pub summary: Option<CrawlSummary>,
+    /// Archived JSON report, once the job has completed
+    pub report_path: Option<String>,
+}
+
+/// A finished crawl that found more errors or warnings than the previous completed crawl of
+/// the same URL, as POSTed to the site's `notify_url`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Regression {
+    pub url: String,
+    pub job_id: JobId,
+    pub previous_job_id: JobId,
+    pub errors: usize,
+    pub previous_errors: usize,
+    pub warnings: usize,
+    pub previous_warnings: usize,
+    pub report_path: Option<String>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    next_id: JobId,
+    pending: VecDeque<(JobId, Config)>,
+    jobs: BTreeMap<JobId, Job>,
+    /// Signals that stop running jobs when they are cancelled
+    running: HashMap<JobId, Arc<Notify>>,
+    /// Configs of the running jobs, saved so they can be queued again after a restart
+    running_configs: HashMap<JobId, Config>,
+}
+
+/// `QueueState` as saved to the queue file
+#[derive(Serialize, Deserialize)]
+struct SavedQueue<'a> {
+    next_id: JobId,
+    jobs: Cow<'a, BTreeMap<JobId, Job>>,
+    /// The configs of queued and running jobs, in the order they were submitted
+    pending: Vec<(JobId, Cow<'a, Config>)>,
+}
+
+/// Jobs waiting for a worker plus the history of every job seen by this process, or with a
+/// queue file by every run of the service
+#[derive(Default)]
+pub struct JobQueue {
+    state: Mutex<QueueState>,
+    job_available: Notify,
+    path: Option<PathBuf>,
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A queue saved to `path` on every change, starting with the jobs an earlier run saved
+    /// there. Jobs that were running when that run stopped are queued again.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut state = QueueState::default();
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                let saved: SavedQueue = serde_json::from_str(&contents)
+                    .with_context(|| format!("Failed to parse job queue: {}", path.display()))?;
+                state.next_id = saved.next_id;
+                state.jobs = saved.jobs.into_owned();
+                let mut pending = saved.pending;
+                pending.sort_by_key(|(id, _)| *id);
+                for (id, config) in pending {
+                    if let Some(job) = state.jobs.get_mut(&id)
+                        && matches!(job.status, JobStatus::Queued | JobStatus::Running)
+                    {
+                        job.status = JobStatus::Queued;
+                        job.started_at = None;
+                        state.pending.push_back((id, config.into_owned()));
+                    }
+                }
+                // Without a saved config a job can't run again
+                for job in state.jobs.values_mut() {
+                    if job.status == JobStatus::Running {
+                        job.status = JobStatus::Failed;
+                        job.finished_at = Some(now());
+                        job.error =
+                            Some("The service stopped while the job was running".to_string());
+                    }
+                }
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("Failed to read job queue: {}", path.display()));
+            }
+        }
+
+        Ok(Self {
+            state: Mutex::new(state),
+            job_available: Notify::new(),
+            path: Some(path),
+        })
+    }
+
+    /// Queue a crawl described by a config; the config must name the start URL
+    pub fn submit(&self, config: Config, source: JobSource) -> Result<Job> {
+        self.submit_for(None, config, source)
+    }
+
+    /// Queue a crawl of a site from the sites directory with its own config
+    pub fn submit_site(&self, site: Site, source: JobSource) -> Result<Job> {
+        self.submit_for(Some(site.name), site.config, source)
+    }
+
+    fn submit_for(&self, site: Option<String>, config: Config, source: JobSource) -> Result<Job> {
+        let url = config
+            .url
+            .clone()
+            .context("A job must include a url to crawl")?;
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            anyhow::bail!("URL must start with http:// or https://");
+        }
+
+        let job = {
+            let mut state = self.lock();
+            state.next_id += 1;
+            let job = Job {
+                id: state.next_id,
+                url,
+                site,
+                status: JobStatus::Queued,
+                priority: config.priority.unwrap_or_default(),
+                source,
+                submitted_at: now(),
+                started_at: None,
+                finished_at: None,
+                error: None,
+                summary: None,
+                report_path: None,
+            };
+            state.pending.push_back((job.id, config));
+            state.jobs.insert(job.id, job.clone());
+            self.save(&state);
+            job
+        };
+
+        self.job_available.notify_one();
+        Ok(job)
+    }
+
+    pub fn get(&self, id: JobId) -> Option<Job> {
+        self.lock().jobs.get(&id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<Job> {
+        self.lock().jobs.values().cloned().collect()
+    }
+
+    /// The jobs of one site, or with `None` the jobs submitted without a site
+    pub fn list_site(&self, site: Option<&str>) -> Vec<Job> {
+        self.lock()
+            .jobs
+            .values()
+            .filter(|job| job.site.as_deref() == site)
+            .cloned()
+            .collect()
+    }
+
+    /// Move a queued job ahead of or behind other queued jobs
+    pub fn set_priority(&self, id: JobId, priority: i32) -> Result<Job> {
+        let mut state = self.lock();
+        let job = state
+            .jobs
+            .get_mut(&id)
+            .with_context(|| format!("Job {id} not found"))?;
+        if job.status != JobStatus::Queued {
+            anyhow::bail!("Job {id} is no longer queued (status: {:?})", job.status);
+        }
+        job.priority = priority;
+        let job = job.clone();
+        self.save(&state);
+        Ok(job)
+    }
+
+    /// Cancel a job: a queued job never starts, and a running crawl is stopped without
+    /// archiving a report
+    pub fn cancel(&self, id: JobId) -> Result<Job> {
+        let mut state = self.lock();
+        let job = state
+            .jobs
+            .get_mut(&id)
+            .with_context(|| format!("Job {id} not found"))?;
+        if !matches!(job.status, JobStatus::Queued | JobStatus::Running) {
+            anyhow::bail!("Job {id} has already finished (status: {:?})", job.status);
+        }
+        job.status = JobStatus::Cancelled;
+        job.finished_at = Some(now());
+        let job = job.clone();
+
+        state.pending.retain(|(pending_id, _)| *pending_id != id);
+        state.running_configs.remove(&id);
+        if let Some(cancelled) = state.running.remove(&id) {
+            cancelled.notify_one();
+        }
+        self.save(&state);
+        Ok(job)
+    }
+
+    /// Wait for the next queued job and mark it as running
+    async fn next_job(&self) -> (JobId, Config, Arc<Notify>) {
+        loop {
+            let notified = self.job_available.notified();
+            if let Some(next) = self.take_pending() {
+                return next;
+            }
+            notified.await;
+        }
+    }
+
+    /// Take the queued job with the highest priority, the earliest submitted among equals
+    fn take_pending(&self) -> Option<(JobId, Config, Arc<Notify>)> {
+        let mut state = self.lock();
+        let state = &mut *state;
+        let priority = |id: &JobId| state.jobs.get(id).map_or(0, |job| job.priority);
+        let index = state
+            .pending
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, (id, _))| (priority(id), std::cmp::Reverse(*index)))?
+            .0;
+        let (id, config) = state.pending.remove(index)?;
+        if let Some(job) = state.jobs.get_mut(&id) {
+            job.status = JobStatus::Running;
+            job.started_at = Some(now());
+        }
+        let cancelled = Arc::new(Notify::new());
+        state.running.insert(id, cancelled.clone());
+        state.running_configs.insert(id, config.clone());
+        self.save(state);
+        // Another job may still be waiting for an idle worker
+        if !state.pending.is_empty() {
+            self.job_available.notify_one();
+        }
+        Some((id, config, cancelled))
+    }
+
+    fn finish(&self, id: JobId, outcome: Result<(CrawlSummary, PathBuf)>) {
+        let mut state = self.lock();
+        state.running.remove(&id);
+        state.running_configs.remove(&id);
+        let Some(job) = state.jobs.get_mut(&id) else {
+            return;
+        };
+        // A job cancelled as it finished stays cancelled
+        if job.status == JobStatus::Cancelled {
+            return;
+        }
+
+        job.finished_at = Some(now());
+        match outcome {
+            Ok((summary, report_path)) => {
+                job.status = JobStatus::Completed;
+                job.summary = Some(summary);
+                job.report_path = Some(report_path.display().to_string());
+            }
+            Err(error) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(format!("{error:#}"));
+            }
+        }
+        self.save(&state);
+    }
+
+    /// How a completed job compares with the previous completed job for the same URL and
+    /// site, if it found more errors or warnings
+    pub fn regression(&self, id: JobId) -> Option<Regression> {
+        let state = self.lock();
+        let job = state.jobs.get(&id)?;
+        let summary = job.summary.as_ref()?;
+        let (previous_job_id, previous) = state
+            .jobs
+            .range(..id)
+            .rev()
+            .find(|(_, previous)| {
+                previous.url == job.url
+                    && previous.site == job.site
+                    && previous.status == JobStatus::Completed
+            })
+            .and_then(|(previous_id, previous)| Some((*previous_id, previous.summary.as_ref()?)))?;
+
+        (summary.errors > previous.errors || summary.warnings > previous.warnings).then(|| {
+            Regression {
+                url: job.url.clone(),
+                job_id: id,
+                previous_job_id,
+                errors: summary.errors,
+                previous_errors: previous.errors,
+                warnings: summary.warnings,
+                previous_warnings: previous.warnings,
+                report_path: job.report_path.clone(),
+            }
+        })
+    }
+
+    /// Write the queue to its file, if it has one. A failed write is logged and the queue
+    /// carries on in memory; the next change tries again.
+    fn save(&self, state: &QueueState) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(error) = write_queue(path, state) {
+            tracing::warn!(path = %path.display(), error = %format!("{error:#}"), "Failed to save the job queue");
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Start the workers, the job directory watcher, and the HTTP API, then serve until stopped
+pub async fn run(options: DaemonOptions) -> Result<()> {
+    if options.api_tokens.is_empty() && !options.no_auth {
+        anyhow::bail!(
+            "The API has no api_tokens; list them in the config file, or pass --no-auth to serve it without authentication"
+        );
+    }
+    fs::create_dir_all(&options.archive_dir).with_context(|| {
+        format!(
+            "Failed to create archive directory: {}",
+            options.archive_dir.display()
+        )
+    })?;
+    let queue = Arc::new(JobQueue::open(options.archive_dir.join(QUEUE_FILE_NAME))?);
+
+    spawn_workers(queue.clone(), options.workers, options.archive_dir.clone());
+
+    if let Some(sites_dir) = options.sites_dir.clone() {
+        tokio::spawn(schedule::run_schedules(
+            queue.clone(),
+            sites_dir,
+            options.poll_interval,
+        ));
+    }
+
+    if let Some(jobs_dir) = options.jobs_dir.clone() {
+        watch::prepare_jobs_dir(&jobs_dir)?;
+        tokio::spawn(watch::watch_jobs_dir(
+            queue.clone(),
+            jobs_dir,
+            options.poll_interval,
+        ));
+    }
+
+    let listener = tokio::net::TcpListener::bind(options.listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", options.listen))?;
+    eprintln!("Scoutly daemon listening on http://{}", options.listen);
+    if options.api_tokens.is_empty() && !options.listen.ip().is_loopback() {
+        tracing::warn!(
+            listen = %options.listen,
+            "The API is reachable from other hosts without a token (--no-auth)"
+        );
+    }
+
+    let mut api_state = api::ApiState::new(queue);
+    api_state.sites_dir = options.sites_dir.clone();
+    api_state.tokens = options.api_tokens.clone();
+    axum::serve(listener, api::router(api_state))
+        .await
+        .context("HTTP server failed")
+}
+
+/// Run jobs on a bounded pool of worker threads. Each worker owns a single-threaded runtime
+/// because a scan future holds data that can't move between threads.
+pub fn spawn_workers(
+    queue: Arc<JobQueue>,
+    workers: usize,
+    archive_dir: PathBuf,
+) -> Vec<JoinHandle<()>> {
+    (0..workers.max(1))
+        .map(|index| {
+            let queue = queue.clone();
+            let archive_dir = archive_dir.clone();
+            std::thread::Builder::new()
+                .name(format!("scoutly-worker-{index}"))
+                .spawn(move || {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .expect("Failed to build worker runtime");
+                    runtime.block_on(worker_loop(queue, archive_dir));
+                })
+                .expect("Failed to spawn worker thread")
+        })
+        .collect()
+}
+
+async fn worker_loop(queue: Arc<JobQueue>, archive_dir: PathBuf) {
+    loop {
+        let (id, config, cancelled) = queue.next_job().await;
+        tracing::info!(job = id, "Starting job");
+        // Each site's reports are archived in a directory of their own
+        let job_archive_dir = match queue.get(id).and_then(|job| job.site) {
+            Some(site) => archive_dir.join(site),
+            None => archive_dir.clone(),
+        };
+        // Dropping the crawl future stops it at its next request
+        let outcome = tokio::select! {
+            outcome = run_job(id, &config, &job_archive_dir) => outcome,
+            () = cancelled.notified() => {
+                tracing::info!(job = id, "Job cancelled");
+                continue;
+            }
+        };
+        if let Err(error) = &outcome {
+            tracing::warn!(job = id, error = %error, "Job failed");
+        }
+        queue.finish(id, outcome);
+
+        if let Some(notify_url) = &config.notify_url
+            && let Some(regression) = queue.regression(id)
+        {
+            notify_regression(notify_url, &regression).await;
+        }
+    }
+}
+
+/// POST a regression to the site's webhook; failures are logged rather than failing the job
+async fn notify_regression(notify_url: &str, regression: &Regression) {
+    let sent = reqwest::Client::new()
+        .post(notify_url)
+        .timeout(NOTIFY_TIMEOUT)
+        .json(regression)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    match sent {
+        Ok(_) => {
+            tracing::info!(job = regression.job_id, url = %notify_url, "Sent regression notification")
+        }
+        Err(error) => {
+            tracing::warn!(job = regression.job_id, url = %notify_url, error = %error, "Failed to send regression notification")
+        }
+    }
+}
+
+async fn run_job(
+    id: JobId,
+    config: &Config,
+    archive_dir: &Path,
+) -> Result<(CrawlSummary, PathBuf)> {
+    let runtime = config.resolve_runtime_options(&Cli::default());
+    let report = execute_scan(&runtime, None, false).await?;
+    record_trend(&report, &report.pages, &runtime)?;
+
+    let report_path = archive_report(id, &report, archive_dir)?;
+    Ok((report.summary, report_path))
+}
+
+fn archive_report(id: JobId, report: &CrawlReport, archive_dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(archive_dir).with_context(|| {
+        format!(
+            "Failed to create archive directory: {}",
+            archive_dir.display()
+        )
+    })?;
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+    let path = archive_dir.join(format!("{timestamp}-job-{id}.json"));
+    let json = serde_json::to_string_pretty(report)?;
+    fs::write(&path, json)
+        .with_context(|| format!("Failed to archive report: {}", path.display()))?;
+    Ok(path)
+}
+
+/// Replace the queue file through a temporary file, so a crash mid-write leaves the last
+/// complete queue behind
+fn write_queue(path: &Path, state: &QueueState) -> Result<()> {
+    let mut pending: Vec<(JobId, Cow<Config>)> = state
+        .pending
+        .iter()
+        .map(|(id, config)| (id, config))
+        .chain(&state.running_configs)
+        .map(|(id, config)| (*id, Cow::Borrowed(config)))
+        .collect();
+    pending.sort_by_key(|(id, _)| *id);
+    let saved = SavedQueue {
+        next_id: state.next_id,
+        jobs: Cow::Borrowed(&state.jobs),
+        pending,
+    };
+
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, serde_json::to_string(&saved)?)
+        .with_context(|| format!("Failed to write job queue: {}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .with_context(|| format!("Failed to replace job queue: {}", path.display()))
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
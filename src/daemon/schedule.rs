@@ -0,0 +1,369 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, NaiveTime, TimeDelta, Timelike, Utc};
+
+use super::sites::{self, Site};
+use super::{JobQueue, JobSource};
+
+/// How far ahead `CronSchedule::next_after` looks; four years covers schedules that only
+/// fire on February 29
+const MAX_LOOKAHEAD_DAYS: i64 = 4 * 366;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A five-field cron expression (minute, hour, day of month, month, day of week) evaluated in
+/// UTC. Fields take `*`, values, ranges, lists, and steps (`*/15`, `1-5`, `mon,wed,fri`), and
+/// the `@hourly`, `@daily`, `@weekly`, `@monthly`, and `@yearly` shorthands are accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: BTreeSet<u32>,
+    hours: BTreeSet<u32>,
+    days_of_month: BTreeSet<u32>,
+    months: BTreeSet<u32>,
+    /// 0 is Sunday; 7 is accepted for Sunday too
+    days_of_week: BTreeSet<u32>,
+    /// As in classic cron, when both day fields are restricted a day matching either one fires
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = anyhow::Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        let expression = match expression.trim().to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            other => other.to_string(),
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            bail!(
+                "Invalid cron expression '{expression}': expected 5 fields (minute hour day-of-month month day-of-week)"
+            );
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7, &WEEKDAY_NAMES, "day of week")?;
+        if days_of_week.remove(&7) {
+            days_of_week.insert(0);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[], "minute")?,
+            hours: parse_field(hour, 0, 23, &[], "hour")?,
+            days_of_month: parse_field(day_of_month, 1, 31, &[], "day of month")?,
+            months: parse_field(month, 1, 12, &MONTH_NAMES, "month")?,
+            days_of_week,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        })
+    }
+}
+
+impl CronSchedule {
+    /// The first minute strictly after `after` that the schedule fires on, or `None` when it
+    /// never fires (e.g. `0 0 31 2 *`)
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        let limit = after + TimeDelta::days(MAX_LOOKAHEAD_DAYS);
+
+        while time <= limit {
+            if !self.months.contains(&time.month()) || !self.day_matches(time) {
+                let next_day = time.date_naive().succ_opt()?;
+                time = next_day.and_time(NaiveTime::MIN).and_utc();
+            } else if !self.hours.contains(&time.hour()) {
+                time = time.with_minute(0)? + TimeDelta::hours(1);
+            } else if !self.minutes.contains(&time.minute()) {
+                time += TimeDelta::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month = self.days_of_month.contains(&time.day());
+        let day_of_week = self
+            .days_of_week
+            .contains(&time.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_of_month || day_of_week,
+            (false, true) => day_of_month,
+            (true, false) => day_of_week,
+            (true, true) => true,
+        }
+    }
+}
+
+/// The values one cron field allows, from a comma-separated list of `*`, `N`, `N-M`, each
+/// optionally followed by `/STEP`. `names` spell out the values from `min` onwards.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    label: &str,
+) -> Result<BTreeSet<u32>> {
+    let value = |text: &str| -> Result<u32> {
+        let value = match names.iter().position(|name| *name == text) {
+            Some(index) => min + index as u32,
+            None => text
+                .parse()
+                .with_context(|| format!("Invalid {label} '{text}' in cron expression"))?,
+        };
+        if !(min..=max).contains(&value) {
+            bail!("Cron {label} {value} is outside {min}-{max}");
+        }
+        Ok(value)
+    };
+
+    let mut values = BTreeSet::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .with_context(|| format!("Invalid {label} step '{step}' in cron expression"))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            // `N/STEP` runs from N to the end of the field
+            None if step > 1 => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if start > end {
+            bail!("Invalid {label} range '{range}' in cron expression");
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    Ok(values)
+}
+
+struct ScheduledSite {
+    schedule: String,
+    cron: CronSchedule,
+    next_run: DateTime<Utc>,
+}
+
+/// Crawls the sites in the sites directory that have a cron `schedule`. Files are re-read on
+/// every tick, so edits, new files, and deletions take effect without restarting the service.
+#[derive(Default)]
+pub struct Scheduler {
+    sites: BTreeMap<PathBuf, ScheduledSite>,
+    /// The last error for each file that couldn't be scheduled, so it is logged once
+    rejected: BTreeMap<PathBuf, String>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Re-read the site files and queue a job for each site whose next run time has passed.
+    /// A new site or a changed schedule starts counting from `now`, so it first runs at the
+    /// schedule's next time rather than immediately. Returns the number of jobs queued.
+    pub fn tick(
+        &mut self,
+        queue: &JobQueue,
+        sites_dir: &Path,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let site_files = sites::site_files(sites_dir)?;
+        self.sites.retain(|path, _| site_files.contains(path));
+        self.rejected.retain(|path, _| site_files.contains(path));
+
+        let mut queued = 0;
+        for path in site_files {
+            let (site, cron) = match Self::load_scheduled_site(&path, now) {
+                Ok(Some(site)) => site,
+                // Sites without a schedule are only crawled when asked through the API
+                Ok(None) => {
+                    self.sites.remove(&path);
+                    self.rejected.remove(&path);
+                    continue;
+                }
+                Err(error) => {
+                    let error = format!("{error:#}");
+                    if self.rejected.get(&path) != Some(&error) {
+                        tracing::warn!(file = %path.display(), error = %error, "Skipping scheduled site");
+                        self.rejected.insert(path.clone(), error);
+                    }
+                    self.sites.remove(&path);
+                    continue;
+                }
+            };
+            self.rejected.remove(&path);
+
+            let schedule = site.config.schedule.clone().unwrap_or_default();
+            if self
+                .sites
+                .get(&path)
+                .is_none_or(|scheduled| scheduled.schedule != schedule)
+            {
+                // `load_scheduled_site` rejects schedules that never fire
+                let Some(next_run) = cron.next_after(now) else {
+                    continue;
+                };
+                tracing::info!(site = %site.name, next_run = %next_run, "Scheduled site");
+                self.sites.insert(
+                    path.clone(),
+                    ScheduledSite {
+                        schedule,
+                        cron,
+                        next_run,
+                    },
+                );
+            }
+            let Some(scheduled) = self.sites.get_mut(&path) else {
+                continue;
+            };
+            if now < scheduled.next_run {
+                continue;
+            }
+
+            let name = site.name.clone();
+            match queue.submit_site(site, JobSource::Schedule(path.display().to_string())) {
+                Ok(job) => {
+                    tracing::info!(job = job.id, site = %name, "Queued scheduled crawl");
+                    queued += 1;
+                }
+                Err(error) => {
+                    tracing::warn!(site = %name, error = %error, "Failed to queue scheduled crawl");
+                }
+            }
+            match scheduled.cron.next_after(now) {
+                Some(next_run) => scheduled.next_run = next_run,
+                None => {
+                    self.sites.remove(&path);
+                }
+            }
+        }
+
+        Ok(queued)
+    }
+
+    /// The time each scheduled site runs next, keyed by its file
+    pub fn next_runs(&self) -> BTreeMap<PathBuf, DateTime<Utc>> {
+        self.sites
+            .iter()
+            .map(|(path, site)| (path.clone(), site.next_run))
+            .collect()
+    }
+
+    /// The site in `path` and its parsed schedule, or `None` when it has no schedule
+    fn load_scheduled_site(
+        path: &Path,
+        now: DateTime<Utc>,
+    ) -> Result<Option<(Site, CronSchedule)>> {
+        let site = sites::load_site(path)?;
+        let Some(schedule) = site.config.schedule.as_deref() else {
+            return Ok(None);
+        };
+        let cron: CronSchedule = schedule.parse()?;
+        if cron.next_after(now).is_none() {
+            bail!("Schedule '{schedule}' never fires");
+        }
+        Ok(Some((site, cron)))
+    }
+}
+
+/// Check the sites directory on every poll interval and queue the crawls that are due
+pub async fn run_schedules(queue: Arc<JobQueue>, sites_dir: PathBuf, poll_interval: Duration) {
+    let mut scheduler = Scheduler::new();
+    let mut interval = tokio::time::interval(poll_interval);
+    loop {
+        interval.tick().await;
+        if let Err(error) = scheduler.tick(&queue, &sites_dir, Utc::now()) {
+            tracing::warn!(dir = %sites_dir.display(), error = %error, "Failed to scan sites directory");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_cron_fields() {
+        let schedule: CronSchedule = "*/15 9-17 * jan,jul mon-fri".parse().unwrap();
+        assert_eq!(
+            schedule.minutes.iter().copied().collect::<Vec<_>>(),
+            [0, 15, 30, 45]
+        );
+        assert_eq!(schedule.hours.len(), 9);
+        assert_eq!(schedule.months.iter().copied().collect::<Vec<_>>(), [1, 7]);
+        assert_eq!(
+            schedule.days_of_week.iter().copied().collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5]
+        );
+
+        let sunday: CronSchedule = "0 0 * * 7".parse().unwrap();
+        assert_eq!(sunday, "@weekly".parse().unwrap());
+
+        for invalid in [
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(
+                invalid.parse::<CronSchedule>().is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_after_finds_the_next_matching_minute() {
+        let daily: CronSchedule = "30 3 * * *".parse().unwrap();
+        assert_eq!(
+            daily.next_after(at("2026-03-10T03:30:00Z")),
+            Some(at("2026-03-11T03:30:00Z"))
+        );
+        assert_eq!(
+            daily.next_after(at("2026-03-10T01:12:45Z")),
+            Some(at("2026-03-10T03:30:00Z"))
+        );
+
+        // Either day field matches when both are restricted: the 1st, or any Monday
+        let either: CronSchedule = "0 0 1 * mon".parse().unwrap();
+        assert_eq!(
+            either.next_after(at("2026-03-10T00:00:00Z")),
+            Some(at("2026-03-16T00:00:00Z"))
+        );
+
+        let leap_day: CronSchedule = "0 12 29 2 *".parse().unwrap();
+        assert_eq!(
+            leap_day.next_after(at("2026-03-01T00:00:00Z")),
+            Some(at("2028-02-29T12:00:00Z"))
+        );
+
+        let never: CronSchedule = "0 0 31 2 *".parse().unwrap();
+        assert_eq!(never.next_after(at("2026-03-01T00:00:00Z")), None);
+    }
+}
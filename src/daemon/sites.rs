@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::api::tokens_match;
+use crate::config::{Config, ConfigFormat};
+
+/// A site config from the sites directory, addressed in the API by its file name without the
+/// extension
+#[derive(Debug, Clone)]
+pub struct Site {
+    pub name: String,
+    pub path: PathBuf,
+    pub config: Config,
+}
+
+/// What the API shows about a site; its token and crawl settings stay private
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SiteInfo {
+    pub name: String,
+    pub url: String,
+    pub schedule: Option<String>,
+}
+
+impl Site {
+    pub fn info(&self) -> SiteInfo {
+        SiteInfo {
+            name: self.name.clone(),
+            url: self.config.url.clone().unwrap_or_default(),
+            schedule: self.config.schedule.clone(),
+        }
+    }
+
+    /// Whether a request's bearer token may use this site's routes. Sites without an
+    /// `api_token` are open to anyone who can reach the service.
+    pub fn authorizes(&self, bearer_token: Option<&str>) -> bool {
+        match &self.config.api_token {
+            Some(token) => bearer_token.is_some_and(|bearer| tokens_match(token, bearer)),
+            None => true,
+        }
+    }
+}
+
+/// The site name for a config file: its file stem, limited to letters, digits, `-`, and `_`
+/// so it can be used in URLs and as an archive directory
+pub fn site_name(path: &Path) -> Option<String> {
+    ConfigFormat::from_path(path)?;
+    let name = path.file_stem()?.to_str()?;
+    name.chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        .then(|| name.to_string())
+}
+
+/// Config files in the sites directory that have a valid site name, in name order
+pub fn site_files(sites_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(sites_dir)
+        .with_context(|| format!("Failed to read sites directory: {}", sites_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && site_name(path).is_some())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Read one site file; a site must name the URL to crawl
+pub fn load_site(path: &Path) -> Result<Site> {
+    let name = site_name(path).context("Invalid site file name")?;
+    let config = Config::from_file(path)?;
+    if config.url.is_none() {
+        bail!("A site must include a url to crawl");
+    }
+    Ok(Site {
+        name,
+        path: path.to_path_buf(),
+        config,
+    })
+}
+
+/// Every site that loads, skipping and logging files that don't
+pub fn list_sites(sites_dir: &Path) -> Result<Vec<Site>> {
+    Ok(site_files(sites_dir)?
+        .iter()
+        .filter_map(|path| {
+            load_site(path)
+                .inspect_err(|error| {
+                    tracing::warn!(file = %path.display(), error = %format!("{error:#}"), "Skipping site file");
+                })
+                .ok()
+        })
+        .collect())
+}
+
+/// The site called `name`, if its file exists and loads
+pub fn find_site(sites_dir: &Path, name: &str) -> Result<Option<Site>> {
+    Ok(list_sites(sites_dir)?
+        .into_iter()
+        .find(|site| site.name == name))
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use super::{JobQueue, JobSource};
+use crate::config::{Config, ConfigFormat};
+
+const ACCEPTED_DIR: &str = "accepted";
+const REJECTED_DIR: &str = "rejected";
+
+/// Create the job directory and the subdirectories processed job files are moved into
+pub fn prepare_jobs_dir(jobs_dir: &Path) -> Result<()> {
+    for dir in [
+        jobs_dir.to_path_buf(),
+        jobs_dir.join(ACCEPTED_DIR),
+        jobs_dir.join(REJECTED_DIR),
+    ] {
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create job directory: {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+/// Poll the job directory and queue every config file dropped into it
+pub async fn watch_jobs_dir(queue: Arc<JobQueue>, jobs_dir: PathBuf, poll_interval: Duration) {
+    let mut interval = tokio::time::interval(poll_interval);
+    loop {
+        interval.tick().await;
+        if let Err(error) = scan_jobs_dir(&queue, &jobs_dir) {
+            tracing::warn!(dir = %jobs_dir.display(), error = %error, "Failed to scan job directory");
+        }
+    }
+}
+
+/// Queue the job files currently in the directory. Accepted files are moved to `accepted/`
+/// and files that can't be parsed or queued to `rejected/`, so each file is handled once.
+pub fn scan_jobs_dir(queue: &JobQueue, jobs_dir: &Path) -> Result<usize> {
+    let mut job_files: Vec<PathBuf> = fs::read_dir(jobs_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && ConfigFormat::from_path(path).is_some())
+        .collect();
+    job_files.sort();
+
+    let mut queued = 0;
+    for path in job_files {
+        let Some(file_name) = path.file_name().map(|name| name.to_owned()) else {
+            continue;
+        };
+
+        let submitted = Config::from_file(&path)
+            .and_then(|config| queue.submit(config, JobSource::File(path.display().to_string())));
+        let destination = match &submitted {
+            Ok(job) => {
+                tracing::info!(job = job.id, file = %path.display(), "Queued job file");
+                queued += 1;
+                jobs_dir.join(ACCEPTED_DIR)
+            }
+            Err(error) => {
+                tracing::warn!(file = %path.display(), error = %error, "Rejected job file");
+                jobs_dir.join(REJECTED_DIR)
+            }
+        };
+
+        fs::rename(&path, destination.join(&file_name))
+            .with_context(|| format!("Failed to move job file: {}", path.display()))?;
+    }
+
+    Ok(queued)
+}
@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::baseline::Baseline;
+use crate::models::{CrawlReport, IssueSeverity, IssueType, SeoIssue, normalize_fingerprint_url};
+
+/// Issues that compare a page with the rest of the site, which a recrawl of a few pages can't judge
+const SITE_WIDE_ISSUES: [IssueType; 2] = [IssueType::OrphanPage, IssueType::CrossHostDuplicate];
+
+/// An issue from an earlier scan that is expected to have been fixed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListedIssue {
+    pub page_url: String,
+    pub issue_type: IssueType,
+    /// The link or resource the issue is about, e.g. the target of a broken link
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offending_url: Option<String>,
+    /// Computed from the other fields when the list doesn't include it
+    #[serde(default)]
+    pub fingerprint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ListedIssue {
+    fn from_issue(page_url: &str, issue: &SeoIssue) -> Self {
+        Self {
+            page_url: page_url.to_string(),
+            issue_type: issue.issue_type,
+            offending_url: issue.offending_url.clone(),
+            fingerprint: issue
+                .fingerprint
+                .clone()
+                .unwrap_or_else(|| issue.compute_fingerprint(page_url)),
+            message: Some(issue.message.clone()),
+        }
+    }
+
+    fn fill_fingerprint(&mut self) {
+        if self.fingerprint.is_empty() {
+            let mut issue = SeoIssue::new(IssueSeverity::Info, self.issue_type, "");
+            issue.offending_url = self.offending_url.clone();
+            self.fingerprint = issue.compute_fingerprint(&self.page_url);
+        }
+    }
+}
+
+/// Read the issues to verify from a saved JSON report (every issue on every page), a baseline
+/// file, a verify-fixes JSON report or a JSON array like its `checks`, or a CSV of
+/// `page_url,issue_type[,offending_url]` rows whose header row is optional
+pub fn read_issue_list(path: &Path) -> Result<Vec<ListedIssue>> {
+    let is_csv = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+    let mut issues = if is_csv {
+        read_csv(path)?
+    } else {
+        read_json(path)?
+    };
+    for issue in &mut issues {
+        issue.fill_fingerprint();
+    }
+    if issues.is_empty() {
+        anyhow::bail!("Issue list {} lists no issues", path.display());
+    }
+    Ok(issues)
+}
+
+fn read_json(path: &Path) -> Result<Vec<ListedIssue>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read issue list: {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse issue list: {}", path.display()))?;
+
+    if value.get("pages").is_some() {
+        let report: CrawlReport = serde_json::from_value(value)
+            .with_context(|| format!("Failed to parse report: {}", path.display()))?;
+        let mut issues: Vec<ListedIssue> = report
+            .pages
+            .iter()
+            .flat_map(|(page_url, page)| {
+                page.issues
+                    .iter()
+                    .map(|issue| ListedIssue::from_issue(page_url, issue))
+            })
+            .collect();
+        issues.sort_by(|a, b| (&a.page_url, &a.fingerprint).cmp(&(&b.page_url, &b.fingerprint)));
+        return Ok(issues);
+    }
+    if value.get("issues").is_some() {
+        // Baselines are versioned, so go through their own loader
+        let baseline = Baseline::from_file(path)?;
+        return Ok(baseline
+            .issues
+            .into_iter()
+            .map(|entry| ListedIssue {
+                page_url: entry.page_url,
+                issue_type: entry.issue_type,
+                offending_url: None,
+                fingerprint: entry.fingerprint,
+                message: Some(entry.message),
+            })
+            .collect());
+    }
+
+    // A verify-fixes report lists its issues under `checks`
+    let value = match value.get("checks") {
+        Some(checks) => checks.clone(),
+        None => value,
+    };
+    serde_json::from_value(value)
+        .with_context(|| format!("Failed to parse issue list: {}", path.display()))
+}
+
+fn read_csv(path: &Path) -> Result<Vec<ListedIssue>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_path(path)
+        .with_context(|| format!("Failed to read issue list: {}", path.display()))?;
+
+    let mut issues = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record =
+            record.with_context(|| format!("Failed to parse issue list: {}", path.display()))?;
+        let (Some(page_url), Some(issue_type)) = (record.get(0), record.get(1)) else {
+            anyhow::bail!(
+                "Line {} of {} must have a page URL and an issue type",
+                index + 1,
+                path.display()
+            );
+        };
+        if url::Url::parse(page_url).is_err() {
+            if index == 0 {
+                continue;
+            }
+            anyhow::bail!(
+                "Line {} of {} has an invalid page URL: {page_url}",
+                index + 1,
+                path.display()
+            );
+        }
+        let issue_type: IssueType =
+            serde_json::from_value(serde_json::Value::String(issue_type.to_string()))
+                .with_context(|| {
+                    format!(
+                        "Line {} of {} has an unknown issue type: {issue_type}",
+                        index + 1,
+                        path.display()
+                    )
+                })?;
+
+        issues.push(ListedIssue {
+            page_url: page_url.to_string(),
+            issue_type,
+            offending_url: record
+                .get(2)
+                .filter(|offending_url| !offending_url.is_empty())
+                .map(str::to_string),
+            fingerprint: String::new(),
+            message: None,
+        });
+    }
+
+    Ok(issues)
+}
+
+/// Page URLs to recrawl, in the order the list first names them
+pub fn pages_to_recrawl(issues: &[ListedIssue]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    issues
+        .iter()
+        .filter(|issue| seen.insert(normalize_fingerprint_url(&issue.page_url)))
+        .map(|issue| issue.page_url.clone())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixStatus {
+    /// The page was fetched again and no longer has the issue
+    Resolved,
+    /// The page still has the issue
+    Persists,
+    /// The page couldn't be fetched or returned an error, or the issue needs a full crawl to judge
+    Unverified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixCheck {
+    #[serde(flatten)]
+    pub issue: ListedIssue,
+    pub status: FixStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixReport {
+    pub total: usize,
+    pub resolved: usize,
+    pub persisting: usize,
+    pub unverified: usize,
+    pub checks: Vec<FixCheck>,
+}
+
+impl FixReport {
+    /// Look up each listed issue in a fresh scan of its page; checks keep the list's order
+    pub fn new(issues: Vec<ListedIssue>, fresh: &CrawlReport) -> Self {
+        let fresh_fingerprints: HashSet<&str> = fresh
+            .pages
+            .values()
+            .flat_map(|page| &page.issues)
+            .filter_map(|issue| issue.fingerprint.as_deref())
+            .collect();
+        let fetched_pages: HashSet<String> = fresh
+            .pages
+            .iter()
+            // A page that now fails to load has nothing left to check
+            .filter(|(_, page)| page.status_code.is_some_and(|code| code < 400))
+            .flat_map(|(key, page)| {
+                [
+                    normalize_fingerprint_url(key),
+                    normalize_fingerprint_url(&page.url),
+                ]
+            })
+            .collect();
+
+        let checks: Vec<FixCheck> = issues
+            .into_iter()
+            .map(|issue| {
+                let status = if fresh_fingerprints.contains(issue.fingerprint.as_str()) {
+                    FixStatus::Persists
+                } else if SITE_WIDE_ISSUES.contains(&issue.issue_type)
+                    || !fetched_pages.contains(&normalize_fingerprint_url(&issue.page_url))
+                {
+                    FixStatus::Unverified
+                } else {
+                    FixStatus::Resolved
+                };
+                FixCheck { issue, status }
+            })
+            .collect();
+        let count = |status| checks.iter().filter(|check| check.status == status).count();
+
+        Self {
+            total: checks.len(),
+            resolved: count(FixStatus::Resolved),
+            persisting: count(FixStatus::Persists),
+            unverified: count(FixStatus::Unverified),
+            checks,
+        }
+    }
+
+    pub fn print_text_report(&self) {
+        println!("\n{}", "=".repeat(80).bright_blue());
+        println!("{}", "FIX VERIFICATION".bright_cyan().bold());
+        println!("{}", "=".repeat(80).bright_blue());
+        println!("{} {}", "Issues checked:".bright_white().bold(), self.total);
+        println!(
+            "{} {}",
+            "Resolved:".bright_white().bold(),
+            self.resolved.to_string().bright_green()
+        );
+        println!(
+            "{} {}",
+            "Persisting:".bright_white().bold(),
+            self.persisting.to_string().bright_red()
+        );
+        println!(
+            "{} {}",
+            "Unverified:".bright_white().bold(),
+            self.unverified.to_string().bright_yellow()
+        );
+
+        for check in &self.checks {
+            let marker = match check.status {
+                FixStatus::Resolved => "✓".bright_green(),
+                FixStatus::Persists => "✗".bright_red(),
+                FixStatus::Unverified => "?".bright_yellow(),
+            };
+            println!(
+                "\n{} {:?} {}",
+                marker,
+                check.issue.issue_type,
+                check.issue.page_url.bright_white().bold()
+            );
+            if let Some(offending_url) = &check.issue.offending_url {
+                println!("    {} {}", "URL:".bold(), offending_url);
+            }
+            if let Some(message) = &check.issue.message {
+                println!("    {} {}", "Issue:".bold(), message);
+            }
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use colored::*;
+use reqwest::{Client, Response, header};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use url::Url;
+
+use crate::http_client::{TlsOptions, build_health_client};
+
+/// Response headers shown in the report, when the page sends them
+const KEY_HEADERS: [header::HeaderName; 4] = [
+    header::CONTENT_TYPE,
+    header::CACHE_CONTROL,
+    header::STRICT_TRANSPORT_SECURITY,
+    header::HeaderName::from_static("x-robots-tag"),
+];
+
+/// Limits a page must stay within to pass its health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthThresholds {
+    /// Slowest acceptable time to first byte, redirects included
+    pub max_ttfb: Duration,
+    /// Fewest days the TLS certificate must remain valid
+    pub min_tls_days: i64,
+}
+
+/// One part of a health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCheckKind {
+    /// The page answered with a 2xx status after any redirects
+    Status,
+    /// The response headers arrived within `max_ttfb`
+    Ttfb,
+    /// The certificate of an HTTPS page is valid for at least `min_tls_days` more days
+    Tls,
+    /// The page declares a Content-Type and no X-Robots-Tag keeps it out of search results
+    Headers,
+    /// The site's robots.txt answered without a server error
+    Robots,
+}
+
+impl HealthCheckKind {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Ttfb => "ttfb",
+            Self::Tls => "tls",
+            Self::Headers => "headers",
+            Self::Robots => "robots",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthCheck {
+    pub kind: HealthCheckKind,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// The outcome of `probe`, with what was measured alongside each check
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttfb_ms: Option<u64>,
+    /// When the certificate expires, in RFC 3339
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_expires_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_days_left: Option<i64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub robots_txt_status: Option<u16>,
+    pub checks: Vec<HealthCheck>,
+    pub passed: bool,
+}
+
+impl HealthReport {
+    fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            final_url: None,
+            status_code: None,
+            ttfb_ms: None,
+            tls_expires_at: None,
+            tls_days_left: None,
+            headers: BTreeMap::new(),
+            robots_txt_status: None,
+            checks: Vec::new(),
+            passed: true,
+        }
+    }
+
+    fn check(&mut self, kind: HealthCheckKind, passed: bool, detail: impl Into<String>) {
+        self.passed &= passed;
+        self.checks.push(HealthCheck {
+            kind,
+            passed,
+            detail: detail.into(),
+        });
+    }
+
+    /// The checks that failed, by label, e.g. for an error message
+    pub fn failed_checks(&self) -> Vec<&'static str> {
+        self.checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.kind.label())
+            .collect()
+    }
+
+    /// One line for the result and one per check, short enough to read in a cron mail
+    pub fn print_text_report(&self) {
+        let verdict = if self.passed {
+            "PASS".bright_green().bold()
+        } else {
+            "FAIL".bright_red().bold()
+        };
+        println!("{verdict} {}", self.url.bright_white().bold());
+        for check in &self.checks {
+            let mark = if check.passed {
+                "✓".bright_green()
+            } else {
+                "✗".bright_red()
+            };
+            println!("  {mark} {:<8} {}", check.kind.label(), check.detail);
+        }
+    }
+}
+
+/// Fetch `url` once and check its status, time to first byte, certificate expiry, and
+/// key headers, then fetch the site's robots.txt to check it is reachable. A page that
+/// can't be fetched fails the status check; only invalid input is an `Err`.
+pub async fn probe(
+    url: &str,
+    timeout_secs: u64,
+    tls: &TlsOptions,
+    thresholds: &HealthThresholds,
+) -> Result<HealthReport> {
+    let parsed = Url::parse(url).with_context(|| format!("Invalid URL: {url}"))?;
+    let client = build_health_client(timeout_secs, tls)?;
+    let mut report = HealthReport::new(url);
+
+    let started = Instant::now();
+    // `send` returns once the status line and headers arrive, before the body is read
+    let response = match client.get(parsed.as_str()).send().await {
+        Ok(response) => response,
+        Err(error) => {
+            report.check(HealthCheckKind::Status, false, error.to_string());
+            return Ok(report);
+        }
+    };
+    let ttfb = started.elapsed();
+
+    let status = response.status();
+    report.final_url = Some(response.url().to_string());
+    report.status_code = Some(status.as_u16());
+    report.check(
+        HealthCheckKind::Status,
+        status.is_success(),
+        status.to_string(),
+    );
+
+    report.ttfb_ms = Some(ttfb.as_millis() as u64);
+    report.check(
+        HealthCheckKind::Ttfb,
+        ttfb <= thresholds.max_ttfb,
+        format!(
+            "{} ms (limit {} ms)",
+            ttfb.as_millis(),
+            thresholds.max_ttfb.as_millis()
+        ),
+    );
+
+    if response.url().scheme() == "https" {
+        check_certificate(&mut report, &response, thresholds.min_tls_days);
+    }
+    check_headers(&mut report, &response);
+
+    let robots_url = response.url().join("/robots.txt")?;
+    check_robots_txt(&mut report, &client, &robots_url).await;
+
+    Ok(report)
+}
+
+fn check_certificate(report: &mut HealthReport, response: &Response, min_days: i64) {
+    let not_after = response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .and_then(certificate_not_after);
+    let Some(not_after) = not_after else {
+        report.check(
+            HealthCheckKind::Tls,
+            false,
+            "certificate expiry could not be read",
+        );
+        return;
+    };
+
+    let days_left = (not_after - Utc::now()).num_days();
+    report.tls_expires_at = Some(not_after.to_rfc3339());
+    report.tls_days_left = Some(days_left);
+    report.check(
+        HealthCheckKind::Tls,
+        days_left >= min_days,
+        format!(
+            "expires in {days_left} day(s) on {}",
+            not_after.format("%Y-%m-%d")
+        ),
+    );
+}
+
+fn check_headers(report: &mut HealthReport, response: &Response) {
+    for name in &KEY_HEADERS {
+        let values: Vec<&str> = response
+            .headers()
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect();
+        if !values.is_empty() {
+            report
+                .headers
+                .insert(name.as_str().to_string(), values.join(", "));
+        }
+    }
+
+    let mut problems = Vec::new();
+    if !report.headers.contains_key(header::CONTENT_TYPE.as_str()) {
+        problems.push("no content-type".to_string());
+    }
+    if let Some(robots_tag) = report.headers.get("x-robots-tag") {
+        let lowercase = robots_tag.to_lowercase();
+        if lowercase.contains("noindex") || lowercase.contains("none") {
+            problems.push(format!("x-robots-tag {robots_tag}"));
+        }
+    }
+
+    let detail = if problems.is_empty() {
+        report
+            .headers
+            .get(header::CONTENT_TYPE.as_str())
+            .cloned()
+            .unwrap_or_default()
+    } else {
+        problems.join(", ")
+    };
+    report.check(HealthCheckKind::Headers, problems.is_empty(), detail);
+}
+
+/// A missing robots.txt is fine, since crawlers then treat everything as allowed, but a
+/// server error makes them stop crawling the site
+async fn check_robots_txt(report: &mut HealthReport, client: &Client, robots_url: &Url) {
+    match client.get(robots_url.as_str()).send().await {
+        Ok(response) => {
+            let status = response.status();
+            report.robots_txt_status = Some(status.as_u16());
+            report.check(
+                HealthCheckKind::Robots,
+                !status.is_server_error(),
+                format!("robots.txt {status}"),
+            );
+        }
+        Err(error) => report.check(
+            HealthCheckKind::Robots,
+            false,
+            format!("robots.txt {error}"),
+        ),
+    }
+}
+
+/// When the DER-encoded X.509 certificate `der` stops being valid, read from the
+/// `notAfter` of its validity
+fn certificate_not_after(der: &[u8]) -> Option<DateTime<Utc>> {
+    const SEQUENCE: u8 = 0x30;
+    const INTEGER: u8 = 0x02;
+    const EXPLICIT_VERSION: u8 = 0xa0;
+
+    let (certificate, _) = der_element(der, SEQUENCE)?;
+    let (mut tbs_certificate, _) = der_element(certificate, SEQUENCE)?;
+    // Version 1 certificates leave out the version
+    if tbs_certificate.first() == Some(&EXPLICIT_VERSION) {
+        tbs_certificate = der_element(tbs_certificate, EXPLICIT_VERSION)?.1;
+    }
+    let (_, rest) = der_element(tbs_certificate, INTEGER)?; // serialNumber
+    let (_, rest) = der_element(rest, SEQUENCE)?; // signature
+    let (_, rest) = der_element(rest, SEQUENCE)?; // issuer
+    let (validity, _) = der_element(rest, SEQUENCE)?;
+    let (_, rest) = der_element(validity, *validity.first()?)?; // notBefore
+    let tag = *rest.first()?;
+    let (not_after, _) = der_element(rest, tag)?;
+    parse_certificate_time(tag, std::str::from_utf8(not_after).ok()?)
+}
+
+/// The contents of the DER element at the start of `input` when it has `tag`, and the bytes
+/// after it
+fn der_element(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (&first, rest) = input.split_first()?;
+    if first != tag {
+        return None;
+    }
+    let (&length, mut rest) = rest.split_first()?;
+    let length = if length < 0x80 {
+        usize::from(length)
+    } else {
+        // Long form: the low bits count the length bytes that follow
+        let count = usize::from(length & 0x7f);
+        if count == 0 || count > std::mem::size_of::<u32>() || rest.len() < count {
+            return None;
+        }
+        let (bytes, tail) = rest.split_at(count);
+        rest = tail;
+        bytes
+            .iter()
+            .fold(0, |length, byte| (length << 8) | usize::from(*byte))
+    };
+    (rest.len() >= length).then(|| rest.split_at(length))
+}
+
+/// Certificates use UTCTime (`YYMMDDHHMMSSZ`) for dates through 2049 and GeneralizedTime
+/// (`YYYYMMDDHHMMSSZ`) after
+fn parse_certificate_time(tag: u8, value: &str) -> Option<DateTime<Utc>> {
+    const UTC_TIME: u8 = 0x17;
+    const GENERALIZED_TIME: u8 = 0x18;
+
+    let value = match tag {
+        UTC_TIME => {
+            let year: u32 = value.get(..2)?.parse().ok()?;
+            let century = if year < 50 { "20" } else { "19" };
+            format!("{century}{value}")
+        }
+        GENERALIZED_TIME => value.to_string(),
+        _ => return None,
+    };
+    NaiveDateTime::parse_from_str(&value, "%Y%m%d%H%M%SZ")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_certificate_not_after_reads_generalized_time() {
+        let der = include_bytes!("../tests/static/tls/localhost.der");
+
+        let not_after = certificate_not_after(der).unwrap();
+
+        assert_eq!(not_after.to_rfc3339(), "2126-09-24T04:45:52+00:00");
+    }
+
+    #[test]
+    fn test_certificate_not_after_rejects_truncated_certificates() {
+        let der = include_bytes!("../tests/static/tls/localhost.der");
+
+        assert_eq!(certificate_not_after(&der[..200]), None);
+        assert_eq!(certificate_not_after(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_certificate_time_picks_the_century_of_utc_time() {
+        assert_eq!(
+            parse_certificate_time(0x17, "491231235959Z")
+                .unwrap()
+                .to_rfc3339(),
+            "2049-12-31T23:59:59+00:00"
+        );
+        assert_eq!(
+            parse_certificate_time(0x17, "990101000000Z")
+                .unwrap()
+                .to_rfc3339(),
+            "1999-01-01T00:00:00+00:00"
+        );
+        assert_eq!(parse_certificate_time(0x17, "bad"), None);
+    }
+}
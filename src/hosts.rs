@@ -0,0 +1,175 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+
+/// Domain suffixes that only resolve inside a machine or a local network
+const LOCAL_DOMAIN_SUFFIXES: [&str; 4] = [".localhost", ".local", ".internal", ".home.arpa"];
+
+/// Why a link's host can't be reached from the public internet, or is a bare address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonPublicHost {
+    /// `localhost` or a name under it
+    Localhost,
+    /// A name under a suffix for local networks, such as `.local` for mDNS
+    LocalDomain,
+    /// Loopback, RFC 1918, link-local, shared (CGNAT), unique local, or unspecified addresses
+    PrivateAddress,
+    /// A public IP address used instead of a hostname
+    PublicAddress,
+}
+
+impl NonPublicHost {
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Localhost => "localhost",
+            Self::LocalDomain => "local network hostname",
+            Self::PrivateAddress => "private IP address",
+            Self::PublicAddress => "raw IP address",
+        }
+    }
+}
+
+/// How `url`'s host falls short of a public hostname, or `None` when it is one
+pub fn classify_url(url: &Url) -> Option<NonPublicHost> {
+    match url.host()? {
+        Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            if domain == "localhost" || domain.ends_with(".localhost") {
+                Some(NonPublicHost::Localhost)
+            } else if LOCAL_DOMAIN_SUFFIXES
+                .iter()
+                .any(|suffix| domain.ends_with(suffix))
+            {
+                Some(NonPublicHost::LocalDomain)
+            } else {
+                None
+            }
+        }
+        Host::Ipv4(ip) => Some(classify_ip(IpAddr::V4(ip))),
+        Host::Ipv6(ip) => Some(classify_ip(IpAddr::V6(ip))),
+    }
+}
+
+/// The IP address `url` names instead of a hostname, if any
+pub fn ip_address(url: &Url) -> Option<IpAddr> {
+    match url.host()? {
+        Host::Domain(_) => None,
+        Host::Ipv4(ip) => Some(IpAddr::V4(ip)),
+        Host::Ipv6(ip) => Some(IpAddr::V6(ip)),
+    }
+}
+
+fn classify_ip(ip: IpAddr) -> NonPublicHost {
+    if is_private_ip(ip) {
+        NonPublicHost::PrivateAddress
+    } else {
+        NonPublicHost::PublicAddress
+    }
+}
+
+/// Addresses that only make sense inside a machine or a private network
+pub fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ipv4) => is_private_ipv4(ipv4),
+            None => is_private_ipv6(ip),
+        },
+    }
+}
+
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // Shared address space for carrier-grade NAT, 100.64.0.0/10
+        || (first == 100 && (64..128).contains(&second))
+}
+
+fn is_private_ipv6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    // NAT64, 64:ff9b::/96, reaches the IPv4 address in its last 32 bits
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., a, b, c, d] = ip.octets();
+        return is_private_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+    let first = segments[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        // Unique local, fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(url: &str) -> Option<NonPublicHost> {
+        classify_url(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_classify_url_flags_local_names() {
+        assert_eq!(
+            classify("http://localhost:3000/"),
+            Some(NonPublicHost::Localhost)
+        );
+        assert_eq!(
+            classify("http://app.localhost/"),
+            Some(NonPublicHost::Localhost)
+        );
+        assert_eq!(
+            classify("http://printer.local/"),
+            Some(NonPublicHost::LocalDomain)
+        );
+        assert_eq!(
+            classify("https://wiki.corp.internal/"),
+            Some(NonPublicHost::LocalDomain)
+        );
+        assert_eq!(classify("https://example.com/"), None);
+        assert_eq!(classify("https://localhost.example.com/"), None);
+        assert_eq!(classify("mailto:someone@example.com"), None);
+    }
+
+    #[test]
+    fn test_classify_url_separates_private_and_public_addresses() {
+        for url in [
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://172.16.0.1/",
+            "http://192.168.1.10:8080/admin",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fd12:3456::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:192.168.0.1]/",
+            "http://[64:ff9b::a00:1]/",
+            "http://[64:ff9b::7f00:1]/",
+            "http://[64:ff9b::a9fe:a9fe]/",
+        ] {
+            assert_eq!(classify(url), Some(NonPublicHost::PrivateAddress), "{url}");
+        }
+        assert_eq!(
+            classify("http://93.184.216.34/"),
+            Some(NonPublicHost::PublicAddress)
+        );
+        assert_eq!(
+            classify("http://172.32.0.1/"),
+            Some(NonPublicHost::PublicAddress)
+        );
+        assert_eq!(
+            classify("http://[2606:4700::1111]/"),
+            Some(NonPublicHost::PublicAddress)
+        );
+        assert_eq!(
+            classify("http://[64:ff9b::5db8:d822]/"),
+            Some(NonPublicHost::PublicAddress)
+        );
+    }
+}
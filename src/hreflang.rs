@@ -0,0 +1,310 @@
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use reqwest::cookie::Jar;
+use reqwest::header;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::crawler::Crawler;
+use crate::hosts;
+use crate::http_client::{
+    TlsOptions, build_public_only_redirect_tracing_client, build_redirect_tracing_client,
+    find_private_address,
+};
+use crate::limits::RequestLimits;
+use crate::link_checker::DEFAULT_LINK_TIMEOUT_SECS;
+use crate::models::{
+    HreflangLink, IssueSeverity, IssueType, LinkSkipReason, PageInfo, SeoIssue, document_url,
+};
+use crate::page_store::PageStore;
+use crate::rules::PathRules;
+use url::Url;
+
+/// What an hreflang alternate answered when requested without following redirects
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Ok {
+        alternates: Vec<HreflangLink>,
+    },
+    Redirect {
+        status: u16,
+        location: Option<String>,
+    },
+    Broken {
+        actual: String,
+    },
+    /// Not requested because it is or resolves to a private address, with `block_private_ips`
+    PrivateAddress,
+}
+
+/// Requests every hreflang alternate the crawled pages declare and checks that it answers
+/// 200 and declares an alternate pointing back
+pub struct HreflangChecker {
+    client: reqwest::Client,
+    /// Client for alternates on other hosts that refuses private addresses, with
+    /// `block_private_ips`
+    public_only_client: Option<reqwest::Client>,
+    block_private_ips: bool,
+    timeout_secs: u64,
+    cookie_jar: Option<Arc<Jar>>,
+    tls: TlsOptions,
+    concurrent_checks: usize,
+    request_limits: Option<Arc<RequestLimits>>,
+}
+
+impl HreflangChecker {
+    pub fn new(concurrent_checks: usize) -> Result<Self> {
+        let tls = TlsOptions::default();
+        Ok(Self {
+            client: build_redirect_tracing_client(DEFAULT_LINK_TIMEOUT_SECS, None, &tls)?,
+            public_only_client: None,
+            block_private_ips: false,
+            timeout_secs: DEFAULT_LINK_TIMEOUT_SECS,
+            cookie_jar: None,
+            tls,
+            concurrent_checks: concurrent_checks.max(1),
+            request_limits: None,
+        })
+    }
+
+    /// Verify alternates' certificates the way the crawl does
+    pub fn set_tls(&mut self, tls: TlsOptions) -> Result<()> {
+        self.tls = tls;
+        self.rebuild_client()
+    }
+
+    /// Send the cookies shared with the crawl, so alternates behind a login load as they do
+    /// for the crawler
+    pub fn set_cookie_jar(&mut self, jar: Arc<Jar>) -> Result<()> {
+        self.cookie_jar = Some(jar);
+        self.rebuild_client()
+    }
+
+    /// Give up on requests that take longer than this many seconds
+    pub fn set_timeout(&mut self, timeout_secs: u64) -> Result<()> {
+        self.timeout_secs = timeout_secs;
+        self.rebuild_client()
+    }
+
+    /// Don't request alternates on other hosts that are, resolve to, or redirect to private
+    /// addresses, and mark them as skipped instead
+    pub fn set_block_private_ips(&mut self, enabled: bool) -> Result<()> {
+        self.block_private_ips = enabled;
+        self.rebuild_client()
+    }
+
+    fn rebuild_client(&mut self) -> Result<()> {
+        self.client =
+            build_redirect_tracing_client(self.timeout_secs, self.cookie_jar.clone(), &self.tls)?;
+        self.public_only_client = self
+            .block_private_ips
+            .then(|| {
+                build_public_only_redirect_tracing_client(
+                    self.timeout_secs,
+                    self.cookie_jar.clone(),
+                    &self.tls,
+                )
+            })
+            .transpose()?;
+        Ok(())
+    }
+
+    /// Leave alternates unchecked once the request or bandwidth limit shared with the crawl is reached
+    pub fn set_request_limits(&mut self, limits: Arc<RequestLimits>) {
+        self.request_limits = Some(limits);
+    }
+
+    /// Flag alternates that fail, redirect, or don't link back, skipping paths whose rules
+    /// disable the issue. Alternates pointing at their own page are not requested.
+    pub async fn check_all(&self, pages: &mut dyn PageStore, rules: &PathRules) -> Result<()> {
+        let mut targets = BTreeSet::new();
+        let mut site_hosts = HashSet::new();
+        pages.for_each_page(&mut |_, page| {
+            targets.extend(alternate_targets(page));
+            site_hosts.extend(page_host(page));
+            Ok(())
+        })?;
+        let results = self.fetch_all(targets, &site_hosts).await;
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        pages.update_each(&mut |_, page| {
+            Self::apply_results(page, &results, rules);
+            Ok(())
+        })
+    }
+
+    /// What each alternate answered, leaving out those the request limit left unchecked
+    async fn fetch_all(
+        &self,
+        targets: BTreeSet<String>,
+        site_hosts: &HashSet<String>,
+    ) -> HashMap<String, Target> {
+        stream::iter(targets)
+            .map(|url| async move {
+                let target = self.fetch(&url, site_hosts).await;
+                (url, target)
+            })
+            .buffer_unordered(self.concurrent_checks)
+            .filter_map(|(url, target)| async move { target.map(|target| (url, target)) })
+            .collect()
+            .await
+    }
+
+    /// Mark the page's skipped alternates and add the issues of the others
+    fn apply_results(page: &mut PageInfo, results: &HashMap<String, Target>, rules: &PathRules) {
+        for alternate in &mut page.alternates {
+            if results.get(&alternate.url) == Some(&Target::PrivateAddress) {
+                alternate.skip_reason = Some(LinkSkipReason::PrivateAddress);
+            }
+        }
+        let issues = Self::page_issues(page, results, rules);
+        page.issues.extend(issues);
+    }
+
+    fn page_issues(
+        page: &PageInfo,
+        results: &HashMap<String, Target>,
+        rules: &PathRules,
+    ) -> Vec<SeoIssue> {
+        let rule = rules.for_url(&page.url);
+        let mut issues = Vec::new();
+        for alternate in &page.alternates {
+            let Some(target) = results.get(&alternate.url) else {
+                continue;
+            };
+            let issue = match target {
+                Target::PrivateAddress => continue,
+                Target::Broken { actual } => SeoIssue::new(
+                    IssueSeverity::Error,
+                    IssueType::HreflangBrokenTarget,
+                    format!("Hreflang \"{}\" alternate is broken", alternate.hreflang),
+                )
+                .with_expected("200")
+                .with_actual(actual),
+                Target::Redirect { status, location } => SeoIssue::new(
+                    IssueSeverity::Error,
+                    IssueType::HreflangRedirectTarget,
+                    format!("Hreflang \"{}\" alternate redirects", alternate.hreflang),
+                )
+                .with_expected("200")
+                .with_actual(match location {
+                    Some(location) => format!("{status} to {location}"),
+                    None => status.to_string(),
+                }),
+                Target::Ok { alternates } => {
+                    if alternates
+                        .iter()
+                        .any(|back| is_same_document(&back.url, &page.url))
+                    {
+                        continue;
+                    }
+                    SeoIssue::new(
+                        IssueSeverity::Warning,
+                        IssueType::HreflangMissingReturnLink,
+                        format!(
+                            "Hreflang \"{}\" alternate doesn't declare this page as an alternate",
+                            alternate.hreflang
+                        ),
+                    )
+                    .with_expected(format!("hreflang alternate pointing to {}", page.url))
+                    .with_actual(if alternates.is_empty() {
+                        "no hreflang alternates".to_string()
+                    } else {
+                        alternates
+                            .iter()
+                            .map(|back| format!("{}: {}", back.hreflang, back.url))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                }
+            };
+            if !rule.is_disabled(issue.issue_type) {
+                issues.push(issue.with_offending_url(&alternate.url));
+            }
+        }
+        issues
+    }
+
+    /// None when the request limit left the alternate unchecked. Only alternates on hosts
+    /// outside the crawl are guarded by `block_private_ips`, as with the link checker.
+    async fn fetch(&self, url: &str, site_hosts: &HashSet<String>) -> Option<Target> {
+        let public_only_client = self.public_only_client.as_ref().filter(|_| {
+            Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .is_none_or(|host| !site_hosts.contains(&host))
+        });
+        if public_only_client.is_some()
+            && let Some(address) = Url::parse(url).ok().as_ref().and_then(hosts::ip_address)
+            && hosts::is_private_ip(address)
+        {
+            tracing::info!(url = %url, address = %address, "Not checking hreflang alternate at a private address");
+            return Some(Target::PrivateAddress);
+        }
+        if let Some(limits) = &self.request_limits
+            && !limits.try_acquire()
+        {
+            return None;
+        }
+
+        let client = public_only_client.unwrap_or(&self.client);
+        let response = match client.get(url).send().await {
+            Ok(response) => response,
+            Err(error) => {
+                if let Some(refused) = find_private_address(&error) {
+                    tracing::info!(url = %url, refused = %refused, "Not checking hreflang alternate at a private address");
+                    return Some(Target::PrivateAddress);
+                }
+                return Some(Target::Broken {
+                    actual: error.to_string(),
+                });
+            }
+        };
+        let status = response.status();
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|location| response.url().join(location).ok())
+                .map(String::from);
+            return Some(Target::Redirect {
+                status: status.as_u16(),
+                location,
+            });
+        }
+        if !status.is_success() {
+            return Some(Target::Broken {
+                actual: status.as_u16().to_string(),
+            });
+        }
+
+        let headers = response.headers().clone();
+        let page_url = response.url().clone();
+        let body = response.text().await.unwrap_or_default();
+        if let Some(limits) = &self.request_limits {
+            limits.record_bytes(body.len() as u64);
+        }
+        Some(Target::Ok {
+            alternates: Crawler::response_alternates(&headers, &page_url, &body),
+        })
+    }
+}
+
+/// The alternates `page` declares, other than itself
+fn alternate_targets(page: &PageInfo) -> impl Iterator<Item = String> + '_ {
+    page.alternates
+        .iter()
+        .filter(|alternate| !is_same_document(&alternate.url, &page.url))
+        .map(|alternate| alternate.url.clone())
+}
+
+fn page_host(page: &PageInfo) -> Option<String> {
+    Url::parse(&page.url).ok()?.host_str().map(str::to_string)
+}
+
+fn is_same_document(left: &str, right: &str) -> bool {
+    document_url(left).is_some_and(|left| Some(left) == document_url(right))
+}
@@ -0,0 +1,39 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory the raw bodies of crawled pages are written to, for `--save-html`
+#[derive(Debug, Clone)]
+pub struct HtmlSnapshots {
+    dir: PathBuf,
+}
+
+impl HtmlSnapshots {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).with_context(|| {
+            format!(
+                "Failed to create HTML snapshot directory: {}",
+                dir.display()
+            )
+        })?;
+        Ok(Self { dir })
+    }
+
+    /// Write `body` as fetched from `url`, replacing an earlier snapshot of the same URL, and
+    /// return the file's path
+    pub fn save(&self, url: &str, body: &str) -> Result<PathBuf> {
+        let path = self.snapshot_path(url);
+        fs::write(&path, body)
+            .with_context(|| format!("Failed to write HTML snapshot: {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Snapshots are named after a hash of the URL, which is safe as a file name on any platform
+    pub fn snapshot_path(&self, url: &str) -> PathBuf {
+        let digest = Sha256::digest(url.as_bytes());
+        let name: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        self.dir.join(format!("{name}.html"))
+    }
+}
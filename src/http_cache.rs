@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::models::{PageContent, PageInfo};
+
+const HTTP_CACHE_VERSION: u32 = 1;
+
+/// Pages from earlier runs with their validators, kept in a directory so a later run can
+/// ask the server whether each page changed and reuse it when the answer is 304
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+/// A page as it was crawled, with the `ETag` and `Last-Modified` headers it was served with.
+/// Reports leave out `content`, so it is saved alongside like in the crawl state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPage {
+    pub version: u32,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    pub page: PageInfo,
+    pub content: PageContent,
+}
+
+impl CachedPage {
+    /// A cache entry for `page`, or `None` when the response had no validator to revalidate
+    /// it with
+    pub fn new(url: &str, headers: &HeaderMap, page: &PageInfo) -> Option<Self> {
+        let validator = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+        let etag = validator(header::ETAG);
+        let last_modified = validator(header::LAST_MODIFIED);
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+        Some(Self {
+            version: HTTP_CACHE_VERSION,
+            url: url.to_string(),
+            etag,
+            last_modified,
+            page: page.clone(),
+            content: page.content.clone(),
+        })
+    }
+
+    /// `If-None-Match` and `If-Modified-Since` headers asking whether the page changed
+    pub fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let validators = [
+            (header::IF_NONE_MATCH, &self.etag),
+            (header::IF_MODIFIED_SINCE, &self.last_modified),
+        ];
+        for (name, value) in validators {
+            if let Some(value) = value
+                .as_deref()
+                .and_then(|value| HeaderValue::from_str(value).ok())
+            {
+                headers.insert(name, value);
+            }
+        }
+        headers
+    }
+
+    /// The cached page with its content restored
+    pub fn into_page(self) -> PageInfo {
+        PageInfo {
+            content: self.content,
+            ..self.page
+        }
+    }
+}
+
+impl HttpCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    /// The entry stored for `url`. Missing, unreadable, and outdated entries are all `None`,
+    /// since the page is then simply fetched again.
+    pub fn get(&self, url: &str) -> Option<CachedPage> {
+        let contents = fs::read_to_string(self.entry_path(url)).ok()?;
+        serde_json::from_str::<CachedPage>(&contents)
+            .ok()
+            .filter(|entry| entry.version == HTTP_CACHE_VERSION && entry.url == url)
+    }
+
+    pub fn put(&self, entry: &CachedPage) -> Result<()> {
+        let path = self.entry_path(&entry.url);
+        let json = serde_json::to_string(entry)?;
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, json)
+            .with_context(|| format!("Failed to write cache entry: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to write cache entry: {}", path.display()))
+    }
+
+    /// Entries are named after a hash of the URL, which is safe as a file name on any platform
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let digest = Sha256::digest(url.as_bytes());
+        let name: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        self.dir.join(format!("{name}.json"))
+    }
+}
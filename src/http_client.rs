@@ -1,38 +1,552 @@
-use anyhow::Result;
-use reqwest::{Client, ClientBuilder, header};
+use crate::cli::{DEFAULT_RETRIES, DEFAULT_RETRY_BACKOFF_MS};
+use crate::hosts;
+use crate::limits::{LimitReachedError, RequestLimits};
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Utc};
+use reqwest::cookie::Jar;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::HeaderMap;
+use reqwest::{Certificate, Client, ClientBuilder, Response, StatusCode, header, redirect};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Common HTTP headers used for all requests
-const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.3 Safari/605.1.15";
+pub const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.3 Safari/605.1.15";
 const ACCEPT: &str = "*/*";
 const ACCEPT_LANGUAGE: &str = "en-US,en;q=0.9";
 const CONNECTION: &str = "keep-alive";
+pub const MAX_REDIRECTS: usize = 10;
+/// Longest `Retry-After` wait honored before giving up on a URL
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+/// Retries of one request after `Retry-After` responses
+const MAX_RETRY_AFTER_ATTEMPTS: usize = 2;
+/// Longest wait between retries of a transient failure, however many attempts came before
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How often and how patiently to retry requests that failed in a way that may not happen
+/// again: timeouts, dropped connections, and 5xx responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first one; 0 turns retries off
+    pub retries: usize,
+    /// Wait before the first retry, doubled for each one after it
+    pub backoff: Duration,
+    /// Wait a random time between half and all of the backoff, so many failed requests
+    /// don't all retry at the same moment
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: DEFAULT_RETRIES,
+            backoff: Duration::from_millis(DEFAULT_RETRY_BACKOFF_MS),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The wait before retry number `retry`, counting from 0
+    pub fn delay(&self, retry: usize) -> Duration {
+        let factor = 2u32.saturating_pow(retry.try_into().unwrap_or(u32::MAX));
+        let delay = self
+            .backoff
+            .checked_mul(factor)
+            .unwrap_or(MAX_RETRY_BACKOFF)
+            .min(MAX_RETRY_BACKOFF);
+        if self.jitter {
+            delay / 2 + delay.mul_f64(fastrand::f64() / 2.0)
+        } else {
+            delay
+        }
+    }
+
+    /// Send a request with `send`, retrying it while it fails transiently. Each retry counts
+    /// against `limits`; once they refuse one, the last failure is returned.
+    pub async fn send<F, Fut>(
+        &self,
+        url: &str,
+        limits: Option<&RequestLimits>,
+        send: F,
+    ) -> reqwest::Result<Response>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = reqwest::Result<Response>>,
+    {
+        let mut retry = 0;
+        loop {
+            let result = send().await;
+            let failure = match &result {
+                Ok(response) if response.status().is_server_error() => {
+                    format!("HTTP {}", response.status().as_u16())
+                }
+                Err(error) if is_transient(error) => error.to_string(),
+                _ => return result,
+            };
+            if retry >= self.retries {
+                return result;
+            }
+            if limits.is_some_and(|limits| !limits.try_acquire()) {
+                tracing::info!(url = %url, failure = %failure, "Not retrying, the request limit was reached");
+                return result;
+            }
+
+            let wait = self.delay(retry);
+            retry += 1;
+            tracing::info!(url = %url, failure = %failure, retry, wait_secs = wait.as_secs_f64(), "Retrying after a transient failure");
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Timeouts, refused or dropped connections, and bodies cut off mid-response; not redirect
+/// loops or invalid URLs, which fail the same way every time
+fn is_transient(error: &reqwest::Error) -> bool {
+    if find_private_address(error).is_some() {
+        return false;
+    }
+    if error.is_timeout() || error.is_connect() {
+        return true;
+    }
+    std::iter::successors(Some(error as &(dyn Error + 'static)), |&error| {
+        error.source()
+    })
+    .filter_map(|error| error.downcast_ref::<io::Error>())
+    .any(|error| {
+        matches!(
+            error.kind(),
+            io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::UnexpectedEof
+                | io::ErrorKind::TimedOut
+        )
+    })
+}
+
+/// Error message of a redirect chain that went past `MAX_REDIRECTS`
+pub const TOO_MANY_REDIRECTS: &str = "too many redirects";
+
+/// A redirect chain that came back to a URL it had already visited
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectLoopError {
+    /// The URLs of the loop, starting and ending with the repeated URL
+    pub cycle: Vec<String>,
+}
+
+impl fmt::Display for RedirectLoopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "redirect loop: {}", self.cycle.join(" -> "))
+    }
+}
+
+impl Error for RedirectLoopError {}
+
+/// Find a redirect loop anywhere in an error's chain of sources
+pub fn find_redirect_loop<'a>(error: &'a (dyn Error + 'static)) -> Option<&'a RedirectLoopError> {
+    std::iter::successors(Some(error), |&error| error.source())
+        .find_map(|error| error.downcast_ref::<RedirectLoopError>())
+}
+
+/// A request refused because its host is, or resolves to, a private address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateAddressError {
+    pub host: String,
+    pub address: IpAddr,
+}
+
+impl fmt::Display for PrivateAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} resolves to private address {}",
+            self.host, self.address
+        )
+    }
+}
+
+impl Error for PrivateAddressError {}
+
+/// Find a refused private address anywhere in an error's chain of sources
+pub fn find_private_address<'a>(
+    error: &'a (dyn Error + 'static),
+) -> Option<&'a PrivateAddressError> {
+    std::iter::successors(Some(error), |&error| error.source())
+        .find_map(|error| error.downcast_ref::<PrivateAddressError>())
+}
+
+/// Resolves hostnames with the system resolver, but fails when any of a name's addresses is
+/// private, so requests can't reach the machine or network scoutly runs in. Checking every
+/// address at connection time also catches names that resolve differently on a second lookup.
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|addr| hosts::is_private_ip(addr.ip())) {
+                return Err(Box::new(PrivateAddressError {
+                    host,
+                    address: addr.ip(),
+                }) as Box<dyn Error + Send + Sync>);
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Follow up to `MAX_REDIRECTS` redirects, but stop as soon as a URL repeats so loops are
+/// reported with their cycle instead of as a generic too-many-redirects error. Each hop counts
+/// against `limits`, and fails with a `LimitReachedError` once they refuse it.
+fn loop_detecting_policy(limits: Option<Arc<RequestLimits>>) -> redirect::Policy {
+    redirect::Policy::custom(move |attempt| follow_unless_looping(attempt, limits.as_deref()))
+}
+
+/// Like `loop_detecting_policy`, but refuses redirects to private IP addresses. Redirects to
+/// hostnames are checked when `PublicOnlyResolver` resolves them.
+fn public_only_policy(limits: Option<Arc<RequestLimits>>) -> redirect::Policy {
+    redirect::Policy::custom(move |attempt| match hosts::ip_address(attempt.url()) {
+        Some(address) if hosts::is_private_ip(address) => {
+            let host = attempt.url().host_str().unwrap_or_default().to_string();
+            attempt.error(PrivateAddressError { host, address })
+        }
+        _ => follow_unless_looping(attempt, limits.as_deref()),
+    })
+}
+
+fn follow_unless_looping(
+    attempt: redirect::Attempt,
+    limits: Option<&RequestLimits>,
+) -> redirect::Action {
+    if let Some(start) = attempt
+        .previous()
+        .iter()
+        .position(|visited| visited == attempt.url())
+    {
+        let cycle = attempt.previous()[start..]
+            .iter()
+            .chain([attempt.url()])
+            .map(|url| url.to_string())
+            .collect();
+        attempt.error(RedirectLoopError { cycle })
+    } else if attempt.previous().len() > MAX_REDIRECTS {
+        attempt.error(TOO_MANY_REDIRECTS)
+    } else if limits.is_some_and(|limits| !limits.try_acquire()) {
+        attempt.error(LimitReachedError)
+    } else {
+        attempt.follow()
+    }
+}
+
+/// Parse a `Retry-After` value: delay seconds or an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// GET a URL, and when the server answers 429 or 503 with a `Retry-After` of at most
+/// `MAX_RETRY_AFTER`, wait it out and try again, as long as `limits` allow another request
+pub async fn get_honoring_retry_after(
+    client: &Client,
+    url: &str,
+    limits: Option<&RequestLimits>,
+) -> reqwest::Result<Response> {
+    get_with_headers_honoring_retry_after(client, url, &HeaderMap::new(), limits).await
+}
+
+/// `get_honoring_retry_after` sending `headers` with every attempt, e.g. conditional headers
+pub async fn get_with_headers_honoring_retry_after(
+    client: &Client,
+    url: &str,
+    headers: &HeaderMap,
+    limits: Option<&RequestLimits>,
+) -> reqwest::Result<Response> {
+    let mut attempts = 0;
+    loop {
+        let response = client.get(url).headers(headers.clone()).send().await?;
+        let retry_after = matches!(
+            response.status(),
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+        )
+        .then(|| response.headers().get(header::RETRY_AFTER))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after)
+        .filter(|wait| *wait <= MAX_RETRY_AFTER);
+
+        match retry_after {
+            Some(wait)
+                if attempts < MAX_RETRY_AFTER_ATTEMPTS
+                    && limits.is_none_or(|limits| limits.try_acquire()) =>
+            {
+                attempts += 1;
+                tracing::info!(url = %url, status = %response.status(), wait_secs = wait.as_secs_f64(), "Waiting for Retry-After before retrying");
+                tokio::time::sleep(wait).await;
+            }
+            _ => return Ok(response),
+        }
+    }
+}
+
+/// How the crawler and link checker verify the certificates of HTTPS sites
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// Accept any certificate, including self-signed, expired, and mismatched ones
+    pub insecure: bool,
+    /// Root certificates trusted on top of the built-in ones, e.g. an internal CA
+    pub ca_certs: Vec<Certificate>,
+    /// Base64 SHA-256 digests of the public keys of `ca_certs`, for browsers, which can't be
+    /// handed a CA file but can be told to accept chains that include these keys
+    pub ca_key_hashes: Vec<String>,
+}
+
+impl TlsOptions {
+    /// Load the PEM certificates of `ca_cert`, which may hold a whole bundle
+    pub fn new(insecure: bool, ca_cert: Option<&Path>) -> Result<Self> {
+        let (ca_certs, ca_key_hashes) = match ca_cert {
+            Some(path) => {
+                let pem = std::fs::read(path).with_context(|| {
+                    format!("Failed to read CA certificate: {}", path.display())
+                })?;
+                let certs = Certificate::from_pem_bundle(&pem).with_context(|| {
+                    format!("Failed to parse CA certificate: {}", path.display())
+                })?;
+                if certs.is_empty() {
+                    anyhow::bail!("No PEM certificates found in {}", path.display());
+                }
+                let hashes = public_key_hashes(&pem).with_context(|| {
+                    format!("Failed to parse CA certificate: {}", path.display())
+                })?;
+                (certs, hashes)
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+        Ok(Self {
+            insecure,
+            ca_certs,
+            ca_key_hashes,
+        })
+    }
+}
+
+/// Base64 SHA-256 digests of the SubjectPublicKeyInfo of each certificate in a PEM bundle,
+/// the form Chrome's `--ignore-certificate-errors-spki-list` takes
+fn public_key_hashes(pem: &[u8]) -> Result<Vec<String>> {
+    let pem = String::from_utf8_lossy(pem);
+    let mut hashes = Vec::new();
+    for block in pem.split("-----BEGIN CERTIFICATE-----").skip(1) {
+        let body = block
+            .split("-----END CERTIFICATE-----")
+            .next()
+            .unwrap_or_default();
+        let encoded: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+        let der = BASE64
+            .decode(encoded)
+            .context("Invalid base64 in PEM certificate")?;
+        let key = subject_public_key_info(&der).context("Malformed certificate")?;
+        hashes.push(BASE64.encode(Sha256::digest(key)));
+    }
+    Ok(hashes)
+}
+
+/// The DER encoding of a certificate's SubjectPublicKeyInfo, the field of TBSCertificate
+/// after the optional version, serial number, signature algorithm, issuer, validity, and
+/// subject
+fn subject_public_key_info(der: &[u8]) -> Option<&[u8]> {
+    let (_, certificate, _) = der_element(der)?;
+    let (_, mut fields, _) = der_element(certificate)?;
+    if fields.first() == Some(&0xa0) {
+        fields = der_element(fields)?.2;
+    }
+    for _ in 0..5 {
+        fields = der_element(fields)?.2;
+    }
+    let (_, _, rest) = der_element(fields)?;
+    Some(&fields[..fields.len() - rest.len()])
+}
+
+/// Split the first DER element off `data` into its tag, its contents, and the bytes after it
+fn der_element(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, data) = data.split_first()?;
+    let (&first, mut data) = data.split_first()?;
+    let length = if first < 0x80 {
+        usize::from(first)
+    } else {
+        let count = usize::from(first & 0x7f);
+        if count == 0 || count > 4 || data.len() < count {
+            return None;
+        }
+        let (bytes, rest) = data.split_at(count);
+        data = rest;
+        bytes
+            .iter()
+            .fold(0, |length, byte| length << 8 | usize::from(*byte))
+    };
+    if data.len() < length {
+        return None;
+    }
+    let (contents, rest) = data.split_at(length);
+    Some((tag, contents, rest))
+}
 
 /// Creates a reqwest client with standard browser-like headers and configuration
 pub fn build_http_client(timeout_secs: u64) -> Result<Client> {
-    build_client(timeout_secs, ACCEPT)
+    build_client(
+        timeout_secs,
+        ACCEPT,
+        loop_detecting_policy(None),
+        None,
+        &TlsOptions::default(),
+    )
+}
+
+/// Like `build_http_client`, but for the crawl itself: sends the jar's cookies and stores the
+/// ones responses set, verifies certificates as `tls` says, and counts each redirect it
+/// follows against `limits`
+pub fn build_crawl_client(
+    timeout_secs: u64,
+    cookies: Option<Arc<Jar>>,
+    tls: &TlsOptions,
+    limits: Option<Arc<RequestLimits>>,
+) -> Result<Client> {
+    build_client(
+        timeout_secs,
+        ACCEPT,
+        loop_detecting_policy(limits),
+        cookies,
+        tls,
+    )
+}
+
+/// Like `build_crawl_client`, but refuses to connect to private addresses, whether a URL
+/// names one, its host resolves to one, or a redirect leads to one. Such requests fail with a
+/// `PrivateAddressError`.
+pub fn build_public_only_client(
+    timeout_secs: u64,
+    cookies: Option<Arc<Jar>>,
+    tls: &TlsOptions,
+    limits: Option<Arc<RequestLimits>>,
+) -> Result<Client> {
+    let client = client_builder(
+        timeout_secs,
+        ACCEPT,
+        public_only_policy(limits),
+        cookies,
+        tls,
+    )
+    .dns_resolver(Arc::new(PublicOnlyResolver))
+    .build()?;
+
+    Ok(client)
+}
+
+/// Like `build_crawl_client`, but returns redirect responses instead of following them so
+/// each hop of a redirect chain can be inspected
+pub fn build_redirect_tracing_client(
+    timeout_secs: u64,
+    cookies: Option<Arc<Jar>>,
+    tls: &TlsOptions,
+) -> Result<Client> {
+    build_client(timeout_secs, ACCEPT, redirect::Policy::none(), cookies, tls)
+}
+
+/// Like `build_redirect_tracing_client`, but refuses to connect to hosts that resolve to
+/// private addresses, failing with a `PrivateAddressError`. URLs that name a private address
+/// themselves have to be turned away by the caller.
+pub fn build_public_only_redirect_tracing_client(
+    timeout_secs: u64,
+    cookies: Option<Arc<Jar>>,
+    tls: &TlsOptions,
+) -> Result<Client> {
+    let client = client_builder(timeout_secs, ACCEPT, redirect::Policy::none(), cookies, tls)
+        .dns_resolver(Arc::new(PublicOnlyResolver))
+        .build()?;
+
+    Ok(client)
 }
 
 /// Creates a reqwest client for API requests with a JSON-friendly Accept header.
 pub fn build_api_client(timeout_secs: u64) -> Result<Client> {
-    build_client(timeout_secs, "application/vnd.github+json")
+    build_client(
+        timeout_secs,
+        "application/vnd.github+json",
+        redirect::Policy::limited(MAX_REDIRECTS),
+        None,
+        &TlsOptions::default(),
+    )
 }
 
-fn build_client(timeout_secs: u64, accept: &str) -> Result<Client> {
+/// Like `build_crawl_client`, but keeps the server's certificate on each response so its
+/// expiry can be read from the `TlsInfo` extension
+pub fn build_health_client(timeout_secs: u64, tls: &TlsOptions) -> Result<Client> {
+    let client = client_builder(timeout_secs, ACCEPT, loop_detecting_policy(None), None, tls)
+        .tls_info(true)
+        .build()?;
+
+    Ok(client)
+}
+
+fn build_client(
+    timeout_secs: u64,
+    accept: &str,
+    redirect_policy: redirect::Policy,
+    cookies: Option<Arc<Jar>>,
+    tls: &TlsOptions,
+) -> Result<Client> {
+    let client = client_builder(timeout_secs, accept, redirect_policy, cookies, tls).build()?;
+
+    Ok(client)
+}
+
+fn client_builder(
+    timeout_secs: u64,
+    accept: &str,
+    redirect_policy: redirect::Policy,
+    cookies: Option<Arc<Jar>>,
+    tls: &TlsOptions,
+) -> ClientBuilder {
     let mut headers = header::HeaderMap::new();
     headers.insert(header::ACCEPT, accept.parse().unwrap());
     headers.insert(header::ACCEPT_LANGUAGE, ACCEPT_LANGUAGE.parse().unwrap());
     headers.insert(header::CONNECTION, CONNECTION.parse().unwrap());
 
-    let client = ClientBuilder::new()
+    let mut builder = ClientBuilder::new();
+    if let Some(jar) = cookies {
+        builder = builder.cookie_provider(jar);
+    }
+    for cert in &tls.ca_certs {
+        builder = builder.add_root_certificate(cert.clone());
+    }
+    if tls.insecure {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    builder
         .user_agent(USER_AGENT)
         .default_headers(headers)
         .timeout(Duration::from_secs(timeout_secs))
-        .redirect(reqwest::redirect::Policy::limited(10))
+        .redirect(redirect_policy)
         .gzip(true)
         .brotli(true)
         .deflate(true)
-        .build()?;
-
-    Ok(client)
 }
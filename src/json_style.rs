@@ -0,0 +1,461 @@
+use clap::ValueEnum;
+use once_cell::sync::Lazy;
+use serde::ser::{
+    SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// How field names are written in JSON output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+pub enum JsonStyle {
+    /// `status_code`, the names the report is read back with
+    #[default]
+    #[value(name = "snake_case")]
+    #[serde(rename = "snake_case")]
+    SnakeCase,
+    /// `statusCode`, the convention of most JavaScript tooling
+    #[value(name = "camelCase")]
+    #[serde(rename = "camelCase")]
+    CamelCase,
+}
+
+/// Pretty-print `value` as JSON with its field names in `style`. Only struct fields are
+/// renamed: map keys such as page URLs and enum values are written as they are.
+pub fn to_string_pretty<T: Serialize + ?Sized>(
+    value: &T,
+    style: JsonStyle,
+) -> serde_json::Result<String> {
+    match style {
+        JsonStyle::SnakeCase => serde_json::to_string_pretty(value),
+        JsonStyle::CamelCase => serde_json::to_string_pretty(&CamelCase(value)),
+    }
+}
+
+/// Like `to_string_pretty`, but on a single line
+pub fn to_string<T: Serialize + ?Sized>(value: &T, style: JsonStyle) -> serde_json::Result<String> {
+    match style {
+        JsonStyle::SnakeCase => serde_json::to_string(value),
+        JsonStyle::CamelCase => serde_json::to_string(&CamelCase(value)),
+    }
+}
+
+/// Like `to_string_pretty`, writing to `writer` as the value serializes instead of building
+/// the whole string first
+pub fn to_writer_pretty<W: std::io::Write, T: Serialize + ?Sized>(
+    writer: W,
+    value: &T,
+    style: JsonStyle,
+) -> serde_json::Result<()> {
+    match style {
+        JsonStyle::SnakeCase => serde_json::to_writer_pretty(writer, value),
+        JsonStyle::CamelCase => serde_json::to_writer_pretty(writer, &CamelCase(value)),
+    }
+}
+
+/// `status_code` to `statusCode`
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for ch in name.chars() {
+        if ch == '_' {
+            upper_next = !camel.is_empty();
+        } else if upper_next {
+            camel.extend(ch.to_uppercase());
+            upper_next = false;
+        } else {
+            camel.push(ch);
+        }
+    }
+    camel
+}
+
+/// Serializers take field names as `&'static str`, so each renamed name is leaked once and
+/// reused; there are only as many as the models have fields
+fn camel_case_field(name: &'static str) -> &'static str {
+    static FIELDS: Lazy<Mutex<HashMap<&'static str, &'static str>>> =
+        Lazy::new(|| Mutex::new(HashMap::new()));
+
+    if !name.contains('_') {
+        return name;
+    }
+    let mut fields = FIELDS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    fields
+        .entry(name)
+        .or_insert_with(|| Box::leak(camel_case(name).into_boxed_str()))
+}
+
+/// Serializes the wrapped value with camelCase field names
+struct CamelCase<'a, T: ?Sized>(&'a T);
+
+impl<T: Serialize + ?Sized> Serialize for CamelCase<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(CamelCaseSerializer(serializer))
+    }
+}
+
+/// Passes everything through to the inner serializer, renaming struct fields on the way
+struct CamelCaseSerializer<S>(S);
+
+/// A compound serializer whose elements are serialized with camelCase field names too
+struct Renaming<C>(C);
+
+impl<S: Serializer> Serializer for CamelCaseSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Renaming<S::SerializeSeq>;
+    type SerializeTuple = Renaming<S::SerializeTuple>;
+    type SerializeTupleStruct = Renaming<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Renaming<S::SerializeTupleVariant>;
+    type SerializeMap = Renaming<S::SerializeMap>;
+    type SerializeStruct = Renaming<S::SerializeStruct>;
+    type SerializeStructVariant = Renaming<S::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<S::Ok, S::Error> {
+        self.0.serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<S::Ok, S::Error> {
+        self.0.serialize_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<S::Ok, S::Error> {
+        self.0.serialize_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> {
+        self.0.serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> {
+        self.0.serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<S::Ok, S::Error> {
+        self.0.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<S::Ok, S::Error> {
+        self.0.serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<S::Ok, S::Error> {
+        self.0.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(&CamelCase(value))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, &CamelCase(value))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0
+            .serialize_newtype_variant(name, variant_index, variant, &CamelCase(value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        self.0.serialize_seq(len).map(Renaming)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.0.serialize_tuple(len).map(Renaming)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        self.0.serialize_tuple_struct(name, len).map(Renaming)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.0
+            .serialize_tuple_variant(name, variant_index, variant, len)
+            .map(Renaming)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.0.serialize_map(len).map(Renaming)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        self.0.serialize_struct(name, len).map(Renaming)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.0
+            .serialize_struct_variant(name, variant_index, variant, len)
+            .map(Renaming)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+impl<C: SerializeSeq> SerializeSeq for Renaming<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_element(&CamelCase(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeTuple> SerializeTuple for Renaming<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_element(&CamelCase(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeTupleStruct> SerializeTupleStruct for Renaming<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_field(&CamelCase(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeTupleVariant> SerializeTupleVariant for Renaming<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_field(&CamelCase(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeMap> SerializeMap for Renaming<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    // Map keys are data, like page URLs, so they are left alone
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        self.0.serialize_key(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_value(&CamelCase(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeStruct> SerializeStruct for Renaming<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        self.0
+            .serialize_field(camel_case_field(key), &CamelCase(value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.0.skip_field(camel_case_field(key))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+impl<C: SerializeStructVariant> SerializeStructVariant for Renaming<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), C::Error> {
+        self.0
+            .serialize_field(camel_case_field(key), &CamelCase(value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), C::Error> {
+        self.0.skip_field(camel_case_field(key))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Reason {
+        RequestLimit,
+    }
+
+    #[derive(Serialize)]
+    struct Link {
+        status_code: Option<u16>,
+        skip_reason: Reason,
+    }
+
+    #[derive(Serialize)]
+    struct Report {
+        start_url: String,
+        pages: BTreeMap<String, Vec<Link>>,
+    }
+
+    #[test]
+    fn test_camel_case_renames_struct_fields_only() {
+        let report = Report {
+            start_url: "https://example.com/".to_string(),
+            pages: BTreeMap::from([(
+                "https://example.com/my_page".to_string(),
+                vec![Link {
+                    status_code: Some(200),
+                    skip_reason: Reason::RequestLimit,
+                }],
+            )]),
+        };
+
+        let json: serde_json::Value =
+            serde_json::from_str(&to_string_pretty(&report, JsonStyle::CamelCase).unwrap())
+                .unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "startUrl": "https://example.com/",
+                "pages": {
+                    "https://example.com/my_page": [
+                        {"statusCode": 200, "skipReason": "request_limit"}
+                    ]
+                }
+            })
+        );
+        assert!(
+            to_string_pretty(&report, JsonStyle::SnakeCase)
+                .unwrap()
+                .contains("\"start_url\"")
+        );
+    }
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("status_code"), "statusCode");
+        assert_eq!(camel_case("h1_tags"), "h1Tags");
+        assert_eq!(camel_case("url"), "url");
+    }
+}
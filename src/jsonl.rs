@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio::task::JoinHandle;
+
+use crate::json_style::{self, JsonStyle};
+use crate::models::{
+    CrawlError, CrawlErrorCause, CrawlReport, CrawlSummary, HtmlContentTypes, PageInfo, SeoIssue,
+};
+use crate::page_store::PageStore;
+use crate::rules::PathRules;
+use crate::seo_analyzer::SeoAnalyzer;
+
+/// One line of `--output jsonl`, tagged with its `type`
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JsonlRecord<'a> {
+    /// A page as soon as it was crawled, with the issues found on it alone
+    Page { page: &'a PageInfo },
+    /// Issues of an earlier page that were found once the whole site was crawled: broken
+    /// links and the checks that compare pages with each other
+    Issues {
+        url: &'a str,
+        issues: Vec<&'a SeoIssue>,
+    },
+    /// The last line, written when the scan is done
+    Summary {
+        start_url: &'a str,
+        summary: &'a CrawlSummary,
+        timestamp: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        limit_reached: Option<&'a str>,
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        errors: &'a BTreeMap<CrawlErrorCause, Vec<CrawlError>>,
+    },
+}
+
+/// Writes JSONL records, flushing after each so readers see every line as it is written
+pub struct JsonlWriter {
+    out: Box<dyn Write + Send>,
+    style: JsonStyle,
+    /// Issues of each type listed on a page line before the rest are counted, as in the report
+    max_issues_per_type: Option<usize>,
+    /// Fingerprints of the issues already written for each page, by page URL
+    written: HashMap<String, HashSet<String>>,
+}
+
+impl JsonlWriter {
+    pub fn new(
+        out: Box<dyn Write + Send>,
+        style: JsonStyle,
+        max_issues_per_type: Option<usize>,
+    ) -> Self {
+        Self {
+            out,
+            style,
+            max_issues_per_type,
+            written: HashMap::new(),
+        }
+    }
+
+    fn write(&mut self, record: &JsonlRecord) -> Result<()> {
+        let line = json_style::to_string(record, self.style)?;
+        writeln!(self.out, "{line}").context("Failed to write JSONL output")?;
+        self.out.flush().context("Failed to write JSONL output")
+    }
+
+    /// Analyze a freshly crawled page on its own and write it
+    pub fn write_page(
+        &mut self,
+        mut page: PageInfo,
+        rules: &PathRules,
+        html_content_types: &HtmlContentTypes,
+    ) -> Result<()> {
+        SeoAnalyzer::analyze_single_page(&mut page, rules, html_content_types);
+        if let Some(max_per_type) = self.max_issues_per_type {
+            page.limit_issues(max_per_type);
+        }
+        for issue in &mut page.issues {
+            issue.fingerprint = Some(issue.compute_fingerprint(&page.url));
+        }
+        self.written.insert(
+            page.url.clone(),
+            page.issues
+                .iter()
+                .filter_map(|issue| issue.fingerprint.clone())
+                .collect(),
+        );
+        self.write(&JsonlRecord::Page { page: &page })
+    }
+
+    /// Write what the lines so far left out of the finished `report`: pages that weren't
+    /// crawled in this run, such as those restored with `--resume`, the issues found after
+    /// the crawl, and the summary. `pages` is `report.pages` unless they are kept in a page
+    /// store.
+    pub fn finish(mut self, report: &CrawlReport, pages: &dyn PageStore) -> Result<()> {
+        pages.for_each_page(&mut |_, page| self.write_rest(page))?;
+        self.write_summary(report)
+    }
+
+    /// Write `page`, or the issues its line left out when it was already written
+    fn write_rest(&mut self, page: &PageInfo) -> Result<()> {
+        let record = match self.written.get(&page.url) {
+            None => JsonlRecord::Page { page },
+            Some(written) => {
+                let issues: Vec<&SeoIssue> = page
+                    .issues
+                    .iter()
+                    .filter(|issue| {
+                        issue
+                            .fingerprint
+                            .as_ref()
+                            .is_none_or(|fingerprint| !written.contains(fingerprint))
+                    })
+                    .collect();
+                if issues.is_empty() {
+                    return Ok(());
+                }
+                JsonlRecord::Issues {
+                    url: &page.url,
+                    issues,
+                }
+            }
+        };
+        self.write(&record)
+    }
+
+    fn write_summary(&mut self, report: &CrawlReport) -> Result<()> {
+        self.write(&JsonlRecord::Summary {
+            start_url: &report.start_url,
+            summary: &report.summary,
+            timestamp: &report.timestamp,
+            limit_reached: report.limit_reached.as_deref(),
+            errors: &report.errors,
+        })
+    }
+}
+
+/// Write pages sent to the returned sender as they arrive. The task hands the writer back
+/// once every sender is dropped, so the scan can `finish` it.
+pub fn spawn_jsonl_writer(
+    mut writer: JsonlWriter,
+    rules: PathRules,
+    html_content_types: HtmlContentTypes,
+) -> (UnboundedSender<PageInfo>, JoinHandle<Result<JsonlWriter>>) {
+    let (sender, mut receiver) = unbounded_channel::<PageInfo>();
+    let task = tokio::spawn(async move {
+        while let Some(page) = receiver.recv().await {
+            writer.write_page(page, &rules, &html_content_types)?;
+        }
+        Ok(writer)
+    });
+    (sender, task)
+}
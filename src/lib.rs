@@ -1,32 +1,91 @@
+pub mod baseline;
 pub mod cli;
+pub mod compare;
 pub mod config;
+pub mod contacts;
+pub mod cookies;
+pub mod crawl_state;
 pub mod crawler;
+pub mod crux;
+pub mod daemon;
+pub mod fixes;
+pub mod health;
+pub mod hosts;
+pub mod hreflang;
+pub mod html_snapshot;
+pub mod http_cache;
 pub mod http_client;
+pub mod json_style;
+pub mod jsonl;
+pub mod limits;
 pub mod link_checker;
+pub mod login;
+pub mod logs;
+pub mod migration;
 pub mod models;
+pub mod outline;
+pub mod page_store;
+pub mod progress;
+pub mod recrawl;
+pub mod render;
+pub mod report_dir;
 pub mod reporter;
+pub mod result_file;
 pub mod robots;
+pub mod rules;
 pub mod runtime;
+pub mod screenshot;
+pub mod scripting;
+pub mod secrets;
 pub mod seo_analyzer;
+pub mod sitemap;
+pub mod trends;
 pub mod tui;
 pub mod update;
+pub mod wizard;
 
-use anyhow::Result;
-use cli::{Cli, OutputFormat};
+use anyhow::{Context, Result};
+use baseline::Baseline;
+use cli::{Cli, Command, OutputFormat};
 use colored::*;
+use compare::SiteComparison;
 use config::{Config, RuntimeOptions};
-use crawler::{Crawler, CrawlerConfig};
+use crawler::{Crawler, CrawlerConfig, QueryNormalization};
+use crux::CruxClient;
+use fixes::FixReport;
+use health::HealthThresholds;
+use hreflang::HreflangChecker;
+use html_snapshot::HtmlSnapshots;
+use http_cache::HttpCache;
+use jsonl::{JsonlWriter, spawn_jsonl_writer};
+use limits::RequestLimits;
 use link_checker::LinkChecker;
-use models::{CrawlReport, PageInfo};
-use reporter::Reporter;
+use logs::{CrawlBudgetReport, LogFormat};
+use migration::MigrationValidator;
+use models::{CrawlReport, HtmlContentTypes, OutlineNode, PageInfo};
+use page_store::{PageStore, SqlitePageStore};
+use progress::{ProgressFormat, spawn_json_progress};
+use recrawl::Recrawl;
+use render::{JsRenderer, RenderMode};
+use reporter::{Reporter, TextReportOptions};
+use result_file::{ResultCheck, RunResult};
+use rules::{PageAssertions, PathRules, RuleOverride};
 use runtime::{
     LaunchMode, ProgressSnapshot, RunEvent, RunEventSender, RunStage, TerminalSupport,
     resolve_launch_mode,
 };
+use screenshot::Screenshotter;
+use scripting::ScriptChecks;
+use secrets::SecretScanner;
 use seo_analyzer::SeoAnalyzer;
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use trends::{TrendPoint, TrendStore};
+use url::Url;
 
 pub async fn run(args: Cli) -> Result<()> {
     run_with_terminal(args, TerminalSupport::current()).await
@@ -34,8 +93,30 @@ pub async fn run(args: Cli) -> Result<()> {
 
 #[doc(hidden)]
 pub async fn run_with_terminal(args: Cli, terminal: TerminalSupport) -> Result<()> {
+    if let Some(command) = &args.command {
+        return run_command(&args, command).await;
+    }
+
     let loaded_config = load_config(&args)?;
-    let runtime = RuntimeOptions::from_cli_and_config(&args, loaded_config.config());
+    let mut runtime = RuntimeOptions::from_cli_and_config(&args, loaded_config.config());
+    if let Some(path) = &args.url_file {
+        apply_url_file(&mut runtime, Path::new(path))?;
+    }
+    let recrawl = args
+        .recrawl_urls_from
+        .as_deref()
+        .map(|path| Recrawl::from_file(Path::new(path), args.only_errors))
+        .transpose()?;
+    if let Some(recrawl) = &recrawl {
+        if runtime.page_store.is_some() {
+            anyhow::bail!("--page-store can't be combined with --recrawl-urls-from");
+        }
+        recrawl.apply(&mut runtime);
+        // The merged report only exists in CLI and JSON modes
+        if runtime.output.is_none() {
+            runtime.cli = true;
+        }
+    }
 
     let launch_mode = resolve_launch_mode(&runtime, terminal)?;
 
@@ -44,29 +125,198 @@ pub async fn run_with_terminal(args: Cli, terminal: TerminalSupport) -> Result<(
             if let Some(url) = runtime.url.as_deref() {
                 validate_url(url)?;
             }
+            if runtime.page_store.is_some() {
+                anyhow::bail!("{PAGE_STORE_NEEDS_CLI}");
+            }
             tui::run(runtime).await
         }
         LaunchMode::Text => {
             validate_required_url(&runtime, "CLI mode")?;
-            run_cli(runtime, loaded_config, OutputFormat::Text).await
+            run_cli(runtime, loaded_config, recrawl, OutputFormat::Text).await
         }
         LaunchMode::Json => {
             validate_required_url(&runtime, "JSON output mode")?;
-            run_cli(runtime, loaded_config, OutputFormat::Json).await
+            run_cli(runtime, loaded_config, recrawl, OutputFormat::Json).await
+        }
+        LaunchMode::Jsonl => {
+            validate_required_url(&runtime, "JSONL output mode")?;
+            run_cli(runtime, loaded_config, recrawl, OutputFormat::Jsonl).await
         }
     }
 }
 
+async fn run_command(args: &Cli, command: &Command) -> Result<()> {
+    match command {
+        Command::Wizard => {
+            wizard::run(&mut std::io::stdin().lock(), &mut std::io::stdout())?;
+        }
+        Command::Trends {
+            url,
+            state_dir,
+            limit,
+            html,
+        } => {
+            let state_dir = match state_dir {
+                Some(state_dir) => state_dir.clone(),
+                None => load_config(args)?
+                    .config()
+                    .and_then(|config| config.state_dir.clone())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "No state directory configured. Pass --state-dir or set state_dir in the config file."
+                        )
+                    })?,
+            };
+            show_trends(
+                Path::new(&state_dir),
+                url.as_deref(),
+                *limit,
+                html.as_deref(),
+            )?;
+        }
+        Command::CompareSites { left, right } => {
+            let loaded_config = load_config(args)?;
+            let runtime = RuntimeOptions::from_cli_and_config(args, loaded_config.config());
+            compare_sites(&runtime, left, right).await?;
+        }
+        Command::ValidateMigration { mappings } => {
+            let loaded_config = load_config(args)?;
+            let runtime = RuntimeOptions::from_cli_and_config(args, loaded_config.config());
+            validate_migration(&runtime, mappings).await?;
+        }
+        Command::VerifyFixes { issues } => {
+            let loaded_config = load_config(args)?;
+            let runtime = RuntimeOptions::from_cli_and_config(args, loaded_config.config());
+            verify_fixes(&runtime, issues).await?;
+        }
+        Command::Logs {
+            log,
+            url,
+            format,
+            report,
+            limit,
+        } => {
+            let loaded_config = load_config(args)?;
+            let mut runtime = RuntimeOptions::from_cli_and_config(args, loaded_config.config());
+            if url.is_some() {
+                runtime.url = url.clone();
+            }
+            analyze_logs(&runtime, log, *format, report.as_deref(), *limit).await?;
+        }
+        Command::Health {
+            url,
+            max_ttfb_ms,
+            min_tls_days,
+        } => {
+            let loaded_config = load_config(args)?;
+            let runtime = RuntimeOptions::from_cli_and_config(args, loaded_config.config());
+            let thresholds = HealthThresholds {
+                max_ttfb: Duration::from_millis(*max_ttfb_ms),
+                min_tls_days: *min_tls_days,
+            };
+            check_health(&runtime, url, &thresholds).await?;
+        }
+        Command::Serve {
+            listen,
+            workers,
+            jobs_dir,
+            sites_dir,
+            archive_dir,
+            poll_interval,
+            no_auth,
+        } => {
+            daemon::run(daemon::DaemonOptions {
+                listen: *listen,
+                workers: *workers,
+                jobs_dir: jobs_dir.clone(),
+                sites_dir: sites_dir.clone(),
+                archive_dir: archive_dir.clone(),
+                poll_interval: Duration::from_secs((*poll_interval).max(1)),
+                api_tokens: load_config(args)?
+                    .config()
+                    .and_then(|config| config.api_tokens.clone())
+                    .unwrap_or_default(),
+                no_auth: *no_auth,
+            })
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a scan for the TUI and daemon, which work with the whole report in memory, so they
+/// refuse `--page-store`
 pub(crate) async fn execute_scan(
     runtime: &RuntimeOptions,
     event_sender: Option<RunEventSender>,
     show_progress_bars: bool,
 ) -> Result<CrawlReport> {
+    if runtime.page_store.is_some() {
+        anyhow::bail!("{PAGE_STORE_NEEDS_CLI}");
+    }
+    let ScanOutcome { report, .. } =
+        execute_scan_streaming(runtime, event_sender, show_progress_bars, None).await?;
+    Ok(report)
+}
+
+/// Why the TUI, the daemon, and other whole-report runs refuse a page store
+const PAGE_STORE_NEEDS_CLI: &str = "--page-store only works with --cli or --output json/jsonl; the TUI and the daemon keep the whole report in memory";
+
+/// A finished scan. With `--page-store` the pages stay in the store and `report.pages` is
+/// empty.
+struct ScanOutcome {
+    report: CrawlReport,
+    page_store: Option<Box<dyn PageStore>>,
+}
+
+impl ScanOutcome {
+    /// The scan's pages, wherever they are kept
+    fn pages(&self) -> &dyn PageStore {
+        match &self.page_store {
+            Some(store) => store.as_ref(),
+            None => &self.report.pages,
+        }
+    }
+}
+
+/// The per-path rules of a scan, on top of the budget and comment markers set for all pages
+fn scan_path_rules(runtime: &RuntimeOptions) -> Result<PathRules> {
+    Ok(
+        PathRules::new(&runtime.rule_overrides)?.with_base(RuleOverride {
+            budget: runtime.budget,
+            comment_markers: runtime.comment_markers.clone(),
+            ..Default::default()
+        }),
+    )
+}
+
+/// Like `execute_scan`, but also sends each page to `page_sender` as soon as it is crawled,
+/// and leaves pages kept in a page store there
+async fn execute_scan_streaming(
+    runtime: &RuntimeOptions,
+    event_sender: Option<RunEventSender>,
+    show_progress_bars: bool,
+    page_sender: Option<UnboundedSender<PageInfo>>,
+) -> Result<ScanOutcome> {
     let url = runtime
         .url
         .as_deref()
         .ok_or_else(|| anyhow::anyhow!("A URL is required to start a scan"))?;
     validate_url(url)?;
+    for additional_url in &runtime.additional_urls {
+        validate_url(additional_url)?;
+    }
+    let path_rules = scan_path_rules(runtime)?;
+    let html_content_types = runtime.resolved_html_content_types();
+
+    let script_checks = (!runtime.scripts.is_empty())
+        .then(|| ScriptChecks::load(&runtime.scripts))
+        .transpose()?;
+    let secret_scanner = runtime
+        .scan_secrets
+        .then(|| SecretScanner::new(&runtime.secret_patterns))
+        .transpose()?;
 
     emit_progress(
         &event_sender,
@@ -74,36 +324,61 @@ pub(crate) async fn execute_scan(
     );
 
     let mut crawler = build_crawler(runtime)?;
+    crawler.set_keep_html(script_checks.is_some() || secret_scanner.is_some());
+    crawler.set_assertions(PageAssertions::new(&runtime.assertions)?);
     if let Some(sender) = &event_sender {
         crawler.set_progress_sender(sender.clone());
     }
     if show_progress_bars {
         crawler.enable_progress_bar();
     }
+    if let Some(sender) = page_sender {
+        crawler.set_page_sender(sender);
+    }
 
     emit_progress(
         &event_sender,
         ProgressSnapshot::new(RunStage::Crawling, format!("Crawling {url}")),
     );
     crawler.crawl().await?;
+    // The checks after the crawl read and update pages through the same interface, whether
+    // they are in memory or in the page store
+    let mut pages_in_memory = std::mem::take(&mut crawler.pages);
+    let mut page_store = crawler.take_page_store();
+    let in_memory = page_store.is_none();
 
-    let unique_links = collect_unique_links(&crawler);
+    let mut link_checker = build_link_checker(runtime)?;
+    if runtime.gentle {
+        link_checker.set_gentle(crawler.request_interval());
+    }
+    let request_limits = crawler.request_limits();
+    if let Some(limits) = &request_limits {
+        link_checker.set_request_limits(limits.clone())?;
+    }
+    if let Some(jar) = crawler.cookie_jar() {
+        link_checker.set_cookie_jar(jar)?;
+    }
+    let pages: &mut dyn PageStore = match &mut page_store {
+        Some(store) => store.as_mut(),
+        None => &mut pages_in_memory,
+    };
+    let unique_links = link_checker.collect_links_to_check(pages)?;
     emit_progress(
         &event_sender,
         snapshot_from_pages(
             RunStage::CheckingLinks,
             format!(
                 "Discovered {} page(s) and {} unique link(s)",
-                crawler.pages.len(),
+                pages.len()?,
                 unique_links.len()
             ),
-            &crawler.pages,
+            pages,
+            &html_content_types,
             0,
             unique_links.len(),
-        ),
+        )?,
     );
 
-    let mut link_checker = LinkChecker::with_concurrency(runtime.concurrency);
     if let Some(sender) = &event_sender {
         link_checker.set_progress_sender(sender.clone());
     }
@@ -111,32 +386,112 @@ pub(crate) async fn execute_scan(
         link_checker.enable_progress_bar(unique_links.len());
     }
     link_checker
-        .check_all_links(&mut crawler.pages, runtime.ignore_redirects)
+        .check_all_links(pages, runtime.ignore_redirects)
         .await?;
 
+    let mut hreflang_checker = HreflangChecker::new(if runtime.gentle {
+        1
+    } else {
+        runtime.concurrency
+    })?;
+    if let Some(limits) = &request_limits {
+        hreflang_checker.set_request_limits(limits.clone());
+    }
+    hreflang_checker.set_timeout(runtime.link_timeout)?;
+    hreflang_checker.set_block_private_ips(runtime.block_private_ips)?;
+    if runtime.insecure || runtime.ca_cert.is_some() {
+        hreflang_checker.set_tls(runtime.tls_options()?)?;
+    }
+    if let Some(jar) = crawler.cookie_jar() {
+        hreflang_checker.set_cookie_jar(jar)?;
+    }
+    hreflang_checker.check_all(pages, &path_rules).await?;
+
     emit_progress(
         &event_sender,
         snapshot_from_pages(
             RunStage::AnalyzingSeo,
             "Analyzing SEO issues".to_string(),
-            &crawler.pages,
+            pages,
+            &html_content_types,
             unique_links.len(),
             unique_links.len(),
-        ),
+        )?,
     );
-    SeoAnalyzer::analyze_pages(&mut crawler.pages);
+    SeoAnalyzer::analyze_pages_with_rules(pages, &path_rules, &html_content_types)?;
+    pages.update_each(&mut |key, page| {
+        sitemap::audit_page_lastmod(key, page, &crawler.sitemap_lastmods, &path_rules);
+        if runtime.find_contacts {
+            contacts::report_page_contacts(page, &path_rules, &html_content_types);
+        }
+        if let Some(secret_scanner) = &secret_scanner {
+            secret_scanner.scan_page(page, &path_rules);
+        }
+        if runtime.heading_outline {
+            page.outline = OutlineNode::build(&page.content.headings);
+        }
+        // Script checks drop the markup once they've run; otherwise it was only kept for the
+        // secret scan
+        match &script_checks {
+            Some(script_checks) => script_checks.run_page(page, &path_rules, &html_content_types),
+            None => page.content.html = None,
+        }
+        if let Some(max_per_type) = runtime.max_issues_per_type {
+            page.limit_issues(max_per_type);
+        }
+        Ok(())
+    })?;
+
+    if let Some(api_key) = &runtime.crux_api_key {
+        emit_progress(
+            &event_sender,
+            snapshot_from_pages(
+                RunStage::AnalyzingSeo,
+                "Looking up Core Web Vitals field data".to_string(),
+                pages,
+                &html_content_types,
+                unique_links.len(),
+                unique_links.len(),
+            )?,
+        );
+        CruxClient::new(api_key.clone())?
+            .with_timeout(runtime.link_timeout)?
+            .with_html_content_types(html_content_types.clone())
+            .annotate_pages(pages)
+            .await?;
+    }
 
     emit_progress(
         &event_sender,
         snapshot_from_pages(
             RunStage::GeneratingReport,
             "Generating crawl report".to_string(),
-            &crawler.pages,
+            pages,
+            &html_content_types,
             unique_links.len(),
             unique_links.len(),
-        ),
+        )?,
     );
-    let report = Reporter::generate_report(url, &crawler.pages);
+    let mut report = if in_memory {
+        Reporter::generate_report_from_pages(url, pages_in_memory, &html_content_types)?
+    } else {
+        Reporter::generate_report(url, pages, &html_content_types)?
+    };
+    report.limit_reached = request_limits
+        .and_then(|limits| limits.reached())
+        .or_else(|| {
+            runtime
+                .max_duration
+                .filter(|_| crawler.timed_out())
+                .map(|max_duration| {
+                    format!(
+                        "time limit of {max_duration} reached after {} page(s)",
+                        report.summary.total_pages
+                    )
+                })
+        });
+    report.explain = crawler.explanations();
+    report.errors = std::mem::take(&mut crawler.errors);
 
     let mut complete = ProgressSnapshot::new(RunStage::Completed, "Report ready");
     complete.pages_crawled = report.summary.total_pages;
@@ -145,27 +500,531 @@ pub(crate) async fn execute_scan(
     complete.total_links = unique_links.len();
     complete.summary = report.summary.clone();
     emit_progress(&event_sender, complete);
-    emit_event(&event_sender, RunEvent::ReportReady(report.clone()));
 
-    Ok(report)
+    Ok(ScanOutcome { report, page_store })
 }
 
 async fn run_cli(
     runtime: RuntimeOptions,
     loaded_config: LoadedConfig,
+    recrawl: Option<Recrawl>,
     output_format: OutputFormat,
 ) -> Result<()> {
+    let outcome = scan_and_report(&runtime, loaded_config, recrawl, output_format).await;
+    if let Some(path) = &runtime.result_file {
+        let result = match &outcome {
+            Ok((report, checks)) => RunResult::from_report(report, checks.clone()),
+            Err(error) => RunResult::from_error(error),
+        };
+        result.save(Path::new(path))?;
+        emit_status_line(
+            output_format,
+            format!("Result written to: {}", path.bright_green()),
+        );
+    }
+
+    let (_, checks) = outcome?;
+    if let Some(failed) = checks.iter().find(|check| !check.passed) {
+        anyhow::bail!("{}", failed.detail);
+    }
+
+    Ok(())
+}
+
+/// Run the scan, print and save its report, and evaluate the run's pass/fail checks
+async fn scan_and_report(
+    runtime: &RuntimeOptions,
+    loaded_config: LoadedConfig,
+    recrawl: Option<Recrawl>,
+    output_format: OutputFormat,
+) -> Result<(CrawlReport, Vec<ResultCheck>)> {
     maybe_emit_update_notice(output_format).await;
     print_config_source(&loaded_config, runtime.verbose, output_format);
-    print_run_intro(&runtime, output_format);
+    print_run_intro(runtime, output_format);
+
+    let baseline = runtime
+        .baseline
+        .as_deref()
+        .map(|path| Baseline::from_file(Path::new(path)))
+        .transpose()?;
+
+    // JSONL lines go to the --save file when there is one, and to stdout otherwise
+    let (page_sender, jsonl_task) = if output_format == OutputFormat::Jsonl {
+        let out: Box<dyn std::io::Write + Send> = match &runtime.save {
+            Some(path) => Box::new(
+                std::fs::File::create(path).with_context(|| format!("Failed to create {path}"))?,
+            ),
+            None => Box::new(std::io::stdout()),
+        };
+        let writer = JsonlWriter::new(out, runtime.json_style, runtime.max_issues_per_type);
+        let (sender, task) = spawn_jsonl_writer(
+            writer,
+            scan_path_rules(runtime)?,
+            runtime.resolved_html_content_types(),
+        );
+        (Some(sender), Some(task))
+    } else {
+        (None, None)
+    };
 
-    let report = execute_scan(&runtime, None, !output_format.is_json()).await?;
-    output_report(&report, output_format)?;
-    save_report(&report, &runtime, output_format)?;
+    let mut scan = match runtime.progress {
+        ProgressFormat::Bar | ProgressFormat::None => {
+            execute_scan_streaming(
+                runtime,
+                None,
+                shows_progress_bars(runtime, output_format),
+                page_sender,
+            )
+            .await?
+        }
+        ProgressFormat::Json => {
+            let (sender, writer) = spawn_json_progress(runtime.max_pages);
+            let scan = execute_scan_streaming(runtime, Some(sender), false, page_sender).await;
+            // The scan dropped its sender, so the writer finishes after the last line
+            let _ = writer.await;
+            scan?
+        }
+    };
+    // The crawler dropped the page sender too, so the task ends after the last page
+    let jsonl_writer = match jsonl_task {
+        Some(task) => Some(task.await??),
+        None => None,
+    };
+    // --page-store is refused with --recrawl-urls-from, so the pages are in the report
+    if let Some(recrawl) = recrawl {
+        emit_status_line(
+            output_format,
+            format!(
+                "{} {} of {} page(s)",
+                "Recrawled:".bright_white().bold(),
+                scan.report.pages.len(),
+                recrawl.prior.pages.len()
+            ),
+        );
+        scan.report = recrawl.merge(scan.report, &runtime.resolved_html_content_types())?;
+    }
+    record_trend(&scan.report, scan.pages(), runtime)?;
+    let suppressed = baseline
+        .as_ref()
+        .map(|baseline| {
+            let ScanOutcome { report, page_store } = &mut scan;
+            let pages: &mut dyn PageStore = match page_store {
+                Some(store) => store.as_mut(),
+                None => &mut report.pages,
+            };
+            baseline.suppress_known_issues(
+                pages,
+                &mut report.summary,
+                &runtime.resolved_html_content_types(),
+            )
+        })
+        .transpose()?;
+    capture_screenshots(&mut scan, runtime, output_format).await?;
+
+    match jsonl_writer {
+        Some(writer) => writer.finish(&scan.report, scan.pages())?,
+        None => output_report(&scan, runtime, output_format)?,
+    }
+    save_report(&scan, runtime, output_format)?;
+    save_report_dir(&scan, runtime, output_format)?;
+    write_baseline(&scan, runtime, output_format)?;
+    write_sitemap(&scan, runtime, output_format)?;
+    write_outlines(&scan, runtime, output_format)?;
+
+    let checks = suppressed
+        .map(|suppressed| check_against_baseline(&scan.report, suppressed, output_format))
+        .into_iter()
+        .collect();
+    Ok((scan.report, checks))
+}
+
+async fn compare_sites(runtime: &RuntimeOptions, left: &str, right: &str) -> Result<()> {
+    validate_url(left)?;
+    validate_url(right)?;
+    let output_format = runtime.output.unwrap_or(OutputFormat::Text);
+
+    let mut pages = Vec::with_capacity(2);
+    for url in [left, right] {
+        emit_status_line(
+            output_format,
+            format!("{} {}", "Crawling:".bright_white().bold(), url),
+        );
+        // Only crawled pages are compared, so links and SEO rules are not checked
+        let site_runtime = RuntimeOptions {
+            url: Some(url.to_string()),
+            // One state file can't hold two crawls
+            resume: None,
+            ..runtime.clone()
+        };
+        let mut crawler = build_crawler(&site_runtime)?;
+        if shows_progress_bars(runtime, output_format) {
+            crawler.enable_progress_bar();
+        }
+        crawler.crawl().await?;
+        pages.push(crawler.pages);
+    }
+
+    let comparison = SiteComparison::new(left, &pages[0], right, &pages[1]);
+    match output_format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&comparison)?),
+        OutputFormat::Jsonl => println!("{}", serde_json::to_string(&comparison)?),
+        OutputFormat::Text => comparison.print_text_report(),
+    }
+
+    let differences = comparison.total_differences();
+    if differences > 0 {
+        anyhow::bail!("Found {differences} difference(s) between {left} and {right}");
+    }
 
     Ok(())
 }
 
+async fn analyze_logs(
+    runtime: &RuntimeOptions,
+    log: &Path,
+    format: LogFormat,
+    report: Option<&Path>,
+    limit: usize,
+) -> Result<()> {
+    let output_format = runtime.output.unwrap_or(OutputFormat::Text);
+    let (entries, skipped) = logs::read_log(log, format)?;
+    if skipped > 0 {
+        tracing::warn!(skipped, log = %log.display(), "Skipped unparseable access log lines");
+    }
+
+    let pages = match report {
+        Some(report) => {
+            let content = std::fs::read_to_string(report)
+                .with_context(|| format!("Failed to read report: {}", report.display()))?;
+            serde_json::from_str::<CrawlReport>(&content)
+                .with_context(|| format!("Failed to parse report: {}", report.display()))?
+                .pages
+        }
+        None => {
+            let url = runtime.url.as_deref().ok_or_else(|| {
+                anyhow::anyhow!("Pass a URL to crawl or a saved report with --report")
+            })?;
+            validate_url(url)?;
+            emit_status_line(
+                output_format,
+                format!("{} {}", "Crawling:".bright_white().bold(), url),
+            );
+            let mut crawler = build_crawler(runtime)?;
+            if shows_progress_bars(runtime, output_format) {
+                crawler.enable_progress_bar();
+            }
+            crawler.crawl().await?;
+            crawler.pages
+        }
+    };
+
+    let budget = CrawlBudgetReport::new(&entries, &pages, &runtime.resolved_html_content_types());
+    match output_format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&budget)?),
+        OutputFormat::Jsonl => println!("{}", serde_json::to_string(&budget)?),
+        OutputFormat::Text => budget.print_text_report(limit),
+    }
+
+    Ok(())
+}
+
+async fn validate_migration(runtime: &RuntimeOptions, mappings: &Path) -> Result<()> {
+    let output_format = runtime.output.unwrap_or(OutputFormat::Text);
+    let mappings = migration::read_mappings(mappings)?;
+    emit_status_line(
+        output_format,
+        format!(
+            "{} {} mapping(s)",
+            "Validating:".bright_white().bold(),
+            mappings.len()
+        ),
+    );
+
+    let mut validator = MigrationValidator::new(runtime.concurrency)?;
+    validator.set_timeout(runtime.link_timeout)?;
+    if runtime.insecure || runtime.ca_cert.is_some() {
+        validator.set_tls(runtime.tls_options()?)?;
+    }
+    if !runtime.cookies.is_empty() || runtime.cookies_file.is_some() {
+        // `--cookie` cookies go to each site the old URLs are on
+        let origins: BTreeSet<String> = mappings
+            .iter()
+            .filter_map(|mapping| Url::parse(&mapping.old_url).ok())
+            .map(|url| url.origin().ascii_serialization())
+            .collect();
+        let origins: Vec<&str> = origins.iter().map(String::as_str).collect();
+        validator.set_cookie_jar(cookies::build_cookie_jar(
+            &runtime.cookies,
+            runtime.cookies_file.as_deref().map(Path::new),
+            &origins,
+        )?)?;
+    }
+    let report = validator.validate(&mappings).await;
+    match output_format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Jsonl => println!("{}", serde_json::to_string(&report)?),
+        OutputFormat::Text => report.print_text_report(),
+    }
+
+    if report.failed > 0 {
+        anyhow::bail!(
+            "{} of {} mapping(s) failed validation",
+            report.failed,
+            report.total
+        );
+    }
+
+    Ok(())
+}
+
+async fn check_health(
+    runtime: &RuntimeOptions,
+    url: &str,
+    thresholds: &HealthThresholds,
+) -> Result<()> {
+    validate_url(url)?;
+    let report = health::probe(url, runtime.timeout, &runtime.tls_options()?, thresholds).await?;
+    match runtime.output.unwrap_or(OutputFormat::Text) {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Jsonl => println!("{}", serde_json::to_string(&report)?),
+        OutputFormat::Text => report.print_text_report(),
+    }
+
+    if !report.passed {
+        anyhow::bail!("Health check failed: {}", report.failed_checks().join(", "));
+    }
+
+    Ok(())
+}
+
+async fn verify_fixes(runtime: &RuntimeOptions, issues: &Path) -> Result<()> {
+    let output_format = runtime.output.unwrap_or(OutputFormat::Text);
+    let issues = fixes::read_issue_list(issues)?;
+    let mut urls = fixes::pages_to_recrawl(&issues);
+    for url in &urls {
+        validate_url(url)?;
+    }
+    emit_status_line(
+        output_format,
+        format!(
+            "{} {} issue(s) on {} page(s)",
+            "Verifying:".bright_white().bold(),
+            issues.len(),
+            urls.len()
+        ),
+    );
+
+    // Only the listed pages are fetched, the way --url-file crawls its list
+    let page_runtime = RuntimeOptions {
+        depth: 0,
+        max_pages: urls.len(),
+        additional_urls: urls.split_off(1),
+        url: urls.pop(),
+        resume: None,
+        page_store: None,
+        ..runtime.clone()
+    };
+    let fresh = execute_scan(
+        &page_runtime,
+        None,
+        shows_progress_bars(runtime, output_format),
+    )
+    .await?;
+
+    let report = FixReport::new(issues, &fresh);
+    match output_format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Jsonl => println!("{}", serde_json::to_string(&report)?),
+        OutputFormat::Text => report.print_text_report(),
+    }
+
+    if report.persisting > 0 {
+        anyhow::bail!("{} of {} issue(s) persist", report.persisting, report.total);
+    }
+
+    Ok(())
+}
+
+async fn capture_screenshots(
+    scan: &mut ScanOutcome,
+    runtime: &RuntimeOptions,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let Some(mode) = runtime.screenshots else {
+        return Ok(());
+    };
+    let Some(save) = &runtime.save else {
+        anyhow::bail!("Screenshots are stored next to the saved report, so they require --save");
+    };
+
+    let dir = screenshot::screenshot_dir_for(Path::new(save));
+    let screenshotter = Screenshotter::new(runtime.screenshot_browser.as_deref())?
+        .with_html_content_types(runtime.resolved_html_content_types());
+    let pages: &mut dyn PageStore = match &mut scan.page_store {
+        Some(store) => store.as_mut(),
+        None => &mut scan.report.pages,
+    };
+    let captured = screenshotter.capture_pages(pages, mode, &dir).await?;
+    emit_status_line(
+        output_format,
+        format!(
+            "Captured {captured} screenshot(s) in: {}",
+            dir.display().to_string().bright_green()
+        ),
+    );
+
+    Ok(())
+}
+
+/// Append this run's summary metrics to the history in the configured state directory
+pub(crate) fn record_trend(
+    report: &CrawlReport,
+    pages: &dyn PageStore,
+    runtime: &RuntimeOptions,
+) -> Result<()> {
+    if let Some(state_dir) = &runtime.state_dir {
+        TrendStore::new(Path::new(state_dir)).record(&TrendPoint::from_report(report, pages)?)?;
+    }
+
+    Ok(())
+}
+
+fn show_trends(
+    state_dir: &Path,
+    url: Option<&str>,
+    limit: usize,
+    html: Option<&str>,
+) -> Result<()> {
+    let history = TrendStore::new(state_dir).load()?;
+    let points = trends::points_for_site(&history, url);
+    if points.is_empty() && url.is_some() && !history.is_empty() {
+        anyhow::bail!("No runs recorded for {}", url.unwrap_or_default());
+    }
+
+    print!("{}", trends::render_text(&points, limit));
+
+    if let Some(html) = html {
+        std::fs::write(html, trends::render_html(&points))
+            .with_context(|| format!("Failed to write trends page: {html}"))?;
+        println!(
+            "\n{} {}",
+            "Trends page written to:".bright_white().bold(),
+            html.bright_green()
+        );
+    }
+
+    Ok(())
+}
+
+fn save_report_dir(
+    scan: &ScanOutcome,
+    args: &RuntimeOptions,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if let Some(dir) = &args.save_dir {
+        let written = report_dir::save_report_dir(
+            &scan.report,
+            scan.pages(),
+            Path::new(dir),
+            args.json_style,
+        )?;
+        emit_status_line(
+            output_format,
+            format!(
+                "{written} page report(s) and {} written to: {}",
+                report_dir::INDEX_FILE_NAME,
+                dir.bright_green()
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+fn write_baseline(
+    scan: &ScanOutcome,
+    args: &RuntimeOptions,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if let Some(filename) = &args.write_baseline {
+        let baseline = Baseline::from_pages(scan.pages())?;
+        baseline.save(Path::new(filename))?;
+        emit_status_line(
+            output_format,
+            format!(
+                "Baseline with {} issue(s) written to: {}",
+                baseline.issues.len(),
+                filename.bright_green()
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+fn write_sitemap(
+    scan: &ScanOutcome,
+    args: &RuntimeOptions,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if let Some(filename) = &args.emit_sitemap {
+        Reporter::save_sitemap(
+            &scan.report.start_url,
+            scan.pages(),
+            &args.resolved_html_content_types(),
+            filename,
+        )?;
+        emit_status_line(
+            output_format,
+            format!("Sitemap written to: {}", filename.bright_green()),
+        );
+    }
+
+    Ok(())
+}
+
+fn write_outlines(
+    scan: &ScanOutcome,
+    args: &RuntimeOptions,
+    output_format: OutputFormat,
+) -> Result<()> {
+    if let Some(dir) = &args.outline_dir {
+        let written = outline::save_markdown_outlines(scan.pages(), Path::new(dir))?;
+        emit_status_line(
+            output_format,
+            format!(
+                "{written} heading outline(s) written to: {}",
+                dir.bright_green()
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+/// Passes when the report has no errors or warnings beyond the baseline's known issues
+fn check_against_baseline(
+    report: &CrawlReport,
+    suppressed: usize,
+    output_format: OutputFormat,
+) -> ResultCheck {
+    emit_status_line(
+        output_format,
+        format!("Suppressed {suppressed} known issue(s) from baseline"),
+    );
+
+    let new_issues = report.summary.errors + report.summary.warnings;
+    ResultCheck {
+        name: "baseline".to_string(),
+        passed: new_issues == 0,
+        detail: if new_issues > 0 {
+            format!("Found {new_issues} new error(s) or warning(s) not recorded in the baseline")
+        } else {
+            format!("No new errors or warnings; suppressed {suppressed} known issue(s)")
+        },
+    }
+}
+
 async fn maybe_emit_update_notice(output_format: OutputFormat) {
     let notice = tokio::time::timeout(Duration::from_millis(500), update::check_for_update())
         .await
@@ -179,7 +1038,7 @@ async fn maybe_emit_update_notice(output_format: OutputFormat) {
 }
 
 enum LoadedConfig {
-    Explicit { path: PathBuf, config: Config },
+    Explicit { paths: Vec<PathBuf>, config: Config },
     Default(Config),
     None,
 }
@@ -191,19 +1050,37 @@ impl LoadedConfig {
             Self::None => None,
         }
     }
+
+    fn with_profile(self, profile: &str) -> Result<Self> {
+        Ok(match self {
+            Self::Explicit { paths, config } => Self::Explicit {
+                paths,
+                config: config.with_profile(profile)?,
+            },
+            Self::Default(config) => Self::Default(config.with_profile(profile)?),
+            Self::None => {
+                anyhow::bail!("Profile '{profile}' requested but no config file was found")
+            }
+        })
+    }
 }
 
 fn load_config(args: &Cli) -> Result<LoadedConfig> {
-    if let Some(config_path) = &args.config {
-        let path = PathBuf::from(config_path);
-        let config = Config::from_file(&path)?;
-        return Ok(LoadedConfig::Explicit { path, config });
-    }
+    let loaded = if !args.config.is_empty() {
+        let paths: Vec<PathBuf> = args.config.iter().map(PathBuf::from).collect();
+        let config = Config::from_files(&paths)?;
+        LoadedConfig::Explicit { paths, config }
+    } else {
+        match Config::from_default_paths()? {
+            Some(config) => LoadedConfig::Default(config),
+            None => LoadedConfig::None,
+        }
+    };
 
-    Ok(match Config::from_default_paths()? {
-        Some(config) => LoadedConfig::Default(config),
-        None => LoadedConfig::None,
-    })
+    match &args.profile {
+        Some(profile) => loaded.with_profile(profile),
+        None => Ok(loaded),
+    }
 }
 
 fn print_config_source(config: &LoadedConfig, verbose: bool, output_format: OutputFormat) {
@@ -212,12 +1089,16 @@ fn print_config_source(config: &LoadedConfig, verbose: bool, output_format: Outp
     }
 
     match config {
-        LoadedConfig::Explicit { path, .. } => emit_status_line(
+        LoadedConfig::Explicit { paths, .. } => emit_status_line(
             output_format,
             format!(
                 "{} {}",
                 "Loading config from:".bright_white().bold(),
-                path.display()
+                paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
             ),
         ),
         LoadedConfig::Default(_) => emit_status_line(
@@ -239,6 +1120,33 @@ fn validate_url(url: &str) -> Result<()> {
     Ok(())
 }
 
+/// List mode: crawl the URLs in `path` after any given as arguments, at depth 0 so no
+/// discovered link is followed. Blank lines and `#` comments are skipped.
+fn apply_url_file(runtime: &mut RuntimeOptions, path: &Path) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read URL file: {}", path.display()))?;
+    let mut urls: Vec<String> = runtime
+        .url
+        .take()
+        .into_iter()
+        .chain(std::mem::take(&mut runtime.additional_urls))
+        .collect();
+    for line in contents.lines().map(str::trim) {
+        if !line.is_empty() && !line.starts_with('#') && !urls.iter().any(|url| url == line) {
+            urls.push(line.to_string());
+        }
+    }
+    if urls.is_empty() {
+        anyhow::bail!("URL file {} lists no URLs", path.display());
+    }
+
+    runtime.depth = 0;
+    runtime.max_pages = runtime.max_pages.max(urls.len());
+    runtime.additional_urls = urls.split_off(1);
+    runtime.url = urls.pop();
+    Ok(())
+}
+
 fn validate_required_url(runtime: &RuntimeOptions, mode_name: &str) -> Result<()> {
     let Some(url) = runtime.url.as_deref() else {
         anyhow::bail!(
@@ -287,39 +1195,130 @@ fn build_crawler(args: &RuntimeOptions) -> Result<Crawler> {
         requests_per_second: args.rate_limit,
         concurrent_requests: args.concurrency,
         respect_robots_txt: args.respect_robots_txt,
+        audit_vary: args.audit_vary,
+        include: args.include.clone(),
+        exclude: args.exclude.clone(),
     };
 
-    Crawler::new(
-        args.url
-            .as_deref()
-            .ok_or_else(|| anyhow::anyhow!("A URL is required to build the crawler"))?,
-        config,
-    )
+    let url = args
+        .url
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("A URL is required to build the crawler"))?;
+    let start_urls: Vec<&str> = std::iter::once(url)
+        .chain(args.additional_urls.iter().map(String::as_str))
+        .collect();
+    let mut crawler = Crawler::new(&start_urls, config)?;
+    crawler.set_timeout(args.timeout)?;
+    if args.insecure || args.ca_cert.is_some() {
+        if args.insecure {
+            tracing::warn!("TLS certificate verification is off; any certificate is accepted");
+        }
+        crawler.set_tls(args.tls_options()?)?;
+    }
+    if !args.cookies.is_empty() || args.cookies_file.is_some() {
+        crawler.set_cookie_jar(cookies::build_cookie_jar(
+            &args.cookies,
+            args.cookies_file.as_deref().map(Path::new),
+            &start_urls,
+        )?)?;
+    }
+    if let Some(login) = &args.login {
+        crawler.set_login(login.clone())?;
+    }
+    crawler.set_query_normalization(QueryNormalization {
+        strip_tracking: args.strip_tracking_params,
+        sort: args.sort_query,
+        ignored: args.ignore_params.clone(),
+    });
+    crawler.set_html_content_types(args.resolved_html_content_types());
+    crawler.set_gentle(args.gentle);
+    crawler.set_auto_throttle(args.auto_throttle);
+    crawler.set_retry_policy(args.retry_policy());
+    crawler.set_robots_agent(args.robots_agent.clone());
+    crawler.set_use_sitemap(args.use_sitemap);
+    crawler.set_respect_nofollow(args.respect_nofollow);
+    crawler.set_max_sitemaps(args.max_sitemaps);
+    crawler.set_max_body_bytes(args.max_body_bytes.0);
+    crawler.set_trace_redirects(args.trace_redirects)?;
+    crawler.set_redirect_chain_limit(args.redirect_chain_limit);
+    if let Some(path) = &args.resume {
+        crawler.set_state_file(PathBuf::from(path))?;
+    }
+    if let Some(dir) = &args.cache_dir {
+        crawler.set_http_cache(HttpCache::new(dir)?);
+    }
+    if let Some(path) = &args.page_store {
+        if args.resume.is_some() {
+            anyhow::bail!("--page-store can't be combined with --resume");
+        }
+        crawler.set_page_store(Box::new(SqlitePageStore::create(Path::new(path))?));
+    }
+    if let Some(dir) = &args.save_html {
+        crawler.set_html_snapshots(HtmlSnapshots::new(dir)?);
+    }
+    if !args.explain.is_empty() {
+        crawler.set_explain(&args.explain);
+    }
+    if args.render == RenderMode::Js {
+        crawler.set_renderer(JsRenderer::new(args.screenshot_browser.as_deref())?);
+    }
+    if let Some(max_duration) = args.max_duration {
+        crawler.set_max_duration(max_duration.duration());
+    }
+    crawler.set_block_private_ips(args.block_private_ips)?;
+    if args.max_requests.is_some() || args.max_bandwidth.is_some() {
+        crawler.set_request_limits(Arc::new(RequestLimits::new(
+            args.max_requests,
+            args.max_bandwidth,
+        )))?;
+    }
+    Ok(crawler)
 }
 
-fn collect_unique_links(crawler: &Crawler) -> HashSet<String> {
-    crawler
-        .pages
-        .values()
-        .flat_map(|page| page.links.iter().map(|link| link.url.clone()))
-        .collect()
+fn build_link_checker(args: &RuntimeOptions) -> Result<LinkChecker> {
+    let mut link_checker = LinkChecker::with_concurrency(args.concurrency);
+    link_checker.set_check_scope(args.check_links);
+    link_checker.set_domain_rules(
+        args.never_check_domains.clone(),
+        args.always_check_domains.clone(),
+    );
+    link_checker.set_respect_robots_for_links(args.respect_robots_for_links);
+    link_checker.set_audit_tracking_links(args.audit_tracking_links);
+    link_checker.set_html_content_types(args.resolved_html_content_types());
+    link_checker.set_block_private_ips(args.block_private_ips)?;
+    link_checker.set_robots_agent(args.robots_agent.clone());
+    link_checker.set_retry_policy(args.retry_policy());
+    link_checker.set_timeout(args.link_timeout)?;
+    if args.insecure || args.ca_cert.is_some() {
+        link_checker.set_tls(args.tls_options()?)?;
+    }
+    link_checker.set_external_policy(args.external_check_policy())?;
+    Ok(link_checker)
 }
 
 fn snapshot_from_pages(
     stage: RunStage,
     message: String,
-    pages: &HashMap<String, PageInfo>,
+    pages: &dyn PageStore,
+    html_content_types: &HtmlContentTypes,
     links_checked: usize,
     total_links: usize,
-) -> ProgressSnapshot {
-    let summary = Reporter::summarize_pages(pages);
+) -> Result<ProgressSnapshot> {
+    let summary = Reporter::summarize_pages(pages, html_content_types)?;
     let mut snapshot = ProgressSnapshot::new(stage, message);
-    snapshot.pages_crawled = pages.len();
+    snapshot.pages_crawled = summary.total_pages;
     snapshot.links_discovered = summary.total_links;
     snapshot.links_checked = links_checked;
     snapshot.total_links = total_links;
     snapshot.summary = summary;
-    snapshot
+    Ok(snapshot)
+}
+
+/// Whether CLI runs draw the crawl and link check progress bars
+fn shows_progress_bars(runtime: &RuntimeOptions, output_format: OutputFormat) -> bool {
+    runtime
+        .progress
+        .shows_bars(output_format, std::io::stderr().is_terminal())
 }
 
 fn emit_progress(sender: &Option<RunEventSender>, snapshot: ProgressSnapshot) {
@@ -332,25 +1331,39 @@ fn emit_event(sender: &Option<RunEventSender>, event: RunEvent) {
     }
 }
 
-fn output_report(report: &CrawlReport, output_format: OutputFormat) -> Result<()> {
+fn output_report(
+    scan: &ScanOutcome,
+    args: &RuntimeOptions,
+    output_format: OutputFormat,
+) -> Result<()> {
+    let report = &scan.report;
     match output_format {
         OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(report)?;
-            println!("{}", json);
+            let mut stdout = std::io::stdout().lock();
+            Reporter::write_json_report(report, scan.pages(), &mut stdout, args.json_style)?;
+            writeln!(stdout)?;
         }
-        OutputFormat::Text => Reporter::print_text_report(report),
+        OutputFormat::Jsonl => println!("{}", json_style::to_string(report, args.json_style)?),
+        OutputFormat::Text => Reporter::print_text_report(
+            report,
+            scan.pages(),
+            &TextReportOptions::for_stdout(args.max_pages_shown),
+        )?,
     }
 
     Ok(())
 }
 
 fn save_report(
-    report: &CrawlReport,
+    scan: &ScanOutcome,
     args: &RuntimeOptions,
     output_format: OutputFormat,
 ) -> Result<()> {
     if let Some(filename) = &args.save {
-        Reporter::save_json_report(report, filename)?;
+        // JSONL output was already written to the file, line by line
+        if output_format != OutputFormat::Jsonl {
+            Reporter::save_json_report(&scan.report, scan.pages(), filename, args.json_style)?;
+        }
         emit_status_line(
             output_format,
             format!("Report saved to: {}", filename.bright_green()),
@@ -0,0 +1,478 @@
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Units accepted after a size, from largest to smallest so `Display` picks the largest exact one
+const BYTE_UNITS: [(&str, u64); 9] = [
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("KB", 1_000),
+    ("B", 1),
+];
+
+/// Units accepted in a duration, from largest to smallest so `Display` writes e.g. `1h30m`
+const DURATION_UNITS: [(&str, u64); 3] = [("h", 3600), ("m", 60), ("s", 1)];
+
+/// A length of time written as `10m`, `1h30m`, `90s`, or a plain number of seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HumanDuration(pub Duration);
+
+impl HumanDuration {
+    pub const fn duration(self) -> Duration {
+        self.0
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            bail!("Invalid duration '': expected e.g. 10m or 1h30m");
+        }
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Ok(Self(Duration::from_secs(seconds)));
+        }
+
+        let mut seconds: u64 = 0;
+        let mut rest = value;
+        while !rest.is_empty() {
+            let split = rest
+                .find(|ch: char| !ch.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let (number, tail) = rest.split_at(split);
+            let unit_end = tail
+                .find(|ch: char| ch.is_ascii_digit())
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(unit_end);
+            let number: u64 = number.parse().with_context(|| {
+                format!("Invalid duration '{value}': expected e.g. 10m or 1h30m")
+            })?;
+            let multiplier = DURATION_UNITS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(unit.trim()))
+                .map(|(_, multiplier)| *multiplier)
+                .ok_or_else(|| anyhow!("Invalid duration unit '{unit}': use h, m, or s"))?;
+            seconds = number
+                .checked_mul(multiplier)
+                .and_then(|part| seconds.checked_add(part))
+                .ok_or_else(|| anyhow!("Duration '{value}' is too long"))?;
+            rest = tail;
+        }
+        Ok(Self(Duration::from_secs(seconds)))
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut seconds = self.0.as_secs();
+        if seconds == 0 {
+            return write!(f, "0s");
+        }
+        for (name, multiplier) in DURATION_UNITS {
+            if seconds >= multiplier {
+                write!(f, "{}{name}", seconds / multiplier)?;
+                seconds %= multiplier;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for HumanDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Seconds(u64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Seconds(seconds) => Ok(Self(Duration::from_secs(seconds))),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// A number of bytes written as `500MB`, `1.5GB`, `64KiB`, or a plain byte count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub const fn bytes(self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let split = value
+            .find(|ch: char| !(ch.is_ascii_digit() || ch == '.'))
+            .unwrap_or(value.len());
+        let (number, unit) = value.split_at(split);
+        let number: f64 = number
+            .parse()
+            .with_context(|| format!("Invalid size '{value}': expected e.g. 500MB"))?;
+        let unit = unit.trim();
+        let multiplier = if unit.is_empty() {
+            1
+        } else {
+            BYTE_UNITS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+                .map(|(_, multiplier)| *multiplier)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Invalid size unit '{unit}': use B, KB, MB, GB, TB, KiB, MiB, GiB, or TiB"
+                    )
+                })?
+        };
+
+        let bytes = number * multiplier as f64;
+        if !bytes.is_finite() || bytes > u64::MAX as f64 {
+            bail!("Size '{value}' is too large");
+        }
+        Ok(Self(bytes.round() as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, multiplier) = BYTE_UNITS
+            .iter()
+            .find(|(_, multiplier)| self.0 > 0 && self.0.is_multiple_of(*multiplier))
+            .unwrap_or(&("B", 1));
+        write!(f, "{}{name}", self.0 / multiplier)
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bytes(u64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Bytes(bytes) => Ok(Self(bytes)),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Caps on the requests a scan sends and the response bytes it downloads, shared by the
+/// crawler and the link checker so one budget covers the whole run
+#[derive(Debug, Default)]
+pub struct RequestLimits {
+    max_requests: Option<usize>,
+    max_bandwidth: Option<ByteSize>,
+    requests: AtomicUsize,
+    bytes: AtomicU64,
+    /// Set once a request was refused, so reaching a limit exactly doesn't count as cutting the scan short
+    refused: AtomicBool,
+}
+
+impl RequestLimits {
+    pub fn new(max_requests: Option<usize>, max_bandwidth: Option<ByteSize>) -> Self {
+        Self {
+            max_requests,
+            max_bandwidth,
+            ..Default::default()
+        }
+    }
+
+    /// Reserve one request, or return false once either limit has been reached
+    pub fn try_acquire(&self) -> bool {
+        let within_bandwidth = self
+            .max_bandwidth
+            .is_none_or(|max| self.bytes.load(Ordering::SeqCst) < max.bytes());
+        let acquired = within_bandwidth
+            && self
+                .requests
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |requests| {
+                    self.max_requests
+                        .is_none_or(|max| requests < max)
+                        .then_some(requests + 1)
+                })
+                .is_ok();
+
+        if !acquired {
+            self.refused.store(true, Ordering::SeqCst);
+        }
+        acquired
+    }
+
+    /// Count downloaded response bytes against the bandwidth limit
+    pub fn record_bytes(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::SeqCst);
+    }
+
+    /// Count the body of a response whose content isn't needed. With a bandwidth limit the
+    /// body is read, so chunked and compressed responses count what was actually downloaded,
+    /// until the limit is passed; without one it's left unread and its declared length counted.
+    pub async fn record_body(&self, mut response: reqwest::Response) {
+        let Some(max) = self.max_bandwidth else {
+            self.record_bytes(response.content_length().unwrap_or_default());
+            return;
+        };
+        while let Ok(Some(chunk)) = response.chunk().await {
+            self.record_bytes(chunk.len() as u64);
+            if self.bytes() >= max.bytes() {
+                break;
+            }
+        }
+    }
+
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::SeqCst)
+    }
+
+    /// Describe the limit that cut the scan short, if a request had to be refused
+    pub fn reached(&self) -> Option<String> {
+        if !self.refused.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        match self.max_bandwidth {
+            Some(max) if self.bytes() >= max.bytes() => Some(format!(
+                "bandwidth limit of {max} reached after {} request(s)",
+                self.requests()
+            )),
+            _ => Some(format!(
+                "request limit of {} reached",
+                self.max_requests.unwrap_or_else(|| self.requests())
+            )),
+        }
+    }
+}
+
+/// A request that wasn't sent because the request or bandwidth limit was reached, e.g. a retry
+/// or the next hop of a redirect chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitReachedError;
+
+impl fmt::Display for LimitReachedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request or bandwidth limit reached")
+    }
+}
+
+impl Error for LimitReachedError {}
+
+/// Find a request refused by the limits anywhere in an error's chain of sources
+pub fn find_limit_reached<'a>(error: &'a (dyn Error + 'static)) -> Option<&'a LimitReachedError> {
+    std::iter::successors(Some(error), |&error| error.source())
+        .find_map(|error| error.downcast_ref::<LimitReachedError>())
+}
+
+/// Share of a batch's requests that may be throttled, fail, or get a 5xx before the crawl backs off
+const STRESS_ERROR_RATE: f64 = 0.1;
+
+/// How many times slower than usual a batch may answer before the crawl backs off
+const STRESS_LATENCY_FACTOR: u32 = 2;
+
+/// Adjusts how many pages the crawler fetches at once: halves it when the site answers with
+/// 429s, 5xx responses, failed requests, or slows down, and grows it by one again after each
+/// batch that looks healthy, never beyond the configured concurrency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoThrottle {
+    max: usize,
+    current: usize,
+    /// Moving average of the response times of batches without failures
+    typical_latency: Option<Duration>,
+}
+
+/// How one request of a batch went: its response time and status, or `None` when it failed
+pub type RequestSample = (Duration, Option<u16>);
+
+impl AutoThrottle {
+    /// Start at `max`; a site under no stress is crawled as fast as before
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            max,
+            current: max,
+            typical_latency: None,
+        }
+    }
+
+    /// Requests to send at once
+    pub fn concurrency(&self) -> usize {
+        self.current
+    }
+
+    /// Adjust to how a batch of requests went and return the new concurrency
+    pub fn record_batch(&mut self, samples: &[RequestSample]) -> usize {
+        if samples.is_empty() {
+            return self.current;
+        }
+
+        let failures = samples
+            .iter()
+            .filter(|(_, status)| status.is_none_or(|code| code == 429 || code >= 500))
+            .count();
+        let latency = samples
+            .iter()
+            .map(|(elapsed, _)| *elapsed)
+            .sum::<Duration>()
+            / samples.len() as u32;
+        let slow = self
+            .typical_latency
+            .is_some_and(|typical| latency > typical * STRESS_LATENCY_FACTOR);
+        if failures == 0 {
+            // A site that stays slower becomes the new normal instead of being throttled forever
+            self.typical_latency = Some(
+                self.typical_latency
+                    .map_or(latency, |typical| (typical * 4 + latency) / 5),
+            );
+        }
+
+        let previous = self.current;
+        if failures as f64 / samples.len() as f64 > STRESS_ERROR_RATE || slow {
+            self.current = (self.current / 2).max(1);
+        } else if self.current < self.max {
+            self.current += 1;
+        }
+        if self.current != previous {
+            tracing::info!(
+                concurrency = self.current,
+                previous,
+                failures,
+                latency_ms = latency.as_millis() as u64,
+                "Adjusted crawl concurrency"
+            );
+        }
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_size_parses_decimal_and_binary_units() {
+        assert_eq!("500MB".parse::<ByteSize>().unwrap(), ByteSize(500_000_000));
+        assert_eq!(
+            "1.5 gb".parse::<ByteSize>().unwrap(),
+            ByteSize(1_500_000_000)
+        );
+        assert_eq!("64KiB".parse::<ByteSize>().unwrap(), ByteSize(65_536));
+        assert_eq!("2048".parse::<ByteSize>().unwrap(), ByteSize(2048));
+        assert!("MB".parse::<ByteSize>().is_err());
+        assert!("10 parsecs".parse::<ByteSize>().is_err());
+    }
+
+    #[test]
+    fn test_byte_size_displays_largest_exact_unit() {
+        assert_eq!(ByteSize(500_000_000).to_string(), "500MB");
+        assert_eq!(ByteSize(1 << 30).to_string(), "1GiB");
+        assert_eq!(ByteSize(1234).to_string(), "1234B");
+        assert_eq!(ByteSize(0).to_string(), "0B");
+    }
+
+    #[test]
+    fn test_human_duration_parses_and_displays_compound_units() {
+        let parse = |value: &str| value.parse::<HumanDuration>().map(|parsed| parsed.0);
+        assert_eq!(parse("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse("45").unwrap(), Duration::from_secs(45));
+        assert!(parse("").is_err());
+        assert!(parse("m").is_err());
+        assert!(parse("10 fortnights").is_err());
+
+        assert_eq!(
+            HumanDuration(Duration::from_secs(5400)).to_string(),
+            "1h30m"
+        );
+        assert_eq!(HumanDuration(Duration::from_secs(90)).to_string(), "1m30s");
+        assert_eq!(HumanDuration(Duration::ZERO).to_string(), "0s");
+    }
+
+    #[test]
+    fn test_request_limits_refuse_requests_past_either_limit() {
+        let limits = RequestLimits::new(Some(2), None);
+        assert!(limits.try_acquire());
+        assert!(limits.try_acquire());
+        assert_eq!(limits.reached(), None);
+        assert!(!limits.try_acquire());
+        assert_eq!(limits.requests(), 2);
+        assert_eq!(
+            limits.reached().as_deref(),
+            Some("request limit of 2 reached")
+        );
+
+        let limits = RequestLimits::new(None, Some(ByteSize(1_000)));
+        assert!(limits.try_acquire());
+        limits.record_bytes(1_500);
+        assert!(!limits.try_acquire());
+        assert_eq!(
+            limits.reached().as_deref(),
+            Some("bandwidth limit of 1KB reached after 1 request(s)")
+        );
+    }
+
+    #[test]
+    fn test_auto_throttle_backs_off_under_stress_and_recovers() {
+        let ok = (Duration::from_millis(100), Some(200));
+        let mut throttle = AutoThrottle::new(8);
+        assert_eq!(throttle.record_batch(&[ok; 8]), 8);
+
+        // One 503 in four is too many
+        assert_eq!(
+            throttle.record_batch(&[ok, ok, ok, (Duration::from_millis(100), Some(503))]),
+            4
+        );
+        assert_eq!(throttle.record_batch(&[(Duration::from_secs(1), None)]), 2);
+        assert_eq!(throttle.record_batch(&[(Duration::ZERO, Some(429))]), 1);
+        assert_eq!(throttle.record_batch(&[(Duration::ZERO, Some(429))]), 1);
+
+        // Healthy batches add one request at a time, up to the configured concurrency
+        assert_eq!(throttle.record_batch(&[ok]), 2);
+        assert_eq!(throttle.record_batch(&[ok, ok]), 3);
+        assert_eq!(throttle.record_batch(&[]), 3);
+    }
+
+    #[test]
+    fn test_auto_throttle_backs_off_when_responses_slow_down() {
+        let mut throttle = AutoThrottle::new(4);
+        let batch = |millis| [(Duration::from_millis(millis), Some(200)); 4];
+        assert_eq!(throttle.record_batch(&batch(100)), 4);
+        assert_eq!(throttle.record_batch(&batch(180)), 4);
+        assert_eq!(throttle.record_batch(&batch(250)), 2);
+        assert_eq!(throttle.concurrency(), 2);
+        assert_eq!(AutoThrottle::new(0).concurrency(), 1);
+    }
+}
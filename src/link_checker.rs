@@ -1,25 +1,188 @@
-use crate::http_client::build_http_client;
-use crate::models::{IssueSeverity, IssueType, Link, PageInfo, SeoIssue};
+use crate::cli::{DEFAULT_ROBOTS_AGENT, LinkCheckScope};
+use crate::crawler::{TRACKING_PARAMS, param_name_matches};
+use crate::hosts;
+use crate::http_client::{
+    RetryPolicy, TlsOptions, build_crawl_client, build_http_client, build_public_only_client,
+    find_private_address, find_redirect_loop, get_honoring_retry_after,
+};
+use crate::limits::{RequestLimits, find_limit_reached};
+use crate::models::{
+    CrawlSummary, HtmlContentTypes, IssueSeverity, IssueType, Link, LinkSkipReason, SeoIssue,
+};
+use crate::page_store::PageStore;
 use crate::reporter::Reporter;
+use crate::robots::RobotsTxt;
 use crate::runtime::{ProgressSnapshot, RunEvent, RunEventSender, RunStage};
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use futures::{
     pin_mut,
     stream::{self, StreamExt},
 };
+use governor::{
+    Jitter, Quota, RateLimiter, clock::DefaultClock, state::InMemoryState, state::direct::NotKeyed,
+};
 use indicatif::{ProgressBar, ProgressStyle};
-use std::collections::HashMap;
+use reqwest::cookie::Jar;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use url::Url;
 
 const DEFAULT_CONCURRENT_CHECKS: usize = 20;
+/// Seconds a link check waits for a response
+pub const DEFAULT_LINK_TIMEOUT_SECS: u64 = 10;
+
+/// Parameters affiliate networks use to credit a referral, audited along with the
+/// click-tracking parameters in `TRACKING_PARAMS`
+const AFFILIATE_PARAMS: &[&str] = &[
+    "tag",
+    "ref",
+    "aff_id",
+    "affid",
+    "affiliate_id",
+    "aff_sub",
+    "irclickid",
+    "clickid",
+    "subid",
+    "awc",
+    "cjevent",
+    "ranMID",
+    "ranEAID",
+    "ranSiteID",
+];
+
+/// Names of the tracking and affiliate parameters in `url`'s query string, in order
+pub fn tracking_param_names(url: &str) -> Vec<String> {
+    let Ok(url) = Url::parse(url) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = Vec::new();
+    for (name, _) in url.query_pairs() {
+        let tracked = TRACKING_PARAMS
+            .iter()
+            .chain(AFFILIATE_PARAMS)
+            .any(|pattern| param_name_matches(pattern, &name));
+        if tracked && !names.iter().any(|seen| seen.eq_ignore_ascii_case(&name)) {
+            names.push(name.into_owned());
+        }
+    }
+    names
+}
+
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
+/// A share of external links to check, e.g. `20%`; which links are picked depends only on
+/// their URLs, so repeated runs check the same ones
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleRate(f64);
+
+impl SampleRate {
+    /// The rate as a percentage from 0 to 100
+    pub fn percent(self) -> f64 {
+        self.0
+    }
+
+    /// Whether `url` falls inside the sample
+    pub fn includes(self, url: &str) -> bool {
+        let digest = Sha256::digest(url.as_bytes());
+        let bucket = u64::from_be_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+        ((bucket % 10_000) as f64) < self.0 * 100.0
+    }
+}
+
+impl FromStr for SampleRate {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let percent: f64 = value
+            .trim()
+            .trim_end_matches('%')
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid sample '{value}': expected e.g. 20%"))?;
+        if !(percent > 0.0 && percent <= 100.0) {
+            bail!("Sample '{value}' must be above 0% and at most 100%");
+        }
+        Ok(Self(percent))
+    }
+}
+
+impl fmt::Display for SampleRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+impl Serialize for SampleRate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SampleRate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Percent(f64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Percent(percent) => percent.to_string().parse().map_err(de::Error::custom),
+            Raw::Text(text) => text.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+/// How links to other hosts are checked. They are checked after internal links, as a phase
+/// of their own, so slow or strict third-party hosts don't hold up the site's own links.
+#[derive(Debug, Clone)]
+pub struct ExternalCheckPolicy {
+    pub concurrency: usize,
+    /// Requests per second across all external hosts
+    pub rate_limit: Option<f64>,
+    pub retry_policy: RetryPolicy,
+    pub timeout: Duration,
+    /// Check only this share of external links and mark the rest as sampled out
+    pub sample: Option<SampleRate>,
+}
+
+struct ExternalChecks {
+    client: reqwest::Client,
+    rate_limiter: Option<(DirectRateLimiter, Duration)>,
+    policy: ExternalCheckPolicy,
+}
+
+/// The client and pacing one phase of link checks uses
+struct CheckPhase<'a> {
+    client: &'a reqwest::Client,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<&'a (DirectRateLimiter, Duration)>,
+    /// Skip links to private IP addresses; `client` refuses hostnames resolving to them
+    block_private_ips: bool,
+}
 
 #[derive(Clone)]
 enum LinkCheckOutcome {
     Reachable {
         status_code: u16,
         redirected_url: Option<String>,
+        content_type: Option<String>,
+        content_length: Option<u64>,
     },
     SkippedUnsupportedScheme,
+    SkippedByRobots,
+    SkippedByLimit,
+    SkippedBySample,
+    SkippedPrivateAddress,
+    RedirectLoop {
+        cycle: Vec<String>,
+    },
     TransportFailure {
         error: String,
     },
@@ -27,8 +190,26 @@ enum LinkCheckOutcome {
 
 pub struct LinkChecker {
     client: reqwest::Client,
+    /// Client for external links that refuses private addresses, with `block_private_ips`
+    public_only_client: Option<reqwest::Client>,
+    timeout_secs: u64,
+    cookie_jar: Option<Arc<Jar>>,
+    tls: TlsOptions,
     progress_bar: Option<ProgressBar>,
     concurrent_checks: usize,
+    check_scope: LinkCheckScope,
+    never_check_domains: Vec<String>,
+    always_check_domains: Vec<String>,
+    respect_robots_for_links: bool,
+    audit_tracking_links: bool,
+    block_private_ips: bool,
+    html_content_types: HtmlContentTypes,
+    robots_agent: String,
+    gentle: bool,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<(DirectRateLimiter, Duration)>,
+    external: Option<ExternalChecks>,
+    request_limits: Option<Arc<RequestLimits>>,
     progress_sender: Option<RunEventSender>,
 }
 
@@ -45,9 +226,27 @@ impl LinkChecker {
 
     pub fn with_concurrency(concurrent_checks: usize) -> Self {
         Self {
-            client: build_http_client(10).expect("Failed to build HTTP client"),
+            client: build_http_client(DEFAULT_LINK_TIMEOUT_SECS)
+                .expect("Failed to build HTTP client"),
+            public_only_client: None,
+            timeout_secs: DEFAULT_LINK_TIMEOUT_SECS,
+            cookie_jar: None,
+            tls: TlsOptions::default(),
             progress_bar: None,
             concurrent_checks: concurrent_checks.max(1),
+            check_scope: LinkCheckScope::All,
+            never_check_domains: Vec::new(),
+            always_check_domains: Vec::new(),
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            html_content_types: HtmlContentTypes::default(),
+            robots_agent: DEFAULT_ROBOTS_AGENT.to_string(),
+            gentle: false,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+            external: None,
+            request_limits: None,
             progress_sender: None,
         }
     }
@@ -69,157 +268,680 @@ impl LinkChecker {
         self.progress_sender = Some(sender);
     }
 
+    /// Restrict which links are checked; links outside the scope are left untouched
+    pub fn set_check_scope(&mut self, scope: LinkCheckScope) {
+        self.check_scope = scope;
+    }
+
+    /// Configure domain patterns that are never or always checked, regardless of scope.
+    /// A pattern matches the domain itself and any of its subdomains; a leading `*.` is optional.
+    pub fn set_domain_rules(&mut self, never_check: Vec<String>, always_check: Vec<String>) {
+        self.never_check_domains = never_check;
+        self.always_check_domains = always_check;
+    }
+
+    /// Consult each external host's robots.txt and skip links it disallows
+    pub fn set_respect_robots_for_links(&mut self, enabled: bool) {
+        self.respect_robots_for_links = enabled;
+    }
+
+    /// Check every link with tracking or affiliate parameters whatever the check scope, and
+    /// report redirects that drop those parameters before the destination
+    pub fn set_audit_tracking_links(&mut self, enabled: bool) {
+        self.audit_tracking_links = enabled;
+    }
+
+    /// Count link targets served as anything but these content types as downloads
+    pub fn set_html_content_types(&mut self, content_types: HtmlContentTypes) {
+        self.html_content_types = content_types;
+    }
+
+    /// Refuse to request external links, and their hosts' robots.txt, when they point to,
+    /// resolve to, or redirect to a private address, and mark them as skipped instead
+    pub fn set_block_private_ips(&mut self, enabled: bool) -> Result<()> {
+        self.block_private_ips = enabled;
+        if let Some(external) = &mut self.external {
+            external.client = Self::external_client(
+                external.policy.timeout.as_secs().max(1),
+                &self.tls,
+                enabled,
+                self.request_limits.clone(),
+            )?;
+        }
+        self.rebuild_client()
+    }
+
+    /// Match external robots.txt groups against this user-agent token instead of "scoutly"
+    pub fn set_robots_agent(&mut self, agent: impl Into<String>) {
+        self.robots_agent = agent.into();
+    }
+
+    /// Check links politely: pace requests to `interval` with random jitter, and wait out
+    /// `Retry-After` on 429 and 503 responses
+    pub fn set_gentle(&mut self, interval: Option<Duration>) {
+        self.gentle = true;
+        self.rate_limiter = interval.and_then(|interval| {
+            Some((RateLimiter::direct(Quota::with_period(interval)?), interval))
+        });
+    }
+
+    /// Retry link checks that time out, lose their connection, or get a 5xx response
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Leave links unchecked once the request or bandwidth limit shared with the crawl is
+    /// reached. Retries, redirects, and external robots.txt files count as requests too.
+    pub fn set_request_limits(&mut self, limits: Arc<RequestLimits>) -> Result<()> {
+        self.request_limits = Some(limits);
+        if let Some(external) = &mut self.external {
+            external.client = Self::external_client(
+                external.policy.timeout.as_secs().max(1),
+                &self.tls,
+                self.block_private_ips,
+                self.request_limits.clone(),
+            )?;
+        }
+        self.rebuild_client()
+    }
+
+    /// Give up on link checks that take longer than this many seconds
+    pub fn set_timeout(&mut self, timeout_secs: u64) -> Result<()> {
+        self.timeout_secs = timeout_secs;
+        self.rebuild_client()
+    }
+
+    /// Verify link targets' certificates against extra CAs, or not at all
+    pub fn set_tls(&mut self, tls: TlsOptions) -> Result<()> {
+        self.tls = tls;
+        if let Some(external) = &mut self.external {
+            external.client = Self::external_client(
+                external.policy.timeout.as_secs().max(1),
+                &self.tls,
+                self.block_private_ips,
+                self.request_limits.clone(),
+            )?;
+        }
+        self.rebuild_client()
+    }
+
+    /// Send the cookies shared with the crawl, so links to pages behind a login check as they load
+    pub fn set_cookie_jar(&mut self, jar: Arc<Jar>) -> Result<()> {
+        self.cookie_jar = Some(jar);
+        self.rebuild_client()
+    }
+
+    fn rebuild_client(&mut self) -> Result<()> {
+        self.client = build_crawl_client(
+            self.timeout_secs,
+            self.cookie_jar.clone(),
+            &self.tls,
+            self.request_limits.clone(),
+        )?;
+        self.public_only_client = self
+            .block_private_ips
+            .then(|| {
+                build_public_only_client(
+                    self.timeout_secs,
+                    self.cookie_jar.clone(),
+                    &self.tls,
+                    self.request_limits.clone(),
+                )
+            })
+            .transpose()?;
+        Ok(())
+    }
+
+    fn external_client(
+        timeout_secs: u64,
+        tls: &TlsOptions,
+        block_private_ips: bool,
+        limits: Option<Arc<RequestLimits>>,
+    ) -> Result<reqwest::Client> {
+        if block_private_ips {
+            build_public_only_client(timeout_secs, None, tls, limits)
+        } else {
+            build_crawl_client(timeout_secs, None, tls, limits)
+        }
+    }
+
+    /// Check external links with their own concurrency, pacing, retries, and timeout, and
+    /// optionally only a sample of them
+    pub fn set_external_policy(&mut self, policy: ExternalCheckPolicy) -> Result<()> {
+        let rate_limiter = policy.rate_limit.and_then(|rate| {
+            let interval = Duration::from_secs_f64(1.0 / rate);
+            Some((RateLimiter::direct(Quota::with_period(interval)?), interval))
+        });
+        self.external = Some(ExternalChecks {
+            client: Self::external_client(
+                policy.timeout.as_secs().max(1),
+                &self.tls,
+                self.block_private_ips,
+                self.request_limits.clone(),
+            )?,
+            rate_limiter,
+            policy,
+        });
+        Ok(())
+    }
+
+    fn internal_phase(&self) -> CheckPhase<'_> {
+        CheckPhase {
+            client: &self.client,
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter.as_ref(),
+            block_private_ips: false,
+        }
+    }
+
+    /// Only external links are guarded by `block_private_ips`: the site being crawled may well
+    /// be served from a private network itself
+    fn external_phase(&self) -> CheckPhase<'_> {
+        match &self.external {
+            Some(external) => CheckPhase {
+                client: &external.client,
+                retry_policy: external.policy.retry_policy,
+                rate_limiter: external.rate_limiter.as_ref(),
+                block_private_ips: self.block_private_ips,
+            },
+            None => CheckPhase {
+                client: self.public_only_client.as_ref().unwrap_or(&self.client),
+                block_private_ips: self.block_private_ips,
+                ..self.internal_phase()
+            },
+        }
+    }
+
+    fn should_check(&self, link: &Link) -> bool {
+        let host = Url::parse(&link.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase));
+
+        if let Some(host) = host.as_deref() {
+            if Self::matches_any_domain(host, &self.never_check_domains) {
+                return false;
+            }
+            if Self::matches_any_domain(host, &self.always_check_domains) {
+                return true;
+            }
+        }
+
+        self.check_scope.includes(link.is_external)
+            || (self.audit_tracking_links && !tracking_param_names(&link.url).is_empty())
+    }
+
+    fn matches_any_domain(host: &str, patterns: &[String]) -> bool {
+        patterns.iter().any(|pattern| {
+            let pattern = pattern.trim().trim_start_matches("*.").to_ascii_lowercase();
+            !pattern.is_empty()
+                && (host == pattern
+                    || host
+                        .strip_suffix(pattern.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.')))
+        })
+    }
+
+    /// Unique link URLs that `check_all_links` would request for the given pages
+    pub fn collect_links_to_check(&self, pages: &dyn PageStore) -> Result<HashSet<String>> {
+        let mut links = HashSet::new();
+        pages.for_each_page(&mut |_, page| {
+            links.extend(
+                page.links
+                    .iter()
+                    .filter(|link| self.should_check(link))
+                    .map(|link| link.url.clone()),
+            );
+            Ok(())
+        })?;
+        Ok(links)
+    }
+
+    /// Check every link within the configured scope and domain rules. The links are gathered
+    /// and the outcomes applied one page at a time, so with a page store only the unique link
+    /// URLs are held in memory.
     pub async fn check_all_links(
         &self,
-        pages: &mut HashMap<String, PageInfo>,
+        pages: &mut dyn PageStore,
         ignore_redirects: bool,
     ) -> Result<()> {
-        // Collect all unique links
-        let mut all_links: HashMap<String, Vec<(String, usize)>> = HashMap::new();
-
-        for (page_url, page_info) in pages.iter() {
-            for (idx, link) in page_info.links.iter().enumerate() {
-                all_links
-                    .entry(link.url.clone())
-                    .or_default()
-                    .push((page_url.clone(), idx));
+        let mut urls: HashSet<String> = HashSet::new();
+        let mut external_links: HashSet<String> = HashSet::new();
+        pages.for_each_page(&mut |_, page| {
+            for link in page.links.iter().filter(|link| self.should_check(link)) {
+                if link.is_external {
+                    external_links.insert(link.url.clone());
+                }
+                urls.insert(link.url.clone());
+            }
+            Ok(())
+        })?;
+
+        // Reading a page store for a fresh summary after every link would undo the point of it
+        let page_count = pages.len()?;
+        let summary = Reporter::summarize_pages(pages, &self.html_content_types)?;
+        let total_links = urls.len();
+        let mut outcomes: HashMap<String, LinkCheckOutcome> = HashMap::new();
+        self.check_urls(
+            urls.into_iter().collect(),
+            &external_links,
+            &mut |url, outcome| {
+                outcomes.insert(url.to_string(), outcome.clone());
+                self.report_progress(url, outcomes.len(), total_links, page_count, || {
+                    summary.clone()
+                });
+            },
+        )
+        .await;
+
+        pages.update_each(&mut |_, page| {
+            let mut issues = Vec::new();
+            for link in &mut page.links {
+                if !self.should_check(link) {
+                    continue;
+                }
+                if let Some(outcome) = outcomes.get(&link.url) {
+                    issues.extend(Self::apply_outcome(
+                        link,
+                        outcome,
+                        ignore_redirects,
+                        self.audit_tracking_links,
+                        &self.html_content_types,
+                    ));
+                }
             }
+            page.issues.extend(issues);
+            Ok(())
+        })?;
+
+        if let Some(ref pb) = self.progress_bar {
+            pb.finish_with_message(format!("Checked {} links", total_links));
         }
 
-        let link_urls: Vec<String> = all_links.keys().cloned().collect();
-        let total_links = link_urls.len();
+        Ok(())
+    }
+
+    /// Request each of `urls`, once per fragment-less URL, and call `checked` with every URL
+    /// and the outcome of its request as they complete
+    async fn check_urls(
+        &self,
+        urls: Vec<String>,
+        external_links: &HashSet<String>,
+        checked: &mut dyn FnMut(&str, &LinkCheckOutcome),
+    ) {
+        // URLs that differ only by fragment resolve to the same resource, so group them
+        // and issue a single request per fragment-less URL
+        let mut fragment_variants: HashMap<String, Vec<String>> = HashMap::new();
+        for url in urls {
+            fragment_variants
+                .entry(Self::strip_fragment(&url).to_string())
+                .or_default()
+                .push(url);
+        }
 
         // Initialize progress bar if enabled
         if let Some(ref pb) = self.progress_bar {
             pb.set_position(0);
         }
 
-        let pending_checks = stream::iter(link_urls.iter().cloned())
-            .map(|url| async move {
-                let outcome = self.check_link(&url).await;
-                (url, outcome)
-            })
-            .buffer_unordered(self.concurrent_checks);
+        let external_robots = if self.respect_robots_for_links {
+            self.fetch_external_robots(external_links).await
+        } else {
+            HashMap::new()
+        };
+        let external_robots = &external_robots;
+
+        // Internal links first, then external ones as a phase with its own pacing
+        let (external_urls, internal_urls): (Vec<String>, Vec<String>) =
+            fragment_variants.keys().cloned().partition(|url| {
+                fragment_variants[url]
+                    .iter()
+                    .any(|variant| external_links.contains(variant))
+            });
+        let sample = self
+            .external
+            .as_ref()
+            .and_then(|external| external.policy.sample);
+        let (external_urls, sampled_out): (Vec<String>, Vec<String>) = external_urls
+            .into_iter()
+            .partition(|url| sample.is_none_or(|sample| sample.includes(url)));
+        let external_concurrency = self
+            .external
+            .as_ref()
+            .map_or(self.concurrent_checks, |external| {
+                external.policy.concurrency
+            });
+
+        let pending_checks = stream::iter(internal_urls)
+            .map(|url| self.check_unless_disallowed(external_robots, url, self.internal_phase()))
+            .buffer_unordered(self.concurrent_checks)
+            .chain(
+                stream::iter(external_urls)
+                    .map(|url| {
+                        self.check_unless_disallowed(external_robots, url, self.external_phase())
+                    })
+                    .buffer_unordered(external_concurrency.max(1)),
+            )
+            .chain(stream::iter(
+                sampled_out
+                    .into_iter()
+                    .map(|url| (url, LinkCheckOutcome::SkippedBySample)),
+            ));
         pin_mut!(pending_checks);
 
-        let mut completed = 0usize;
-
-        while let Some((url, outcome)) = pending_checks.next().await {
-            if let Some(locations) = all_links.get(&url) {
-                for (page_url, link_idx) in locations {
-                    if let Some(page) = pages.get_mut(page_url) {
-                        let issues = if let Some(link) = page.links.get_mut(*link_idx) {
-                            Self::apply_outcome(link, &outcome, ignore_redirects)
-                        } else {
-                            Vec::new()
-                        };
-
-                        if !issues.is_empty() {
-                            page.issues.extend(issues);
-                        }
-                    }
-                }
-            }
-
-            completed += 1;
-
-            // Update progress bar
-            if let Some(ref pb) = self.progress_bar {
-                pb.set_position(completed as u64);
-                pb.set_message(format!("Checking {}", url));
-            }
-
-            if let Some(sender) = &self.progress_sender {
-                let mut snapshot = ProgressSnapshot::new(
-                    RunStage::CheckingLinks,
-                    format!("Checking link {}/{}: {}", completed, total_links, url),
-                );
-                snapshot.pages_crawled = pages.len();
-                snapshot.links_discovered = total_links;
-                snapshot.links_checked = completed;
-                snapshot.total_links = total_links;
-                snapshot.summary = Reporter::summarize_pages(pages);
-
-                let _ = sender.send(RunEvent::Progress(snapshot));
+        while let Some((request_url, outcome)) = pending_checks.next().await {
+            let Some(variants) = fragment_variants.get(&request_url) else {
+                continue;
+            };
+            for url in variants {
+                checked(url, &outcome);
             }
         }
+    }
 
-        // Finish progress bar
+    /// Move the progress bar and send a progress snapshot after `url` was checked
+    fn report_progress(
+        &self,
+        url: &str,
+        completed: usize,
+        total_links: usize,
+        pages_crawled: usize,
+        summary: impl FnOnce() -> CrawlSummary,
+    ) {
         if let Some(ref pb) = self.progress_bar {
-            pb.finish_with_message(format!("Checked {} links", total_links));
+            pb.set_position(completed as u64);
+            pb.set_message(format!("Checking {}", url));
         }
 
-        Ok(())
+        if let Some(sender) = &self.progress_sender {
+            let mut snapshot = ProgressSnapshot::new(
+                RunStage::CheckingLinks,
+                format!("Checking link {}/{}: {}", completed, total_links, url),
+            );
+            snapshot.pages_crawled = pages_crawled;
+            snapshot.links_discovered = total_links;
+            snapshot.links_checked = completed;
+            snapshot.total_links = total_links;
+            snapshot.summary = summary();
+
+            let _ = sender.send(RunEvent::Progress(snapshot));
+        }
     }
 
     fn apply_outcome(
         link: &mut Link,
         outcome: &LinkCheckOutcome,
         ignore_redirects: bool,
+        audit_tracking_links: bool,
+        html_content_types: &HtmlContentTypes,
     ) -> Vec<SeoIssue> {
         let mut issues = Vec::new();
+        let tracked = audit_tracking_links && !tracking_param_names(&link.url).is_empty();
 
         match outcome {
             LinkCheckOutcome::Reachable {
                 status_code,
                 redirected_url,
+                content_type,
+                content_length,
             } => {
                 link.status_code = Some(*status_code);
                 link.redirected_url = redirected_url.clone();
                 link.check_error = None;
+                link.skip_reason = None;
+                link.content_type = content_type.clone();
+                link.content_length = *content_length;
 
                 if !ignore_redirects && let Some(redirect_to) = redirected_url {
-                    issues.push(SeoIssue {
-                        severity: IssueSeverity::Info,
-                        issue_type: IssueType::Redirect,
-                        message: format!("Link redirected: {} -> {}", link.url, redirect_to),
-                    });
+                    issues.push(
+                        SeoIssue::new(
+                            IssueSeverity::Info,
+                            IssueType::Redirect,
+                            format!("Link redirected: {} -> {}", link.url, redirect_to),
+                        )
+                        .with_offending_url(&link.url)
+                        .with_expected(&link.url)
+                        .with_actual(redirect_to),
+                    );
+                }
+
+                if tracked && let Some(redirect_to) = redirected_url {
+                    let kept = tracking_param_names(redirect_to);
+                    let dropped: Vec<String> = tracking_param_names(&link.url)
+                        .into_iter()
+                        .filter(|name| !kept.iter().any(|kept| kept.eq_ignore_ascii_case(name)))
+                        .collect();
+                    if !dropped.is_empty() {
+                        issues.push(
+                            SeoIssue::new(
+                                IssueSeverity::Warning,
+                                IssueType::TrackingParamsStripped,
+                                format!(
+                                    "Redirect dropped tracking parameters ({}): {} -> {}",
+                                    dropped.join(", "),
+                                    link.url,
+                                    redirect_to
+                                ),
+                            )
+                            .with_offending_url(&link.url)
+                            .with_expected(format!("a destination keeping {}", dropped.join(", ")))
+                            .with_actual(redirect_to),
+                        );
+                    }
                 }
 
                 if *status_code >= 400 {
-                    issues.push(SeoIssue {
-                        severity: IssueSeverity::Error,
-                        issue_type: IssueType::BrokenLink,
-                        message: format!("Broken link: {} (HTTP {})", link.url, status_code),
-                    });
+                    let kind = if link.is_download(html_content_types) {
+                        "Broken download"
+                    } else if tracked {
+                        "Broken tracked link"
+                    } else {
+                        "Broken link"
+                    };
+                    issues.push(
+                        SeoIssue::new(
+                            IssueSeverity::Error,
+                            IssueType::BrokenLink,
+                            format!("{kind}: {} (HTTP {})", link.url, status_code),
+                        )
+                        .with_offending_url(&link.url)
+                        .with_expected("HTTP status below 400")
+                        .with_actual(format!("HTTP {status_code}")),
+                    );
                 }
             }
             LinkCheckOutcome::SkippedUnsupportedScheme => {
                 link.status_code = None;
                 link.redirected_url = None;
                 link.check_error = None;
+                link.skip_reason = None;
+                link.content_type = None;
+                link.content_length = None;
+            }
+            LinkCheckOutcome::SkippedByRobots => {
+                link.status_code = None;
+                link.redirected_url = None;
+                link.check_error = None;
+                link.skip_reason = Some(LinkSkipReason::Robots);
+                link.content_type = None;
+                link.content_length = None;
+            }
+            LinkCheckOutcome::SkippedByLimit => {
+                link.status_code = None;
+                link.redirected_url = None;
+                link.check_error = None;
+                link.skip_reason = Some(LinkSkipReason::RequestLimit);
+                link.content_type = None;
+                link.content_length = None;
+            }
+            LinkCheckOutcome::SkippedBySample => {
+                link.status_code = None;
+                link.redirected_url = None;
+                link.check_error = None;
+                link.skip_reason = Some(LinkSkipReason::Sampled);
+                link.content_type = None;
+                link.content_length = None;
+            }
+            LinkCheckOutcome::SkippedPrivateAddress => {
+                link.status_code = None;
+                link.redirected_url = None;
+                link.check_error = None;
+                link.skip_reason = Some(LinkSkipReason::PrivateAddress);
+                link.content_type = None;
+                link.content_length = None;
+            }
+            LinkCheckOutcome::RedirectLoop { cycle } => {
+                link.status_code = None;
+                link.redirected_url = None;
+                link.check_error = Some("redirect loop".to_string());
+                link.skip_reason = None;
+                link.content_type = None;
+                link.content_length = None;
+
+                issues.push(redirect_loop_issue(&link.url, cycle));
             }
             LinkCheckOutcome::TransportFailure { error } => {
                 link.status_code = None;
                 link.redirected_url = None;
                 link.check_error = Some(error.clone());
-
-                issues.push(SeoIssue {
-                    severity: IssueSeverity::Error,
-                    issue_type: IssueType::BrokenLink,
-                    message: format!("Link check failed: {} ({})", link.url, error),
-                });
+                link.skip_reason = None;
+                link.content_type = None;
+                link.content_length = None;
+
+                issues.push(
+                    SeoIssue::new(
+                        IssueSeverity::Error,
+                        IssueType::BrokenLink,
+                        format!(
+                            "{} check failed: {} ({})",
+                            if link.is_download(html_content_types) {
+                                "Download"
+                            } else if tracked {
+                                "Tracked link"
+                            } else {
+                                "Link"
+                            },
+                            link.url,
+                            error
+                        ),
+                    )
+                    .with_offending_url(&link.url)
+                    .with_actual(error),
+                );
             }
         }
 
         issues
     }
 
-    async fn check_link(&self, url: &str) -> LinkCheckOutcome {
+    /// Fetch robots.txt once per external origin, keyed by the origin's serialization
+    async fn fetch_external_robots(&self, urls: &HashSet<String>) -> HashMap<String, RobotsTxt> {
+        let origins: HashMap<String, Url> = urls
+            .iter()
+            .filter_map(|url| Url::parse(url).ok())
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .filter(|url| {
+                !self.block_private_ips
+                    || hosts::ip_address(url).is_none_or(|address| !hosts::is_private_ip(address))
+            })
+            .map(|url| (url.origin().ascii_serialization(), url))
+            .collect();
+        let client = self.public_only_client.as_ref().unwrap_or(&self.client);
+        let limits = self.request_limits.as_deref();
+
+        stream::iter(origins)
+            .map(|(origin, url)| async move {
+                let mut robots = RobotsTxt::new();
+                if let Err(error) = robots.fetch(client, &url, limits).await {
+                    tracing::warn!(origin = %origin, error = %error, "Failed to fetch robots.txt for external host");
+                }
+                (origin, robots)
+            })
+            .buffer_unordered(self.concurrent_checks)
+            .collect()
+            .await
+    }
+
+    fn is_disallowed_by_robots(&self, robots: &HashMap<String, RobotsTxt>, url: &str) -> bool {
+        let Ok(parsed_url) = Url::parse(url) else {
+            return false;
+        };
+
+        robots
+            .get(&parsed_url.origin().ascii_serialization())
+            .is_some_and(|robots| !robots.is_allowed(&parsed_url, &self.robots_agent))
+    }
+
+    async fn check_unless_disallowed(
+        &self,
+        robots: &HashMap<String, RobotsTxt>,
+        url: String,
+        phase: CheckPhase<'_>,
+    ) -> (String, LinkCheckOutcome) {
+        let outcome = if self.is_disallowed_by_robots(robots, &url) {
+            LinkCheckOutcome::SkippedByRobots
+        } else {
+            self.check_link(&url, &phase).await
+        };
+        (url, outcome)
+    }
+
+    async fn check_link(&self, url: &str, phase: &CheckPhase<'_>) -> LinkCheckOutcome {
         if let Ok(parsed_url) = Url::parse(url)
             && !matches!(parsed_url.scheme(), "http" | "https")
         {
             return LinkCheckOutcome::SkippedUnsupportedScheme;
         }
 
-        match self.client.get(url).send().await {
+        if phase.block_private_ips
+            && let Some(address) = Url::parse(url).ok().as_ref().and_then(hosts::ip_address)
+            && hosts::is_private_ip(address)
+        {
+            tracing::info!(url = %url, "Not checking link to a private address");
+            return LinkCheckOutcome::SkippedPrivateAddress;
+        }
+
+        if self
+            .request_limits
+            .as_ref()
+            .is_some_and(|limits| !limits.try_acquire())
+        {
+            return LinkCheckOutcome::SkippedByLimit;
+        }
+
+        if let Some((limiter, interval)) = phase.rate_limiter {
+            limiter
+                .until_ready_with_jitter(Jitter::up_to(*interval / 2))
+                .await;
+        }
+
+        let limits = self.request_limits.as_deref();
+        let response = phase
+            .retry_policy
+            .send(url, limits, || async {
+                if self.gentle {
+                    get_honoring_retry_after(phase.client, url, limits).await
+                } else {
+                    phase.client.get(url).send().await
+                }
+            })
+            .await;
+        match response {
             Ok(response) => {
                 let status = response.status().as_u16();
                 let final_url = response.url().to_string();
+                let content_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                let content_length = response.content_length();
+                if let Some(limits) = limits {
+                    limits.record_body(response).await;
+                }
 
                 // Check if URL was redirected (ignoring fragment differences)
-                let url_without_fragment = url.split('#').next().unwrap_or(url);
-                let final_url_without_fragment = final_url.split('#').next().unwrap_or(&final_url);
+                let url_without_fragment = Self::strip_fragment(url);
+                let final_url_without_fragment = Self::strip_fragment(&final_url);
 
                 let redirected_url = if final_url_without_fragment != url_without_fragment {
                     Some(final_url)
@@ -230,14 +952,34 @@ impl LinkChecker {
                 LinkCheckOutcome::Reachable {
                     status_code: status,
                     redirected_url,
+                    content_type,
+                    content_length,
+                }
+            }
+            Err(error) => {
+                if find_limit_reached(&error).is_some() {
+                    return LinkCheckOutcome::SkippedByLimit;
+                }
+                if let Some(refused) = find_private_address(&error) {
+                    tracing::info!(url = %url, refused = %refused, "Not checking link to a private address");
+                    return LinkCheckOutcome::SkippedPrivateAddress;
+                }
+                match find_redirect_loop(&error) {
+                    Some(redirect_loop) => LinkCheckOutcome::RedirectLoop {
+                        cycle: redirect_loop.cycle.clone(),
+                    },
+                    None => LinkCheckOutcome::TransportFailure {
+                        error: Self::classify_request_error(&error),
+                    },
                 }
             }
-            Err(error) => LinkCheckOutcome::TransportFailure {
-                error: Self::classify_request_error(&error),
-            },
         }
     }
 
+    fn strip_fragment(url: &str) -> &str {
+        url.split('#').next().unwrap_or(url)
+    }
+
     fn classify_request_error(error: &reqwest::Error) -> String {
         if error.is_timeout() {
             "request timed out".to_string()
@@ -250,3 +992,15 @@ impl LinkChecker {
         }
     }
 }
+
+/// Error for a URL whose redirects lead back to a URL already visited
+pub(crate) fn redirect_loop_issue(url: &str, cycle: &[String]) -> SeoIssue {
+    SeoIssue::new(
+        IssueSeverity::Error,
+        IssueType::RedirectLoop,
+        format!("Redirect loop: {}", cycle.join(" -> ")),
+    )
+    .with_offending_url(url)
+    .with_expected("a final response that doesn't redirect")
+    .with_actual(cycle.join(" -> "))
+}
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use reqwest::{Client, Method};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use url::Url;
+
+/// A login form submitted once before crawling, so the session cookies it sets are sent with
+/// every request of the crawl
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoginConfig {
+    /// Page with the login form
+    pub url: String,
+    /// Form fields to submit by name, e.g. `username` and `password`. `${NAME}` in a value is
+    /// replaced with the environment variable `NAME`, so passwords can stay out of the file.
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    /// CSS selector that only matches once logged in, checked on the page the form leads to
+    pub success_selector: String,
+}
+
+/// Load the login page, fill in the form that has the configured fields (keeping its hidden
+/// inputs such as CSRF tokens), submit it, and check the page it leads to for
+/// `success_selector`. The client must keep cookies for the session to outlive this call.
+pub async fn log_in(client: &Client, login: &LoginConfig) -> Result<()> {
+    let success_selector = Selector::parse(&login.success_selector).map_err(|error| {
+        anyhow::anyhow!(
+            "Invalid login success_selector `{}`: {error}",
+            login.success_selector
+        )
+    })?;
+    let mut fields = BTreeMap::new();
+    for (name, value) in &login.fields {
+        let value =
+            expand_env(value).with_context(|| format!("Failed to fill in login field {name}"))?;
+        fields.insert(name.clone(), value);
+    }
+
+    let response = client
+        .get(&login.url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to load login page: {}", login.url))?;
+    let page_url = response.url().clone();
+    let html = response.text().await?;
+    let form = find_login_form(&html, &page_url, &fields);
+
+    // Without a form on the page, post the fields to the login URL itself
+    let (method, action, mut submitted) =
+        form.unwrap_or_else(|| (Method::POST, page_url.clone(), BTreeMap::new()));
+    submitted.extend(fields);
+    let request = if method == Method::GET {
+        client.get(action.clone()).query(&submitted)
+    } else {
+        client.post(action.clone()).form(&submitted)
+    };
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to submit login form to {action}"))?;
+    let landed_on = response.url().to_string();
+    let html = response.text().await?;
+
+    if Html::parse_document(&html)
+        .select(&success_selector)
+        .next()
+        .is_none()
+    {
+        anyhow::bail!(
+            "Login failed: `{}` not found on {landed_on} after submitting the form",
+            login.success_selector
+        );
+    }
+    tracing::info!(url = %login.url, landed_on = %landed_on, "Logged in");
+    Ok(())
+}
+
+/// The method, resolved action, and prefilled inputs of the first form with an input named
+/// after one of `fields`, or the page's only form
+fn find_login_form(
+    html: &str,
+    page_url: &Url,
+    fields: &BTreeMap<String, String>,
+) -> Option<(Method, Url, BTreeMap<String, String>)> {
+    let document = Html::parse_document(html);
+    let form_selector = Selector::parse("form").expect("form selector should be valid");
+    let input_selector = Selector::parse("input[name], textarea[name], select[name]")
+        .expect("input selector should be valid");
+
+    let forms: Vec<ElementRef> = document.select(&form_selector).collect();
+    let has_field = |form: &ElementRef| {
+        form.select(&input_selector).any(|input| {
+            input
+                .attr("name")
+                .is_some_and(|name| fields.contains_key(name))
+        })
+    };
+    let form = forms
+        .iter()
+        .find(|form| has_field(form))
+        .or_else(|| forms.first().filter(|_| forms.len() == 1))?;
+
+    let action = form
+        .attr("action")
+        .filter(|action| !action.trim().is_empty())
+        .and_then(|action| page_url.join(action.trim()).ok())
+        .unwrap_or_else(|| page_url.clone());
+    let method = match form.attr("method") {
+        Some(method) if method.eq_ignore_ascii_case("post") => Method::POST,
+        _ => Method::GET,
+    };
+    let prefilled = form
+        .select(&input_selector)
+        .filter(|input| {
+            !matches!(
+                input.attr("type").map(str::to_ascii_lowercase).as_deref(),
+                Some("submit" | "button" | "reset" | "checkbox" | "radio" | "file")
+            )
+        })
+        .filter_map(|input| {
+            let name = input.attr("name")?;
+            Some((
+                name.to_string(),
+                input.attr("value").unwrap_or_default().to_string(),
+            ))
+        })
+        .collect();
+
+    Some((method, action, prefilled))
+}
+
+/// Replace each `${NAME}` in `value` with the environment variable `NAME`
+pub(crate) fn expand_env(value: &str) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(length) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + length];
+        let variable = std::env::var(name)
+            .with_context(|| format!("Refers to environment variable {name}, which isn't set"))?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&variable);
+        rest = &rest[start + 3 + length..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_login_form_keeps_hidden_inputs() {
+        let html = r#"
+            <form action="/search"><input name="q"></form>
+            <form action="/session" method="post">
+                <input type="hidden" name="csrf" value="token123">
+                <input name="username">
+                <input type="password" name="password">
+                <input type="checkbox" name="remember" value="1">
+                <button type="submit">Log in</button>
+            </form>
+        "#;
+        let page_url = Url::parse("https://example.com/login").unwrap();
+        let fields = BTreeMap::from([("username".to_string(), "ana".to_string())]);
+
+        let (method, action, prefilled) = find_login_form(html, &page_url, &fields).unwrap();
+
+        assert_eq!(method, Method::POST);
+        assert_eq!(action.as_str(), "https://example.com/session");
+        assert_eq!(
+            prefilled,
+            BTreeMap::from([
+                ("csrf".to_string(), "token123".to_string()),
+                ("password".to_string(), String::new()),
+                ("username".to_string(), String::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_expand_env() {
+        // SAFETY: no other test reads or writes this variable
+        unsafe { std::env::set_var("SCOUTLY_TEST_LOGIN_PASSWORD", "hunter2") };
+
+        assert_eq!(
+            expand_env("pre-${SCOUTLY_TEST_LOGIN_PASSWORD}-post").unwrap(),
+            "pre-hunter2-post"
+        );
+        assert_eq!(expand_env("no variables").unwrap(), "no variables");
+        assert!(expand_env("${SCOUTLY_TEST_LOGIN_UNSET}").is_err());
+    }
+}
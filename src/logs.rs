@@ -0,0 +1,362 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use url::Url;
+
+use crate::compare::path_key;
+use crate::models::{HtmlContentTypes, PageInfo};
+
+/// Base for resolving request targets, which are usually just a path and query
+const LOG_BASE_URL: &str = "http://log.invalid/";
+/// Extensions of assets Googlebot fetches to render pages; they only count when the crawl found them
+const STATIC_EXTENSIONS: [&str; 14] = [
+    "css", "js", "mjs", "map", "png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "woff", "woff2",
+    "ttf",
+];
+
+/// Layout of the access log lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Apache/Nginx combined format: common log fields plus referer and user agent
+    Combined,
+}
+
+/// One request from an access log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Requested path and query
+    pub path: String,
+    pub status_code: u16,
+    pub user_agent: String,
+}
+
+impl LogEntry {
+    pub fn is_googlebot(&self) -> bool {
+        self.user_agent.to_lowercase().contains("googlebot")
+    }
+}
+
+/// Read every parseable line of an access log and count the lines that couldn't be parsed
+pub fn read_log(path: &Path, format: LogFormat) -> Result<(Vec<LogEntry>, usize)> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to read access log: {}", path.display()))?;
+
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+    for line in BufReader::new(file).lines() {
+        let line =
+            line.with_context(|| format!("Failed to read access log: {}", path.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let entry = match format {
+            LogFormat::Combined => parse_combined_line(&line),
+        };
+        match entry {
+            Some(entry) => entries.push(entry),
+            None => skipped += 1,
+        }
+    }
+
+    Ok((entries, skipped))
+}
+
+/// Parse `host ident user [time] "METHOD target PROTOCOL" status bytes "referer" "user agent"`
+pub fn parse_combined_line(line: &str) -> Option<LogEntry> {
+    let fields = split_log_fields(line);
+    if fields.len() < 9 {
+        return None;
+    }
+
+    let mut request = fields[4].split_whitespace();
+    let _method = request.next()?;
+    let target = request.next()?;
+    let path = Url::parse(LOG_BASE_URL)
+        .ok()?
+        .join(target)
+        .ok()
+        .map(|url| path_key(&url))?;
+
+    Some(LogEntry {
+        path,
+        status_code: fields[5].parse().ok()?,
+        user_agent: fields[8].to_string(),
+    })
+}
+
+/// Split on spaces, keeping `"quoted"` and `[bracketed]` fields together
+fn split_log_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&next) = chars.peek() {
+        if next == ' ' {
+            chars.next();
+            continue;
+        }
+
+        let mut field = String::new();
+        match next {
+            '"' => {
+                chars.next();
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '\\' => field.extend(chars.next()),
+                        '"' => break,
+                        _ => field.push(ch),
+                    }
+                }
+            }
+            '[' => {
+                chars.next();
+                for ch in chars.by_ref() {
+                    if ch == ']' {
+                        break;
+                    }
+                    field.push(ch);
+                }
+            }
+            _ => {
+                while let Some(&ch) = chars.peek() {
+                    if ch == ' ' {
+                        break;
+                    }
+                    field.push(ch);
+                    chars.next();
+                }
+            }
+        }
+        fields.push(field);
+    }
+
+    fields
+}
+
+/// Why Googlebot's visits to a URL are likely wasted crawl budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LowValueReason {
+    /// The crawl never reached the URL, so nothing on the site links to it
+    NotInCrawl,
+    /// A query string variant of a page the crawl found without it
+    ParameterVariant,
+    Redirect,
+    ErrorStatus,
+}
+
+impl LowValueReason {
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::NotInCrawl => "not linked from the site",
+            Self::ParameterVariant => "parameter variant of a crawled page",
+            Self::Redirect => "redirects",
+            Self::ErrorStatus => "error status",
+        }
+    }
+}
+
+/// A URL Googlebot requested that doesn't look worth its crawl budget
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LowValueUrl {
+    pub path: String,
+    pub googlebot_hits: usize,
+    /// Status of Googlebot's most recent request
+    pub last_status: u16,
+    pub reasons: Vec<LowValueReason>,
+}
+
+/// A crawled page Googlebot never requested
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissedPage {
+    pub url: String,
+    pub crawl_depth: usize,
+    /// Number of crawled pages linking to it
+    pub inlinks: usize,
+}
+
+/// Googlebot activity from an access log set against a crawl of the same site
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlBudgetReport {
+    pub log_entries: usize,
+    pub googlebot_hits: usize,
+    pub googlebot_urls: usize,
+    /// Sorted by hits, most-crawled first
+    pub low_value: Vec<LowValueUrl>,
+    /// Sorted by inlinks, then by depth
+    pub never_crawled: Vec<MissedPage>,
+}
+
+impl CrawlBudgetReport {
+    pub fn new(
+        entries: &[LogEntry],
+        pages: &HashMap<String, PageInfo>,
+        html_content_types: &HtmlContentTypes,
+    ) -> Self {
+        let mut hits: BTreeMap<&str, (usize, u16)> = BTreeMap::new();
+        for entry in entries.iter().filter(|entry| entry.is_googlebot()) {
+            let counts = hits.entry(entry.path.as_str()).or_default();
+            counts.0 += 1;
+            counts.1 = entry.status_code;
+        }
+
+        let crawled: HashMap<String, &PageInfo> = pages
+            .values()
+            .filter_map(|page| Some((path_key(&Url::parse(&page.url).ok()?), page)))
+            .collect();
+
+        let mut low_value: Vec<LowValueUrl> = hits
+            .iter()
+            .filter_map(|(&path, &(googlebot_hits, last_status))| {
+                let reasons = low_value_reasons(path, last_status, &crawled);
+                (!reasons.is_empty()).then(|| LowValueUrl {
+                    path: path.to_string(),
+                    googlebot_hits,
+                    last_status,
+                    reasons,
+                })
+            })
+            .collect();
+        low_value.sort_by(|a, b| b.googlebot_hits.cmp(&a.googlebot_hits));
+
+        let inlinks = count_inlinks(pages);
+        let mut never_crawled: Vec<MissedPage> = crawled
+            .iter()
+            .filter(|(path, page)| {
+                !hits.contains_key(path.as_str())
+                    && page
+                        .status_code
+                        .is_some_and(|code| (200..300).contains(&code))
+                    && html_content_types.contains(page.content_type.as_deref())
+            })
+            .map(|(path, page)| MissedPage {
+                url: page.url.clone(),
+                crawl_depth: page.crawl_depth,
+                inlinks: inlinks.get(path).copied().unwrap_or_default(),
+            })
+            .collect();
+        never_crawled.sort_by(|a, b| {
+            b.inlinks
+                .cmp(&a.inlinks)
+                .then(a.crawl_depth.cmp(&b.crawl_depth))
+                .then_with(|| a.url.cmp(&b.url))
+        });
+
+        Self {
+            log_entries: entries.len(),
+            googlebot_hits: hits.values().map(|(count, _)| count).sum(),
+            googlebot_urls: hits.len(),
+            low_value,
+            never_crawled,
+        }
+    }
+
+    /// Print the summary and up to `limit` URLs of each list
+    pub fn print_text_report(&self, limit: usize) {
+        println!("\n{}", "=".repeat(80).bright_blue());
+        println!("{}", "CRAWL BUDGET".bright_cyan().bold());
+        println!("{}", "=".repeat(80).bright_blue());
+        println!(
+            "{} {}",
+            "Log entries:".bright_white().bold(),
+            self.log_entries
+        );
+        println!(
+            "{} {} across {} URL(s)",
+            "Googlebot hits:".bright_white().bold(),
+            self.googlebot_hits,
+            self.googlebot_urls
+        );
+
+        println!(
+            "\n{} ({})",
+            "Frequently crawled, low value".bright_yellow().bold(),
+            self.low_value.len()
+        );
+        for url in self.low_value.iter().take(limit) {
+            let reasons: Vec<&str> = url
+                .reasons
+                .iter()
+                .map(|reason| reason.description())
+                .collect();
+            println!(
+                "  {:>6} hits  {}  {} {}",
+                url.googlebot_hits,
+                url.last_status,
+                url.path.bright_white(),
+                format!("({})", reasons.join(", ")).dimmed()
+            );
+        }
+
+        println!(
+            "\n{} ({})",
+            "Never crawled by Googlebot".bright_yellow().bold(),
+            self.never_crawled.len()
+        );
+        for page in self.never_crawled.iter().take(limit) {
+            println!(
+                "  {:>6} inlinks  depth {}  {}",
+                page.inlinks,
+                page.crawl_depth,
+                page.url.bright_white()
+            );
+        }
+    }
+}
+
+fn low_value_reasons(
+    path: &str,
+    last_status: u16,
+    crawled: &HashMap<String, &PageInfo>,
+) -> Vec<LowValueReason> {
+    let mut reasons = Vec::new();
+    if !crawled.contains_key(path) {
+        match path.split_once('?') {
+            Some((without_query, _)) if crawled.contains_key(without_query) => {
+                reasons.push(LowValueReason::ParameterVariant);
+            }
+            _ if is_static_asset(path) => {}
+            _ => reasons.push(LowValueReason::NotInCrawl),
+        }
+    }
+    if (300..400).contains(&last_status) {
+        reasons.push(LowValueReason::Redirect);
+    } else if last_status >= 400 {
+        reasons.push(LowValueReason::ErrorStatus);
+    }
+    reasons
+}
+
+fn is_static_asset(path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or(path);
+    path.rsplit_once('.').is_some_and(|(_, extension)| {
+        STATIC_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+    })
+}
+
+/// Count the distinct crawled pages linking to each crawled path
+fn count_inlinks(pages: &HashMap<String, PageInfo>) -> HashMap<String, usize> {
+    let mut inlinks: HashMap<String, usize> = HashMap::new();
+    for page in pages.values() {
+        let targets: HashSet<String> = page
+            .links
+            .iter()
+            .filter(|link| !link.is_external)
+            .filter_map(|link| {
+                let mut url = Url::parse(&link.url).ok()?;
+                url.set_fragment(None);
+                (url.as_str() != page.url).then(|| path_key(&url))
+            })
+            .collect();
+        for target in targets {
+            *inlinks.entry(target).or_default() += 1;
+        }
+    }
+    inlinks
+}
@@ -0,0 +1,341 @@
+use anyhow::{Context, Result};
+use colored::*;
+use futures::stream::{self, StreamExt};
+use reqwest::cookie::Jar;
+use reqwest::{Client, header};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use url::Url;
+
+use crate::http_client::{TlsOptions, build_redirect_tracing_client};
+use crate::link_checker::DEFAULT_LINK_TIMEOUT_SECS;
+use crate::models::RedirectHop;
+
+const MAX_REDIRECT_HOPS: usize = 10;
+
+/// An old URL and the URL it is expected to permanently redirect to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationMapping {
+    pub old_url: String,
+    pub expected_url: String,
+}
+
+/// Read `old_url,expected_url` rows from a CSV file. A header row is skipped when its
+/// first column is not a URL, and relative targets are resolved against the old URL.
+pub fn read_mappings(path: &Path) -> Result<Vec<MigrationMapping>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_path(path)
+        .with_context(|| format!("Failed to read mapping file: {}", path.display()))?;
+
+    let mut mappings = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record =
+            record.with_context(|| format!("Failed to parse mapping file: {}", path.display()))?;
+        let (Some(old_url), Some(expected_url)) = (record.get(0), record.get(1)) else {
+            anyhow::bail!(
+                "Line {} of {} must have an old URL and an expected URL",
+                index + 1,
+                path.display()
+            );
+        };
+
+        let Ok(old) = Url::parse(old_url) else {
+            if index == 0 {
+                continue;
+            }
+            anyhow::bail!(
+                "Line {} of {} has an invalid old URL: {old_url}",
+                index + 1,
+                path.display()
+            );
+        };
+        let expected = old.join(expected_url).with_context(|| {
+            format!(
+                "Line {} of {} has an invalid expected URL: {expected_url}",
+                index + 1,
+                path.display()
+            )
+        })?;
+
+        mappings.push(MigrationMapping {
+            old_url: old.to_string(),
+            expected_url: expected.to_string(),
+        });
+    }
+
+    Ok(mappings)
+}
+
+/// Something that keeps a mapping from being a single permanent redirect to the expected page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationProblem {
+    /// The old URL answered without redirecting
+    NotRedirected,
+    /// The first redirect was not permanent (301 or 308), e.g. a 302
+    TemporaryRedirect,
+    /// More than one redirect before the final page
+    RedirectChain,
+    /// The redirects ended somewhere other than the expected URL
+    WrongTarget,
+    /// The old URL or the final page returned 404 or 410
+    NotFound,
+    /// The redirects loop or exceed the hop limit
+    RedirectLoop,
+    /// A request failed before a final response was received
+    RequestFailed,
+}
+
+impl MigrationProblem {
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::NotRedirected => "old URL does not redirect",
+            Self::TemporaryRedirect => "temporary redirect instead of 301",
+            Self::RedirectChain => "redirect chain",
+            Self::WrongTarget => "redirects to the wrong target",
+            Self::NotFound => "not found",
+            Self::RedirectLoop => "redirect loop",
+            Self::RequestFailed => "request failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationCheck {
+    pub old_url: String,
+    pub expected_url: String,
+    /// Every response received, starting with the old URL
+    pub hops: Vec<RedirectHop>,
+    pub final_url: Option<String>,
+    pub problems: Vec<MigrationProblem>,
+    pub error: Option<String>,
+}
+
+impl MigrationCheck {
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub checks: Vec<MigrationCheck>,
+}
+
+impl MigrationReport {
+    pub fn new(checks: Vec<MigrationCheck>) -> Self {
+        let passed = checks.iter().filter(|check| check.passed()).count();
+        Self {
+            total: checks.len(),
+            passed,
+            failed: checks.len() - passed,
+            checks,
+        }
+    }
+
+    pub fn print_text_report(&self) {
+        println!("\n{}", "=".repeat(80).bright_blue());
+        println!("{}", "MIGRATION VALIDATION".bright_cyan().bold());
+        println!("{}", "=".repeat(80).bright_blue());
+        println!(
+            "{} {}",
+            "Mappings checked:".bright_white().bold(),
+            self.total
+        );
+        println!(
+            "{} {}",
+            "Passed:".bright_white().bold(),
+            self.passed.to_string().bright_green()
+        );
+        println!(
+            "{} {}",
+            "Failed:".bright_white().bold(),
+            self.failed.to_string().bright_red()
+        );
+
+        for check in self.checks.iter().filter(|check| !check.passed()) {
+            let problems: Vec<&str> = check
+                .problems
+                .iter()
+                .map(|problem| problem.description())
+                .collect();
+            println!(
+                "\n{} {}",
+                "✗".bright_red(),
+                check.old_url.bright_white().bold()
+            );
+            println!("    {} {}", "Problems:".bold(), problems.join(", "));
+            println!("    {} {}", "Expected:".bold(), check.expected_url);
+            for hop in &check.hops {
+                println!("    {} {} {}", "→".bright_blue(), hop.status_code, hop.url);
+            }
+            if let Some(error) = &check.error {
+                println!("    {} {}", "Error:".bold(), error);
+            }
+        }
+    }
+}
+
+/// Follows the redirects of each old URL one hop at a time
+pub struct MigrationValidator {
+    client: Client,
+    timeout_secs: u64,
+    cookie_jar: Option<Arc<Jar>>,
+    tls: TlsOptions,
+    concurrency: usize,
+}
+
+impl MigrationValidator {
+    pub fn new(concurrency: usize) -> Result<Self> {
+        let tls = TlsOptions::default();
+        Ok(Self {
+            client: build_redirect_tracing_client(DEFAULT_LINK_TIMEOUT_SECS, None, &tls)?,
+            timeout_secs: DEFAULT_LINK_TIMEOUT_SECS,
+            cookie_jar: None,
+            tls,
+            concurrency: concurrency.max(1),
+        })
+    }
+
+    /// Verify certificates against extra CAs, or not at all
+    pub fn set_tls(&mut self, tls: TlsOptions) -> Result<()> {
+        self.tls = tls;
+        self.rebuild_client()
+    }
+
+    /// Send these cookies with every hop, so redirects that depend on a session are followed
+    /// as a signed-in visitor sees them
+    pub fn set_cookie_jar(&mut self, jar: Arc<Jar>) -> Result<()> {
+        self.cookie_jar = Some(jar);
+        self.rebuild_client()
+    }
+
+    /// Give up on requests that take longer than this many seconds
+    pub fn set_timeout(&mut self, timeout_secs: u64) -> Result<()> {
+        self.timeout_secs = timeout_secs;
+        self.rebuild_client()
+    }
+
+    fn rebuild_client(&mut self) -> Result<()> {
+        self.client =
+            build_redirect_tracing_client(self.timeout_secs, self.cookie_jar.clone(), &self.tls)?;
+        Ok(())
+    }
+
+    /// Check every mapping; results keep the order of the mapping file
+    pub async fn validate(&self, mappings: &[MigrationMapping]) -> MigrationReport {
+        let checks = stream::iter(mappings)
+            .map(|mapping| self.check(mapping))
+            .buffered(self.concurrency)
+            .collect()
+            .await;
+
+        MigrationReport::new(checks)
+    }
+
+    async fn check(&self, mapping: &MigrationMapping) -> MigrationCheck {
+        let mut check = MigrationCheck {
+            old_url: mapping.old_url.clone(),
+            expected_url: mapping.expected_url.clone(),
+            hops: vec![],
+            final_url: None,
+            problems: vec![],
+            error: None,
+        };
+        let mut current = mapping.old_url.clone();
+
+        loop {
+            if check.hops.len() > MAX_REDIRECT_HOPS
+                || check.hops.iter().any(|hop| same_url(&hop.url, &current))
+            {
+                check.problems.push(MigrationProblem::RedirectLoop);
+                break;
+            }
+
+            let response = match self.client.get(&current).send().await {
+                Ok(response) => response,
+                Err(error) => {
+                    check.problems.push(MigrationProblem::RequestFailed);
+                    check.error = Some(error.to_string());
+                    break;
+                }
+            };
+            let status = response.status();
+            check.hops.push(RedirectHop {
+                url: current.clone(),
+                status_code: status.as_u16(),
+            });
+
+            if !status.is_redirection() {
+                check.final_url = Some(current);
+                break;
+            }
+
+            let next = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| Url::parse(&current).ok()?.join(location).ok());
+            match next {
+                Some(next) => current = next.to_string(),
+                None => {
+                    check.problems.push(MigrationProblem::RequestFailed);
+                    check.error = Some(format!("{status} response without a valid Location"));
+                    break;
+                }
+            }
+        }
+
+        check.problems.extend(classify(&check));
+        check
+    }
+}
+
+/// Problems visible from the recorded hops once the final response is known
+fn classify(check: &MigrationCheck) -> Vec<MigrationProblem> {
+    let Some(final_url) = &check.final_url else {
+        return vec![];
+    };
+    let redirects = check.hops.len() - 1;
+    let mut problems = Vec::new();
+
+    let first_status = check.hops.first().map(|hop| hop.status_code);
+    if redirects > 0 && !matches!(first_status, Some(301 | 308)) {
+        problems.push(MigrationProblem::TemporaryRedirect);
+    }
+    if redirects > 1 {
+        problems.push(MigrationProblem::RedirectChain);
+    }
+
+    let final_status = check.hops.last().map(|hop| hop.status_code);
+    if matches!(final_status, Some(404 | 410)) {
+        problems.push(MigrationProblem::NotFound);
+    } else if redirects == 0 {
+        problems.push(MigrationProblem::NotRedirected);
+    }
+
+    if redirects > 0 && !same_url(final_url, &check.expected_url) {
+        problems.push(MigrationProblem::WrongTarget);
+    }
+
+    problems
+}
+
+/// Compare URLs after parsing, ignoring fragments, which are never sent to the server
+fn same_url(a: &str, b: &str) -> bool {
+    match (Url::parse(a), Url::parse(b)) {
+        (Ok(mut a), Ok(mut b)) => {
+            a.set_fragment(None);
+            b.set_fragment(None);
+            a == b
+        }
+        _ => a == b,
+    }
+}
@@ -1,42 +1,233 @@
+use crate::crawler::DEFAULT_HTML_CONTENT_TYPES;
+use crate::page_store::PageStore;
+use crate::rules;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use url::Url;
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Same-page fragment links a page needs before it counts as using fragment navigation
+const FRAGMENT_NAVIGATION_MIN_LINKS: usize = 5;
+
+/// The content types parsed as HTML; pages of any other type are treated as files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlContentTypes(Vec<String>);
+
+impl HtmlContentTypes {
+    /// Entries are MIME types without parameters, e.g. `text/xml`, and match
+    /// case-insensitively
+    pub fn new(content_types: &[impl AsRef<str>]) -> Self {
+        Self(
+            content_types
+                .iter()
+                .map(|content_type| content_type.as_ref().trim().to_ascii_lowercase())
+                .collect(),
+        )
+    }
+
+    /// Whether a response served as `content_type` is parsed as HTML. Responses without a
+    /// content type are, as before the list was configurable.
+    pub fn contains(&self, content_type: Option<&str>) -> bool {
+        content_type.is_none_or(|content_type| {
+            let mime = content_type
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase();
+            self.0.contains(&mime)
+        })
+    }
+}
+
+impl Default for HtmlContentTypes {
+    fn default() -> Self {
+        Self::new(&DEFAULT_HTML_CONTENT_TYPES)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PageInfo {
+    /// The URL the page was served from, after following redirects
     pub url: String,
+    /// Other crawled URLs that resolved to this page, e.g. ones redirecting to it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
     pub status_code: Option<u16>,
     pub content_type: Option<String>,
+    /// The `Last-Modified` response header as an RFC 3339 timestamp in UTC
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Milliseconds from sending the request, retries included, until the body was read, or
+    /// until the headers arrived for responses whose body isn't read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_time_ms: Option<u64>,
+    /// Size of the response body: the bytes read for parsed pages, otherwise the declared
+    /// `Content-Length`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<u64>,
+    /// Every response from the requested URL to the page, with `--trace-redirects`; empty
+    /// when the page was served without redirects
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirect_chain: Vec<RedirectHop>,
+    /// URL of an earlier crawled page that returned the same status and a byte-identical body,
+    /// e.g. a session-id or print-view variant; the copy isn't parsed or analyzed again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
     pub title: Option<String>,
     pub meta_description: Option<String>,
+    /// Directives from `<meta name="robots">`, e.g. `noindex, follow`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta_robots: Option<String>,
+    /// Directives from the `X-Robots-Tag` response header, e.g. `googlebot: noindex`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_robots_tag: Option<String>,
+    /// Absolute URL from `<link rel="canonical">`, or from the `Link` header when the HTML has none
+    pub canonical: Option<String>,
+    /// `rel="alternate"` language versions from the HTML and the `Link` header
+    pub alternates: Vec<HreflangLink>,
+    /// Canonical and alternate links sent in HTTP `Link` response headers
+    pub link_headers: LinkHeaders,
+    /// Request headers named in the `Vary` response header, lowercased
+    pub vary: Vec<String>,
+    /// Request headers the page content was seen to change with (only filled in with `--audit-vary`)
+    pub vary_observed: Vec<String>,
     pub h1_tags: Vec<String>,
+    /// The page's headings as a tree, filled in with `--heading-outline`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outline: Vec<OutlineNode>,
     pub links: Vec<Link>,
     pub images: Vec<Image>,
+    pub weight: PageWeight,
     pub open_graph: OpenGraphTags,
+    /// Text, headings, and meta tags kept from parsing the page so analyzers don't parse it
+    /// again; left out of saved reports to keep them small
+    #[serde(skip)]
+    pub content: PageContent,
     pub issues: Vec<SeoIssue>,
+    /// Issues left out of `issues` by `--max-issues-per-type`, counted per type
+    pub omitted_issues: Vec<OmittedIssues>,
     pub crawl_depth: usize,
+    /// Where the crawler found the page, for tracing broken pages back to the pages linking to them
+    pub source: CrawlSource,
+    /// Screenshot file, relative to the saved report
+    pub screenshot: Option<String>,
+    /// File the body was written to with `--save-html`, as served and before any rendering
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html_snapshot: Option<String>,
+    /// Core Web Vitals measured by real Chrome users, from the Chrome UX Report
+    pub field_data: Option<FieldData>,
 }
 
 impl PageInfo {
-    pub fn display_title(&self) -> String {
+    /// Record another URL that resolved to this page, once
+    pub fn add_alias(&mut self, url: &str) {
+        if url != self.url && !self.aliases.iter().any(|alias| alias == url) {
+            self.aliases.push(url.to_string());
+        }
+    }
+
+    /// The page's title, or for files the name at the end of their URL
+    pub fn display_title(&self, html_content_types: &HtmlContentTypes) -> String {
         self.title
             .as_deref()
             .map(str::trim)
             .filter(|title| !title.is_empty())
             .map(ToOwned::to_owned)
             .or_else(|| {
-                (!Self::is_html_content_type(self.content_type.as_deref()))
+                (!html_content_types.contains(self.content_type.as_deref()))
                     .then(|| Self::resource_name_from_url(&self.url))
                     .flatten()
             })
             .unwrap_or_else(|| "(untitled)".to_string())
     }
 
-    pub fn is_html_content_type(content_type: Option<&str>) -> bool {
-        content_type.is_none_or(|ct| {
-            let ct_lower = ct.to_lowercase();
-            ct_lower.contains("text/html") || ct_lower.contains("application/xhtml")
-        })
+    /// Whether the page's robots meta tags or `X-Robots-Tag` header include `directive`, such
+    /// as `noindex` or `nofollow`; `none` counts as both. Directives aimed at one crawler, like
+    /// `googlebot: noindex`, count too.
+    pub fn has_robots_directive(&self, directive: &str) -> bool {
+        [self.meta_robots.as_deref(), self.x_robots_tag.as_deref()]
+            .into_iter()
+            .flatten()
+            .flat_map(|directives| directives.split(','))
+            .map(|token| token.rsplit(':').next().unwrap_or(token).trim())
+            .any(|token| {
+                token.eq_ignore_ascii_case(directive)
+                    || (token.eq_ignore_ascii_case("none")
+                        && ["noindex", "nofollow"]
+                            .iter()
+                            .any(|implied| directive.eq_ignore_ascii_case(implied)))
+            })
+    }
+
+    /// Keep the first `max_per_type` issues of each type and count the rest in `omitted_issues`
+    pub fn limit_issues(&mut self, max_per_type: usize) {
+        let mut kept: HashMap<IssueType, usize> = HashMap::new();
+        let mut omitted = std::mem::take(&mut self.omitted_issues);
+        self.issues.retain(|issue| {
+            let count = kept.entry(issue.issue_type).or_default();
+            if *count < max_per_type {
+                *count += 1;
+                return true;
+            }
+
+            match omitted
+                .iter_mut()
+                .find(|omitted| omitted.issue_type == issue.issue_type)
+            {
+                Some(omitted) => omitted.count += 1,
+                None => omitted.push(OmittedIssues {
+                    issue_type: issue.issue_type,
+                    severity: issue.severity,
+                    count: 1,
+                }),
+            }
+            false
+        });
+        self.omitted_issues = omitted;
+    }
+
+    /// Internal links that only jump to a section of this same page, like `#pricing`
+    pub fn same_page_fragment_links(&self) -> usize {
+        let Some(page_url) = document_url(&self.url) else {
+            return 0;
+        };
+        self.links
+            .iter()
+            .filter(|link| !link.is_external)
+            .filter(|link| {
+                Url::parse(&link.url).is_ok_and(|url| url.fragment().is_some())
+                    && document_url(&link.url).as_ref() == Some(&page_url)
+            })
+            .count()
+    }
+
+    /// One-page layouts, where most internal links are jumps to sections of the page itself
+    pub fn uses_fragment_navigation(&self) -> bool {
+        let fragment_links = self.same_page_fragment_links();
+        let internal_links = self.links.iter().filter(|link| !link.is_external).count();
+        fragment_links >= FRAGMENT_NAVIGATION_MIN_LINKS && fragment_links * 2 >= internal_links
+    }
+
+    /// Hosts of the `<iframe>` elements embedding other sites, with how many frames load
+    /// from each
+    pub fn third_party_frame_domains(&self) -> BTreeMap<String, usize> {
+        let mut domains = BTreeMap::new();
+        for link in &self.links {
+            if link.kind != DiscoveryMethod::Iframe || !link.is_external {
+                continue;
+            }
+            let Some(domain) = Url::parse(&link.url)
+                .ok()
+                .filter(|url| matches!(url.scheme(), "http" | "https"))
+                .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            else {
+                continue;
+            };
+            *domains.entry(domain).or_default() += 1;
+        }
+        domains
     }
 
     fn resource_name_from_url(url: &str) -> Option<String> {
@@ -49,6 +240,13 @@ impl PageInfo {
     }
 }
 
+/// The URL without its fragment, i.e. the document a `page#section` URL loads
+pub fn document_url(url: &str) -> Option<Url> {
+    let mut url = Url::parse(url).ok()?;
+    url.set_fragment(None);
+    Some(url)
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OpenGraphTags {
     pub og_title: Option<String>,
@@ -60,20 +258,345 @@ pub struct OpenGraphTags {
     pub og_locale: Option<String>,
 }
 
+/// Data extracted from a page's HTML for analyzers that need more than the SEO fields
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageContent {
+    /// Visible body text with whitespace collapsed, without scripts and styles
+    pub text: String,
+    /// Every `h1`-`h6` in document order
+    pub headings: Vec<Heading>,
+    /// `<meta>` content keyed by lowercased `name`, `property`, or `http-equiv`; the first tag wins
+    pub meta: BTreeMap<String, String>,
+    /// The raw HTML, only kept when script checks or the secret scan need it
+    pub html: Option<String>,
+    /// Text of every HTML comment, trimmed, in document order
+    pub comments: Vec<String>,
+}
+
+impl PageContent {
+    pub fn word_count(&self) -> usize {
+        self.text.split_whitespace().count()
+    }
+
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.meta.get(&key.to_ascii_lowercase()).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heading {
+    /// 1 for `h1` through 6 for `h6`
+    pub level: u8,
+    pub text: String,
+}
+
+/// A heading with the lower-level headings that follow it until the next heading at its level or above
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutlineNode {
+    pub level: u8,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineNode>,
+}
+
+impl OutlineNode {
+    /// Nest headings given in document order. A heading that skips levels (an `h3` right after
+    /// an `h1`) goes under the closest heading above it, and headings before the first `h1` are
+    /// roots of their own.
+    pub fn build(headings: &[Heading]) -> Vec<OutlineNode> {
+        let mut roots: Vec<OutlineNode> = Vec::new();
+        for heading in headings {
+            let node = OutlineNode {
+                level: heading.level,
+                text: heading.text.clone(),
+                children: vec![],
+            };
+            let mut siblings = &mut roots;
+            while siblings
+                .last()
+                .is_some_and(|parent| parent.level < heading.level)
+            {
+                siblings = &mut siblings.last_mut().unwrap().children;
+            }
+            siblings.push(node);
+        }
+        roots
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Link {
     pub url: String,
     pub text: String,
     pub is_external: bool,
+    /// The kind of element the link came from
+    pub kind: DiscoveryMethod,
+    /// The anchor's `rel` attribute as written, e.g. `nofollow noopener`
+    #[serde(default)]
+    pub rel: Option<String>,
     pub status_code: Option<u16>,
     pub redirected_url: Option<String>,
     pub check_error: Option<String>,
+    pub skip_reason: Option<LinkSkipReason>,
+    /// The `Content-Type` the target was served with when the link was checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// The target's declared `Content-Length` in bytes; bodies aren't downloaded to measure it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<u64>,
+}
+
+impl Link {
+    /// Whether the link leads to a file rather than a page: the check found something other
+    /// than HTML, or, when it failed, the URL ends in a document, archive, or media extension
+    pub fn is_download(&self, html_content_types: &HtmlContentTypes) -> bool {
+        let served_as_file = self.status_code.is_some_and(|code| code < 400)
+            && self.content_type.is_some()
+            && !html_content_types.contains(self.content_type.as_deref());
+        served_as_file || has_download_extension(&self.url)
+    }
+
+    /// Whether `rel` asks crawlers not to follow the link: `nofollow`, `ugc`, or `sponsored`
+    pub fn is_nofollow(&self) -> bool {
+        self.rel.as_deref().is_some_and(|rel| {
+            rel.split_ascii_whitespace().any(|token| {
+                ["nofollow", "ugc", "sponsored"]
+                    .iter()
+                    .any(|hint| token.eq_ignore_ascii_case(hint))
+            })
+        })
+    }
+}
+
+/// File extensions of links that are downloads whatever the server answers
+const DOWNLOAD_EXTENSIONS: [&str; 29] = [
+    "7z", "apk", "avi", "csv", "dmg", "doc", "docx", "epub", "exe", "flac", "gz", "iso", "mov",
+    "mp3", "mp4", "msi", "odp", "ods", "odt", "pdf", "ppt", "pptx", "rar", "tar", "tgz", "wav",
+    "xls", "xlsx", "zip",
+];
+
+fn has_download_extension(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            let segment = url.path_segments()?.next_back()?.to_string();
+            let (_, extension) = segment.rsplit_once('.')?;
+            Some(extension.to_ascii_lowercase())
+        })
+        .is_some_and(|extension| DOWNLOAD_EXTENSIONS.contains(&extension.as_str()))
+}
+
+/// How the crawler came across a URL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoveryMethod {
+    /// A start URL given to the crawl
+    Seed,
+    /// `<a href>`
+    #[default]
+    Anchor,
+    /// `<iframe src>`
+    Iframe,
+    /// `<video>`, `<audio>`, `<source>`, `<embed>` or `<object>`
+    Embed,
+    /// A `<loc>` entry in an XML sitemap
+    Sitemap,
+}
+
+impl DiscoveryMethod {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Seed => "seed",
+            Self::Anchor => "anchor",
+            Self::Iframe => "iframe",
+            Self::Embed => "embed",
+            Self::Sitemap => "sitemap",
+        }
+    }
+}
+
+/// What the crawler did with a URL at one point of the crawl
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontierAction {
+    Queued,
+    Skipped,
+    Crawled,
+}
+
+/// One step in the crawl's handling of a URL followed with `--explain`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrontierDecision {
+    pub action: FrontierAction,
+    pub reason: String,
+    /// The page or sitemap the URL was found on at this step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub found_on: Option<String>,
+}
+
+/// Why a page couldn't be crawled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrawlErrorCause {
+    /// No response before the request timeout
+    Timeout,
+    /// The host name didn't resolve
+    Dns,
+    /// The TLS handshake failed, e.g. on an untrusted or expired certificate
+    Tls,
+    /// The connection was refused or dropped
+    Connection,
+    /// The server answered with a 5xx status, after any retries
+    ServerError,
+    /// Redirects looped or went on past the redirect limit
+    Redirect,
+    /// The response couldn't be read or parsed, e.g. a body cut off mid-transfer
+    Parse,
+    Other,
+}
+
+impl CrawlErrorCause {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::Dns => "DNS",
+            Self::Tls => "TLS",
+            Self::Connection => "connection",
+            Self::ServerError => "5xx",
+            Self::Redirect => "redirect",
+            Self::Parse => "parse",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// A page that failed to fetch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlError {
+    pub url: String,
+    /// The request error, or the status for server errors
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    pub crawl_depth: usize,
+    pub source: CrawlSource,
+}
+
+/// The page a URL was first found on and how
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlSource {
+    pub method: DiscoveryMethod,
+    /// The referring page, or `None` for start URLs
+    pub found_on: Option<String>,
+}
+
+impl Default for CrawlSource {
+    fn default() -> Self {
+        Self {
+            method: DiscoveryMethod::Seed,
+            found_on: None,
+        }
+    }
+}
+
+impl CrawlSource {
+    /// "anchor on https://example.com/", or just "seed" for start URLs
+    pub fn describe(&self) -> String {
+        match &self.found_on {
+            Some(found_on) => format!("{} on {found_on}", self.method.label()),
+            None => self.method.label().to_string(),
+        }
+    }
+}
+
+/// Why a link was deliberately not requested during link checking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSkipReason {
+    Robots,
+    /// `--max-requests` or `--max-bandwidth` was reached before the link's turn
+    RequestLimit,
+    /// Left out of the share of external links checked with `--external-check-sample`
+    Sampled,
+    /// The target is, resolves to, or redirects to a private address, with `--block-private-ips`
+    PrivateAddress,
+}
+
+impl LinkSkipReason {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Robots => "not checked (robots)",
+            Self::RequestLimit => "not checked (request limit)",
+            Self::Sampled => "not checked (sampled out)",
+            Self::PrivateAddress => "not checked (private address)",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Image {
     pub src: String,
     pub alt: Option<String>,
+    /// Size from the `width` and `height` attributes, in CSS pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+/// A language or regional version of a page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HreflangLink {
+    pub hreflang: String,
+    pub url: String,
+    /// Set when the hreflang check deliberately didn't request the alternate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<LinkSkipReason>,
+}
+
+/// Links some servers and CDNs only send as `Link: <url>; rel="canonical"` response headers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkHeaders {
+    pub canonical: Option<String>,
+    pub alternates: Vec<HreflangLink>,
+}
+
+/// Whether field data describes the page itself or, when the page has too little traffic, its origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldDataScope {
+    Url,
+    Origin,
+}
+
+/// 75th percentile Core Web Vitals from the Chrome UX Report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldData {
+    pub scope: FieldDataScope,
+    /// Largest Contentful Paint in milliseconds
+    pub lcp_ms: Option<f64>,
+    /// Cumulative Layout Shift (unitless)
+    pub cls: Option<f64>,
+    /// Interaction to Next Paint in milliseconds
+    pub inp_ms: Option<f64>,
+}
+
+/// Size of a page's HTML and the requests its markup implies, used for performance budgets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageWeight {
+    pub html_bytes: usize,
+    /// The document plus every script, stylesheet, image, frame, and media source it references
+    pub requests: usize,
+    /// Inline and external `<script>` elements
+    pub scripts: usize,
+}
+
+/// How many issues of one type were left off a page's issue list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OmittedIssues {
+    pub issue_type: IssueType,
+    pub severity: IssueSeverity,
+    pub count: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,6 +604,95 @@ pub struct SeoIssue {
     pub severity: IssueSeverity,
     pub issue_type: IssueType,
     pub message: String,
+    /// URL of the link or resource that triggered the issue, when it isn't the page itself
+    pub offending_url: Option<String>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    pub help_url: Option<String>,
+    /// Name of the custom script check that raised the issue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check: Option<String>,
+    /// Stable identifier used to match the same issue across runs; filled in when a report is generated
+    pub fingerprint: Option<String>,
+}
+
+impl SeoIssue {
+    pub fn new(severity: IssueSeverity, issue_type: IssueType, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            issue_type,
+            message: message.into(),
+            offending_url: None,
+            expected: None,
+            actual: None,
+            help_url: Some(rules::rule_for(issue_type).help_url.to_string()),
+            check: None,
+            fingerprint: None,
+        }
+    }
+
+    /// Hash of the issue type, the normalized page URL, the offending URL, and the custom check
+    /// name. Free-form details like the message or measured lengths are left out so that
+    /// editing a page doesn't turn a known issue into a new one.
+    pub fn compute_fingerprint(&self, page_url: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{:?}", self.issue_type));
+        hasher.update([0]);
+        hasher.update(normalize_fingerprint_url(page_url));
+        hasher.update([0]);
+        if let Some(offending_url) = &self.offending_url {
+            hasher.update(normalize_fingerprint_url(offending_url));
+        }
+        if let Some(check) = &self.check {
+            hasher.update([0]);
+            hasher.update(check);
+        }
+
+        hasher.finalize()[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    pub fn with_offending_url(mut self, url: impl Into<String>) -> Self {
+        self.offending_url = Some(url.into());
+        self
+    }
+
+    pub fn with_expected(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+
+    pub fn with_actual(mut self, actual: impl Into<String>) -> Self {
+        self.actual = Some(actual.into());
+        self
+    }
+
+    pub fn with_check(mut self, check: impl Into<String>) -> Self {
+        self.check = Some(check.into());
+        self
+    }
+}
+
+pub fn normalize_fingerprint_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.trim().to_string();
+    };
+    parsed.set_fragment(None);
+
+    let mut normalized = parsed.to_string();
+    if parsed.path() != "/" && parsed.query().is_none() && normalized.ends_with('/') {
+        normalized.pop();
+    }
+    normalized
+}
+
+/// One response in a redirect path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status_code: u16,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -90,7 +702,7 @@ pub enum IssueSeverity {
     Info,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum IssueType {
     MissingTitle,
     TitleTooShort,
@@ -104,22 +716,101 @@ pub enum IssueType {
     ThinContent,
     BrokenLink,
     Redirect,
+    RedirectLoop,
     MissingOgTitle,
     MissingOgDescription,
     MissingOgImage,
     MissingOgUrl,
     MissingOgType,
+    PerformanceBudgetExceeded,
+    CanonicalConflict,
+    HreflangConflict,
+    HreflangBrokenTarget,
+    HreflangRedirectTarget,
+    HreflangMissingReturnLink,
+    UndeclaredVary,
+    MissingVaryAcceptEncoding,
+    /// Raised by a user's script check
+    CustomCheck,
+    OrphanPage,
+    CrossHostDuplicate,
+    /// A selector assertion from the config doesn't hold
+    AssertionFailed,
+    CommentMarker,
+    CommentSecret,
+    ExposedEmail,
+    ExposedPhoneNumber,
+    ExposedSecret,
+    /// The page changed after its sitemap `<lastmod>`
+    SitemapLastmodStale,
+    /// A sitemap `<lastmod>` that doesn't parse, lies in the future, or claims a later change
+    /// than the page's `Last-Modified`
+    SitemapLastmodInaccurate,
+    /// The page's robots directives keep it out of search results
+    NoindexPage,
+    /// The page's body passed the crawler's `max_body_bytes`, so it wasn't parsed
+    ResponseTooLarge,
+    /// A traced redirect chain has more hops than `redirect_chain_limit`
+    RedirectChainTooLong,
+    /// A link with tracking or affiliate parameters redirects to a URL without them
+    TrackingParamsStripped,
+    /// A link or image points at localhost, a local network name, or an IP address
+    NonPublicLink,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrawlReport {
     pub start_url: String,
     pub pages: HashMap<String, PageInfo>,
+    /// Every link target, without its fragment, mapped to the crawled pages linking to it
+    #[serde(default)]
+    pub inlinks: BTreeMap<String, Vec<String>>,
     pub summary: CrawlSummary,
     pub timestamp: String,
+    /// Set when `--max-requests`, `--max-bandwidth`, or `--max-duration` cut the scan short
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_reached: Option<String>,
+    /// How the crawl handled each URL given to `--explain`, step by step
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub explain: BTreeMap<String, Vec<FrontierDecision>>,
+    /// Pages that failed to fetch, grouped by cause. They are missing from `pages`, except
+    /// server errors and redirect loops, whose responses are reported there too.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<CrawlErrorCause, Vec<CrawlError>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+impl CrawlReport {
+    /// Build the reverse link index: target URL to the sorted URLs of the pages linking to it
+    pub fn index_inlinks(pages: &dyn PageStore) -> anyhow::Result<BTreeMap<String, Vec<String>>> {
+        let mut inlinks: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        pages.for_each_page(&mut |_, page| {
+            let page_url = document_url(&page.url);
+            for target in page.links.iter().filter_map(|link| document_url(&link.url)) {
+                if Some(&target) != page_url.as_ref() {
+                    inlinks
+                        .entry(target.to_string())
+                        .or_default()
+                        .insert(page.url.clone());
+                }
+            }
+            Ok(())
+        })?;
+        Ok(inlinks
+            .into_iter()
+            .map(|(target, sources)| (target, sources.into_iter().collect()))
+            .collect())
+    }
+
+    /// The crawled pages linking to `url`, ignoring its fragment
+    pub fn linking_pages(&self, url: &str) -> &[String] {
+        document_url(url)
+            .and_then(|url| self.inlinks.get(url.as_str()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct CrawlSummary {
     pub total_pages: usize,
     pub total_links: usize,
@@ -127,6 +818,128 @@ pub struct CrawlSummary {
     pub errors: usize,
     pub warnings: usize,
     pub infos: usize,
+    /// Pages whose internal links mostly jump to their own sections
+    #[serde(default)]
+    pub fragment_navigation_pages: usize,
+    #[serde(default)]
+    pub images: ImageStats,
+    #[serde(default)]
+    pub downloads: DownloadStats,
+    #[serde(default)]
+    pub performance: PerformanceStats,
+    #[serde(default)]
+    pub third_party_frames: ThirdPartyFrameStats,
+}
+
+/// Alt text coverage of the `<img>` elements on every crawled page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ImageStats {
+    /// `<img>` elements, counting an image once per page it appears on
+    pub total: usize,
+    /// Distinct image URLs
+    pub unique: usize,
+    pub with_alt: usize,
+    /// Images marked decorative with an empty `alt=""`
+    pub decorative: usize,
+    pub missing_alt: usize,
+    /// Images with the largest declared `width` x `height`, largest first
+    pub largest: Vec<LargestImage>,
+}
+
+impl ImageStats {
+    pub fn with_alt_percent(&self) -> f64 {
+        self.percent(self.with_alt)
+    }
+
+    pub fn decorative_percent(&self) -> f64 {
+        self.percent(self.decorative)
+    }
+
+    fn percent(&self, count: usize) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        count as f64 * 100.0 / self.total as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LargestImage {
+    pub src: String,
+    /// A page the image appears on
+    pub page_url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl LargestImage {
+    pub fn area(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+}
+
+/// Links to files rather than pages, counting each target URL once
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DownloadStats {
+    pub total: usize,
+    /// Downloads whose check returned an error status or failed
+    pub broken: usize,
+    /// Downloads with the largest declared size, largest first
+    pub largest: Vec<LargestDownload>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LargestDownload {
+    pub url: String,
+    /// A page linking to the download
+    pub page_url: String,
+    pub content_type: Option<String>,
+    pub content_length: u64,
+}
+
+/// Response times and body sizes of the crawled pages that were fetched
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PerformanceStats {
+    /// Pages with a measured response time
+    pub measured: usize,
+    pub average_response_time_ms: Option<u64>,
+    pub average_content_length: Option<u64>,
+    /// Pages that took longest to respond, slowest first
+    pub slowest: Vec<SlowestPage>,
+    /// Pages with the largest bodies, largest first
+    pub largest: Vec<LargestPage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SlowestPage {
+    pub url: String,
+    pub response_time_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LargestPage {
+    pub url: String,
+    pub content_length: u64,
+}
+
+/// Iframes loading other sites, such as video players, maps, and ads, by the host they load from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ThirdPartyFrameStats {
+    /// Third-party `<iframe>` elements, counting a frame once per page it appears on
+    pub total: usize,
+    /// Pages embedding at least one third-party iframe
+    pub pages: usize,
+    /// Each host frames load from, most frames first
+    pub domains: Vec<ThirdPartyFrameDomain>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ThirdPartyFrameDomain {
+    pub domain: String,
+    /// Iframes loading from the domain across all pages
+    pub frames: usize,
+    /// The pages embedding it, in URL order
+    pub pages: Vec<String>,
 }
 
 #[cfg(test)]
@@ -138,14 +951,35 @@ mod tests {
             url: url.to_string(),
             status_code: Some(200),
             content_type: content_type.map(str::to_string),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
             title: title.map(str::to_string),
             meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
             h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
             links: vec![],
             images: vec![],
+            weight: Default::default(),
             open_graph: OpenGraphTags::default(),
+            content: Default::default(),
             issues: vec![],
+            omitted_issues: vec![],
             crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
         }
     }
 
@@ -157,7 +991,10 @@ mod tests {
             None,
         );
 
-        assert_eq!(page.display_title(), "video1.mp4");
+        assert_eq!(
+            page.display_title(&HtmlContentTypes::default()),
+            "video1.mp4"
+        );
     }
 
     #[test]
@@ -168,6 +1005,178 @@ mod tests {
             None,
         );
 
-        assert_eq!(page.display_title(), "(untitled)");
+        assert_eq!(
+            page.display_title(&HtmlContentTypes::default()),
+            "(untitled)"
+        );
+    }
+
+    #[test]
+    fn display_title_follows_the_configured_html_content_types() {
+        let page = page("https://example.com/feed.xml", Some("text/xml"), None);
+        let with_xml = HtmlContentTypes::new(&["text/html", "text/xml"]);
+
+        assert_eq!(page.display_title(&HtmlContentTypes::default()), "feed.xml");
+        assert_eq!(page.display_title(&with_xml), "(untitled)");
+    }
+
+    #[test]
+    fn limit_issues_keeps_the_first_of_each_type_and_counts_the_rest() {
+        let mut page = page("https://example.com/", Some("text/html"), None);
+        page.issues = (0..5)
+            .map(|index| {
+                SeoIssue::new(
+                    IssueSeverity::Error,
+                    IssueType::BrokenLink,
+                    format!("Broken link {index}"),
+                )
+            })
+            .chain([SeoIssue::new(
+                IssueSeverity::Warning,
+                IssueType::MissingTitle,
+                "Page is missing a title tag",
+            )])
+            .collect();
+
+        page.limit_issues(2);
+
+        let messages: Vec<&str> = page
+            .issues
+            .iter()
+            .map(|issue| issue.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Broken link 0",
+                "Broken link 1",
+                "Page is missing a title tag"
+            ]
+        );
+        assert_eq!(
+            page.omitted_issues,
+            [OmittedIssues {
+                issue_type: IssueType::BrokenLink,
+                severity: IssueSeverity::Error,
+                count: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn fragment_navigation_needs_mostly_same_page_links() {
+        let link = |url: &str| Link {
+            url: url.to_string(),
+            text: String::new(),
+            is_external: false,
+            kind: Default::default(),
+            rel: None,
+            status_code: None,
+            redirected_url: None,
+            check_error: None,
+            skip_reason: None,
+            content_type: None,
+            content_length: None,
+        };
+        let mut one_page = page("https://example.com/", Some("text/html"), None);
+        one_page.links = ["#about", "#services", "#work", "#team", "#contact"]
+            .iter()
+            .map(|fragment| link(&format!("https://example.com/{fragment}")))
+            .chain([
+                link("https://example.com/blog"),
+                link("https://example.com/other#top"),
+            ])
+            .collect();
+
+        assert_eq!(one_page.same_page_fragment_links(), 5);
+        assert!(one_page.uses_fragment_navigation());
+
+        one_page
+            .links
+            .extend((0..4).map(|index| link(&format!("https://example.com/post-{index}"))));
+        assert!(!one_page.uses_fragment_navigation());
+    }
+
+    #[test]
+    fn fingerprint_ignores_message_and_url_fragments() {
+        let issue = SeoIssue::new(
+            IssueSeverity::Warning,
+            IssueType::TitleTooShort,
+            "Title (10 chars)",
+        );
+        let mut edited = issue.clone();
+        edited.message = "Title (12 chars)".to_string();
+
+        assert_eq!(
+            issue.compute_fingerprint("https://example.com/about"),
+            edited.compute_fingerprint("HTTPS://Example.com/about/#team")
+        );
+        assert_eq!(
+            issue.compute_fingerprint("https://example.com/about").len(),
+            16
+        );
+    }
+
+    #[test]
+    fn fingerprint_distinguishes_type_page_and_offending_url() {
+        let issue = SeoIssue::new(IssueSeverity::Error, IssueType::BrokenLink, "Broken link")
+            .with_offending_url("https://example.com/a");
+        let other_link = issue.clone().with_offending_url("https://example.com/b");
+        let other_type = SeoIssue {
+            issue_type: IssueType::Redirect,
+            ..issue.clone()
+        };
+
+        let fingerprint = issue.compute_fingerprint("https://example.com/");
+        assert_ne!(
+            fingerprint,
+            other_link.compute_fingerprint("https://example.com/")
+        );
+        assert_ne!(
+            fingerprint,
+            other_type.compute_fingerprint("https://example.com/")
+        );
+        assert_ne!(
+            fingerprint,
+            issue.compute_fingerprint("https://example.com/other")
+        );
+    }
+
+    #[test]
+    fn test_outline_nests_headings_under_the_closest_higher_level() {
+        let heading = |level, text: &str| Heading {
+            level,
+            text: text.to_string(),
+        };
+        let node = |level, text: &str, children| OutlineNode {
+            level,
+            text: text.to_string(),
+            children,
+        };
+
+        let outline = OutlineNode::build(&[
+            heading(2, "Skip to content"),
+            heading(1, "Guide"),
+            heading(3, "Skipped a level"),
+            heading(2, "Setup"),
+            heading(3, "Install"),
+            heading(2, "Usage"),
+        ]);
+
+        assert_eq!(
+            outline,
+            vec![
+                node(2, "Skip to content", vec![]),
+                node(
+                    1,
+                    "Guide",
+                    vec![
+                        node(3, "Skipped a level", vec![]),
+                        node(2, "Setup", vec![node(3, "Install", vec![])]),
+                        node(2, "Usage", vec![]),
+                    ]
+                ),
+            ]
+        );
     }
 }
@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use crate::models::{OutlineNode, PageInfo};
+use crate::page_store::PageStore;
+use crate::report_dir::page_file_name;
+
+/// Write the outline of every page that has headings to `<dir>/<file>.md`, named after the
+/// page's host and path. Returns how many files were written.
+pub fn save_markdown_outlines(pages: &dyn PageStore, dir: &Path) -> Result<usize> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create outline directory: {}", dir.display()))?;
+
+    let mut written = 0;
+    pages.for_each_page(&mut |_, page| {
+        if !page.outline.is_empty() {
+            save_markdown_outline(page, dir)?;
+            written += 1;
+        }
+        Ok(())
+    })?;
+    Ok(written)
+}
+
+fn save_markdown_outline(page: &PageInfo, dir: &Path) -> Result<()> {
+    let path = dir.join(markdown_file_name(&page.url));
+    fs::write(&path, render_markdown(page))
+        .with_context(|| format!("Failed to write outline: {}", path.display()))
+}
+
+/// The page's outline as a nested Markdown list, one `- H2 Heading text` item per heading
+pub fn render_markdown(page: &PageInfo) -> String {
+    let mut markdown = format!("# Outline of {}\n\n", page.url);
+    if let Some(title) = &page.title {
+        let _ = writeln!(markdown, "Title: {title}\n");
+    }
+    for node in &page.outline {
+        write_node(&mut markdown, node, 0);
+    }
+    markdown
+}
+
+fn write_node(markdown: &mut String, node: &OutlineNode, depth: usize) {
+    let text = if node.text.is_empty() {
+        "(empty)"
+    } else {
+        node.text.as_str()
+    };
+    let _ = writeln!(markdown, "{}- H{} {text}", "  ".repeat(depth), node.level);
+    for child in &node.children {
+        write_node(markdown, child, depth + 1);
+    }
+}
+
+/// A file name for a page's outline, e.g. `example.com_blog_post.md`
+pub fn markdown_file_name(page_url: &str) -> String {
+    page_file_name(page_url, "md")
+}
@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::crawl_state::SavedPage;
+use crate::models::PageInfo;
+
+/// Where a crawl keeps the pages it fetched, keyed by normalized URL like `Crawler::pages`
+pub trait PageStore: Send + Sync {
+    /// Add a page, replacing any page kept under the same key
+    fn insert(&mut self, key: String, page: PageInfo) -> Result<()>;
+
+    fn get(&self, key: &str) -> Result<Option<PageInfo>>;
+
+    fn len(&self) -> Result<usize>;
+
+    fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Call `visit` with every page in key order, reading them one at a time so a store on
+    /// disk never holds all of them in memory
+    fn for_each_page(&self, visit: &mut dyn FnMut(&str, &PageInfo) -> Result<()>) -> Result<()>;
+
+    /// Call `update` with every page in key order and keep what it changed, one page at a time
+    fn update_each(
+        &mut self,
+        update: &mut dyn FnMut(&str, &mut PageInfo) -> Result<()>,
+    ) -> Result<()>;
+}
+
+/// Pages kept in memory, the default for crawls that fit in RAM
+impl PageStore for HashMap<String, PageInfo> {
+    fn insert(&mut self, key: String, page: PageInfo) -> Result<()> {
+        HashMap::insert(self, key, page);
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<PageInfo>> {
+        Ok(HashMap::get(self, key).cloned())
+    }
+
+    fn len(&self) -> Result<usize> {
+        Ok(HashMap::len(self))
+    }
+
+    fn for_each_page(&self, visit: &mut dyn FnMut(&str, &PageInfo) -> Result<()>) -> Result<()> {
+        let mut keys: Vec<&String> = self.keys().collect();
+        keys.sort();
+        for key in keys {
+            visit(key, &self[key])?;
+        }
+        Ok(())
+    }
+
+    fn update_each(
+        &mut self,
+        update: &mut dyn FnMut(&str, &mut PageInfo) -> Result<()>,
+    ) -> Result<()> {
+        let mut pages: Vec<(&String, &mut PageInfo)> = self.iter_mut().collect();
+        pages.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, page) in pages {
+            update(key, page)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pages kept in an SQLite database file, one row of JSON per page, for crawls too large to
+/// hold in memory
+pub struct SqlitePageStore {
+    // A connection can't be shared between threads, while the crawler is
+    connection: Mutex<Connection>,
+}
+
+impl SqlitePageStore {
+    /// Open the database at `path`, creating it if needed. Pages of an earlier crawl stored in
+    /// it are removed, so the store only ever holds one crawl.
+    pub fn create(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path)
+            .with_context(|| format!("Failed to open page store: {}", path.display()))?;
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 PRAGMA synchronous = NORMAL;
+                 CREATE TABLE IF NOT EXISTS pages (key TEXT PRIMARY KEY, page TEXT NOT NULL);
+                 DELETE FROM pages;",
+            )
+            .with_context(|| format!("Failed to set up page store: {}", path.display()))?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Pages are saved with their content, which reports leave out, like in the crawl state
+fn decode_page(json: &str) -> Result<PageInfo> {
+    let saved: SavedPage = serde_json::from_str(json).context("Failed to parse stored page")?;
+    Ok(saved.into_page().1)
+}
+
+impl PageStore for SqlitePageStore {
+    fn insert(&mut self, key: String, page: PageInfo) -> Result<()> {
+        let json = serde_json::to_string(&SavedPage::new(&key, &page))?;
+        self.connection()
+            .execute(
+                "INSERT OR REPLACE INTO pages (key, page) VALUES (?1, ?2)",
+                params![key, json],
+            )
+            .context("Failed to store page")?;
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<PageInfo>> {
+        let json: Option<String> = self
+            .connection()
+            .query_row(
+                "SELECT page FROM pages WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Failed to read stored page")?;
+        json.as_deref().map(decode_page).transpose()
+    }
+
+    fn len(&self) -> Result<usize> {
+        let count: i64 = self
+            .connection()
+            .query_row("SELECT COUNT(*) FROM pages", [], |row| row.get(0))
+            .context("Failed to count stored pages")?;
+        Ok(count as usize)
+    }
+
+    fn for_each_page(&self, visit: &mut dyn FnMut(&str, &PageInfo) -> Result<()>) -> Result<()> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT key, page FROM pages ORDER BY key")?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next().context("Failed to read stored pages")? {
+            let key: String = row.get(0)?;
+            let json: String = row.get(1)?;
+            visit(&key, &decode_page(&json)?)?;
+        }
+        Ok(())
+    }
+
+    fn update_each(
+        &mut self,
+        update: &mut dyn FnMut(&str, &mut PageInfo) -> Result<()>,
+    ) -> Result<()> {
+        // Rows aren't rewritten while a query is reading the table, so the keys come first
+        let keys: Vec<String> = {
+            let connection = self.connection();
+            let mut statement = connection.prepare("SELECT key FROM pages ORDER BY key")?;
+            statement
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()
+                .context("Failed to read stored page keys")?
+        };
+
+        self.connection().execute_batch("BEGIN")?;
+        let result = keys.iter().try_for_each(|key| {
+            let Some(mut page) = self.get(key)? else {
+                return Ok(());
+            };
+            update(key, &mut page)?;
+            self.insert(key.clone(), page)
+        });
+        match result {
+            Ok(()) => self.connection().execute_batch("COMMIT")?,
+            Err(_) => self.connection().execute_batch("ROLLBACK")?,
+        }
+        result
+    }
+}
@@ -0,0 +1,121 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::task::JoinHandle;
+
+use crate::cli::OutputFormat;
+use crate::runtime::{ProgressSnapshot, RunEvent, RunEventSender, RunStage};
+
+/// Minimum time between two JSON progress lines within the same stage
+const JSON_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How scan progress is shown in CLI mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressFormat {
+    /// Spinner and progress bar on the terminal
+    #[default]
+    Bar,
+    /// One JSON object per line on stderr
+    Json,
+    /// No progress output
+    None,
+}
+
+impl ProgressFormat {
+    /// Progress bars draw on stderr, so they are skipped when it isn't a terminal and in JSON
+    /// output mode, where wrappers read the streams
+    pub const fn shows_bars(self, output_format: OutputFormat, stderr_is_terminal: bool) -> bool {
+        matches!(self, Self::Bar) && !output_format.is_json() && stderr_is_terminal
+    }
+}
+
+/// One line of `--progress json` output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressLine {
+    pub stage: RunStage,
+    pub pages_crawled: usize,
+    /// URLs waiting to be crawled
+    pub queued_urls: usize,
+    pub links_checked: usize,
+    pub total_links: usize,
+    pub elapsed_secs: f64,
+    /// Estimated seconds until the current stage finishes, once there is progress to extrapolate
+    pub eta_secs: Option<f64>,
+}
+
+impl ProgressLine {
+    /// Describe a snapshot taken `elapsed` into the scan and `stage_elapsed` into its stage
+    pub fn new(
+        snapshot: &ProgressSnapshot,
+        elapsed: Duration,
+        stage_elapsed: Duration,
+        max_pages: usize,
+    ) -> Self {
+        let (done, remaining) = match snapshot.stage {
+            RunStage::Crawling => (
+                snapshot.pages_crawled,
+                snapshot
+                    .queued_urls
+                    .min(max_pages.saturating_sub(snapshot.pages_crawled)),
+            ),
+            RunStage::CheckingLinks => (
+                snapshot.links_checked,
+                snapshot.total_links.saturating_sub(snapshot.links_checked),
+            ),
+            _ => (0, 0),
+        };
+        let eta_secs = (done > 0)
+            .then(|| stage_elapsed.as_secs_f64() * remaining as f64 / done as f64)
+            .map(round_tenths);
+
+        Self {
+            stage: snapshot.stage,
+            pages_crawled: snapshot.pages_crawled,
+            queued_urls: snapshot.queued_urls,
+            links_checked: snapshot.links_checked,
+            total_links: snapshot.total_links,
+            elapsed_secs: round_tenths(elapsed.as_secs_f64()),
+            eta_secs,
+        }
+    }
+}
+
+fn round_tenths(seconds: f64) -> f64 {
+    (seconds * 10.0).round() / 10.0
+}
+
+/// Print progress events sent to the returned sender as JSON lines on stderr: on every stage
+/// change and at most once per second otherwise. The task ends once every sender is dropped.
+pub fn spawn_json_progress(max_pages: usize) -> (RunEventSender, JoinHandle<()>) {
+    let (sender, mut receiver) = unbounded_channel();
+    let writer = tokio::spawn(async move {
+        let started = Instant::now();
+        let mut stage_started = started;
+        let mut last_stage = None;
+        let mut last_line: Option<Instant> = None;
+
+        while let Some(event) = receiver.recv().await {
+            let RunEvent::Progress(snapshot) = event else {
+                continue;
+            };
+
+            let now = Instant::now();
+            let stage_changed = last_stage != Some(snapshot.stage);
+            if stage_changed {
+                stage_started = now;
+                last_stage = Some(snapshot.stage);
+            } else if last_line.is_some_and(|line| now - line < JSON_PROGRESS_INTERVAL) {
+                continue;
+            }
+
+            let line = ProgressLine::new(&snapshot, now - started, now - stage_started, max_pages);
+            if let Ok(json) = serde_json::to_string(&line) {
+                eprintln!("{json}");
+            }
+            last_line = Some(now);
+        }
+    });
+    (sender, writer)
+}
@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::config::RuntimeOptions;
+use crate::models::{CrawlReport, HtmlContentTypes, IssueSeverity, PageInfo};
+use crate::reporter::Reporter;
+
+/// A saved report whose pages are fetched again, with the fresh results merged back into it
+#[derive(Debug, Clone)]
+pub struct Recrawl {
+    pub prior: CrawlReport,
+    /// Pages to fetch again, sorted
+    pub urls: Vec<String>,
+}
+
+impl Recrawl {
+    /// Load a saved JSON report and pick every page, or with `only_errors` just the pages that
+    /// had errors, failed to load, or had broken links
+    pub fn from_file(path: &Path, only_errors: bool) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read report: {}", path.display()))?;
+        let prior: CrawlReport = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse report: {}", path.display()))?;
+
+        let mut urls: Vec<String> = prior
+            .pages
+            .iter()
+            .filter(|(_, page)| !only_errors || needs_fix(page))
+            .map(|(url, _)| url.clone())
+            .chain(
+                prior
+                    .errors
+                    .values()
+                    .flatten()
+                    .map(|error| error.url.clone()),
+            )
+            .collect();
+        urls.sort();
+        urls.dedup();
+        if urls.is_empty() {
+            anyhow::bail!(
+                "Report {} has no {}pages to recrawl",
+                path.display(),
+                if only_errors { "failing or error " } else { "" }
+            );
+        }
+
+        Ok(Self { prior, urls })
+    }
+
+    /// Crawl the picked URLs the way `--url-file` does: at depth 0, every one of them
+    pub fn apply(&self, runtime: &mut RuntimeOptions) {
+        runtime.depth = 0;
+        runtime.max_pages = runtime.max_pages.max(self.urls.len());
+        runtime.url = self.urls.first().cloned();
+        runtime.additional_urls = self.urls[1..].to_vec();
+    }
+
+    /// The prior report with each recrawled page replaced by its fresh result. Pages keep the
+    /// depth, source, and aliases they were first found with, a page that now redirects is
+    /// replaced by the page it resolves to, a page that now fails to fetch is replaced by its
+    /// error, and the summary and inlinks are recomputed.
+    pub fn merge(
+        self,
+        fresh: CrawlReport,
+        html_content_types: &HtmlContentTypes,
+    ) -> Result<CrawlReport> {
+        let Self { prior, urls } = self;
+        let mut pages = prior.pages;
+        let mut errors = prior.errors;
+        for failures in errors.values_mut() {
+            failures.retain(|error| urls.binary_search(&error.url).is_err());
+        }
+        for (cause, failures) in fresh.errors {
+            for error in &failures {
+                pages.remove(&error.url);
+            }
+            errors.entry(cause).or_default().extend(failures);
+        }
+        errors.retain(|_, failures| !failures.is_empty());
+        for (url, mut page) in fresh.pages {
+            let previous: Vec<PageInfo> = std::iter::once(&url)
+                .chain(&page.aliases)
+                .filter_map(|key| pages.remove(key))
+                .collect();
+            if let Some(first) = previous.first() {
+                page.crawl_depth = first.crawl_depth;
+                page.source = first.source.clone();
+            }
+            for previous in &previous {
+                for alias in std::iter::once(&previous.url).chain(&previous.aliases) {
+                    page.add_alias(alias);
+                }
+            }
+            pages.insert(url, page);
+        }
+
+        Ok(CrawlReport {
+            summary: Reporter::summarize_pages(&pages, html_content_types)?,
+            inlinks: CrawlReport::index_inlinks(&pages)?,
+            pages,
+            start_url: prior.start_url,
+            timestamp: fresh.timestamp,
+            limit_reached: fresh.limit_reached,
+            explain: fresh.explain,
+            errors,
+        })
+    }
+}
+
+/// Whether a page still has something to fix: an error, a failed fetch, or a broken link
+fn needs_fix(page: &PageInfo) -> bool {
+    page.status_code.is_none_or(|code| code >= 400)
+        || page
+            .issues
+            .iter()
+            .any(|issue| issue.severity == IssueSeverity::Error)
+        || page
+            .omitted_issues
+            .iter()
+            .any(|omitted| omitted.severity == IssueSeverity::Error)
+        || page.links.iter().any(|link| {
+            link.status_code.is_some_and(|code| code >= 400) || link.check_error.is_some()
+        })
+}
@@ -0,0 +1,293 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use futures::{SinkExt, StreamExt};
+use reqwest::cookie::{CookieStore, Jar};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+use crate::http_client::{TlsOptions, USER_AGENT};
+use crate::limits::RequestLimits;
+use crate::screenshot::{BROWSER_CANDIDATES, find_browser};
+
+/// Time the page's scripts get to run, in virtual time, before the DOM is read
+const SCRIPT_BUDGET_MS: u32 = 5_000;
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+/// What Chrome prints to stderr once its DevTools endpoint is up
+const DEVTOOLS_LISTENING: &str = "DevTools listening on ";
+
+/// How the crawler gets the HTML it extracts page data from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderMode {
+    /// Parse the HTML the server sends
+    #[default]
+    Http,
+    /// Parse the DOM after a headless Chrome or Chromium has run the page's JavaScript
+    Js,
+}
+
+/// The crawl's session, so the browser requests pages the way the crawler does
+#[derive(Clone, Copy)]
+pub struct RenderSession<'a> {
+    /// Cookies sent with the page, e.g. from `--cookie` or a login
+    pub cookies: Option<&'a Jar>,
+    pub tls: &'a TlsOptions,
+    /// Every request the browser makes counts against these, and is refused once they run out
+    pub limits: Option<&'a RequestLimits>,
+}
+
+/// Renders pages with a headless Chrome or Chromium so links and tags added by client-side
+/// frameworks are visible to the crawler
+pub struct JsRenderer {
+    browser: PathBuf,
+}
+
+impl JsRenderer {
+    /// Use the configured browser, or the first Chrome/Chromium found on `PATH`
+    pub fn new(browser: Option<&str>) -> Result<Self> {
+        let browser = find_browser(browser).with_context(|| {
+            format!(
+                "--render js needs Chrome or Chromium; none of {} was found on PATH. Set screenshot_browser in the config file.",
+                BROWSER_CANDIDATES.join(", ")
+            )
+        })?;
+
+        Ok(Self { browser })
+    }
+
+    /// The serialized DOM of `url` once its scripts have run. The browser is driven over the
+    /// DevTools protocol, which is what lets it be given the session's cookies and have its
+    /// requests counted.
+    pub async fn render(&self, url: &Url, session: RenderSession<'_>) -> Result<String> {
+        // A fresh profile keeps renders from sharing cookies, and Chrome only honors the
+        // trusted key list with one
+        let profile = tempfile::tempdir().context("Failed to create browser profile")?;
+        let mut command = Command::new(&self.browser);
+        command
+            .arg("--headless")
+            .arg("--disable-gpu")
+            .arg("--remote-debugging-port=0")
+            .arg(format!("--user-data-dir={}", profile.path().display()))
+            .arg(format!("--user-agent={USER_AGENT}"));
+        if session.tls.insecure {
+            command.arg("--ignore-certificate-errors");
+        } else if !session.tls.ca_key_hashes.is_empty() {
+            command.arg(format!(
+                "--ignore-certificate-errors-spki-list={}",
+                session.tls.ca_key_hashes.join(",")
+            ));
+        }
+        command
+            .arg("about:blank")
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut browser = command
+            .spawn()
+            .with_context(|| format!("Failed to run browser: {}", self.browser.display()))?;
+        let dom = tokio::time::timeout(RENDER_TIMEOUT, dump_dom(&mut browser, url, session))
+            .await
+            .with_context(|| format!("Timed out rendering {url}"))?
+            .with_context(|| format!("Browser failed to render {url}"));
+        let _ = browser.kill().await;
+        dom
+    }
+}
+
+/// Load `url` in a new tab of `browser` and read its DOM once the script budget has run out
+async fn dump_dom(browser: &mut Child, url: &Url, session: RenderSession<'_>) -> Result<String> {
+    let endpoint = devtools_endpoint(browser).await?;
+    let (socket, _) = tokio_tungstenite::connect_async(endpoint.as_str())
+        .await
+        .with_context(|| format!("Failed to connect to DevTools at {endpoint}"))?;
+    let mut devtools = DevTools {
+        socket,
+        next_id: 0,
+        session_id: None,
+        limits: session.limits,
+    };
+
+    let target = devtools
+        .call("Target.createTarget", json!({ "url": "about:blank" }))
+        .await?;
+    let attached = devtools
+        .call(
+            "Target.attachToTarget",
+            json!({ "targetId": target["targetId"], "flatten": true }),
+        )
+        .await?;
+    devtools.session_id = attached["sessionId"].as_str().map(str::to_string);
+
+    devtools.call("Network.enable", json!({})).await?;
+    if session.limits.is_some() {
+        devtools
+            .call(
+                "Fetch.enable",
+                json!({ "patterns": [{ "urlPattern": "*" }] }),
+            )
+            .await?;
+    }
+    let cookies = page_cookies(session.cookies, url);
+    if !cookies.is_empty() {
+        devtools
+            .call("Network.setCookies", json!({ "cookies": cookies }))
+            .await?;
+    }
+    devtools
+        .call(
+            "Emulation.setVirtualTimePolicy",
+            json!({ "policy": "pauseIfNetworkFetchesPending", "budget": SCRIPT_BUDGET_MS }),
+        )
+        .await?;
+    let navigated = devtools
+        .call("Page.navigate", json!({ "url": url.as_str() }))
+        .await?;
+    if let Some(error) = navigated["errorText"].as_str() {
+        anyhow::bail!("Navigation failed: {error}");
+    }
+    devtools
+        .wait_for("Emulation.virtualTimeBudgetExpired")
+        .await?;
+
+    let evaluated = devtools
+        .call(
+            "Runtime.evaluate",
+            json!({ "expression": "document.documentElement.outerHTML", "returnByValue": true }),
+        )
+        .await?;
+    evaluated["result"]["value"]
+        .as_str()
+        .map(str::to_string)
+        .context("The page has no document")
+}
+
+/// The browser's DevTools WebSocket URL, as announced on its stderr
+async fn devtools_endpoint(browser: &mut Child) -> Result<String> {
+    let stderr = browser
+        .stderr
+        .take()
+        .context("Browser stderr not captured")?;
+    let mut lines = BufReader::new(stderr).lines();
+    let mut output = Vec::new();
+    while let Some(line) = lines.next_line().await? {
+        if let Some(endpoint) = line.strip_prefix(DEVTOOLS_LISTENING) {
+            let endpoint = endpoint.trim().to_string();
+            // Keep reading so a chatty browser never blocks on a full pipe
+            tokio::spawn(async move { while let Ok(Some(_)) = lines.next_line().await {} });
+            return Ok(endpoint);
+        }
+        output.push(line);
+    }
+    anyhow::bail!(
+        "Browser exited before opening DevTools: {}",
+        output.join("\n").trim()
+    )
+}
+
+/// The jar's cookies for `url`, as DevTools `CookieParam`s
+fn page_cookies(jar: Option<&Jar>, url: &Url) -> Vec<Value> {
+    let Some(header) = jar.and_then(|jar| jar.cookies(url)) else {
+        return Vec::new();
+    };
+    header
+        .to_str()
+        .unwrap_or_default()
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .map(|(name, value)| json!({ "name": name, "value": value, "url": url.as_str() }))
+        .collect()
+}
+
+/// A DevTools connection to one tab. Requests the tab makes are let through while commands
+/// and events are awaited, as long as the limits allow them.
+struct DevTools<'a> {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+    session_id: Option<String>,
+    limits: Option<&'a RequestLimits>,
+}
+
+impl DevTools<'_> {
+    /// Send a command and wait for its result
+    async fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.send(method, params).await?;
+        loop {
+            let message = self.next_message().await?;
+            if message["id"].as_u64() != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                anyhow::bail!("{method} failed: {}", error["message"]);
+            }
+            return Ok(message["result"].clone());
+        }
+    }
+
+    async fn wait_for(&mut self, event: &str) -> Result<()> {
+        while self.next_message().await?["method"] != event {}
+        Ok(())
+    }
+
+    async fn send(&mut self, method: &str, params: Value) -> Result<u64> {
+        self.next_id += 1;
+        let mut message = json!({ "id": self.next_id, "method": method, "params": params });
+        if let Some(session_id) = &self.session_id {
+            message["sessionId"] = json!(session_id);
+        }
+        self.socket
+            .send(Message::text(message.to_string()))
+            .await
+            .context("Lost the DevTools connection")?;
+        Ok(self.next_id)
+    }
+
+    /// The next message from the browser, after handling the request events it may be
+    async fn next_message(&mut self) -> Result<Value> {
+        loop {
+            let message = self
+                .socket
+                .next()
+                .await
+                .context("The browser closed the DevTools connection")?
+                .context("Lost the DevTools connection")?;
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let message: Value = serde_json::from_str(&text)?;
+            match message["method"].as_str() {
+                Some("Fetch.requestPaused") => {
+                    let request_id = message["params"]["requestId"].clone();
+                    if self.limits.is_none_or(|limits| limits.try_acquire()) {
+                        self.send("Fetch.continueRequest", json!({ "requestId": request_id }))
+                            .await?;
+                    } else {
+                        self.send(
+                            "Fetch.failRequest",
+                            json!({ "requestId": request_id, "errorReason": "BlockedByClient" }),
+                        )
+                        .await?;
+                    }
+                }
+                Some("Network.loadingFinished") => {
+                    if let (Some(limits), Some(bytes)) =
+                        (self.limits, message["params"]["encodedDataLength"].as_f64())
+                    {
+                        limits.record_bytes(bytes as u64);
+                    }
+                }
+                _ => {}
+            }
+            return Ok(message);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use url::Url;
+
+use crate::json_style::{self, JsonStyle};
+use crate::models::{CrawlReport, CrawlSummary, PageInfo};
+use crate::page_store::PageStore;
+
+/// Longest part of a page file name taken from the URL; longer ones are cut and get a hash
+const MAX_READABLE_NAME_LEN: usize = 100;
+
+/// Name of the summary file written next to the page files
+pub const INDEX_FILE_NAME: &str = "index.json";
+
+/// The summary file of a report directory: the run's totals and which file holds each page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportIndex {
+    pub start_url: String,
+    pub timestamp: String,
+    pub summary: CrawlSummary,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_reached: Option<String>,
+    /// Every page, sorted by URL so the file diffs cleanly between runs
+    pub pages: Vec<IndexedPage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedPage {
+    pub url: String,
+    /// File name of the page's JSON, relative to the directory
+    pub file: String,
+    pub status_code: Option<u16>,
+    pub issues: usize,
+}
+
+/// Write each page to `<dir>/<file>.json` and an `index.json` summary of the report listing
+/// them, so reports can be kept in git and reviewed page by page. Pages are read one at a
+/// time in URL order from `pages`, which is `report.pages` unless they are kept in a page
+/// store. Page files listed by an earlier index in the directory that this run didn't write
+/// are removed. Returns how many page files were written.
+pub fn save_report_dir(
+    report: &CrawlReport,
+    pages: &dyn PageStore,
+    dir: &Path,
+    style: JsonStyle,
+) -> Result<usize> {
+    let mut writer = ReportDirWriter::new(dir, style)?;
+    let mut keys = Vec::new();
+    pages.for_each_page(&mut |key, page| {
+        keys.push((page.url.clone(), key.to_string()));
+        Ok(())
+    })?;
+    keys.sort();
+    for (_, key) in keys {
+        if let Some(page) = pages.get(&key)? {
+            writer.write_page(&page)?;
+        }
+    }
+    writer.finish(report)
+}
+
+struct ReportDirWriter<'a> {
+    dir: &'a Path,
+    style: JsonStyle,
+    /// Page files listed by the index an earlier run left in the directory
+    previous_files: Vec<String>,
+    used_names: HashSet<String>,
+    indexed: Vec<IndexedPage>,
+}
+
+impl<'a> ReportDirWriter<'a> {
+    fn new(dir: &'a Path, style: JsonStyle) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create report directory: {}", dir.display()))?;
+        // Read as plain JSON since `file` is named the same in either JSON style
+        let previous_files: Vec<String> = fs::read_to_string(dir.join(INDEX_FILE_NAME))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+            .and_then(|index| index.get("pages")?.as_array().cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|page| Some(page.get("file")?.as_str()?.to_string()))
+            .collect();
+
+        Ok(Self {
+            dir,
+            style,
+            previous_files,
+            used_names: HashSet::new(),
+            indexed: Vec::new(),
+        })
+    }
+
+    fn write_page(&mut self, page: &PageInfo) -> Result<()> {
+        let file = unique_file_name(&page.url, &mut self.used_names);
+        let path = self.dir.join(&file);
+        let json = json_style::to_string_pretty(page, self.style)?;
+        fs::write(&path, json + "\n")
+            .with_context(|| format!("Failed to write page report: {}", path.display()))?;
+        self.indexed.push(IndexedPage {
+            url: page.url.clone(),
+            file,
+            status_code: page.status_code,
+            issues: page.issues.len(),
+        });
+        Ok(())
+    }
+
+    /// Remove the stale page files and write the index
+    fn finish(self, report: &CrawlReport) -> Result<usize> {
+        for stale in self
+            .previous_files
+            .iter()
+            .filter(|file| !self.used_names.contains(file.as_str()))
+        {
+            // Only plain names from the old index are removed, never paths outside the directory
+            if Path::new(stale)
+                .file_name()
+                .is_some_and(|name| name == stale.as_str())
+            {
+                let _ = fs::remove_file(self.dir.join(stale));
+            }
+        }
+
+        let written = self.indexed.len();
+        let index = ReportIndex {
+            start_url: report.start_url.clone(),
+            timestamp: report.timestamp.clone(),
+            summary: report.summary.clone(),
+            limit_reached: report.limit_reached.clone(),
+            pages: self.indexed,
+        };
+        let index_path = self.dir.join(INDEX_FILE_NAME);
+        fs::write(
+            &index_path,
+            json_style::to_string_pretty(&index, self.style)? + "\n",
+        )
+        .with_context(|| format!("Failed to write report index: {}", index_path.display()))?;
+        Ok(written)
+    }
+}
+
+/// `page_file_name` with a `_2`, `_3`, ... suffix when another page already took the name
+fn unique_file_name(page_url: &str, used_names: &mut HashSet<String>) -> String {
+    let name = page_file_name(page_url, "json");
+    let stem = name.trim_end_matches(".json");
+    let file = (1..)
+        .map(|counter| match counter {
+            1 => name.clone(),
+            _ => format!("{stem}_{counter}.json"),
+        })
+        .find(|file| !used_names.contains(file))
+        .expect("some suffix should be free");
+    used_names.insert(file.clone());
+    file
+}
+
+/// A file name for a page, e.g. `example.com_blog_post.json`; query strings are kept so
+/// variants of a page don't overwrite each other
+pub fn page_file_name(page_url: &str, extension: &str) -> String {
+    let name = match Url::parse(page_url) {
+        Ok(url) => {
+            let mut name = url.host_str().unwrap_or_default().to_string();
+            if let Some(port) = url.port() {
+                let _ = write!(name, "_{port}");
+            }
+            let path = url.path().trim_matches('/');
+            name.push('_');
+            name.push_str(if path.is_empty() { "index" } else { path });
+            if let Some(query) = url.query() {
+                name.push('_');
+                name.push_str(query);
+            }
+            name
+        }
+        Err(_) => page_url.to_string(),
+    };
+
+    let name: String = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '_') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.len() <= MAX_READABLE_NAME_LEN {
+        return format!("{name}.{extension}");
+    }
+    // Most file systems refuse names over 255 bytes, so long URLs keep a readable prefix and a
+    // hash of the whole URL tells them apart
+    let digest = Sha256::digest(page_url.as_bytes());
+    let hash: String = digest[..4]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("{}_{hash}.{extension}", &name[..MAX_READABLE_NAME_LEN])
+}
@@ -1,80 +1,280 @@
-use crate::models::{CrawlReport, CrawlSummary, IssueSeverity, PageInfo};
-use anyhow::Result;
+use crate::json_style::{self, JsonStyle};
+use crate::models::{
+    CrawlError, CrawlErrorCause, CrawlReport, CrawlSummary, DownloadStats, FieldData,
+    FieldDataScope, FrontierAction, FrontierDecision, HtmlContentTypes, ImageStats, IssueSeverity,
+    LargestDownload, LargestImage, LargestPage, PageInfo, PerformanceStats, SlowestPage,
+    ThirdPartyFrameDomain, ThirdPartyFrameStats,
+};
+use crate::page_store::PageStore;
+use crate::rules::{self, Rule};
+use crate::sitemap::render_sitemap;
+use anyhow::{Context, Result, anyhow};
 use colored::*;
-use std::collections::HashMap;
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, IsTerminal, Write};
+
+/// Fallback for separator lines when the output isn't a terminal
+const DEFAULT_REPORT_WIDTH: usize = 80;
+
+/// Images listed under `largest` in the summary's image stats
+const MAX_LARGEST_IMAGES: usize = 5;
+/// Downloads listed under `largest` in the summary's download stats
+const MAX_LARGEST_DOWNLOADS: usize = 5;
+/// Pages listed under `slowest` and `largest` in the summary's performance stats
+const MAX_PERFORMANCE_PAGES: usize = 5;
 
 pub struct Reporter;
 
+/// Layout limits for the text report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextReportOptions {
+    /// Terminal width URLs are shortened to fit; `None` prints them in full
+    pub width: Option<usize>,
+    /// Pages listed in each section before the rest are only counted
+    pub max_pages_shown: Option<usize>,
+}
+
+impl TextReportOptions {
+    /// Fit the report to stdout's terminal; piped output keeps full URLs
+    pub fn for_stdout(max_pages_shown: Option<usize>) -> Self {
+        let width = std::io::stdout()
+            .is_terminal()
+            .then(crossterm::terminal::size)
+            .and_then(Result::ok)
+            .map(|(columns, _)| usize::from(columns));
+        Self {
+            width,
+            max_pages_shown,
+        }
+    }
+
+    /// Shorten `url` to the columns left after `indent` columns of labels
+    fn fit(&self, url: &str, indent: usize) -> String {
+        match self.width {
+            Some(width) => truncate_middle(url, width.saturating_sub(indent)),
+            None => url.to_string(),
+        }
+    }
+
+    fn rule(&self) -> String {
+        "=".repeat(self.width.map_or(DEFAULT_REPORT_WIDTH, |width| {
+            width.min(DEFAULT_REPORT_WIDTH)
+        }))
+    }
+
+    fn shown(&self, total: usize) -> usize {
+        self.max_pages_shown.map_or(total, |max| total.min(max))
+    }
+}
+
 impl Reporter {
-    pub fn generate_report(start_url: &str, pages: &HashMap<String, PageInfo>) -> CrawlReport {
-        let summary = Self::summarize_pages(pages);
+    /// Build the report of pages held in memory, with `pages` moved into it
+    pub fn generate_report_from_pages(
+        start_url: &str,
+        mut pages: HashMap<String, PageInfo>,
+        html_content_types: &HtmlContentTypes,
+    ) -> Result<CrawlReport> {
+        let mut report = Self::generate_report(start_url, &mut pages, html_content_types)?;
+        report.pages = pages;
+        Ok(report)
+    }
+
+    /// Build the report of the crawled pages: the summary and link index are built one page
+    /// at a time, issue fingerprints are written back into `pages`, and the report's own
+    /// `pages` is left empty, so pages kept in a page store are never all held in memory.
+    /// Write it with `write_json_report`, or use `generate_report_from_pages` for pages held
+    /// in memory.
+    pub fn generate_report(
+        start_url: &str,
+        pages: &mut dyn PageStore,
+        html_content_types: &HtmlContentTypes,
+    ) -> Result<CrawlReport> {
+        let summary = Self::summarize_pages(pages, html_content_types)?;
+        let inlinks = CrawlReport::index_inlinks(pages)?;
         let timestamp = chrono::Utc::now().to_rfc3339();
 
-        CrawlReport {
+        pages.update_each(&mut |page_url, page| {
+            for issue in &mut page.issues {
+                issue.fingerprint = Some(issue.compute_fingerprint(page_url));
+            }
+            Ok(())
+        })?;
+
+        Ok(CrawlReport {
             start_url: start_url.to_string(),
-            pages: pages.clone(),
+            pages: HashMap::new(),
+            inlinks,
             summary,
             timestamp,
+            limit_reached: None,
+            explain: BTreeMap::new(),
+            errors: BTreeMap::new(),
+        })
+    }
+
+    /// Links to targets served as something other than `html_content_types` count as
+    /// downloads
+    pub fn summarize_pages(
+        pages: &dyn PageStore,
+        html_content_types: &HtmlContentTypes,
+    ) -> Result<CrawlSummary> {
+        let mut tally = SummaryTally::default();
+        pages.for_each_page(&mut |_, page| {
+            tally.add(page, html_content_types);
+            Ok(())
+        })?;
+        Ok(tally.finish())
+    }
+
+    pub fn summarize_images(pages: &HashMap<String, PageInfo>) -> ImageStats {
+        let mut tally = ImageTally::default();
+        for page in by_url(pages) {
+            tally.add(page);
+        }
+        tally.finish()
+    }
+
+    pub fn summarize_downloads(
+        pages: &HashMap<String, PageInfo>,
+        html_content_types: &HtmlContentTypes,
+    ) -> DownloadStats {
+        let mut tally = DownloadTally::default();
+        for page in by_url(pages) {
+            tally.add(page, html_content_types);
         }
+        tally.finish()
     }
 
-    pub fn summarize_pages(pages: &HashMap<String, PageInfo>) -> CrawlSummary {
-        let mut errors = 0;
-        let mut warnings = 0;
-        let mut infos = 0;
-        let mut broken_links = 0;
-        let mut total_links = 0;
+    pub fn summarize_third_party_frames(pages: &HashMap<String, PageInfo>) -> ThirdPartyFrameStats {
+        let mut tally = ThirdPartyFrameTally::default();
+        for page in by_url(pages) {
+            tally.add(page);
+        }
+        tally.finish()
+    }
 
-        for page in pages.values() {
-            total_links += page.links.len();
+    pub fn summarize_performance(pages: &HashMap<String, PageInfo>) -> PerformanceStats {
+        let mut tally = PerformanceTally::default();
+        for page in by_url(pages) {
+            tally.add(page);
+        }
+        tally.finish()
+    }
 
-            for issue in &page.issues {
-                match issue.severity {
-                    IssueSeverity::Error => errors += 1,
-                    IssueSeverity::Warning => warnings += 1,
-                    IssueSeverity::Info => infos += 1,
-                }
+    /// Rules for every issue type that appears in the pages, in a stable order
+    pub fn referenced_rules(pages: &dyn PageStore) -> Result<Vec<Rule>> {
+        let mut issue_types = BTreeSet::new();
+        pages.for_each_page(&mut |_, page| {
+            issue_types.extend(page.issues.iter().map(|issue| issue.issue_type));
+            Ok(())
+        })?;
+        Ok(issue_types.into_iter().map(rules::rule_for).collect())
+    }
+
+    /// Print the report with its pages read from `pages`, which is `report.pages` unless they
+    /// are kept in a page store. Only the pages the report lists are held in memory.
+    pub fn print_text_report(
+        report: &CrawlReport,
+        pages: &dyn PageStore,
+        options: &TextReportOptions,
+    ) -> Result<()> {
+        let mut broken_keys = Vec::new();
+        let mut keys_with_issues = Vec::new();
+        pages.for_each_page(&mut |key, page| {
+            if is_broken(page) {
+                broken_keys.push((page.url.clone(), key.to_string()));
+            }
+            if !page.issues.is_empty() {
+                keys_with_issues.push((page.crawl_depth, page.url.clone(), key.to_string()));
             }
+            Ok(())
+        })?;
+        broken_keys.sort();
+        keys_with_issues.sort();
 
-            broken_links += page
-                .links
+        let shown_broken = options.shown(broken_keys.len());
+        let shown_with_issues = options.shown(keys_with_issues.len());
+        let read = |keys: Vec<String>| -> Result<Vec<PageInfo>> {
+            keys.iter()
+                .filter_map(|key| pages.get(key).transpose())
+                .collect()
+        };
+        let broken = read(
+            broken_keys[..shown_broken]
                 .iter()
-                .filter(|link| {
-                    link.status_code.is_some_and(|code| code >= 400) || link.check_error.is_some()
-                })
-                .count();
-        }
+                .map(|(_, key)| key.clone())
+                .collect(),
+        )?;
+        let with_issues = read(
+            keys_with_issues[..shown_with_issues]
+                .iter()
+                .map(|(_, _, key)| key.clone())
+                .collect(),
+        )?;
 
-        CrawlSummary {
-            total_pages: pages.len(),
-            total_links,
-            broken_links,
-            errors,
-            warnings,
-            infos,
-        }
+        Self::print_text(
+            report,
+            ListedPages {
+                broken: &broken.iter().collect::<Vec<_>>(),
+                hidden_broken: broken_keys.len() - shown_broken,
+                with_issues: &with_issues.iter().collect::<Vec<_>>(),
+                hidden_with_issues: keys_with_issues.len() - shown_with_issues,
+                referenced_rules: Self::referenced_rules(pages)?,
+            },
+            options,
+        );
+        Ok(())
     }
 
-    pub fn print_text_report(report: &CrawlReport) {
-        println!("\n{}", "=".repeat(80).bright_blue());
+    fn print_text(report: &CrawlReport, listed: ListedPages, options: &TextReportOptions) {
+        println!("\n{}", options.rule().bright_blue());
         println!("{}", "Scoutly - Crawl Report".bright_cyan().bold());
-        println!("{}", "=".repeat(80).bright_blue());
+        println!("{}", options.rule().bright_blue());
         println!();
 
         println!(
             "{}: {}",
             "Start URL".bright_white().bold(),
-            report.start_url
+            options.fit(&report.start_url, "Start URL: ".len())
         );
         println!(
             "{}: {}",
             "Timestamp".bright_white().bold(),
             report.timestamp
         );
+        if let Some(limit_reached) = &report.limit_reached {
+            println!(
+                "{}: {}",
+                "Stopped early".bright_yellow().bold(),
+                limit_reached
+            );
+        }
         println!();
 
+        if !report.explain.is_empty() {
+            println!("{}", "Crawl Decisions".bright_yellow().bold().underline());
+            for (url, decisions) in &report.explain {
+                println!("  {}", url.bright_white());
+                for decision in decisions {
+                    let action = match decision.action {
+                        FrontierAction::Queued => "queued ".bright_blue(),
+                        FrontierAction::Skipped => "skipped".bright_yellow(),
+                        FrontierAction::Crawled => "crawled".bright_green(),
+                    };
+                    match &decision.found_on {
+                        Some(found_on) => {
+                            println!("    {action} {} (from {found_on})", decision.reason)
+                        }
+                        None => println!("    {action} {}", decision.reason),
+                    }
+                }
+            }
+            println!();
+        }
+
         // Summary
         println!("{}", "Summary".bright_yellow().bold().underline());
         println!(
@@ -113,21 +313,167 @@ impl Reporter {
             "  Info:                {}",
             report.summary.infos.to_string().bright_cyan()
         );
+        if report.summary.fragment_navigation_pages > 0 {
+            println!(
+                "  Fragment Navigation: {} page(s) link mostly to their own sections; #section URLs are analyzed as one page",
+                report
+                    .summary
+                    .fragment_navigation_pages
+                    .to_string()
+                    .bright_cyan()
+            );
+        }
+        let images = &report.summary.images;
+        if images.total > 0 {
+            println!(
+                "  Images:              {} ({} unique): {:.1}% with alt text, {:.1}% decorative, {} missing alt",
+                images.total.to_string().bright_green(),
+                images.unique,
+                images.with_alt_percent(),
+                images.decorative_percent(),
+                if images.missing_alt > 0 {
+                    images.missing_alt.to_string().yellow()
+                } else {
+                    images.missing_alt.to_string().bright_green()
+                }
+            );
+            for image in &images.largest {
+                println!(
+                    "    {}x{} {}",
+                    image.width,
+                    image.height,
+                    options.fit(&image.src, 16)
+                );
+            }
+        }
+        let downloads = &report.summary.downloads;
+        if downloads.total > 0 {
+            println!(
+                "  Downloads:           {} linked, {} broken",
+                downloads.total.to_string().bright_green(),
+                if downloads.broken > 0 {
+                    downloads.broken.to_string().red()
+                } else {
+                    downloads.broken.to_string().bright_green()
+                }
+            );
+            for download in &downloads.largest {
+                println!(
+                    "    {} {}",
+                    format_bytes(download.content_length),
+                    options.fit(&download.url, 16)
+                );
+            }
+        }
+        let frames = &report.summary.third_party_frames;
+        if frames.total > 0 {
+            println!(
+                "  Third-party Frames:  {} on {} page(s) from {} domain(s)",
+                frames.total.to_string().bright_cyan(),
+                frames.pages,
+                frames.domains.len()
+            );
+            for domain in &frames.domains {
+                println!(
+                    "    {} {} on {} page(s)",
+                    domain.frames,
+                    domain.domain,
+                    domain.pages.len()
+                );
+            }
+        }
+        let performance = &report.summary.performance;
+        if let Some(average) = performance.average_response_time_ms {
+            println!(
+                "  Response Time:       {} ms average over {} page(s), slowest:",
+                average.to_string().bright_green(),
+                performance.measured
+            );
+            for page in &performance.slowest {
+                println!(
+                    "    {} ms {}",
+                    page.response_time_ms,
+                    options.fit(&page.url, 16)
+                );
+            }
+        }
+        if let Some(average) = performance.average_content_length {
+            println!(
+                "  Page Size:           {} average, largest:",
+                format_bytes(average).bright_green()
+            );
+            for page in &performance.largest {
+                println!(
+                    "    {} {}",
+                    format_bytes(page.content_length),
+                    options.fit(&page.url, 16)
+                );
+            }
+        }
         println!();
 
+        // Crawled pages that failed, with the pages that need their links fixed
+        if !listed.broken.is_empty() {
+            println!("{}", "Broken Pages".bright_yellow().bold().underline());
+            for page in listed.broken {
+                println!();
+                println!(
+                    "  {} {}",
+                    options.fit(&page.url, "  ".len() + " (HTTP 404)".len()),
+                    format!(
+                        "({})",
+                        page.status_code
+                            .map(|code| format!("HTTP {code}"))
+                            .unwrap_or_else(|| "failed".to_string())
+                    )
+                    .bright_red()
+                );
+                let linking_pages = report.linking_pages(&page.url);
+                if linking_pages.is_empty() {
+                    println!("    Linked from: {}", "no crawled pages".dimmed());
+                } else {
+                    println!("    Linked from:");
+                    let shown = options.shown(linking_pages.len());
+                    for linking_page in &linking_pages[..shown] {
+                        println!("      {}", options.fit(linking_page, "      ".len()));
+                    }
+                    print_hidden_count(linking_pages.len() - shown, "      ", "linking page(s)");
+                }
+            }
+            print_hidden_count(listed.hidden_broken, "\n  ", "broken page(s)");
+            println!();
+        }
+
+        // Pages that failed to fetch, by cause
+        if !report.errors.is_empty() {
+            println!("{}", "Crawl Errors".bright_yellow().bold().underline());
+            for (cause, errors) in &report.errors {
+                println!();
+                println!(
+                    "  {} {}",
+                    cause.label().bright_white().bold(),
+                    format!("({})", errors.len()).bright_red()
+                );
+                let shown = options.shown(errors.len());
+                for error in &errors[..shown] {
+                    println!("    {}", options.fit(&error.url, "    ".len()));
+                    println!("      {}", error.message.dimmed());
+                }
+                print_hidden_count(errors.len() - shown, "    ", "page(s)");
+            }
+            println!();
+        }
+
         // Pages with issues
-        let mut pages_with_issues: Vec<_> = report
-            .pages
-            .values()
-            .filter(|page| !page.issues.is_empty())
-            .collect();
-        pages_with_issues.sort_by_key(|page| page.crawl_depth);
-
-        if !pages_with_issues.is_empty() {
+        if !listed.with_issues.is_empty() {
             println!("{}", "Pages with Issues".bright_yellow().bold().underline());
-            for page in pages_with_issues {
+            for page in listed.with_issues {
                 println!();
-                println!("  {} {}", "URL:".bright_white().bold(), page.url);
+                println!(
+                    "  {} {}",
+                    "URL:".bright_white().bold(),
+                    options.fit(&page.url, "  URL: ".len())
+                );
                 println!(
                     "    Status: {}",
                     page.status_code
@@ -143,6 +489,33 @@ impl Reporter {
                         .unwrap_or_else(|| "N/A".dimmed())
                 );
                 println!("    Depth:  {}", page.crawl_depth);
+                if let Some(found_on) = &page.source.found_on {
+                    println!(
+                        "    Found on: {} {}",
+                        options.fit(
+                            found_on,
+                            "    Found on: ".len() + page.source.method.label().len() + 3
+                        ),
+                        format!("({})", page.source.method.label()).dimmed()
+                    );
+                }
+                if let Some(screenshot) = &page.screenshot {
+                    println!("    Screenshot: {}", screenshot.dimmed());
+                }
+                if let Some(html_snapshot) = &page.html_snapshot {
+                    println!("    HTML snapshot: {}", html_snapshot.dimmed());
+                }
+                if let Some(field_data) = &page.field_data {
+                    println!("    Field data (p75): {}", format_field_data(field_data));
+                }
+                let frame_domains = page.third_party_frame_domains();
+                if !frame_domains.is_empty() {
+                    let frame_domains: Vec<String> = frame_domains
+                        .iter()
+                        .map(|(domain, frames)| format!("{domain} ({frames})"))
+                        .collect();
+                    println!("    Third-party frames: {}", frame_domains.join(", "));
+                }
 
                 if let Some(title) = &page.title {
                     println!("    Title:  {}", title.bright_white());
@@ -188,17 +561,471 @@ impl Reporter {
                     };
                     println!("      [{}] {}", severity_str, issue.message);
                 }
+                for omitted in &page.omitted_issues {
+                    println!(
+                        "      {}",
+                        format!(
+                            "... and {} more {:?} issue(s)",
+                            omitted.count, omitted.issue_type
+                        )
+                        .dimmed()
+                    );
+                }
+            }
+            print_hidden_count(listed.hidden_with_issues, "\n  ", "page(s) with issues");
+        }
+
+        if !listed.referenced_rules.is_empty() {
+            println!();
+            println!("{}", "Issue Reference".bright_yellow().bold().underline());
+            for rule in listed.referenced_rules {
+                println!();
+                println!(
+                    "  {}",
+                    format!("{:?}", rule.issue_type).bright_white().bold()
+                );
+                println!("    {}", rule.explanation);
+                println!("    {}", rule.help_url.dimmed());
             }
         }
 
         println!();
-        println!("{}", "=".repeat(80).bright_blue());
+        println!("{}", options.rule().bright_blue());
+    }
+
+    pub fn save_json_report(
+        report: &CrawlReport,
+        pages: &dyn PageStore,
+        filename: &str,
+        style: JsonStyle,
+    ) -> Result<()> {
+        let file =
+            File::create(filename).with_context(|| format!("Failed to create {filename}"))?;
+        let mut writer = BufWriter::new(file);
+        Self::write_json_report(report, pages, &mut writer, style)?;
+        writer.flush()?;
+        Ok(())
     }
 
-    pub fn save_json_report(report: &CrawlReport, filename: &str) -> Result<()> {
-        let json = serde_json::to_string_pretty(report)?;
-        let mut file = File::create(filename)?;
-        file.write_all(json.as_bytes())?;
+    /// Write `report` as JSON with its pages read one at a time from `pages` instead of
+    /// `report.pages`, so the report of a crawl kept in a page store never has to hold every
+    /// page in memory
+    pub fn write_json_report(
+        report: &CrawlReport,
+        pages: &dyn PageStore,
+        writer: impl Write,
+        style: JsonStyle,
+    ) -> Result<()> {
+        let report = StoredReport {
+            start_url: &report.start_url,
+            pages: StoredPages(pages),
+            inlinks: &report.inlinks,
+            summary: &report.summary,
+            timestamp: &report.timestamp,
+            limit_reached: report.limit_reached.as_deref(),
+            explain: &report.explain,
+            errors: &report.errors,
+        };
+        json_style::to_writer_pretty(writer, &report, style)?;
         Ok(())
     }
+
+    pub fn save_sitemap(
+        start_url: &str,
+        pages: &dyn PageStore,
+        html_content_types: &HtmlContentTypes,
+        filename: &str,
+    ) -> Result<()> {
+        let xml = render_sitemap(start_url, pages, html_content_types)?;
+        std::fs::write(filename, xml)
+            .with_context(|| format!("Failed to write sitemap: {filename}"))
+    }
+}
+
+/// The pages a text report lists, and how many more it leaves out
+struct ListedPages<'a> {
+    broken: &'a [&'a PageInfo],
+    hidden_broken: usize,
+    with_issues: &'a [&'a PageInfo],
+    hidden_with_issues: usize,
+    referenced_rules: Vec<Rule>,
+}
+
+/// Crawled pages that failed to load or answered with an error status
+fn is_broken(page: &PageInfo) -> bool {
+    page.status_code.is_none_or(|code| code >= 400)
+}
+
+/// Note the entries a list left out because of `--max-pages-shown`
+fn print_hidden_count(hidden: usize, prefix: &str, label: &str) {
+    if hidden > 0 {
+        println!(
+            "{prefix}{}",
+            format!("... and {hidden} more {label} (see the JSON report for all)").dimmed()
+        );
+    }
+}
+
+/// Shorten `text` to at most `max_chars` characters by replacing its middle with an ellipsis,
+/// so both the host and the last path segment of a URL stay visible
+pub fn truncate_middle(text: &str, max_chars: usize) -> String {
+    let length = text.chars().count();
+    if length <= max_chars {
+        return text.to_string();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+
+    let kept = max_chars - 1;
+    let head = kept.div_ceil(2);
+    let tail = kept - head;
+    let mut truncated: String = text.chars().take(head).collect();
+    truncated.push('…');
+    truncated.extend(text.chars().skip(length - tail));
+    truncated
+}
+
+fn format_field_data(field_data: &FieldData) -> String {
+    let metric = |value: Option<f64>, format: &dyn Fn(f64) -> String| {
+        value.map(format).unwrap_or_else(|| "n/a".to_string())
+    };
+    let scope = match field_data.scope {
+        FieldDataScope::Url => "page",
+        FieldDataScope::Origin => "origin",
+    };
+
+    format!(
+        "LCP {}, CLS {}, INP {} ({scope})",
+        metric(field_data.lcp_ms, &|ms| format!("{ms:.0} ms")),
+        metric(field_data.cls, &|cls| format!("{cls:.2}")),
+        metric(field_data.inp_ms, &|ms| format!("{ms:.0} ms")),
+    )
+}
+
+/// A size in bytes with a decimal unit for the text report, e.g. `4.2 MB`
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if size < 1000.0 {
+            break;
+        }
+        size /= 1000.0;
+        unit = next;
+    }
+    format!("{size:.1} {unit}")
+}
+
+/// Pages sorted by URL, so the page named for an image or download doesn't change between runs
+fn by_url(pages: &HashMap<String, PageInfo>) -> Vec<&PageInfo> {
+    let mut pages: Vec<&PageInfo> = pages.values().collect();
+    pages.sort_by(|a, b| a.url.cmp(&b.url));
+    pages
+}
+
+/// Running totals behind `CrawlSummary`, fed one page at a time so a page store can be
+/// summarized without reading every page into memory
+#[derive(Default)]
+struct SummaryTally {
+    total_pages: usize,
+    total_links: usize,
+    broken_links: usize,
+    errors: usize,
+    warnings: usize,
+    infos: usize,
+    fragment_navigation_pages: usize,
+    images: ImageTally,
+    downloads: DownloadTally,
+    performance: PerformanceTally,
+    third_party_frames: ThirdPartyFrameTally,
+}
+
+impl SummaryTally {
+    fn add(&mut self, page: &PageInfo, html_content_types: &HtmlContentTypes) {
+        self.total_pages += 1;
+        self.total_links += page.links.len();
+
+        let issue_counts = page.issues.iter().map(|issue| (issue.severity, 1)).chain(
+            page.omitted_issues
+                .iter()
+                .map(|omitted| (omitted.severity, omitted.count)),
+        );
+        for (severity, count) in issue_counts {
+            match severity {
+                IssueSeverity::Error => self.errors += count,
+                IssueSeverity::Warning => self.warnings += count,
+                IssueSeverity::Info => self.infos += count,
+            }
+        }
+
+        self.broken_links += page
+            .links
+            .iter()
+            .filter(|link| {
+                link.status_code.is_some_and(|code| code >= 400) || link.check_error.is_some()
+            })
+            .count();
+        if page.uses_fragment_navigation() {
+            self.fragment_navigation_pages += 1;
+        }
+
+        self.images.add(page);
+        self.downloads.add(page, html_content_types);
+        self.performance.add(page);
+        self.third_party_frames.add(page);
+    }
+
+    fn finish(self) -> CrawlSummary {
+        CrawlSummary {
+            total_pages: self.total_pages,
+            total_links: self.total_links,
+            broken_links: self.broken_links,
+            errors: self.errors,
+            warnings: self.warnings,
+            infos: self.infos,
+            fragment_navigation_pages: self.fragment_navigation_pages,
+            images: self.images.finish(),
+            downloads: self.downloads.finish(),
+            performance: self.performance.finish(),
+            third_party_frames: self.third_party_frames.finish(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct ImageTally {
+    stats: ImageStats,
+    seen: HashSet<String>,
+    largest: Vec<LargestImage>,
+}
+
+impl ImageTally {
+    fn add(&mut self, page: &PageInfo) {
+        for image in &page.images {
+            self.stats.total += 1;
+            match image.alt.as_deref().map(str::trim) {
+                Some("") => self.stats.decorative += 1,
+                Some(_) => self.stats.with_alt += 1,
+                None => self.stats.missing_alt += 1,
+            }
+            if !self.seen.insert(image.src.clone()) {
+                continue;
+            }
+            self.stats.unique += 1;
+            if let (Some(width), Some(height)) = (image.width, image.height) {
+                self.largest.push(LargestImage {
+                    src: image.src.clone(),
+                    page_url: page.url.clone(),
+                    width,
+                    height,
+                });
+            }
+        }
+    }
+
+    fn finish(self) -> ImageStats {
+        let mut largest = self.largest;
+        largest.sort_by(|a, b| b.area().cmp(&a.area()).then_with(|| a.src.cmp(&b.src)));
+        largest.truncate(MAX_LARGEST_IMAGES);
+        ImageStats {
+            largest,
+            ..self.stats
+        }
+    }
+}
+
+#[derive(Default)]
+struct DownloadTally {
+    stats: DownloadStats,
+    seen: HashSet<String>,
+    largest: Vec<LargestDownload>,
+}
+
+impl DownloadTally {
+    fn add(&mut self, page: &PageInfo, html_content_types: &HtmlContentTypes) {
+        for link in page
+            .links
+            .iter()
+            .filter(|link| link.is_download(html_content_types))
+        {
+            if !self.seen.insert(link.url.clone()) {
+                continue;
+            }
+            self.stats.total += 1;
+            if link.status_code.is_some_and(|code| code >= 400) || link.check_error.is_some() {
+                self.stats.broken += 1;
+            } else if let Some(content_length) = link.content_length {
+                self.largest.push(LargestDownload {
+                    url: link.url.clone(),
+                    page_url: page.url.clone(),
+                    content_type: link.content_type.clone(),
+                    content_length,
+                });
+            }
+        }
+    }
+
+    fn finish(self) -> DownloadStats {
+        let mut largest = self.largest;
+        largest.sort_by(|a, b| {
+            b.content_length
+                .cmp(&a.content_length)
+                .then_with(|| a.url.cmp(&b.url))
+        });
+        largest.truncate(MAX_LARGEST_DOWNLOADS);
+        DownloadStats {
+            largest,
+            ..self.stats
+        }
+    }
+}
+
+#[derive(Default)]
+struct ThirdPartyFrameTally {
+    total: usize,
+    pages: usize,
+    domains: BTreeMap<String, ThirdPartyFrameDomain>,
+}
+
+impl ThirdPartyFrameTally {
+    fn add(&mut self, page: &PageInfo) {
+        let page_domains = page.third_party_frame_domains();
+        if page_domains.is_empty() {
+            return;
+        }
+        self.pages += 1;
+        for (domain, frames) in page_domains {
+            self.total += frames;
+            let entry =
+                self.domains
+                    .entry(domain.clone())
+                    .or_insert_with(|| ThirdPartyFrameDomain {
+                        domain,
+                        frames: 0,
+                        pages: Vec::new(),
+                    });
+            entry.frames += frames;
+            entry.pages.push(page.url.clone());
+        }
+    }
+
+    fn finish(self) -> ThirdPartyFrameStats {
+        let mut domains: Vec<ThirdPartyFrameDomain> = self.domains.into_values().collect();
+        domains.sort_by(|a, b| {
+            b.frames
+                .cmp(&a.frames)
+                .then_with(|| a.domain.cmp(&b.domain))
+        });
+        ThirdPartyFrameStats {
+            total: self.total,
+            pages: self.pages,
+            domains,
+        }
+    }
+}
+
+#[derive(Default)]
+struct PerformanceTally {
+    slowest: Vec<SlowestPage>,
+    largest: Vec<LargestPage>,
+}
+
+impl PerformanceTally {
+    fn add(&mut self, page: &PageInfo) {
+        if let Some(response_time_ms) = page.response_time_ms {
+            self.slowest.push(SlowestPage {
+                url: page.url.clone(),
+                response_time_ms,
+            });
+        }
+        if let Some(content_length) = page.content_length {
+            self.largest.push(LargestPage {
+                url: page.url.clone(),
+                content_length,
+            });
+        }
+    }
+
+    fn finish(self) -> PerformanceStats {
+        let Self {
+            mut slowest,
+            mut largest,
+        } = self;
+        let average = |total: u64, count: usize| (count > 0).then(|| total / count as u64);
+        let measured = slowest.len();
+        let average_response_time_ms = average(
+            slowest.iter().map(|page| page.response_time_ms).sum(),
+            measured,
+        );
+        let average_content_length = average(
+            largest.iter().map(|page| page.content_length).sum(),
+            largest.len(),
+        );
+
+        slowest.sort_by(|a, b| {
+            b.response_time_ms
+                .cmp(&a.response_time_ms)
+                .then_with(|| a.url.cmp(&b.url))
+        });
+        slowest.truncate(MAX_PERFORMANCE_PAGES);
+        largest.sort_by(|a, b| {
+            b.content_length
+                .cmp(&a.content_length)
+                .then_with(|| a.url.cmp(&b.url))
+        });
+        largest.truncate(MAX_PERFORMANCE_PAGES);
+        PerformanceStats {
+            measured,
+            average_response_time_ms,
+            average_content_length,
+            slowest,
+            largest,
+        }
+    }
+}
+
+/// `CrawlReport` with its pages streamed from a page store, serialized with the same fields
+#[derive(Serialize)]
+struct StoredReport<'a> {
+    start_url: &'a str,
+    pages: StoredPages<'a>,
+    inlinks: &'a BTreeMap<String, Vec<String>>,
+    summary: &'a CrawlSummary,
+    timestamp: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_reached: Option<&'a str>,
+    #[serde(skip_serializing_if = "is_empty_map")]
+    explain: &'a BTreeMap<String, Vec<FrontierDecision>>,
+    #[serde(skip_serializing_if = "is_empty_map")]
+    errors: &'a BTreeMap<CrawlErrorCause, Vec<CrawlError>>,
+}
+
+fn is_empty_map<K, V>(map: &&BTreeMap<K, V>) -> bool {
+    map.is_empty()
+}
+
+struct StoredPages<'a>(&'a dyn PageStore);
+
+impl Serialize for StoredPages<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        let mut write_error = None;
+        let result = self.0.for_each_page(&mut |key, page| {
+            map.serialize_entry(key, page).map_err(|error| {
+                let message = error.to_string();
+                write_error = Some(error);
+                anyhow!(message)
+            })
+        });
+        if let Some(error) = write_error {
+            return Err(error);
+        }
+        result.map_err(|error| S::Error::custom(format!("{error:#}")))?;
+        map.end()
+    }
 }
@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::models::CrawlReport;
+
+/// The outcome of a CLI run in a few lines, for CI steps that only need to know whether it
+/// passed and why, without loading the full report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunResult {
+    /// The process's exit code: 0 when the run passed, 1 otherwise
+    pub exit_code: i32,
+    pub passed: bool,
+    /// Why the run stopped, when it failed before its checks could be evaluated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Pass/fail checks the run evaluated, such as `--baseline`
+    pub checks: Vec<ResultCheck>,
+    /// Totals of the report, missing when the run failed before producing one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counts: Option<ResultCounts>,
+    /// Set when `--max-requests`, `--max-bandwidth`, or `--max-duration` cut the scan short
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_reached: Option<String>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultCounts {
+    pub pages: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    pub links: usize,
+    pub broken_links: usize,
+}
+
+impl RunResult {
+    /// The result of a run that produced `report` and evaluated `checks`
+    pub fn from_report(report: &CrawlReport, checks: Vec<ResultCheck>) -> Self {
+        let passed = checks.iter().all(|check| check.passed);
+        let summary = &report.summary;
+        Self {
+            exit_code: if passed { 0 } else { 1 },
+            passed,
+            error: None,
+            checks,
+            counts: Some(ResultCounts {
+                pages: summary.total_pages,
+                errors: summary.errors,
+                warnings: summary.warnings,
+                infos: summary.infos,
+                links: summary.total_links,
+                broken_links: summary.broken_links,
+            }),
+            limit_reached: report.limit_reached.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// The result of a run that failed with `error`
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        Self {
+            exit_code: 1,
+            passed: false,
+            error: Some(format!("{error:#}")),
+            checks: Vec::new(),
+            counts: None,
+            limit_reached: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n")
+            .with_context(|| format!("Failed to write result file: {}", path.display()))
+    }
+}
@@ -1,5 +1,7 @@
+use crate::limits::{LimitReachedError, RequestLimits, find_limit_reached};
 use anyhow::Result;
 use std::collections::HashMap;
+use std::time::Duration;
 use url::Url;
 
 /// Represents a robots.txt rule (either Allow or Disallow)
@@ -12,8 +14,12 @@ struct Rule {
 /// Represents the parsed robots.txt file
 #[derive(Debug)]
 pub struct RobotsTxt {
-    /// Rules grouped by user-agent (lowercased)
+    /// Rules grouped by user-agent product token (lowercased, version stripped)
     rules: HashMap<String, Vec<Rule>>,
+    /// `Crawl-delay` values keyed like `rules`
+    crawl_delays: HashMap<String, Duration>,
+    /// `Sitemap` URLs per domain, which apply to every user agent
+    sitemaps: HashMap<String, Vec<String>>,
     /// Cache of fetched robots.txt per domain
     cache: HashMap<String, bool>,
 }
@@ -28,12 +34,20 @@ impl RobotsTxt {
     pub fn new() -> Self {
         Self {
             rules: HashMap::new(),
+            crawl_delays: HashMap::new(),
+            sitemaps: HashMap::new(),
             cache: HashMap::new(),
         }
     }
 
-    /// Fetches and parses robots.txt for a given URL
-    pub async fn fetch(&mut self, client: &reqwest::Client, base_url: &Url) -> Result<()> {
+    /// Fetches and parses robots.txt for a given URL, counting the request and its body
+    /// against `limits`
+    pub async fn fetch(
+        &mut self,
+        client: &reqwest::Client,
+        base_url: &Url,
+        limits: Option<&RequestLimits>,
+    ) -> Result<()> {
         let robots_url = self.get_robots_url(base_url)?;
         let domain_key = self.get_domain_key(base_url);
 
@@ -41,10 +55,15 @@ impl RobotsTxt {
         if self.cache.contains_key(&domain_key) {
             return Ok(());
         }
+        if limits.is_some_and(|limits| !limits.try_acquire()) {
+            return Err(LimitReachedError.into());
+        }
 
         // Fetch robots.txt
         let response = match client.get(&robots_url).send().await {
             Ok(resp) => resp,
+            // A redirect the limits refused says nothing about the rules
+            Err(error) if find_limit_reached(&error).is_some() => return Err(error.into()),
             Err(_) => {
                 // If robots.txt doesn't exist or can't be fetched, allow all
                 tracing::info!(url = %robots_url, "robots.txt not found, allowing all paths");
@@ -66,8 +85,11 @@ impl RobotsTxt {
             return Ok(());
         }
 
-        let content = response.text().await?;
-        self.parse(&domain_key, &content);
+        let body = response.bytes().await?;
+        if let Some(limits) = limits {
+            limits.record_bytes(body.len() as u64);
+        }
+        self.parse(&domain_key, &String::from_utf8_lossy(&body));
         self.cache.insert(domain_key, true);
 
         Ok(())
@@ -77,6 +99,7 @@ impl RobotsTxt {
     fn parse(&mut self, domain_key: &str, content: &str) {
         let mut current_agents: Vec<String> = Vec::new();
         let mut current_rules: Vec<Rule> = Vec::new();
+        let mut current_delay: Option<Duration> = None;
 
         for line in content.lines() {
             let line = line.trim();
@@ -97,14 +120,16 @@ impl RobotsTxt {
 
             match field.as_str() {
                 "user-agent" => {
-                    if current_rules.is_empty() {
+                    if current_rules.is_empty() && current_delay.is_none() {
                         current_agents.push(value.to_string());
                         continue;
                     }
 
                     self.save_rules(domain_key, &current_agents, &current_rules);
+                    self.save_crawl_delay(domain_key, &current_agents, current_delay);
                     current_agents = vec![value.to_string()];
                     current_rules = Vec::new();
+                    current_delay = None;
                 }
                 "disallow" => {
                     if !value.is_empty() {
@@ -122,13 +147,43 @@ impl RobotsTxt {
                         });
                     }
                 }
+                "crawl-delay" => {
+                    current_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+                        .map(Duration::from_secs_f64);
+                }
+                "sitemap" => {
+                    if !value.is_empty() {
+                        self.sitemaps
+                            .entry(domain_key.to_string())
+                            .or_default()
+                            .push(value.to_string());
+                    }
+                }
                 _ => {
-                    // Ignore other directives (Crawl-delay, Sitemap, etc.)
+                    // Ignore other directives (Host, Clean-param, etc.)
                 }
             }
         }
 
         self.save_rules(domain_key, &current_agents, &current_rules);
+        self.save_crawl_delay(domain_key, &current_agents, current_delay);
+    }
+
+    /// The `Crawl-delay` that applies to a user agent on the URL's host, if any
+    pub fn crawl_delay(&self, url: &Url, user_agent: &str) -> Option<Duration> {
+        let domain_key = self.get_domain_key(url);
+        Self::find_group(&self.crawl_delays, &domain_key, user_agent).copied()
+    }
+
+    /// Sitemap URLs listed in the robots.txt of the URL's host
+    pub fn sitemaps(&self, url: &Url) -> &[String] {
+        self.sitemaps
+            .get(&self.get_domain_key(url))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
     }
 
     /// Checks if a URL is allowed to be crawled
@@ -136,20 +191,40 @@ impl RobotsTxt {
         let domain_key = self.get_domain_key(url);
         let path = url.path();
 
-        // Check for user-agent-specific rules
-        let specific_key = format!("{}:{}", domain_key, user_agent.to_lowercase());
-        if let Some(rules) = self.rules.get(&specific_key) {
-            return self.check_rules(rules, path);
+        match Self::find_group(&self.rules, &domain_key, user_agent) {
+            Some(rules) => self.check_rules(rules, path),
+            // If no rules found, allow by default
+            None => true,
         }
+    }
 
-        // Check for wildcard (*) rules
-        let wildcard_key = format!("{}:*", domain_key);
-        if let Some(rules) = self.rules.get(&wildcard_key) {
-            return self.check_rules(rules, path);
-        }
+    /// The entry of the group whose user-agent best matches `user_agent` on a host: the
+    /// longest group token that `user_agent` starts with, ignoring case (so `User-agent: scout`
+    /// applies to "scoutly"), or the `*` group when none does
+    fn find_group<'a, T>(
+        groups: &'a HashMap<String, T>,
+        domain_key: &str,
+        user_agent: &str,
+    ) -> Option<&'a T> {
+        let user_agent = user_agent.to_lowercase();
+        let prefix = format!("{}:", domain_key);
+
+        groups
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix(&prefix)?, value)))
+            .filter(|(token, _)| {
+                !token.is_empty() && *token != "*" && user_agent.starts_with(token)
+            })
+            .max_by_key(|(token, _)| token.len())
+            .map(|(_, value)| value)
+            .or_else(|| groups.get(&format!("{}:*", domain_key)))
+    }
 
-        // If no rules found, allow by default
-        true
+    /// The key a group's `User-agent` line is stored under: the product token without a
+    /// version, lowercased, so `User-agent: Scoutly/1.0` is matched as "scoutly"
+    fn group_key(domain_key: &str, agent: &str) -> String {
+        let token = agent.split('/').next().unwrap_or_default().trim();
+        format!("{}:{}", domain_key, token.to_lowercase())
     }
 
     /// Checks if a path matches any rules
@@ -265,8 +340,19 @@ impl RobotsTxt {
         }
 
         for agent in agents {
-            let key = format!("{}:{}", domain_key, agent.to_lowercase());
-            self.rules.insert(key, rules.to_vec());
+            self.rules
+                .insert(Self::group_key(domain_key, agent), rules.to_vec());
+        }
+    }
+
+    fn save_crawl_delay(&mut self, domain_key: &str, agents: &[String], delay: Option<Duration>) {
+        let Some(delay) = delay else {
+            return;
+        };
+
+        for agent in agents {
+            self.crawl_delays
+                .insert(Self::group_key(domain_key, agent), delay);
         }
     }
 }
@@ -387,6 +473,40 @@ Allow: /private/public
         assert!(robots.is_allowed(&url3, "scoutly"));
     }
 
+    #[test]
+    fn test_is_allowed_matches_longest_user_agent_prefix() {
+        let content = r#"
+User-agent: *
+Disallow: /
+
+User-agent: Scout
+Disallow: /drafts
+
+User-agent: scoutly/2.0
+Disallow: /private
+
+User-agent: scoutlybot
+Disallow: /
+"#;
+
+        let mut robots = RobotsTxt::new();
+        robots.parse("http://example.com", content);
+
+        let drafts = Url::parse("http://example.com/drafts").unwrap();
+        let private = Url::parse("http://example.com/private").unwrap();
+
+        // "scoutly" beats the shorter "scout" group; "scoutlybot" doesn't apply to it
+        assert!(robots.is_allowed(&drafts, "Scoutly"));
+        assert!(!robots.is_allowed(&private, "SCOUTLY"));
+
+        // A token only "scout" prefixes falls back to that group
+        assert!(!robots.is_allowed(&drafts, "scouter"));
+        assert!(robots.is_allowed(&private, "scouter"));
+
+        // Anything else gets the wildcard group
+        assert!(!robots.is_allowed(&drafts, "otherbot"));
+    }
+
     #[test]
     fn test_is_allowed_no_rules() {
         let robots = RobotsTxt::new();
@@ -496,6 +616,69 @@ Disallow: /secret
         assert_eq!(google_rules.len(), 1); // Only Disallow /secret
     }
 
+    #[test]
+    fn test_parse_sitemaps_outside_user_agent_groups() {
+        let content = r#"
+Sitemap: https://example.com/sitemap.xml
+User-agent: *
+Disallow: /admin
+Sitemap: https://example.com/news-sitemap.xml
+"#;
+
+        let mut robots = RobotsTxt::new();
+        robots.parse("https://example.com", content);
+
+        let url = Url::parse("https://example.com/page").unwrap();
+        assert_eq!(
+            robots.sitemaps(&url),
+            [
+                "https://example.com/sitemap.xml",
+                "https://example.com/news-sitemap.xml"
+            ]
+        );
+        assert!(robots.is_allowed(&url, "scoutly"));
+        let other = Url::parse("https://other.example/").unwrap();
+        assert!(robots.sitemaps(&other).is_empty());
+    }
+
+    #[test]
+    fn test_parse_crawl_delay_per_user_agent() {
+        let content = r#"
+User-agent: *
+Crawl-delay: 2.5
+
+User-agent: scoutly
+User-agent: otherbot
+Crawl-delay: 10
+Disallow: /private
+
+User-agent: badbot
+Crawl-delay: soon
+"#;
+
+        let mut robots = RobotsTxt::new();
+        robots.parse("http://example.com", content);
+
+        let url = Url::parse("http://example.com/page").unwrap();
+        assert_eq!(
+            robots.crawl_delay(&url, "Scoutly"),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            robots.crawl_delay(&url, "somebot"),
+            Some(Duration::from_millis(2500))
+        );
+        assert_eq!(
+            robots.crawl_delay(&url, "badbot"),
+            Some(Duration::from_millis(2500))
+        );
+        assert!(!robots.is_allowed(
+            &Url::parse("http://example.com/private").unwrap(),
+            "otherbot"
+        ));
+        assert!(!robots.rules.contains_key("http://example.com:*"));
+    }
+
     #[test]
     fn test_path_matches_wildcard_failure() {
         // Test wildcard pattern that should fail to match
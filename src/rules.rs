@@ -0,0 +1,575 @@
+use anyhow::{Context, Result, anyhow, bail};
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use url::Url;
+
+use crate::models::{IssueSeverity, IssueType, SeoIssue};
+
+pub const DEFAULT_TITLE_MIN_LENGTH: usize = 50;
+pub const DEFAULT_TITLE_MAX_LENGTH: usize = 60;
+pub const DEFAULT_META_DESCRIPTION_MIN_LENGTH: usize = 150;
+pub const DEFAULT_META_DESCRIPTION_MAX_LENGTH: usize = 160;
+pub const DEFAULT_COMMENT_MARKERS: [&str; 4] = ["TODO", "FIXME", "HACK", "XXX"];
+
+/// Reference information shown alongside issues so readers know why they matter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub issue_type: IssueType,
+    pub explanation: &'static str,
+    pub help_url: &'static str,
+}
+
+const TITLE_HELP_URL: &str = "https://developers.google.com/search/docs/appearance/title-link";
+const META_DESCRIPTION_HELP_URL: &str =
+    "https://developers.google.com/search/docs/appearance/snippet";
+const HEADING_HELP_URL: &str =
+    "https://developer.mozilla.org/en-US/docs/Web/HTML/Element/Heading_Elements";
+const OPEN_GRAPH_HELP_URL: &str = "https://ogp.me/";
+
+pub const fn rule_for(issue_type: IssueType) -> Rule {
+    let (explanation, help_url) = match issue_type {
+        IssueType::MissingTitle => (
+            "Search engines use the title as the headline of a result; without one they have to guess.",
+            TITLE_HELP_URL,
+        ),
+        IssueType::TitleTooShort => (
+            "Short titles waste space in search results and rarely describe the page well enough to earn a click.",
+            TITLE_HELP_URL,
+        ),
+        IssueType::TitleTooLong => (
+            "Long titles get truncated in search results, hiding the end of the text from users.",
+            TITLE_HELP_URL,
+        ),
+        IssueType::MissingMetaDescription => (
+            "The meta description is often used as the snippet under a search result; without it one is generated from page text.",
+            META_DESCRIPTION_HELP_URL,
+        ),
+        IssueType::MetaDescriptionTooShort => (
+            "Short descriptions give searchers little reason to choose this page over others.",
+            META_DESCRIPTION_HELP_URL,
+        ),
+        IssueType::MetaDescriptionTooLong => (
+            "Long descriptions get truncated in search results.",
+            META_DESCRIPTION_HELP_URL,
+        ),
+        IssueType::MissingImageAlt => (
+            "Alt text describes images to screen readers and search engines, and is shown when an image fails to load.",
+            "https://developers.google.com/search/docs/appearance/google-images#use-descriptive-alt-text",
+        ),
+        IssueType::MissingH1 => (
+            "The H1 states what a page is about for both users and crawlers.",
+            HEADING_HELP_URL,
+        ),
+        IssueType::MultipleH1 => (
+            "Several H1 tags blur the main topic of a page and make the heading outline harder to follow.",
+            HEADING_HELP_URL,
+        ),
+        IssueType::ThinContent => (
+            "Pages with very little content are less likely to be useful to visitors or to rank in search.",
+            "https://developers.google.com/search/docs/fundamentals/creating-helpful-content",
+        ),
+        IssueType::BrokenLink => (
+            "Broken links send visitors and crawlers to dead ends and waste crawl budget.",
+            "https://developer.mozilla.org/en-US/docs/Web/HTTP/Status",
+        ),
+        IssueType::Redirect => (
+            "Each redirect adds a round trip; link straight to the final URL where possible.",
+            "https://developers.google.com/search/docs/crawling-indexing/301-redirects",
+        ),
+        IssueType::RedirectLoop => (
+            "A redirect loop never reaches a page, so visitors see an error and crawlers give up on the URL.",
+            "https://developers.google.com/search/docs/crawling-indexing/301-redirects",
+        ),
+        IssueType::MissingOgTitle
+        | IssueType::MissingOgDescription
+        | IssueType::MissingOgImage
+        | IssueType::MissingOgUrl
+        | IssueType::MissingOgType => (
+            "Open Graph tags control how the page looks when shared on social networks and chat apps.",
+            OPEN_GRAPH_HELP_URL,
+        ),
+        IssueType::PerformanceBudgetExceeded => (
+            "Heavy pages and pages that pull in many resources load slowly, especially on mobile connections.",
+            "https://web.dev/articles/performance-budgets-101",
+        ),
+        IssueType::CanonicalConflict => (
+            "When the Link header and the HTML name different canonicals, search engines may ignore both.",
+            "https://developers.google.com/search/docs/crawling-indexing/consolidate-duplicate-urls",
+        ),
+        IssueType::HreflangConflict => (
+            "Alternate links that point one language at different URLs send mixed signals about which version to show.",
+            "https://developers.google.com/search/docs/specialty/international/localized-versions",
+        ),
+        IssueType::HreflangBrokenTarget | IssueType::HreflangRedirectTarget => (
+            "Search engines ignore hreflang alternates that don't answer 200 directly, so the language version may never be shown.",
+            "https://developers.google.com/search/docs/specialty/international/localized-versions",
+        ),
+        IssueType::HreflangMissingReturnLink => (
+            "Hreflang annotations only count when both pages point to each other; without the return link the pair is ignored.",
+            "https://developers.google.com/search/docs/specialty/international/localized-versions#bidirectional",
+        ),
+        IssueType::UndeclaredVary => (
+            "Caches and CDNs that aren't told a response varies may serve one visitor's version to everyone, including crawlers.",
+            "https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Vary",
+        ),
+        IssueType::MissingVaryAcceptEncoding => (
+            "Without Vary: Accept-Encoding, a shared cache can hand a compressed response to a client that can't decode it.",
+            "https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Vary",
+        ),
+        IssueType::CustomCheck => (
+            "A check from one of the scripts configured for this site failed.",
+            "https://rhai.rs/book/",
+        ),
+        IssueType::AssertionFailed => (
+            "A page doesn't match what the config asserts about it, such as a required form that went missing or a reference to a retired hostname.",
+            "https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_selectors",
+        ),
+        IssueType::CommentMarker => (
+            "Notes left in HTML comments are sent to every visitor and often reveal unfinished work or how the site is built.",
+            "https://developer.mozilla.org/en-US/docs/Web/HTML/Comments",
+        ),
+        IssueType::CommentSecret => (
+            "HTML comments are public: keys, passwords, and internal hostnames in them can be read by anyone who views the source.",
+            "https://owasp.org/www-project-web-security-testing-guide/latest/4-Web_Application_Security_Testing/01-Information_Gathering/05-Review_Web_Page_Content_for_Information_Leakage",
+        ),
+        IssueType::ExposedEmail | IssueType::ExposedPhoneNumber => (
+            "Contact details in plain text are easy for scrapers to harvest and may need a privacy review.",
+            "https://owasp.org/www-project-web-security-testing-guide/latest/4-Web_Application_Security_Testing/01-Information_Gathering/05-Review_Web_Page_Content_for_Information_Leakage",
+        ),
+        IssueType::ExposedSecret => (
+            "Keys, tokens, and internal addresses in public page source can be copied by anyone who views it; revoke exposed credentials and remove them from the markup.",
+            "https://owasp.org/www-project-web-security-testing-guide/latest/4-Web_Application_Security_Testing/01-Information_Gathering/05-Review_Web_Page_Content_for_Information_Leakage",
+        ),
+        IssueType::CrossHostDuplicate => (
+            "The same page on two hostnames, such as www and the apex domain, splits links and rankings between copies; redirect one host to the other or point both at one canonical URL.",
+            "https://developers.google.com/search/docs/crawling-indexing/consolidate-duplicate-urls",
+        ),
+        IssueType::OrphanPage => (
+            "Pages that only a sitemap points to get no link equity and are hard for visitors to find; search engines may treat them as unimportant.",
+            "https://developers.google.com/search/docs/crawling-indexing/links-crawlable",
+        ),
+        IssueType::SitemapLastmodStale => (
+            "A sitemap lastmod older than the page's last change tells search engines nothing new happened, so the update may wait for a routine recrawl; regenerate the sitemap when pages change.",
+            "https://developers.google.com/search/docs/crawling-indexing/sitemaps/build-sitemap#additional-notes-about-xml-sitemaps",
+        ),
+        IssueType::SitemapLastmodInaccurate => (
+            "Search engines only use lastmod when it is consistently accurate; dates that are malformed, in the future, or bumped without a real change teach them to ignore it for the whole site.",
+            "https://developers.google.com/search/docs/crawling-indexing/sitemaps/build-sitemap#additional-notes-about-xml-sitemaps",
+        ),
+        IssueType::NoindexPage => (
+            "A noindex directive removes the page from search results; that's intended for thank-you pages and internal search, but a template or staging setting left in place can de-index real content.",
+            "https://developers.google.com/search/docs/crawling-indexing/block-indexing",
+        ),
+        IssueType::ResponseTooLarge => (
+            "Search engines only read the start of very large HTML files, so content past the cutoff may never be indexed, and slow downloads hurt visitors too; split the page or move inline data out of the markup.",
+            "https://developers.google.com/search/docs/crawling-indexing/googlebot#how-googlebot-accesses-your-site",
+        ),
+        IssueType::RedirectChainTooLong => (
+            "Every extra redirect adds a round trip for visitors and crawlers, and search engines may stop following long chains; link and redirect straight to the final URL.",
+            "https://developers.google.com/search/docs/crawling-indexing/301-redirects",
+        ),
+        IssueType::TrackingParamsStripped => (
+            "A redirect that drops utm_* or affiliate parameters loses the attribution of the click, so campaigns look unused and affiliate sales go uncredited; link to the final URL or make the redirect carry the query string over.",
+            "https://support.google.com/analytics/answer/10917952",
+        ),
+        IssueType::NonPublicLink => (
+            "Links to localhost, local network names, and IP addresses are usually left over from development: visitors can't reach them, and private addresses reveal how the internal network is laid out.",
+            "https://datatracker.ietf.org/doc/html/rfc1918",
+        ),
+    };
+
+    Rule {
+        issue_type,
+        explanation,
+        help_url,
+    }
+}
+
+/// Per-page limits checked against the weight measured while crawling; unset limits aren't checked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformanceBudget {
+    pub max_html_bytes: Option<usize>,
+    /// Requests implied by the markup: the document plus scripts, stylesheets, images, frames, and media
+    pub max_requests: Option<usize>,
+    pub max_scripts: Option<usize>,
+}
+
+impl PerformanceBudget {
+    pub fn merge(self, overlay: &PerformanceBudget) -> Self {
+        Self {
+            max_html_bytes: overlay.max_html_bytes.or(self.max_html_bytes),
+            max_requests: overlay.max_requests.or(self.max_requests),
+            max_scripts: overlay.max_scripts.or(self.max_scripts),
+        }
+    }
+}
+
+/// Threshold changes and disabled checks for pages whose path matches a config pattern
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleOverride {
+    pub title_min_length: Option<usize>,
+    pub title_max_length: Option<usize>,
+    pub meta_description_min_length: Option<usize>,
+    pub meta_description_max_length: Option<usize>,
+    /// Budget limits for matching pages, written inline like the length thresholds
+    #[serde(flatten)]
+    pub budget: PerformanceBudget,
+    /// Words that flag an HTML comment as a leftover note, e.g. `TODO`; empty turns the check off
+    pub comment_markers: Option<Vec<String>>,
+    /// Issue types that are not reported for matching pages
+    pub disable: Option<Vec<IssueType>>,
+}
+
+impl RuleOverride {
+    fn merge(self, overlay: &RuleOverride) -> Self {
+        Self {
+            budget: self.budget.merge(&overlay.budget),
+            title_min_length: overlay.title_min_length.or(self.title_min_length),
+            title_max_length: overlay.title_max_length.or(self.title_max_length),
+            meta_description_min_length: overlay
+                .meta_description_min_length
+                .or(self.meta_description_min_length),
+            meta_description_max_length: overlay
+                .meta_description_max_length
+                .or(self.meta_description_max_length),
+            comment_markers: overlay.comment_markers.clone().or(self.comment_markers),
+            disable: overlay.disable.clone().or(self.disable),
+        }
+    }
+
+    pub fn is_disabled(&self, issue_type: IssueType) -> bool {
+        self.disable
+            .as_ref()
+            .is_some_and(|disabled| disabled.contains(&issue_type))
+    }
+}
+
+/// Compiled per-path overrides, keyed by glob patterns matched against the URL path.
+/// `*` stays within a path segment and `**` spans segments, so `/blog/**` covers every blog post.
+#[derive(Debug, Clone, Default)]
+pub struct PathRules {
+    base: RuleOverride,
+    rules: Vec<(GlobMatcher, RuleOverride)>,
+}
+
+impl PathRules {
+    pub fn new(overrides: &BTreeMap<String, RuleOverride>) -> Result<Self> {
+        let mut patterns: Vec<_> = overrides.iter().collect();
+        // Longer patterns are usually more specific, so they are applied last and win
+        patterns.sort_by_key(|(pattern, _)| pattern.len());
+
+        let rules = patterns
+            .into_iter()
+            .map(|(pattern, rule)| {
+                let matcher = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .with_context(|| format!("Invalid rule path pattern: {pattern}"))?
+                    .compile_matcher();
+                Ok((matcher, rule.clone()))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            base: RuleOverride::default(),
+            rules,
+        })
+    }
+
+    /// Apply site-wide settings to every page; matching path patterns still override them
+    pub fn with_base(mut self, base: RuleOverride) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Combined override for a page; fields set by more specific patterns win
+    pub fn for_url(&self, url: &str) -> RuleOverride {
+        let Ok(parsed) = Url::parse(url) else {
+            return self.base.clone();
+        };
+
+        self.rules
+            .iter()
+            .filter(|(matcher, _)| matcher.is_match(parsed.path()))
+            .fold(self.base.clone(), |merged, (_, rule)| merged.merge(rule))
+    }
+}
+
+/// A config rule about the pages under a path pattern: a CSS selector that must (or must not)
+/// match an element, or a regex that must (or must not) match the page's HTML
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageAssertion {
+    pub selector: Option<String>,
+    /// Regular expression searched for in the HTML, e.g. an old CDN hostname
+    pub pattern: Option<String>,
+    /// `false` asserts that nothing matches
+    #[serde(default = "default_must_exist")]
+    pub must_exist: bool,
+    /// Path pattern of the pages to check, like the keys of `rules`; every page when unset
+    pub on: Option<String>,
+    /// Issue message shown instead of the generated one
+    pub message: Option<String>,
+}
+
+const fn default_must_exist() -> bool {
+    true
+}
+
+#[derive(Debug, Clone)]
+enum AssertionMatcher {
+    Selector(Selector),
+    Pattern(Regex),
+}
+
+/// Compiled page assertions, checked against each HTML page as it is crawled
+#[derive(Debug, Clone, Default)]
+pub struct PageAssertions {
+    assertions: Vec<(Option<GlobMatcher>, AssertionMatcher, PageAssertion)>,
+}
+
+impl PageAssertions {
+    pub fn new(assertions: &[PageAssertion]) -> Result<Self> {
+        let assertions = assertions
+            .iter()
+            .map(|assertion| {
+                let path_matcher = assertion
+                    .on
+                    .as_deref()
+                    .map(|pattern| {
+                        GlobBuilder::new(pattern)
+                            .literal_separator(true)
+                            .build()
+                            .map(|glob| glob.compile_matcher())
+                            .with_context(|| format!("Invalid assertion path pattern: {pattern}"))
+                    })
+                    .transpose()?;
+                let matcher = match (&assertion.selector, &assertion.pattern) {
+                    (Some(selector), None) => {
+                        AssertionMatcher::Selector(Selector::parse(selector).map_err(|error| {
+                            anyhow!("Invalid assertion selector '{selector}': {error}")
+                        })?)
+                    }
+                    (None, Some(pattern)) => AssertionMatcher::Pattern(
+                        Regex::new(pattern)
+                            .with_context(|| format!("Invalid assertion pattern: {pattern}"))?,
+                    ),
+                    _ => bail!("Each assertion needs either a selector or a pattern, not both"),
+                };
+                Ok((path_matcher, matcher, assertion.clone()))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { assertions })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assertions.is_empty()
+    }
+
+    /// An `AssertionFailed` error for each assertion covering `url` that the page breaks
+    pub fn check(&self, url: &Url, document: &Html, html: &str) -> Vec<SeoIssue> {
+        self.assertions
+            .iter()
+            .filter(|(path_matcher, _, _)| {
+                path_matcher
+                    .as_ref()
+                    .is_none_or(|matcher| matcher.is_match(url.path()))
+            })
+            .filter_map(|(_, matcher, assertion)| {
+                let (check, subject, found) = match matcher {
+                    AssertionMatcher::Selector(selector) => {
+                        let source = assertion.selector.as_deref().unwrap_or_default();
+                        (
+                            source,
+                            format!("element matching `{source}`"),
+                            document
+                                .select(selector)
+                                .next()
+                                .map(|_| "element found".to_string()),
+                        )
+                    }
+                    AssertionMatcher::Pattern(regex) => (
+                        regex.as_str(),
+                        format!("text matching /{}/", regex.as_str()),
+                        regex
+                            .find(html)
+                            .map(|found| format!("`{}`", found.as_str())),
+                    ),
+                };
+                let (message, expected, actual) = match (assertion.must_exist, found) {
+                    (true, None) => (
+                        format!("Page has no {subject}"),
+                        "a match",
+                        "none found".to_string(),
+                    ),
+                    (false, Some(found)) => (format!("Page has {subject}"), "no match", found),
+                    _ => return None,
+                };
+                Some(
+                    SeoIssue::new(
+                        IssueSeverity::Error,
+                        IssueType::AssertionFailed,
+                        assertion.message.clone().unwrap_or(message),
+                    )
+                    .with_expected(expected)
+                    .with_actual(actual)
+                    .with_check(check),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_for_returns_rule_for_requested_type() {
+        let rule = rule_for(IssueType::BrokenLink);
+
+        assert_eq!(rule.issue_type, IssueType::BrokenLink);
+        assert!(rule.help_url.starts_with("https://"));
+        assert!(!rule.explanation.is_empty());
+    }
+
+    #[test]
+    fn path_rules_apply_most_specific_matching_pattern() {
+        let overrides = BTreeMap::from([
+            (
+                "/blog/**".to_string(),
+                RuleOverride {
+                    title_min_length: Some(20),
+                    disable: Some(vec![IssueType::ThinContent]),
+                    ..Default::default()
+                },
+            ),
+            (
+                "/blog/tags/*".to_string(),
+                RuleOverride {
+                    title_min_length: Some(5),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let rules = PathRules::new(&overrides).unwrap();
+
+        let post = rules.for_url("https://example.com/blog/2024/hello");
+        assert_eq!(post.title_min_length, Some(20));
+        assert!(post.is_disabled(IssueType::ThinContent));
+
+        let tag = rules.for_url("https://example.com/blog/tags/rust?page=2");
+        assert_eq!(tag.title_min_length, Some(5));
+        assert!(tag.is_disabled(IssueType::ThinContent));
+
+        assert_eq!(
+            rules.for_url("https://example.com/about"),
+            RuleOverride::default()
+        );
+    }
+
+    #[test]
+    fn page_assertions_check_pages_under_their_path() {
+        let assertions = PageAssertions::new(&[
+            PageAssertion {
+                selector: Some("form#newsletter".to_string()),
+                on: Some("/blog/**".to_string()),
+                ..assertion()
+            },
+            PageAssertion {
+                selector: Some(".lorem-ipsum".to_string()),
+                must_exist: false,
+                message: Some("Placeholder text left on the page".to_string()),
+                ..assertion()
+            },
+            PageAssertion {
+                pattern: Some(r"old-cdn\.example\.com".to_string()),
+                must_exist: false,
+                ..assertion()
+            },
+        ])
+        .unwrap();
+        let html =
+            r#"<p class="lorem-ipsum">Lorem</p><img src="https://old-cdn.example.com/a.png">"#;
+        let check = |url: &str, html: &str| {
+            assertions
+                .check(&Url::parse(url).unwrap(), &Html::parse_document(html), html)
+                .into_iter()
+                .map(|issue| (issue.message, issue.actual, issue.check))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            check("https://example.com/blog/2024/hello", html),
+            [
+                (
+                    "Page has no element matching `form#newsletter`".to_string(),
+                    Some("none found".to_string()),
+                    Some("form#newsletter".to_string())
+                ),
+                (
+                    "Placeholder text left on the page".to_string(),
+                    Some("element found".to_string()),
+                    Some(".lorem-ipsum".to_string())
+                ),
+                (
+                    r"Page has text matching /old-cdn\.example\.com/".to_string(),
+                    Some("`old-cdn.example.com`".to_string()),
+                    Some(r"old-cdn\.example\.com".to_string())
+                ),
+            ]
+        );
+        assert_eq!(check("https://example.com/about", html).len(), 2);
+        assert!(
+            check(
+                "https://example.com/blog/post",
+                r#"<form id="newsletter"></form>"#
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn page_assertions_reject_invalid_assertions() {
+        let selector = |selector: &str, on: &str| PageAssertion {
+            selector: Some(selector.to_string()),
+            on: Some(on.to_string()),
+            ..assertion()
+        };
+
+        assert!(PageAssertions::new(&[selector("form[", "/**")]).is_err());
+        assert!(PageAssertions::new(&[selector("form", "/blog/[")]).is_err());
+        assert!(
+            PageAssertions::new(&[PageAssertion {
+                pattern: Some("cdn(".to_string()),
+                ..assertion()
+            }])
+            .is_err()
+        );
+        assert!(PageAssertions::new(&[assertion()]).is_err());
+        assert!(
+            PageAssertions::new(&[PageAssertion {
+                pattern: Some("cdn".to_string()),
+                ..selector("form", "/**")
+            }])
+            .is_err()
+        );
+    }
+
+    fn assertion() -> PageAssertion {
+        PageAssertion {
+            must_exist: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn path_rules_reject_invalid_patterns() {
+        let overrides = BTreeMap::from([("/blog/[".to_string(), RuleOverride::default())]);
+
+        assert!(PathRules::new(&overrides).is_err());
+    }
+}
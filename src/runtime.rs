@@ -1,4 +1,5 @@
 use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
 use std::io::{IsTerminal, stdin, stdout};
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -12,6 +13,7 @@ pub enum LaunchMode {
     Tui,
     Text,
     Json,
+    Jsonl,
 }
 
 impl LaunchMode {
@@ -20,11 +22,13 @@ impl LaunchMode {
             Self::Tui => None,
             Self::Text => Some(OutputFormat::Text),
             Self::Json => Some(OutputFormat::Json),
+            Self::Jsonl => Some(OutputFormat::Jsonl),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RunStage {
     LoadingConfig,
     Crawling,
@@ -40,6 +44,8 @@ pub struct ProgressSnapshot {
     pub stage: RunStage,
     pub message: String,
     pub pages_crawled: usize,
+    /// URLs waiting to be crawled
+    pub queued_urls: usize,
     pub links_discovered: usize,
     pub links_checked: usize,
     pub total_links: usize,
@@ -52,6 +58,7 @@ impl ProgressSnapshot {
             stage,
             message: message.into(),
             pages_crawled: 0,
+            queued_urls: 0,
             links_discovered: 0,
             links_checked: 0,
             total_links: 0,
@@ -62,6 +69,11 @@ impl ProgressSnapshot {
                 errors: 0,
                 warnings: 0,
                 infos: 0,
+                fragment_navigation_pages: 0,
+                images: Default::default(),
+                downloads: Default::default(),
+                performance: Default::default(),
+                third_party_frames: Default::default(),
             },
         }
     }
@@ -114,6 +126,7 @@ pub fn resolve_launch_mode(
 
     match runtime.output {
         Some(OutputFormat::Json) => Ok(LaunchMode::Json),
+        Some(OutputFormat::Jsonl) => Ok(LaunchMode::Jsonl),
         Some(OutputFormat::Text) => Ok(LaunchMode::Text),
         None if runtime.cli => Ok(LaunchMode::Text),
         None if terminal.is_interactive() => Ok(LaunchMode::Tui),
@@ -124,25 +137,97 @@ pub fn resolve_launch_mode(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::LinkCheckScope;
     use crate::config::RuntimeOptions;
+    use crate::limits::ByteSize;
 
     fn runtime() -> RuntimeOptions {
         RuntimeOptions {
             url: None,
+            additional_urls: vec![],
             depth: 5,
             max_pages: 200,
             output: None,
+            json_style: Default::default(),
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
+            screenshot_browser: None,
             cli: false,
             external: false,
             verbose: false,
             ignore_redirects: false,
+            check_links: LinkCheckScope::All,
+            never_check_domains: vec![],
+            always_check_domains: vec![],
+            html_content_types: None,
             keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            sort_query: false,
+            ignore_params: vec![],
+            respect_nofollow: false,
             rate_limit: None,
             concurrency: 5,
+            timeout: 30,
+            link_timeout: 10,
+            insecure: false,
+            ca_cert: None,
             respect_robots_txt: true,
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            robots_agent: "scoutly".to_string(),
+            cookies: vec![],
+            cookies_file: None,
+            login: None,
+            audit_vary: false,
+            trace_redirects: false,
+            redirect_chain_limit: 1,
+            find_contacts: false,
+            scan_secrets: false,
+            heading_outline: false,
+            secret_patterns: Default::default(),
+            use_sitemap: false,
+            max_sitemaps: 50,
+            render: Default::default(),
+            gentle: false,
+            auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: ByteSize(10_000_000),
+            max_duration: None,
+            retries: 0,
+            retry_backoff_ms: 500,
+            retry_jitter: true,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: 10,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: Default::default(),
+            budget: Default::default(),
+            comment_markers: None,
+            crux_api_key: None,
+            rule_overrides: Default::default(),
+            assertions: vec![],
             tui: false,
-            config: None,
+            config: vec![],
         }
     }
 
@@ -347,5 +432,6 @@ mod tests {
         assert_eq!(LaunchMode::Tui.output_format(), None);
         assert_eq!(LaunchMode::Text.output_format(), Some(OutputFormat::Text));
         assert_eq!(LaunchMode::Json.output_format(), Some(OutputFormat::Json));
+        assert_eq!(LaunchMode::Jsonl.output_format(), Some(OutputFormat::Jsonl));
     }
 }
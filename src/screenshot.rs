@@ -0,0 +1,191 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::models::{HtmlContentTypes, IssueSeverity, PageInfo};
+use crate::page_store::PageStore;
+
+/// Browser executables tried, in order, when no browser is configured
+pub(crate) const BROWSER_CANDIDATES: [&str; 5] = [
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+];
+const WINDOW_SIZE: &str = "1280,800";
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Which pages get a screenshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotMode {
+    /// Every HTML page
+    All,
+    /// Only pages with at least one error-level issue
+    Errors,
+}
+
+impl ScreenshotMode {
+    pub fn includes(self, page: &PageInfo, html_content_types: &HtmlContentTypes) -> bool {
+        // Pages that failed to load or aren't HTML have nothing worth rendering
+        if page.status_code.is_none() || !html_content_types.contains(page.content_type.as_deref())
+        {
+            return false;
+        }
+
+        match self {
+            Self::All => true,
+            Self::Errors => page
+                .issues
+                .iter()
+                .any(|issue| issue.severity == IssueSeverity::Error),
+        }
+    }
+}
+
+/// Directory for the screenshots of a saved report: `report.json` gets `report-screenshots/`
+pub fn screenshot_dir_for(report_path: &Path) -> PathBuf {
+    let stem = report_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "report".to_string());
+    report_path.with_file_name(format!("{stem}-screenshots"))
+}
+
+/// Captures pages with a headless Chrome or Chromium
+pub struct Screenshotter {
+    browser: PathBuf,
+    html_content_types: HtmlContentTypes,
+}
+
+impl Screenshotter {
+    /// Use the configured browser, or the first Chrome/Chromium found on `PATH`
+    pub fn new(browser: Option<&str>) -> Result<Self> {
+        let browser = find_browser(browser).with_context(|| {
+            format!(
+                "Screenshots need Chrome or Chromium; none of {} was found on PATH. Set screenshot_browser in the config file.",
+                BROWSER_CANDIDATES.join(", ")
+            )
+        })?;
+
+        Ok(Self {
+            browser,
+            html_content_types: HtmlContentTypes::default(),
+        })
+    }
+
+    /// Count pages of these content types as HTML pages worth capturing
+    pub fn with_html_content_types(mut self, html_content_types: HtmlContentTypes) -> Self {
+        self.html_content_types = html_content_types;
+        self
+    }
+
+    pub async fn capture(&self, url: &str, output: &Path) -> Result<()> {
+        let mut command = Command::new(&self.browser);
+        command
+            .arg("--headless")
+            .arg("--disable-gpu")
+            .arg("--hide-scrollbars")
+            .arg(format!("--window-size={WINDOW_SIZE}"))
+            .arg(format!("--screenshot={}", output.display()))
+            .arg(url)
+            .kill_on_drop(true);
+
+        let result = tokio::time::timeout(CAPTURE_TIMEOUT, command.output())
+            .await
+            .with_context(|| format!("Timed out capturing screenshot of {url}"))?
+            .with_context(|| format!("Failed to run browser: {}", self.browser.display()))?;
+
+        if !result.status.success() || !output.exists() {
+            anyhow::bail!(
+                "Browser did not produce a screenshot of {url} ({}): {}",
+                result.status,
+                String::from_utf8_lossy(&result.stderr).trim()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Capture the selected pages into `dir` and record each file on its page, relative to the
+    /// directory holding the report. Pages that fail are logged and left without a screenshot.
+    pub async fn capture_pages(
+        &self,
+        pages: &mut dyn PageStore,
+        mode: ScreenshotMode,
+        dir: &Path,
+    ) -> Result<usize> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create screenshot directory: {}", dir.display()))?;
+        let dir_name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut selected = Vec::new();
+        pages.for_each_page(&mut |key, page| {
+            if mode.includes(page, &self.html_content_types) {
+                selected.push((key.to_string(), page.url.clone()));
+            }
+            Ok(())
+        })?;
+
+        let mut captured = 0;
+        for (key, url) in selected {
+            let Some(screenshot) = self.capture_page(&url, dir, &dir_name).await else {
+                continue;
+            };
+            if let Some(mut page) = pages.get(&key)? {
+                page.screenshot = Some(screenshot);
+                pages.insert(key, page)?;
+                captured += 1;
+            }
+        }
+
+        Ok(captured)
+    }
+
+    /// Capture `url` into `dir`, returning the file relative to the directory holding the report
+    async fn capture_page(&self, url: &str, dir: &Path, dir_name: &str) -> Option<String> {
+        let file_name = format!("{}.png", file_stem_for(url));
+        match self.capture(url, &dir.join(&file_name)).await {
+            Ok(()) => Some(format!("{dir_name}/{file_name}")),
+            Err(error) => {
+                tracing::warn!(url = %url, error = %error, "Failed to capture screenshot");
+                None
+            }
+        }
+    }
+}
+
+/// Stable file name for a page URL
+fn file_stem_for(url: &str) -> String {
+    Sha256::digest(url.as_bytes())[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// The configured browser, or the first of `BROWSER_CANDIDATES` on `PATH`
+pub(crate) fn find_browser(browser: Option<&str>) -> Option<PathBuf> {
+    match browser {
+        Some(browser) => Some(PathBuf::from(browser)),
+        None => BROWSER_CANDIDATES
+            .iter()
+            .find_map(|name| find_on_path(name)),
+    }
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
@@ -0,0 +1,248 @@
+use anyhow::{Context, Result, anyhow};
+use rhai::{AST, Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+use crate::models::{HtmlContentTypes, IssueSeverity, IssueType, PageInfo, SeoIssue};
+use crate::rules::PathRules;
+
+/// Operations one script may run on one page, so a runaway loop can't stall the scan
+const MAX_SCRIPT_OPERATIONS: u64 = 1_000_000;
+
+type Reports = Arc<Mutex<Vec<(IssueSeverity, String)>>>;
+
+struct Script {
+    /// File stem, recorded as the `check` of the issues the script raises
+    name: String,
+    ast: AST,
+}
+
+/// User-written Rhai checks run against every crawled HTML page.
+///
+/// Each script sees a `page` map (`url`, `path`, `status_code`, `title`, `meta_description`,
+/// `canonical`, `h1_tags`, `headings`, `text`, `word_count`, `meta`, `links`, `images`,
+/// `crawl_depth`, and `html`) and reports problems with `error(message)`, `warning(message)`,
+/// or `info(message)`. `select(page.html, "css selector")` returns the matching elements as
+/// maps with `text` and `attrs`.
+pub struct ScriptChecks {
+    engine: Engine,
+    scripts: Vec<Script>,
+    reports: Reports,
+}
+
+impl ScriptChecks {
+    /// Read and compile the scripts, failing on the first one that doesn't parse
+    pub fn load(paths: &[String]) -> Result<Self> {
+        let reports: Reports = Arc::default();
+        let engine = Self::build_engine(&reports);
+
+        let scripts = paths
+            .iter()
+            .map(|path| {
+                let source = fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read script: {path}"))?;
+                let ast = engine
+                    .compile(&source)
+                    .map_err(|error| anyhow!("Failed to compile script {path}: {error}"))?;
+                let name = Path::new(path)
+                    .file_stem()
+                    .map_or_else(|| path.clone(), |stem| stem.to_string_lossy().into_owned());
+                Ok(Script { name, ast })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            engine,
+            scripts,
+            reports,
+        })
+    }
+
+    fn build_engine(reports: &Reports) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+        engine.on_print(|text| tracing::info!(output = %text, "Script print"));
+        engine.on_debug(|text, source, position| {
+            tracing::debug!(output = %text, source = ?source, position = %position, "Script debug");
+        });
+        engine.register_fn("select", select);
+
+        for (name, severity) in [
+            ("error", IssueSeverity::Error),
+            ("warning", IssueSeverity::Warning),
+            ("info", IssueSeverity::Info),
+        ] {
+            let reports = Arc::clone(reports);
+            engine.register_fn(name, move |message: &str| {
+                if let Ok(mut reports) = reports.lock() {
+                    reports.push((severity, message.to_string()));
+                }
+            });
+        }
+
+        engine
+    }
+
+    /// Run every script against the HTML pages, skipping paths whose rules disable
+    /// `CustomCheck`. Script errors are logged and don't stop the scan.
+    pub fn run(
+        &self,
+        pages: &mut HashMap<String, PageInfo>,
+        rules: &PathRules,
+        html_content_types: &HtmlContentTypes,
+    ) {
+        for page in pages.values_mut() {
+            self.run_page(page, rules, html_content_types);
+        }
+    }
+
+    /// `run` for a single page
+    pub fn run_page(
+        &self,
+        page: &mut PageInfo,
+        rules: &PathRules,
+        html_content_types: &HtmlContentTypes,
+    ) {
+        if !html_content_types.contains(page.content_type.as_deref())
+            || rules.for_url(&page.url).is_disabled(IssueType::CustomCheck)
+        {
+            return;
+        }
+
+        let page_map = Dynamic::from_map(page_map(page));
+        for script in &self.scripts {
+            let mut scope = Scope::new();
+            scope.push_dynamic("page", page_map.clone());
+            if let Err(error) = self.engine.run_ast_with_scope(&mut scope, &script.ast) {
+                tracing::warn!(script = %script.name, url = %page.url, error = %error, "Script check failed to run");
+            }
+
+            let reports = self
+                .reports
+                .lock()
+                .map(|mut reports| std::mem::take(&mut *reports))
+                .unwrap_or_default();
+            page.issues
+                .extend(reports.into_iter().map(|(severity, message)| {
+                    SeoIssue::new(severity, IssueType::CustomCheck, message)
+                        .with_check(&script.name)
+                }));
+        }
+
+        // The markup was only kept for the scripts
+        page.content.html = None;
+    }
+}
+
+/// The elements of `html` matching a CSS selector, as `#{ text, attrs }` maps
+fn select(html: &str, selector: &str) -> Result<Array, Box<EvalAltResult>> {
+    let parsed = Selector::parse(selector)
+        .map_err(|error| format!("Invalid CSS selector '{selector}': {error}"))?;
+
+    Ok(Html::parse_document(html)
+        .select(&parsed)
+        .map(|element| {
+            let attrs: Map = element
+                .value()
+                .attrs()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect();
+            let mut found = Map::new();
+            found.insert(
+                "text".into(),
+                element.text().collect::<String>().trim().into(),
+            );
+            found.insert("attrs".into(), Dynamic::from_map(attrs));
+            Dynamic::from_map(found)
+        })
+        .collect())
+}
+
+fn page_map(page: &PageInfo) -> Map {
+    let optional = |value: &Option<String>| value.clone().map_or(Dynamic::UNIT, Dynamic::from);
+    let strings = |values: &[String]| -> Dynamic {
+        Dynamic::from_array(values.iter().cloned().map(Dynamic::from).collect())
+    };
+
+    let headings = page
+        .content
+        .headings
+        .iter()
+        .map(|heading| {
+            let mut map = Map::new();
+            map.insert("level".into(), (heading.level as i64).into());
+            map.insert("text".into(), heading.text.clone().into());
+            Dynamic::from_map(map)
+        })
+        .collect();
+    let links = page
+        .links
+        .iter()
+        .map(|link| {
+            let mut map = Map::new();
+            map.insert("url".into(), link.url.clone().into());
+            map.insert("text".into(), link.text.clone().into());
+            map.insert("is_external".into(), link.is_external.into());
+            map.insert(
+                "status_code".into(),
+                link.status_code
+                    .map_or(Dynamic::UNIT, |code| (code as i64).into()),
+            );
+            Dynamic::from_map(map)
+        })
+        .collect();
+    let images = page
+        .images
+        .iter()
+        .map(|image| {
+            let mut map = Map::new();
+            map.insert("src".into(), image.src.clone().into());
+            map.insert("alt".into(), optional(&image.alt));
+            Dynamic::from_map(map)
+        })
+        .collect();
+    let meta: Map = page
+        .content
+        .meta
+        .iter()
+        .map(|(name, content)| (name.into(), content.clone().into()))
+        .collect();
+
+    let mut map = Map::new();
+    map.insert("url".into(), page.url.clone().into());
+    map.insert(
+        "path".into(),
+        Url::parse(&page.url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_default()
+            .into(),
+    );
+    map.insert(
+        "status_code".into(),
+        page.status_code
+            .map_or(Dynamic::UNIT, |code| (code as i64).into()),
+    );
+    map.insert("title".into(), optional(&page.title));
+    map.insert("meta_description".into(), optional(&page.meta_description));
+    map.insert("canonical".into(), optional(&page.canonical));
+    map.insert("h1_tags".into(), strings(&page.h1_tags));
+    map.insert("headings".into(), Dynamic::from_array(headings));
+    map.insert("text".into(), page.content.text.clone().into());
+    map.insert(
+        "word_count".into(),
+        (page.content.word_count() as i64).into(),
+    );
+    map.insert("meta".into(), Dynamic::from_map(meta));
+    map.insert("links".into(), Dynamic::from_array(links));
+    map.insert("images".into(), Dynamic::from_array(images));
+    map.insert("crawl_depth".into(), (page.crawl_depth as i64).into());
+    map.insert(
+        "html".into(),
+        page.content.html.clone().unwrap_or_default().into(),
+    );
+    map
+}
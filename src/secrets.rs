@@ -0,0 +1,288 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+use crate::models::{IssueSeverity, IssueType, PageInfo, SeoIssue};
+use crate::rules::PathRules;
+
+/// Credentials and internal addresses that shouldn't be public, shared by `--scan-secrets`
+/// and the HTML comment check so the two look for the same things
+pub struct SecretPattern {
+    /// The name `secret_patterns` overrides it with
+    pub name: &'static str,
+    /// What a match looks like, as comment issues describe it
+    pub label: &'static str,
+    pub pattern: &'static str,
+    /// Whether `--scan-secrets` looks for it unless `secret_patterns` turns it off
+    pub scanned_by_default: bool,
+    /// Severity of a match in an HTML comment, or `None` when comments aren't checked for it
+    pub comment_severity: Option<IssueSeverity>,
+    /// Whether comment issues cut a match to its first four characters
+    pub redact_in_comments: bool,
+}
+
+pub const SECRET_PATTERNS: [SecretPattern; 10] = [
+    SecretPattern {
+        name: "aws_access_key",
+        label: "an AWS access key",
+        pattern: r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+        scanned_by_default: true,
+        comment_severity: Some(IssueSeverity::Error),
+        redact_in_comments: true,
+    },
+    SecretPattern {
+        name: "google_api_key",
+        label: "a Google API key",
+        pattern: r"\bAIza[0-9A-Za-z_\-]{35}\b",
+        scanned_by_default: true,
+        comment_severity: Some(IssueSeverity::Error),
+        redact_in_comments: true,
+    },
+    SecretPattern {
+        name: "github_token",
+        label: "a GitHub token",
+        pattern: r"\bgh[pousr]_[0-9A-Za-z]{36,}\b",
+        scanned_by_default: true,
+        comment_severity: Some(IssueSeverity::Error),
+        redact_in_comments: true,
+    },
+    SecretPattern {
+        name: "stripe_secret_key",
+        label: "a Stripe secret key",
+        pattern: r"\b[rs]k_live_[0-9A-Za-z]{16,}\b",
+        scanned_by_default: true,
+        comment_severity: Some(IssueSeverity::Error),
+        redact_in_comments: true,
+    },
+    SecretPattern {
+        name: "slack_token",
+        label: "a Slack token",
+        pattern: r"\bxox[abporsu]-[0-9A-Za-z-]{10,}",
+        scanned_by_default: true,
+        comment_severity: Some(IssueSeverity::Error),
+        redact_in_comments: true,
+    },
+    SecretPattern {
+        name: "private_key",
+        label: "a private key",
+        pattern: r"-----BEGIN [A-Z ]*PRIVATE KEY-----",
+        scanned_by_default: true,
+        comment_severity: Some(IssueSeverity::Error),
+        redact_in_comments: false,
+    },
+    SecretPattern {
+        name: "jwt",
+        label: "a JSON Web Token",
+        pattern: r"\beyJ[0-9A-Za-z_-]{10,}\.eyJ[0-9A-Za-z_-]{10,}\.[0-9A-Za-z_-]{10,}",
+        scanned_by_default: true,
+        comment_severity: None,
+        redact_in_comments: true,
+    },
+    // Too common in scripts to search whole pages for, but telling in a comment
+    SecretPattern {
+        name: "credential",
+        label: "a credential",
+        pattern: r#"(?i)\b(?:api[_-]?key|secret|password|passwd|token)\s*[:=]\s*['"]?[^\s'"]{8,}"#,
+        scanned_by_default: false,
+        comment_severity: Some(IssueSeverity::Error),
+        redact_in_comments: true,
+    },
+    SecretPattern {
+        name: "internal_hostname",
+        label: "an internal hostname",
+        pattern: r"(?i)\b(?:localhost|[a-z0-9-]+(?:\.[a-z0-9-]+)*\.(?:internal|local|corp|lan|intranet))(?::\d+)?\b",
+        scanned_by_default: false,
+        comment_severity: Some(IssueSeverity::Warning),
+        redact_in_comments: false,
+    },
+    SecretPattern {
+        name: "private_ip",
+        label: "a private IP address",
+        pattern: r"\b(?:10\.\d{1,3}|172\.(?:1[6-9]|2\d|3[01])|192\.168)\.\d{1,3}\.\d{1,3}\b",
+        scanned_by_default: true,
+        comment_severity: Some(IssueSeverity::Warning),
+        redact_in_comments: false,
+    },
+];
+
+/// Matches longer than this are cut to their first four characters in reports
+const MAX_UNREDACTED_CHARS: usize = 16;
+/// Distinct matches quoted in an issue before the rest are only counted
+const MAX_LISTED: usize = 3;
+
+/// Scans the served HTML of every crawled page for credentials, tokens, and internal
+/// addresses that shouldn't be public
+pub struct SecretScanner {
+    /// Each pattern's name, regex, and whether the comment check already reports its matches
+    patterns: Vec<(String, Regex, bool)>,
+}
+
+impl SecretScanner {
+    /// The built-in patterns with `overrides` applied: a name that matches a built-in pattern
+    /// replaces it, an empty pattern turns it off, and other names add a pattern
+    pub fn new(overrides: &BTreeMap<String, String>) -> Result<Self> {
+        let mut sources: BTreeMap<String, String> = SECRET_PATTERNS
+            .iter()
+            .filter(|secret| secret.scanned_by_default)
+            .map(|secret| (secret.name.to_string(), secret.pattern.to_string()))
+            .collect();
+        sources.extend(overrides.clone());
+
+        let patterns = sources
+            .into_iter()
+            .filter(|(_, pattern)| !pattern.is_empty())
+            .map(|(name, pattern)| {
+                let regex = Regex::new(&pattern)
+                    .with_context(|| format!("Invalid secret pattern '{name}': {pattern}"))?;
+                let in_comments = SECRET_PATTERNS.iter().any(|secret| {
+                    secret.name == name
+                        && secret.pattern == pattern
+                        && secret.comment_severity.is_some()
+                });
+                Ok((name, regex, in_comments))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// Report each pattern found in a page's HTML as an error, skipping paths whose rules
+    /// disable `ExposedSecret`
+    pub fn run(&self, pages: &mut HashMap<String, PageInfo>, rules: &PathRules) {
+        for page in pages.values_mut() {
+            self.scan_page(page, rules);
+        }
+    }
+
+    /// `run` for a single page. Matches inside HTML comments are left to the comment check
+    /// when it looks for the same pattern, so they aren't reported twice.
+    pub fn scan_page(&self, page: &mut PageInfo, rules: &PathRules) {
+        let Some(html) = &page.content.html else {
+            return;
+        };
+        let rule = rules.for_url(&page.url);
+        if rule.is_disabled(IssueType::ExposedSecret) {
+            return;
+        }
+        let comments = if rule.is_disabled(IssueType::CommentSecret) {
+            Vec::new()
+        } else {
+            comment_spans(html)
+        };
+
+        let mut issues = Vec::new();
+        for (name, regex, in_comments) in &self.patterns {
+            let mut found: Vec<&str> = Vec::new();
+            for matched in regex.find_iter(html) {
+                if *in_comments && comments.iter().any(|span| span.contains(&matched.start())) {
+                    continue;
+                }
+                if !found.contains(&matched.as_str()) {
+                    found.push(matched.as_str());
+                }
+            }
+            if found.is_empty() {
+                continue;
+            }
+
+            let mut listed = found
+                .iter()
+                .take(MAX_LISTED)
+                .map(|matched| redact(matched))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if found.len() > MAX_LISTED {
+                listed.push_str(&format!(" and {} more", found.len() - MAX_LISTED));
+            }
+            issues.push(
+                SeoIssue::new(
+                    IssueSeverity::Error,
+                    IssueType::ExposedSecret,
+                    format!(
+                        "Page source contains {} match(es) for secret pattern `{name}`",
+                        found.len()
+                    ),
+                )
+                .with_check(name)
+                .with_actual(listed),
+            );
+        }
+        page.issues.extend(issues);
+    }
+}
+
+/// Byte ranges of the HTML comments in `html`, an unclosed one running to the end
+fn comment_spans(html: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut from = 0;
+    while let Some(start) = html[from..].find("<!--").map(|offset| from + offset) {
+        let end = html[start + 4..]
+            .find("-->")
+            .map_or(html.len(), |offset| start + 4 + offset + 3);
+        spans.push(start..end);
+        from = end;
+    }
+    spans
+}
+
+fn redact(matched: &str) -> String {
+    if matched.chars().count() > MAX_UNREDACTED_CHARS {
+        format!("{}…", matched.chars().take(4).collect::<String>())
+    } else {
+        matched.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_secret_patterns_compile() {
+        let scanner = SecretScanner::new(&BTreeMap::new()).unwrap();
+        assert_eq!(
+            scanner.patterns.len(),
+            SECRET_PATTERNS
+                .iter()
+                .filter(|secret| secret.scanned_by_default)
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_overrides_replace_disable_and_add_patterns() {
+        let overrides = BTreeMap::from([
+            ("private_ip".to_string(), String::new()),
+            ("jwt".to_string(), r"\beyJ\S+".to_string()),
+            (
+                "internal_host".to_string(),
+                r"\b\w+\.corp\.example\b".to_string(),
+            ),
+        ]);
+        let scanner = SecretScanner::new(&overrides).unwrap();
+        let names: Vec<_> = scanner
+            .patterns
+            .iter()
+            .map(|(name, _, _)| name.as_str())
+            .collect();
+
+        assert!(!names.contains(&"private_ip"));
+        assert!(names.contains(&"internal_host"));
+        assert_eq!(
+            scanner.patterns[names.iter().position(|name| *name == "jwt").unwrap()]
+                .1
+                .as_str(),
+            r"\beyJ\S+"
+        );
+
+        let error = SecretScanner::new(&BTreeMap::from([("broken".to_string(), "[".to_string())]))
+            .err()
+            .unwrap();
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid secret pattern 'broken'")
+        );
+    }
+}
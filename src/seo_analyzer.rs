@@ -1,5 +1,36 @@
-use crate::models::{IssueSeverity, IssueType, PageInfo, SeoIssue};
-use std::collections::HashMap;
+use crate::hosts;
+use crate::models::{
+    DiscoveryMethod, HtmlContentTypes, IssueSeverity, IssueType, PageInfo, SeoIssue, document_url,
+};
+use crate::page_store::PageStore;
+use crate::rules::{
+    DEFAULT_COMMENT_MARKERS, DEFAULT_META_DESCRIPTION_MAX_LENGTH,
+    DEFAULT_META_DESCRIPTION_MIN_LENGTH, DEFAULT_TITLE_MAX_LENGTH, DEFAULT_TITLE_MIN_LENGTH,
+    PathRules, RuleOverride,
+};
+use crate::secrets::{SECRET_PATTERNS, SecretPattern};
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use url::Url;
+
+/// Characters of a comment quoted in its issue
+const COMMENT_EXCERPT_CHARS: usize = 80;
+
+/// The secret patterns comments are checked for, with the severity a match is reported with
+static COMMENT_SECRET_PATTERNS: Lazy<Vec<(&SecretPattern, Regex, IssueSeverity)>> =
+    Lazy::new(|| {
+        SECRET_PATTERNS
+            .iter()
+            .filter_map(|secret| {
+                let severity = secret.comment_severity?;
+                let regex = Regex::new(secret.pattern).expect("secret pattern should be valid");
+                Some((secret, regex, severity))
+            })
+            .collect()
+    });
 
 struct LengthRule<'a> {
     min_length: usize,
@@ -15,35 +46,153 @@ struct LengthRule<'a> {
 pub struct SeoAnalyzer;
 
 impl SeoAnalyzer {
-    pub fn analyze_pages(pages: &mut HashMap<String, PageInfo>) {
-        for page in pages.values_mut() {
-            // Only analyze SEO for HTML pages
-            if let Some(content_type) = &page.content_type
-                && content_type.to_lowercase().contains("text/html")
-            {
-                Self::analyze_page(page);
-            }
+    pub fn analyze_pages(pages: &mut dyn PageStore) -> Result<()> {
+        Self::analyze_pages_with_rules(pages, &PathRules::default(), &HtmlContentTypes::default())
+    }
+
+    /// Analyze pages with per-path threshold overrides; issue types disabled for a page's
+    /// path are dropped, including those reported earlier by the link checker. What the checks
+    /// comparing pages need is gathered in one pass and the pages are analyzed in a second, so
+    /// a page store is read one page at a time. Only pages of `html_content_types` are
+    /// analyzed.
+    pub fn analyze_pages_with_rules(
+        pages: &mut dyn PageStore,
+        rules: &PathRules,
+        html_content_types: &HtmlContentTypes,
+    ) -> Result<()> {
+        let mut index = SiteIndex::default();
+        pages.for_each_page(&mut |key, page| {
+            index.add(key, page, html_content_types);
+            Ok(())
+        })?;
+        let mut site = index.finish();
+        pages.update_each(&mut |key, page| {
+            Self::analyze_site_page(key, page, &mut site, rules, html_content_types);
+            Ok(())
+        })
+    }
+
+    fn analyze_site_page(
+        key: &str,
+        page: &mut PageInfo,
+        site: &mut Site,
+        rules: &PathRules,
+        html_content_types: &HtmlContentTypes,
+    ) {
+        let rule = rules.for_url(&page.url);
+        Self::flag_orphan_page(page, &site.linked);
+        if let Some(others) = site.duplicates.remove(&page.url) {
+            Self::flag_cross_host_duplicate(page, &others);
+        }
+
+        // Only analyze SEO for HTML pages that were parsed, and only once per document
+        if site.documents.contains(key) && Self::is_analyzable(page, html_content_types) {
+            Self::analyze_page(page, &rule);
+        }
+
+        if rule.disable.is_some() {
+            page.issues
+                .retain(|issue| !rule.is_disabled(issue.issue_type));
+        }
+    }
+
+    /// Flag a page the crawl only reached through a sitemap: no crawled page links to it
+    fn flag_orphan_page(page: &mut PageInfo, linked: &HashSet<String>) {
+        // Links to a URL that redirects to the page count too
+        if page.source.method != DiscoveryMethod::Sitemap
+            || std::iter::once(&page.url)
+                .chain(&page.aliases)
+                .filter_map(|url| document_url(url))
+                .any(|url| linked.contains(url.as_str()))
+        {
+            return;
+        }
+
+        let mut issue = Self::issue(
+            IssueSeverity::Warning,
+            IssueType::OrphanPage,
+            "Page is listed in a sitemap but no crawled page links to it".to_string(),
+        )
+        .with_expected("at least one internal link");
+        if let Some(sitemap_url) = &page.source.found_on {
+            issue = issue.with_offending_url(sitemap_url);
         }
+        page.issues.push(issue);
     }
 
-    fn analyze_page(page: &mut PageInfo) {
+    /// Flag a page whose title and text are identical to `others` on another host, e.g. `www`
+    /// and the apex domain both serving the site
+    fn flag_cross_host_duplicate(page: &mut PageInfo, others: &[String]) {
+        page.issues.push(
+            Self::issue(
+                IssueSeverity::Warning,
+                IssueType::CrossHostDuplicate,
+                format!(
+                    "Page content is identical to {} page(s) on another host",
+                    others.len()
+                ),
+            )
+            .with_offending_url(&others[0])
+            .with_expected("one host serving the page, or a shared canonical URL")
+            .with_actual(others.join(", ")),
+        );
+    }
+
+    /// Run only the checks that need nothing but the page itself, for pages reported before
+    /// the rest of the site is crawled. The issues are the ones `analyze_pages_with_rules`
+    /// finds on the page, without those that compare it with other pages.
+    pub fn analyze_single_page(
+        page: &mut PageInfo,
+        rules: &PathRules,
+        html_content_types: &HtmlContentTypes,
+    ) {
+        let rule = rules.for_url(&page.url);
+        if Self::is_analyzable(page, html_content_types) {
+            Self::analyze_page(page, &rule);
+        }
+        if rule.disable.is_some() {
+            page.issues
+                .retain(|issue| !rule.is_disabled(issue.issue_type));
+        }
+    }
+
+    /// HTML pages that were parsed and aren't a copy of another page; pages that failed to
+    /// load have no content type
+    fn is_analyzable(page: &PageInfo, html_content_types: &HtmlContentTypes) -> bool {
+        page.content_type.is_some()
+            && html_content_types.contains(page.content_type.as_deref())
+            && page.duplicate_of.is_none()
+            && !page
+                .issues
+                .iter()
+                .any(|issue| issue.issue_type == IssueType::ResponseTooLarge)
+    }
+
+    fn analyze_page(page: &mut PageInfo, rule: &RuleOverride) {
         page.issues
-            .extend(Self::validate_title(page.title.as_deref()));
+            .extend(Self::validate_title(page.title.as_deref(), rule));
         page.issues.extend(Self::validate_meta_description(
             page.meta_description.as_deref(),
+            rule,
         ));
         page.issues.extend(Self::validate_h1_tags(&page.h1_tags));
         page.issues.extend(Self::validate_images(page));
+        page.issues.extend(Self::validate_link_hosts(page));
         page.issues.extend(Self::validate_thin_content(page));
         page.issues.extend(Self::validate_open_graph(page));
+        page.issues.extend(Self::validate_budget(page, rule));
+        page.issues.extend(Self::validate_link_headers(page));
+        page.issues.extend(Self::validate_vary(page));
+        page.issues.extend(Self::validate_comments(page, rule));
+        page.issues.extend(Self::validate_robots_directives(page));
     }
 
-    fn validate_title(title: Option<&str>) -> Vec<SeoIssue> {
+    fn validate_title(title: Option<&str>, rule: &RuleOverride) -> Vec<SeoIssue> {
         Self::validate_length(
             title,
             LengthRule {
-                min_length: 50,
-                max_length: 60,
+                min_length: rule.title_min_length.unwrap_or(DEFAULT_TITLE_MIN_LENGTH),
+                max_length: rule.title_max_length.unwrap_or(DEFAULT_TITLE_MAX_LENGTH),
                 missing_type: IssueType::MissingTitle,
                 missing_message: "Page is missing a title tag",
                 too_short_type: IssueType::TitleTooShort,
@@ -54,12 +203,16 @@ impl SeoAnalyzer {
         )
     }
 
-    fn validate_meta_description(description: Option<&str>) -> Vec<SeoIssue> {
+    fn validate_meta_description(description: Option<&str>, rule: &RuleOverride) -> Vec<SeoIssue> {
         Self::validate_length(
             description,
             LengthRule {
-                min_length: 150,
-                max_length: 160,
+                min_length: rule
+                    .meta_description_min_length
+                    .unwrap_or(DEFAULT_META_DESCRIPTION_MIN_LENGTH),
+                max_length: rule
+                    .meta_description_max_length
+                    .unwrap_or(DEFAULT_META_DESCRIPTION_MAX_LENGTH),
                 missing_type: IssueType::MissingMetaDescription,
                 missing_message: "Page is missing a meta description",
                 too_short_type: IssueType::MetaDescriptionTooShort,
@@ -80,26 +233,36 @@ impl SeoAnalyzer {
         };
 
         let value_len = value.len();
+        let expected = format!("{}-{} chars", rule.min_length, rule.max_length);
+        let actual = format!("{value_len} chars");
         if value_len < rule.min_length {
-            return vec![Self::issue(
-                IssueSeverity::Warning,
-                rule.too_short_type,
-                format!(
-                    "{} ({} chars, recommended: {}-{})",
-                    rule.too_short_label, value_len, rule.min_length, rule.max_length
-                ),
-            )];
+            return vec![
+                Self::issue(
+                    IssueSeverity::Warning,
+                    rule.too_short_type,
+                    format!(
+                        "{} ({} chars, recommended: {}-{})",
+                        rule.too_short_label, value_len, rule.min_length, rule.max_length
+                    ),
+                )
+                .with_expected(expected)
+                .with_actual(actual),
+            ];
         }
 
         if value_len > rule.max_length {
-            return vec![Self::issue(
-                IssueSeverity::Warning,
-                rule.too_long_type,
-                format!(
-                    "{} ({} chars, recommended: {}-{})",
-                    rule.too_long_label, value_len, rule.min_length, rule.max_length
-                ),
-            )];
+            return vec![
+                Self::issue(
+                    IssueSeverity::Warning,
+                    rule.too_long_type,
+                    format!(
+                        "{} ({} chars, recommended: {}-{})",
+                        rule.too_long_label, value_len, rule.min_length, rule.max_length
+                    ),
+                )
+                .with_expected(expected)
+                .with_actual(actual),
+            ];
         }
 
         Vec::new()
@@ -115,27 +278,223 @@ impl SeoAnalyzer {
         }
 
         if h1_tags.len() > 1 {
-            return vec![Self::issue(
-                IssueSeverity::Warning,
-                IssueType::MultipleH1,
-                format!("Page has multiple H1 tags ({})", h1_tags.len()),
-            )];
+            return vec![
+                Self::issue(
+                    IssueSeverity::Warning,
+                    IssueType::MultipleH1,
+                    format!("Page has multiple H1 tags ({})", h1_tags.len()),
+                )
+                .with_expected("1 H1 tag")
+                .with_actual(format!("{} H1 tags", h1_tags.len())),
+            ];
         }
 
         Vec::new()
     }
 
     fn validate_images(page: &PageInfo) -> Vec<SeoIssue> {
-        let missing_alt_count = page.images.iter().filter(|img| img.alt.is_none()).count();
-        if missing_alt_count == 0 {
+        let mut missing_alt = page.images.iter().filter(|img| img.alt.is_none());
+        let Some(first_missing) = missing_alt.next() else {
+            return Vec::new();
+        };
+        let missing_alt_count = 1 + missing_alt.count();
+
+        vec![
+            Self::issue(
+                IssueSeverity::Warning,
+                IssueType::MissingImageAlt,
+                format!("{} image(s) missing alt text", missing_alt_count),
+            )
+            .with_offending_url(&first_missing.src)
+            .with_expected("0 images without alt text")
+            .with_actual(format!("{missing_alt_count} image(s) without alt text")),
+        ]
+    }
+
+    /// Links and images pointing at localhost, local network names, or bare IP addresses,
+    /// usually left over from development. Pages served from such a host themselves, as in
+    /// a crawl of a development server, aren't checked.
+    fn validate_link_hosts(page: &PageInfo) -> Vec<SeoIssue> {
+        let served_locally = Url::parse(&page.url)
+            .ok()
+            .and_then(|url| hosts::classify_url(&url))
+            .is_some();
+        if served_locally {
             return Vec::new();
         }
 
-        vec![Self::issue(
-            IssueSeverity::Warning,
-            IssueType::MissingImageAlt,
-            format!("{} image(s) missing alt text", missing_alt_count),
-        )]
+        let mut seen = HashSet::new();
+        page.links
+            .iter()
+            .map(|link| link.url.as_str())
+            .chain(page.images.iter().map(|image| image.src.as_str()))
+            .filter(|target| seen.insert(*target))
+            .filter_map(|target| {
+                let host = hosts::classify_url(&Url::parse(target).ok()?)?;
+                Some(
+                    Self::issue(
+                        IssueSeverity::Error,
+                        IssueType::NonPublicLink,
+                        format!("Link to a {}: {target}", host.description()),
+                    )
+                    .with_offending_url(target)
+                    .with_expected("a public hostname")
+                    .with_actual(host.description()),
+                )
+            })
+            .collect()
+    }
+
+    fn validate_budget(page: &PageInfo, rule: &RuleOverride) -> Vec<SeoIssue> {
+        let weight = &page.weight;
+        [
+            (
+                rule.budget.max_html_bytes,
+                weight.html_bytes,
+                "html_bytes",
+                "HTML size",
+                "bytes",
+            ),
+            (
+                rule.budget.max_requests,
+                weight.requests,
+                "requests",
+                "Requests",
+                "requests",
+            ),
+            (
+                rule.budget.max_scripts,
+                weight.scripts,
+                "scripts",
+                "Scripts",
+                "scripts",
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(allowed, actual, check, label, unit)| {
+            let allowed = allowed.filter(|allowed| actual > *allowed)?;
+            Some(
+                Self::issue(
+                    IssueSeverity::Warning,
+                    IssueType::PerformanceBudgetExceeded,
+                    format!("{label} over budget ({actual} {unit}, allowed: {allowed})"),
+                )
+                .with_check(check)
+                .with_expected(format!("at most {allowed} {unit}"))
+                .with_actual(format!("{actual} {unit}")),
+            )
+        })
+        .collect()
+    }
+
+    /// Flag `Link` header canonicals and alternates that disagree with the HTML. The page's
+    /// merged values come from the HTML whenever it declares them.
+    fn validate_link_headers(page: &PageInfo) -> Vec<SeoIssue> {
+        let mut issues = Vec::new();
+
+        if let (Some(header), Some(html)) = (&page.link_headers.canonical, &page.canonical)
+            && header != html
+        {
+            issues.push(
+                Self::issue(
+                    IssueSeverity::Warning,
+                    IssueType::CanonicalConflict,
+                    "Link header and HTML declare different canonical URLs".to_string(),
+                )
+                .with_offending_url(header)
+                .with_expected(html)
+                .with_actual(header),
+            );
+        }
+
+        for header in &page.link_headers.alternates {
+            let Some(html) = page.alternates.iter().find(|alternate| {
+                alternate.hreflang.eq_ignore_ascii_case(&header.hreflang)
+                    && alternate.url != header.url
+            }) else {
+                continue;
+            };
+            issues.push(
+                Self::issue(
+                    IssueSeverity::Warning,
+                    IssueType::HreflangConflict,
+                    format!(
+                        "Link header and HTML declare different URLs for hreflang \"{}\"",
+                        header.hreflang
+                    ),
+                )
+                .with_offending_url(&header.url)
+                .with_expected(&html.url)
+                .with_actual(&header.url),
+            );
+        }
+
+        issues
+    }
+
+    fn validate_vary(page: &PageInfo) -> Vec<SeoIssue> {
+        let declares = |name: &str| page.vary.iter().any(|vary| vary == "*" || vary == name);
+        let declared = if page.vary.is_empty() {
+            "no Vary header".to_string()
+        } else {
+            format!("Vary: {}", page.vary.join(", "))
+        };
+        let mut issues = Vec::new();
+
+        for name in page.vary_observed.iter().filter(|name| !declares(name)) {
+            issues.push(
+                Self::issue(
+                    IssueSeverity::Warning,
+                    IssueType::UndeclaredVary,
+                    format!(
+                        "Content changes with the {name} request header but Vary does not list it"
+                    ),
+                )
+                .with_expected(format!("Vary includes {name}"))
+                .with_actual(declared.clone()),
+            );
+        }
+
+        if is_compressible_content_type(page.content_type.as_deref())
+            && !declares("accept-encoding")
+        {
+            issues.push(
+                Self::issue(
+                    IssueSeverity::Info,
+                    IssueType::MissingVaryAcceptEncoding,
+                    "Compressible response does not send Vary: Accept-Encoding".to_string(),
+                )
+                .with_expected("Vary includes accept-encoding")
+                .with_actual(declared),
+            );
+        }
+
+        issues
+    }
+
+    /// Note pages kept out of search results, so accidental noindex tags stand out
+    fn validate_robots_directives(page: &PageInfo) -> Vec<SeoIssue> {
+        if !page.has_robots_directive("noindex") {
+            return Vec::new();
+        }
+
+        vec![
+            Self::issue(
+                IssueSeverity::Info,
+                IssueType::NoindexPage,
+                "Page is marked noindex and won't appear in search results".to_string(),
+            )
+            .with_actual(
+                [
+                    ("meta robots", page.meta_robots.as_deref()),
+                    ("X-Robots-Tag", page.x_robots_tag.as_deref()),
+                ]
+                .into_iter()
+                .filter_map(|(source, value)| Some(format!("{source}: {}", value?)))
+                .collect::<Vec<_>>()
+                .join("; "),
+            ),
+        ]
     }
 
     fn validate_thin_content(page: &PageInfo) -> Vec<SeoIssue> {
@@ -182,11 +541,64 @@ impl SeoAnalyzer {
             ),
         ] {
             if value.map(str::trim).is_none_or(str::is_empty) {
-                issues.push(Self::issue(
-                    IssueSeverity::Info,
-                    issue_type,
-                    format!("Page is missing {tag_name} tag"),
-                ));
+                issues.push(
+                    Self::issue(
+                        IssueSeverity::Info,
+                        issue_type,
+                        format!("Page is missing {tag_name} tag"),
+                    )
+                    .with_expected(format!("{tag_name} tag present")),
+                );
+            }
+        }
+
+        issues
+    }
+
+    /// Flag HTML comments holding marker words such as `TODO`, or anything that looks like a
+    /// secret or an internal address
+    fn validate_comments(page: &PageInfo, rule: &RuleOverride) -> Vec<SeoIssue> {
+        let markers: Vec<&str> = match &rule.comment_markers {
+            Some(markers) => markers.iter().map(String::as_str).collect(),
+            None => DEFAULT_COMMENT_MARKERS.to_vec(),
+        };
+        let mut issues = Vec::new();
+
+        for comment in &page.content.comments {
+            if let Some(marker) = comment
+                .split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+                .find(|word| markers.contains(word))
+            {
+                issues.push(
+                    Self::issue(
+                        IssueSeverity::Info,
+                        IssueType::CommentMarker,
+                        format!("HTML comment contains {marker}"),
+                    )
+                    .with_check(marker)
+                    .with_actual(excerpt(comment)),
+                );
+            }
+
+            for (secret, regex, severity) in COMMENT_SECRET_PATTERNS.iter() {
+                if let Some(found) = regex.find(comment) {
+                    let found = found.as_str();
+                    let shown = if secret.redact_in_comments {
+                        format!("{}…", found.chars().take(4).collect::<String>())
+                    } else {
+                        found.to_string()
+                    };
+                    issues.push(
+                        Self::issue(
+                            *severity,
+                            IssueType::CommentSecret,
+                            format!("HTML comment contains what looks like {}", secret.label),
+                        )
+                        .with_check(secret.name)
+                        .with_expected("no secrets or internal addresses in comments")
+                        .with_actual(shown),
+                    );
+                }
             }
         }
 
@@ -194,10 +606,191 @@ impl SeoAnalyzer {
     }
 
     fn issue(severity: IssueSeverity, issue_type: IssueType, message: String) -> SeoIssue {
-        SeoIssue {
-            severity,
-            issue_type,
-            message,
+        SeoIssue::new(severity, issue_type, message)
+    }
+}
+
+/// What the checks comparing pages with each other need from every page, gathered one page
+/// at a time
+#[derive(Default)]
+struct SiteIndex {
+    /// Whether the URL has a fragment, the page's key, and its document URL
+    candidates: Vec<(bool, String, String)>,
+    /// Document URLs linked from another page
+    linked: HashSet<String>,
+    /// The title, text digest and canonical URL of each page, by URL
+    contents: HashMap<String, PageContent>,
+    /// Pages that may be a copy of a page on another host
+    copies: Vec<CopyCandidate>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct PageContent {
+    title: Option<String>,
+    /// SHA-256 of the text with its whitespace collapsed, or None when the page has no text
+    text: Option<[u8; 32]>,
+    canonical: Option<String>,
+}
+
+struct CopyCandidate {
+    key: String,
+    url: String,
+    duplicate_of: Option<String>,
+}
+
+/// The site-wide results `SeoAnalyzer::analyze_site_page` checks each page against
+struct Site {
+    documents: HashSet<String>,
+    linked: HashSet<String>,
+    /// Pages on another host with the same content, by page URL
+    duplicates: HashMap<String, Vec<String>>,
+}
+
+impl SiteIndex {
+    fn add(&mut self, key: &str, page: &PageInfo, html_content_types: &HtmlContentTypes) {
+        let document =
+            document_url(&page.url).map_or_else(|| page.url.clone(), |url| url.to_string());
+        self.candidates
+            .push((document != page.url, key.to_string(), document));
+
+        let page_url = document_url(&page.url);
+        self.linked.extend(
+            page.links
+                .iter()
+                .filter_map(|link| document_url(&link.url))
+                .filter(|target| Some(target) != page_url.as_ref())
+                .map(String::from),
+        );
+
+        let text = page
+            .content
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.contents.insert(
+            page.url.clone(),
+            PageContent {
+                title: page.title.clone(),
+                text: (!text.is_empty()).then(|| Sha256::digest(text.as_bytes()).into()),
+                canonical: page.canonical.clone(),
+            },
+        );
+        if page.status_code == Some(200)
+            && html_content_types.contains(page.content_type.as_deref())
+        {
+            self.copies.push(CopyCandidate {
+                key: key.to_string(),
+                url: page.url.clone(),
+                duplicate_of: page.duplicate_of.clone(),
+            });
+        }
+    }
+
+    fn finish(self) -> Site {
+        let documents = Self::one_key_per_document(self.candidates);
+        let duplicates = Self::cross_host_duplicates(&self.contents, &self.copies, &documents);
+        Site {
+            documents,
+            linked: self.linked,
+            duplicates,
+        }
+    }
+
+    /// With `--keep-fragments`, `page#intro` and `page#faq` are crawled as separate pages
+    /// but load the same document. Keep the fragment-less page for each document, or the
+    /// first fragment variant when the plain URL wasn't crawled.
+    fn one_key_per_document(mut candidates: Vec<(bool, String, String)>) -> HashSet<String> {
+        candidates.sort();
+
+        let mut documents: HashMap<String, String> = HashMap::new();
+        for (_, key, document) in candidates {
+            documents.entry(document).or_insert(key);
         }
+        documents.into_values().collect()
     }
+
+    /// Pages whose title and text are identical to a page on another host. Copies that all
+    /// declare the same canonical URL are left alone, since that already tells search engines
+    /// which one to index.
+    fn cross_host_duplicates(
+        contents: &HashMap<String, PageContent>,
+        copies: &[CopyCandidate],
+        documents: &HashSet<String>,
+    ) -> HashMap<String, Vec<String>> {
+        // Byte-identical copies weren't parsed, so they're compared by their original's content
+        let mut groups: HashMap<_, Vec<(&str, &PageContent)>> = HashMap::new();
+        for copy in copies.iter().filter(|copy| documents.contains(&copy.key)) {
+            let content = copy
+                .duplicate_of
+                .as_deref()
+                .and_then(|original| contents.get(original))
+                .or_else(|| contents.get(&copy.url));
+            if let Some(content) = content
+                && let Some(text) = content.text
+            {
+                groups
+                    .entry((content.title.clone(), text))
+                    .or_default()
+                    .push((&copy.url, content));
+            }
+        }
+
+        let mut duplicates: HashMap<String, Vec<String>> = HashMap::new();
+        for copies in groups.values() {
+            let host = |url: &str| {
+                Url::parse(url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            };
+            let hosts: HashSet<_> = copies.iter().map(|(url, _)| host(url)).collect();
+            let canonicals: HashSet<_> = copies
+                .iter()
+                .map(|(_, content)| &content.canonical)
+                .collect();
+            if hosts.len() < 2 || (canonicals.len() == 1 && copies[0].1.canonical.is_some()) {
+                continue;
+            }
+
+            for (url, _) in copies {
+                let mut others: Vec<String> = copies
+                    .iter()
+                    .filter(|(other, _)| host(other) != host(url))
+                    .map(|(other, _)| other.to_string())
+                    .collect();
+                others.sort();
+                duplicates.insert(url.to_string(), others);
+            }
+        }
+        duplicates
+    }
+}
+
+/// The start of a comment on one line, cut to `COMMENT_EXCERPT_CHARS`
+fn excerpt(comment: &str) -> String {
+    let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+    if comment.chars().count() <= COMMENT_EXCERPT_CHARS {
+        return comment;
+    }
+    let cut: String = comment.chars().take(COMMENT_EXCERPT_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn is_compressible_content_type(content_type: Option<&str>) -> bool {
+    let Some(content_type) = content_type else {
+        return false;
+    };
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    mime.starts_with("text/")
+        || mime.ends_with("+xml")
+        || mime.ends_with("+json")
+        || matches!(
+            mime.as_str(),
+            "application/json" | "application/javascript" | "application/xml"
+        )
 }
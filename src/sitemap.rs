@@ -0,0 +1,344 @@
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use scraper::{Html, Selector};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::io::Read;
+use url::Url;
+
+use crate::models::{HtmlContentTypes, IssueSeverity, IssueType, PageInfo, SeoIssue, document_url};
+use crate::page_store::PageStore;
+use crate::rules::PathRules;
+
+/// The sitemap protocol caps a sitemap at 50MB uncompressed, which also bounds gzip bombs
+pub const MAX_SITEMAP_BYTES: u64 = 50 * 1024 * 1024;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// How far `<lastmod>` and `Last-Modified` may drift apart before the sitemap date counts as
+/// wrong; a date-only `<lastmod>` is already up to a day off
+const LASTMOD_TOLERANCE_HOURS: i64 = 24;
+
+static URL_LOC_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("url > loc").expect("sitemap loc selector should be valid"));
+static URL_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("urlset > url").expect("sitemap url selector should be valid"));
+static LOC_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("loc").expect("loc selector should be valid"));
+static LASTMOD_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse("lastmod").expect("lastmod selector should be valid"));
+static SITEMAP_LOC_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse("sitemapindex > sitemap > loc")
+        .expect("sitemap index loc selector should be valid")
+});
+
+/// The URLs listed in a sitemap document: pages from a `<urlset>`, and nested sitemaps from a
+/// `<sitemapindex>`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sitemap {
+    pub page_urls: Vec<String>,
+    pub sitemap_urls: Vec<String>,
+    /// The `<lastmod>` text of each page URL that has one
+    pub lastmods: BTreeMap<String, String>,
+}
+
+/// The conventional sitemap location at the root of the site
+pub fn default_sitemap_url(base_url: &Url) -> Option<String> {
+    base_url.join("/sitemap.xml").ok().map(String::from)
+}
+
+/// Parse a sitemap or sitemap index, keeping document order. The HTML parser reads the XML
+/// well enough for this and decodes entities such as `&amp;`.
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let document = Html::parse_document(xml);
+    let locs = |selector: &Selector| -> Vec<String> {
+        document
+            .select(selector)
+            .map(|loc| loc.text().collect::<String>().trim().to_string())
+            .filter(|loc| !loc.is_empty())
+            .collect()
+    };
+    let text = |element: scraper::ElementRef, selector: &Selector| -> Option<String> {
+        let text = element
+            .select(selector)
+            .next()?
+            .text()
+            .collect::<String>()
+            .trim()
+            .to_string();
+        (!text.is_empty()).then_some(text)
+    };
+
+    Sitemap {
+        page_urls: locs(&URL_LOC_SELECTOR),
+        sitemap_urls: locs(&SITEMAP_LOC_SELECTOR),
+        lastmods: document
+            .select(&URL_SELECTOR)
+            .filter_map(|url| Some((text(url, &LOC_SELECTOR)?, text(url, &LASTMOD_SELECTOR)?)))
+            .collect(),
+    }
+}
+
+/// Flag sitemap `<lastmod>` dates that search engines would learn to distrust: dates that don't
+/// parse or lie in the future, and dates more than a day before (stale) or after (inaccurate)
+/// the page's own `Last-Modified` header. `lastmods` is keyed like `pages`.
+pub fn audit_lastmod(
+    pages: &mut HashMap<String, PageInfo>,
+    lastmods: &HashMap<String, String>,
+    rules: &PathRules,
+) {
+    for (key, page) in pages.iter_mut() {
+        audit_page_lastmod(key, page, lastmods, rules);
+    }
+}
+
+/// `audit_lastmod` for the page stored under `key`
+pub fn audit_page_lastmod(
+    key: &str,
+    page: &mut PageInfo,
+    lastmods: &HashMap<String, String>,
+    rules: &PathRules,
+) {
+    let now = Utc::now();
+    let tolerance = chrono::Duration::hours(LASTMOD_TOLERANCE_HOURS);
+
+    let Some(lastmod) = lastmods.get(key) else {
+        return;
+    };
+    if page.status_code != Some(200) {
+        return;
+    }
+    let rule = rules.for_url(&page.url);
+    let last_modified = page
+        .last_modified
+        .as_deref()
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        .map(|value| value.with_timezone(&Utc));
+
+    let issue = match parse_lastmod(lastmod) {
+        None => Some((
+            IssueType::SitemapLastmodInaccurate,
+            "Sitemap lastmod isn't a W3C date".to_string(),
+            format!("lastmod {lastmod}"),
+        )),
+        Some(date) if date > now + tolerance => Some((
+            IssueType::SitemapLastmodInaccurate,
+            "Sitemap lastmod is in the future".to_string(),
+            format!("lastmod {lastmod}"),
+        )),
+        Some(date) => match last_modified {
+            Some(modified) if modified > date + tolerance => Some((
+                IssueType::SitemapLastmodStale,
+                "Page changed after the lastmod its sitemap lists".to_string(),
+                format!("lastmod {lastmod}, Last-Modified {}", modified.to_rfc3339()),
+            )),
+            Some(modified) if date > modified + tolerance => Some((
+                IssueType::SitemapLastmodInaccurate,
+                "Sitemap lastmod is later than the page's Last-Modified".to_string(),
+                format!("lastmod {lastmod}, Last-Modified {}", modified.to_rfc3339()),
+            )),
+            _ => None,
+        },
+    };
+
+    if let Some((issue_type, message, actual)) = issue
+        && !rule.is_disabled(issue_type)
+    {
+        page.issues
+            .push(SeoIssue::new(IssueSeverity::Warning, issue_type, message).with_actual(actual));
+    }
+}
+
+/// A W3C datetime as sitemaps use it: a full timestamp with a time zone, or a date alone
+/// (`2026-01-15`, `2026-01`, or `2026`), read as midnight UTC at its start
+fn parse_lastmod(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    // RFC 3339 requires seconds, which W3C datetimes may leave out
+    let with_offset = match value.strip_suffix('Z') {
+        Some(local) => format!("{local}+00:00"),
+        None => value.to_string(),
+    };
+    if let Ok(date) = DateTime::parse_from_str(&with_offset, "%Y-%m-%dT%H:%M%:z") {
+        return Some(date.with_timezone(&Utc));
+    }
+
+    let date = match value.len() {
+        10 => NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?,
+        7 => NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d").ok()?,
+        4 => NaiveDate::parse_from_str(&format!("{value}-01-01"), "%Y-%m-%d").ok()?,
+        _ => return None,
+    };
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+/// The XML of a sitemap response. `.xml.gz` sitemaps are usually served as plain gzip files
+/// rather than with `Content-Encoding`, so gzip is detected from the body itself.
+pub fn decode_sitemap_body(body: &[u8]) -> Result<String> {
+    if !body.starts_with(&GZIP_MAGIC) {
+        return Ok(String::from_utf8_lossy(body).into_owned());
+    }
+
+    let mut xml = Vec::new();
+    GzDecoder::new(body)
+        .take(MAX_SITEMAP_BYTES + 1)
+        .read_to_end(&mut xml)
+        .context("Failed to decompress gzipped sitemap")?;
+    if xml.len() as u64 > MAX_SITEMAP_BYTES {
+        bail!("Sitemap is larger than {MAX_SITEMAP_BYTES} bytes uncompressed");
+    }
+    Ok(String::from_utf8_lossy(&xml).into_owned())
+}
+
+/// A sitemap of the crawled pages worth indexing: HTML pages on the start URL's host that
+/// returned 200, one entry per document, with `lastmod` when the server sent `Last-Modified`
+pub fn render_sitemap(
+    start_url: &str,
+    pages: &dyn PageStore,
+    html_content_types: &HtmlContentTypes,
+) -> Result<String> {
+    let mut entries = SitemapEntries::new(start_url);
+    pages.for_each_page(&mut |_, page| {
+        entries.add(page, html_content_types);
+        Ok(())
+    })?;
+    Ok(entries.render())
+}
+
+/// The `<url>` entries of a generated sitemap with their `<lastmod>`, by document URL
+struct SitemapEntries {
+    host: Option<String>,
+    entries: BTreeMap<String, Option<String>>,
+}
+
+impl SitemapEntries {
+    fn new(start_url: &str) -> Self {
+        Self {
+            host: Url::parse(start_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string)),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, page: &PageInfo, html_content_types: &HtmlContentTypes) {
+        let Some(url) = document_url(&page.url) else {
+            return;
+        };
+        if page.status_code == Some(200)
+            && html_content_types.contains(page.content_type.as_deref())
+            && url.host_str() == self.host.as_deref()
+        {
+            let lastmod = self.entries.entry(url.to_string()).or_default();
+            if lastmod.is_none() {
+                *lastmod = page.last_modified.clone();
+            }
+        }
+    }
+
+    fn render(self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for (url, lastmod) in self.entries {
+            xml.push_str("  <url>\n");
+            let _ = writeln!(xml, "    <loc>{}</loc>", escape_xml(&url));
+            if let Some(lastmod) = lastmod {
+                let _ = writeln!(xml, "    <lastmod>{}</lastmod>", escape_xml(&lastmod));
+            }
+            xml.push_str("  </url>\n");
+        }
+        xml.push_str("</urlset>\n");
+        xml
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_sitemap_index_lists_nested_sitemaps() {
+        let sitemap = parse_sitemap(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>https://example.com/pages.xml.gz</loc><lastmod>2026-01-15</lastmod></sitemap>
+  <sitemap><loc>https://example.com/posts.xml</loc></sitemap>
+</sitemapindex>"#,
+        );
+
+        assert!(sitemap.page_urls.is_empty());
+        assert_eq!(
+            sitemap.sitemap_urls,
+            [
+                "https://example.com/pages.xml.gz",
+                "https://example.com/posts.xml"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sitemap_keeps_page_lastmods() {
+        let sitemap = parse_sitemap(
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>https://example.com/</loc><lastmod> 2026-01-15 </lastmod></url>
+  <url><loc>https://example.com/about</loc></url>
+</urlset>"#,
+        );
+
+        assert_eq!(sitemap.page_urls.len(), 2);
+        assert_eq!(
+            sitemap.lastmods,
+            BTreeMap::from([("https://example.com/".to_string(), "2026-01-15".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_parse_lastmod_accepts_w3c_datetimes() {
+        let midnight = |date: &str| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+
+        assert_eq!(parse_lastmod("2026-01-15"), Some(midnight("2026-01-15")));
+        assert_eq!(parse_lastmod("2026-01"), Some(midnight("2026-01-01")));
+        assert_eq!(parse_lastmod("2026"), Some(midnight("2026-01-01")));
+        assert_eq!(
+            parse_lastmod("2026-01-15T10:30+02:00"),
+            Some(midnight("2026-01-15") + chrono::Duration::minutes(8 * 60 + 30))
+        );
+        assert_eq!(
+            parse_lastmod("2026-01-15T10:30Z"),
+            parse_lastmod("2026-01-15T10:30:00.000Z")
+        );
+        assert_eq!(parse_lastmod("15/01/2026"), None);
+        assert_eq!(parse_lastmod("2026-13-01"), None);
+    }
+
+    #[test]
+    fn test_decode_sitemap_body_decompresses_gzip() {
+        let xml = "<urlset><url><loc>https://example.com/</loc></url></urlset>";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        assert_eq!(decode_sitemap_body(&gzipped).unwrap(), xml);
+        assert_eq!(decode_sitemap_body(xml.as_bytes()).unwrap(), xml);
+        assert!(decode_sitemap_body(&GZIP_MAGIC).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use anyhow::{Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::models::{CrawlReport, IssueSeverity};
+use crate::page_store::PageStore;
+
+const HISTORY_FILE: &str = "history.jsonl";
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Summary metrics of a single run, stored one JSON object per line in the state directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub timestamp: String,
+    pub start_url: String,
+    pub total_pages: usize,
+    pub broken_links: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    /// Percentage of crawled pages without any error-level issue
+    pub score: u8,
+}
+
+impl TrendPoint {
+    /// The run's metrics, with the clean pages counted in `pages`, which is `report.pages`
+    /// unless they are kept in a page store
+    pub fn from_report(report: &CrawlReport, pages: &dyn PageStore) -> Result<Self> {
+        let mut clean_pages = 0;
+        let mut total = 0;
+        pages.for_each_page(&mut |_, page| {
+            total += 1;
+            if !page
+                .issues
+                .iter()
+                .any(|issue| issue.severity == IssueSeverity::Error)
+            {
+                clean_pages += 1;
+            }
+            Ok(())
+        })?;
+        let score = match total {
+            0 => 0,
+            total => (clean_pages * 100 + total / 2) / total,
+        };
+
+        Ok(Self {
+            timestamp: report.timestamp.clone(),
+            start_url: report.start_url.clone(),
+            total_pages: report.summary.total_pages,
+            broken_links: report.summary.broken_links,
+            errors: report.summary.errors,
+            warnings: report.summary.warnings,
+            score: score as u8,
+        })
+    }
+}
+
+/// Metrics charted by `scoutly trends`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendMetric {
+    BrokenLinks,
+    Errors,
+    Warnings,
+    Score,
+}
+
+impl TrendMetric {
+    pub const ALL: [Self; 4] = [Self::BrokenLinks, Self::Errors, Self::Warnings, Self::Score];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::BrokenLinks => "Broken links",
+            Self::Errors => "Errors",
+            Self::Warnings => "Warnings",
+            Self::Score => "Health score",
+        }
+    }
+
+    pub fn value(self, point: &TrendPoint) -> usize {
+        match self {
+            Self::BrokenLinks => point.broken_links,
+            Self::Errors => point.errors,
+            Self::Warnings => point.warnings,
+            Self::Score => usize::from(point.score),
+        }
+    }
+}
+
+/// Run history kept in a state directory
+pub struct TrendStore {
+    path: PathBuf,
+}
+
+impl TrendStore {
+    pub fn new(state_dir: &Path) -> Self {
+        Self {
+            path: state_dir.join(HISTORY_FILE),
+        }
+    }
+
+    pub fn record(&self, point: &TrendPoint) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create state directory: {}", dir.display()))?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open run history: {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(point)?)
+            .with_context(|| format!("Failed to write run history: {}", self.path.display()))
+    }
+
+    /// All recorded runs in the order they were recorded; a missing history file means no runs
+    pub fn load(&self) -> Result<Vec<TrendPoint>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => {
+                return Err(error).with_context(|| {
+                    format!("Failed to read run history: {}", self.path.display())
+                });
+            }
+        };
+
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).with_context(|| {
+                    format!(
+                        "Failed to parse run history {} at line {}",
+                        self.path.display(),
+                        index + 1
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Runs for one site: the given start URL, or the site of the most recent run
+pub fn points_for_site(points: &[TrendPoint], url: Option<&str>) -> Vec<TrendPoint> {
+    let Some(url) = url.or_else(|| points.last().map(|point| point.start_url.as_str())) else {
+        return vec![];
+    };
+    let url = url.trim_end_matches('/');
+
+    points
+        .iter()
+        .filter(|point| point.start_url.trim_end_matches('/') == url)
+        .cloned()
+        .collect()
+}
+
+/// One character per value, scaled between the smallest and largest value
+pub fn sparkline(values: &[usize]) -> String {
+    let (Some(min), Some(max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|value| match range {
+            0 => SPARK_LEVELS[0],
+            _ => SPARK_LEVELS[(value - min) * (SPARK_LEVELS.len() - 1) / range],
+        })
+        .collect()
+}
+
+/// Terminal chart of each metric followed by a table of the most recent runs
+pub fn render_text(points: &[TrendPoint], limit: usize) -> String {
+    let Some(latest) = points.last() else {
+        return "No runs recorded yet. Run a scan with --state-dir to start collecting history.\n"
+            .to_string();
+    };
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "{} {} ({} run(s) since {})\n\n",
+        "Trends for".bright_cyan().bold(),
+        latest.start_url,
+        points.len(),
+        short_date(&points[0].timestamp)
+    ));
+
+    for metric in TrendMetric::ALL {
+        let values: Vec<usize> = points.iter().map(|point| metric.value(point)).collect();
+        let latest_value = metric.value(latest);
+        let change = match points.len() {
+            1 => String::new(),
+            len => format_change(latest_value, metric.value(&points[len - 2])),
+        };
+        output.push_str(&format!(
+            "{:<14} {}  latest {}{}\n",
+            metric.label().bright_white().bold(),
+            sparkline(&values),
+            latest_value,
+            change
+        ));
+    }
+
+    output.push_str(&format!(
+        "\n{:<22} {:>6} {:>7} {:>7} {:>9} {:>6}\n",
+        "Run", "Pages", "Broken", "Errors", "Warnings", "Score"
+    ));
+    for point in points.iter().skip(points.len().saturating_sub(limit)) {
+        output.push_str(&format!(
+            "{:<22} {:>6} {:>7} {:>7} {:>9} {:>6}\n",
+            short_date(&point.timestamp),
+            point.total_pages,
+            point.broken_links,
+            point.errors,
+            point.warnings,
+            point.score
+        ));
+    }
+
+    output
+}
+
+/// Standalone HTML page with a line chart per metric
+pub fn render_html(points: &[TrendPoint]) -> String {
+    let site = points
+        .last()
+        .map(|point| escape_html(&point.start_url))
+        .unwrap_or_default();
+    let charts: String = TrendMetric::ALL
+        .iter()
+        .map(|metric| {
+            let values: Vec<usize> = points.iter().map(|point| metric.value(point)).collect();
+            format!(
+                "<section>\n<h2>{}</h2>\n{}\n</section>\n",
+                metric.label(),
+                svg_chart(&values)
+            )
+        })
+        .collect();
+    let rows: String = points
+        .iter()
+        .rev()
+        .map(|point| {
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&point.timestamp),
+                point.total_pages,
+                point.broken_links,
+                point.errors,
+                point.warnings,
+                point.score
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Scoutly trends for {site}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2rem; color: #222; }}
+section {{ margin-bottom: 1.5rem; }}
+svg {{ background: #f7f7f7; }}
+table {{ border-collapse: collapse; }}
+th, td {{ padding: 0.25rem 0.75rem; border-bottom: 1px solid #ddd; text-align: right; }}
+th:first-child, td:first-child {{ text-align: left; }}
+</style>
+</head>
+<body>
+<h1>Trends for {site}</h1>
+{charts}<h2>Runs</h2>
+<table>
+<tr><th>Run</th><th>Pages</th><th>Broken links</th><th>Errors</th><th>Warnings</th><th>Score</th></tr>
+{rows}</table>
+</body>
+</html>
+"#
+    )
+}
+
+fn svg_chart(values: &[usize]) -> String {
+    const WIDTH: usize = 600;
+    const HEIGHT: usize = 120;
+    const PADDING: usize = 10;
+
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    let step = match values.len() {
+        0 | 1 => 0,
+        len => (WIDTH - 2 * PADDING) / (len - 1),
+    };
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let x = PADDING + index * step;
+            let y = HEIGHT - PADDING - value * (HEIGHT - 2 * PADDING) / max;
+            format!("{x},{y}")
+        })
+        .collect();
+
+    format!(
+        r##"<svg width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}"><polyline fill="none" stroke="#2b7bb9" stroke-width="2" points="{}"/></svg>"##,
+        points.join(" ")
+    )
+}
+
+fn format_change(latest: usize, previous: usize) -> String {
+    match latest.cmp(&previous) {
+        std::cmp::Ordering::Equal => " (no change)".to_string(),
+        std::cmp::Ordering::Greater => format!(" (+{})", latest - previous),
+        std::cmp::Ordering::Less => format!(" (-{})", previous - latest),
+    }
+}
+
+fn short_date(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
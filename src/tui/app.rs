@@ -2,7 +2,7 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::time::{Duration, Instant};
 
 use crate::config::RuntimeOptions;
-use crate::models::{CrawlReport, IssueSeverity, PageInfo};
+use crate::models::{CrawlReport, HtmlContentTypes, IssueSeverity, PageInfo};
 use crate::runtime::{ProgressSnapshot, RunEvent, RunStage};
 use crate::update::UpdateNotice;
 
@@ -92,6 +92,8 @@ pub struct App {
     pub url_input: String,
     pub depth: usize,
     pub max_pages: usize,
+    /// Pages of other content types are titled by their file name
+    pub html_content_types: HtmlContentTypes,
     pub mode: UiMode,
     pub progress: ProgressSnapshot,
     pub report: Option<CrawlReport>,
@@ -128,6 +130,7 @@ impl App {
             url_input: initial_url.clone().unwrap_or_default(),
             depth: runtime.depth,
             max_pages: runtime.max_pages,
+            html_content_types: runtime.resolved_html_content_types(),
             mode,
             progress: ProgressSnapshot::new(RunStage::LoadingConfig, message),
             report: None,
@@ -420,7 +423,10 @@ impl App {
         }
 
         let in_url = page.url.to_lowercase().contains(query);
-        let in_title = page.display_title().to_lowercase().contains(query);
+        let in_title = page
+            .display_title(&self.html_content_types)
+            .to_lowercase()
+            .contains(query);
         let in_issues = page
             .issues
             .iter()
@@ -505,6 +511,8 @@ impl App {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::LinkCheckScope;
+    use crate::limits::ByteSize;
     use crate::models::{CrawlSummary, IssueType, OpenGraphTags, SeoIssue};
     use std::collections::HashMap;
 
@@ -513,14 +521,35 @@ mod tests {
             url: url.to_string(),
             status_code: Some(200),
             content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
             title: Some(url.to_string()),
             meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
             h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
             links: vec![],
             images: vec![],
+            weight: Default::default(),
             open_graph: OpenGraphTags::default(),
+            content: Default::default(),
             issues,
+            omitted_issues: vec![],
             crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
         }
     }
 
@@ -529,26 +558,102 @@ mod tests {
             severity,
             issue_type: IssueType::BrokenLink,
             message: message.to_string(),
+            offending_url: None,
+            expected: None,
+            actual: None,
+            help_url: None,
+            check: None,
+            fingerprint: None,
         }
     }
 
     fn app_with_report() -> App {
         let runtime = RuntimeOptions {
             url: Some("https://example.com".to_string()),
+            additional_urls: vec![],
             depth: 5,
             max_pages: 10,
             output: None,
+            json_style: Default::default(),
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
+            screenshot_browser: None,
             cli: false,
             external: false,
             verbose: false,
             ignore_redirects: false,
+            check_links: LinkCheckScope::All,
+            never_check_domains: vec![],
+            always_check_domains: vec![],
+            html_content_types: None,
             keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            sort_query: false,
+            ignore_params: vec![],
+            respect_nofollow: false,
             rate_limit: None,
             concurrency: 5,
+            timeout: 30,
+            link_timeout: 10,
+            insecure: false,
+            ca_cert: None,
             respect_robots_txt: true,
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            robots_agent: "scoutly".to_string(),
+            cookies: vec![],
+            cookies_file: None,
+            login: None,
+            audit_vary: false,
+            trace_redirects: false,
+            redirect_chain_limit: 1,
+            find_contacts: false,
+            scan_secrets: false,
+            heading_outline: false,
+            secret_patterns: Default::default(),
+            use_sitemap: false,
+            max_sitemaps: 50,
+            render: Default::default(),
+            gentle: false,
+            auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: ByteSize(10_000_000),
+            max_duration: None,
+            retries: 0,
+            retry_backoff_ms: 500,
+            retry_jitter: true,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: 10,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: Default::default(),
+            budget: Default::default(),
+            comment_markers: None,
+            crux_api_key: None,
+            rule_overrides: Default::default(),
+            assertions: vec![],
             tui: false,
-            config: None,
+            config: vec![],
         };
 
         let mut pages = HashMap::new();
@@ -570,6 +675,7 @@ mod tests {
         let report = CrawlReport {
             start_url: runtime.url.clone().unwrap(),
             pages,
+            inlinks: Default::default(),
             summary: CrawlSummary {
                 total_pages: 2,
                 total_links: 0,
@@ -577,8 +683,16 @@ mod tests {
                 errors: 1,
                 warnings: 1,
                 infos: 0,
+                fragment_navigation_pages: 0,
+                images: Default::default(),
+                downloads: Default::default(),
+                performance: Default::default(),
+                third_party_frames: Default::default(),
             },
             timestamp: "2026-04-02T00:00:00Z".to_string(),
+            limit_reached: None,
+            explain: Default::default(),
+            errors: Default::default(),
         };
 
         let mut app = App::new(runtime);
@@ -591,20 +705,90 @@ mod tests {
     fn empty_initial_url_starts_in_url_input_mode() {
         let app = App::new(RuntimeOptions {
             url: None,
+            additional_urls: vec![],
             depth: 5,
             max_pages: 10,
             output: None,
+            json_style: Default::default(),
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
+            screenshot_browser: None,
             cli: false,
             external: false,
             verbose: false,
             ignore_redirects: false,
+            check_links: LinkCheckScope::All,
+            never_check_domains: vec![],
+            always_check_domains: vec![],
+            html_content_types: None,
             keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            sort_query: false,
+            ignore_params: vec![],
+            respect_nofollow: false,
             rate_limit: None,
             concurrency: 5,
+            timeout: 30,
+            link_timeout: 10,
+            insecure: false,
+            ca_cert: None,
             respect_robots_txt: true,
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            robots_agent: "scoutly".to_string(),
+            cookies: vec![],
+            cookies_file: None,
+            login: None,
+            audit_vary: false,
+            trace_redirects: false,
+            redirect_chain_limit: 1,
+            find_contacts: false,
+            scan_secrets: false,
+            heading_outline: false,
+            secret_patterns: Default::default(),
+            use_sitemap: false,
+            max_sitemaps: 50,
+            render: Default::default(),
+            gentle: false,
+            auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: ByteSize(10_000_000),
+            max_duration: None,
+            retries: 0,
+            retry_backoff_ms: 500,
+            retry_jitter: true,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: 10,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: Default::default(),
+            budget: Default::default(),
+            comment_markers: None,
+            crux_api_key: None,
+            rule_overrides: Default::default(),
+            assertions: vec![],
             tui: false,
-            config: None,
+            config: vec![],
         });
 
         assert_eq!(app.mode, UiMode::UrlInput);
@@ -616,20 +800,90 @@ mod tests {
     fn url_input_enter_starts_scan() {
         let mut app = App::new(RuntimeOptions {
             url: None,
+            additional_urls: vec![],
             depth: 5,
             max_pages: 10,
             output: None,
+            json_style: Default::default(),
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
+            screenshot_browser: None,
             cli: false,
             external: false,
             verbose: false,
             ignore_redirects: false,
+            check_links: LinkCheckScope::All,
+            never_check_domains: vec![],
+            always_check_domains: vec![],
+            html_content_types: None,
             keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            sort_query: false,
+            ignore_params: vec![],
+            respect_nofollow: false,
             rate_limit: None,
             concurrency: 5,
+            timeout: 30,
+            link_timeout: 10,
+            insecure: false,
+            ca_cert: None,
             respect_robots_txt: true,
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            robots_agent: "scoutly".to_string(),
+            cookies: vec![],
+            cookies_file: None,
+            login: None,
+            audit_vary: false,
+            trace_redirects: false,
+            redirect_chain_limit: 1,
+            find_contacts: false,
+            scan_secrets: false,
+            heading_outline: false,
+            secret_patterns: Default::default(),
+            use_sitemap: false,
+            max_sitemaps: 50,
+            render: Default::default(),
+            gentle: false,
+            auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: ByteSize(10_000_000),
+            max_duration: None,
+            retries: 0,
+            retry_backoff_ms: 500,
+            retry_jitter: true,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: 10,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: Default::default(),
+            budget: Default::default(),
+            comment_markers: None,
+            crux_api_key: None,
+            rule_overrides: Default::default(),
+            assertions: vec![],
             tui: false,
-            config: None,
+            config: vec![],
         });
         app.url_input = "https://example.com".to_string();
 
@@ -647,20 +901,90 @@ mod tests {
     fn initial_url_marks_scan_as_active() {
         let app = App::new(RuntimeOptions {
             url: Some("https://example.com".to_string()),
+            additional_urls: vec![],
             depth: 5,
             max_pages: 10,
             output: None,
+            json_style: Default::default(),
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
+            screenshot_browser: None,
             cli: false,
             external: false,
             verbose: false,
             ignore_redirects: false,
+            check_links: LinkCheckScope::All,
+            never_check_domains: vec![],
+            always_check_domains: vec![],
+            html_content_types: None,
             keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            sort_query: false,
+            ignore_params: vec![],
+            respect_nofollow: false,
             rate_limit: None,
             concurrency: 5,
+            timeout: 30,
+            link_timeout: 10,
+            insecure: false,
+            ca_cert: None,
             respect_robots_txt: true,
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            robots_agent: "scoutly".to_string(),
+            cookies: vec![],
+            cookies_file: None,
+            login: None,
+            audit_vary: false,
+            trace_redirects: false,
+            redirect_chain_limit: 1,
+            find_contacts: false,
+            scan_secrets: false,
+            heading_outline: false,
+            secret_patterns: Default::default(),
+            use_sitemap: false,
+            max_sitemaps: 50,
+            render: Default::default(),
+            gentle: false,
+            auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: ByteSize(10_000_000),
+            max_duration: None,
+            retries: 0,
+            retry_backoff_ms: 500,
+            retry_jitter: true,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: 10,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: Default::default(),
+            budget: Default::default(),
+            comment_markers: None,
+            crux_api_key: None,
+            rule_overrides: Default::default(),
+            assertions: vec![],
             tui: false,
-            config: None,
+            config: vec![],
         });
 
         assert_eq!(app.mode, UiMode::Normal);
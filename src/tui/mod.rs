@@ -66,11 +66,16 @@ async fn run_scan(
     let mut runtime = template.clone();
     runtime.url = Some(url);
 
-    let result = execute_scan(&runtime, Some(event_sender.clone()), false).await;
-    if let Err(error) = &result {
-        let _ = event_sender.send(RunEvent::Error(error.to_string()));
+    match execute_scan(&runtime, Some(event_sender.clone()), false).await {
+        Ok(report) => {
+            let _ = event_sender.send(RunEvent::ReportReady(report));
+            Ok(())
+        }
+        Err(error) => {
+            let _ = event_sender.send(RunEvent::Error(error.to_string()));
+            Err(error)
+        }
     }
-    result.map(|_| ())
 }
 
 struct UiOutcome {
@@ -301,7 +301,7 @@ fn render_pages_table(frame: &mut Frame, app: &App, pages: &[&PageInfo], area: R
                     .unwrap_or_else(|| "-".to_string()),
             ),
             Cell::from(issue_summary(page)),
-            Cell::from(trimmed(&page.display_title(), 24)),
+            Cell::from(trimmed(&page.display_title(&app.html_content_types), 24)),
             Cell::from(trimmed(&page.url, 48)),
         ])
     });
@@ -366,7 +366,7 @@ fn render_detail_pane(frame: &mut Frame, app: &App, pages: &[&PageInfo], area: R
         ]),
         Line::from(vec![
             Span::styled("Title: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(page.display_title()),
+            Span::raw(page.display_title(&app.html_content_types)),
         ]),
         Line::from(vec![
             Span::styled("Status: ", Style::default().add_modifier(Modifier::BOLD)),
@@ -380,6 +380,10 @@ fn render_detail_pane(frame: &mut Frame, app: &App, pages: &[&PageInfo], area: R
             Span::styled("Depth: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(page.crawl_depth.to_string()),
         ]),
+        Line::from(vec![
+            Span::styled("Found on: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(page.source.describe()),
+        ]),
         Line::from(vec![
             Span::styled("Issues: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(page.issues.len().to_string()),
@@ -411,6 +415,15 @@ fn render_detail_pane(frame: &mut Frame, app: &App, pages: &[&PageInfo], area: R
                 Span::raw(issue.message.clone()),
             ]));
         }
+        for omitted in &page.omitted_issues {
+            lines.push(Line::styled(
+                format!(
+                    "... and {} more {:?} issue(s)",
+                    omitted.count, omitted.issue_type
+                ),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
     }
 
     let details = Paragraph::new(Text::from(lines))
@@ -629,6 +642,7 @@ fn trimmed(value: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::limits::ByteSize;
     use crate::models::{CrawlReport, CrawlSummary, IssueType, OpenGraphTags, SeoIssue};
     use crate::runtime::ProgressSnapshot;
     use ratatui::{Terminal, backend::TestBackend};
@@ -639,18 +653,45 @@ mod tests {
             url: "https://example.com/about".to_string(),
             status_code: Some(200),
             content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
             title: Some("About".to_string()),
             meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
             h1_tags: vec!["About".to_string()],
+            outline: vec![],
+            aliases: vec![],
             links: vec![],
             images: vec![],
+            weight: Default::default(),
             open_graph: OpenGraphTags::default(),
+            content: Default::default(),
             issues: vec![SeoIssue {
                 severity: IssueSeverity::Warning,
                 issue_type: IssueType::MissingMetaDescription,
                 message: "Missing meta description".to_string(),
+                offending_url: None,
+                expected: None,
+                actual: None,
+                help_url: None,
+                check: None,
+                fingerprint: None,
             }],
             crawl_depth: 1,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+            omitted_issues: vec![],
         }
     }
 
@@ -662,21 +703,45 @@ mod tests {
                 severity: IssueSeverity::Info,
                 issue_type: IssueType::Redirect,
                 message: "redirected".to_string(),
+                offending_url: None,
+                expected: None,
+                actual: None,
+                help_url: None,
+                check: None,
+                fingerprint: None,
             },
             SeoIssue {
                 severity: IssueSeverity::Warning,
                 issue_type: IssueType::MissingMetaDescription,
                 message: "missing description".to_string(),
+                offending_url: None,
+                expected: None,
+                actual: None,
+                help_url: None,
+                check: None,
+                fingerprint: None,
             },
             SeoIssue {
                 severity: IssueSeverity::Error,
                 issue_type: IssueType::BrokenLink,
                 message: "broken link".to_string(),
+                offending_url: None,
+                expected: None,
+                actual: None,
+                help_url: None,
+                check: None,
+                fingerprint: None,
             },
             SeoIssue {
                 severity: IssueSeverity::Info,
                 issue_type: IssueType::Redirect,
                 message: "another redirect".to_string(),
+                offending_url: None,
+                expected: None,
+                actual: None,
+                help_url: None,
+                check: None,
+                fingerprint: None,
             },
         ];
 
@@ -700,20 +765,90 @@ mod tests {
     fn render_outputs_core_labels_to_test_backend() {
         let runtime = crate::config::RuntimeOptions {
             url: Some("https://example.com".to_string()),
+            additional_urls: vec![],
             depth: 2,
             max_pages: 10,
             output: None,
+            json_style: Default::default(),
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
+            screenshot_browser: None,
             cli: false,
             external: false,
             verbose: false,
             ignore_redirects: false,
+            check_links: crate::cli::LinkCheckScope::All,
+            never_check_domains: vec![],
+            always_check_domains: vec![],
+            html_content_types: None,
             keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            sort_query: false,
+            ignore_params: vec![],
+            respect_nofollow: false,
             rate_limit: None,
             concurrency: 5,
+            timeout: 30,
+            link_timeout: 10,
+            insecure: false,
+            ca_cert: None,
             respect_robots_txt: true,
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            robots_agent: "scoutly".to_string(),
+            cookies: vec![],
+            cookies_file: None,
+            login: None,
+            audit_vary: false,
+            trace_redirects: false,
+            redirect_chain_limit: 1,
+            find_contacts: false,
+            scan_secrets: false,
+            heading_outline: false,
+            secret_patterns: Default::default(),
+            use_sitemap: false,
+            max_sitemaps: 50,
+            render: Default::default(),
+            gentle: false,
+            auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: ByteSize(10_000_000),
+            max_duration: None,
+            retries: 0,
+            retry_backoff_ms: 500,
+            retry_jitter: true,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: 10,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: Default::default(),
+            budget: Default::default(),
+            comment_markers: None,
+            crux_api_key: None,
+            rule_overrides: Default::default(),
+            assertions: vec![],
             tui: false,
-            config: None,
+            config: vec![],
         };
         let mut app = App::new(runtime);
         let page = sample_page();
@@ -723,6 +858,7 @@ mod tests {
         app.report = Some(CrawlReport {
             start_url: "https://example.com".to_string(),
             pages,
+            inlinks: Default::default(),
             summary: CrawlSummary {
                 total_pages: 1,
                 total_links: 2,
@@ -730,8 +866,16 @@ mod tests {
                 errors: 0,
                 warnings: 1,
                 infos: 0,
+                fragment_navigation_pages: 0,
+                images: Default::default(),
+                downloads: Default::default(),
+                performance: Default::default(),
+                third_party_frames: Default::default(),
             },
             timestamp: "2026-04-02T00:00:00Z".to_string(),
+            limit_reached: None,
+            explain: Default::default(),
+            errors: Default::default(),
         });
         app.scan_in_progress = false;
         app.scan_started_at = None;
@@ -757,20 +901,90 @@ mod tests {
     fn render_shows_explicit_loading_state_while_scan_is_running() {
         let runtime = crate::config::RuntimeOptions {
             url: Some("https://example.com".to_string()),
+            additional_urls: vec![],
             depth: 2,
             max_pages: 10,
             output: None,
+            json_style: Default::default(),
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
+            screenshot_browser: None,
             cli: false,
             external: false,
             verbose: false,
             ignore_redirects: false,
+            check_links: crate::cli::LinkCheckScope::All,
+            never_check_domains: vec![],
+            always_check_domains: vec![],
+            html_content_types: None,
             keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            sort_query: false,
+            ignore_params: vec![],
+            respect_nofollow: false,
             rate_limit: None,
             concurrency: 5,
+            timeout: 30,
+            link_timeout: 10,
+            insecure: false,
+            ca_cert: None,
             respect_robots_txt: true,
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            robots_agent: "scoutly".to_string(),
+            cookies: vec![],
+            cookies_file: None,
+            login: None,
+            audit_vary: false,
+            trace_redirects: false,
+            redirect_chain_limit: 1,
+            find_contacts: false,
+            scan_secrets: false,
+            heading_outline: false,
+            secret_patterns: Default::default(),
+            use_sitemap: false,
+            max_sitemaps: 50,
+            render: Default::default(),
+            gentle: false,
+            auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: ByteSize(10_000_000),
+            max_duration: None,
+            retries: 0,
+            retry_backoff_ms: 500,
+            retry_jitter: true,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: 10,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: Default::default(),
+            budget: Default::default(),
+            comment_markers: None,
+            crux_api_key: None,
+            rule_overrides: Default::default(),
+            assertions: vec![],
             tui: false,
-            config: None,
+            config: vec![],
         };
         let mut app = App::new(runtime);
         app.progress = ProgressSnapshot::new(
@@ -805,20 +1019,90 @@ mod tests {
     fn render_footer_shows_update_notice_when_available() {
         let runtime = crate::config::RuntimeOptions {
             url: None,
+            additional_urls: vec![],
             depth: 2,
             max_pages: 10,
             output: None,
+            json_style: Default::default(),
             save: None,
+            save_dir: None,
+            result_file: None,
+            write_baseline: None,
+            emit_sitemap: None,
+            outline_dir: None,
+            resume: None,
+            cache_dir: None,
+            page_store: None,
+            save_html: None,
+            explain: Vec::new(),
+            baseline: None,
+            state_dir: None,
+            screenshots: None,
+            screenshot_browser: None,
             cli: false,
             external: false,
             verbose: false,
             ignore_redirects: false,
+            check_links: crate::cli::LinkCheckScope::All,
+            never_check_domains: vec![],
+            always_check_domains: vec![],
+            html_content_types: None,
             keep_fragments: false,
+            include: vec![],
+            exclude: vec![],
+            strip_tracking_params: false,
+            sort_query: false,
+            ignore_params: vec![],
+            respect_nofollow: false,
             rate_limit: None,
             concurrency: 5,
+            timeout: 30,
+            link_timeout: 10,
+            insecure: false,
+            ca_cert: None,
             respect_robots_txt: true,
+            respect_robots_for_links: false,
+            audit_tracking_links: false,
+            block_private_ips: false,
+            robots_agent: "scoutly".to_string(),
+            cookies: vec![],
+            cookies_file: None,
+            login: None,
+            audit_vary: false,
+            trace_redirects: false,
+            redirect_chain_limit: 1,
+            find_contacts: false,
+            scan_secrets: false,
+            heading_outline: false,
+            secret_patterns: Default::default(),
+            use_sitemap: false,
+            max_sitemaps: 50,
+            render: Default::default(),
+            gentle: false,
+            auto_throttle: false,
+            max_requests: None,
+            max_bandwidth: None,
+            max_body_bytes: ByteSize(10_000_000),
+            max_duration: None,
+            retries: 0,
+            retry_backoff_ms: 500,
+            retry_jitter: true,
+            external_concurrency: None,
+            external_rate_limit: None,
+            external_retries: None,
+            external_timeout: 10,
+            external_check_sample: None,
+            max_issues_per_type: None,
+            max_pages_shown: None,
+            scripts: vec![],
+            progress: Default::default(),
+            budget: Default::default(),
+            comment_markers: None,
+            crux_api_key: None,
+            rule_overrides: Default::default(),
+            assertions: vec![],
             tui: false,
-            config: None,
+            config: vec![],
         };
         let mut app = App::new(runtime);
         app.update_notice = Some(crate::update::UpdateNotice {
@@ -0,0 +1,402 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cli::{CrawlPreset, OutputFormat};
+use crate::config::Config;
+use crate::login::LoginConfig;
+
+const DEFAULT_CONFIG_PATH: &str = "scoutly.toml";
+
+/// How the user wants to look at results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputChoice {
+    Tui,
+    Text,
+    Json,
+}
+
+/// How the crawl gets past the site's login, if it has one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AuthChoice {
+    None,
+    Cookies,
+    CookiesFile,
+    Form,
+}
+
+/// Credentials the crawl signs in with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WizardAuth {
+    /// Names of session cookies whose values are read from environment variables, so the
+    /// session never ends up in the config file
+    Cookies(Vec<String>),
+    /// Netscape-format cookies.txt file
+    CookiesFile(String),
+    /// Login form submitted before crawling
+    Login(LoginConfig),
+}
+
+/// Answers collected by the wizard
+#[derive(Debug, Clone, PartialEq)]
+pub struct WizardAnswers {
+    pub url: String,
+    pub preset: CrawlPreset,
+    pub output: Option<OutputFormat>,
+    pub save: Option<String>,
+    pub auth: Option<WizardAuth>,
+    pub config_path: PathBuf,
+}
+
+impl WizardAnswers {
+    pub fn to_config(&self) -> Config {
+        Config {
+            url: Some(self.url.clone()),
+            preset: Some(self.preset),
+            output: self.output,
+            cli: self.output.map(|_| true),
+            save: self.save.clone(),
+            cookies: match &self.auth {
+                Some(WizardAuth::Cookies(names)) => Some(cookie_references(names)),
+                _ => None,
+            },
+            cookies_file: match &self.auth {
+                Some(WizardAuth::CookiesFile(path)) => Some(path.clone()),
+                _ => None,
+            },
+            login: match &self.auth {
+                Some(WizardAuth::Login(login)) => Some(login.clone()),
+                _ => None,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// The command line that runs the same scan without a config file. A login form can only be
+    /// set in the config file, so it is left out.
+    pub fn equivalent_command(&self) -> String {
+        let mut parts = vec!["scoutly".to_string(), shell_quote(&self.url)];
+        parts.push(format!("--preset {}", value_name(self.preset)));
+        match self.output {
+            Some(OutputFormat::Text) => parts.push("--cli".to_string()),
+            Some(OutputFormat::Json) => parts.push("--output json".to_string()),
+            Some(OutputFormat::Jsonl) => parts.push("--output jsonl".to_string()),
+            None => {}
+        }
+        if let Some(save) = &self.save {
+            parts.push(format!("--save {}", shell_quote(save)));
+        }
+        match &self.auth {
+            Some(WizardAuth::Cookies(names)) => {
+                for cookie in cookie_references(names) {
+                    parts.push(format!("--cookie {}", shell_quote(&cookie)));
+                }
+            }
+            Some(WizardAuth::CookiesFile(path)) => {
+                parts.push(format!("--cookies-file {}", shell_quote(path)));
+            }
+            Some(WizardAuth::Login(_)) | None => {}
+        }
+        parts.join(" ")
+    }
+}
+
+/// Ask the setup questions, write the config file, and print how to run the scan
+pub fn run<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> Result<WizardAnswers> {
+    writeln!(output, "Scoutly setup")?;
+    writeln!(
+        output,
+        "Press Enter to accept the default shown in brackets."
+    )?;
+    writeln!(output)?;
+
+    let url = loop {
+        let answer = ask(input, output, "Website URL to crawl", None)?;
+        if answer.starts_with("http://") || answer.starts_with("https://") {
+            break answer;
+        }
+        writeln!(output, "URL must start with http:// or https://")?;
+    };
+
+    let preset = ask_choice(
+        input,
+        output,
+        "Crawl scale (quick, standard, deep)",
+        CrawlPreset::Standard,
+    )?;
+    let output_choice = ask_choice(
+        input,
+        output,
+        "How do you want to view results (tui, text, json)",
+        OutputChoice::Tui,
+    )?;
+    let save = ask(
+        input,
+        output,
+        "Save the report to a JSON file (leave blank to skip)",
+        Some(""),
+    )?;
+    let auth = ask_auth(input, output)?;
+    let config_path = PathBuf::from(ask(
+        input,
+        output,
+        "Where should the config file be written",
+        Some(DEFAULT_CONFIG_PATH),
+    )?);
+
+    if config_path.exists() {
+        let overwrite = ask(
+            input,
+            output,
+            &format!(
+                "{} already exists. Overwrite it? (y/N)",
+                config_path.display()
+            ),
+            Some(""),
+        )?;
+        if !overwrite.eq_ignore_ascii_case("y") && !overwrite.eq_ignore_ascii_case("yes") {
+            anyhow::bail!(
+                "Setup cancelled; {} was left unchanged",
+                config_path.display()
+            );
+        }
+    }
+
+    let answers = WizardAnswers {
+        url,
+        preset,
+        output: match output_choice {
+            OutputChoice::Tui => None,
+            OutputChoice::Text => Some(OutputFormat::Text),
+            OutputChoice::Json => Some(OutputFormat::Json),
+        },
+        save: Some(save).filter(|save| !save.is_empty()),
+        auth,
+        config_path,
+    };
+
+    write_config(&answers.to_config(), &answers.config_path)?;
+
+    writeln!(output)?;
+    writeln!(
+        output,
+        "Config written to {}",
+        answers.config_path.display()
+    )?;
+    writeln!(output, "Run it with:")?;
+    writeln!(
+        output,
+        "  scoutly --config {}",
+        shell_quote(&answers.config_path.display().to_string())
+    )?;
+    match &answers.auth {
+        Some(WizardAuth::Cookies(names)) => {
+            writeln!(
+                output,
+                "Set the cookie values from your browser in the environment first:"
+            )?;
+            for name in names {
+                writeln!(output, "  export {}=...", cookie_env_var(name))?;
+            }
+        }
+        Some(WizardAuth::CookiesFile(path)) => writeln!(
+            output,
+            "{path} holds a live session; keep it out of version control."
+        )?,
+        _ => {}
+    }
+    // Without the config file the crawl would skip the login form
+    if !matches!(answers.auth, Some(WizardAuth::Login(_))) {
+        writeln!(output, "Or without the config file:")?;
+        writeln!(output, "  {}", answers.equivalent_command())?;
+    }
+
+    Ok(answers)
+}
+
+/// Ask whether the site needs a login and collect what the crawl signs in with
+fn ask_auth<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> Result<Option<WizardAuth>> {
+    let choice = ask_choice(
+        input,
+        output,
+        "Does the site need a login (none, cookies, cookies-file, form)",
+        AuthChoice::None,
+    )?;
+    Ok(match choice {
+        AuthChoice::None => None,
+        AuthChoice::Cookies => {
+            let names = ask_cookie_names(input, output)?;
+            Some(WizardAuth::Cookies(names))
+        }
+        AuthChoice::CookiesFile => Some(WizardAuth::CookiesFile(ask(
+            input,
+            output,
+            "Path to the cookies.txt file",
+            None,
+        )?)),
+        AuthChoice::Form => {
+            let url = ask(input, output, "Login page URL", None)?;
+            let fields = ask_pairs(
+                input,
+                output,
+                "Form fields as name=value separated by spaces (write ${NAME} to read a password from the environment)",
+                ' ',
+            )?
+            .into_iter()
+            .filter_map(|field| {
+                let (name, value) = field.split_once('=')?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect();
+            let success_selector = ask(
+                input,
+                output,
+                "CSS selector that only matches once logged in",
+                None,
+            )?;
+            Some(WizardAuth::Login(LoginConfig {
+                url,
+                fields,
+                success_selector,
+            }))
+        }
+    })
+}
+
+/// Ask for the names of the session cookies, asking again until none of them carries a value
+fn ask_cookie_names<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> Result<Vec<String>> {
+    loop {
+        let answer = ask(
+            input,
+            output,
+            "Names of the session cookies, separated by spaces (their values are read from the environment)",
+            None,
+        )?;
+        let names: Vec<String> = answer.split_whitespace().map(str::to_string).collect();
+        match names.iter().find(|name| name.contains(['=', ';'])) {
+            Some(name) => writeln!(
+                output,
+                "Expected a cookie name without its value, got: {name}"
+            )?,
+            None => return Ok(names),
+        }
+    }
+}
+
+/// `name=${VAR}` for each cookie, so the config and command refer to the session instead of
+/// holding it
+fn cookie_references(names: &[String]) -> Vec<String> {
+    names
+        .iter()
+        .map(|name| format!("{name}=${{{}}}", cookie_env_var(name)))
+        .collect()
+}
+
+/// The environment variable a cookie's value is read from, e.g. `SCOUTLY_COOKIE_SESSION_ID`
+fn cookie_env_var(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("SCOUTLY_COOKIE_{name}")
+}
+
+/// Ask for `name=value` pairs split on `separator`, asking again until every pair has a name
+fn ask_pairs<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    question: &str,
+    separator: char,
+) -> Result<Vec<String>> {
+    loop {
+        let answer = ask(input, output, question, None)?;
+        let pairs: Vec<String> = answer
+            .split(separator)
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(str::to_string)
+            .collect();
+        match pairs
+            .iter()
+            .find(|pair| pair.split_once('=').is_none_or(|(name, _)| name.is_empty()))
+        {
+            Some(pair) => writeln!(output, "Expected name=value, got: {pair}")?,
+            None => return Ok(pairs),
+        }
+    }
+}
+
+fn write_config(config: &Config, path: &Path) -> Result<()> {
+    let contents = toml::to_string(config).context("Failed to serialize config")?;
+    fs::write(path, contents)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))
+}
+
+fn ask<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    question: &str,
+    default: Option<&str>,
+) -> Result<String> {
+    loop {
+        match default {
+            Some(default) if !default.is_empty() => write!(output, "{question} [{default}]: ")?,
+            _ => write!(output, "{question}: ")?,
+        }
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            anyhow::bail!("Setup cancelled: input ended before all questions were answered");
+        }
+
+        let answer = line.trim();
+        match (answer.is_empty(), default) {
+            (false, _) => return Ok(answer.to_string()),
+            (true, Some(default)) => return Ok(default.to_string()),
+            (true, None) => writeln!(output, "An answer is required.")?,
+        }
+    }
+}
+
+fn ask_choice<T: ValueEnum, R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    question: &str,
+    default: T,
+) -> Result<T> {
+    let default_name = value_name(default.clone());
+    loop {
+        let answer = ask(input, output, question, Some(&default_name))?;
+        match T::from_str(&answer, true) {
+            Ok(choice) => return Ok(choice),
+            Err(_) => writeln!(output, "Unknown choice: {answer}")?,
+        }
+    }
+}
+
+fn value_name<T: ValueEnum>(value: T) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn shell_quote(value: &str) -> String {
+    let is_plain = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c));
+    if is_plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
@@ -0,0 +1,118 @@
+use clap::Parser;
+use scoutly::cli::{Cli, Command};
+use scoutly::compare::{ComparedField, PageDifference, SiteComparison};
+use scoutly::models::PageInfo;
+use std::collections::HashMap;
+
+fn page(url: &str, title: &str, canonical: Option<&str>) -> (String, PageInfo) {
+    (
+        url.to_string(),
+        PageInfo {
+            url: url.to_string(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            title: Some(title.to_string()),
+            canonical: canonical.map(str::to_string),
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn test_compare_sites_matches_pages_by_path() {
+    let production = HashMap::from([
+        page(
+            "https://www.example.com/",
+            "Home",
+            Some("https://www.example.com/"),
+        ),
+        page(
+            "https://www.example.com/about",
+            "About us",
+            Some("https://www.example.com/about"),
+        ),
+        page("https://www.example.com/legacy", "Legacy", None),
+    ]);
+    let mut staging = HashMap::from([
+        // Staging pages canonicalize to production, which still counts as the same page
+        page(
+            "https://staging.example.com/",
+            "Home",
+            Some("https://www.example.com/"),
+        ),
+        page(
+            "https://staging.example.com/about",
+            "About",
+            Some("https://cdn.example.net/about"),
+        ),
+        page("https://staging.example.com/new?tab=1", "New", None),
+    ]);
+    staging
+        .get_mut("https://staging.example.com/")
+        .unwrap()
+        .status_code = Some(500);
+
+    let comparison = SiteComparison::new(
+        "https://www.example.com",
+        &production,
+        "https://staging.example.com",
+        &staging,
+    );
+
+    assert_eq!(comparison.pages_compared, 2);
+    assert_eq!(comparison.only_in_left, vec!["/legacy"]);
+    assert_eq!(comparison.only_in_right, vec!["/new?tab=1"]);
+    assert_eq!(
+        comparison.differences,
+        vec![
+            PageDifference {
+                path: "/".to_string(),
+                field: ComparedField::StatusCode,
+                left: Some("200".to_string()),
+                right: Some("500".to_string()),
+            },
+            PageDifference {
+                path: "/about".to_string(),
+                field: ComparedField::Title,
+                left: Some("About us".to_string()),
+                right: Some("About".to_string()),
+            },
+            PageDifference {
+                path: "/about".to_string(),
+                field: ComparedField::Canonical,
+                left: Some("/about".to_string()),
+                right: Some("https://cdn.example.net/about".to_string()),
+            },
+        ]
+    );
+    assert_eq!(comparison.total_differences(), 5);
+}
+
+#[test]
+fn test_compare_sites_accepts_crawl_options_after_subcommand() {
+    let cli = Cli::try_parse_from([
+        "scoutly",
+        "compare-sites",
+        "https://www.example.com",
+        "https://staging.example.com",
+        "--depth",
+        "2",
+        "--output",
+        "json",
+    ])
+    .unwrap();
+
+    assert_eq!(cli.depth, Some(2));
+    assert_eq!(
+        cli.command,
+        Some(Command::CompareSites {
+            left: "https://www.example.com".to_string(),
+            right: "https://staging.example.com".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_tui_still_conflicts_with_output() {
+    assert!(Cli::try_parse_from(["scoutly", "--tui", "--output", "json"]).is_err());
+}
@@ -380,3 +380,17 @@ respect_robots_txt: false
     // Just verify the binary can load the config without error
     assert!(output.status.success() || output.status.code() == Some(0));
 }
+
+#[test]
+fn test_request_limits_from_config() {
+    use scoutly::config::Config;
+    use scoutly::limits::ByteSize;
+
+    let config: Config = toml::from_str("max_requests = 5000\nmax_bandwidth = \"500MB\"").unwrap();
+    assert_eq!(config.max_requests, Some(5000));
+    assert_eq!(config.max_bandwidth, Some(ByteSize(500_000_000)));
+
+    let config: Config = serde_json::from_str(r#"{"max_bandwidth": 1048576}"#).unwrap();
+    assert_eq!(config.max_bandwidth, Some(ByteSize(1 << 20)));
+    assert!(toml::from_str::<Config>("max_bandwidth = \"lots\"").is_err());
+}
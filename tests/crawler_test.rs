@@ -1,7 +1,13 @@
 mod server;
 
-use scoutly::crawler::{Crawler, CrawlerConfig};
-use server::{get_test_server_url, start_link_test_server};
+use scoutly::crawler::{Crawler, CrawlerConfig, QueryNormalization};
+use scoutly::http_cache::HttpCache;
+use scoutly::models::{FrontierAction, HtmlContentTypes, IssueSeverity, IssueType};
+use scoutly::seo_analyzer::SeoAnalyzer;
+use server::{
+    counted_requests, get_test_server_url, not_modified_responses, start_link_test_server,
+    start_tls_test_server,
+};
 
 #[tokio::test]
 #[serial_test::serial]
@@ -14,7 +20,7 @@ async fn test_crawler() {
         // Part 1: keep_fragments = true
         {
             let mut crawler = Crawler::new(
-                &base_url,
+                &[&base_url],
                 CrawlerConfig {
                     max_depth: 2,
                     max_pages: 50,
@@ -23,6 +29,9 @@ async fn test_crawler() {
                     requests_per_second: None,
                     concurrent_requests: 1,
                     respect_robots_txt: false,
+                    audit_vary: false,
+                    include: vec![],
+                    exclude: vec![],
                 },
             )
             .expect("Failed to create crawler");
@@ -103,7 +112,7 @@ async fn test_crawler() {
         // Part 2: keep_fragments = false
         {
             let mut crawler = Crawler::new(
-                &base_url,
+                &[&base_url],
                 CrawlerConfig {
                     max_depth: 2,
                     max_pages: 50,
@@ -112,6 +121,9 @@ async fn test_crawler() {
                     requests_per_second: None,
                     concurrent_requests: 1,
                     respect_robots_txt: false,
+                    audit_vary: false,
+                    include: vec![],
+                    exclude: vec![],
                 },
             )
             .expect("Failed to create crawler");
@@ -165,7 +177,7 @@ async fn test_crawler() {
 
             // Compare with keep_fragments=true crawler
             let mut crawler_with_fragments = Crawler::new(
-                &base_url,
+                &[&base_url],
                 CrawlerConfig {
                     max_depth: 2,
                     max_pages: 50,
@@ -174,6 +186,9 @@ async fn test_crawler() {
                     requests_per_second: None,
                     concurrent_requests: 1,
                     respect_robots_txt: false,
+                    audit_vary: false,
+                    include: vec![],
+                    exclude: vec![],
                 },
             )
             .expect("Failed to create crawler");
@@ -191,7 +206,7 @@ async fn test_crawler() {
     // Test case 2: Extract from <iframe src> tags
     {
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 50,
@@ -200,6 +215,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -273,7 +291,7 @@ async fn test_crawler() {
     // Test case 3: Extract from <video src> and <source src> tags
     {
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 50,
@@ -282,6 +300,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -358,7 +379,7 @@ async fn test_crawler() {
     // Test case 4: Extract from <audio src> tags
     {
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 50,
@@ -367,6 +388,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -430,7 +454,7 @@ async fn test_crawler() {
     // Test case 5: Extract from <embed src> tags
     {
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 50,
@@ -439,6 +463,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -486,7 +513,7 @@ async fn test_crawler() {
     // Test case 6: Extract from <object data> tags
     {
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 50,
@@ -495,6 +522,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -543,7 +573,7 @@ async fn test_crawler() {
     {
         // Create a crawler with a low max_pages limit
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 5,
                 max_pages: 3,
@@ -552,6 +582,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -582,7 +615,7 @@ async fn test_crawler() {
     // Test case 8: Test max_depth limit
     {
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 1,
                 max_pages: 100,
@@ -591,6 +624,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -616,7 +652,7 @@ async fn test_crawler() {
         );
 
         let mut crawler_depth_0 = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 0,
                 max_pages: 100,
@@ -625,6 +661,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -649,7 +688,7 @@ async fn test_crawler() {
     // Test case 9: Test follow_external parameter
     {
         let mut crawler_no_external = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 5,
                 max_pages: 50,
@@ -658,6 +697,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -710,7 +752,7 @@ async fn test_crawler() {
 
         // Now crawl with follow_external = true
         let mut crawler_with_external = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 5,
                 max_pages: 50,
@@ -719,6 +761,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -746,7 +791,7 @@ async fn test_crawler() {
     // Test case 10: Test content-type validation (HTML types)
     {
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 50,
@@ -755,6 +800,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -783,7 +831,7 @@ async fn test_crawler() {
 
         // Test with rate limiting (1 request per second)
         let mut crawler_limited = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 1,
                 max_pages: 5,
@@ -792,6 +840,9 @@ async fn test_crawler() {
                 requests_per_second: Some(2.0),
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -809,7 +860,7 @@ async fn test_crawler() {
 
         // Test without rate limiting (should be faster)
         let mut crawler_unlimited = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 1,
                 max_pages: 5,
@@ -818,6 +869,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -843,7 +897,7 @@ async fn test_crawler() {
     {
         // Test sequential crawling (concurrency = 1)
         let mut crawler_sequential = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 20,
@@ -852,6 +906,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -867,7 +924,7 @@ async fn test_crawler() {
 
         // Test concurrent crawling (concurrency = 5)
         let mut crawler_concurrent = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 20,
@@ -876,6 +933,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 5,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -905,7 +965,7 @@ async fn test_crawler() {
     {
         // Concurrent crawling with rate limiting
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 1,
                 max_pages: 10,
@@ -914,6 +974,9 @@ async fn test_crawler() {
                 requests_per_second: Some(3.0),
                 concurrent_requests: 3,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -938,7 +1001,7 @@ async fn test_crawler() {
     {
         // Test with ftp:// scheme (should be rejected)
         let result = Crawler::new(
-            "ftp://example.com",
+            &["ftp://example.com"],
             CrawlerConfig {
                 max_depth: 1,
                 max_pages: 10,
@@ -947,6 +1010,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         );
 
@@ -971,7 +1037,7 @@ async fn test_crawler() {
     // Test case 15: Test file:// scheme validation
     {
         let result = Crawler::new(
-            "file:///etc/passwd",
+            &["file:///etc/passwd"],
             CrawlerConfig {
                 max_depth: 1,
                 max_pages: 10,
@@ -980,6 +1046,9 @@ async fn test_crawler() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         );
 
@@ -993,7 +1062,7 @@ async fn test_crawler_keeps_special_scheme_links_without_crawling_them() {
     let start_url = format!("{}/links-special-schemes.html", get_test_server_url().await);
 
     let mut crawler = Crawler::new(
-        &start_url,
+        &[&start_url],
         CrawlerConfig {
             max_depth: 2,
             max_pages: 10,
@@ -1002,6 +1071,9 @@ async fn test_crawler_keeps_special_scheme_links_without_crawling_them() {
             requests_per_second: None,
             concurrent_requests: 1,
             respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
         },
     )
     .expect("Failed to create crawler");
@@ -1053,11 +1125,14 @@ async fn test_robots_txt_fetch_failure_warning() {
         requests_per_second: None,
         concurrent_requests: 1,
         respect_robots_txt: true,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
     };
 
     // Use a URL that will fail to connect (port unlikely to be in use)
     let mut crawler =
-        Crawler::new("http://localhost:65535", config).expect("Failed to create crawler");
+        Crawler::new(&["http://localhost:65535"], config).expect("Failed to create crawler");
 
     // The crawl should continue despite robots.txt fetch failure
     let result = crawler.crawl().await;
@@ -1083,7 +1158,7 @@ async fn test_content_type_validation() {
     {
         let base_url = get_test_server_url().await;
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 1,
                 max_pages: 5,
@@ -1092,6 +1167,9 @@ async fn test_content_type_validation() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -1130,8 +1208,11 @@ async fn test_content_type_validation() {
             requests_per_second: None,
             concurrent_requests: 1,
             respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
         };
-        let mut crawler = Crawler::new(&format!("{}/json-response", link_server_url), config)
+        let mut crawler = Crawler::new(&[&format!("{}/json-response", link_server_url)], config)
             .expect("Failed to create crawler");
 
         crawler.crawl().await.expect("JSON crawl should succeed");
@@ -1180,8 +1261,11 @@ async fn test_content_type_validation() {
             requests_per_second: None,
             concurrent_requests: 1,
             respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
         };
-        let mut crawler = Crawler::new(&format!("{}/ok", link_server_url), config)
+        let mut crawler = Crawler::new(&[&format!("{}/ok", link_server_url)], config)
             .expect("Failed to create crawler");
 
         crawler.crawl().await.expect("Crawl failed");
@@ -1222,7 +1306,7 @@ async fn test_media_resources_are_not_crawled_as_pages() {
     let base_url = get_test_server_url().await;
     let start_url = format!("{}/crawler-media.html", base_url);
     let mut crawler = Crawler::new(
-        &start_url,
+        &[&start_url],
         CrawlerConfig {
             max_depth: 2,
             max_pages: 50,
@@ -1231,6 +1315,9 @@ async fn test_media_resources_are_not_crawled_as_pages() {
             requests_per_second: None,
             concurrent_requests: 1,
             respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
         },
     )
     .expect("Failed to create crawler");
@@ -1263,3 +1350,2201 @@ async fn test_media_resources_are_not_crawled_as_pages() {
         "MP4 resources should not appear in the crawled pages list"
     );
 }
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_extracts_absolute_canonical_url() {
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/canonical.html");
+
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 1,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+
+    crawler.crawl().await.expect("Crawl failed");
+
+    let page = crawler
+        .pages
+        .get(&start_url)
+        .expect("canonical.html should be present");
+    assert_eq!(
+        page.canonical.as_deref(),
+        Some(format!("{base_url}/canonical.html?ref=home").as_str())
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_measures_page_weight() {
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/page-weight.html");
+
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 1,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+
+    crawler.crawl().await.expect("Crawl failed");
+
+    let page = crawler
+        .pages
+        .get(&start_url)
+        .expect("page-weight.html should be present");
+    let html = std::fs::read_to_string("tests/static/page-weight.html").unwrap();
+    assert_eq!(page.weight.html_bytes, html.len());
+    // Document, stylesheet, external script, image, and iframe; the icon and inline script add no request
+    assert_eq!(page.weight.requests, 5);
+    assert_eq!(page.weight.scripts, 2);
+}
+
+async fn crawl_single_page(start_url: &str) -> scoutly::models::PageInfo {
+    let mut crawler = Crawler::new(
+        &[start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 1,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+
+    crawler.crawl().await.expect("Crawl failed");
+    crawler
+        .pages
+        .remove(start_url)
+        .expect("Start page should be present")
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_reports_content_assertions_from_config() {
+    use scoutly::config::Config;
+    use scoutly::rules::PageAssertions;
+
+    let base_url = get_test_server_url().await;
+    let config: Config = toml::from_str(
+        r#"
+[[assertions]]
+pattern = 'old-cdn\.example\.com'
+must_exist = false
+message = "Page still references the old CDN"
+"#,
+    )
+    .unwrap();
+    let old_cdn_url = format!("{base_url}/old-cdn.html");
+    let index_url = format!("{base_url}/index.html");
+    let mut crawler = Crawler::new(
+        &[&old_cdn_url, &index_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 10,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.set_assertions(PageAssertions::new(&config.assertions.unwrap()).unwrap());
+    crawler.crawl().await.expect("Crawl failed");
+
+    let failed: Vec<_> = crawler.pages[&old_cdn_url]
+        .issues
+        .iter()
+        .filter(|issue| issue.issue_type == IssueType::AssertionFailed)
+        .collect();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].severity, IssueSeverity::Error);
+    assert_eq!(failed[0].message, "Page still references the old CDN");
+    assert_eq!(failed[0].actual.as_deref(), Some("`old-cdn.example.com`"));
+    assert!(
+        crawler.pages[&index_url]
+            .issues
+            .iter()
+            .all(|issue| issue.issue_type != IssueType::AssertionFailed),
+        "Pages without the old hostname pass"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_merges_link_header_canonical_and_alternates() {
+    let base_url = start_link_test_server().await;
+    let page = crawl_single_page(&format!("{base_url}/link-headers")).await;
+
+    assert_eq!(
+        page.link_headers.canonical.as_deref(),
+        Some(format!("{base_url}/canonical-header").as_str())
+    );
+    let header_alternates: Vec<(&str, String)> = page
+        .link_headers
+        .alternates
+        .iter()
+        .map(|alternate| (alternate.hreflang.as_str(), alternate.url.clone()))
+        .collect();
+    assert_eq!(
+        header_alternates,
+        vec![
+            ("de", format!("{base_url}/de-header")),
+            ("fr", format!("{base_url}/fr")),
+        ]
+    );
+
+    // The HTML wins where both declare a value; the header fills in the rest
+    assert_eq!(
+        page.canonical.as_deref(),
+        Some(format!("{base_url}/canonical-html").as_str())
+    );
+    let alternates: Vec<(&str, String)> = page
+        .alternates
+        .iter()
+        .map(|alternate| (alternate.hreflang.as_str(), alternate.url.clone()))
+        .collect();
+    assert_eq!(
+        alternates,
+        vec![
+            ("de", format!("{base_url}/de-html")),
+            ("fr", format!("{base_url}/fr")),
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_uses_link_header_canonical_when_html_has_none() {
+    let base_url = start_link_test_server().await;
+    let page = crawl_single_page(&format!("{base_url}/link-header-only")).await;
+
+    assert_eq!(page.canonical.as_deref(), Some("https://example.com/a,b"));
+    assert!(page.alternates.is_empty());
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_audit_vary_observes_user_agent_and_cookie_variation() {
+    let base_url = start_link_test_server().await;
+
+    for (path, vary, observed) in [
+        ("/vary-user-agent", vec![], vec!["user-agent"]),
+        (
+            "/vary-declared",
+            vec!["user-agent", "accept-encoding"],
+            vec!["user-agent"],
+        ),
+        ("/vary-cookie", vec!["accept-encoding"], vec!["cookie"]),
+        ("/link-headers", vec![], vec![]),
+    ] {
+        let start_url = format!("{base_url}{path}");
+        let mut crawler = Crawler::new(
+            &[&start_url],
+            CrawlerConfig {
+                max_depth: 0,
+                max_pages: 1,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: true,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler");
+        crawler.crawl().await.expect("Crawl failed");
+
+        let page = &crawler.pages[&start_url];
+        assert_eq!(page.vary, vary, "{path}");
+        assert_eq!(page.vary_observed, observed, "{path}");
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_audit_vary_skips_probes_past_the_body_limit() {
+    let base_url = start_link_test_server().await;
+    let start_url = format!("{base_url}/vary-oversized");
+
+    for (max_body_bytes, observed) in [(None, vec!["user-agent"]), (Some(1_024), vec![])] {
+        let mut crawler = Crawler::new(
+            &[&start_url],
+            CrawlerConfig {
+                max_depth: 0,
+                max_pages: 1,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: true,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler");
+        if let Some(max_body_bytes) = max_body_bytes {
+            crawler.set_max_body_bytes(max_body_bytes);
+        }
+        crawler.crawl().await.expect("Crawl failed");
+
+        let page = &crawler.pages[&start_url];
+        assert_eq!(page.title.as_deref(), Some("For browsers"));
+        assert_eq!(page.vary_observed, observed, "{max_body_bytes:?}");
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_records_vary_without_auditing_by_default() {
+    let base_url = start_link_test_server().await;
+    let page = crawl_single_page(&format!("{base_url}/vary-declared")).await;
+
+    assert_eq!(page.vary, vec!["user-agent", "accept-encoding"]);
+    assert!(page.vary_observed.is_empty());
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_reports_redirect_loop_on_start_page() {
+    use scoutly::models::IssueType;
+
+    let base_url = start_link_test_server().await;
+    let start_url = format!("{base_url}/loop-a");
+    let page = crawl_single_page(&start_url).await;
+
+    assert_eq!(page.status_code, None);
+    assert_eq!(page.issues.len(), 1);
+    assert_eq!(page.issues[0].issue_type, IssueType::RedirectLoop);
+    assert_eq!(
+        page.issues[0].actual.as_deref(),
+        Some(format!("{start_url} -> {base_url}/loop-b -> {start_url}").as_str())
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_parses_only_allowed_content_types() {
+    let base_url = start_link_test_server().await;
+
+    for (path, content_types, parsed) in [
+        ("/xml-page", None, false),
+        ("/xml-page", Some(vec!["text/html", "TEXT/XML"]), true),
+        ("/xhtml-page", None, true),
+        ("/xhtml-page", Some(vec!["text/html"]), false),
+    ] {
+        let start_url = format!("{base_url}{path}");
+        let mut crawler = Crawler::new(
+            &[&start_url],
+            CrawlerConfig {
+                max_depth: 0,
+                max_pages: 1,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler");
+        if let Some(content_types) = content_types {
+            crawler.set_html_content_types(HtmlContentTypes::new(&content_types));
+        }
+        crawler.crawl().await.expect("Crawl failed");
+
+        let page = &crawler.pages[&start_url];
+        assert_eq!(page.title.is_some(), parsed, "{path}");
+        assert_eq!(page.links.len(), usize::from(parsed), "{path}");
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_records_where_each_page_was_found() {
+    use scoutly::models::{CrawlSource, DiscoveryMethod};
+
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/crawler-iframe.html");
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 1,
+            max_pages: 10,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    assert_eq!(crawler.pages[&start_url].source, CrawlSource::default());
+    assert_eq!(
+        crawler.pages[&format!("{base_url}/missing-title.html")].source,
+        CrawlSource {
+            method: DiscoveryMethod::Iframe,
+            found_on: Some(start_url.clone()),
+        }
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_keeps_page_content_for_analyzers() {
+    use scoutly::models::Heading;
+
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/page-content.html");
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 1,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    assert_eq!(
+        crawler.pages[&start_url].last_modified.as_deref(),
+        Some("2026-01-15T08:30:00Z"),
+        "Last-Modified is kept as an RFC 3339 time for sitemaps"
+    );
+    let content = &crawler.pages[&start_url].content;
+    assert_eq!(
+        content.text,
+        "Field Guide Birds of the northern coast. Gulls Herring gull"
+    );
+    assert_eq!(content.word_count(), 10);
+    assert_eq!(
+        content.headings,
+        vec![
+            Heading {
+                level: 1,
+                text: "Field Guide".to_string()
+            },
+            Heading {
+                level: 2,
+                text: "Gulls".to_string()
+            },
+            Heading {
+                level: 3,
+                text: "Herring gull".to_string()
+            },
+        ]
+    );
+    assert_eq!(
+        content.meta("description"),
+        Some("Keeps text, headings, and meta tags for analyzers.")
+    );
+    assert_eq!(content.meta("og:type"), Some("article"));
+    assert_eq!(content.meta("charset"), None);
+    assert_eq!(
+        content.comments,
+        ["TODO: replace the placeholder photo"],
+        "Empty comments are left out"
+    );
+
+    let json = serde_json::to_value(&crawler.pages[&start_url]).unwrap();
+    assert!(
+        json.get("content").is_none(),
+        "Saved reports leave content out"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_seeds_frontier_from_sitemap() {
+    use scoutly::models::DiscoveryMethod;
+
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/page-content.html");
+    let crawl = |use_sitemap: bool, max_sitemaps: usize| {
+        let start_url = start_url.clone();
+        async move {
+            let mut crawler = Crawler::new(
+                &[&start_url],
+                CrawlerConfig {
+                    max_depth: 1,
+                    max_pages: 10,
+                    follow_external: false,
+                    keep_fragments: false,
+                    requests_per_second: None,
+                    concurrent_requests: 1,
+                    respect_robots_txt: false,
+                    audit_vary: false,
+                    include: vec![],
+                    exclude: vec![],
+                },
+            )
+            .expect("Failed to create crawler");
+            crawler.set_use_sitemap(use_sitemap);
+            crawler.set_max_sitemaps(max_sitemaps);
+            crawler.crawl().await.expect("Crawl failed");
+            crawler.pages
+        }
+    };
+
+    assert_eq!(
+        crawl(false, 50).await.len(),
+        1,
+        "The start page links nowhere"
+    );
+    assert_eq!(
+        crawl(true, 1).await.len(),
+        1,
+        "Only the sitemap index fits a limit of one sitemap"
+    );
+
+    // The index at /sitemap.xml lists a plain sitemap, a gzipped one, and itself
+    let pages = crawl(true, 50).await;
+    let mut urls: Vec<&str> = pages.keys().map(String::as_str).collect();
+    urls.sort_unstable();
+    let expected_urls = [
+        format!("{base_url}/missing-h1.html?ref=sitemap&lang=en"),
+        format!("{base_url}/missing-title.html"),
+        format!("{base_url}/og-complete.html"),
+        start_url.clone(),
+    ];
+    assert_eq!(
+        urls,
+        expected_urls.iter().map(String::as_str).collect::<Vec<_>>()
+    );
+
+    let page = &pages[&format!("{base_url}/missing-title.html")];
+    assert_eq!(page.crawl_depth, 1);
+    assert_eq!(page.source.method, DiscoveryMethod::Sitemap);
+    assert_eq!(
+        page.source.found_on.as_deref(),
+        Some(format!("{base_url}/sitemap-pages.xml").as_str())
+    );
+    assert_eq!(
+        pages[&format!("{base_url}/og-complete.html")]
+            .source
+            .found_on
+            .as_deref(),
+        Some(format!("{base_url}/sitemap-extra.xml.gz").as_str())
+    );
+}
+
+/// DevTools commands a fake browser received, with their params
+#[cfg(unix)]
+type DevToolsCalls = std::sync::Arc<std::sync::Mutex<Vec<(String, serde_json::Value)>>>;
+
+/// Stand-in for Chrome: a script that records its arguments and announces a DevTools
+/// endpoint, served here, whose tab loads one request of `body_bytes` and has `dom` as its DOM
+#[cfg(unix)]
+async fn fake_devtools_browser(
+    dir: &std::path::Path,
+    dom: &'static str,
+    body_bytes: u64,
+) -> (std::path::PathBuf, std::path::PathBuf, DevToolsCalls) {
+    use futures::{SinkExt, StreamExt};
+    use serde_json::{Value, json};
+    use std::os::unix::fs::PermissionsExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let calls = DevToolsCalls::default();
+    let recorded = calls.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let recorded = recorded.clone();
+            tokio::spawn(async move {
+                let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(Message::Text(text))) = socket.next().await {
+                    let message: Value = serde_json::from_str(&text).unwrap();
+                    let method = message["method"].as_str().unwrap().to_string();
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push((method.clone(), message["params"].clone()));
+                    let result = match method.as_str() {
+                        "Target.createTarget" => json!({ "targetId": "tab" }),
+                        "Target.attachToTarget" => json!({ "sessionId": "session" }),
+                        "Page.navigate" => json!({ "frameId": "frame" }),
+                        "Runtime.evaluate" => {
+                            json!({ "result": { "type": "string", "value": dom } })
+                        }
+                        _ => json!({}),
+                    };
+                    let mut replies = vec![json!({ "id": message["id"], "result": result })];
+                    if method == "Page.navigate" {
+                        replies.extend([
+                            json!({ "method": "Fetch.requestPaused", "params": { "requestId": "request" } }),
+                            json!({ "method": "Network.loadingFinished", "params": { "encodedDataLength": body_bytes } }),
+                            json!({ "method": "Emulation.virtualTimeBudgetExpired", "params": {} }),
+                        ]);
+                    }
+                    for reply in replies {
+                        socket.send(Message::text(reply.to_string())).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+
+    let browser = dir.join("fake-chrome");
+    let args = dir.join("args.txt");
+    std::fs::write(
+        &browser,
+        format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$@\" > '{}'\necho 'DevTools listening on ws://{address}/devtools/browser/fake' >&2\nexec sleep 60\n",
+            args.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&browser, std::fs::Permissions::from_mode(0o755)).unwrap();
+    (browser, args, calls)
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_extracts_page_data_from_rendered_dom() {
+    use scoutly::render::JsRenderer;
+
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/page-content.html");
+    let dir = tempfile::tempdir().unwrap();
+    // The DOM a client-side app would have built
+    let (browser, _, _) = fake_devtools_browser(
+        dir.path(),
+        "<html><head><title>Rendered by the app</title></head><body><a href=\"/missing-title.html\">Next</a></body></html>",
+        0,
+    )
+    .await;
+
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 1,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.set_renderer(JsRenderer::new(browser.to_str()).unwrap());
+    crawler.crawl().await.expect("Crawl failed");
+
+    let page = &crawler.pages[&start_url];
+    assert_eq!(page.status_code, Some(200));
+    assert_eq!(page.title.as_deref(), Some("Rendered by the app"));
+    assert_eq!(
+        page.links
+            .iter()
+            .map(|link| link.url.as_str())
+            .collect::<Vec<_>>(),
+        [format!("{base_url}/missing-title.html")]
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_renders_with_the_crawl_session() {
+    use scoutly::cookies::build_cookie_jar;
+    use scoutly::http_client::TlsOptions;
+    use scoutly::limits::RequestLimits;
+    use scoutly::render::JsRenderer;
+    use std::sync::Arc;
+
+    let link_server_url = start_link_test_server().await;
+    let start_url = format!("{link_server_url}/redirect-to-html");
+    let dir = tempfile::tempdir().unwrap();
+    let (browser, args, calls) = fake_devtools_browser(
+        dir.path(),
+        "<html><head><title>Rendered</title></head><body></body></html>",
+        2048,
+    )
+    .await;
+
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 1,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler
+        .set_cookie_jar(
+            build_cookie_jar(&["session=abc123".to_string()], None, &[&start_url]).unwrap(),
+        )
+        .unwrap();
+    crawler
+        .set_tls(TlsOptions::new(true, None).unwrap())
+        .unwrap();
+    let limits = Arc::new(RequestLimits::new(None, None));
+    crawler.set_request_limits(limits.clone()).unwrap();
+    crawler.set_renderer(JsRenderer::new(browser.to_str()).unwrap());
+    crawler.crawl().await.expect("Crawl failed");
+
+    let served_url = format!("{link_server_url}/link-headers");
+    let page = &crawler.pages[&served_url];
+    assert_eq!(page.aliases, [start_url]);
+    assert_eq!(page.title.as_deref(), Some("Rendered"));
+    let args = std::fs::read_to_string(args).unwrap();
+    assert!(
+        args.lines()
+            .any(|arg| arg.starts_with("--user-agent=Mozilla/5.0"))
+    );
+    assert!(args.lines().any(|arg| arg == "--ignore-certificate-errors"));
+
+    let calls = calls.lock().unwrap();
+    let params = |method: &str| {
+        calls
+            .iter()
+            .find(|(called, _)| called == method)
+            .map(|(_, params)| params.clone())
+            .unwrap_or_else(|| panic!("{method} was not called"))
+    };
+    // The page is rendered where the redirect led, not at the URL that was queued
+    assert_eq!(params("Page.navigate")["url"], served_url);
+    let cookie = &params("Network.setCookies")["cookies"][0];
+    assert_eq!(cookie["name"], "session");
+    assert_eq!(cookie["value"], "abc123");
+    assert_eq!(params("Fetch.continueRequest")["requestId"], "request");
+    // The crawler's request for the page, the redirect it followed, and the browser's request
+    assert_eq!(limits.requests(), 3);
+    assert!(limits.bytes() >= 2048);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_resumes_from_saved_state() {
+    use scoutly::limits::RequestLimits;
+    use std::sync::Arc;
+
+    let base_url = get_test_server_url().await;
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("crawl-state.json");
+    let new_crawler = || {
+        Crawler::new(
+            &[&base_url],
+            CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler")
+    };
+
+    let mut uninterrupted = new_crawler();
+    uninterrupted.crawl().await.expect("Crawl failed");
+
+    // A request limit stops the first run early and leaves its progress on disk
+    let mut first = new_crawler();
+    first.set_state_file(state_path.clone()).unwrap();
+    first
+        .set_request_limits(Arc::new(RequestLimits::new(Some(3), None)))
+        .unwrap();
+    first.crawl().await.expect("Crawl failed");
+    assert_eq!(first.pages.len(), 3);
+    assert!(state_path.exists(), "An unfinished crawl keeps its state");
+
+    let mut resumed = new_crawler();
+    resumed.set_state_file(state_path.clone()).unwrap();
+    assert_eq!(resumed.pages.len(), 3, "Saved pages are restored");
+    let limits = Arc::new(RequestLimits::new(None, None));
+    resumed.set_request_limits(limits.clone()).unwrap();
+    resumed.crawl().await.expect("Crawl failed");
+
+    let mut expected: Vec<&String> = uninterrupted.pages.keys().collect();
+    let mut actual: Vec<&String> = resumed.pages.keys().collect();
+    expected.sort();
+    actual.sort();
+    assert_eq!(actual, expected);
+    assert_eq!(
+        limits.requests(),
+        uninterrupted.pages.len() - 3,
+        "Saved pages aren't fetched again"
+    );
+    assert!(!state_path.exists(), "A finished crawl removes its state");
+
+    let mut other_site = Crawler::new(
+        &["https://example.com/"],
+        CrawlerConfig {
+            max_depth: 1,
+            max_pages: 50,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .unwrap();
+    first.crawl().await.expect("Crawl failed");
+    assert!(
+        other_site
+            .set_state_file(state_path.clone())
+            .unwrap_err()
+            .to_string()
+            .contains("belongs to a crawl of")
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_keeps_the_shortest_depth_a_page_was_found_at() {
+    use scoutly::limits::RequestLimits;
+    use std::sync::Arc;
+
+    let base_url = get_test_server_url().await;
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("crawl-state.json");
+    let new_crawler = || {
+        Crawler::new(
+            &[&base_url],
+            CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler")
+    };
+
+    let mut first = new_crawler();
+    first.set_state_file(state_path.clone()).unwrap();
+    first
+        .set_request_limits(Arc::new(RequestLimits::new(Some(3), None)))
+        .unwrap();
+    first.crawl().await.expect("Crawl failed");
+
+    // Pretend a page the start page links to was first reached by a longer path
+    let mut state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    let saved = state["pages"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|saved| saved["page"]["crawl_depth"] == 1)
+        .expect("A linked page should be saved");
+    saved["page"]["crawl_depth"] = 4.into();
+    let deep_key = saved["key"].as_str().unwrap().to_string();
+    std::fs::write(&state_path, state.to_string()).unwrap();
+
+    let mut resumed = new_crawler();
+    resumed.set_state_file(state_path.clone()).unwrap();
+    resumed.crawl().await.expect("Crawl failed");
+
+    assert_eq!(resumed.pages[&deep_key].crawl_depth, 1);
+    assert!(
+        resumed.pages.values().all(|page| page.crawl_depth <= 1),
+        "No page is deeper than the start page's links"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_request_limit_covers_retries_and_redirect_hops() {
+    use scoutly::http_client::RetryPolicy;
+    use scoutly::limits::RequestLimits;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let link_server_url = start_link_test_server().await;
+
+    // A 5xx is retried and a redirect followed, by the crawl client and hop by hop
+    for (path, trace_redirects) in [
+        ("counted-server-error", false),
+        ("counted-redirect", false),
+        ("counted-redirect", true),
+    ] {
+        let start_url = format!("{link_server_url}/{path}");
+        let mut crawler = Crawler::new(
+            &[&start_url],
+            CrawlerConfig {
+                max_depth: 0,
+                max_pages: 10,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler");
+        crawler.set_retry_policy(RetryPolicy {
+            retries: 2,
+            backoff: Duration::from_millis(10),
+            jitter: false,
+        });
+        crawler.set_trace_redirects(trace_redirects).unwrap();
+        let limits = Arc::new(RequestLimits::new(Some(1), None));
+        crawler.set_request_limits(limits.clone()).unwrap();
+
+        let requests_before = counted_requests();
+        crawler.crawl().await.expect("Crawl failed");
+
+        assert_eq!(
+            counted_requests() - requests_before,
+            1,
+            "{path}: only the first request fits the limit"
+        );
+        assert_eq!(limits.requests(), 1);
+        assert_eq!(
+            limits.reached().as_deref(),
+            Some("request limit of 1 reached")
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_crawler_blocks_off_site_pages_at_private_addresses() {
+    let link_server_url = start_link_test_server().await;
+    let port = link_server_url.rsplit(':').next().unwrap();
+    let start_url = format!("{link_server_url}/private-links");
+    let resolved = format!("http://localhost:{port}/ok");
+    let literal = format!("http://[::1]:{port}/ok");
+    let new_crawler = || {
+        Crawler::new(
+            &[&start_url],
+            CrawlerConfig {
+                max_depth: 1,
+                max_pages: 10,
+                follow_external: true,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 2,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler")
+    };
+
+    for trace_redirects in [false, true] {
+        let mut crawler = new_crawler();
+        crawler.set_trace_redirects(trace_redirects).unwrap();
+        crawler.set_block_private_ips(true).unwrap();
+        crawler.set_explain(&[resolved.clone(), literal.clone()]);
+        crawler.crawl().await.expect("Crawl failed");
+
+        assert!(crawler.errors.is_empty(), "{:?}", crawler.errors);
+        assert_eq!(
+            crawler.pages.keys().collect::<Vec<_>>(),
+            [&start_url],
+            "The start page is crawled, the off-site pages aren't"
+        );
+        let explanations = crawler.explanations();
+        for url in [&resolved, &literal] {
+            let decision = explanations[url].last().unwrap();
+            assert_eq!(decision.action, FrontierAction::Skipped, "{url}");
+            assert!(
+                decision.reason.contains("resolves to private address")
+                    && decision.reason.ends_with("and --block-private-ips is on"),
+                "{url}: {}",
+                decision.reason
+            );
+        }
+    }
+
+    let mut crawler = new_crawler();
+    crawler.crawl().await.expect("Crawl failed");
+    assert_eq!(crawler.pages[&resolved].status_code, Some(200));
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_follows_include_and_exclude_patterns() {
+    let base_url = get_test_server_url().await;
+    let config = |include: Vec<String>, exclude: Vec<String>| CrawlerConfig {
+        max_depth: 1,
+        max_pages: 50,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 1,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include,
+        exclude,
+    };
+    let mut crawler = Crawler::new(
+        &[&base_url],
+        config(
+            vec![r"/(links|title)-".to_string()],
+            vec!["links-broken".to_string()],
+        ),
+    )
+    .expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    let mut paths: Vec<_> = crawler
+        .pages
+        .values()
+        .map(|page| page.url.trim_start_matches(&base_url).to_string())
+        .collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            "",
+            "/links-duplicate.html",
+            "/links-fragments.html",
+            "/links-mixed.html",
+            "/links-redirect.html",
+            "/links-unreachable.html",
+            "/links-working.html",
+            "/title-too-long.html",
+            "/title-too-short.html",
+        ]
+    );
+
+    let error = Crawler::new(&[&base_url], config(vec![], vec!["[wp-admin".to_string()]))
+        .err()
+        .expect("Invalid pattern should be rejected");
+    assert!(
+        error
+            .to_string()
+            .contains("Invalid exclude pattern '[wp-admin'")
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_normalizes_query_parameters() {
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/crawler-query-params.html");
+    let crawl = |normalization: QueryNormalization| {
+        let start_url = start_url.clone();
+        async move {
+            let config = CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            };
+            let mut crawler =
+                Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+            crawler.set_query_normalization(normalization);
+            crawler.crawl().await.expect("Crawl failed");
+            crawler.pages.len()
+        }
+    };
+
+    // The start page plus six query variants
+    assert_eq!(crawl(QueryNormalization::default()).await, 7);
+
+    let pages = crawl(QueryNormalization {
+        strip_tracking: true,
+        sort: true,
+        ignored: vec!["session*".to_string()],
+    })
+    .await;
+    assert_eq!(pages, 4);
+
+    let pages = crawl(QueryNormalization {
+        strip_tracking: true,
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(pages, 6);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_links_byte_identical_pages_to_the_first_copy() {
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/crawler-query-params.html");
+    let config = CrawlerConfig {
+        max_depth: 1,
+        max_pages: 50,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 1,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+    SeoAnalyzer::analyze_pages(&mut crawler.pages).unwrap();
+
+    // Each file is linked twice with different query strings, which the server ignores
+    assert_eq!(crawler.pages.len(), 7);
+    for file in ["title-too-long", "title-too-short", "missing-title"] {
+        let variants: Vec<_> = crawler
+            .pages
+            .values()
+            .filter(|page| page.url.contains(&format!("/{file}.html?")))
+            .collect();
+        assert_eq!(variants.len(), 2, "{file}");
+        let (copies, originals): (Vec<_>, Vec<_>) = variants
+            .into_iter()
+            .partition(|page| page.duplicate_of.is_some());
+        let (copy, original) = (copies[0], originals[0]);
+        assert_eq!(copy.duplicate_of.as_deref(), Some(original.url.as_str()));
+        assert_eq!(copy.status_code, Some(200));
+        assert_eq!(copy.content_length, original.content_length);
+        assert!(copy.issues.is_empty(), "{file} copy isn't analyzed again");
+        assert!(!original.issues.is_empty(), "{file} is analyzed once");
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_queues_each_normalized_url_once() {
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/crawler-query-params.html");
+    let config = CrawlerConfig {
+        max_depth: 1,
+        max_pages: 50,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 1,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+    crawler.set_query_normalization(QueryNormalization {
+        strip_tracking: true,
+        ..Default::default()
+    });
+    let target = format!("{base_url}/title-too-long.html");
+    crawler.set_explain(std::slice::from_ref(&target));
+    crawler.crawl().await.expect("Crawl failed");
+
+    // Both tracking variants normalize to the same URL; the second finds it already queued
+    let decisions = &crawler.explanations()[&target];
+    let actions: Vec<_> = decisions.iter().map(|decision| decision.action).collect();
+    assert_eq!(
+        actions,
+        [
+            FrontierAction::Queued,
+            FrontierAction::Skipped,
+            FrontierAction::Crawled
+        ]
+    );
+    assert_eq!(decisions[1].reason, "already queued");
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_explains_frontier_decisions() {
+    let base_url = get_test_server_url().await;
+    let config = CrawlerConfig {
+        max_depth: 1,
+        max_pages: 50,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 1,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec!["links-broken".to_string()],
+    };
+    let mut crawler = Crawler::new(&[&base_url], config).expect("Failed to create crawler");
+    let excluded_url = format!("{base_url}/links-broken.html");
+    let crawled_url = format!("{base_url}/title-too-long.html");
+    let unknown_url = format!("{base_url}/nowhere.html");
+    crawler.set_explain(&[
+        base_url.clone(),
+        excluded_url.clone(),
+        crawled_url.clone(),
+        unknown_url.clone(),
+    ]);
+    crawler.crawl().await.expect("Crawl failed");
+
+    let explanations = crawler.explanations();
+    let actions = |url: &str| -> Vec<FrontierAction> {
+        explanations[url]
+            .iter()
+            .map(|decision| decision.action)
+            .collect()
+    };
+
+    assert_eq!(
+        actions(&base_url),
+        [FrontierAction::Queued, FrontierAction::Crawled]
+    );
+    assert_eq!(explanations[&base_url][0].reason, "start URL");
+    assert_eq!(
+        actions(&crawled_url),
+        [FrontierAction::Queued, FrontierAction::Crawled]
+    );
+    assert_eq!(
+        explanations[&crawled_url][0].found_on.as_deref(),
+        Some(base_url.as_str())
+    );
+    assert!(explanations[&crawled_url][1].reason.contains("HTTP 200"));
+
+    let excluded = &explanations[&excluded_url];
+    assert!(
+        excluded
+            .iter()
+            .all(|decision| decision.action == FrontierAction::Skipped)
+    );
+    assert_eq!(
+        excluded[0].reason,
+        "matches --exclude pattern `links-broken`"
+    );
+
+    assert_eq!(actions(&unknown_url), [FrontierAction::Skipped]);
+    assert!(
+        explanations[&unknown_url][0]
+            .reason
+            .starts_with("never found")
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_treats_every_start_host_as_internal() {
+    let link_server_url = start_link_test_server().await;
+    let base_url = get_test_server_url().await;
+    let mixed_url = format!("{base_url}/links-mixed.html");
+    let xhtml_url = format!("{link_server_url}/xhtml-page");
+    let mut crawler = Crawler::new(
+        &[&mixed_url, &xhtml_url],
+        CrawlerConfig {
+            max_depth: 1,
+            max_pages: 50,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    assert!(crawler.pages.contains_key(&xhtml_url));
+    let cross_host_links: Vec<_> = crawler.pages[&mixed_url]
+        .links
+        .iter()
+        .filter(|link| link.url.starts_with(&link_server_url))
+        .collect();
+    assert!(!cross_host_links.is_empty());
+    assert!(cross_host_links.iter().all(|link| !link.is_external));
+    assert!(crawler.pages.contains_key(&format!("{link_server_url}/ok")));
+
+    assert!(
+        Crawler::new::<&str>(
+            &[],
+            CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .is_err()
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_stops_queueing_when_time_limit_runs_out() {
+    let base_url = get_test_server_url().await;
+    let config = || CrawlerConfig {
+        max_depth: 1,
+        max_pages: 50,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 1,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+
+    let mut crawler = Crawler::new(&[&base_url], config()).expect("Failed to create crawler");
+    crawler.set_max_duration(std::time::Duration::ZERO);
+    crawler.crawl().await.expect("Crawl failed");
+    assert!(crawler.timed_out());
+    assert!(crawler.pages.is_empty());
+
+    let mut crawler = Crawler::new(&[&base_url], config()).expect("Failed to create crawler");
+    crawler.set_max_duration(std::time::Duration::from_secs(600));
+    crawler.crawl().await.expect("Crawl failed");
+    assert!(!crawler.timed_out());
+    assert!(!crawler.pages.is_empty());
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_respects_nofollow_links_when_asked() {
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/crawler-nofollow.html");
+    let crawl = |respect_nofollow: bool| {
+        let start_url = start_url.clone();
+        async move {
+            let config = CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            };
+            let mut crawler =
+                Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+            crawler.set_respect_nofollow(respect_nofollow);
+            crawler.crawl().await.expect("Crawl failed");
+            crawler
+        }
+    };
+
+    let crawler = crawl(false).await;
+    assert_eq!(crawler.pages.len(), 6);
+    let start_page = crawler
+        .pages
+        .values()
+        .find(|page| page.url.ends_with("/crawler-nofollow.html"))
+        .unwrap();
+    let rels: Vec<Option<&str>> = start_page
+        .links
+        .iter()
+        .map(|link| link.rel.as_deref())
+        .collect();
+    assert_eq!(
+        rels,
+        [
+            None,
+            Some("nofollow"),
+            Some("noopener UGC"),
+            Some("sponsored"),
+            Some("noopener noreferrer")
+        ]
+    );
+
+    // Nofollow links are still extracted but not crawled
+    let crawler = crawl(true).await;
+    let mut crawled: Vec<&str> = crawler
+        .pages
+        .values()
+        .map(|page| page.url.rsplit('/').next().unwrap())
+        .collect();
+    crawled.sort();
+    assert_eq!(
+        crawled,
+        [
+            "crawler-nofollow.html",
+            "missing-h1.html",
+            "title-too-long.html"
+        ]
+    );
+    let start_page = crawler
+        .pages
+        .values()
+        .find(|page| page.url.ends_with("/crawler-nofollow.html"))
+        .unwrap();
+    assert_eq!(start_page.links.len(), 5);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_honors_meta_robots() {
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{base_url}/meta-robots.html");
+    let crawl = |respect_nofollow: bool| {
+        let start_url = start_url.clone();
+        async move {
+            let config = CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            };
+            let mut crawler =
+                Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+            crawler.set_respect_nofollow(respect_nofollow);
+            crawler.crawl().await.expect("Crawl failed");
+            crawler
+        }
+    };
+
+    let mut crawler = crawl(false).await;
+    assert_eq!(crawler.pages.len(), 2);
+    SeoAnalyzer::analyze_pages(&mut crawler.pages).unwrap();
+    let page = crawler
+        .pages
+        .values()
+        .find(|page| page.url.ends_with("/meta-robots.html"))
+        .unwrap();
+    assert_eq!(page.meta_robots.as_deref(), Some("noindex, nofollow"));
+    assert!(page.has_robots_directive("noindex"));
+    assert!(page.has_robots_directive("nofollow"));
+    let noindex = page
+        .issues
+        .iter()
+        .find(|issue| issue.issue_type == IssueType::NoindexPage)
+        .expect("noindex page is flagged");
+    assert_eq!(noindex.severity, IssueSeverity::Info);
+    let linked = crawler
+        .pages
+        .values()
+        .find(|page| page.url.ends_with("/title-too-long.html"))
+        .unwrap();
+    assert!(linked.meta_robots.is_none());
+    assert!(
+        !linked
+            .issues
+            .iter()
+            .any(|issue| issue.issue_type == IssueType::NoindexPage)
+    );
+
+    // The page's links are kept but not followed
+    let crawler = crawl(true).await;
+    assert_eq!(crawler.pages.len(), 1);
+    assert_eq!(crawler.pages.values().next().unwrap().links.len(), 1);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_honors_x_robots_tag_header() {
+    let link_server_url = start_link_test_server().await;
+    let start_url = format!("{link_server_url}/x-robots-tag");
+    let crawl = |respect_nofollow: bool| {
+        let start_url = start_url.clone();
+        async move {
+            let config = CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            };
+            let mut crawler =
+                Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+            crawler.set_respect_nofollow(respect_nofollow);
+            crawler.crawl().await.expect("Crawl failed");
+            crawler
+        }
+    };
+
+    let mut crawler = crawl(false).await;
+    assert_eq!(crawler.pages.len(), 2);
+    SeoAnalyzer::analyze_pages(&mut crawler.pages).unwrap();
+    let page = crawler
+        .pages
+        .values()
+        .find(|page| page.url == start_url)
+        .unwrap();
+    assert_eq!(
+        page.x_robots_tag.as_deref(),
+        Some("googlebot: noindex, nofollow")
+    );
+    assert!(page.meta_robots.is_none());
+    let noindex = page
+        .issues
+        .iter()
+        .find(|issue| issue.issue_type == IssueType::NoindexPage)
+        .expect("noindex header is flagged");
+    assert_eq!(
+        noindex.actual.as_deref(),
+        Some("X-Robots-Tag: googlebot: noindex, nofollow")
+    );
+
+    let crawler = crawl(true).await;
+    assert_eq!(crawler.pages.len(), 1);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_retries_pages_after_transient_failures() {
+    use scoutly::http_client::RetryPolicy;
+    use std::time::Duration;
+
+    let link_server_url = start_link_test_server().await;
+    let crawl = |key: &'static str, retries: usize| {
+        let start_url = format!("{link_server_url}/flaky/{key}");
+        async move {
+            let config = CrawlerConfig {
+                max_depth: 0,
+                max_pages: 1,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            };
+            let mut crawler =
+                Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+            crawler.set_retry_policy(RetryPolicy {
+                retries,
+                backoff: Duration::from_millis(10),
+                jitter: false,
+            });
+            crawler.crawl().await.expect("Crawl failed");
+            let page = crawler.pages.into_values().next().unwrap();
+            (page.status_code, page.title)
+        }
+    };
+
+    assert_eq!(crawl("page-once", 0).await, (Some(502), None));
+    assert_eq!(
+        crawl("page-retried", 1).await,
+        (Some(200), Some("Flaky page".to_string()))
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_auto_throttle_finishes_crawl_of_struggling_site() {
+    let link_server_url = start_link_test_server().await;
+    let start_urls: Vec<String> = (0..8)
+        .map(|n| {
+            let path = if n % 2 == 0 { "server-error" } else { "ok" };
+            format!("{link_server_url}/{path}?n={n}")
+        })
+        .collect();
+    let config = CrawlerConfig {
+        max_depth: 0,
+        max_pages: 8,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 4,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(&start_urls, config).expect("Failed to create crawler");
+    crawler.set_auto_throttle(true);
+    crawler.crawl().await.expect("Crawl failed");
+
+    assert_eq!(crawler.pages.len(), 8);
+    let errors = crawler
+        .pages
+        .values()
+        .filter(|page| page.status_code == Some(500))
+        .count();
+    assert_eq!(errors, 4);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_records_declared_image_dimensions() {
+    let base_url = get_test_server_url().await;
+    let config = CrawlerConfig {
+        max_depth: 0,
+        max_pages: 1,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 1,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(
+        &[format!("{base_url}/crawler-image-dimensions.html")],
+        config,
+    )
+    .expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    let page = crawler.pages.values().next().unwrap();
+    let dimensions: Vec<_> = page
+        .images
+        .iter()
+        .map(|image| (image.width, image.height))
+        .collect();
+    assert_eq!(dimensions, [(Some(1200), Some(630)), (Some(64), None)]);
+    let alts: Vec<_> = page
+        .images
+        .iter()
+        .map(|image| image.alt.as_deref())
+        .collect();
+    assert_eq!(alts, [Some("Hero banner"), None]);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_keys_redirected_pages_by_final_url_with_aliases() {
+    let link_server_url = start_link_test_server().await;
+    let start_urls: Vec<String> = ["redirect", "ok", "redirect-chain", "redirect-temp"]
+        .iter()
+        .map(|path| format!("{link_server_url}/{path}"))
+        .collect();
+    let config = CrawlerConfig {
+        max_depth: 0,
+        max_pages: 10,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 2,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(&start_urls, config).expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    let final_url = format!("{link_server_url}/ok");
+    assert_eq!(crawler.pages.len(), 1, "{:?}", crawler.pages.keys());
+    let page = &crawler.pages[&final_url];
+    assert_eq!(page.url, final_url);
+    assert_eq!(page.status_code, Some(200));
+    let mut aliases = page.aliases.clone();
+    aliases.sort();
+    assert_eq!(
+        aliases,
+        vec![
+            format!("{link_server_url}/redirect"),
+            format!("{link_server_url}/redirect-chain"),
+            format!("{link_server_url}/redirect-temp"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_crawler_stops_reading_pages_past_max_body_bytes() {
+    let link_server_url = start_link_test_server().await;
+    let start_urls: Vec<String> = ["large-page", "large-page-streamed", "x-robots-tag"]
+        .iter()
+        .map(|path| format!("{link_server_url}/{path}"))
+        .collect();
+    let config = CrawlerConfig {
+        max_depth: 0,
+        max_pages: 10,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 2,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(&start_urls, config).expect("Failed to create crawler");
+    crawler.set_max_body_bytes(100_000);
+    crawler.crawl().await.expect("Crawl failed");
+
+    for path in ["large-page", "large-page-streamed"] {
+        let page = &crawler.pages[&format!("{link_server_url}/{path}")];
+        assert_eq!(page.status_code, Some(200));
+        assert_eq!(page.title, None, "{path} shouldn't be parsed");
+        assert_eq!(
+            page.issues
+                .iter()
+                .map(|issue| issue.issue_type)
+                .collect::<Vec<_>>(),
+            vec![IssueType::ResponseTooLarge],
+            "{path}"
+        );
+    }
+    let small = &crawler.pages[&format!("{link_server_url}/x-robots-tag")];
+    assert_eq!(small.title.as_deref(), Some("Header directives"));
+    assert!(small.issues.is_empty());
+}
+
+#[tokio::test]
+async fn test_crawler_measures_response_time_and_size() {
+    let link_server_url = start_link_test_server().await;
+    let start_urls: Vec<String> = ["x-robots-tag", "pdf-response"]
+        .iter()
+        .map(|path| format!("{link_server_url}/{path}"))
+        .collect();
+    let config = CrawlerConfig {
+        max_depth: 0,
+        max_pages: 10,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 2,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(&start_urls, config).expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    let html = &crawler.pages[&format!("{link_server_url}/x-robots-tag")];
+    assert!(html.response_time_ms.is_some());
+    assert_eq!(html.content_length, Some(html.weight.html_bytes as u64));
+    // The PDF isn't downloaded, so its declared length is reported
+    let pdf = &crawler.pages[&format!("{link_server_url}/pdf-response")];
+    assert!(pdf.response_time_ms.is_some());
+    assert_eq!(pdf.content_length, Some(100));
+}
+
+#[tokio::test]
+async fn test_crawler_traces_redirect_chains() {
+    let link_server_url = start_link_test_server().await;
+    let start_urls: Vec<String> = ["redirect-chain", "redirect-temp", "loop-a"]
+        .iter()
+        .map(|path| format!("{link_server_url}/{path}"))
+        .collect();
+    let config = CrawlerConfig {
+        max_depth: 0,
+        max_pages: 10,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 1,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(&start_urls, config).expect("Failed to create crawler");
+    crawler.set_trace_redirects(true).unwrap();
+    crawler.crawl().await.expect("Crawl failed");
+
+    let page = &crawler.pages[&format!("{link_server_url}/ok")];
+    let hops: Vec<_> = page
+        .redirect_chain
+        .iter()
+        .map(|hop| {
+            (
+                hop.url.trim_start_matches(&link_server_url),
+                hop.status_code,
+            )
+        })
+        .collect();
+    assert_eq!(
+        hops,
+        [("/redirect-chain", 301), ("/redirect", 301), ("/ok", 200)]
+    );
+    assert_eq!(page.status_code, Some(200));
+    assert_eq!(
+        page.issues
+            .iter()
+            .map(|issue| issue.issue_type)
+            .collect::<Vec<_>>(),
+        [IssueType::RedirectChainTooLong]
+    );
+    assert!(
+        page.aliases
+            .contains(&format!("{link_server_url}/redirect-temp"))
+    );
+
+    let looping = &crawler.pages[&format!("{link_server_url}/loop-a")];
+    assert_eq!(looping.issues[0].issue_type, IssueType::RedirectLoop);
+
+    // One redirect is within the default limit
+    let mut crawler = Crawler::new(
+        &[format!("{link_server_url}/redirect-temp")],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 10,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.set_trace_redirects(true).unwrap();
+    crawler.crawl().await.expect("Crawl failed");
+    let page = &crawler.pages[&format!("{link_server_url}/ok")];
+    assert_eq!(page.redirect_chain.len(), 2);
+    assert!(page.issues.is_empty());
+}
+
+#[tokio::test]
+async fn test_crawler_sends_imported_cookies() {
+    use scoutly::cookies::build_cookie_jar;
+
+    let link_server_url = start_link_test_server().await;
+    let start_url = format!("{link_server_url}/cookie-gated");
+    let host = url::Url::parse(&link_server_url)
+        .unwrap()
+        .host_str()
+        .unwrap()
+        .to_string();
+    let dir = tempfile::tempdir().unwrap();
+    let cookies_file = dir.path().join("cookies.txt");
+    std::fs::write(
+        &cookies_file,
+        format!("# Netscape HTTP Cookie File\n{host}\tFALSE\t/\tFALSE\t0\tsession\tabc123\n"),
+    )
+    .unwrap();
+
+    let crawl = |jar| {
+        let start_url = start_url.clone();
+        async move {
+            let config = CrawlerConfig {
+                max_depth: 0,
+                max_pages: 1,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            };
+            let mut crawler =
+                Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+            if let Some(jar) = jar {
+                crawler
+                    .set_cookie_jar(jar)
+                    .expect("Failed to set cookie jar");
+            }
+            crawler.crawl().await.expect("Crawl failed");
+            let page = crawler.pages.into_values().next().unwrap();
+            (page.status_code, page.title)
+        }
+    };
+
+    assert_eq!(crawl(None).await, (Some(401), None));
+    let from_flag = build_cookie_jar(&["session=abc123".to_string()], None, &[&start_url]).unwrap();
+    assert_eq!(
+        crawl(Some(from_flag)).await,
+        (Some(200), Some("Members area".to_string()))
+    );
+    let from_file = build_cookie_jar(&[], Some(&cookies_file), &[&start_url]).unwrap();
+    assert_eq!(
+        crawl(Some(from_file)).await,
+        (Some(200), Some("Members area".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_crawler_logs_in_before_crawling() {
+    use scoutly::login::LoginConfig;
+    use std::collections::BTreeMap;
+
+    let link_server_url = start_link_test_server().await;
+    let start_url = format!("{link_server_url}/cookie-gated");
+    let crawl = |password: &str| {
+        let start_url = start_url.clone();
+        let login = LoginConfig {
+            url: format!("{link_server_url}/login"),
+            fields: BTreeMap::from([
+                ("username".to_string(), "ana".to_string()),
+                ("password".to_string(), password.to_string()),
+            ]),
+            success_selector: "p.account".to_string(),
+        };
+        async move {
+            let config = CrawlerConfig {
+                max_depth: 0,
+                max_pages: 1,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            };
+            let mut crawler =
+                Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+            crawler.set_login(login).expect("Failed to set login");
+            crawler.crawl().await.map(|()| crawler.pages)
+        }
+    };
+
+    let pages = crawl("hunter2").await.expect("Crawl failed");
+    let page = pages.values().next().unwrap();
+    assert_eq!(page.status_code, Some(200));
+    assert_eq!(page.title.as_deref(), Some("Members area"));
+
+    let error = crawl("wrong").await.unwrap_err().to_string();
+    assert!(error.contains("Login failed"), "{error}");
+}
+
+#[tokio::test]
+async fn test_crawler_trusts_custom_ca_or_skips_verification() {
+    use scoutly::http_client::TlsOptions;
+    use std::path::Path;
+
+    let start_url = format!("{}/", start_tls_test_server().await);
+    let crawl = |tls: Option<TlsOptions>| {
+        let start_url = start_url.clone();
+        async move {
+            let config = CrawlerConfig {
+                max_depth: 0,
+                max_pages: 1,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            };
+            let mut crawler =
+                Crawler::new(&[&start_url], config).expect("Failed to create crawler");
+            if let Some(tls) = tls {
+                crawler.set_tls(tls).expect("Failed to set TLS options");
+            }
+            crawler.crawl().await.expect("Crawl failed");
+            crawler
+                .pages
+                .into_values()
+                .next()
+                .and_then(|page| page.title)
+        }
+    };
+
+    assert_eq!(
+        crawl(None).await,
+        None,
+        "A certificate from an unknown CA should be rejected by default"
+    );
+    let custom_ca = TlsOptions::new(false, Some(Path::new("tests/static/tls/ca.pem"))).unwrap();
+    // What `openssl pkey -pubin -outform der | openssl dgst -sha256 -binary | base64` prints
+    // for the CA's public key, which the browser is told to trust with --render js
+    assert_eq!(
+        custom_ca.ca_key_hashes,
+        ["L/bS0o70SV2xbPvJr6Ci/K/LldvPeWViKrpO2VuAN8k="]
+    );
+    assert_eq!(
+        crawl(Some(custom_ca)).await,
+        Some("Secure staging".to_string())
+    );
+    let insecure = TlsOptions::new(true, None).unwrap();
+    assert_eq!(
+        crawl(Some(insecure)).await,
+        Some("Secure staging".to_string())
+    );
+    assert!(TlsOptions::new(false, Some(Path::new("tests/static/index.html"))).is_err());
+}
+
+#[tokio::test]
+async fn test_crawler_reuses_cached_pages_the_server_says_are_unchanged() {
+    let link_server_url = start_link_test_server().await;
+    let page_url = format!("{link_server_url}/etag-page");
+    let cache_dir = tempfile::tempdir().unwrap();
+    let crawl = || async {
+        let config = CrawlerConfig {
+            max_depth: 0,
+            max_pages: 10,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        };
+        let mut crawler = Crawler::new(&[&page_url], config).expect("Failed to create crawler");
+        crawler.set_http_cache(HttpCache::new(cache_dir.path()).expect("Failed to open cache"));
+        crawler.crawl().await.expect("Crawl failed");
+        crawler
+    };
+
+    let first = crawl().await;
+    assert_eq!(not_modified_responses(), 0);
+    assert_eq!(first.pages[&page_url].title.as_deref(), Some("Tagged"));
+
+    let second = crawl().await;
+    assert_eq!(
+        not_modified_responses(),
+        1,
+        "the second run should revalidate the page"
+    );
+    let page = &second.pages[&page_url];
+    assert_eq!(page.status_code, Some(200));
+    assert_eq!(page.title.as_deref(), Some("Tagged"));
+    assert_eq!(page.links.len(), 1);
+    assert_eq!(page.content.text, "OK");
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_crawler_saves_html_snapshots() {
+    use scoutly::html_snapshot::HtmlSnapshots;
+
+    let base_url = get_test_server_url().await;
+    let dir = tempfile::tempdir().unwrap();
+    let config = CrawlerConfig {
+        max_depth: 0,
+        max_pages: 10,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 1,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let snapshots = HtmlSnapshots::new(dir.path().join("html")).expect("Failed to open snapshots");
+    let mut crawler = Crawler::new(&[&base_url], config).expect("Failed to create crawler");
+    crawler.set_html_snapshots(snapshots.clone());
+    crawler.crawl().await.expect("Crawl failed");
+
+    assert_eq!(crawler.pages.len(), 1);
+    let page = crawler.pages.values().next().unwrap();
+    let snapshot = page
+        .html_snapshot
+        .as_deref()
+        .expect("The page should have a snapshot");
+    assert_eq!(
+        std::path::Path::new(snapshot),
+        snapshots.snapshot_path(&page.url)
+    );
+    assert_eq!(
+        std::fs::read_to_string(snapshot).unwrap(),
+        std::fs::read_to_string("tests/static/index.html").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_crawler_groups_failed_fetches_by_cause() {
+    use scoutly::http_client::RetryPolicy;
+    use scoutly::models::CrawlErrorCause;
+    use std::time::Duration;
+
+    let link_server_url = start_link_test_server().await;
+    let tls_url = format!("{}/", start_tls_test_server().await);
+    let start_urls = [
+        format!("{link_server_url}/slow"),
+        format!("{link_server_url}/server-error"),
+        tls_url.clone(),
+        "http://scoutly-test.invalid/".to_string(),
+        format!("{link_server_url}/ok"),
+    ];
+    let config = CrawlerConfig {
+        max_depth: 0,
+        max_pages: 10,
+        follow_external: false,
+        keep_fragments: false,
+        requests_per_second: None,
+        concurrent_requests: 5,
+        respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
+    };
+    let mut crawler = Crawler::new(&start_urls, config).expect("Failed to create crawler");
+    crawler.set_timeout(1).expect("Failed to set timeout");
+    crawler.set_retry_policy(RetryPolicy {
+        retries: 0,
+        backoff: Duration::from_millis(10),
+        jitter: false,
+    });
+    crawler.crawl().await.expect("Crawl failed");
+
+    let failed: Vec<(CrawlErrorCause, &str)> = crawler
+        .errors
+        .iter()
+        .flat_map(|(cause, errors)| errors.iter().map(|error| (*cause, error.url.as_str())))
+        .collect();
+    assert_eq!(
+        failed,
+        [
+            (CrawlErrorCause::Timeout, start_urls[0].as_str()),
+            (CrawlErrorCause::Dns, "http://scoutly-test.invalid/"),
+            (CrawlErrorCause::Tls, tls_url.as_str()),
+            (CrawlErrorCause::ServerError, start_urls[1].as_str()),
+        ]
+    );
+    assert_eq!(
+        crawler.errors[&CrawlErrorCause::ServerError][0].status_code,
+        Some(500)
+    );
+
+    // Only pages that answered are reported as pages
+    let mut pages: Vec<&str> = crawler.pages.keys().map(String::as_str).collect();
+    pages.sort_unstable();
+    assert_eq!(pages, [start_urls[4].as_str(), start_urls[1].as_str()]);
+}
+
+#[tokio::test]
+async fn test_crawler_keeps_pages_in_a_page_store_while_crawling() {
+    use scoutly::page_store::{PageStore, SqlitePageStore};
+
+    let base_url = get_test_server_url().await;
+    let dir = tempfile::tempdir().unwrap();
+    let store_path = dir.path().join("pages.sqlite");
+    let new_crawler = || {
+        Crawler::new(
+            &[&base_url],
+            CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 2,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler")
+    };
+
+    let mut in_memory = new_crawler();
+    in_memory.crawl().await.expect("Crawl failed");
+
+    let mut stored = new_crawler();
+    stored.set_page_store(Box::new(SqlitePageStore::create(&store_path).unwrap()));
+    stored.crawl().await.expect("Crawl failed");
+
+    let summarize = |pages: &dyn PageStore| {
+        let mut summary = Vec::new();
+        pages
+            .for_each_page(&mut |key, page| {
+                summary.push((
+                    key.to_string(),
+                    page.title.clone(),
+                    page.crawl_depth,
+                    page.links.len(),
+                    page.content.text.clone(),
+                ));
+                Ok(())
+            })
+            .unwrap();
+        summary
+    };
+    assert!(!in_memory.pages.is_empty());
+    assert!(
+        stored.pages.is_empty(),
+        "stored pages aren't copied into memory"
+    );
+    let store = stored
+        .take_page_store()
+        .expect("the crawler keeps its store");
+    assert_eq!(summarize(store.as_ref()), summarize(&in_memory.pages));
+    drop(store);
+
+    // Creating the store again starts a new crawl
+    let store = SqlitePageStore::create(&store_path).unwrap();
+    assert!(store.is_empty().unwrap(), "a new store starts empty");
+}
@@ -0,0 +1,126 @@
+use actix_web::{App, HttpResponse, HttpServer, web};
+use scoutly::crux::CruxClient;
+use scoutly::models::{FieldData, FieldDataScope, PageInfo};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+const SITE: &str = "https://site.test";
+
+/// Stand-in for the CrUX API: `/popular` has its own record, other pages only have origin data,
+/// and `/broken` makes the API fail.
+async fn query_record(body: web::Json<Value>) -> HttpResponse {
+    let popular = format!("{SITE}/popular");
+    let broken = format!("{SITE}/broken");
+    match (body["url"].as_str(), body["origin"].as_str()) {
+        (Some(url), _) if url == popular => HttpResponse::Ok().json(json!({
+            "record": {
+                "metrics": {
+                    "largest_contentful_paint": { "percentiles": { "p75": 2100 } },
+                    "cumulative_layout_shift": { "percentiles": { "p75": "0.05" } },
+                    "interaction_to_next_paint": { "percentiles": { "p75": 180 } }
+                }
+            }
+        })),
+        (Some(url), _) if url == broken => HttpResponse::BadRequest().json(json!({
+            "error": { "message": "Quota exceeded" }
+        })),
+        (None, Some(SITE)) => HttpResponse::Ok().json(json!({
+            "record": {
+                "metrics": {
+                    "largest_contentful_paint": { "percentiles": { "p75": 3200 } },
+                    "cumulative_layout_shift": { "percentiles": { "p75": "0.12" } }
+                }
+            }
+        })),
+        _ => HttpResponse::NotFound().json(json!({
+            "error": { "message": "chrome ux report data not found" }
+        })),
+    }
+}
+
+fn start_fake_crux_api() -> String {
+    let listener =
+        std::net::TcpListener::bind(("127.0.0.1", 0)).expect("Failed to bind fake CrUX API");
+    let endpoint = format!(
+        "http://{}/v1/records:queryRecord",
+        listener.local_addr().unwrap()
+    );
+    let server = HttpServer::new(|| {
+        App::new().route("/v1/records:queryRecord", web::post().to(query_record))
+    })
+    .listen(listener)
+    .expect("Failed to start fake CrUX API")
+    .run();
+    actix_web::rt::spawn(server);
+    endpoint
+}
+
+fn page(path: &str, content_type: &str) -> (String, PageInfo) {
+    let url = format!("{SITE}{path}");
+    (
+        url.clone(),
+        PageInfo {
+            url,
+            status_code: Some(200),
+            content_type: Some(content_type.to_string()),
+            ..Default::default()
+        },
+    )
+}
+
+#[actix_web::test]
+async fn test_annotate_pages_uses_url_records_and_falls_back_to_origin() {
+    let endpoint = start_fake_crux_api();
+    let mut pages: HashMap<String, PageInfo> = [
+        page("/popular", "text/html"),
+        page("/quiet", "text/html"),
+        page("/broken", "text/html"),
+        page("/logo.png", "image/png"),
+    ]
+    .into_iter()
+    .collect();
+
+    CruxClient::new("test-key")
+        .unwrap()
+        .with_endpoint(endpoint)
+        .annotate_pages(&mut pages)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        pages[&format!("{SITE}/popular")].field_data,
+        Some(FieldData {
+            scope: FieldDataScope::Url,
+            lcp_ms: Some(2100.0),
+            cls: Some(0.05),
+            inp_ms: Some(180.0),
+        })
+    );
+    assert_eq!(
+        pages[&format!("{SITE}/quiet")].field_data,
+        Some(FieldData {
+            scope: FieldDataScope::Origin,
+            lcp_ms: Some(3200.0),
+            cls: Some(0.12),
+            inp_ms: None,
+        })
+    );
+    assert!(pages[&format!("{SITE}/broken")].field_data.is_none());
+    assert!(pages[&format!("{SITE}/logo.png")].field_data.is_none());
+}
+
+#[test]
+fn test_field_data_is_serialized_in_json_report() {
+    let (_, mut page) = page("/popular", "text/html");
+    page.field_data = Some(FieldData {
+        scope: FieldDataScope::Origin,
+        lcp_ms: Some(2500.0),
+        cls: Some(0.1),
+        inp_ms: None,
+    });
+
+    let json = serde_json::to_value(&page).unwrap();
+    assert_eq!(json["field_data"]["scope"], "origin");
+    assert_eq!(json["field_data"]["lcp_ms"], 2500.0);
+    assert!(json["field_data"]["inp_ms"].is_null());
+}
@@ -0,0 +1,837 @@
+mod server;
+
+use scoutly::cli::LinkCheckScope;
+use scoutly::config::Config;
+use scoutly::daemon::api::{ApiScope, ApiToken};
+use scoutly::daemon::schedule::Scheduler;
+use scoutly::daemon::watch::{prepare_jobs_dir, scan_jobs_dir};
+use scoutly::daemon::{self, DaemonOptions, JobQueue, JobSource, JobStatus, api, spawn_workers};
+use serde_json::{Value, json};
+use server::get_test_server_url;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::tempdir;
+
+async fn serve_api(queue: Arc<JobQueue>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, api::router(api::ApiState::new(queue)))
+            .await
+            .unwrap();
+    });
+    format!("http://{address}")
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_daemon_runs_submitted_job_and_archives_report() {
+    let base_url = get_test_server_url().await;
+    let archive = tempdir().unwrap();
+    let queue = Arc::new(JobQueue::new());
+    spawn_workers(queue.clone(), 1, archive.path().to_path_buf());
+    let api_url = serve_api(queue).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{api_url}/jobs"))
+        .json(&json!({ "url": base_url, "depth": 0, "check_links": "none" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    let job: Value = response.json().await.unwrap();
+    let id = job["id"].as_u64().unwrap();
+    assert_eq!(job["status"], "queued");
+
+    let mut job = job;
+    for _ in 0..200 {
+        job = client
+            .get(format!("{api_url}/jobs/{id}"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if job["status"] == "completed" || job["status"] == "failed" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(job["status"], "completed", "job did not complete: {job}");
+    assert_eq!(job["summary"]["total_pages"], 1);
+
+    let report_path = job["report_path"].as_str().unwrap();
+    assert!(report_path.starts_with(&archive.path().display().to_string()));
+    assert!(fs::metadata(report_path).is_ok());
+
+    let report: Value = client
+        .get(format!("{api_url}/jobs/{id}/report"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(report["start_url"], job["url"]);
+
+    let jobs: Value = client
+        .get(format!("{api_url}/jobs"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(jobs.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_daemon_api_rejects_invalid_jobs() {
+    let api_url = serve_api(Arc::new(JobQueue::new())).await;
+    let client = reqwest::Client::new();
+
+    let missing_url = client
+        .post(format!("{api_url}/jobs"))
+        .json(&json!({ "depth": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing_url.status(), 400);
+    let body: Value = missing_url.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("url"));
+
+    let not_found = client
+        .get(format!("{api_url}/jobs/42"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(not_found.status(), 404);
+}
+
+#[tokio::test]
+async fn test_daemon_api_refuses_jobs_that_touch_the_server() {
+    let queue = Arc::new(JobQueue::new());
+    let api_url = serve_api(queue.clone()).await;
+    let client = reqwest::Client::new();
+
+    for field in [
+        json!({ "scripts": ["/etc/scoutly/evil.rhai"] }),
+        json!({ "save": "/tmp/overwritten.json" }),
+        json!({ "cookies_file": "/root/cookies.txt" }),
+        json!({ "notify_url": "http://169.254.169.254/" }),
+    ] {
+        let mut job = json!({ "url": "https://example.com", "depth": 1 });
+        job.as_object_mut()
+            .unwrap()
+            .extend(field.as_object().unwrap().clone());
+        let name = field.as_object().unwrap().keys().next().unwrap().clone();
+
+        let response = client
+            .post(format!("{api_url}/jobs"))
+            .json(&job)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400, "{name} should be refused");
+        let body: Value = response.json().await.unwrap();
+        assert!(body["error"].as_str().unwrap().contains(&name));
+    }
+    assert!(queue.list().is_empty());
+
+    let allowed = client
+        .post(format!("{api_url}/jobs"))
+        .json(&json!({
+            "url": "https://example.com",
+            "preset": "quick",
+            "max_bandwidth": "5MB",
+            "check_links": "none",
+            "priority": 3
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(allowed.status(), 202);
+}
+
+#[test]
+fn test_report_unavailable_until_job_finishes() {
+    let queue = JobQueue::new();
+    let job = queue
+        .submit(
+            Config {
+                url: Some("https://example.com".to_string()),
+                ..Default::default()
+            },
+            JobSource::Api,
+        )
+        .unwrap();
+
+    assert_eq!(job.status, JobStatus::Queued);
+    assert_eq!(queue.get(job.id).unwrap().report_path, None);
+    assert!(
+        queue
+            .submit(
+                Config {
+                    url: Some("ftp://example.com".to_string()),
+                    ..Default::default()
+                },
+                JobSource::Api,
+            )
+            .is_err()
+    );
+    assert_eq!(queue.list().len(), 1);
+}
+
+#[test]
+fn test_scan_jobs_dir_queues_valid_files_and_moves_them() {
+    let dir = tempdir().unwrap();
+    prepare_jobs_dir(dir.path()).unwrap();
+    fs::write(
+        dir.path().join("site.toml"),
+        "url = \"https://example.com\"\ndepth = 1\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("broken.json"), "{ not json").unwrap();
+    fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+    let queue = JobQueue::new();
+    assert_eq!(scan_jobs_dir(&queue, dir.path()).unwrap(), 1);
+
+    let jobs = queue.list();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].url, "https://example.com");
+    assert!(matches!(&jobs[0].source, JobSource::File(path) if path.ends_with("site.toml")));
+
+    assert!(dir.path().join("accepted/site.toml").exists());
+    assert!(dir.path().join("rejected/broken.json").exists());
+    assert!(dir.path().join("notes.txt").exists());
+
+    // Processed files are not queued a second time
+    assert_eq!(scan_jobs_dir(&queue, dir.path()).unwrap(), 0);
+}
+
+#[test]
+fn test_scheduler_queues_sites_when_their_schedule_is_due() {
+    let at = |time: &str| {
+        chrono::DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    };
+    let dir = tempdir().unwrap();
+    let site = dir.path().join("site.toml");
+    fs::write(
+        &site,
+        "url = \"https://example.com\"\nschedule = \"0 3 * * *\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("unscheduled.toml"),
+        "url = \"https://example.org\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("never.toml"),
+        "url = \"https://example.net\"\nschedule = \"0 0 31 2 *\"\n",
+    )
+    .unwrap();
+
+    let queue = JobQueue::new();
+    let mut scheduler = Scheduler::new();
+    assert_eq!(
+        scheduler
+            .tick(&queue, dir.path(), at("2026-03-10T02:00:00Z"))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        scheduler.next_runs().into_values().collect::<Vec<_>>(),
+        [at("2026-03-10T03:00:00Z")]
+    );
+
+    assert_eq!(
+        scheduler
+            .tick(&queue, dir.path(), at("2026-03-10T03:00:30Z"))
+            .unwrap(),
+        1
+    );
+    let jobs = queue.list();
+    assert_eq!(jobs[0].url, "https://example.com");
+    assert!(matches!(&jobs[0].source, JobSource::Schedule(path) if path.ends_with("site.toml")));
+
+    // Not again until tomorrow
+    assert_eq!(
+        scheduler
+            .tick(&queue, dir.path(), at("2026-03-10T03:01:00Z"))
+            .unwrap(),
+        0
+    );
+
+    // A changed schedule takes effect on the next tick
+    fs::write(
+        &site,
+        "url = \"https://example.com\"\nschedule = \"*/5 * * * *\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        scheduler
+            .tick(&queue, dir.path(), at("2026-03-10T03:02:00Z"))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        scheduler
+            .tick(&queue, dir.path(), at("2026-03-10T03:05:00Z"))
+            .unwrap(),
+        1
+    );
+
+    fs::remove_file(&site).unwrap();
+    scheduler
+        .tick(&queue, dir.path(), at("2026-03-10T03:10:00Z"))
+        .unwrap();
+    assert!(scheduler.next_runs().is_empty());
+    assert_eq!(queue.list().len(), 2);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_daemon_notifies_webhook_when_errors_or_warnings_increase() {
+    let base_url = get_test_server_url().await;
+    let notifications = Arc::new(Mutex::new(Vec::<Value>::new()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let webhook_url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let received = notifications.clone();
+    tokio::spawn(async move {
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |axum::Json(body): axum::Json<Value>| {
+                let received = received.clone();
+                async move {
+                    received.lock().unwrap().push(body);
+                }
+            }),
+        );
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let archive = tempdir().unwrap();
+    let queue = Arc::new(JobQueue::new());
+    spawn_workers(queue.clone(), 1, archive.path().to_path_buf());
+    let job = |depth: usize| Config {
+        url: Some(base_url.clone()),
+        depth: Some(depth),
+        check_links: Some(LinkCheckScope::None),
+        notify_url: Some(webhook_url.clone()),
+        ..Default::default()
+    };
+    // The start page alone, then the whole fixture site with many more issues
+    queue.submit(job(0), JobSource::Api).unwrap();
+    let second = queue.submit(job(1), JobSource::Api).unwrap();
+
+    for _ in 0..400 {
+        if !notifications.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let regression = queue.regression(second.id).expect("second crawl regressed");
+    assert_eq!(regression.previous_job_id, second.id - 1);
+    assert!(regression.warnings > regression.previous_warnings);
+    assert_eq!(queue.regression(second.id - 1), None);
+
+    let notifications = notifications.lock().unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0]["job_id"], second.id);
+    assert_eq!(notifications[0]["url"], base_url);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_daemon_serves_named_sites_with_their_own_token_and_history() {
+    let base_url = get_test_server_url().await;
+    let sites = tempdir().unwrap();
+    fs::write(
+        sites.path().join("docs.toml"),
+        format!(
+            "url = \"{base_url}\"\ndepth = 0\ncheck_links = \"none\"\napi_token = \"docs-secret\"\n"
+        ),
+    )
+    .unwrap();
+    fs::write(
+        sites.path().join("blog.toml"),
+        format!("url = \"{base_url}\"\ndepth = 0\ncheck_links = \"none\"\n"),
+    )
+    .unwrap();
+
+    let archive = tempdir().unwrap();
+    let queue = Arc::new(JobQueue::new());
+    spawn_workers(queue.clone(), 1, archive.path().to_path_buf());
+    let mut state = api::ApiState::new(queue.clone());
+    state.sites_dir = Some(sites.path().to_path_buf());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let api_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, api::router(state)).await.unwrap();
+    });
+    let client = reqwest::Client::new();
+
+    let sites_list: Value = client
+        .get(format!("{api_url}/sites"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(sites_list[0]["name"], "blog");
+    assert_eq!(sites_list[1]["name"], "docs");
+    assert!(sites_list[1].get("api_token").is_none());
+
+    let docs_jobs = format!("{api_url}/sites/docs/jobs");
+    assert_eq!(client.post(&docs_jobs).send().await.unwrap().status(), 401);
+    let wrong_token = client
+        .post(&docs_jobs)
+        .bearer_auth("nope")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(wrong_token.status(), 401);
+    let missing = client
+        .post(format!("{api_url}/sites/missing/jobs"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), 404);
+
+    let response = client
+        .post(&docs_jobs)
+        .bearer_auth("docs-secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    let job: Value = response.json().await.unwrap();
+    let id = job["id"].as_u64().unwrap();
+    assert_eq!(job["site"], "docs");
+
+    // The job belongs to the docs site alone
+    let global: Value = client
+        .get(format!("{api_url}/jobs"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(global, json!([]));
+    let other_site = client
+        .get(format!("{api_url}/sites/blog/jobs/{id}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(other_site.status(), 404);
+
+    let mut job = job;
+    for _ in 0..200 {
+        job = client
+            .get(format!("{docs_jobs}/{id}"))
+            .bearer_auth("docs-secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if job["status"] == "completed" || job["status"] == "failed" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(job["status"], "completed", "job: {job}");
+    let report_path = std::path::PathBuf::from(job["report_path"].as_str().unwrap());
+    assert_eq!(report_path.parent().unwrap(), archive.path().join("docs"));
+
+    let report = client
+        .get(format!("{docs_jobs}/{id}/report"))
+        .bearer_auth("docs-secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(report.status(), 200);
+    let global_report = client
+        .get(format!("{api_url}/jobs/{id}/report"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(global_report.status(), 404);
+}
+
+#[tokio::test]
+async fn test_daemon_api_requires_tokens_with_the_right_scope() {
+    let mut state = api::ApiState::new(Arc::new(JobQueue::new()));
+    state.tokens = vec![
+        ApiToken {
+            token: "ci".to_string(),
+            scopes: vec![ApiScope::Crawl, ApiScope::Reports],
+        },
+        ApiToken {
+            token: "dashboard".to_string(),
+            scopes: vec![ApiScope::Reports],
+        },
+    ];
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let api_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, api::router(state)).await.unwrap();
+    });
+    let client = reqwest::Client::new();
+    let submit = |token: Option<&str>| {
+        let request = client
+            .post(format!("{api_url}/jobs"))
+            .json(&json!({ "url": "https://example.com" }));
+        match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    };
+
+    let health = client
+        .get(format!("{api_url}/health"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(health.status(), 200);
+
+    assert_eq!(submit(None).send().await.unwrap().status(), 401);
+    assert_eq!(submit(Some("unknown")).send().await.unwrap().status(), 401);
+    assert_eq!(submit(Some("c")).send().await.unwrap().status(), 401);
+    assert_eq!(submit(Some("ci-extra")).send().await.unwrap().status(), 401);
+    let forbidden = submit(Some("dashboard")).send().await.unwrap();
+    assert_eq!(forbidden.status(), 403);
+    let error: Value = forbidden.json().await.unwrap();
+    assert!(error["error"].as_str().unwrap().contains("crawl"));
+    assert_eq!(submit(Some("ci")).send().await.unwrap().status(), 202);
+
+    let jobs_url = format!("{api_url}/jobs/1");
+    assert_eq!(client.get(&jobs_url).send().await.unwrap().status(), 401);
+    let job = client
+        .get(&jobs_url)
+        .bearer_auth("dashboard")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(job.status(), 200);
+}
+
+#[tokio::test]
+async fn test_daemon_refuses_to_serve_without_tokens_unless_no_auth() {
+    let archive = tempdir().unwrap();
+    let options = |api_tokens: Vec<ApiToken>, no_auth: bool| DaemonOptions {
+        listen: "127.0.0.1:0".parse().unwrap(),
+        workers: 1,
+        jobs_dir: None,
+        sites_dir: None,
+        archive_dir: archive.path().join("archive"),
+        poll_interval: Duration::from_secs(1),
+        api_tokens,
+        no_auth,
+    };
+
+    let error = daemon::run(options(Vec::new(), false)).await.unwrap_err();
+    assert!(error.to_string().contains("--no-auth"));
+    assert!(!archive.path().join("archive").exists());
+
+    // With tokens, or with the explicit opt-in, the service starts and keeps running
+    let with_tokens = vec![ApiToken {
+        token: "ci".to_string(),
+        scopes: vec![ApiScope::Crawl],
+    }];
+    for options in [options(with_tokens, false), options(Vec::new(), true)] {
+        let serving = tokio::time::timeout(Duration::from_millis(200), daemon::run(options)).await;
+        assert!(serving.is_err(), "serve should still be running");
+    }
+}
+
+#[tokio::test]
+async fn test_daemon_serves_openapi_document() {
+    let mut state = api::ApiState::new(Arc::new(JobQueue::new()));
+    state.tokens = vec![ApiToken {
+        token: "ci".to_string(),
+        scopes: vec![ApiScope::Crawl],
+    }];
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let api_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, api::router(state)).await.unwrap();
+    });
+
+    // Readable without a token
+    let response = reqwest::get(format!("{api_url}/openapi.json"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let document: Value = response.json().await.unwrap();
+
+    assert!(document["openapi"].as_str().unwrap().starts_with("3."));
+    let mut paths: Vec<&str> = document["paths"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    paths.sort_unstable();
+    assert_eq!(
+        paths,
+        [
+            "/health",
+            "/jobs",
+            "/jobs/{id}",
+            "/jobs/{id}/cancel",
+            "/jobs/{id}/report",
+            "/sites",
+            "/sites/{name}",
+            "/sites/{name}/jobs",
+            "/sites/{name}/jobs/{id}",
+            "/sites/{name}/jobs/{id}/cancel",
+            "/sites/{name}/jobs/{id}/report"
+        ]
+    );
+    assert!(document["paths"]["/jobs"]["post"]["responses"]["202"].is_object());
+    let schemas = &document["components"]["schemas"];
+    assert!(schemas["Job"]["properties"]["status"].is_object());
+    assert_eq!(
+        schemas["JobStatus"]["enum"],
+        json!(["queued", "running", "completed", "failed", "cancelled"])
+    );
+    assert_eq!(
+        document["components"]["securitySchemes"]["bearer_token"]["scheme"],
+        "bearer"
+    );
+}
+
+async fn wait_for_status(client: &reqwest::Client, url: &str, statuses: &[&str]) -> Value {
+    let mut job = Value::Null;
+    for _ in 0..200 {
+        job = client.get(url).send().await.unwrap().json().await.unwrap();
+        if statuses.iter().any(|status| job["status"] == *status) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    job
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_daemon_starts_higher_priority_jobs_first() {
+    let base_url = get_test_server_url().await;
+    let archive = tempdir().unwrap();
+    let queue = Arc::new(JobQueue::new());
+    let api_url = serve_api(queue.clone()).await;
+    let client = reqwest::Client::new();
+
+    let mut ids = Vec::new();
+    for priority in [0, 5, 0] {
+        let job: Value = client
+            .post(format!("{api_url}/jobs"))
+            .json(&json!({
+                "url": base_url,
+                "depth": 0,
+                "check_links": "none",
+                "priority": priority
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(job["priority"], priority);
+        ids.push(job["id"].as_u64().unwrap());
+    }
+
+    let bumped: Value = client
+        .patch(format!("{api_url}/jobs/{}", ids[2]))
+        .json(&json!({ "priority": 10 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(bumped["priority"], 10);
+
+    spawn_workers(queue.clone(), 1, archive.path().to_path_buf());
+    let mut started = Vec::new();
+    for id in &ids {
+        let job = wait_for_status(
+            &client,
+            &format!("{api_url}/jobs/{id}"),
+            &["completed", "failed"],
+        )
+        .await;
+        assert_eq!(job["status"], "completed", "job did not complete: {job}");
+        let started_at =
+            chrono::DateTime::parse_from_rfc3339(job["started_at"].as_str().unwrap()).unwrap();
+        started.push((started_at, *id));
+    }
+    started.sort();
+    let order: Vec<u64> = started.into_iter().map(|(_, id)| id).collect();
+    assert_eq!(order, [ids[2], ids[1], ids[0]]);
+
+    let finished = client
+        .patch(format!("{api_url}/jobs/{}", ids[0]))
+        .json(&json!({ "priority": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(finished.status(), 409);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_daemon_cancels_queued_and_running_jobs() {
+    let link_server_url = server::start_link_test_server().await;
+    let archive = tempdir().unwrap();
+    let queue = Arc::new(JobQueue::new());
+    let api_url = serve_api(queue.clone()).await;
+    let client = reqwest::Client::new();
+
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let job: Value = client
+            .post(format!("{api_url}/jobs"))
+            .json(&json!({
+                "url": format!("{link_server_url}/slow"),
+                "depth": 0,
+                "check_links": "none"
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        ids.push(job["id"].as_u64().unwrap());
+    }
+
+    let queued: Value = client
+        .post(format!("{api_url}/jobs/{}/cancel", ids[1]))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(queued["status"], "cancelled");
+    assert!(queued["finished_at"].is_string());
+
+    spawn_workers(queue.clone(), 1, archive.path().to_path_buf());
+    let running =
+        wait_for_status(&client, &format!("{api_url}/jobs/{}", ids[0]), &["running"]).await;
+    assert_eq!(running["status"], "running");
+
+    let cancelled = client
+        .post(format!("{api_url}/jobs/{}/cancel", ids[0]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(cancelled.status(), 200);
+
+    // The worker moves on instead of finishing the slow crawl
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    for id in &ids {
+        let job = queue.get(*id).unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert_eq!(job.report_path, None);
+    }
+
+    let again = client
+        .post(format!("{api_url}/jobs/{}/cancel", ids[0]))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(again.status(), 409);
+
+    let missing = client
+        .post(format!("{api_url}/jobs/42/cancel"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), 404);
+}
+
+async fn wait_for_job(queue: &JobQueue, id: u64, status: JobStatus) {
+    for _ in 0..200 {
+        if queue.get(id).is_some_and(|job| job.status == status) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("job {id} never reached {status:?}: {:?}", queue.get(id));
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_daemon_restores_the_job_queue_after_a_restart() {
+    let base_url = get_test_server_url().await;
+    let link_server_url = server::start_link_test_server().await;
+    let archive = tempdir().unwrap();
+    let queue_path = archive.path().join(daemon::QUEUE_FILE_NAME);
+    let queue = Arc::new(JobQueue::open(&queue_path).unwrap());
+    let job = |url: String| Config {
+        url: Some(url),
+        depth: Some(0),
+        check_links: Some(LinkCheckScope::None),
+        ..Default::default()
+    };
+
+    let slow = queue
+        .submit(job(format!("{link_server_url}/slow")), JobSource::Api)
+        .unwrap();
+    let next = queue.submit(job(base_url.clone()), JobSource::Api).unwrap();
+    let cancelled = queue.submit(job(base_url.clone()), JobSource::Api).unwrap();
+    queue.set_priority(slow.id, 10).unwrap();
+    queue.set_priority(next.id, 5).unwrap();
+    queue.cancel(cancelled.id).unwrap();
+    spawn_workers(queue.clone(), 1, archive.path().to_path_buf());
+    wait_for_job(&queue, slow.id, JobStatus::Running).await;
+
+    // The file as left by a service that stopped in the middle of the slow crawl
+    let restart_path = archive.path().join("restart.json");
+    fs::copy(&queue_path, &restart_path).unwrap();
+    queue.cancel(slow.id).unwrap();
+    queue.cancel(next.id).unwrap();
+
+    let restarted = Arc::new(JobQueue::open(&restart_path).unwrap());
+    let interrupted = restarted.get(slow.id).unwrap();
+    assert_eq!(interrupted.status, JobStatus::Queued);
+    assert_eq!(interrupted.started_at, None);
+    assert_eq!(interrupted.priority, 10);
+    assert_eq!(restarted.get(next.id).unwrap().priority, 5);
+    assert_eq!(
+        restarted.get(cancelled.id).unwrap().status,
+        JobStatus::Cancelled
+    );
+    let submitted = restarted
+        .submit(job(base_url.clone()), JobSource::Api)
+        .unwrap();
+    assert_eq!(submitted.id, cancelled.id + 1);
+    restarted.cancel(submitted.id).unwrap();
+
+    restarted.cancel(slow.id).unwrap();
+    spawn_workers(restarted.clone(), 1, archive.path().to_path_buf());
+    wait_for_job(&restarted, next.id, JobStatus::Completed).await;
+
+    // Completed crawls stay in the history later crawls are compared with
+    let reopened = JobQueue::open(&restart_path).unwrap();
+    let completed = reopened.get(next.id).unwrap();
+    assert_eq!(completed.status, JobStatus::Completed);
+    assert_eq!(completed.summary.unwrap().total_pages, 1);
+    assert!(fs::metadata(completed.report_path.unwrap()).is_ok());
+    assert_eq!(reopened.list().len(), 4);
+}
@@ -0,0 +1,117 @@
+mod server;
+
+use scoutly::fixes::{FixReport, FixStatus, ListedIssue, read_issue_list};
+use scoutly::models::IssueType;
+use server::{get_test_server_url, start_link_test_server};
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn test_read_issue_list_reads_csv_rows_and_fills_fingerprints() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("issues.csv");
+    fs::write(
+        &path,
+        "page_url,issue_type,offending_url\nhttps://example.com/about,MissingTitle\nhttps://example.com/,BrokenLink,https://example.com/gone\n",
+    )
+    .unwrap();
+
+    let issues = read_issue_list(&path).unwrap();
+
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].issue_type, IssueType::MissingTitle);
+    assert_eq!(issues[0].offending_url, None);
+    assert_eq!(
+        issues[1].offending_url.as_deref(),
+        Some("https://example.com/gone")
+    );
+    assert_eq!(issues[1].fingerprint.len(), 16);
+    assert_ne!(issues[0].fingerprint, issues[1].fingerprint);
+
+    fs::write(&path, "https://example.com/,NotAnIssue\n").unwrap();
+    let error = read_issue_list(&path).unwrap_err();
+    assert!(error.to_string().contains("unknown issue type"));
+}
+
+#[test]
+fn test_read_issue_list_accepts_its_own_json_report() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("fixes.json");
+    let issue = ListedIssue {
+        page_url: "https://example.com/".to_string(),
+        issue_type: IssueType::MissingH1,
+        offending_url: None,
+        fingerprint: "0123456789abcdef".to_string(),
+        message: Some("Page is missing an H1 tag".to_string()),
+    };
+    let report = FixReport::new(
+        vec![issue.clone()],
+        &serde_json::from_value(serde_json::json!({
+            "start_url": "https://example.com/",
+            "pages": {},
+            "summary": {
+                "total_pages": 0,
+                "total_links": 0,
+                "broken_links": 0,
+                "errors": 0,
+                "warnings": 0,
+                "infos": 0
+            },
+            "timestamp": "2026-01-01T00:00:00Z"
+        }))
+        .unwrap(),
+    );
+    assert_eq!(report.checks[0].status, FixStatus::Unverified);
+    fs::write(&path, serde_json::to_string(&report).unwrap()).unwrap();
+
+    assert_eq!(read_issue_list(&path).unwrap(), vec![issue]);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_verify_fixes_reports_resolved_persisting_and_unverified_issues() {
+    let link_server_url = start_link_test_server().await;
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let issues_path = dir.path().join("issues.csv");
+    fs::write(
+        &issues_path,
+        format!(
+            "{base_url}/missing-title.html,MissingTitle\n{base_url}/page-content.html,MissingTitle\n{link_server_url}/not-found,MissingTitle\n"
+        ),
+    )
+    .unwrap();
+
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_scoutly"))
+            .current_dir(dir.path())
+            .args([
+                "verify-fixes",
+                issues_path.to_str().unwrap(),
+                "--output",
+                "json",
+                "--respect-robots-txt",
+                "false",
+            ])
+            .output()
+            .expect("Failed to run binary")
+    })
+    .await
+    .expect("Binary execution task should complete");
+
+    assert!(
+        !output.status.success(),
+        "A persisting issue should fail the run"
+    );
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let statuses: Vec<&str> = report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|check| check["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["persists", "resolved", "unverified"]);
+    assert_eq!(report["persisting"], 1);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 of 3 issue(s) persist"));
+}
@@ -0,0 +1,157 @@
+mod server;
+
+use clap::Parser;
+use scoutly::cli::{Cli, Command, DEFAULT_HEALTH_MIN_TLS_DAYS};
+use scoutly::health::{HealthCheckKind, HealthThresholds, probe};
+use scoutly::http_client::TlsOptions;
+use server::{start_link_test_server, start_tls_test_server};
+use std::path::Path;
+use std::time::Duration;
+
+const THRESHOLDS: HealthThresholds = HealthThresholds {
+    max_ttfb: Duration::from_secs(5),
+    min_tls_days: 14,
+};
+
+fn failed(report: &scoutly::health::HealthReport) -> Vec<HealthCheckKind> {
+    report
+        .checks
+        .iter()
+        .filter(|check| !check.passed)
+        .map(|check| check.kind)
+        .collect()
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_probe_passes_a_healthy_page() {
+    let base_url = start_link_test_server().await;
+
+    let report = probe(
+        &format!("{base_url}/etag-page"),
+        10,
+        &TlsOptions::default(),
+        &THRESHOLDS,
+    )
+    .await
+    .unwrap();
+
+    assert!(report.passed, "{report:?}");
+    assert_eq!(report.status_code, Some(200));
+    assert!(report.ttfb_ms.is_some());
+    assert_eq!(
+        report.headers.get("content-type").map(String::as_str),
+        Some("text/html")
+    );
+    // The test server has no robots.txt, which crawlers treat as allowing everything
+    assert_eq!(report.robots_txt_status, Some(404));
+    let kinds: Vec<_> = report.checks.iter().map(|check| check.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            HealthCheckKind::Status,
+            HealthCheckKind::Ttfb,
+            HealthCheckKind::Headers,
+            HealthCheckKind::Robots,
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_probe_fails_error_status_and_noindex_header() {
+    let base_url = start_link_test_server().await;
+    let tls = TlsOptions::default();
+
+    let server_error = probe(&format!("{base_url}/server-error"), 10, &tls, &THRESHOLDS)
+        .await
+        .unwrap();
+    let noindex = probe(&format!("{base_url}/x-robots-tag"), 10, &tls, &THRESHOLDS)
+        .await
+        .unwrap();
+
+    assert!(!server_error.passed);
+    assert_eq!(server_error.status_code, Some(500));
+    assert!(failed(&server_error).contains(&HealthCheckKind::Status));
+    assert!(!noindex.passed);
+    assert_eq!(failed(&noindex), vec![HealthCheckKind::Headers]);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_probe_fails_slow_and_unreachable_pages() {
+    let base_url = start_link_test_server().await;
+    let tls = TlsOptions::default();
+    let impatient = HealthThresholds {
+        max_ttfb: Duration::from_millis(100),
+        ..THRESHOLDS
+    };
+
+    let slow = probe(&format!("{base_url}/slow"), 10, &tls, &impatient)
+        .await
+        .unwrap();
+    let unreachable = probe("http://127.0.0.1:9/", 2, &tls, &THRESHOLDS)
+        .await
+        .unwrap();
+
+    assert_eq!(failed(&slow), vec![HealthCheckKind::Ttfb]);
+    assert!(!unreachable.passed);
+    assert_eq!(unreachable.status_code, None);
+    assert_eq!(failed(&unreachable), vec![HealthCheckKind::Status]);
+}
+
+#[tokio::test]
+async fn test_probe_reads_certificate_expiry() {
+    let base_url = start_tls_test_server().await;
+    let tls = TlsOptions::new(false, Some(Path::new("tests/static/tls/ca.pem"))).unwrap();
+
+    let report = probe(&base_url, 10, &tls, &THRESHOLDS).await.unwrap();
+    let strict = probe(
+        &base_url,
+        10,
+        &tls,
+        &HealthThresholds {
+            min_tls_days: 100 * 366,
+            ..THRESHOLDS
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        report.tls_expires_at.as_deref(),
+        Some("2126-09-24T04:45:52+00:00")
+    );
+    assert!(report.tls_days_left.unwrap() > 30_000);
+    assert!(
+        report
+            .checks
+            .iter()
+            .any(|check| check.kind == HealthCheckKind::Tls && check.passed)
+    );
+    assert!(failed(&strict).contains(&HealthCheckKind::Tls));
+}
+
+#[test]
+fn test_health_accepts_thresholds_and_output_after_subcommand() {
+    let cli = Cli::try_parse_from([
+        "scoutly",
+        "health",
+        "https://www.example.com",
+        "--max-ttfb-ms",
+        "500",
+        "--output",
+        "json",
+    ])
+    .unwrap();
+
+    assert!(cli.output.is_some_and(|output| output.is_json()));
+    assert_eq!(
+        cli.command,
+        Some(Command::Health {
+            url: "https://www.example.com".to_string(),
+            max_ttfb_ms: 500,
+            min_tls_days: DEFAULT_HEALTH_MIN_TLS_DAYS,
+        })
+    );
+}
@@ -0,0 +1,142 @@
+mod server;
+
+use scoutly::crawler::{Crawler, CrawlerConfig};
+use scoutly::hreflang::HreflangChecker;
+use scoutly::models::{HreflangLink, IssueType, LinkSkipReason};
+use scoutly::rules::PathRules;
+use server::start_link_test_server;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_hreflang_checker_flags_broken_redirecting_and_one_way_alternates() {
+    let link_server_url = start_link_test_server().await;
+    let start_url = format!("{link_server_url}/hreflang-en");
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 10,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    HreflangChecker::new(4)
+        .unwrap()
+        .check_all(&mut crawler.pages, &PathRules::default())
+        .await
+        .unwrap();
+
+    let mut issues: Vec<_> = crawler.pages[&start_url]
+        .issues
+        .iter()
+        .filter(|issue| {
+            matches!(
+                issue.issue_type,
+                IssueType::HreflangBrokenTarget
+                    | IssueType::HreflangRedirectTarget
+                    | IssueType::HreflangMissingReturnLink
+            )
+        })
+        .map(|issue| {
+            (
+                issue.issue_type,
+                issue.offending_url.clone().unwrap_or_default(),
+                issue.actual.clone().unwrap_or_default(),
+            )
+        })
+        .collect();
+    issues.sort_by(|left, right| left.1.cmp(&right.1));
+
+    assert_eq!(
+        issues,
+        vec![
+            (
+                IssueType::HreflangBrokenTarget,
+                format!("{link_server_url}/not-found"),
+                "404".to_string()
+            ),
+            (
+                IssueType::HreflangMissingReturnLink,
+                format!("{link_server_url}/ok"),
+                "no hreflang alternates".to_string()
+            ),
+            (
+                IssueType::HreflangRedirectTarget,
+                format!("{link_server_url}/redirect"),
+                format!("301 to {link_server_url}/ok")
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_hreflang_checker_skips_private_alternates_on_other_hosts() {
+    let link_server_url = start_link_test_server().await;
+    let start_url = format!("{link_server_url}/hreflang-en");
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 10,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+    let port = link_server_url.rsplit(':').next().unwrap();
+    let resolves_privately = format!("http://localhost:{port}/not-found");
+    let names_private_address = "http://10.255.255.1/fr".to_string();
+    let page = crawler.pages.get_mut(&start_url).unwrap();
+    for (hreflang, url) in [("de", &resolves_privately), ("fr", &names_private_address)] {
+        page.alternates.push(HreflangLink {
+            hreflang: hreflang.to_string(),
+            url: url.clone(),
+            skip_reason: None,
+        });
+    }
+
+    let mut checker = HreflangChecker::new(4).unwrap();
+    checker.set_block_private_ips(true).unwrap();
+    checker
+        .check_all(&mut crawler.pages, &PathRules::default())
+        .await
+        .unwrap();
+
+    let page = &crawler.pages[&start_url];
+    for url in [&resolves_privately, &names_private_address] {
+        let alternate = page
+            .alternates
+            .iter()
+            .find(|alternate| &alternate.url == url)
+            .unwrap();
+        assert_eq!(alternate.skip_reason, Some(LinkSkipReason::PrivateAddress));
+        assert!(
+            !page
+                .issues
+                .iter()
+                .any(|issue| issue.offending_url.as_ref() == Some(url))
+        );
+    }
+    // Alternates on the crawled host are still checked
+    assert!(page.issues.iter().any(|issue| {
+        issue.issue_type == IssueType::HreflangBrokenTarget
+            && issue.offending_url == Some(format!("{link_server_url}/not-found"))
+    }));
+}
@@ -1,10 +1,11 @@
 mod server;
 
+use scoutly::cli::LinkCheckScope;
 use scoutly::crawler::{Crawler, CrawlerConfig};
 use scoutly::link_checker::LinkChecker;
-use scoutly::models::{IssueSeverity, IssueType};
+use scoutly::models::{HtmlContentTypes, IssueSeverity, IssueType};
 use scoutly::runtime::RunEvent;
-use server::{get_test_server_url, link_test_server_url, start_link_test_server};
+use server::{counted_requests, get_test_server_url, link_test_server_url, start_link_test_server};
 use tokio::sync::mpsc::unbounded_channel;
 
 #[tokio::test]
@@ -15,7 +16,7 @@ async fn test_link_checker() {
     let base_url = get_test_server_url().await;
 
     let mut crawler = Crawler::new(
-        &base_url,
+        &[&base_url],
         CrawlerConfig {
             max_depth: 2,
             max_pages: 50,
@@ -24,6 +25,9 @@ async fn test_link_checker() {
             requests_per_second: None,
             concurrent_requests: 1,
             respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
         },
     )
     .expect("Failed to create crawler");
@@ -119,6 +123,12 @@ async fn test_link_checker() {
             IssueSeverity::Error,
             "Broken link issue should have Error severity"
         );
+        assert_eq!(
+            issues[0].offending_url.as_deref(),
+            Some(format!("{}/not-found", link_server_url).as_str()),
+            "Broken link issue should point at the offending link"
+        );
+        assert_eq!(issues[0].actual.as_deref(), Some("HTTP 404"));
 
         // Check 500 link
         let server_error_link = page
@@ -387,7 +397,7 @@ async fn test_link_checker() {
     {
         // Create a new crawler and check with ignore_redirects = true
         let mut crawler = Crawler::new(
-            &base_url,
+            &[&base_url],
             CrawlerConfig {
                 max_depth: 2,
                 max_pages: 50,
@@ -396,6 +406,9 @@ async fn test_link_checker() {
                 requests_per_second: None,
                 concurrent_requests: 1,
                 respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
             },
         )
         .expect("Failed to create crawler");
@@ -451,7 +464,7 @@ async fn test_link_checker_skips_special_scheme_links() {
     let start_url = format!("{}/links-special-schemes.html", get_test_server_url().await);
 
     let mut crawler = Crawler::new(
-        &start_url,
+        &[&start_url],
         CrawlerConfig {
             max_depth: 0,
             max_pages: 10,
@@ -460,6 +473,9 @@ async fn test_link_checker_skips_special_scheme_links() {
             requests_per_second: None,
             concurrent_requests: 1,
             respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
         },
     )
     .expect("Failed to create crawler");
@@ -519,7 +535,7 @@ async fn test_link_checker_emits_live_progress_with_current_url() {
     let base_url = get_test_server_url().await;
 
     let mut crawler = Crawler::new(
-        &base_url,
+        &[&base_url],
         CrawlerConfig {
             max_depth: 2,
             max_pages: 50,
@@ -528,6 +544,9 @@ async fn test_link_checker_emits_live_progress_with_current_url() {
             requests_per_second: None,
             concurrent_requests: 1,
             respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
         },
     )
     .expect("Failed to create crawler");
@@ -591,7 +610,7 @@ async fn test_link_checker_default() {
     let base_url = get_test_server_url().await;
 
     let mut crawler = Crawler::new(
-        &base_url,
+        &[&base_url],
         CrawlerConfig {
             max_depth: 2,
             max_pages: 50,
@@ -600,6 +619,9 @@ async fn test_link_checker_default() {
             requests_per_second: None,
             concurrent_requests: 1,
             respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
         },
     )
     .expect("Failed to create crawler");
@@ -631,3 +653,1144 @@ async fn test_link_checker_default() {
         "Default checker should work the same as new()"
     );
 }
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_respects_check_scope() {
+    let link_server_url = start_link_test_server().await;
+    let start_url = format!("{}/links-mixed.html", get_test_server_url().await);
+    let base_url = get_test_server_url().await;
+
+    for (scope, expect_external_checked) in [
+        (LinkCheckScope::Internal, false),
+        (LinkCheckScope::External, true),
+        (LinkCheckScope::None, false),
+    ] {
+        let mut crawler = Crawler::new(
+            &[&base_url],
+            CrawlerConfig {
+                max_depth: 1,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler");
+
+        crawler.crawl().await.expect("Crawl failed");
+
+        let mut checker = LinkChecker::new();
+        checker.set_check_scope(scope);
+        checker
+            .check_all_links(&mut crawler.pages, false)
+            .await
+            .expect("Link checking failed");
+
+        let page = crawler
+            .pages
+            .get(&start_url)
+            .expect("links-mixed.html not found");
+        let external_link = page
+            .links
+            .iter()
+            .find(|link| link.url == format!("{}/not-found", link_server_url))
+            .expect("External broken link not found");
+        assert_eq!(
+            external_link.status_code.is_some(),
+            expect_external_checked,
+            "External link check state should follow the {scope:?} scope"
+        );
+        assert_eq!(
+            page.issues
+                .iter()
+                .any(|issue| issue.issue_type == IssueType::BrokenLink),
+            expect_external_checked,
+            "Broken-link issues should only be reported for in-scope links"
+        );
+
+        let home = crawler.pages.get(&base_url).expect("Home page not found");
+        let internal_link = home
+            .links
+            .iter()
+            .find(|link| link.url.ends_with("/non-existent-page.html"))
+            .expect("Internal broken link not found");
+        assert_eq!(
+            internal_link.status_code.is_some(),
+            scope == LinkCheckScope::Internal,
+            "Internal link check state should follow the {scope:?} scope"
+        );
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_applies_domain_rules_before_requests() {
+    let link_server_url = start_link_test_server().await;
+    let base_url = get_test_server_url().await;
+    let start_url = format!("{}/links-mixed.html", base_url);
+    let link_host = url::Url::parse(&link_server_url)
+        .unwrap()
+        .host_str()
+        .unwrap()
+        .to_string();
+
+    let mut crawler = Crawler::new(
+        &[&start_url],
+        CrawlerConfig {
+            max_depth: 0,
+            max_pages: 10,
+            follow_external: false,
+            keep_fragments: false,
+            requests_per_second: None,
+            concurrent_requests: 1,
+            respect_robots_txt: false,
+            audit_vary: false,
+            include: vec![],
+            exclude: vec![],
+        },
+    )
+    .expect("Failed to create crawler");
+    crawler.crawl().await.expect("Crawl failed");
+
+    // Never-check rules win over the default "all" scope
+    let mut pages = crawler.pages.clone();
+    let mut checker = LinkChecker::new();
+    checker.set_domain_rules(vec![link_host.clone()], vec![]);
+    assert!(checker.collect_links_to_check(&pages).unwrap().is_empty());
+    checker
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+    let page = pages.get(&start_url).expect("links-mixed.html not found");
+    assert!(
+        page.links.iter().all(|link| link.status_code.is_none()),
+        "Links on never-check domains should not be requested"
+    );
+    assert!(page.issues.is_empty());
+
+    // Always-check rules win over a restrictive scope
+    let mut pages = crawler.pages.clone();
+    let mut checker = LinkChecker::new();
+    checker.set_check_scope(LinkCheckScope::None);
+    checker.set_domain_rules(vec![], vec![format!("*.{link_host}")]);
+    checker
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+    let page = pages.get(&start_url).expect("links-mixed.html not found");
+    let broken = page
+        .links
+        .iter()
+        .find(|link| link.url == format!("{}/not-found", link_server_url))
+        .expect("Broken link not found");
+    assert_eq!(
+        broken.status_code,
+        Some(404),
+        "Links on always-check domains should be checked even when the scope excludes them"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_shares_results_across_fragment_variants() {
+    use scoutly::models::{Link, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let link_server_url = start_link_test_server().await;
+    let link = |suffix: &str| Link {
+        url: format!("{link_server_url}/counted{suffix}"),
+        text: suffix.to_string(),
+        is_external: true,
+        kind: Default::default(),
+        rel: None,
+        status_code: None,
+        redirected_url: None,
+        check_error: None,
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
+    };
+
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::new();
+    pages.insert(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: None,
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
+            links: vec![link("#intro"), link("#details"), link("")],
+            images: vec![],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    );
+
+    let requests_before = counted_requests();
+    LinkChecker::new()
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+
+    assert_eq!(
+        counted_requests() - requests_before,
+        1,
+        "URLs differing only by fragment should be requested once"
+    );
+
+    let page = pages.get(&page_url).unwrap();
+    for link in &page.links {
+        assert_eq!(
+            link.status_code,
+            Some(200),
+            "{} should share the result of its fragment-less URL",
+            link.url
+        );
+        assert_eq!(link.redirected_url, None);
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_reports_redirect_loops_with_their_cycle() {
+    use scoutly::models::{Link, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let link_server_url = start_link_test_server().await;
+    let link = |path: &str| Link {
+        url: format!("{link_server_url}{path}"),
+        text: path.to_string(),
+        is_external: true,
+        kind: Default::default(),
+        rel: None,
+        status_code: None,
+        redirected_url: None,
+        check_error: None,
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
+    };
+
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::from([(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: None,
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
+            links: vec![
+                link("/loop-a"),
+                link("/redirect-loop"),
+                link("/redirect-chain"),
+            ],
+            images: vec![],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    )]);
+
+    LinkChecker::new()
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+
+    let page = &pages[&page_url];
+    let mut loops: Vec<(String, String)> = page
+        .issues
+        .iter()
+        .filter(|issue| issue.issue_type == IssueType::RedirectLoop)
+        .map(|issue| {
+            assert_eq!(issue.severity, IssueSeverity::Error);
+            (
+                issue.offending_url.clone().unwrap(),
+                issue.actual.clone().unwrap(),
+            )
+        })
+        .collect();
+    loops.sort();
+    let loop_a = format!("{link_server_url}/loop-a");
+    let loop_b = format!("{link_server_url}/loop-b");
+    let self_loop = format!("{link_server_url}/redirect-loop");
+    assert_eq!(
+        loops,
+        vec![
+            (loop_a.clone(), format!("{loop_a} -> {loop_b} -> {loop_a}")),
+            (self_loop.clone(), format!("{self_loop} -> {self_loop}")),
+        ]
+    );
+
+    assert_eq!(page.links[0].check_error.as_deref(), Some("redirect loop"));
+    // A chain that ends somewhere is still an ordinary redirect
+    assert_eq!(page.links[2].status_code, Some(200));
+    assert_eq!(page.links[2].check_error, None);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_gentle_link_checker_waits_out_retry_after() {
+    use scoutly::models::{Link, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    let link_server_url = start_link_test_server().await;
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::from([(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: None,
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
+            links: vec![Link {
+                url: format!("{link_server_url}/retry-after"),
+                text: "Busy".to_string(),
+                is_external: true,
+                kind: Default::default(),
+                rel: None,
+                status_code: None,
+                redirected_url: None,
+                check_error: None,
+                skip_reason: None,
+                content_type: None,
+                content_length: None,
+            }],
+            images: vec![],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    )]);
+
+    let mut checker = LinkChecker::new();
+    checker.set_gentle(Some(Duration::from_millis(100)));
+    let started = Instant::now();
+    checker
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+
+    assert_eq!(pages[&page_url].links[0].status_code, Some(200));
+    assert!(pages[&page_url].issues.is_empty());
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_retries_transient_failures() {
+    use scoutly::http_client::RetryPolicy;
+    use scoutly::models::{Link, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    let link_server_url = start_link_test_server().await;
+    let check = |key: &'static str, retries: usize| {
+        let link_server_url = link_server_url.clone();
+        async move {
+            let page_url = "https://example.com/".to_string();
+            let mut pages = HashMap::from([(
+                page_url.clone(),
+                PageInfo {
+                    url: page_url.clone(),
+                    status_code: Some(200),
+                    content_type: Some("text/html".to_string()),
+                    last_modified: None,
+                    response_time_ms: None,
+                    content_length: None,
+                    redirect_chain: vec![],
+                    duplicate_of: None,
+                    title: None,
+                    meta_description: None,
+                    meta_robots: None,
+                    x_robots_tag: None,
+                    canonical: None,
+                    alternates: vec![],
+                    link_headers: Default::default(),
+                    vary: vec![],
+                    vary_observed: vec![],
+                    h1_tags: vec![],
+                    outline: vec![],
+                    aliases: vec![],
+                    links: vec![
+                        Link {
+                            url: format!("{link_server_url}/flaky/{key}"),
+                            text: "Flaky".to_string(),
+                            is_external: true,
+                            kind: Default::default(),
+                            rel: None,
+                            status_code: None,
+                            redirected_url: None,
+                            check_error: None,
+                            skip_reason: None,
+                            content_type: None,
+                            content_length: None,
+                        },
+                        Link {
+                            url: format!("{link_server_url}/not-found"),
+                            text: "Missing".to_string(),
+                            is_external: true,
+                            kind: Default::default(),
+                            rel: None,
+                            status_code: None,
+                            redirected_url: None,
+                            check_error: None,
+                            skip_reason: None,
+                            content_type: None,
+                            content_length: None,
+                        },
+                    ],
+                    images: vec![],
+                    weight: Default::default(),
+                    open_graph: OpenGraphTags::default(),
+                    content: Default::default(),
+                    issues: vec![],
+                    omitted_issues: vec![],
+                    crawl_depth: 0,
+                    source: Default::default(),
+                    screenshot: None,
+                    html_snapshot: None,
+                    field_data: None,
+                },
+            )]);
+
+            let mut checker = LinkChecker::new();
+            checker.set_retry_policy(RetryPolicy {
+                retries,
+                backoff: Duration::from_millis(10),
+                jitter: true,
+            });
+            checker
+                .check_all_links(&mut pages, false)
+                .await
+                .expect("Link checking failed");
+            pages
+                .remove(&page_url)
+                .unwrap()
+                .links
+                .iter()
+                .map(|link| link.status_code)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(check("link-once", 0).await, [Some(502), Some(404)]);
+    // 4xx responses aren't retried
+    assert_eq!(check("link-retried", 2).await, [Some(200), Some(404)]);
+}
+
+#[test]
+fn test_retry_policy_backs_off_exponentially_with_jitter() {
+    use scoutly::http_client::RetryPolicy;
+    use std::time::Duration;
+
+    let steady = RetryPolicy {
+        retries: 5,
+        backoff: Duration::from_millis(100),
+        jitter: false,
+    };
+    assert_eq!(steady.delay(0), Duration::from_millis(100));
+    assert_eq!(steady.delay(3), Duration::from_millis(800));
+    assert_eq!(steady.delay(40), Duration::from_secs(30));
+
+    let jittered = RetryPolicy {
+        jitter: true,
+        ..steady
+    };
+    for _ in 0..20 {
+        let delay = jittered.delay(2);
+        assert!(delay >= Duration::from_millis(200) && delay <= Duration::from_millis(400));
+    }
+}
+
+#[test]
+fn test_parse_retry_after_accepts_seconds_and_http_dates() {
+    use scoutly::http_client::parse_retry_after;
+    use std::time::Duration;
+
+    assert_eq!(parse_retry_after(" 30 "), Some(Duration::from_secs(30)));
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+        Some(Duration::ZERO)
+    );
+    let later = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+    let wait = parse_retry_after(&later).unwrap();
+    assert!(wait > Duration::from_secs(80) && wait <= Duration::from_secs(90));
+    assert_eq!(parse_retry_after("soon"), None);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_request_limits_are_shared_by_crawl_and_link_check() {
+    use scoutly::limits::{ByteSize, RequestLimits};
+    use scoutly::models::LinkSkipReason;
+    use std::sync::Arc;
+
+    let base_url = get_test_server_url().await;
+
+    for (limits, expected) in [
+        (
+            RequestLimits::new(Some(1), None),
+            "request limit of 1 reached",
+        ),
+        (
+            RequestLimits::new(None, Some(ByteSize(1))),
+            "bandwidth limit of 1B reached after 1 request(s)",
+        ),
+    ] {
+        let limits = Arc::new(limits);
+        let mut crawler = Crawler::new(
+            &[&base_url],
+            CrawlerConfig {
+                max_depth: 2,
+                max_pages: 50,
+                follow_external: false,
+                keep_fragments: false,
+                requests_per_second: None,
+                concurrent_requests: 1,
+                respect_robots_txt: false,
+                audit_vary: false,
+                include: vec![],
+                exclude: vec![],
+            },
+        )
+        .expect("Failed to create crawler");
+        crawler.set_request_limits(limits.clone()).unwrap();
+        crawler.crawl().await.expect("Crawl failed");
+
+        assert_eq!(crawler.pages.len(), 1, "Only the start page fits the limit");
+
+        let mut checker = LinkChecker::new();
+        checker
+            .set_request_limits(crawler.request_limits().unwrap())
+            .unwrap();
+        checker
+            .check_all_links(&mut crawler.pages, false)
+            .await
+            .expect("Link checking failed");
+
+        let home = crawler.pages.get(&base_url).expect("Home page not found");
+        assert!(!home.links.is_empty());
+        assert!(home.links.iter().all(|link| {
+            link.status_code.is_none() && link.skip_reason == Some(LinkSkipReason::RequestLimit)
+        }));
+        assert_eq!(limits.requests(), 1);
+        assert_eq!(limits.reached().as_deref(), Some(expected));
+    }
+}
+
+#[tokio::test]
+async fn test_link_checker_records_target_content_type_and_size() {
+    use scoutly::models::{Link, OpenGraphTags, PageInfo};
+    use scoutly::reporter::Reporter;
+    use std::collections::HashMap;
+
+    let link_server_url = start_link_test_server().await;
+    let link = |path: &str| Link {
+        url: format!("{link_server_url}{path}"),
+        text: path.to_string(),
+        is_external: true,
+        kind: Default::default(),
+        rel: None,
+        status_code: None,
+        redirected_url: None,
+        check_error: None,
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
+    };
+
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::new();
+    pages.insert(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: None,
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
+            links: vec![
+                link("/files/guide.pdf"),
+                link("/files/missing.zip"),
+                link("/not-found"),
+            ],
+            images: vec![],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    );
+
+    LinkChecker::new()
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+
+    let page = &pages[&page_url];
+    let pdf = &page.links[0];
+    assert_eq!(pdf.content_type.as_deref(), Some("application/pdf"));
+    assert_eq!(pdf.content_length, Some(2_500));
+    let html_content_types = HtmlContentTypes::default();
+    assert!(pdf.is_download(&html_content_types));
+    assert!(page.links[1].is_download(&html_content_types));
+    assert!(!page.links[2].is_download(&html_content_types));
+
+    let mut messages: Vec<&str> = page
+        .issues
+        .iter()
+        .filter(|issue| issue.issue_type == IssueType::BrokenLink)
+        .map(|issue| issue.message.as_str())
+        .collect();
+    messages.sort();
+    assert_eq!(
+        messages,
+        [
+            format!("Broken download: {link_server_url}/files/missing.zip (HTTP 404)"),
+            format!("Broken link: {link_server_url}/not-found (HTTP 404)"),
+        ]
+    );
+
+    let downloads = Reporter::summarize_downloads(&pages, &HtmlContentTypes::default());
+    assert_eq!(downloads.total, 2);
+    assert_eq!(downloads.broken, 1);
+    assert_eq!(downloads.largest.len(), 1);
+    assert_eq!(downloads.largest[0].url, pdf.url);
+    assert_eq!(downloads.largest[0].page_url, page_url);
+    assert_eq!(downloads.largest[0].content_length, 2_500);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_samples_external_links_in_their_own_phase() {
+    use scoutly::http_client::RetryPolicy;
+    use scoutly::link_checker::{ExternalCheckPolicy, SampleRate};
+    use scoutly::models::{Link, LinkSkipReason, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    let link_server_url = start_link_test_server().await;
+    let links: Vec<Link> = (0..40)
+        .map(|index| Link {
+            url: format!("{link_server_url}/counted?page={index}"),
+            text: index.to_string(),
+            is_external: true,
+            kind: Default::default(),
+            rel: None,
+            status_code: None,
+            redirected_url: None,
+            check_error: None,
+            skip_reason: None,
+            content_type: None,
+            content_length: None,
+        })
+        .collect();
+
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::from([(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: None,
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
+            links,
+            images: vec![],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    )]);
+
+    let sample: SampleRate = "25%".parse().unwrap();
+    let mut checker = LinkChecker::new();
+    checker
+        .set_external_policy(ExternalCheckPolicy {
+            concurrency: 2,
+            rate_limit: None,
+            retry_policy: RetryPolicy::default(),
+            timeout: Duration::from_secs(5),
+            sample: Some(sample),
+        })
+        .unwrap();
+
+    let requests_before = counted_requests();
+    checker
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+
+    let page = pages.get(&page_url).unwrap();
+    let (checked, sampled_out): (Vec<&Link>, Vec<&Link>) = page
+        .links
+        .iter()
+        .partition(|link| sample.includes(&link.url));
+    assert!(
+        !checked.is_empty() && !sampled_out.is_empty(),
+        "A 25% sample of 40 links should check some and skip others"
+    );
+    assert_eq!(counted_requests() - requests_before, checked.len());
+    for link in checked {
+        assert_eq!(
+            link.status_code,
+            Some(200),
+            "{} should be checked",
+            link.url
+        );
+        assert_eq!(link.skip_reason, None);
+    }
+    for link in sampled_out {
+        assert_eq!(link.status_code, None, "{} should be sampled out", link.url);
+        assert_eq!(link.skip_reason, Some(LinkSkipReason::Sampled));
+    }
+}
+
+#[test]
+fn test_sample_rate_parses_percentages() {
+    use scoutly::link_checker::SampleRate;
+
+    assert_eq!("20%".parse::<SampleRate>().unwrap().percent(), 20.0);
+    assert_eq!(" 2.5 ".parse::<SampleRate>().unwrap().percent(), 2.5);
+    assert!("0%".parse::<SampleRate>().is_err());
+    assert!("150%".parse::<SampleRate>().is_err());
+    assert!("some".parse::<SampleRate>().is_err());
+
+    let all: SampleRate = "100%".parse().unwrap();
+    assert!(all.includes("https://example.com/any"));
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_gives_up_after_link_timeout() {
+    use scoutly::models::{Link, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    let link_server_url = start_link_test_server().await;
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::from([(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: None,
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
+            links: vec![Link {
+                url: format!("{link_server_url}/slow"),
+                text: "Slow".to_string(),
+                is_external: false,
+                kind: Default::default(),
+                rel: None,
+                status_code: None,
+                redirected_url: None,
+                check_error: None,
+                skip_reason: None,
+                content_type: None,
+                content_length: None,
+            }],
+            images: vec![],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    )]);
+
+    let mut checker = LinkChecker::new();
+    checker.set_timeout(1).unwrap();
+    let started = Instant::now();
+    checker
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+
+    assert!(
+        started.elapsed() < Duration::from_secs(4),
+        "The check should stop waiting after the 1s timeout"
+    );
+    let link = &pages[&page_url].links[0];
+    assert_eq!(link.status_code, None);
+    assert!(
+        link.check_error.is_some(),
+        "A timed-out link should record why"
+    );
+}
+
+#[test]
+fn test_tracking_param_names_finds_tracking_and_affiliate_params() {
+    use scoutly::link_checker::tracking_param_names;
+
+    assert_eq!(
+        tracking_param_names(
+            "https://shop.example.com/item?id=7&UTM_Source=news&utm_medium=email&tag=site-20&utm_source=x"
+        ),
+        vec!["UTM_Source", "utm_medium", "tag"]
+    );
+    assert!(tracking_param_names("https://example.com/search?q=shoes&page=2").is_empty());
+    assert!(tracking_param_names("not a url").is_empty());
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_audits_tracking_links_outside_the_check_scope() {
+    use scoutly::models::{Link, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let link_server_url = start_link_test_server().await;
+    let link = |path: &str| Link {
+        url: format!("{link_server_url}{path}"),
+        text: path.to_string(),
+        is_external: true,
+        kind: Default::default(),
+        rel: None,
+        status_code: None,
+        redirected_url: None,
+        check_error: None,
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
+    };
+
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::from([(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: None,
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
+            links: vec![
+                link("/redirect?utm_source=news&tag=site-20"),
+                link("/redirect-keep-query?utm_source=news&tag=site-20"),
+                link("/not-found?ref=partner"),
+                link("/ok"),
+            ],
+            images: vec![],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    )]);
+
+    let mut checker = LinkChecker::new();
+    checker.set_check_scope(LinkCheckScope::Internal);
+    checker.set_audit_tracking_links(true);
+    checker
+        .check_all_links(&mut pages, true)
+        .await
+        .expect("Link checking failed");
+
+    let page = &pages[&page_url];
+    let stripped: Vec<_> = page
+        .issues
+        .iter()
+        .filter(|issue| issue.issue_type == IssueType::TrackingParamsStripped)
+        .collect();
+    assert_eq!(stripped.len(), 1, "{:?}", page.issues);
+    assert_eq!(stripped[0].severity, IssueSeverity::Warning);
+    assert_eq!(
+        stripped[0].offending_url.as_deref(),
+        Some(page.links[0].url.as_str())
+    );
+    assert!(
+        stripped[0].message.contains("(utm_source, tag)"),
+        "{}",
+        stripped[0].message
+    );
+
+    let broken: Vec<_> = page
+        .issues
+        .iter()
+        .filter(|issue| issue.issue_type == IssueType::BrokenLink)
+        .map(|issue| issue.message.as_str())
+        .collect();
+    assert_eq!(
+        broken,
+        vec![format!(
+            "Broken tracked link: {link_server_url}/not-found?ref=partner (HTTP 404)"
+        )]
+    );
+
+    // The link without tracking parameters stays outside the internal-only scope
+    assert_eq!(page.links[1].status_code, Some(200));
+    assert_eq!(page.links[3].status_code, None);
+}
+
+#[tokio::test]
+async fn test_link_checker_blocks_external_links_to_private_addresses() {
+    use scoutly::http_client::RetryPolicy;
+    use scoutly::link_checker::ExternalCheckPolicy;
+    use scoutly::models::{Link, LinkSkipReason, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    let link_server_url = start_link_test_server().await;
+    let port = link_server_url.rsplit(':').next().unwrap();
+    let link = |url: String, is_external: bool| Link {
+        url,
+        text: String::new(),
+        is_external,
+        kind: Default::default(),
+        rel: None,
+        status_code: None,
+        redirected_url: None,
+        check_error: None,
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
+    };
+    let page_url = "https://example.com/";
+    let pages = || {
+        HashMap::from([(
+            page_url.to_string(),
+            PageInfo {
+                url: page_url.to_string(),
+                status_code: Some(200),
+                content_type: Some("text/html".to_string()),
+                last_modified: None,
+                response_time_ms: None,
+                content_length: None,
+                redirect_chain: vec![],
+                duplicate_of: None,
+                title: None,
+                meta_description: None,
+                meta_robots: None,
+                x_robots_tag: None,
+                canonical: None,
+                alternates: vec![],
+                link_headers: Default::default(),
+                vary: vec![],
+                vary_observed: vec![],
+                h1_tags: vec![],
+                outline: vec![],
+                aliases: vec![],
+                links: vec![
+                    link(format!("{link_server_url}/ok?external"), true),
+                    link(format!("http://localhost:{port}/ok"), true),
+                    link(format!("{link_server_url}/ok?internal"), false),
+                ],
+                images: vec![],
+                weight: Default::default(),
+                open_graph: OpenGraphTags::default(),
+                content: Default::default(),
+                issues: vec![],
+                omitted_issues: vec![],
+                crawl_depth: 0,
+                source: Default::default(),
+                screenshot: None,
+                html_snapshot: None,
+                field_data: None,
+            },
+        )])
+    };
+    let check = |checker: LinkChecker| async move {
+        let mut pages = pages();
+        checker
+            .check_all_links(&mut pages, false)
+            .await
+            .expect("Link checking failed");
+        pages.remove(page_url).unwrap()
+    };
+
+    let mut guarded = LinkChecker::new();
+    guarded.set_block_private_ips(true).unwrap();
+    let mut guarded_with_policy = LinkChecker::new();
+    guarded_with_policy
+        .set_external_policy(ExternalCheckPolicy {
+            concurrency: 2,
+            rate_limit: None,
+            retry_policy: RetryPolicy::default(),
+            timeout: Duration::from_secs(5),
+            sample: None,
+        })
+        .unwrap();
+    guarded_with_policy.set_block_private_ips(true).unwrap();
+
+    for checker in [guarded, guarded_with_policy] {
+        let page = check(checker).await;
+        for external in &page.links[..2] {
+            assert_eq!(external.status_code, None, "{} was requested", external.url);
+            assert_eq!(external.skip_reason, Some(LinkSkipReason::PrivateAddress));
+        }
+        assert_eq!(
+            page.links[2].status_code,
+            Some(200),
+            "Internal links are checked"
+        );
+        assert!(page.issues.is_empty(), "{:?}", page.issues);
+    }
+
+    let page = check(LinkChecker::new()).await;
+    assert!(
+        page.links
+            .iter()
+            .all(|link| link.status_code == Some(200) && link.skip_reason.is_none())
+    );
+}
@@ -0,0 +1,239 @@
+use scoutly::json_style::JsonStyle;
+use scoutly::logs::{
+    CrawlBudgetReport, LogEntry, LogFormat, LowValueReason, parse_combined_line, read_log,
+};
+use scoutly::models::{HtmlContentTypes, Link, PageInfo};
+use scoutly::reporter::Reporter;
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+const GOOGLEBOT: &str = "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+const BROWSER: &str = "Mozilla/5.0 (X11; Linux x86_64) Firefox/128.0";
+
+fn log_line(path: &str, status: u16, user_agent: &str) -> String {
+    format!(
+        r#"66.249.66.1 - - [10/Oct/2025:13:55:36 +0000] "GET {path} HTTP/1.1" {status} 2326 "-" "{user_agent}""#
+    )
+}
+
+fn entry(path: &str, status_code: u16, user_agent: &str) -> LogEntry {
+    LogEntry {
+        path: path.to_string(),
+        status_code,
+        user_agent: user_agent.to_string(),
+    }
+}
+
+fn page(path: &str, crawl_depth: usize, links: &[&str]) -> (String, PageInfo) {
+    let url = format!("https://example.com{path}");
+    (
+        url.clone(),
+        PageInfo {
+            url,
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            links: links
+                .iter()
+                .map(|link| Link {
+                    url: format!("https://example.com{link}"),
+                    text: String::new(),
+                    is_external: false,
+                    kind: Default::default(),
+                    rel: None,
+                    status_code: Some(200),
+                    redirected_url: None,
+                    check_error: None,
+                    skip_reason: None,
+                    content_type: None,
+                    content_length: None,
+                })
+                .collect(),
+            crawl_depth,
+            ..Default::default()
+        },
+    )
+}
+
+fn site() -> HashMap<String, PageInfo> {
+    [
+        page("/", 0, &["/products", "/about", "/pricing"]),
+        page("/products", 1, &["/about", "/pricing#plans"]),
+        page("/about", 1, &[]),
+        page("/pricing", 1, &[]),
+        page("/contact", 2, &[]),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn test_parse_combined_line() {
+    let entry = parse_combined_line(&log_line("/products?sort=price", 200, GOOGLEBOT))
+        .expect("Combined log line should parse");
+
+    assert_eq!(entry.path, "/products?sort=price");
+    assert_eq!(entry.status_code, 200);
+    assert!(entry.is_googlebot());
+    assert!(
+        !parse_combined_line(&log_line("/", 200, BROWSER))
+            .unwrap()
+            .is_googlebot()
+    );
+}
+
+#[test]
+fn test_parse_combined_line_accepts_absolute_targets_and_escaped_quotes() {
+    let line = r#"10.0.0.1 - - [10/Oct/2025:13:55:36 +0000] "GET https://example.com/a%20b HTTP/1.1" 301 0 "-" "Googlebot \"test\"""#;
+    let entry = parse_combined_line(line).expect("Line should parse");
+
+    assert_eq!(entry.path, "/a%20b");
+    assert_eq!(entry.status_code, 301);
+    assert_eq!(entry.user_agent, "Googlebot \"test\"");
+}
+
+#[test]
+fn test_parse_combined_line_rejects_malformed_lines() {
+    assert!(parse_combined_line("not a log line").is_none());
+    assert!(
+        parse_combined_line(r#"1.2.3.4 - - [x] "GET / HTTP/1.1" abc 0 "-" "Googlebot""#).is_none()
+    );
+}
+
+#[test]
+fn test_read_log_counts_skipped_lines() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("access.log");
+    fs::write(
+        &path,
+        format!(
+            "{}\ngarbage\n\n{}\n",
+            log_line("/", 200, GOOGLEBOT),
+            log_line("/about", 200, BROWSER)
+        ),
+    )
+    .unwrap();
+
+    let (entries, skipped) = read_log(&path, LogFormat::Combined).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(skipped, 1);
+}
+
+#[test]
+fn test_crawl_budget_report_flags_low_value_urls() {
+    let mut entries = vec![entry("/", 200, GOOGLEBOT)];
+    entries.extend((0..5).map(|_| entry("/products?sort=price", 200, GOOGLEBOT)));
+    entries.extend((0..3).map(|_| entry("/old-page", 404, GOOGLEBOT)));
+    entries.extend((0..2).map(|_| entry("/about", 301, GOOGLEBOT)));
+    entries.push(entry("/assets/app.css", 200, GOOGLEBOT));
+    entries.push(entry("/secret", 200, BROWSER));
+
+    let report = CrawlBudgetReport::new(&entries, &site(), &HtmlContentTypes::default());
+
+    assert_eq!(report.log_entries, 13);
+    assert_eq!(report.googlebot_hits, 12);
+    assert_eq!(report.googlebot_urls, 5);
+
+    let low_value: Vec<(&str, usize, &[LowValueReason])> = report
+        .low_value
+        .iter()
+        .map(|url| {
+            (
+                url.path.as_str(),
+                url.googlebot_hits,
+                url.reasons.as_slice(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        low_value,
+        vec![
+            (
+                "/products?sort=price",
+                5,
+                &[LowValueReason::ParameterVariant][..]
+            ),
+            (
+                "/old-page",
+                3,
+                &[LowValueReason::NotInCrawl, LowValueReason::ErrorStatus][..]
+            ),
+            ("/about", 2, &[LowValueReason::Redirect][..]),
+        ]
+    );
+}
+
+#[test]
+fn test_crawl_budget_report_ranks_never_crawled_pages_by_inlinks() {
+    let entries = vec![entry("/", 200, GOOGLEBOT), entry("/about", 200, GOOGLEBOT)];
+
+    let report = CrawlBudgetReport::new(&entries, &site(), &HtmlContentTypes::default());
+
+    let missed: Vec<(&str, usize)> = report
+        .never_crawled
+        .iter()
+        .map(|page| (page.url.as_str(), page.inlinks))
+        .collect();
+    assert_eq!(
+        missed,
+        vec![
+            ("https://example.com/pricing", 2),
+            ("https://example.com/products", 1),
+            ("https://example.com/contact", 0),
+        ]
+    );
+}
+
+#[test]
+fn test_logs_command_with_saved_report_outputs_json() {
+    let dir = tempdir().unwrap();
+    let log_path = dir.path().join("access.log");
+    let report_path = dir.path().join("report.json");
+    fs::write(
+        &log_path,
+        [
+            log_line("/", 200, GOOGLEBOT),
+            log_line("/old-page", 404, GOOGLEBOT),
+        ]
+        .join("\n"),
+    )
+    .unwrap();
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        site(),
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    Reporter::save_json_report(
+        &report,
+        &report.pages,
+        report_path.to_str().unwrap(),
+        JsonStyle::SnakeCase,
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_scoutly"))
+        .args([
+            "logs",
+            log_path.to_str().unwrap(),
+            "--format",
+            "combined",
+            "--report",
+            report_path.to_str().unwrap(),
+            "--output",
+            "json",
+        ])
+        .output()
+        .expect("Failed to run binary");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["googlebot_hits"], 2);
+    assert_eq!(json["low_value"][0]["path"], "/old-page");
+    assert_eq!(json["never_crawled"].as_array().unwrap().len(), 4);
+}
@@ -2,7 +2,7 @@ mod server;
 
 use scoutly::cli::{Cli, OutputFormat};
 use scoutly::run_with_terminal;
-use server::{get_test_server_url, start_link_test_server};
+use server::{counted_requests, get_test_server_url, start_link_test_server};
 use std::fs;
 use std::process::Command;
 
@@ -10,21 +10,104 @@ use std::process::Command;
 #[serial_test::serial]
 async fn test_invalid_url_no_protocol() {
     let args = Cli {
-        url: Some("example.com".to_string()),
+        urls: vec!["example.com".to_string()],
+        preset: None,
         depth: Some(2),
         max_pages: Some(10),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -52,21 +135,104 @@ async fn test_invalid_url_no_protocol() {
 #[serial_test::serial]
 async fn test_invalid_url_missing_https() {
     let args = Cli {
-        url: Some("ftp://example.com".to_string()),
+        urls: vec!["ftp://example.com".to_string()],
+        preset: None,
         depth: Some(2),
         max_pages: Some(10),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -90,21 +256,104 @@ async fn test_valid_http_url() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(5),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -122,21 +371,104 @@ async fn test_valid_http_url() {
 #[serial_test::serial]
 async fn test_valid_https_url() {
     let args = Cli {
-        url: Some("https://example.com".to_string()),
+        urls: vec!["https://example.com".to_string()],
+        preset: None,
         depth: Some(1),
         max_pages: Some(1),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -163,21 +495,104 @@ async fn test_full_crawl_with_text_output() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(2),
         max_pages: Some(10),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -198,21 +613,104 @@ async fn test_full_crawl_with_json_output() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(2),
         max_pages: Some(10),
         output: Some(OutputFormat::Json),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -238,21 +736,104 @@ async fn test_crawl_with_save_file() {
     let test_filename = dir.path().join("test_report.json");
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(5),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: Some(test_filename.to_string_lossy().to_string()),
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -279,21 +860,104 @@ async fn test_crawl_with_verbose_flag() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(3),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: true,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -317,21 +981,104 @@ async fn test_crawl_with_external_flag() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(5),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: true,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -355,21 +1102,104 @@ async fn test_crawl_with_ignore_redirects_flag() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(5),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: true,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -393,21 +1223,104 @@ async fn test_crawl_with_keep_fragments_flag() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(5),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: true,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -431,21 +1344,104 @@ async fn test_crawl_with_custom_depth_and_max_pages() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(3),
         max_pages: Some(15),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -474,21 +1470,104 @@ async fn test_crawl_with_all_flags_combined() {
     let test_filename = dir.path().join("test_report_combined.json");
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(2),
         max_pages: Some(8),
         output: Some(OutputFormat::Json),
+        json_style: None,
         cli: false,
         tui: false,
         save: Some(test_filename.to_string_lossy().to_string()),
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: true,
+        no_external: false,
         verbose: true,
+        no_verbose: false,
         ignore_redirects: true,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: true,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -517,21 +1596,104 @@ async fn test_crawl_with_default_text_output() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(3),
         output: None,
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -555,21 +1717,104 @@ async fn test_crawl_with_cli_flag() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(3),
         output: None,
+        json_style: None,
         cli: true,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -587,21 +1832,104 @@ async fn test_crawl_with_cli_flag() {
 #[serial_test::serial]
 async fn test_explicit_tui_requires_interactive_terminal() {
     let args = Cli {
-        url: None,
+        urls: vec![],
+        preset: None,
         depth: Some(1),
         max_pages: Some(1),
         output: None,
+        json_style: None,
         cli: false,
         tui: true,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let error = run_with_terminal(
@@ -620,21 +1948,104 @@ async fn test_explicit_tui_requires_interactive_terminal() {
 #[serial_test::serial]
 async fn test_cli_mode_without_url_errors() {
     let args = Cli {
-        url: None,
+        urls: vec![],
+        preset: None,
         depth: Some(1),
         max_pages: Some(1),
         output: None,
+        json_style: None,
         cli: true,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let error = run_with_terminal(
@@ -661,21 +2072,104 @@ async fn test_crawl_with_save_and_json_output() {
     let test_filename = dir.path().join("test_report_json_save.json");
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(5),
         output: Some(OutputFormat::Json),
+        json_style: None,
         cli: false,
         tui: false,
         save: Some(test_filename.to_string_lossy().to_string()),
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -704,21 +2198,104 @@ async fn test_crawl_with_verbose_and_json_output() {
     let base_url = get_test_server_url().await;
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(3),
         output: Some(OutputFormat::Json),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: true,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: None,
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -802,6 +2379,286 @@ async fn test_binary_json_output_is_valid_json() {
     );
 }
 
+#[tokio::test]
+#[serial_test::serial]
+async fn test_binary_keeps_concurrently_crawled_pages_under_their_own_urls() {
+    let start_url = format!("{}/batch-order", start_link_test_server().await);
+
+    // The slow page is first in its batch but finishes last
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_scoutly"))
+            .args([
+                start_url.as_str(),
+                "--depth",
+                "1",
+                "--max-pages",
+                "10",
+                "--concurrency",
+                "2",
+                "--output",
+                "json",
+            ])
+            .output()
+            .expect("Failed to run binary")
+    })
+    .await
+    .expect("Binary execution task should complete");
+    assert!(output.status.success(), "Crawl should exit successfully");
+
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a JSON report");
+    let pages = report["pages"]
+        .as_object()
+        .expect("Report should have pages");
+    for (key, page) in pages {
+        assert_eq!(page["url"].as_str(), Some(key.as_str()));
+    }
+    for (path, title) in [("/batch-order/slow", "Slow"), ("/batch-order/fast", "Fast")] {
+        let (_, page) = pages
+            .iter()
+            .find(|(key, _)| key.ends_with(path))
+            .unwrap_or_else(|| panic!("{path} should be crawled"));
+        assert_eq!(page["title"].as_str(), Some(title), "{path}");
+    }
+}
+
+/// Every line of JSONL output, parsed
+fn parse_jsonl(jsonl: &str) -> Vec<serde_json::Value> {
+    jsonl
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect(line))
+        .collect()
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_binary_jsonl_output_writes_one_line_per_page_then_a_summary() {
+    start_link_test_server().await;
+    let base_url = get_test_server_url().await;
+
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_scoutly"))
+            .args([
+                base_url.as_str(),
+                "--depth",
+                "1",
+                "--max-pages",
+                "3",
+                "--output",
+                "jsonl",
+            ])
+            .output()
+            .expect("Failed to run binary")
+    })
+    .await
+    .expect("Binary execution task should complete");
+
+    assert!(
+        output.status.success(),
+        "JSONL mode should exit successfully: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let records = parse_jsonl(&String::from_utf8_lossy(&output.stdout));
+    let (summary, lines) = records.split_last().expect("Output should not be empty");
+    assert_eq!(summary["type"], "summary");
+    assert_eq!(summary["summary"]["total_pages"], 3);
+
+    let pages: Vec<_> = lines.iter().filter(|line| line["type"] == "page").collect();
+    assert_eq!(pages.len(), 3);
+    assert!(
+        lines
+            .iter()
+            .all(|line| line["type"] == "page" || line["type"] == "issues")
+    );
+    // Pages come first, as they are crawled; issues found afterwards follow them
+    let first_issues = lines.iter().position(|line| line["type"] == "issues");
+    assert!(first_issues.is_none_or(|index| index >= pages.len()));
+    assert!(pages.iter().all(|line| line["page"]["url"].is_string()));
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_jsonl_output_goes_to_the_save_file() {
+    use tempfile::tempdir;
+
+    start_link_test_server().await;
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let report_path = dir.path().join("pages.jsonl");
+
+    let output = tokio::task::spawn_blocking(move || {
+        let output = Command::new(env!("CARGO_BIN_EXE_scoutly"))
+            .args([
+                base_url.as_str(),
+                "--max-pages",
+                "2",
+                "--output",
+                "jsonl",
+                "--save",
+                report_path.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to run binary");
+        (output, fs::read_to_string(&report_path))
+    })
+    .await
+    .expect("Binary execution task should complete");
+
+    assert!(output.0.status.success());
+    assert!(output.0.stdout.is_empty());
+    let records = parse_jsonl(&output.1.unwrap());
+    assert_eq!(records.last().unwrap()["type"], "summary");
+    assert_eq!(
+        records
+            .iter()
+            .filter(|record| record["type"] == "page")
+            .count(),
+        2
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_jsonl_page_lines_cap_issues_per_type() {
+    use tempfile::tempdir;
+
+    start_link_test_server().await;
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("scoutly.toml");
+    fs::write(
+        &config_path,
+        r##"
+[[assertions]]
+selector = "#banner"
+
+[[assertions]]
+selector = "#footer-nav"
+
+[[assertions]]
+selector = "#cookie-consent"
+"##,
+    )
+    .unwrap();
+
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_scoutly"))
+            .args([
+                base_url.as_str(),
+                "--depth",
+                "0",
+                "--output",
+                "jsonl",
+                "--max-issues-per-type",
+                "1",
+                "--config",
+                config_path.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to run binary")
+    })
+    .await
+    .expect("Binary execution task should complete");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let records = parse_jsonl(&String::from_utf8_lossy(&output.stdout));
+    let page = &records
+        .iter()
+        .find(|record| record["type"] == "page")
+        .expect("The start page should have a line")["page"];
+    let assertion_issues = page["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|issue| issue["issue_type"] == "AssertionFailed")
+        .count();
+    assert_eq!(assertion_issues, 1, "{page}");
+    assert_eq!(
+        page["omitted_issues"],
+        serde_json::json!([{
+            "issue_type": "AssertionFailed",
+            "severity": "Error",
+            "count": 2
+        }])
+    );
+}
+
+/// Drop what differs between two runs of the same crawl: when it ran, how fast pages answered,
+/// and the order of link issues, which are added as the checks finish
+fn comparable(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|key, _| {
+                !matches!(
+                    key.as_str(),
+                    "timestamp" | "response_time_ms" | "performance"
+                )
+            });
+            if let Some(serde_json::Value::Array(issues)) = map.get_mut("issues") {
+                issues.sort_by_key(|issue| issue["fingerprint"].to_string());
+            }
+            map.values_mut().for_each(comparable);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(comparable),
+        _ => {}
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_page_store_saves_the_same_report_as_memory() {
+    use tempfile::tempdir;
+
+    start_link_test_server().await;
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let store_path = dir.path().join("pages.sqlite");
+
+    let run = |report: &str, page_store: Option<&std::path::Path>| {
+        let report_path = dir.path().join(report);
+        let mut args = vec![
+            base_url.clone(),
+            "--depth".to_string(),
+            "2".to_string(),
+            "--max-pages".to_string(),
+            "20".to_string(),
+            "--output".to_string(),
+            "text".to_string(),
+            "--save".to_string(),
+            report_path.to_str().unwrap().to_string(),
+        ];
+        if let Some(page_store) = page_store {
+            args.push("--page-store".to_string());
+            args.push(page_store.to_str().unwrap().to_string());
+        }
+        tokio::task::spawn_blocking(move || {
+            let output = Command::new(env!("CARGO_BIN_EXE_scoutly"))
+                .args(&args)
+                .output()
+                .expect("Failed to run binary");
+            assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let mut report: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+            comparable(&mut report);
+            (report, String::from_utf8_lossy(&output.stdout).to_string())
+        })
+    };
+    let (in_memory, _) = run("memory.json", None).await.unwrap();
+    let (stored, text) = run("stored.json", Some(&store_path)).await.unwrap();
+
+    assert!(!in_memory["pages"].as_object().unwrap().is_empty());
+    assert_eq!(stored, in_memory);
+    assert!(text.contains("Pages with Issues"), "{text}");
+}
+
 #[tokio::test]
 #[serial_test::serial]
 async fn test_crawl_with_config_file_verbose() {
@@ -822,21 +2679,104 @@ async fn test_crawl_with_config_file_verbose() {
     fs::write(&config_path, json_content).unwrap();
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),
         max_pages: Some(3),
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: true,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: Some(config_path.to_str().unwrap().to_string()),
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![config_path.to_str().unwrap().to_string()],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -874,21 +2814,104 @@ async fn test_config_merge_with_cli() {
     fs::write(&config_path, json_content).unwrap();
 
     let args = Cli {
-        url: Some(base_url),
+        urls: vec![base_url],
+        preset: None,
         depth: Some(1),     // This should override config's depth of 5
         max_pages: Some(3), // This should override config's max_pages of 10
         output: Some(OutputFormat::Text),
+        json_style: None,
         cli: false,
         tui: false,
         save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: None,
+        screenshots: None,
         external: false,
+        no_external: false,
         verbose: false,
+        no_verbose: false,
         ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
         keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
         rate_limit: None,
         concurrency: Some(5),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
         respect_robots_txt: Some(false),
-        config: Some(config_path.to_str().unwrap().to_string()),
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![config_path.to_str().unwrap().to_string()],
+        profile: None,
+        command: None,
     };
 
     let result = run_with_terminal(
@@ -941,3 +2964,525 @@ async fn test_load_default_config_with_verbose() {
         "Should successfully run with default config and verbose"
     );
 }
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_baseline_workflow_fails_only_on_new_issues() {
+    use tempfile::tempdir;
+
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.json");
+    let empty_baseline_path = dir.path().join("empty-baseline.json");
+    fs::write(&empty_baseline_path, r#"{"version": 1, "issues": []}"#).unwrap();
+
+    let args = |write_baseline: Option<&std::path::Path>, baseline: Option<&std::path::Path>| Cli {
+        urls: vec![format!("{base_url}/missing-title.html")],
+        preset: None,
+        depth: Some(0),
+        max_pages: Some(1),
+        output: Some(OutputFormat::Json),
+        json_style: None,
+        cli: false,
+        tui: false,
+        save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: write_baseline.map(|path| path.to_string_lossy().to_string()),
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: baseline.map(|path| path.to_string_lossy().to_string()),
+        state_dir: None,
+        screenshots: None,
+        external: false,
+        no_external: false,
+        verbose: false,
+        no_verbose: false,
+        ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
+        keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
+        rate_limit: None,
+        concurrency: Some(1),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
+        respect_robots_txt: Some(false),
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
+    };
+    let terminal = scoutly::runtime::TerminalSupport {
+        stdin_is_terminal: false,
+        stdout_is_terminal: false,
+    };
+
+    run_with_terminal(args(Some(&baseline_path), None), terminal)
+        .await
+        .expect("Writing a baseline should not fail on existing issues");
+    let baseline: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&baseline_path).unwrap()).unwrap();
+    assert!(
+        !baseline["issues"].as_array().unwrap().is_empty(),
+        "Baseline should record the page's issues"
+    );
+
+    run_with_terminal(args(None, Some(&baseline_path)), terminal)
+        .await
+        .expect("Issues recorded in the baseline should not fail the run");
+
+    let error = run_with_terminal(args(None, Some(&empty_baseline_path)), terminal)
+        .await
+        .expect_err("Issues missing from the baseline should fail the run");
+    assert!(
+        error.to_string().contains("not recorded in the baseline"),
+        "Unexpected error: {error}"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_state_dir_records_runs_for_trends() {
+    use scoutly::cli::Command as ScoutlyCommand;
+    use tempfile::tempdir;
+
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let state_dir = dir.path().join("state");
+    let html_path = dir.path().join("trends.html");
+    let terminal = scoutly::runtime::TerminalSupport {
+        stdin_is_terminal: false,
+        stdout_is_terminal: false,
+    };
+
+    let scan = Cli {
+        urls: vec![format!("{base_url}/missing-title.html")],
+        preset: None,
+        depth: Some(0),
+        max_pages: Some(1),
+        output: Some(OutputFormat::Json),
+        json_style: None,
+        cli: false,
+        tui: false,
+        save: None,
+        save_dir: None,
+        result_file: None,
+        write_baseline: None,
+        emit_sitemap: None,
+        outline_dir: None,
+        resume: None,
+        cache_dir: None,
+        page_store: None,
+        save_html: None,
+        url_file: None,
+        recrawl_urls_from: None,
+        only_errors: false,
+        explain: Vec::new(),
+        baseline: None,
+        state_dir: Some(state_dir.to_string_lossy().to_string()),
+        screenshots: None,
+        external: false,
+        no_external: false,
+        verbose: false,
+        no_verbose: false,
+        ignore_redirects: false,
+        no_ignore_redirects: false,
+        check_links: None,
+        keep_fragments: false,
+        no_keep_fragments: false,
+        include: vec![],
+        exclude: vec![],
+        strip_tracking_params: false,
+        no_strip_tracking_params: false,
+        sort_query: false,
+        no_sort_query: false,
+        respect_nofollow: false,
+        no_respect_nofollow: false,
+        ignore_params: vec![],
+        rate_limit: None,
+        concurrency: Some(1),
+        timeout: None,
+        link_timeout: None,
+        insecure: false,
+        no_insecure: false,
+        ca_cert: None,
+        respect_robots_txt: Some(false),
+        respect_robots_for_links: false,
+        no_respect_robots_for_links: false,
+        audit_tracking_links: false,
+        no_audit_tracking_links: false,
+        block_private_ips: false,
+        no_block_private_ips: false,
+        robots_agent: None,
+        cookies: vec![],
+        cookies_file: None,
+        audit_vary: false,
+        no_audit_vary: false,
+        trace_redirects: false,
+        no_trace_redirects: false,
+        redirect_chain_limit: None,
+        find_contacts: false,
+        no_find_contacts: false,
+        scan_secrets: false,
+        no_scan_secrets: false,
+        heading_outline: false,
+        no_heading_outline: false,
+        use_sitemap: false,
+        no_use_sitemap: false,
+        max_sitemaps: None,
+        render: None,
+        gentle: false,
+        no_gentle: false,
+        auto_throttle: false,
+        no_auto_throttle: false,
+        max_requests: None,
+        max_bandwidth: None,
+        max_body_bytes: None,
+        max_duration: None,
+        retries: None,
+        retry_backoff_ms: None,
+        retry_jitter: None,
+        external_concurrency: None,
+        external_rate_limit: None,
+        external_retries: None,
+        external_timeout: None,
+        external_check_sample: None,
+        max_issues_per_type: None,
+        max_pages_shown: None,
+        scripts: vec![],
+        progress: None,
+        config: vec![],
+        profile: None,
+        command: None,
+    };
+    for _ in 0..2 {
+        run_with_terminal(scan.clone(), terminal)
+            .await
+            .expect("Scan should succeed");
+    }
+
+    let history = fs::read_to_string(state_dir.join("history.jsonl")).unwrap();
+    assert_eq!(history.lines().count(), 2);
+
+    let trends = Cli {
+        command: Some(ScoutlyCommand::Trends {
+            url: None,
+            state_dir: Some(state_dir.to_string_lossy().to_string()),
+            limit: 10,
+            html: Some(html_path.to_string_lossy().to_string()),
+        }),
+        ..Default::default()
+    };
+    run_with_terminal(trends, terminal)
+        .await
+        .expect("Trends should render recorded runs");
+
+    let html = fs::read_to_string(&html_path).unwrap();
+    assert!(html.contains("missing-title.html"));
+    assert_eq!(html.matches("<tr><td>").count(), 2);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_url_file_crawls_only_listed_urls() {
+    use tempfile::tempdir;
+
+    start_link_test_server().await;
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let url_file = dir.path().join("urls.txt");
+    let report_path = dir.path().join("report.json");
+    fs::write(
+        &url_file,
+        format!("# Landing pages\n{base_url}/\n\n{base_url}/missing-title.html\n{base_url}/\n"),
+    )
+    .unwrap();
+
+    let output = tokio::task::spawn_blocking(move || {
+        let output = Command::new(env!("CARGO_BIN_EXE_scoutly"))
+            .current_dir(dir.path())
+            .args([
+                "--url-file",
+                url_file.to_str().unwrap(),
+                "--output",
+                "json",
+                "--max-pages",
+                "1",
+                "--save",
+                report_path.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to run binary");
+        (output, fs::read_to_string(&report_path))
+    })
+    .await
+    .expect("Binary execution task should complete");
+
+    assert!(
+        output.0.status.success(),
+        "List mode should succeed: {}",
+        String::from_utf8_lossy(&output.0.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_str(&output.1.unwrap()).unwrap();
+    let mut urls: Vec<_> = report["pages"]
+        .as_object()
+        .unwrap()
+        .values()
+        .map(|page| page["url"].as_str().unwrap().to_string())
+        .collect();
+    urls.sort();
+    assert_eq!(
+        urls,
+        [
+            format!("{base_url}/"),
+            format!("{base_url}/missing-title.html")
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_recrawl_only_errors_refetches_failing_pages_and_merges() {
+    use tempfile::tempdir;
+
+    let link_server_url = start_link_test_server().await;
+    let dir = tempdir().unwrap();
+    let report_path = dir.path().join("report.json");
+    let merged_path = dir.path().join("merged.json");
+    let flaky_url = format!("{link_server_url}/flaky/recrawl");
+    let counted_url = format!("{link_server_url}/counted");
+
+    let run = |args: Vec<String>| {
+        let dir = dir.path().to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            Command::new(env!("CARGO_BIN_EXE_scoutly"))
+                .current_dir(dir)
+                .args(&args)
+                .args(["--output", "json", "--respect-robots-txt", "false"])
+                .output()
+                .expect("Failed to run binary")
+        })
+    };
+
+    let output = run(vec![
+        flaky_url.clone(),
+        counted_url.clone(),
+        "--save".to_string(),
+        report_path.to_str().unwrap().to_string(),
+    ])
+    .await
+    .unwrap();
+    assert!(
+        output.status.success() || output.status.code() == Some(1),
+        "First crawl should run: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(report["pages"][&flaky_url]["status_code"], 502);
+    let counted_before = counted_requests();
+
+    let output = run(vec![
+        "--recrawl-urls-from".to_string(),
+        report_path.to_str().unwrap().to_string(),
+        "--only-errors".to_string(),
+        "--save".to_string(),
+        merged_path.to_str().unwrap().to_string(),
+    ])
+    .await
+    .unwrap();
+    assert!(
+        output.status.success(),
+        "Recrawl should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(counted_requests(), counted_before);
+    let merged: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&merged_path).unwrap()).unwrap();
+    assert_eq!(merged["start_url"], report["start_url"]);
+    assert_eq!(merged["summary"]["total_pages"], 2);
+    assert_eq!(merged["pages"][&flaky_url]["status_code"], 200);
+    assert_eq!(merged["pages"][&counted_url], report["pages"][&counted_url]);
+}
+
+#[tokio::test]
+async fn test_outline_dir_exports_heading_outline_to_report_and_markdown() {
+    use tempfile::tempdir;
+
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let report_path = dir.path().join("report.json");
+    let outline_dir = dir.path().join("outlines");
+    let page_url = format!("{base_url}/page-content.html");
+
+    let args = vec![
+        page_url.clone(),
+        "--depth".to_string(),
+        "0".to_string(),
+        "--output".to_string(),
+        "json".to_string(),
+        "--save".to_string(),
+        report_path.to_str().unwrap().to_string(),
+        "--outline-dir".to_string(),
+        outline_dir.to_str().unwrap().to_string(),
+    ];
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_scoutly"))
+            .args(&args)
+            .output()
+            .expect("Failed to run binary")
+    })
+    .await
+    .unwrap();
+    assert!(
+        output.status.success() || output.status.code() == Some(1),
+        "Crawl should run: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    let outline = &report["pages"][&page_url]["outline"];
+    assert_eq!(outline[0]["level"], 1);
+    assert_eq!(outline[0]["text"], "Field Guide");
+    assert_eq!(outline[0]["children"][0]["text"], "Gulls");
+    assert_eq!(
+        outline[0]["children"][0]["children"][0]["text"],
+        "Herring gull"
+    );
+
+    let files: Vec<_> = fs::read_dir(&outline_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    let file_name = files[0].file_name().unwrap().to_str().unwrap();
+    assert!(file_name.ends_with("_page-content.html.md"), "{file_name}");
+    let markdown = fs::read_to_string(&files[0]).unwrap();
+    assert!(markdown.starts_with(&format!("# Outline of {page_url}\n")));
+    assert!(markdown.contains("- H1 Field Guide\n  - H2 Gulls\n    - H3 Herring gull\n"));
+}
+
+#[tokio::test]
+async fn test_result_file_records_exit_code_checks_and_counts() {
+    use tempfile::tempdir;
+
+    let base_url = get_test_server_url().await;
+    let dir = tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.json");
+    fs::write(&baseline_path, r#"{"version": 1, "issues": []}"#).unwrap();
+    let result_path = dir.path().join("result.json");
+
+    let run = |baseline: String| {
+        let args = vec![
+            format!("{base_url}/missing-title.html"),
+            "--depth".to_string(),
+            "0".to_string(),
+            "--output".to_string(),
+            "json".to_string(),
+            "--baseline".to_string(),
+            baseline,
+            "--result-file".to_string(),
+            result_path.to_str().unwrap().to_string(),
+        ];
+        let result_path = result_path.clone();
+        async move {
+            let output = tokio::task::spawn_blocking(move || {
+                Command::new(env!("CARGO_BIN_EXE_scoutly"))
+                    .args(&args)
+                    .output()
+                    .expect("Failed to run binary")
+            })
+            .await
+            .unwrap();
+            let result: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(&result_path).unwrap()).unwrap();
+            (output.status.code(), result)
+        }
+    };
+
+    let (code, result) = run(baseline_path.to_str().unwrap().to_string()).await;
+    assert_eq!(code, Some(1), "New errors should fail the run");
+    assert_eq!(result["exit_code"], 1);
+    assert_eq!(result["passed"], false);
+    assert_eq!(result["checks"][0]["name"], "baseline");
+    assert_eq!(result["checks"][0]["passed"], false);
+    assert_eq!(result["counts"]["pages"], 1);
+    assert!(result["counts"]["errors"].as_u64().unwrap() > 0);
+    assert!(result.get("error").is_none());
+
+    let missing = dir.path().join("missing.json");
+    let (code, result) = run(missing.to_str().unwrap().to_string()).await;
+    assert_eq!(code, Some(1));
+    assert_eq!(result["exit_code"], 1);
+    assert!(
+        result["error"].as_str().unwrap().contains("baseline"),
+        "{result}"
+    );
+    assert!(result.get("counts").is_none());
+}
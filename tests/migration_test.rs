@@ -0,0 +1,167 @@
+mod server;
+
+use scoutly::cookies::build_cookie_jar;
+use scoutly::http_client::TlsOptions;
+use scoutly::migration::{MigrationMapping, MigrationProblem, MigrationValidator, read_mappings};
+use server::{start_link_test_server, start_tls_test_server};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn test_read_mappings_skips_header_and_resolves_relative_targets() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("mappings.csv");
+    fs::write(
+        &path,
+        "old,new\nhttps://old.example.com/about, https://www.example.com/about-us\n\"https://old.example.com/a,b\",/new\n",
+    )
+    .unwrap();
+
+    let mappings = read_mappings(&path).unwrap();
+
+    assert_eq!(
+        mappings,
+        vec![
+            MigrationMapping {
+                old_url: "https://old.example.com/about".to_string(),
+                expected_url: "https://www.example.com/about-us".to_string(),
+            },
+            MigrationMapping {
+                old_url: "https://old.example.com/a,b".to_string(),
+                expected_url: "https://old.example.com/new".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_read_mappings_rejects_incomplete_rows() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("mappings.csv");
+    fs::write(&path, "https://old.example.com/about\n").unwrap();
+
+    let error = read_mappings(&path).unwrap_err();
+
+    assert!(error.to_string().contains("Line 1"), "{error}");
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_migration_validator_reports_each_problem() {
+    let base_url = start_link_test_server().await;
+    let mapping = |old: &str, expected: &str| MigrationMapping {
+        old_url: format!("{base_url}{old}"),
+        expected_url: format!("{base_url}{expected}"),
+    };
+    let mappings = vec![
+        mapping("/redirect", "/ok"),
+        mapping("/redirect-temp", "/ok"),
+        mapping("/redirect-chain", "/ok"),
+        mapping("/redirect", "/somewhere-else"),
+        mapping("/not-found", "/ok"),
+        mapping("/ok", "/ok"),
+        mapping("/redirect-loop", "/ok"),
+    ];
+
+    let report = MigrationValidator::new(2)
+        .unwrap()
+        .validate(&mappings)
+        .await;
+
+    let problems: Vec<_> = report
+        .checks
+        .iter()
+        .map(|check| check.problems.clone())
+        .collect();
+    assert_eq!(
+        problems,
+        vec![
+            vec![],
+            vec![MigrationProblem::TemporaryRedirect],
+            vec![MigrationProblem::RedirectChain],
+            vec![MigrationProblem::WrongTarget],
+            vec![MigrationProblem::NotFound],
+            vec![MigrationProblem::NotRedirected],
+            vec![MigrationProblem::RedirectLoop],
+        ]
+    );
+    assert_eq!(report.total, 7);
+    assert_eq!(report.passed, 1);
+    assert_eq!(report.failed, 6);
+
+    let chain = &report.checks[2];
+    assert_eq!(
+        chain
+            .hops
+            .iter()
+            .map(|hop| hop.status_code)
+            .collect::<Vec<_>>(),
+        vec![301, 301, 200]
+    );
+    assert_eq!(chain.final_url, Some(format!("{base_url}/ok")));
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_migration_validator_sends_cookies_and_trusts_the_configured_ca() {
+    let base_url = start_link_test_server().await;
+    let gated = vec![MigrationMapping {
+        old_url: format!("{base_url}/cookie-gated"),
+        expected_url: format!("{base_url}/members"),
+    }];
+    let mut validator = MigrationValidator::new(1).unwrap();
+    validator
+        .set_cookie_jar(
+            build_cookie_jar(&["session=abc123".to_string()], None, &[&base_url]).unwrap(),
+        )
+        .unwrap();
+
+    let report = validator.validate(&gated).await;
+
+    assert_eq!(report.checks[0].hops[0].status_code, 200);
+
+    let tls_url = start_tls_test_server().await;
+    let secure = vec![MigrationMapping {
+        old_url: format!("{tls_url}/old"),
+        expected_url: format!("{tls_url}/new"),
+    }];
+    let untrusted = MigrationValidator::new(1).unwrap().validate(&secure).await;
+    assert_eq!(
+        untrusted.checks[0].problems,
+        vec![MigrationProblem::RequestFailed]
+    );
+
+    let mut validator = MigrationValidator::new(1).unwrap();
+    validator
+        .set_tls(TlsOptions::new(false, Some(Path::new("tests/static/tls/ca.pem"))).unwrap())
+        .unwrap();
+    let trusted = validator.validate(&secure).await;
+    assert_eq!(
+        trusted.checks[0].problems,
+        vec![MigrationProblem::NotRedirected]
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_migration_validator_gives_up_after_its_timeout() {
+    use std::time::{Duration, Instant};
+
+    let base_url = start_link_test_server().await;
+    let mappings = vec![MigrationMapping {
+        old_url: format!("{base_url}/slow"),
+        expected_url: format!("{base_url}/ok"),
+    }];
+    let mut validator = MigrationValidator::new(1).unwrap();
+    validator.set_timeout(1).unwrap();
+
+    let started = Instant::now();
+    let report = validator.validate(&mappings).await;
+
+    assert!(started.elapsed() < Duration::from_secs(4));
+    assert_eq!(
+        report.checks[0].problems,
+        vec![MigrationProblem::RequestFailed]
+    );
+}
@@ -0,0 +1,112 @@
+mod server;
+
+use scoutly::cli::OutputFormat;
+use scoutly::progress::{ProgressFormat, ProgressLine};
+use scoutly::runtime::{ProgressSnapshot, RunStage};
+use server::get_test_server_url;
+use std::process::Command;
+use std::time::Duration;
+
+#[test]
+fn test_progress_line_estimates_crawl_time_from_queue() {
+    let mut snapshot = ProgressSnapshot::new(RunStage::Crawling, "Crawled 10 page(s)");
+    snapshot.pages_crawled = 10;
+    snapshot.queued_urls = 40;
+
+    let line = ProgressLine::new(
+        &snapshot,
+        Duration::from_secs(7),
+        Duration::from_secs(5),
+        30,
+    );
+
+    assert_eq!(line.stage, RunStage::Crawling);
+    assert_eq!(line.queued_urls, 40);
+    assert_eq!(line.elapsed_secs, 7.0);
+    // Only 20 more pages fit under the page limit: 5s per 10 pages
+    assert_eq!(line.eta_secs, Some(10.0));
+}
+
+#[test]
+fn test_progress_line_estimates_link_checking_time() {
+    let mut snapshot = ProgressSnapshot::new(RunStage::CheckingLinks, "Checking links");
+    snapshot.links_checked = 30;
+    snapshot.total_links = 40;
+
+    let line = ProgressLine::new(
+        &snapshot,
+        Duration::from_secs(9),
+        Duration::from_secs(3),
+        200,
+    );
+
+    assert_eq!(line.eta_secs, Some(1.0));
+}
+
+#[test]
+fn test_progress_line_has_no_eta_before_progress() {
+    let snapshot = ProgressSnapshot::new(RunStage::LoadingConfig, "Preparing scan");
+
+    let line = ProgressLine::new(&snapshot, Duration::ZERO, Duration::ZERO, 200);
+
+    assert_eq!(line.eta_secs, None);
+    let json = serde_json::to_value(&line).unwrap();
+    assert_eq!(json["stage"], "loading_config");
+    assert!(json["eta_secs"].is_null());
+}
+
+#[test]
+fn test_progress_bars_only_draw_on_a_terminal_in_text_mode() {
+    assert!(ProgressFormat::Bar.shows_bars(OutputFormat::Text, true));
+    assert!(!ProgressFormat::Bar.shows_bars(OutputFormat::Text, false));
+    assert!(!ProgressFormat::Bar.shows_bars(OutputFormat::Json, true));
+    assert!(!ProgressFormat::None.shows_bars(OutputFormat::Text, true));
+    assert!(!ProgressFormat::Json.shows_bars(OutputFormat::Text, true));
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_binary_prints_json_progress_lines_to_stderr() {
+    let crawl_url = get_test_server_url().await;
+
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_scoutly"))
+            .env("SCOUTLY_UPDATE_API_URL", "http://127.0.0.1:9/latest")
+            .args([
+                crawl_url.as_str(),
+                "--depth",
+                "1",
+                "--max-pages",
+                "3",
+                "--check-links",
+                "none",
+                "--output",
+                "json",
+                "--progress",
+                "json",
+            ])
+            .output()
+            .expect("run binary with JSON progress")
+    })
+    .await
+    .expect("binary task should complete");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice::<serde_json::Value>(&output.stdout)
+        .expect("stdout should stay valid JSON");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<ProgressLine> = stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).expect("progress line should be JSON"))
+        .collect();
+    assert!(lines.iter().any(|line| line.stage == RunStage::Crawling));
+    let last = lines.last().expect("progress lines should be printed");
+    assert_eq!(last.stage, RunStage::Completed);
+    assert_eq!(last.pages_crawled, 3);
+}
@@ -1,7 +1,9 @@
+use scoutly::json_style::{self, JsonStyle};
 use scoutly::models::{
-    CrawlReport, IssueSeverity, IssueType, Link, OpenGraphTags, PageInfo, SeoIssue,
+    CrawlReport, HtmlContentTypes, Image, IssueSeverity, IssueType, Link, OpenGraphTags, PageInfo,
+    SeoIssue,
 };
-use scoutly::reporter::Reporter;
+use scoutly::reporter::{Reporter, TextReportOptions, truncate_middle};
 use std::collections::HashMap;
 
 fn create_test_page(
@@ -16,14 +18,35 @@ fn create_test_page(
         url: url.to_string(),
         status_code,
         content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
         title: title.map(|t| t.to_string()),
         meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
         h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
         links,
         images: vec![],
+        weight: Default::default(),
         open_graph: OpenGraphTags::default(),
+        content: Default::default(),
         issues,
+        omitted_issues: vec![],
         crawl_depth,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
     }
 }
 
@@ -38,6 +61,12 @@ fn create_test_issue(severity: IssueSeverity, message: &str) -> SeoIssue {
         severity,
         issue_type,
         message: message.to_string(),
+        offending_url: None,
+        expected: None,
+        actual: None,
+        help_url: None,
+        check: None,
+        fingerprint: None,
     }
 }
 
@@ -46,9 +75,14 @@ fn create_test_link(url: &str, status_code: Option<u16>) -> Link {
         url: url.to_string(),
         text: "Link Text".to_string(),
         is_external: false,
+        kind: Default::default(),
+        rel: None,
         status_code,
         redirected_url: None,
         check_error: None,
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
     }
 }
 
@@ -57,16 +91,26 @@ fn create_transport_error_link(url: &str, error: &str) -> Link {
         url: url.to_string(),
         text: "Link Text".to_string(),
         is_external: false,
+        kind: Default::default(),
+        rel: None,
         status_code: None,
         redirected_url: None,
         check_error: Some(error.to_string()),
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
     }
 }
 
 #[test]
 fn test_generate_report_empty_pages() {
     let pages = HashMap::new();
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     assert_eq!(report.start_url, "https://example.com");
     assert_eq!(report.summary.total_pages, 0);
@@ -105,7 +149,12 @@ fn test_generate_report_with_all_severity_types() {
 
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     assert_eq!(report.summary.total_pages, 1);
     assert_eq!(report.summary.total_links, 3);
@@ -171,7 +220,12 @@ fn test_generate_report_multiple_pages() {
     pages.insert("https://example.com/page2".to_string(), page2);
     pages.insert("https://example.com/page3".to_string(), page3);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     assert_eq!(report.summary.total_pages, 3);
     assert_eq!(report.summary.total_links, 3);
@@ -198,7 +252,12 @@ fn test_generate_report_broken_links_boundary() {
     let page = create_test_page("https://example.com", Some(200), None, vec![], links, 0);
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     assert_eq!(report.summary.total_links, 6);
     assert_eq!(report.summary.broken_links, 3); // 400, 404, 500
@@ -217,7 +276,12 @@ fn test_generate_report_links_without_status_code() {
     let page = create_test_page("https://example.com", Some(200), None, vec![], links, 0);
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     assert_eq!(report.summary.total_links, 3);
     assert_eq!(report.summary.broken_links, 1); // Only the 404
@@ -235,12 +299,348 @@ fn test_generate_report_transport_failures_count_as_broken_links() {
     let page = create_test_page("https://example.com", Some(200), None, vec![], links, 0);
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     assert_eq!(report.summary.total_links, 2);
     assert_eq!(report.summary.broken_links, 1);
 }
 
+#[test]
+fn test_generate_report_counts_fragment_navigation_pages() {
+    let mut pages = HashMap::new();
+
+    let section_links: Vec<Link> = ["#about", "#services", "#work", "#team", "#contact"]
+        .iter()
+        .map(|fragment| create_test_link(&format!("https://example.com/{fragment}"), None))
+        .collect();
+    pages.insert(
+        "https://example.com/".to_string(),
+        create_test_page(
+            "https://example.com/",
+            Some(200),
+            None,
+            vec![],
+            section_links,
+            0,
+        ),
+    );
+    pages.insert(
+        "https://example.com/blog".to_string(),
+        create_test_page(
+            "https://example.com/blog",
+            Some(200),
+            None,
+            vec![],
+            vec![create_test_link("https://example.com/blog#comments", None)],
+            1,
+        ),
+    );
+
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+
+    assert_eq!(report.summary.fragment_navigation_pages, 1);
+}
+
+#[test]
+fn test_reports_without_fragment_navigation_count_still_load() {
+    let json =
+        r#"{"total_pages":1,"total_links":0,"broken_links":0,"errors":0,"warnings":0,"infos":0}"#;
+    let summary: scoutly::models::CrawlSummary = serde_json::from_str(json).unwrap();
+
+    assert_eq!(summary.fragment_navigation_pages, 0);
+}
+
+#[test]
+fn test_generate_report_indexes_pages_linking_to_each_url() {
+    let mut pages = HashMap::new();
+    for (url, links) in [
+        (
+            "https://example.com/",
+            vec!["https://example.com/old-page", "https://example.com/about"],
+        ),
+        (
+            "https://example.com/about",
+            vec![
+                "https://example.com/old-page#details",
+                "https://example.com/old-page",
+                "https://example.com/about#team",
+            ],
+        ),
+        ("https://example.com/old-page", vec![]),
+    ] {
+        let links = links
+            .into_iter()
+            .map(|link| create_test_link(link, None))
+            .collect();
+        pages.insert(
+            url.to_string(),
+            create_test_page(url, Some(200), None, vec![], links, 0),
+        );
+    }
+
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        report.linking_pages("https://example.com/old-page#anywhere"),
+        ["https://example.com/", "https://example.com/about"]
+    );
+    assert_eq!(
+        report.linking_pages("https://example.com/about"),
+        ["https://example.com/"]
+    );
+    assert!(
+        report
+            .linking_pages("https://example.com/missing")
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_reports_without_inlinks_still_load() {
+    let json = r#"{"start_url":"https://example.com/","pages":{},"summary":{"total_pages":0,"total_links":0,"broken_links":0,"errors":0,"warnings":0,"infos":0},"timestamp":"2025-01-01T00:00:00Z"}"#;
+    let report: CrawlReport = serde_json::from_str(json).unwrap();
+
+    assert!(report.inlinks.is_empty());
+}
+
+#[test]
+fn test_generate_report_summarizes_image_alt_coverage() {
+    let image = |src: &str, alt: Option<&str>, size: Option<(u32, u32)>| Image {
+        src: format!("https://example.com/{src}"),
+        alt: alt.map(str::to_string),
+        width: size.map(|(width, _)| width),
+        height: size.map(|(_, height)| height),
+    };
+    let logo = image("logo.png", Some("Example"), Some((200, 50)));
+    let mut home = create_test_page("https://example.com/", Some(200), None, vec![], vec![], 0);
+    home.images = vec![
+        logo.clone(),
+        image("hero.jpg", Some(""), Some((1600, 900))),
+        image("chart.png", None, None),
+    ];
+    let mut about = create_test_page(
+        "https://example.com/about",
+        Some(200),
+        None,
+        vec![],
+        vec![],
+        1,
+    );
+    about.images = vec![logo, image("team.jpg", Some("  "), Some((800, 600)))];
+    let pages = HashMap::from([(home.url.clone(), home), (about.url.clone(), about)]);
+
+    let images = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap()
+    .summary
+    .images;
+
+    assert_eq!(images.total, 5);
+    assert_eq!(images.unique, 4);
+    assert_eq!(images.with_alt, 2);
+    assert_eq!(images.decorative, 2);
+    assert_eq!(images.missing_alt, 1);
+    assert_eq!(images.with_alt_percent(), 40.0);
+    let largest: Vec<_> = images
+        .largest
+        .iter()
+        .map(|image| (image.src.as_str(), image.page_url.as_str()))
+        .collect();
+    assert_eq!(
+        largest,
+        [
+            ("https://example.com/hero.jpg", "https://example.com/"),
+            ("https://example.com/team.jpg", "https://example.com/about"),
+            ("https://example.com/logo.png", "https://example.com/")
+        ]
+    );
+}
+
+#[test]
+fn test_generate_report_inventories_third_party_iframes() {
+    use scoutly::models::DiscoveryMethod;
+
+    let frame = |url: &str, is_external: bool| Link {
+        is_external,
+        kind: DiscoveryMethod::Iframe,
+        ..create_test_link(url, None)
+    };
+    let anchor = |url: &str| Link {
+        is_external: true,
+        ..create_test_link(url, None)
+    };
+    let page = |path: &str, links: Vec<Link>| {
+        let page = create_test_page(
+            &format!("https://example.com/{path}"),
+            Some(200),
+            None,
+            vec![],
+            links,
+            0,
+        );
+        (page.url.clone(), page)
+    };
+    let pages = HashMap::from([
+        page(
+            "",
+            vec![
+                frame("https://www.youtube.com/embed/a", true),
+                frame("https://www.YouTube.com/embed/b", true),
+                frame("https://www.google.com/maps/embed?pb=1", true),
+                frame("https://example.com/widget", false),
+                anchor("https://www.youtube.com/@example"),
+            ],
+        ),
+        page(
+            "contact",
+            vec![frame("https://www.google.com/maps/embed?pb=2", true)],
+        ),
+        page("about", vec![frame("about:blank", true)]),
+    ]);
+
+    let home = &pages["https://example.com/"];
+    assert_eq!(
+        home.third_party_frame_domains()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        [
+            ("www.google.com".to_string(), 1),
+            ("www.youtube.com".to_string(), 2)
+        ]
+    );
+
+    let frames = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap()
+    .summary
+    .third_party_frames;
+
+    assert_eq!(frames.total, 4);
+    assert_eq!(frames.pages, 2);
+    let domains: Vec<_> = frames
+        .domains
+        .iter()
+        .map(|domain| (domain.domain.as_str(), domain.frames, domain.pages.clone()))
+        .collect();
+    assert_eq!(
+        domains,
+        [
+            (
+                "www.google.com",
+                2,
+                vec![
+                    "https://example.com/".to_string(),
+                    "https://example.com/contact".to_string()
+                ]
+            ),
+            (
+                "www.youtube.com",
+                2,
+                vec!["https://example.com/".to_string()]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_generate_report_lists_slowest_and_largest_pages() {
+    let page = |path: &str, response_time_ms: Option<u64>, content_length: Option<u64>| {
+        let mut page = create_test_page(
+            &format!("https://example.com/{path}"),
+            Some(200),
+            None,
+            vec![],
+            vec![],
+            0,
+        );
+        page.response_time_ms = response_time_ms;
+        page.content_length = content_length;
+        (page.url.clone(), page)
+    };
+    let pages = HashMap::from([
+        page("", Some(120), Some(40_000)),
+        page("slow", Some(2_400), Some(8_000)),
+        page("big", Some(300), Some(900_000)),
+        page("unmeasured", None, None),
+    ]);
+
+    let performance = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap()
+    .summary
+    .performance;
+
+    assert_eq!(performance.measured, 3);
+    assert_eq!(performance.average_response_time_ms, Some(940));
+    assert_eq!(performance.average_content_length, Some(316_000));
+    let slowest: Vec<_> = performance
+        .slowest
+        .iter()
+        .map(|page| (page.url.as_str(), page.response_time_ms))
+        .collect();
+    assert_eq!(
+        slowest,
+        [
+            ("https://example.com/slow", 2_400),
+            ("https://example.com/big", 300),
+            ("https://example.com/", 120)
+        ]
+    );
+    assert_eq!(performance.largest[0].url, "https://example.com/big");
+    assert_eq!(performance.largest[0].content_length, 900_000);
+}
+
+#[test]
+fn test_summary_counts_issues_omitted_by_the_per_type_limit() {
+    let mut page = create_test_page(
+        "https://example.com/",
+        Some(200),
+        None,
+        (0..4)
+            .map(|_| create_test_issue(IssueSeverity::Warning, "Image missing alt text"))
+            .collect(),
+        vec![],
+        0,
+    );
+    page.limit_issues(1);
+    let pages = HashMap::from([(page.url.clone(), page)]);
+
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+
+    assert_eq!(report.pages["https://example.com/"].issues.len(), 1);
+    assert_eq!(report.summary.warnings, 4);
+    Reporter::print_text_report(&report, &report.pages, &TextReportOptions::default()).unwrap();
+}
+
 #[test]
 fn test_print_text_report_with_issues() {
     let mut pages = HashMap::new();
@@ -299,10 +699,15 @@ fn test_print_text_report_with_issues() {
     pages.insert("https://example.com/page3".to_string(), page3);
     pages.insert("https://example.com/page4".to_string(), page4);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     // This test just ensures the function runs without panic
-    Reporter::print_text_report(&report);
+    Reporter::print_text_report(&report, &report.pages, &TextReportOptions::default()).unwrap();
 }
 
 #[test]
@@ -320,10 +725,15 @@ fn test_print_text_report_no_issues() {
 
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     // This test ensures the function runs without panic when there are no issues
-    Reporter::print_text_report(&report);
+    Reporter::print_text_report(&report, &report.pages, &TextReportOptions::default()).unwrap();
 }
 
 #[test]
@@ -343,12 +753,17 @@ fn test_print_text_report_with_broken_links() {
 
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     // Test that broken links are counted correctly
     assert_eq!(report.summary.broken_links, 1);
 
-    Reporter::print_text_report(&report);
+    Reporter::print_text_report(&report, &report.pages, &TextReportOptions::default()).unwrap();
 }
 
 #[test]
@@ -373,10 +788,15 @@ fn test_print_text_report_summary_colors() {
 
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     // This tests the color branching in print_text_report
-    Reporter::print_text_report(&report);
+    Reporter::print_text_report(&report, &report.pages, &TextReportOptions::default()).unwrap();
 }
 
 #[test]
@@ -396,14 +816,19 @@ fn test_save_json_report() {
 
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     let temp_dir = tempfile::tempdir().unwrap();
     let file_path = temp_dir.path().join("test_report.json");
     let filename = file_path.to_str().unwrap();
 
     // Save the report
-    let result = Reporter::save_json_report(&report, filename);
+    let result = Reporter::save_json_report(&report, &report.pages, filename, JsonStyle::SnakeCase);
     assert!(result.is_ok());
 
     // Verify the file exists and can be read
@@ -418,6 +843,142 @@ fn test_save_json_report() {
     assert_eq!(deserialized.summary.errors, 1);
 }
 
+#[test]
+fn test_save_report_dir_writes_a_file_per_page_and_an_index() {
+    use scoutly::report_dir::{INDEX_FILE_NAME, save_report_dir};
+
+    let page =
+        |url: &str, issues| create_test_page(url, Some(200), Some("Page"), issues, vec![], 0);
+    let mut pages = HashMap::from([
+        (
+            "https://example.com/".to_string(),
+            page("https://example.com/", vec![]),
+        ),
+        (
+            "https://example.com/blog?page=2".to_string(),
+            page(
+                "https://example.com/blog?page=2",
+                vec![create_test_issue(IssueSeverity::Error, "Test error")],
+            ),
+        ),
+    ]);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages.clone(),
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    let written =
+        save_report_dir(&report, &report.pages, dir.path(), JsonStyle::SnakeCase).unwrap();
+    assert_eq!(written, 2);
+
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join(INDEX_FILE_NAME)).unwrap())
+            .unwrap();
+    assert_eq!(index["summary"]["total_pages"], 2);
+    assert_eq!(index["pages"][0]["url"], "https://example.com/");
+    assert_eq!(index["pages"][0]["file"], "example.com_index.json");
+    assert_eq!(index["pages"][1]["file"], "example.com_blog_page_2.json");
+    assert_eq!(index["pages"][1]["issues"], 1);
+    let blog: PageInfo = serde_json::from_str(
+        &std::fs::read_to_string(dir.path().join("example.com_blog_page_2.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(blog.url, "https://example.com/blog?page=2");
+
+    // A page that is gone on the next run has its file removed
+    pages.remove("https://example.com/blog?page=2");
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    save_report_dir(&report, &report.pages, dir.path(), JsonStyle::CamelCase).unwrap();
+    assert!(!dir.path().join("example.com_blog_page_2.json").exists());
+    let home = std::fs::read_to_string(dir.path().join("example.com_index.json")).unwrap();
+    assert!(home.contains("\"statusCode\": 200"), "{home}");
+}
+
+#[test]
+fn test_page_file_name_cuts_long_urls_and_keeps_them_apart() {
+    use scoutly::report_dir::page_file_name;
+
+    let long_path = "a".repeat(300);
+    let first = page_file_name(&format!("https://example.com/{long_path}?page=1"), "json");
+    let second = page_file_name(&format!("https://example.com/{long_path}?page=2"), "json");
+
+    assert!(first.len() < 255, "{first}");
+    assert!(first.starts_with("example.com_aaa"), "{first}");
+    assert!(first.ends_with(".json"), "{first}");
+    assert_ne!(first, second);
+    assert_eq!(
+        page_file_name("https://example.com/blog", "md"),
+        "example.com_blog.md"
+    );
+}
+
+#[test]
+fn test_save_sitemap_lists_indexable_internal_pages() {
+    let page = |url: &str, status_code: u16| {
+        create_test_page(url, Some(status_code), None, vec![], vec![], 0)
+    };
+    let mut pages: HashMap<String, PageInfo> = [
+        page("https://example.com/", 200),
+        page("https://example.com/search?q=a&b", 200),
+        page("https://example.com/docs#install", 200),
+        page("https://example.com/docs#usage", 200),
+        page("https://example.com/missing", 404),
+        page("https://other.example/", 200),
+    ]
+    .into_iter()
+    .map(|page| (page.url.clone(), page))
+    .collect();
+    pages
+        .get_mut("https://example.com/docs#usage")
+        .unwrap()
+        .last_modified = Some("2026-01-15T08:30:00Z".to_string());
+    let mut image = page("https://example.com/logo.png", 200);
+    image.content_type = Some("image/png".to_string());
+    pages.insert(image.url.clone(), image);
+
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("sitemap.xml");
+    Reporter::save_sitemap(
+        &report.start_url,
+        &report.pages,
+        &HtmlContentTypes::default(),
+        file_path.to_str().unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(file_path).unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>https://example.com/</loc>
+  </url>
+  <url>
+    <loc>https://example.com/docs</loc>
+    <lastmod>2026-01-15T08:30:00Z</lastmod>
+  </url>
+  <url>
+    <loc>https://example.com/search?q=a&amp;b</loc>
+  </url>
+</urlset>
+"#
+    );
+}
+
 #[test]
 fn test_pages_cloned_in_report() {
     let mut pages = HashMap::new();
@@ -433,12 +994,61 @@ fn test_pages_cloned_in_report() {
 
     pages.insert("https://example.com".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     assert_eq!(report.pages.len(), 1);
     assert!(report.pages.contains_key("https://example.com"));
 }
 
+#[test]
+fn test_generate_report_fingerprints_issues() {
+    let mut pages = HashMap::new();
+    pages.insert(
+        "https://example.com/page".to_string(),
+        create_test_page(
+            "https://example.com/page",
+            Some(200),
+            None,
+            vec![create_test_issue(IssueSeverity::Error, "Missing title")],
+            vec![],
+            0,
+        ),
+    );
+
+    let first = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages.clone(),
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    let second = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages.clone(),
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+
+    let fingerprint = first.pages["https://example.com/page"].issues[0]
+        .fingerprint
+        .clone()
+        .expect("Report issues should be fingerprinted");
+    assert_eq!(
+        second.pages["https://example.com/page"].issues[0].fingerprint,
+        Some(fingerprint),
+        "Fingerprints should be stable across runs"
+    );
+    assert!(
+        pages["https://example.com/page"].issues[0]
+            .fingerprint
+            .is_none()
+    );
+}
+
 #[test]
 fn test_print_text_report_with_open_graph_tags() {
     let mut pages = HashMap::new();
@@ -448,11 +1058,26 @@ fn test_print_text_report_with_open_graph_tags() {
         url: "https://example.com/og-page".to_string(),
         status_code: Some(200),
         content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
         title: Some("Page with OG Tags".to_string()),
         meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
         h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
         links: vec![],
         images: vec![],
+        weight: Default::default(),
         open_graph: OpenGraphTags {
             og_title: Some("OG Title".to_string()),
             og_description: Some("OG Description".to_string()),
@@ -467,14 +1092,25 @@ fn test_print_text_report_with_open_graph_tags() {
             "Test issue to trigger display",
         )],
         crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+        omitted_issues: vec![],
+        content: Default::default(),
     };
 
     pages.insert("https://example.com/og-page".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     // This test ensures the Open Graph display code path is covered
-    Reporter::print_text_report(&report);
+    Reporter::print_text_report(&report, &report.pages, &TextReportOptions::default()).unwrap();
 }
 
 #[test]
@@ -486,11 +1122,26 @@ fn test_print_text_report_with_partial_open_graph_tags() {
         url: "https://example.com/partial-og".to_string(),
         status_code: Some(200),
         content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
         title: Some("Page with Partial OG Tags".to_string()),
         meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
         h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
         links: vec![],
         images: vec![],
+        weight: Default::default(),
         open_graph: OpenGraphTags {
             og_title: Some("Partial OG Title".to_string()),
             og_description: None,
@@ -505,12 +1156,183 @@ fn test_print_text_report_with_partial_open_graph_tags() {
             "Test warning issue",
         )],
         crawl_depth: 1,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+        omitted_issues: vec![],
+        content: Default::default(),
     };
 
     pages.insert("https://example.com/partial-og".to_string(), page);
 
-    let report = Reporter::generate_report("https://example.com", &pages);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
 
     // This test ensures partial OG tags are displayed correctly
-    Reporter::print_text_report(&report);
+    Reporter::print_text_report(&report, &report.pages, &TextReportOptions::default()).unwrap();
+}
+
+#[test]
+fn test_referenced_rules_lists_each_issue_type_once() {
+    let mut pages = HashMap::new();
+    pages.insert(
+        "https://example.com/a".to_string(),
+        create_test_page(
+            "https://example.com/a",
+            Some(200),
+            None,
+            vec![
+                create_test_issue(IssueSeverity::Info, "Redirect 1"),
+                create_test_issue(IssueSeverity::Error, "Missing title"),
+            ],
+            vec![],
+            0,
+        ),
+    );
+    pages.insert(
+        "https://example.com/b".to_string(),
+        create_test_page(
+            "https://example.com/b",
+            Some(200),
+            None,
+            vec![create_test_issue(IssueSeverity::Info, "Redirect 2")],
+            vec![],
+            1,
+        ),
+    );
+
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    let rules = Reporter::referenced_rules(&report.pages).unwrap();
+
+    let issue_types: Vec<_> = rules.iter().map(|rule| rule.issue_type).collect();
+    assert_eq!(
+        issue_types,
+        vec![IssueType::MissingTitle, IssueType::Redirect]
+    );
+    assert!(
+        rules
+            .iter()
+            .all(|rule| rule.help_url.starts_with("https://"))
+    );
+
+    Reporter::print_text_report(&report, &report.pages, &TextReportOptions::default()).unwrap();
+}
+
+#[test]
+fn test_truncate_middle_keeps_both_ends_of_long_urls() {
+    let url = "https://example.com/blog/2025/10/a-very-long-article-slug";
+
+    assert_eq!(truncate_middle(url, 200), url);
+    assert_eq!(truncate_middle(url, 21), "https://ex…ticle-slug");
+    assert_eq!(truncate_middle(url, 21).chars().count(), 21);
+    assert_eq!(truncate_middle("https://例え.jp/ページ", 8), "http…ページ");
+    assert_eq!(truncate_middle(url, 0), "");
+}
+
+#[test]
+fn test_print_text_report_with_page_limit_and_width() {
+    let mut pages = HashMap::new();
+    for index in 0..5 {
+        let url = format!("https://example.com/a/long/path/to/page-{index}");
+        pages.insert(
+            url.clone(),
+            create_test_page(
+                &url,
+                Some(200),
+                None,
+                vec![create_test_issue(IssueSeverity::Warning, "Warning")],
+                vec![],
+                1,
+            ),
+        );
+    }
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+
+    Reporter::print_text_report(
+        &report,
+        &report.pages,
+        &TextReportOptions {
+            width: Some(30),
+            max_pages_shown: Some(2),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_write_json_report_from_a_page_store_matches_the_saved_report() {
+    use scoutly::page_store::{PageStore, SqlitePageStore};
+
+    let mut pages = HashMap::new();
+    pages.insert(
+        "https://example.com/".to_string(),
+        create_test_page(
+            "https://example.com/",
+            Some(200),
+            Some("Home"),
+            vec![create_test_issue(IssueSeverity::Warning, "Title is short")],
+            vec![create_test_link("https://example.com/missing", Some(404))],
+            0,
+        ),
+    );
+    pages.insert(
+        "https://example.com/about".to_string(),
+        create_test_page(
+            "https://example.com/about",
+            Some(200),
+            Some("About"),
+            vec![],
+            vec![],
+            1,
+        ),
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = SqlitePageStore::create(&dir.path().join("pages.sqlite")).unwrap();
+    for (key, page) in &pages {
+        store.insert(key.clone(), page.clone()).unwrap();
+    }
+    let mut stored_report = Reporter::generate_report(
+        "https://example.com/",
+        &mut store,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    assert!(stored_report.pages.is_empty(), "pages stay in the store");
+
+    let mut report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    report.limit_reached = Some("request limit of 2 reached".to_string());
+    stored_report.limit_reached = report.limit_reached.clone();
+    stored_report.timestamp = report.timestamp.clone();
+    assert_eq!(stored_report.summary, report.summary);
+    assert_eq!(stored_report.inlinks, report.inlinks);
+
+    for style in [JsonStyle::SnakeCase, JsonStyle::CamelCase] {
+        let mut streamed = Vec::new();
+        Reporter::write_json_report(&stored_report, &store, &mut streamed, style).unwrap();
+        let streamed: serde_json::Value = serde_json::from_slice(&streamed).unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_str(&json_style::to_string_pretty(&report, style).unwrap()).unwrap();
+        assert_eq!(streamed, saved, "{style:?}");
+    }
 }
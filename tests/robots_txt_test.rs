@@ -94,8 +94,11 @@ async fn test_robots_txt_respected() {
         requests_per_second: None,
         concurrent_requests: 1,
         respect_robots_txt: true,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
     };
-    let mut crawler = Crawler::new(&base_url, config).expect("Failed to create crawler");
+    let mut crawler = Crawler::new(&[&base_url], config).expect("Failed to create crawler");
 
     crawler.crawl().await.expect("Crawl failed");
 
@@ -155,8 +158,11 @@ async fn test_robots_txt_disabled() {
         requests_per_second: None,
         concurrent_requests: 1,
         respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
     };
-    let mut crawler = Crawler::new(&base_url, config).expect("Failed to create crawler");
+    let mut crawler = Crawler::new(&[&base_url], config).expect("Failed to create crawler");
 
     crawler.crawl().await.expect("Crawl failed");
 
@@ -214,8 +220,11 @@ async fn test_robots_txt_not_found() {
         requests_per_second: None,
         concurrent_requests: 1,
         respect_robots_txt: true,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
     };
-    let mut crawler = Crawler::new(&base_url, config).expect("Failed to create crawler");
+    let mut crawler = Crawler::new(&[&base_url], config).expect("Failed to create crawler");
 
     // Should succeed even though robots.txt doesn't exist
     crawler.crawl().await.expect("Crawl should succeed");
@@ -272,8 +281,11 @@ async fn test_robots_txt_server_error() {
         requests_per_second: None,
         concurrent_requests: 1,
         respect_robots_txt: true,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
     };
-    let mut crawler = Crawler::new(&base_url, config).expect("Failed to create crawler");
+    let mut crawler = Crawler::new(&[&base_url], config).expect("Failed to create crawler");
 
     // Should succeed even though robots.txt returns 500
     crawler.crawl().await.expect("Crawl should succeed");
@@ -299,7 +311,7 @@ async fn test_robots_txt_cache() {
 
     // First fetch - should fetch from server
     robots
-        .fetch(&client, &parsed_url)
+        .fetch(&client, &parsed_url, None)
         .await
         .expect("First fetch failed");
 
@@ -309,7 +321,7 @@ async fn test_robots_txt_cache() {
 
     // Second fetch - should use cache
     robots
-        .fetch(&client, &parsed_url)
+        .fetch(&client, &parsed_url, None)
         .await
         .expect("Second fetch failed");
 
@@ -332,7 +344,7 @@ async fn test_robots_txt_connection_failure() {
 
     // Fetch should succeed despite connection failure
     // (it treats connection errors as "no robots.txt, allow all")
-    let result = robots.fetch(&client, &parsed_url).await;
+    let result = robots.fetch(&client, &parsed_url, None).await;
     assert!(
         result.is_ok(),
         "Fetch should succeed even when connection fails"
@@ -345,3 +357,90 @@ async fn test_robots_txt_connection_failure() {
         "Should allow all URLs when robots.txt cannot be fetched"
     );
 }
+
+#[tokio::test]
+#[serial_test::serial]
+async fn test_link_checker_skips_external_links_disallowed_by_robots() {
+    use scoutly::link_checker::LinkChecker;
+    use scoutly::models::{Link, LinkSkipReason, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let external_base_url = start_robots_test_server().await;
+    let link = |path: &str| Link {
+        url: format!("{external_base_url}{path}"),
+        text: path.to_string(),
+        is_external: true,
+        kind: Default::default(),
+        rel: None,
+        status_code: None,
+        redirected_url: None,
+        check_error: None,
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
+    };
+
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::new();
+    pages.insert(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: None,
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec![],
+            outline: vec![],
+            aliases: vec![],
+            links: vec![link("/admin"), link("/allowed")],
+            images: vec![],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    );
+
+    let mut checker = LinkChecker::new();
+    checker.set_respect_robots_for_links(true);
+    checker
+        .check_all_links(&mut pages, false)
+        .await
+        .expect("Link checking failed");
+
+    let page = pages.get(&page_url).unwrap();
+    let admin = &page.links[0];
+    assert_eq!(
+        admin.status_code, None,
+        "Disallowed link should not be requested"
+    );
+    assert_eq!(admin.skip_reason, Some(LinkSkipReason::Robots));
+    assert_eq!(
+        admin.skip_reason.map(LinkSkipReason::label),
+        Some("not checked (robots)")
+    );
+
+    let allowed = &page.links[1];
+    assert_eq!(allowed.status_code, Some(200));
+    assert_eq!(allowed.skip_reason, None);
+    assert!(page.issues.is_empty());
+}
@@ -0,0 +1,129 @@
+#![cfg(unix)]
+
+use scoutly::models::{HtmlContentTypes, IssueSeverity, IssueType, PageInfo, SeoIssue};
+use scoutly::reporter::Reporter;
+use scoutly::screenshot::{ScreenshotMode, Screenshotter, screenshot_dir_for};
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn page(url: &str, content_type: &str, issues: Vec<SeoIssue>) -> (String, PageInfo) {
+    (
+        url.to_string(),
+        PageInfo {
+            url: url.to_string(),
+            status_code: Some(200),
+            content_type: Some(content_type.to_string()),
+            issues,
+            ..Default::default()
+        },
+    )
+}
+
+/// Stand-in for Chrome that writes a file to the `--screenshot=` path it is given
+fn fake_browser(dir: &Path, exit_code: i32) -> PathBuf {
+    let path = dir.join("fake-chrome");
+    fs::write(
+        &path,
+        format!(
+            "#!/bin/sh\nfor arg in \"$@\"; do\n  case \"$arg\" in\n    --screenshot=*) [ {exit_code} -eq 0 ] && printf png > \"${{arg#--screenshot=}}\" ;;\n  esac\ndone\nexit {exit_code}\n"
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn test_screenshot_dir_sits_next_to_report() {
+    assert_eq!(
+        screenshot_dir_for(Path::new("out/report.json")),
+        PathBuf::from("out/report-screenshots")
+    );
+}
+
+#[tokio::test]
+async fn test_capture_report_screenshots_pages_with_errors() {
+    let dir = tempdir().unwrap();
+    let browser = fake_browser(dir.path(), 0);
+    let pages = HashMap::from([
+        page(
+            "https://example.com/broken",
+            "text/html",
+            vec![SeoIssue::new(
+                IssueSeverity::Error,
+                IssueType::MissingTitle,
+                "Page is missing a title tag",
+            )],
+        ),
+        page("https://example.com/", "text/html", vec![]),
+        page(
+            "https://example.com/file.pdf",
+            "application/pdf",
+            vec![SeoIssue::new(
+                IssueSeverity::Error,
+                IssueType::BrokenLink,
+                "Broken link",
+            )],
+        ),
+    ]);
+    let mut report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    let screenshot_dir = dir.path().join("report-screenshots");
+
+    let captured = Screenshotter::new(Some(browser.to_str().unwrap()))
+        .unwrap()
+        .capture_pages(&mut report.pages, ScreenshotMode::Errors, &screenshot_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(captured, 1);
+    let screenshot = report.pages["https://example.com/broken"]
+        .screenshot
+        .clone()
+        .expect("Page with errors should have a screenshot");
+    assert!(screenshot.starts_with("report-screenshots/"));
+    assert!(dir.path().join(&screenshot).exists());
+    assert_eq!(report.pages["https://example.com/"].screenshot, None);
+    assert_eq!(
+        report.pages["https://example.com/file.pdf"].screenshot,
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_failed_captures_leave_page_without_screenshot() {
+    let dir = tempdir().unwrap();
+    let browser = fake_browser(dir.path(), 1);
+    let pages = HashMap::from([page("https://example.com/", "text/html", vec![])]);
+    let mut report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+    let screenshotter = Screenshotter::new(Some(browser.to_str().unwrap())).unwrap();
+
+    let error = screenshotter
+        .capture("https://example.com/", &dir.path().join("page.png"))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("did not produce a screenshot"));
+
+    let captured = screenshotter
+        .capture_pages(
+            &mut report.pages,
+            ScreenshotMode::All,
+            &dir.path().join("shots"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(captured, 0);
+    assert_eq!(report.pages["https://example.com/"].screenshot, None);
+}
@@ -0,0 +1,165 @@
+use scoutly::models::{HtmlContentTypes, IssueSeverity, IssueType, PageContent, PageInfo};
+use scoutly::rules::{PathRules, RuleOverride};
+use scoutly::scripting::ScriptChecks;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use tempfile::tempdir;
+
+const PRODUCT_PRICE_SCRIPT: &str = r#"
+if page.path.starts_with("/products/") && select(page.html, ".price").len() == 0 {
+    error("Product page has no price element");
+}
+for image in select(page.html, "img") {
+    if image.attrs.loading != "lazy" {
+        info("Image not lazy-loaded: " + image.attrs.src);
+    }
+}
+"#;
+
+fn page(path: &str, html: &str) -> (String, PageInfo) {
+    let url = format!("https://shop.test{path}");
+    (
+        url.clone(),
+        PageInfo {
+            url,
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            content: PageContent {
+                text: "Blue widget".to_string(),
+                html: Some(html.to_string()),
+                ..Default::default()
+            },
+            crawl_depth: 1,
+            ..Default::default()
+        },
+    )
+}
+
+fn pages() -> HashMap<String, PageInfo> {
+    [
+        page(
+            "/products/widget",
+            r#"<h1>Widget</h1><span class="price">$5</span><img src="/w.png" loading="lazy">"#,
+        ),
+        page("/products/gadget", r#"<h1>Gadget</h1><img src="/g.png">"#),
+        page("/about", "<h1>About</h1>"),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn write_script(dir: &tempfile::TempDir, name: &str, source: &str) -> String {
+    let path = dir.path().join(name);
+    fs::write(&path, source).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn custom_issues(page: &PageInfo) -> Vec<(IssueSeverity, &str, Option<&str>)> {
+    page.issues
+        .iter()
+        .filter(|issue| issue.issue_type == IssueType::CustomCheck)
+        .map(|issue| {
+            (
+                issue.severity,
+                issue.message.as_str(),
+                issue.check.as_deref(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_script_checks_report_issues_against_page_data_and_dom() {
+    let dir = tempdir().unwrap();
+    let script = write_script(&dir, "product-price.rhai", PRODUCT_PRICE_SCRIPT);
+    let mut pages = pages();
+
+    ScriptChecks::load(&[script]).unwrap().run(
+        &mut pages,
+        &PathRules::default(),
+        &HtmlContentTypes::default(),
+    );
+
+    assert!(custom_issues(&pages["https://shop.test/products/widget"]).is_empty());
+    assert_eq!(
+        custom_issues(&pages["https://shop.test/products/gadget"]),
+        vec![
+            (
+                IssueSeverity::Error,
+                "Product page has no price element",
+                Some("product-price")
+            ),
+            (
+                IssueSeverity::Info,
+                "Image not lazy-loaded: /g.png",
+                Some("product-price")
+            ),
+        ]
+    );
+    assert!(custom_issues(&pages["https://shop.test/about"]).is_empty());
+    assert!(
+        pages.values().all(|page| page.content.html.is_none()),
+        "HTML is only kept until the scripts have run"
+    );
+}
+
+#[test]
+fn test_script_checks_respect_disabled_paths_and_survive_script_errors() {
+    let dir = tempdir().unwrap();
+    let scripts = [
+        write_script(&dir, "runaway.rhai", "loop { }"),
+        write_script(
+            &dir,
+            "words.rhai",
+            r#"if page.word_count < 5 { warning(`Only ${page.word_count} words`); }"#,
+        ),
+    ];
+    let rules = PathRules::new(&BTreeMap::from([(
+        "/products/**".to_string(),
+        RuleOverride {
+            disable: Some(vec![IssueType::CustomCheck]),
+            ..Default::default()
+        },
+    )]))
+    .unwrap();
+    let mut pages = pages();
+
+    ScriptChecks::load(&scripts)
+        .unwrap()
+        .run(&mut pages, &rules, &HtmlContentTypes::default());
+
+    assert_eq!(
+        custom_issues(&pages["https://shop.test/about"]),
+        vec![(IssueSeverity::Warning, "Only 2 words", Some("words"))]
+    );
+    assert!(custom_issues(&pages["https://shop.test/products/gadget"]).is_empty());
+}
+
+#[test]
+fn test_script_checks_fail_to_load_invalid_scripts() {
+    let dir = tempdir().unwrap();
+    let script = write_script(&dir, "broken.rhai", "if page.title {");
+
+    let error = ScriptChecks::load(&[script]).err().unwrap();
+    assert!(error.to_string().contains("Failed to compile script"));
+    assert!(
+        ScriptChecks::load(&["missing.rhai".to_string()])
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Failed to read script: missing.rhai")
+    );
+}
+
+#[test]
+fn test_custom_check_fingerprints_differ_per_check() {
+    use scoutly::models::SeoIssue;
+
+    let issue = |check: &str| {
+        SeoIssue::new(IssueSeverity::Error, IssueType::CustomCheck, "failed").with_check(check)
+    };
+    assert_ne!(
+        issue("price").compute_fingerprint("https://shop.test/"),
+        issue("stock").compute_fingerprint("https://shop.test/")
+    );
+}
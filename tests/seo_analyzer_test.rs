@@ -1,7 +1,7 @@
 mod server;
 
 use scoutly::crawler::{Crawler, CrawlerConfig};
-use scoutly::models::{IssueSeverity, IssueType};
+use scoutly::models::{HtmlContentTypes, IssueSeverity, IssueType};
 use scoutly::seo_analyzer::SeoAnalyzer;
 use server::get_test_server_url;
 
@@ -18,12 +18,15 @@ async fn test_seo_analyzer() {
         requests_per_second: None,
         concurrent_requests: 1,
         respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
     };
-    let mut crawler = Crawler::new(&base_url, config).expect("Failed to create crawler");
+    let mut crawler = Crawler::new(&[&base_url], config).expect("Failed to create crawler");
 
     crawler.crawl().await.expect("Crawl failed");
 
-    SeoAnalyzer::analyze_pages(&mut crawler.pages);
+    SeoAnalyzer::analyze_pages(&mut crawler.pages).unwrap();
 
     let test_cases = [
         TestCase {
@@ -166,8 +169,11 @@ async fn test_open_graph_extraction() {
         requests_per_second: None,
         concurrent_requests: 1,
         respect_robots_txt: false,
+        audit_vary: false,
+        include: vec![],
+        exclude: vec![],
     };
-    let mut crawler = Crawler::new(&base_url, config).expect("Failed to create crawler");
+    let mut crawler = Crawler::new(&[&base_url], config).expect("Failed to create crawler");
 
     crawler.crawl().await.expect("Crawl failed");
 
@@ -252,3 +258,1127 @@ async fn test_open_graph_extraction() {
         "og:locale should be None when not present"
     );
 }
+
+#[test]
+fn test_seo_issues_include_structured_metadata() {
+    use scoutly::models::{Image, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let page_url = "https://example.com/".to_string();
+    let mut pages = HashMap::new();
+    pages.insert(
+        page_url.clone(),
+        PageInfo {
+            url: page_url.clone(),
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: None,
+            response_time_ms: None,
+            content_length: None,
+            redirect_chain: vec![],
+            duplicate_of: None,
+            title: Some("Short title".to_string()),
+            meta_description: None,
+            meta_robots: None,
+            x_robots_tag: None,
+            canonical: None,
+            alternates: vec![],
+            link_headers: Default::default(),
+            vary: vec![],
+            vary_observed: vec![],
+            h1_tags: vec!["Heading".to_string()],
+            outline: vec![],
+            aliases: vec![],
+            links: vec![],
+            images: vec![
+                Image {
+                    src: "https://example.com/described.png".to_string(),
+                    alt: Some("Described".to_string()),
+                    width: None,
+                    height: None,
+                },
+                Image {
+                    src: "https://example.com/undescribed.png".to_string(),
+                    alt: None,
+                    width: None,
+                    height: None,
+                },
+            ],
+            weight: Default::default(),
+            open_graph: OpenGraphTags::default(),
+            content: Default::default(),
+            issues: vec![],
+            omitted_issues: vec![],
+            crawl_depth: 0,
+            source: Default::default(),
+            screenshot: None,
+            html_snapshot: None,
+            field_data: None,
+        },
+    );
+
+    SeoAnalyzer::analyze_pages(&mut pages).unwrap();
+    let page = pages.get(&page_url).unwrap();
+
+    let short_title = page
+        .issues
+        .iter()
+        .find(|issue| issue.issue_type == IssueType::TitleTooShort)
+        .expect("Short title should be reported");
+    assert_eq!(short_title.expected.as_deref(), Some("50-60 chars"));
+    assert_eq!(short_title.actual.as_deref(), Some("11 chars"));
+    assert_eq!(short_title.offending_url, None);
+    assert_eq!(
+        short_title.help_url.as_deref(),
+        Some(scoutly::rules::rule_for(IssueType::TitleTooShort).help_url)
+    );
+
+    let missing_alt = page
+        .issues
+        .iter()
+        .find(|issue| issue.issue_type == IssueType::MissingImageAlt)
+        .expect("Missing alt text should be reported");
+    assert_eq!(
+        missing_alt.offending_url.as_deref(),
+        Some("https://example.com/undescribed.png")
+    );
+    assert_eq!(
+        missing_alt.actual.as_deref(),
+        Some("1 image(s) without alt text")
+    );
+}
+
+#[test]
+fn test_seo_analyzer_applies_path_rule_overrides() {
+    use scoutly::models::{OpenGraphTags, PageInfo};
+    use scoutly::rules::{PathRules, RuleOverride};
+    use std::collections::{BTreeMap, HashMap};
+
+    let page = |url: &str| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: Some("Rust".to_string()),
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec!["Rust".to_string()],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: Default::default(),
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let tag_url = "https://example.com/blog/tags/rust";
+    let post_url = "https://example.com/blog/posts/rust";
+    let mut pages = HashMap::from([
+        (tag_url.to_string(), page(tag_url)),
+        (post_url.to_string(), page(post_url)),
+    ]);
+
+    let rules = PathRules::new(&BTreeMap::from([(
+        "/blog/tags/*".to_string(),
+        RuleOverride {
+            title_min_length: Some(3),
+            disable: Some(vec![IssueType::ThinContent]),
+            ..Default::default()
+        },
+    )]))
+    .unwrap();
+    SeoAnalyzer::analyze_pages_with_rules(&mut pages, &rules, &HtmlContentTypes::default())
+        .unwrap();
+
+    let has_issue = |url: &str, issue_type: IssueType| {
+        pages[url].issues.iter().any(|i| i.issue_type == issue_type)
+    };
+    assert!(!has_issue(tag_url, IssueType::TitleTooShort));
+    assert!(!has_issue(tag_url, IssueType::ThinContent));
+    assert!(has_issue(tag_url, IssueType::MissingMetaDescription));
+    assert!(has_issue(post_url, IssueType::TitleTooShort));
+    assert!(has_issue(post_url, IssueType::ThinContent));
+}
+
+#[test]
+fn test_seo_analyzer_analyzes_the_configured_html_content_types() {
+    use scoutly::models::{OpenGraphTags, PageInfo};
+    use scoutly::rules::PathRules;
+    use std::collections::HashMap;
+
+    let url = "https://example.com/feed.xml";
+    let page = PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/xml; charset=utf-8".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: None,
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: Default::default(),
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let missing_title = |content_types: &HtmlContentTypes| {
+        let mut pages = HashMap::from([(url.to_string(), page.clone())]);
+        SeoAnalyzer::analyze_pages_with_rules(&mut pages, &PathRules::default(), content_types)
+            .unwrap();
+        pages[url]
+            .issues
+            .iter()
+            .any(|issue| issue.issue_type == IssueType::MissingTitle)
+    };
+
+    assert!(!missing_title(&HtmlContentTypes::default()));
+    assert!(missing_title(&HtmlContentTypes::new(&[
+        "text/html",
+        "text/xml"
+    ])));
+    assert!(!missing_title(&HtmlContentTypes::new(&["text/html"])));
+}
+
+#[test]
+fn test_seo_analyzer_reports_performance_budget_violations() {
+    use scoutly::models::{OpenGraphTags, PageInfo, PageWeight};
+    use scoutly::rules::{PathRules, PerformanceBudget, RuleOverride};
+    use std::collections::{BTreeMap, HashMap};
+
+    let page = |url: &str| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: None,
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: PageWeight {
+            html_bytes: 120_000,
+            requests: 40,
+            scripts: 8,
+        },
+        open_graph: OpenGraphTags::default(),
+        content: Default::default(),
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let home_url = "https://example.com/";
+    let app_url = "https://example.com/app/dashboard";
+    let mut pages = HashMap::from([
+        (home_url.to_string(), page(home_url)),
+        (app_url.to_string(), page(app_url)),
+    ]);
+
+    let rules = PathRules::new(&BTreeMap::from([(
+        "/app/**".to_string(),
+        RuleOverride {
+            budget: PerformanceBudget {
+                max_scripts: Some(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )]))
+    .unwrap()
+    .with_base(RuleOverride {
+        budget: PerformanceBudget {
+            max_html_bytes: Some(100_000),
+            max_requests: Some(50),
+            max_scripts: Some(5),
+        },
+        ..Default::default()
+    });
+    SeoAnalyzer::analyze_pages_with_rules(&mut pages, &rules, &HtmlContentTypes::default())
+        .unwrap();
+
+    let budget_issues = |url: &str| -> Vec<_> {
+        pages[url]
+            .issues
+            .iter()
+            .filter(|issue| issue.issue_type == IssueType::PerformanceBudgetExceeded)
+            .map(|issue| {
+                (
+                    issue.expected.clone().unwrap(),
+                    issue.actual.clone().unwrap(),
+                )
+            })
+            .collect()
+    };
+    assert_eq!(
+        budget_issues(home_url),
+        vec![
+            (
+                "at most 100000 bytes".to_string(),
+                "120000 bytes".to_string()
+            ),
+            ("at most 5 scripts".to_string(), "8 scripts".to_string()),
+        ]
+    );
+    assert_eq!(
+        budget_issues(app_url),
+        vec![(
+            "at most 100000 bytes".to_string(),
+            "120000 bytes".to_string()
+        )]
+    );
+    // Each budget is its own check, so a baseline tells them apart
+    let fingerprints: Vec<_> = pages[home_url]
+        .issues
+        .iter()
+        .filter(|issue| issue.issue_type == IssueType::PerformanceBudgetExceeded)
+        .map(|issue| (issue.check.as_deref(), issue.compute_fingerprint(home_url)))
+        .collect();
+    assert_eq!(fingerprints[0].0, Some("html_bytes"));
+    assert_eq!(fingerprints[1].0, Some("scripts"));
+    assert_ne!(fingerprints[0].1, fingerprints[1].1);
+}
+
+#[test]
+fn test_seo_analyzer_reports_link_header_conflicts() {
+    use scoutly::models::{HreflangLink, LinkHeaders, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let alternate = |hreflang: &str, url: &str| HreflangLink {
+        hreflang: hreflang.to_string(),
+        url: url.to_string(),
+        skip_reason: None,
+    };
+    let page = |url: &str, html_canonical: &str, html_de: &str| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: None,
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: Some(html_canonical.to_string()),
+        alternates: vec![
+            alternate("de", html_de),
+            alternate("fr", "https://example.com/fr"),
+        ],
+        link_headers: LinkHeaders {
+            canonical: Some("https://example.com/".to_string()),
+            alternates: vec![
+                alternate("DE", "https://example.com/de"),
+                alternate("fr", "https://example.com/fr"),
+            ],
+        },
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: Default::default(),
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+        vary: vec![],
+        vary_observed: vec![],
+    };
+    let agreeing_url = "https://example.com/";
+    let conflicting_url = "https://example.com/?ref=nav";
+    let mut pages = HashMap::from([
+        (
+            agreeing_url.to_string(),
+            page(
+                agreeing_url,
+                "https://example.com/",
+                "https://example.com/de",
+            ),
+        ),
+        (
+            conflicting_url.to_string(),
+            page(
+                conflicting_url,
+                "https://example.com/?ref=nav",
+                "https://example.com/de/",
+            ),
+        ),
+    ]);
+
+    SeoAnalyzer::analyze_pages(&mut pages).unwrap();
+
+    let conflicts = |url: &str| -> Vec<_> {
+        pages[url]
+            .issues
+            .iter()
+            .filter(|issue| {
+                matches!(
+                    issue.issue_type,
+                    IssueType::CanonicalConflict | IssueType::HreflangConflict
+                )
+            })
+            .map(|issue| {
+                (
+                    issue.issue_type,
+                    issue.expected.clone().unwrap(),
+                    issue.actual.clone().unwrap(),
+                )
+            })
+            .collect()
+    };
+    assert!(conflicts(agreeing_url).is_empty());
+    assert_eq!(
+        conflicts(conflicting_url),
+        vec![
+            (
+                IssueType::CanonicalConflict,
+                "https://example.com/?ref=nav".to_string(),
+                "https://example.com/".to_string()
+            ),
+            (
+                IssueType::HreflangConflict,
+                "https://example.com/de/".to_string(),
+                "https://example.com/de".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_seo_analyzer_reports_undeclared_vary() {
+    use scoutly::models::{OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let page = |url: &str, vary: &[&str], vary_observed: &[&str]| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html; charset=utf-8".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: None,
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vary.iter().map(|name| name.to_string()).collect(),
+        vary_observed: vary_observed.iter().map(|name| name.to_string()).collect(),
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: Default::default(),
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let undeclared_url = "https://example.com/undeclared";
+    let declared_url = "https://example.com/declared";
+    let wildcard_url = "https://example.com/wildcard";
+    let mut pages = HashMap::from([
+        (
+            undeclared_url.to_string(),
+            page(undeclared_url, &[], &["user-agent", "cookie"]),
+        ),
+        (
+            declared_url.to_string(),
+            page(
+                declared_url,
+                &["user-agent", "accept-encoding"],
+                &["user-agent"],
+            ),
+        ),
+        (
+            wildcard_url.to_string(),
+            page(wildcard_url, &["*"], &["cookie"]),
+        ),
+    ]);
+
+    SeoAnalyzer::analyze_pages(&mut pages).unwrap();
+
+    let vary_issues = |url: &str| -> Vec<_> {
+        pages[url]
+            .issues
+            .iter()
+            .filter(|issue| {
+                matches!(
+                    issue.issue_type,
+                    IssueType::UndeclaredVary | IssueType::MissingVaryAcceptEncoding
+                )
+            })
+            .map(|issue| {
+                (
+                    issue.issue_type,
+                    issue.severity,
+                    issue.expected.clone().unwrap(),
+                    issue.actual.clone().unwrap(),
+                )
+            })
+            .collect()
+    };
+    assert_eq!(
+        vary_issues(undeclared_url),
+        vec![
+            (
+                IssueType::UndeclaredVary,
+                IssueSeverity::Warning,
+                "Vary includes user-agent".to_string(),
+                "no Vary header".to_string()
+            ),
+            (
+                IssueType::UndeclaredVary,
+                IssueSeverity::Warning,
+                "Vary includes cookie".to_string(),
+                "no Vary header".to_string()
+            ),
+            (
+                IssueType::MissingVaryAcceptEncoding,
+                IssueSeverity::Info,
+                "Vary includes accept-encoding".to_string(),
+                "no Vary header".to_string()
+            ),
+        ]
+    );
+    assert!(vary_issues(declared_url).is_empty());
+    assert!(vary_issues(wildcard_url).is_empty());
+}
+
+#[test]
+fn test_seo_analyzer_analyzes_fragment_variants_once() {
+    use scoutly::models::{OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let page = |url: &str| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: None,
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: Default::default(),
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let urls = [
+        "https://example.com/",
+        "https://example.com/#about",
+        "https://example.com/#faq",
+        "https://example.com/docs#install",
+        "https://example.com/docs#usage",
+    ];
+    let mut pages: HashMap<String, PageInfo> = urls
+        .iter()
+        .map(|url| (url.to_string(), page(url)))
+        .collect();
+
+    SeoAnalyzer::analyze_pages(&mut pages).unwrap();
+
+    let analyzed: Vec<&str> = urls
+        .into_iter()
+        .filter(|url| {
+            pages[*url]
+                .issues
+                .iter()
+                .any(|issue| issue.issue_type == IssueType::ThinContent)
+        })
+        .collect();
+    assert_eq!(
+        analyzed,
+        vec!["https://example.com/", "https://example.com/docs#install"]
+    );
+}
+
+#[test]
+fn test_seo_analyzer_flags_sitemap_pages_no_crawled_page_links_to() {
+    use scoutly::models::{CrawlSource, DiscoveryMethod, Link, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let sitemap_url = "https://example.com/sitemap.xml";
+    let page = |url: &str, method: DiscoveryMethod, links: &[&str]| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: None,
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: links
+            .iter()
+            .map(|link| Link {
+                url: link.to_string(),
+                text: String::new(),
+                is_external: false,
+                kind: DiscoveryMethod::Anchor,
+                rel: None,
+                status_code: Some(200),
+                redirected_url: None,
+                check_error: None,
+                skip_reason: None,
+                content_type: None,
+                content_length: None,
+            })
+            .collect(),
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: Default::default(),
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 1,
+        source: CrawlSource {
+            method,
+            found_on: (method == DiscoveryMethod::Sitemap).then(|| sitemap_url.to_string()),
+        },
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let mut pages: HashMap<String, PageInfo> = [
+        page(
+            "https://example.com/",
+            DiscoveryMethod::Seed,
+            &["https://example.com/linked#top"],
+        ),
+        page("https://example.com/linked", DiscoveryMethod::Sitemap, &[]),
+        page(
+            "https://example.com/orphan",
+            DiscoveryMethod::Sitemap,
+            &["https://example.com/orphan#self"],
+        ),
+        page("https://example.com/found", DiscoveryMethod::Anchor, &[]),
+    ]
+    .into_iter()
+    .map(|page| (page.url.clone(), page))
+    .collect();
+
+    SeoAnalyzer::analyze_pages(&mut pages).unwrap();
+
+    let orphans: Vec<&str> = {
+        let mut orphans: Vec<&str> = pages
+            .values()
+            .filter(|page| {
+                page.issues
+                    .iter()
+                    .any(|issue| issue.issue_type == IssueType::OrphanPage)
+            })
+            .map(|page| page.url.as_str())
+            .collect();
+        orphans.sort_unstable();
+        orphans
+    };
+    assert_eq!(
+        orphans,
+        ["https://example.com/orphan"],
+        "Links from the page itself don't count"
+    );
+
+    let issue = pages["https://example.com/orphan"]
+        .issues
+        .iter()
+        .find(|issue| issue.issue_type == IssueType::OrphanPage)
+        .unwrap();
+    assert_eq!(issue.severity, IssueSeverity::Warning);
+    assert_eq!(issue.offending_url.as_deref(), Some(sitemap_url));
+}
+
+#[test]
+fn test_seo_analyzer_flags_comment_markers_and_secrets() {
+    use scoutly::models::{OpenGraphTags, PageContent, PageInfo};
+    use scoutly::rules::{PathRules, RuleOverride};
+    use std::collections::{BTreeMap, HashMap};
+
+    let page = |url: &str| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: None,
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: PageContent {
+            comments: vec![
+                "FIXME: remove before launch".to_string(),
+                "todo list styles".to_string(),
+                "api_key = 'sk_test_4eC39HqLyjWDarjtT1zdp7dc'".to_string(),
+                "Served by app-3.prod.internal:8080".to_string(),
+                "NOTE: keep in sync with the app".to_string(),
+            ],
+            ..Default::default()
+        },
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let mut pages: HashMap<String, PageInfo> =
+        ["https://example.com/", "https://example.com/docs/"]
+            .into_iter()
+            .map(|url| (url.to_string(), page(url)))
+            .collect();
+    let rules = PathRules::new(&BTreeMap::from([(
+        "/docs/**".to_string(),
+        RuleOverride {
+            comment_markers: Some(vec!["NOTE".to_string()]),
+            ..Default::default()
+        },
+    )]))
+    .unwrap();
+
+    SeoAnalyzer::analyze_pages_with_rules(&mut pages, &rules, &HtmlContentTypes::default())
+        .unwrap();
+
+    let comment_issues = |url: &str| {
+        pages[url]
+            .issues
+            .iter()
+            .filter(|issue| {
+                matches!(
+                    issue.issue_type,
+                    IssueType::CommentMarker | IssueType::CommentSecret
+                )
+            })
+            .map(|issue| {
+                (
+                    issue.severity,
+                    issue.message.as_str(),
+                    issue.actual.as_deref().unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        comment_issues("https://example.com/"),
+        [
+            (
+                IssueSeverity::Info,
+                "HTML comment contains FIXME",
+                "FIXME: remove before launch"
+            ),
+            (
+                IssueSeverity::Error,
+                "HTML comment contains what looks like a credential",
+                "api_…"
+            ),
+            (
+                IssueSeverity::Warning,
+                "HTML comment contains what looks like an internal hostname",
+                "app-3.prod.internal:8080"
+            ),
+        ],
+        "Markers match whole words with their case, and secrets are redacted"
+    );
+    assert_eq!(
+        comment_issues("https://example.com/docs/")
+            .into_iter()
+            .filter(
+                |(_, message, _)| message.starts_with("HTML comment contains ")
+                    && !message.contains("looks like")
+            )
+            .collect::<Vec<_>>(),
+        [(
+            IssueSeverity::Info,
+            "HTML comment contains NOTE",
+            "NOTE: keep in sync with the app"
+        )],
+        "Path rules replace the default markers"
+    );
+}
+
+#[test]
+fn test_seo_analyzer_flags_pages_duplicated_across_hosts() {
+    use scoutly::models::{OpenGraphTags, PageContent, PageInfo};
+    use std::collections::HashMap;
+
+    let page = |url: &str, text: &str, canonical: Option<&str>| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: Some("Pricing".to_string()),
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: canonical.map(str::to_string),
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: PageContent {
+            text: text.to_string(),
+            ..Default::default()
+        },
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 1,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let canonical = Some("https://example.com/docs");
+    let mut pages: HashMap<String, PageInfo> = [
+        page("https://example.com/pricing", "Plans start at $5", None),
+        page(
+            "https://www.example.com/pricing",
+            "Plans  start at $5\n",
+            None,
+        ),
+        page("https://example.com/about", "About us", None),
+        page(
+            "https://staging.example.com/about",
+            "About us (staging)",
+            None,
+        ),
+        page("https://example.com/docs", "Docs", canonical),
+        page("https://docs.example.com/", "Docs", canonical),
+    ]
+    .into_iter()
+    .map(|page| (page.url.clone(), page))
+    .collect();
+
+    SeoAnalyzer::analyze_pages(&mut pages).unwrap();
+
+    let mut flagged: Vec<(&str, Option<&str>)> = pages
+        .values()
+        .flat_map(|page| {
+            page.issues
+                .iter()
+                .filter(|issue| issue.issue_type == IssueType::CrossHostDuplicate)
+                .map(|issue| (page.url.as_str(), issue.actual.as_deref()))
+        })
+        .collect();
+    flagged.sort_unstable();
+    assert_eq!(
+        flagged,
+        [
+            (
+                "https://example.com/pricing",
+                Some("https://www.example.com/pricing")
+            ),
+            (
+                "https://www.example.com/pricing",
+                Some("https://example.com/pricing")
+            ),
+        ],
+        "Different text and a shared canonical aren't flagged"
+    );
+}
+
+#[test]
+fn test_seo_analyzer_flags_links_to_non_public_hosts() {
+    use scoutly::models::{DiscoveryMethod, Image, Link, OpenGraphTags, PageInfo};
+    use std::collections::HashMap;
+
+    let link = |url: &str| Link {
+        url: url.to_string(),
+        text: String::new(),
+        is_external: true,
+        kind: DiscoveryMethod::Anchor,
+        rel: None,
+        status_code: None,
+        redirected_url: None,
+        check_error: None,
+        skip_reason: None,
+        content_type: None,
+        content_length: None,
+    };
+    let page = |url: &str| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: Some("Docs".to_string()),
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![
+            link("http://localhost:3000/docs"),
+            link("http://192.168.1.20/admin"),
+            link("http://192.168.1.20/admin"),
+            link("http://nas.local/files"),
+            link("https://example.org/"),
+        ],
+        images: vec![Image {
+            src: "http://10.0.0.5/logo.png".to_string(),
+            alt: Some("Logo".to_string()),
+            width: None,
+            height: None,
+        }],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: Default::default(),
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 0,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let mut pages: HashMap<String, PageInfo> = [
+        page("https://example.com/docs"),
+        page("http://localhost:8080/docs"),
+    ]
+    .into_iter()
+    .map(|page| (page.url.clone(), page))
+    .collect();
+
+    SeoAnalyzer::analyze_pages(&mut pages).unwrap();
+
+    let flagged = |url: &str| -> Vec<(IssueSeverity, Option<String>, Option<String>)> {
+        pages[url]
+            .issues
+            .iter()
+            .filter(|issue| issue.issue_type == IssueType::NonPublicLink)
+            .map(|issue| {
+                (
+                    issue.severity,
+                    issue.offending_url.clone(),
+                    issue.actual.clone(),
+                )
+            })
+            .collect()
+    };
+    let expected = |url: &str, actual: &str| {
+        (
+            IssueSeverity::Error,
+            Some(url.to_string()),
+            Some(actual.to_string()),
+        )
+    };
+    assert_eq!(
+        flagged("https://example.com/docs"),
+        [
+            expected("http://localhost:3000/docs", "localhost"),
+            expected("http://192.168.1.20/admin", "private IP address"),
+            expected("http://nas.local/files", "local network hostname"),
+            expected("http://10.0.0.5/logo.png", "private IP address"),
+        ]
+    );
+    assert!(
+        flagged("http://localhost:8080/docs").is_empty(),
+        "Pages served from a local host aren't checked"
+    );
+}
+
+#[test]
+fn test_seo_analyzer_compares_byte_identical_copies_by_their_original() {
+    use scoutly::models::{OpenGraphTags, PageContent, PageInfo};
+    use std::collections::HashMap;
+
+    let page = |url: &str| PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        last_modified: None,
+        response_time_ms: None,
+        content_length: None,
+        redirect_chain: vec![],
+        duplicate_of: None,
+        title: Some("Pricing".to_string()),
+        meta_description: None,
+        meta_robots: None,
+        x_robots_tag: None,
+        canonical: None,
+        alternates: vec![],
+        link_headers: Default::default(),
+        vary: vec![],
+        vary_observed: vec![],
+        h1_tags: vec![],
+        outline: vec![],
+        aliases: vec![],
+        links: vec![],
+        images: vec![],
+        weight: Default::default(),
+        open_graph: OpenGraphTags::default(),
+        content: PageContent {
+            text: "Plans start at $5".to_string(),
+            ..Default::default()
+        },
+        issues: vec![],
+        omitted_issues: vec![],
+        crawl_depth: 1,
+        source: Default::default(),
+        screenshot: None,
+        html_snapshot: None,
+        field_data: None,
+    };
+    let original = page("https://example.com/pricing");
+    // The crawler doesn't parse a copy, so it has no title or text of its own
+    let copy = PageInfo {
+        duplicate_of: Some(original.url.clone()),
+        title: None,
+        content: PageContent::default(),
+        ..page("https://www.example.com/pricing")
+    };
+    let mut pages: HashMap<String, PageInfo> = [original, copy]
+        .into_iter()
+        .map(|page| (page.url.clone(), page))
+        .collect();
+
+    SeoAnalyzer::analyze_pages(&mut pages).unwrap();
+
+    let copy = &pages["https://www.example.com/pricing"];
+    let issue_types: Vec<IssueType> = copy.issues.iter().map(|issue| issue.issue_type).collect();
+    assert_eq!(issue_types, [IssueType::CrossHostDuplicate]);
+    assert_eq!(
+        copy.issues[0].actual.as_deref(),
+        Some("https://example.com/pricing")
+    );
+    assert!(
+        pages["https://example.com/pricing"]
+            .issues
+            .iter()
+            .any(|issue| issue.issue_type == IssueType::CrossHostDuplicate)
+    );
+}
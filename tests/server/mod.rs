@@ -1,7 +1,8 @@
 use actix_web::{App, HttpRequest, HttpResponse, HttpServer, web};
 use std::fs;
 use std::path::PathBuf;
-use std::sync::{Once, OnceLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once, OnceLock};
 use std::time::Duration;
 
 #[allow(dead_code)]
@@ -9,6 +10,10 @@ static INIT: Once = Once::new();
 static FIXTURE_INIT: Once = Once::new();
 static LINK_TEST_SERVER_BASE_URL: OnceLock<String> = OnceLock::new();
 static FIXTURE_TEST_SERVER_BASE_URL: OnceLock<String> = OnceLock::new();
+static COUNTED_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+static RETRY_AFTER_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+static NOT_MODIFIED_RESPONSES: AtomicUsize = AtomicUsize::new(0);
+static FLAKY_KEYS_SEEN: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
 const LINK_TEST_SERVER_HOST: &str = "127.0.0.1";
 
@@ -19,6 +24,9 @@ pub fn link_test_server_url() -> &'static str {
         .expect("Link test server should be started before use")
 }
 
+/// Sent with every fixture so crawls record a `last_modified` time
+pub const FIXTURE_LAST_MODIFIED: &str = "Thu, 15 Jan 2026 08:30:00 GMT";
+
 async fn serve_static_fixture(
     request: HttpRequest,
     link_server_url: web::Data<String>,
@@ -32,15 +40,60 @@ async fn serve_static_fixture(
 
     match fs::read_to_string(&file_path) {
         Ok(contents) => {
-            let html = contents.replace("http://127.0.0.1:3000", link_server_url.get_ref());
+            let fixture_url = FIXTURE_TEST_SERVER_BASE_URL
+                .get()
+                .map(String::as_str)
+                .unwrap_or_default();
+            let html = contents
+                .replace("http://127.0.0.1:3000", link_server_url.get_ref())
+                .replace("http://fixture.invalid", fixture_url);
             HttpResponse::Ok()
                 .content_type("text/html; charset=utf-8")
+                .insert_header(("Last-Modified", FIXTURE_LAST_MODIFIED))
                 .body(html)
         }
         Err(_) => HttpResponse::NotFound().body("Not Found"),
     }
 }
 
+/// A sitemap served the way `.xml.gz` files usually are: gzip bytes without `Content-Encoding`
+async fn gzipped_sitemap() -> HttpResponse {
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    let base_url = FIXTURE_TEST_SERVER_BASE_URL
+        .get()
+        .map(String::as_str)
+        .unwrap_or_default();
+    let xml = format!(
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"><url><loc>{base_url}/og-complete.html</loc></url></urlset>"#
+    );
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(xml.as_bytes())
+        .expect("Gzip encoding in memory should not fail");
+    HttpResponse::Ok().content_type("application/gzip").body(
+        encoder
+            .finish()
+            .expect("Gzip encoding in memory should not fail"),
+    )
+}
+
+/// Serves a different title to Googlebot than to browsers
+fn page_for_user_agent(request: &HttpRequest) -> String {
+    let is_googlebot = request
+        .headers()
+        .get("user-agent")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("Googlebot"));
+    let title = if is_googlebot {
+        "For crawlers"
+    } else {
+        "For browsers"
+    };
+    format!("<html><head><title>{title}</title></head><body></body></html>")
+}
+
 async fn wait_for_server(url: &str) {
     for _ in 0..20 {
         match reqwest::get(url).await {
@@ -72,6 +125,7 @@ pub async fn get_test_server_url() -> String {
         let server = HttpServer::new(move || {
             App::new()
                 .app_data(web::Data::new(link_server_url.clone()))
+                .route("/sitemap-extra.xml.gz", web::get().to(gzipped_sitemap))
                 .default_service(web::to(serve_static_fixture))
         })
         .workers(1)
@@ -96,6 +150,18 @@ pub async fn get_test_server_url() -> String {
     base_url
 }
 
+/// Number of requests the link test server has received on `/counted` and its `/counted-*` routes
+#[allow(dead_code)]
+pub fn counted_requests() -> usize {
+    COUNTED_REQUESTS.load(Ordering::SeqCst)
+}
+
+/// Number of 304 responses the link test server has sent for `/etag-page`
+#[allow(dead_code)]
+pub fn not_modified_responses() -> usize {
+    NOT_MODIFIED_RESPONSES.load(Ordering::SeqCst)
+}
+
 #[allow(dead_code)]
 pub async fn start_link_test_server() -> String {
     INIT.call_once(|| {
@@ -118,10 +184,125 @@ pub async fn start_link_test_server() -> String {
                     "/ok",
                     web::get().to(|| async { HttpResponse::Ok().body("OK") }),
                 )
+                .route(
+                    "/batch-order",
+                    web::get().to(|| async {
+                        HttpResponse::Ok().content_type("text/html").body(
+                            r#"<html><head><title>Batch</title></head><body>
+                            <a href="/batch-order/slow">Slow</a>
+                            <a href="/batch-order/fast">Fast</a>
+                            </body></html>"#,
+                        )
+                    }),
+                )
+                .route(
+                    "/batch-order/slow",
+                    web::get().to(|| async {
+                        tokio::time::sleep(Duration::from_millis(300)).await;
+                        HttpResponse::Ok()
+                            .content_type("text/html")
+                            .body("<html><head><title>Slow</title></head><body></body></html>")
+                    }),
+                )
+                .route(
+                    "/batch-order/fast",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html")
+                            .body("<html><head><title>Fast</title></head><body></body></html>")
+                    }),
+                )
+                .route(
+                    "/counted",
+                    web::get().to(|| async {
+                        COUNTED_REQUESTS.fetch_add(1, Ordering::SeqCst);
+                        HttpResponse::Ok().body("OK")
+                    }),
+                )
+                .route(
+                    "/counted-server-error",
+                    web::get().to(|| async {
+                        COUNTED_REQUESTS.fetch_add(1, Ordering::SeqCst);
+                        HttpResponse::InternalServerError().body("Error")
+                    }),
+                )
+                .route(
+                    "/counted-redirect",
+                    web::get().to(|| async {
+                        COUNTED_REQUESTS.fetch_add(1, Ordering::SeqCst);
+                        HttpResponse::Found()
+                            .append_header(("Location", "/counted"))
+                            .finish()
+                    }),
+                )
+                .route(
+                    "/retry-after",
+                    web::get().to(|| async {
+                        // Every other request is turned away with a one-second Retry-After
+                        if RETRY_AFTER_REQUESTS
+                            .fetch_add(1, Ordering::SeqCst)
+                            .is_multiple_of(2) {
+                            HttpResponse::ServiceUnavailable()
+                                .insert_header(("Retry-After", "1"))
+                                .body("Busy")
+                        } else {
+                            HttpResponse::Ok().body("OK")
+                        }
+                    }),
+                )
+                .route(
+                    "/files/guide.pdf",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("application/pdf")
+                            .body(vec![b'%'; 2_500])
+                    }),
+                )
                 .route(
                     "/not-found",
                     web::get().to(|| async { HttpResponse::NotFound().body("Not Found") }),
                 )
+                .route(
+                    "/login",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .append_header(("Set-Cookie", "csrf=token123; Path=/"))
+                            .body(r#"<html><body><form action="/login" method="post">
+                                <input type="hidden" name="csrf" value="token123">
+                                <input name="username"><input type="password" name="password">
+                                <button type="submit">Log in</button>
+                            </form></body></html>"#)
+                    }),
+                )
+                .route(
+                    "/login",
+                    web::post().to(|request: HttpRequest, form: web::Form<std::collections::HashMap<String, String>>| async move {
+                        let field = |name: &str| form.get(name).map(String::as_str);
+                        let csrf_matches = request
+                            .cookie("csrf")
+                            .is_some_and(|cookie| Some(cookie.value()) == field("csrf"));
+                        if csrf_matches && field("username") == Some("ana") && field("password") == Some("hunter2") {
+                            HttpResponse::SeeOther()
+                                .append_header(("Set-Cookie", "session=abc123; Path=/; HttpOnly"))
+                                .append_header(("Location", "/cookie-gated"))
+                                .finish()
+                        } else {
+                            HttpResponse::Unauthorized().body("Wrong username or password")
+                        }
+                    }),
+                )
+                .route(
+                    "/cookie-gated",
+                    web::get().to(|request: HttpRequest| async move {
+                        match request.cookie("session") {
+                            Some(cookie) if cookie.value() == "abc123" => HttpResponse::Ok()
+                                .content_type("text/html; charset=utf-8")
+                                .body(r#"<html><head><title>Members area</title></head><body><p class="account">Signed in</p></body></html>"#),
+                            _ => HttpResponse::Unauthorized().body("Log in first"),
+                        }
+                    }),
+                )
                 .route(
                     "/redirect",
                     web::get().to(|base_url: web::Data<String>| async move {
@@ -130,6 +311,70 @@ pub async fn start_link_test_server() -> String {
                             .finish()
                     }),
                 )
+                .route(
+                    "/private-links",
+                    web::get().to(|base_url: web::Data<String>| async move {
+                        // Off-site links back to this server through loopback names
+                        let port = base_url.rsplit(':').next().unwrap_or_default().to_string();
+                        HttpResponse::Ok().content_type("text/html; charset=utf-8").body(format!(
+                            r#"<html><head><title>Private links</title></head><body>
+                            <a href="http://localhost:{port}/ok">Resolves to loopback</a>
+                            <a href="http://[::1]:{port}/ok">Loopback address</a>
+                            </body></html>"#
+                        ))
+                    }),
+                )
+                .route(
+                    "/redirect-keep-query",
+                    web::get().to(|base_url: web::Data<String>, request: HttpRequest| async move {
+                        HttpResponse::MovedPermanently()
+                            .append_header((
+                                "Location",
+                                format!("{}/ok?{}", base_url.get_ref(), request.query_string()),
+                            ))
+                            .finish()
+                    }),
+                )
+                .route(
+                    "/redirect-chain",
+                    web::get().to(|base_url: web::Data<String>| async move {
+                        HttpResponse::MovedPermanently()
+                            .append_header(("Location", format!("{}/redirect", base_url.get_ref())))
+                            .finish()
+                    }),
+                )
+                .route(
+                    "/redirect-loop",
+                    web::get().to(|| async {
+                        HttpResponse::MovedPermanently()
+                            .append_header(("Location", "/redirect-loop"))
+                            .finish()
+                    }),
+                )
+                .route(
+                    "/loop-a",
+                    web::get().to(|| async {
+                        HttpResponse::MovedPermanently()
+                            .append_header(("Location", "/loop-b"))
+                            .finish()
+                    }),
+                )
+                .route(
+                    "/loop-b",
+                    web::get().to(|| async {
+                        HttpResponse::Found()
+                            .append_header(("Location", "/loop-a"))
+                            .finish()
+                    }),
+                )
+                .route(
+                    "/redirect-to-html",
+                    web::get().to(|base_url: web::Data<String>| async move {
+                        HttpResponse::MovedPermanently()
+                            .append_header(("Location", format!("{}/link-headers", base_url.get_ref())))
+                            .finish()
+                    }),
+                )
                 .route(
                     "/redirect-temp",
                     web::get().to(|base_url: web::Data<String>| async move {
@@ -138,6 +383,152 @@ pub async fn start_link_test_server() -> String {
                             .finish()
                     }),
                 )
+                .route(
+                    "/link-headers",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .append_header(("Link", r#"</canonical-header>; rel="canonical", </de-header>; rel="alternate"; hreflang="de""#))
+                            .append_header(("Link", r#"</fr>; rel="alternate"; hreflang="fr""#))
+                            .body(
+                                r#"<html><head>
+                                <link rel="canonical" href="/canonical-html">
+                                <link rel="alternate" hreflang="de" href="/de-html">
+                                </head><body></body></html>"#,
+                            )
+                    }),
+                )
+                .route(
+                    "/hreflang-en",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .body(
+                                r#"<html><head>
+                                <link rel="alternate" hreflang="en" href="/hreflang-en">
+                                <link rel="alternate" hreflang="de" href="/hreflang-de">
+                                <link rel="alternate" hreflang="fr" href="/not-found">
+                                <link rel="alternate" hreflang="es" href="/redirect">
+                                <link rel="alternate" hreflang="it" href="/ok">
+                                </head><body></body></html>"#,
+                            )
+                    }),
+                )
+                .route(
+                    "/hreflang-de",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .append_header(("Link", r#"</hreflang-en>; rel="alternate"; hreflang="en""#))
+                            .body("<html><head></head><body></body></html>")
+                    }),
+                )
+                .route(
+                    "/link-header-only",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .append_header(("Link", r#"<https://example.com/a,b>; rel="canonical""#))
+                            .body("<html><head></head><body></body></html>")
+                    }),
+                )
+                .route(
+                    "/vary-user-agent",
+                    web::get().to(|request: HttpRequest| async move {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .body(page_for_user_agent(&request))
+                    }),
+                )
+                .route(
+                    "/vary-oversized",
+                    web::get().to(|request: HttpRequest| async move {
+                        // Crawlers get the different page padded well past a kilobyte
+                        let mut page = page_for_user_agent(&request);
+                        if page.contains("For crawlers") {
+                            page.push_str(&format!("<!-- {} -->", "x".repeat(4_096)));
+                        }
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .body(page)
+                    }),
+                )
+                .route(
+                    "/vary-declared",
+                    web::get().to(|request: HttpRequest| async move {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .append_header(("Vary", "User-Agent, Accept-Encoding"))
+                            .body(page_for_user_agent(&request))
+                    }),
+                )
+                .route(
+                    "/vary-cookie",
+                    web::get().to(|request: HttpRequest| async move {
+                        let title = match request.cookie("session") {
+                            Some(_) => "Welcome back",
+                            None => "Sign in",
+                        };
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .append_header(("Vary", "Accept-Encoding"))
+                            .append_header(("Set-Cookie", "session=abc; Path=/; HttpOnly"))
+                            .body(format!(
+                                "<html><head><title>{title}</title></head><body></body></html>"
+                            ))
+                    }),
+                )
+                .route(
+                    "/xml-page",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/xml; charset=utf-8")
+                            .body(r#"<html><head><title>XML page</title></head><body><a href="/ok">OK</a></body></html>"#)
+                    }),
+                )
+                .route(
+                    "/xhtml-page",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("application/xhtml+xml")
+                            .body(r#"<html><head><title>XHTML page</title></head><body><a href="/ok">OK</a></body></html>"#)
+                    }),
+                )
+                .route(
+                    "/flaky/{key}",
+                    web::get().to(|key: web::Path<String>| async move {
+                        // The first request for each key fails the way an overloaded server does
+                        let mut seen = FLAKY_KEYS_SEEN.lock().unwrap();
+                        if seen.contains(&key) {
+                            HttpResponse::Ok()
+                                .content_type("text/html; charset=utf-8")
+                                .body("<html><head><title>Flaky page</title></head><body></body></html>")
+                        } else {
+                            seen.push(key.into_inner());
+                            HttpResponse::BadGateway().body("Bad Gateway")
+                        }
+                    }),
+                )
+                .route(
+                    "/slow",
+                    web::get().to(|| async {
+                        // Long enough for a test to act on the crawl while it is still running
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .body("<html><head><title>Slow page</title></head><body></body></html>")
+                    }),
+                )
+                .route(
+                    "/x-robots-tag",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html; charset=utf-8")
+                            .append_header(("X-Robots-Tag", "googlebot: noindex"))
+                            .append_header(("X-Robots-Tag", "nofollow"))
+                            .body(r#"<html><head><title>Header directives</title></head><body><a href="/ok">OK</a></body></html>"#)
+                    }),
+                )
                 .route(
                     "/server-error",
                     web::get().to(|| async { HttpResponse::InternalServerError().body("Error") }),
@@ -158,6 +549,43 @@ pub async fn start_link_test_server() -> String {
                             .body(vec![0u8; 100])
                     }),
                 )
+                .route(
+                    "/large-page",
+                    web::get().to(|| async {
+                        HttpResponse::Ok()
+                            .content_type("text/html")
+                            .body(format!("<html><head><title>Large</title></head><body>{}</body></html>", "x".repeat(200_000)))
+                    }),
+                )
+                .route(
+                    "/large-page-streamed",
+                    web::get().to(|| async {
+                        // Chunked, so the size isn't known until the body is read
+                        let chunks = (0..20).map(|_| {
+                            Ok::<_, std::io::Error>(web::Bytes::from("x".repeat(10_000)))
+                        });
+                        HttpResponse::Ok()
+                            .content_type("text/html")
+                            .streaming(futures::stream::iter(chunks))
+                    }),
+                )
+                .route(
+                    "/etag-page",
+                    web::get().to(|request: HttpRequest| async move {
+                        let unchanged = request
+                            .headers()
+                            .get("if-none-match")
+                            .is_some_and(|value| value == "\"v1\"");
+                        if unchanged {
+                            NOT_MODIFIED_RESPONSES.fetch_add(1, Ordering::SeqCst);
+                            return HttpResponse::NotModified().finish();
+                        }
+                        HttpResponse::Ok()
+                            .content_type("text/html")
+                            .insert_header(("ETag", "\"v1\""))
+                            .body("<html><head><title>Tagged</title></head><body><a href=\"/ok\">OK</a></body></html>")
+                    }),
+                )
                 .route(
                     "/pdf-response",
                     web::get().to(|| async {
@@ -185,3 +613,68 @@ pub async fn start_link_test_server() -> String {
 
     base_url
 }
+
+/// Start an HTTPS server whose certificate for 127.0.0.1 is signed by the test CA in
+/// `tests/static/tls/ca.pem`, answering every request with the same small page
+#[allow(dead_code)]
+pub async fn start_tls_test_server() -> String {
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::TlsAcceptor;
+    use tokio_rustls::rustls::ServerConfig;
+    use tokio_rustls::rustls::crypto::ring;
+    use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+
+    let cert = CertificateDer::from(
+        fs::read("tests/static/tls/localhost.der").expect("Test certificate should exist"),
+    );
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(
+        fs::read("tests/static/tls/localhost.key.der").expect("Test key should exist"),
+    ));
+    let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .expect("Test TLS protocol versions should be valid")
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], key)
+        .expect("Test certificate and key should match");
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    let listener = tokio::net::TcpListener::bind((LINK_TEST_SERVER_HOST, 0))
+        .await
+        .expect("Failed to bind TLS test server");
+    let base_url = format!(
+        "https://{}",
+        listener
+            .local_addr()
+            .expect("TLS test server should have a bound address")
+    );
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                // Clients that reject the certificate end the handshake, which is expected
+                let Ok(mut stream) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match stream.read(&mut buffer).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(read) => request.extend_from_slice(&buffer[..read]),
+                    }
+                }
+                let body = "<html><head><title>Secure staging</title></head><body><h1>Staging</h1></body></html>";
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+
+    base_url
+}
@@ -0,0 +1,82 @@
+use scoutly::models::{IssueType, PageInfo};
+use scoutly::rules::{PathRules, RuleOverride};
+use scoutly::sitemap::audit_lastmod;
+use std::collections::{BTreeMap, HashMap};
+
+fn page(path: &str, last_modified: Option<&str>) -> (String, PageInfo) {
+    let url = format!("https://shop.test{path}");
+    (
+        url.clone(),
+        PageInfo {
+            url,
+            status_code: Some(200),
+            content_type: Some("text/html".to_string()),
+            last_modified: last_modified.map(str::to_string),
+            crawl_depth: 1,
+            ..Default::default()
+        },
+    )
+}
+
+fn lastmod_issues(page: &PageInfo) -> Vec<IssueType> {
+    page.issues.iter().map(|issue| issue.issue_type).collect()
+}
+
+#[test]
+fn test_audit_lastmod_flags_stale_and_inaccurate_dates() {
+    let mut pages = HashMap::from([
+        page("/fresh", Some("2026-01-15T09:00:00Z")),
+        page("/stale", Some("2026-03-01T12:00:00Z")),
+        page("/bumped", Some("2025-06-01T12:00:00Z")),
+        page("/future", None),
+        page("/garbled", None),
+        page("/unlisted", Some("2026-03-01T12:00:00Z")),
+    ]);
+    let lastmods = HashMap::from(
+        [
+            ("/fresh", "2026-01-15"),
+            ("/stale", "2026-01-15"),
+            ("/bumped", "2026-01-15T00:00:00+00:00"),
+            ("/future", "2999-01-01"),
+            ("/garbled", "last tuesday"),
+        ]
+        .map(|(path, lastmod)| (format!("https://shop.test{path}"), lastmod.to_string())),
+    );
+
+    audit_lastmod(&mut pages, &lastmods, &PathRules::default());
+
+    let issues = |path: &str| lastmod_issues(&pages[&format!("https://shop.test{path}")]);
+    assert!(issues("/fresh").is_empty());
+    assert_eq!(issues("/stale"), [IssueType::SitemapLastmodStale]);
+    assert_eq!(issues("/bumped"), [IssueType::SitemapLastmodInaccurate]);
+    assert_eq!(issues("/future"), [IssueType::SitemapLastmodInaccurate]);
+    assert_eq!(issues("/garbled"), [IssueType::SitemapLastmodInaccurate]);
+    assert!(issues("/unlisted").is_empty());
+
+    let stale = &pages["https://shop.test/stale"].issues[0];
+    assert_eq!(
+        stale.actual.as_deref(),
+        Some("lastmod 2026-01-15, Last-Modified 2026-03-01T12:00:00+00:00")
+    );
+}
+
+#[test]
+fn test_audit_lastmod_respects_disabled_rules() {
+    let mut pages = HashMap::from([page("/stale", Some("2026-03-01T12:00:00Z"))]);
+    let lastmods = HashMap::from([(
+        "https://shop.test/stale".to_string(),
+        "2026-01-15".to_string(),
+    )]);
+    let rules = PathRules::new(&BTreeMap::from([(
+        "/stale".to_string(),
+        RuleOverride {
+            disable: Some(vec![IssueType::SitemapLastmodStale]),
+            ..Default::default()
+        },
+    )]))
+    .unwrap();
+
+    audit_lastmod(&mut pages, &lastmods, &rules);
+
+    assert!(pages["https://shop.test/stale"].issues.is_empty());
+}
@@ -0,0 +1,133 @@
+use scoutly::models::{HtmlContentTypes, IssueSeverity, IssueType, PageInfo, SeoIssue};
+use scoutly::reporter::Reporter;
+use scoutly::trends::{self, TrendPoint, TrendStore};
+use std::collections::HashMap;
+use tempfile::tempdir;
+
+fn page(url: &str, issues: Vec<SeoIssue>) -> PageInfo {
+    PageInfo {
+        url: url.to_string(),
+        status_code: Some(200),
+        content_type: Some("text/html".to_string()),
+        issues,
+        ..Default::default()
+    }
+}
+
+fn point(start_url: &str, errors: usize, score: u8) -> TrendPoint {
+    TrendPoint {
+        timestamp: "2026-10-01T08:30:00+00:00".to_string(),
+        start_url: start_url.to_string(),
+        total_pages: 4,
+        broken_links: errors,
+        errors,
+        warnings: 1,
+        score,
+    }
+}
+
+#[test]
+fn test_trend_point_scores_pages_without_errors() {
+    let pages = HashMap::from([
+        (
+            "https://example.com/".to_string(),
+            page(
+                "https://example.com/",
+                vec![SeoIssue::new(
+                    IssueSeverity::Error,
+                    IssueType::MissingTitle,
+                    "Page is missing a title tag",
+                )],
+            ),
+        ),
+        (
+            "https://example.com/about".to_string(),
+            page(
+                "https://example.com/about",
+                vec![SeoIssue::new(
+                    IssueSeverity::Warning,
+                    IssueType::MissingH1,
+                    "Page is missing an H1 tag",
+                )],
+            ),
+        ),
+        (
+            "https://example.com/blog".to_string(),
+            page("https://example.com/blog", vec![]),
+        ),
+    ]);
+    let report = Reporter::generate_report_from_pages(
+        "https://example.com/",
+        pages,
+        &HtmlContentTypes::default(),
+    )
+    .unwrap();
+
+    let point = TrendPoint::from_report(&report, &report.pages).unwrap();
+
+    assert_eq!(point.start_url, "https://example.com/");
+    assert_eq!(point.total_pages, 3);
+    assert_eq!(point.errors, 1);
+    assert_eq!(point.warnings, 1);
+    assert_eq!(point.score, 67);
+}
+
+#[test]
+fn test_trend_store_appends_runs() {
+    let dir = tempdir().unwrap();
+    let store = TrendStore::new(&dir.path().join("state"));
+    assert!(store.load().unwrap().is_empty());
+
+    store.record(&point("https://example.com", 3, 50)).unwrap();
+    store.record(&point("https://example.com", 1, 75)).unwrap();
+
+    let history = store.load().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1], point("https://example.com", 1, 75));
+}
+
+#[test]
+fn test_points_for_site_defaults_to_latest_site() {
+    let history = vec![
+        point("https://example.com/", 3, 50),
+        point("https://other.example/", 0, 100),
+        point("https://example.com", 1, 75),
+    ];
+
+    let latest = trends::points_for_site(&history, None);
+    assert_eq!(latest.len(), 2);
+    assert!(latest.iter().all(|point| point.score < 100));
+
+    assert_eq!(
+        trends::points_for_site(&history, Some("https://other.example")).len(),
+        1
+    );
+    assert!(trends::points_for_site(&history, Some("https://missing.example")).is_empty());
+}
+
+#[test]
+fn test_sparkline_scales_between_min_and_max() {
+    assert_eq!(trends::sparkline(&[0, 7, 14]), "▁▄█");
+    assert_eq!(trends::sparkline(&[5, 5]), "▁▁");
+    assert_eq!(trends::sparkline(&[]), "");
+}
+
+#[test]
+fn test_render_trends_as_text_and_html() {
+    let points = vec![
+        point("https://example.com", 3, 50),
+        point("https://example.com", 1, 75),
+    ];
+
+    let text = trends::render_text(&points, 10);
+    assert!(text.contains("2 run(s) since 2026-10-01 08:30"));
+    assert!(text.contains("latest 1 (-2)"));
+    assert!(text.contains("latest 75 (+25)"));
+
+    let html = trends::render_html(&points);
+    assert!(html.contains("<title>Scoutly trends for https://example.com</title>"));
+    assert_eq!(html.matches("<polyline").count(), 4);
+    assert!(html.contains("<td>75</td>"));
+
+    assert!(trends::render_text(&[], 10).contains("No runs recorded yet"));
+}
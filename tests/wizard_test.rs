@@ -0,0 +1,167 @@
+use scoutly::cli::{CrawlPreset, OutputFormat};
+use scoutly::config::Config;
+use scoutly::login::LoginConfig;
+use scoutly::wizard::{self, WizardAuth};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Cursor;
+use tempfile::tempdir;
+
+#[test]
+fn test_wizard_writes_config_and_prints_equivalent_command() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("scoutly.toml");
+    let answers = format!(
+        "example.com\nhttps://example.com\ndeep\njson\nreport.json\n\n{}\n",
+        config_path.display()
+    );
+
+    let mut output = Vec::new();
+    let result = wizard::run(&mut Cursor::new(answers), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("URL must start with http:// or https://"));
+    assert!(
+        output
+            .contains("scoutly https://example.com --preset deep --output json --save report.json")
+    );
+    assert_eq!(result.preset, CrawlPreset::Deep);
+
+    let config = Config::from_file(&config_path).unwrap();
+    assert_eq!(config.url.as_deref(), Some("https://example.com"));
+    assert_eq!(config.preset, Some(CrawlPreset::Deep));
+    assert_eq!(config.output, Some(OutputFormat::Json));
+    assert_eq!(config.save.as_deref(), Some("report.json"));
+}
+
+#[test]
+fn test_wizard_uses_defaults_and_reprompts_invalid_choices() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("site.toml");
+    let answers = format!(
+        "https://example.com\nhuge\n\n\n\n\n{}\n",
+        config_path.display()
+    );
+
+    let mut output = Vec::new();
+    let result = wizard::run(&mut Cursor::new(answers), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Unknown choice: huge"));
+    assert_eq!(result.preset, CrawlPreset::Standard);
+    assert_eq!(result.output, None);
+    assert_eq!(result.save, None);
+    assert_eq!(result.auth, None);
+    assert_eq!(
+        result.equivalent_command(),
+        "scoutly https://example.com --preset standard"
+    );
+}
+
+#[test]
+fn test_wizard_writes_session_cookies() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("scoutly.toml");
+    let answers = format!(
+        "https://example.com\n\ntext\n\ncookies\nsession=abc123\nsession theme-id\n{}\n",
+        config_path.display()
+    );
+
+    let mut output = Vec::new();
+    let result = wizard::run(&mut Cursor::new(answers), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Expected a cookie name without its value, got: session=abc123"));
+    assert!(output.contains(
+        "scoutly https://example.com --preset standard --cli --cookie 'session=${SCOUTLY_COOKIE_SESSION}' --cookie 'theme-id=${SCOUTLY_COOKIE_THEME_ID}'"
+    ));
+    assert!(output.contains("export SCOUTLY_COOKIE_SESSION=..."));
+    let config = Config::from_file(&config_path).unwrap();
+    assert_eq!(
+        config.cookies,
+        Some(vec![
+            "session=${SCOUTLY_COOKIE_SESSION}".to_string(),
+            "theme-id=${SCOUTLY_COOKIE_THEME_ID}".to_string()
+        ])
+    );
+    assert!(!fs::read_to_string(&config_path).unwrap().contains("abc123"));
+    assert_eq!(config.login, None);
+    assert_eq!(
+        result.auth,
+        Some(WizardAuth::Cookies(vec![
+            "session".to_string(),
+            "theme-id".to_string()
+        ]))
+    );
+}
+
+#[test]
+fn test_wizard_writes_cookies_file() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("scoutly.toml");
+    let answers = format!(
+        "https://example.com\n\n\n\ncookies-file\ncookies.txt\n{}\n",
+        config_path.display()
+    );
+
+    let mut output = Vec::new();
+    wizard::run(&mut Cursor::new(answers), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("--cookies-file cookies.txt"));
+    assert!(output.contains("cookies.txt holds a live session"));
+    let config = Config::from_file(&config_path).unwrap();
+    assert_eq!(config.cookies_file.as_deref(), Some("cookies.txt"));
+}
+
+#[test]
+fn test_wizard_writes_login_form() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("scoutly.toml");
+    let answers = format!(
+        "https://example.com\n\n\n\nform\nhttps://example.com/login\nusername=me password=${{SITE_PASSWORD}}\n.account\n{}\n",
+        config_path.display()
+    );
+
+    let mut output = Vec::new();
+    wizard::run(&mut Cursor::new(answers), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    // The login form can't be given on the command line
+    assert!(!output.contains("Or without the config file"));
+    let config = Config::from_file(&config_path).unwrap();
+    assert_eq!(
+        config.login,
+        Some(LoginConfig {
+            url: "https://example.com/login".to_string(),
+            fields: BTreeMap::from([
+                ("password".to_string(), "${SITE_PASSWORD}".to_string()),
+                ("username".to_string(), "me".to_string()),
+            ]),
+            success_selector: ".account".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_wizard_keeps_existing_config_unless_confirmed() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("scoutly.toml");
+    std::fs::write(&config_path, "depth = 1\n").unwrap();
+    let answers = format!("https://example.com\n\n\n\n\n{}\n\n", config_path.display());
+
+    let result = wizard::run(&mut Cursor::new(answers), &mut Vec::new());
+
+    assert!(result.unwrap_err().to_string().contains("left unchanged"));
+    assert_eq!(
+        std::fs::read_to_string(&config_path).unwrap(),
+        "depth = 1\n"
+    );
+}
+
+#[test]
+fn test_wizard_fails_when_input_ends_early() {
+    let result = wizard::run(&mut Cursor::new("https://example.com\n"), &mut Vec::new());
+
+    assert!(result.unwrap_err().to_string().contains("input ended"));
+}